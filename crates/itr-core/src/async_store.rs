@@ -0,0 +1,203 @@
+//! [`ItrStore`] as futures, for servers running on tokio.
+//!
+//! Database calls block, so each one runs on tokio's blocking pool via
+//! `spawn_blocking` and the async task awaits it without tying up a runtime
+//! worker. Calls on one store still run one at a time: it owns a single
+//! connection behind a mutex. Clones share that connection.
+
+use crate::error::ItrError;
+use crate::models::{GraphOutput, IssueDetail, IssueSummary, ListFilter, Note, UnblockedIssue};
+use crate::store::{ItrStore, NewIssue};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Cheaply cloneable async handle to an [`ItrStore`]. Must be used from
+/// within a tokio runtime.
+#[derive(Debug, Clone)]
+pub struct AsyncItrStore {
+    inner: Arc<Mutex<ItrStore>>,
+}
+
+impl From<ItrStore> for AsyncItrStore {
+    fn from(store: ItrStore) -> Self {
+        AsyncItrStore {
+            inner: Arc::new(Mutex::new(store)),
+        }
+    }
+}
+
+/// Run blocking work on the blocking pool. A panic or runtime shutdown
+/// surfaces as an [`ItrError::Io`] rather than tearing down the caller.
+async fn blocking<T, F>(f: F) -> Result<T, ItrError>
+where
+    F: FnOnce() -> Result<T, ItrError> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| ItrError::Io(std::io::Error::other(e)))?
+}
+
+impl AsyncItrStore {
+    /// Open an existing database; see [`ItrStore::open`].
+    pub async fn open(path: impl Into<PathBuf>) -> Result<Self, ItrError> {
+        let path = path.into();
+        blocking(move || ItrStore::open(&path))
+            .await
+            .map(Self::from)
+    }
+
+    /// Create (or upgrade) a database; see [`ItrStore::init`].
+    pub async fn init(path: impl Into<PathBuf>) -> Result<Self, ItrError> {
+        let path = path.into();
+        blocking(move || ItrStore::init(&path))
+            .await
+            .map(Self::from)
+    }
+
+    /// A fresh database that lives as long as the last clone.
+    pub async fn open_in_memory() -> Result<Self, ItrError> {
+        blocking(ItrStore::open_in_memory).await.map(Self::from)
+    }
+
+    /// Run `f` against the store on the blocking pool, for calls this API
+    /// does not wrap (e.g. `db` functions on [`ItrStore::connection`]).
+    pub async fn call<T, F>(&self, f: F) -> Result<T, ItrError>
+    where
+        F: FnOnce(&ItrStore) -> Result<T, ItrError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let inner = Arc::clone(&self.inner);
+        blocking(move || {
+            // A panic mid-call can't leave a write half-applied: the write
+            // transaction rolls back on drop, so the poisoned store is sound.
+            let store = inner.lock().unwrap_or_else(PoisonError::into_inner);
+            f(&store)
+        })
+        .await
+    }
+
+    pub async fn add(&self, issue: NewIssue) -> Result<IssueDetail, ItrError> {
+        self.call(move |s| s.add(issue)).await
+    }
+
+    pub async fn get(&self, id: i64) -> Result<IssueDetail, ItrError> {
+        self.call(move |s| s.get(id)).await
+    }
+
+    pub async fn list(&self, filter: ListFilter) -> Result<Vec<IssueSummary>, ItrError> {
+        self.call(move |s| s.list(&filter)).await
+    }
+
+    pub async fn ready(&self) -> Result<Vec<IssueSummary>, ItrError> {
+        self.call(ItrStore::ready).await
+    }
+
+    pub async fn next(&self, skills: Vec<String>) -> Result<Option<IssueDetail>, ItrError> {
+        self.call(move |s| s.next(&skills)).await
+    }
+
+    pub async fn graph(&self, all: bool) -> Result<GraphOutput, ItrError> {
+        self.call(move |s| s.graph(all)).await
+    }
+
+    pub async fn update_field(
+        &self,
+        id: i64,
+        field: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<IssueDetail, ItrError> {
+        let (field, value) = (field.into(), value.into());
+        self.call(move |s| s.update_field(id, &field, &value)).await
+    }
+
+    /// See [`ItrStore::update_fields`]: all changes apply or none do.
+    pub async fn update_fields(
+        &self,
+        id: i64,
+        changes: Vec<(String, String)>,
+    ) -> Result<IssueDetail, ItrError> {
+        self.call(move |s| {
+            let changes: Vec<(&str, &str)> = changes
+                .iter()
+                .map(|(f, v)| (f.as_str(), v.as_str()))
+                .collect();
+            s.update_fields(id, &changes)
+        })
+        .await
+    }
+
+    pub async fn close(
+        &self,
+        id: i64,
+        reason: Option<String>,
+        wontfix: bool,
+    ) -> Result<(IssueDetail, Vec<UnblockedIssue>), ItrError> {
+        self.call(move |s| s.close(id, reason, wontfix)).await
+    }
+
+    pub async fn depend(&self, blocked: i64, on: i64) -> Result<bool, ItrError> {
+        self.call(move |s| s.depend(blocked, on)).await
+    }
+
+    pub async fn add_note(
+        &self,
+        id: i64,
+        text: impl Into<String>,
+        agent: impl Into<String>,
+    ) -> Result<Note, ItrError> {
+        let (text, agent) = (text.into(), agent.into());
+        self.call(move |s| s.add_note(id, &text, &agent)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn async_store_serves_concurrent_calls() {
+        runtime().block_on(async {
+            let store = AsyncItrStore::open_in_memory().await.unwrap();
+            let tasks: Vec<_> = (0..8)
+                .map(|i| {
+                    let store = store.clone();
+                    tokio::spawn(
+                        async move { store.add(NewIssue::new(format!("issue {i}"))).await },
+                    )
+                })
+                .collect();
+            for task in tasks {
+                task.await.unwrap().unwrap();
+            }
+            assert_eq!(store.ready().await.unwrap().len(), 8);
+
+            assert!(store.depend(2, 1).await.unwrap());
+            let (closed, unblocked) = store.close(1, None, false).await.unwrap();
+            assert_eq!(closed.issue.status, "done");
+            assert_eq!(unblocked[0].id, 2);
+            let updated = store
+                .update_fields(2, vec![("priority".into(), "CRITICAL".into())])
+                .await
+                .unwrap();
+            assert_eq!(updated.issue.priority, "critical");
+            assert!(matches!(store.get(99).await, Err(ItrError::NotFound(99))));
+        });
+    }
+
+    #[test]
+    fn panicking_call_becomes_an_error_and_the_store_stays_usable() {
+        runtime().block_on(async {
+            let store = AsyncItrStore::open_in_memory().await.unwrap();
+            let result: Result<(), _> = store.call(|_| panic!("boom")).await;
+            assert!(matches!(result, Err(ItrError::Io(_))));
+            store.add(NewIssue::new("after")).await.unwrap();
+        });
+    }
+}
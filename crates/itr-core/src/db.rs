@@ -0,0 +1,5702 @@
+use crate::error::ItrError;
+use crate::models::{
+    Agent, Component, Event, ExternalBlocker, Issue, IssueCommit, Note, ProjectCount, Relation,
+    Repro, Session, SubtreeProgress, TagCount,
+};
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const SCHEMA: &str = r"
+PRAGMA journal_mode=WAL;
+PRAGMA foreign_keys=ON;
+
+-- Named components sharing one database; see `itr project`.
+CREATE TABLE IF NOT EXISTS projects (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    name            TEXT NOT NULL UNIQUE,
+    description     TEXT NOT NULL DEFAULT '',
+    key_prefix      TEXT NOT NULL DEFAULT '',
+    created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+-- Areas of the codebase issues are routed to; see `itr component`.
+CREATE TABLE IF NOT EXISTS components (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    name                TEXT NOT NULL UNIQUE,
+    default_assignee    TEXT NOT NULL DEFAULT '',
+    default_tags        TEXT NOT NULL DEFAULT '[]',
+    created_at          TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE TABLE IF NOT EXISTS issues (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    title           TEXT NOT NULL,
+    status          TEXT NOT NULL DEFAULT 'open'
+                    CHECK (status IN ('open', 'in-progress', 'review', 'done', 'wontfix')),
+    priority        TEXT NOT NULL DEFAULT 'medium'
+                    CHECK (priority IN ('critical', 'high', 'medium', 'low')),
+    kind            TEXT NOT NULL DEFAULT 'task'
+                    CHECK (kind IN ('bug', 'feature', 'task', 'epic')),
+    context         TEXT NOT NULL DEFAULT '',
+    files           TEXT NOT NULL DEFAULT '[]',
+    tags            TEXT NOT NULL DEFAULT '[]',
+    skills          TEXT NOT NULL DEFAULT '[]',
+    acceptance      TEXT NOT NULL DEFAULT '',
+    parent_id       INTEGER REFERENCES issues(id) ON DELETE SET NULL,
+    close_reason    TEXT NOT NULL DEFAULT '',
+    assigned_to     TEXT NOT NULL DEFAULT '',
+    branch          TEXT NOT NULL DEFAULT '',
+    project_id      INTEGER REFERENCES projects(id) ON DELETE SET NULL,
+    estimate        INTEGER CHECK (estimate >= 0),
+    severity        TEXT NOT NULL DEFAULT ''
+                    CHECK (severity IN ('', 'blocker', 'major', 'minor', 'trivial')),
+    component_id    INTEGER REFERENCES components(id) ON DELETE SET NULL,
+    affects_version TEXT NOT NULL DEFAULT '',
+    fixed_in_version TEXT NOT NULL DEFAULT '',
+    repro           TEXT NOT NULL DEFAULT '',
+    created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE TABLE IF NOT EXISTS dependencies (
+    blocker_id      INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    blocked_id      INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    PRIMARY KEY (blocker_id, blocked_id),
+    CHECK (blocker_id != blocked_id)
+);
+
+-- Blockers outside the tracker, see EXTERNAL_BLOCKERS below.
+CREATE TABLE IF NOT EXISTS external_blockers (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    issue_id        INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    description     TEXT NOT NULL,
+    url             TEXT NOT NULL DEFAULT '',
+    created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE TABLE IF NOT EXISTS notes (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    issue_id        INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    content         TEXT NOT NULL,
+    agent           TEXT NOT NULL DEFAULT '',
+    kind            TEXT NOT NULL DEFAULT '',
+    created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE TABLE IF NOT EXISTS config (
+    key             TEXT PRIMARY KEY,
+    value           TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS events (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    issue_id        INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    field           TEXT NOT NULL,
+    old_value       TEXT NOT NULL DEFAULT '',
+    new_value       TEXT NOT NULL DEFAULT '',
+    agent           TEXT NOT NULL DEFAULT '',
+    created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE TABLE IF NOT EXISTS relations (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    source_id       INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    target_id       INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    relation_type   TEXT NOT NULL CHECK(relation_type IN ('duplicate', 'related', 'supersedes')),
+    created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    UNIQUE(source_id, target_id, relation_type)
+);
+
+-- Normalized copies of the issues.files / issues.tags JSON arrays, kept in
+-- sync by the trg_issue_lists_* triggers so tag and file filters run in SQL.
+CREATE TABLE IF NOT EXISTS issue_files (
+    issue_id        INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    path            TEXT NOT NULL,
+    PRIMARY KEY (issue_id, path)
+);
+
+CREATE TABLE IF NOT EXISTS issue_tags (
+    issue_id        INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    tag             TEXT NOT NULL,
+    PRIMARY KEY (issue_id, tag)
+);
+
+-- Optional per-tag metadata for `itr tag describe`; tags need no row here.
+CREATE TABLE IF NOT EXISTS tags (
+    name            TEXT PRIMARY KEY,
+    description     TEXT NOT NULL DEFAULT '',
+    color           TEXT NOT NULL DEFAULT ''
+);
+
+-- Agents that work this database and their skills; see `itr agent`.
+-- Registration is optional: an assignee needs no row here.
+CREATE TABLE IF NOT EXISTS agents (
+    name            TEXT PRIMARY KEY,
+    skills          TEXT NOT NULL DEFAULT '[]',
+    description     TEXT NOT NULL DEFAULT '',
+    registered_at   TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    last_seen_at    TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+-- Work sessions from `itr session start`/`end`. `ended_at` is NULL while a
+-- session is open; `issues` (JSON array of IDs) is filled in when it ends.
+CREATE TABLE IF NOT EXISTS sessions (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    agent           TEXT NOT NULL DEFAULT '',
+    started_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    ended_at        TEXT,
+    summary         TEXT NOT NULL DEFAULT '',
+    issues          TEXT NOT NULL DEFAULT '[]'
+);
+
+-- Commits linked to issues by the post-commit hook and `itr git scan`, for
+-- `itr commits`. `relation` is 'close' or 'mention'; full hashes.
+CREATE TABLE IF NOT EXISTS issue_commits (
+    issue_id        INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    hash            TEXT NOT NULL,
+    subject         TEXT NOT NULL DEFAULT '',
+    relation        TEXT NOT NULL DEFAULT 'mention' CHECK(relation IN ('mention', 'close')),
+    linked_at       TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    PRIMARY KEY (issue_id, hash)
+);
+
+-- Named copies of the issue table and dependency edges for `itr diff`.
+CREATE TABLE IF NOT EXISTS snapshots (
+    name            TEXT PRIMARY KEY,
+    data            TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+-- How far `itr sync` has pushed to and pulled from each remote.
+CREATE TABLE IF NOT EXISTS sync_state (
+    remote          TEXT PRIMARY KEY,
+    pushed_event    INTEGER NOT NULL DEFAULT 0,
+    pushed_issue    INTEGER NOT NULL DEFAULT 0,
+    pulled_event    INTEGER NOT NULL DEFAULT 0,
+    pulled_issue    INTEGER NOT NULL DEFAULT 0,
+    synced_at       TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+-- Stable identities that survive export/import between copies, see UUIDS
+-- below. Kept beside the rows so assigning one never touches updated_at.
+CREATE TABLE IF NOT EXISTS issue_uuids (
+    issue_id        INTEGER PRIMARY KEY REFERENCES issues(id) ON DELETE CASCADE,
+    uuid            TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS note_uuids (
+    note_id         INTEGER PRIMARY KEY REFERENCES notes(id) ON DELETE CASCADE,
+    uuid            TEXT NOT NULL UNIQUE
+);
+
+-- Cached DB-derived urgency inputs, see URGENCY_CACHE below.
+CREATE TABLE IF NOT EXISTS urgency_cache (
+    issue_id        INTEGER PRIMARY KEY REFERENCES issues(id) ON DELETE CASCADE,
+    blocking        INTEGER NOT NULL,
+    blocked         INTEGER NOT NULL,
+    note_count      INTEGER NOT NULL,
+    urgency         REAL NOT NULL,
+    computed_at     TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    dirty_at        TEXT
+);
+
+-- `#<id>` mentions in an issue's context and notes, see ISSUE_REFERENCES below.
+CREATE TABLE IF NOT EXISTS issue_references (
+    source_id       INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    target_id       INTEGER NOT NULL,
+    PRIMARY KEY (source_id, target_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_issues_status ON issues(status);
+CREATE INDEX IF NOT EXISTS idx_issues_priority ON issues(priority);
+CREATE INDEX IF NOT EXISTS idx_issues_kind ON issues(kind);
+CREATE INDEX IF NOT EXISTS idx_issues_parent ON issues(parent_id);
+CREATE INDEX IF NOT EXISTS idx_dependencies_blocked ON dependencies(blocked_id);
+CREATE INDEX IF NOT EXISTS idx_dependencies_blocker ON dependencies(blocker_id);
+CREATE INDEX IF NOT EXISTS idx_external_blockers_issue ON external_blockers(issue_id);
+CREATE INDEX IF NOT EXISTS idx_notes_issue ON notes(issue_id);
+CREATE INDEX IF NOT EXISTS idx_events_issue ON events(issue_id);
+CREATE INDEX IF NOT EXISTS idx_events_created ON events(created_at);
+CREATE INDEX IF NOT EXISTS idx_relations_source ON relations(source_id);
+CREATE INDEX IF NOT EXISTS idx_relations_target ON relations(target_id);
+CREATE INDEX IF NOT EXISTS idx_issue_files_path ON issue_files(path);
+CREATE INDEX IF NOT EXISTS idx_issue_tags_tag ON issue_tags(tag);
+CREATE INDEX IF NOT EXISTS idx_issue_references_target ON issue_references(target_id);
+
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_issue AFTER UPDATE ON issues BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND (issue_id = new.id
+        OR issue_id IN (SELECT blocker_id FROM dependencies WHERE blocked_id = new.id)
+        OR issue_id IN (SELECT blocked_id FROM dependencies WHERE blocker_id = new.id));
+END;
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_dep_ai AFTER INSERT ON dependencies BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id IN (new.blocker_id, new.blocked_id);
+END;
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_dep_ad AFTER DELETE ON dependencies BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id IN (old.blocker_id, old.blocked_id);
+END;
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_ext_ai AFTER INSERT ON external_blockers BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id = new.issue_id;
+END;
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_ext_ad AFTER DELETE ON external_blockers BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id = old.issue_id;
+END;
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_note_ai AFTER INSERT ON notes BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id = new.issue_id;
+END;
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_note_ad AFTER DELETE ON notes BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id = old.issue_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_issues_updated_at
+    AFTER UPDATE ON issues
+    FOR EACH ROW
+BEGIN
+    UPDATE issues SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE id = OLD.id;
+END;
+";
+
+/// Locate the database: `--db`/`ITR_DB_PATH`, else the nearest `.itr.db`
+/// at or above the working directory, else the user's `db.fallback`.
+/// `defaults` is the user-level config, the only place `db.search` and
+/// `db.fallback` can come from before a database is open.
+pub fn find_db(
+    override_path: Option<&str>,
+    defaults: &BTreeMap<String, String>,
+) -> Result<PathBuf, ItrError> {
+    // Explicit overrides (ITR_DB_PATH, then --db) are validated before use.
+    let env_path = env::var("ITR_DB_PATH").ok();
+    if let Some(resolved) = resolve_override_db(env_path.as_deref(), override_path) {
+        return resolved;
+    }
+
+    // Walk up from cwd, or only look in it with `db.search = cwd`; then
+    // the user's `db.fallback` tracker, if any.
+    let walk_up = defaults.get("db.search").is_none_or(|s| s != "cwd");
+    let mut dir = env::current_dir().map_err(ItrError::Io)?;
+    loop {
+        let candidate = dir.join(".itr.db");
+        if candidate.exists() {
+            return Ok(candidate);
+        }
+        if !walk_up || !dir.pop() {
+            break;
+        }
+    }
+    match defaults.get("db.fallback") {
+        Some(path) if !path.is_empty() => Ok(db_path_for(path)),
+        _ => Err(ItrError::NoDatabase),
+    }
+}
+
+/// Resolve a DB address (from `--db` or `ITR_DB_PATH`) to a `.itr.db` file.
+///
+/// If `path` is an existing **directory**, resolve to `<path>/.itr.db` — a
+/// control plane can point at a project root without hand-appending the
+/// suffix. Otherwise the path is used verbatim (a `.itr.db` file, or any
+/// filename the caller chose). This is address→file mapping only; it does not
+/// check that the resulting file exists and never creates anything, so it is
+/// shared by both `find_db` (open) and `itr init` (create).
+pub fn db_path_for(path: &str) -> PathBuf {
+    let p = Path::new(path);
+    if p.is_dir() {
+        p.join(".itr.db")
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+/// Resolve an explicit DB override (`--db` flag or `ITR_DB_PATH` env var).
+///
+/// Precedence is unified across every command: an explicit `--db` flag wins
+/// over an ambient `ITR_DB_PATH`, which wins over the walk-up finder. A
+/// control plane sets `ITR_DB_PATH` for its own tracker, then passes `--db
+/// <project>` per call to address another project's tracker — the explicit
+/// flag must not be silently shadowed by the ambient env (matches `itr init`
+/// and the documented rationale in docs/environment.md).
+///
+/// Returns `None` when no usable override is present — an empty-string `--db`
+/// or `ITR_DB_PATH` is treated as unset — so the caller falls through to the
+/// walk-up finder. Both flag and env accept a directory (resolved via
+/// [`db_path_for`]) or a file path.
+///
+/// A directory with no `.itr.db`, or a nonexistent path, is rejected with
+/// `NoDatabase` instead of letting `Connection::open` create an empty junk
+/// file that the walk-up finder would forever discover as a broken database
+/// (#160). The offending path is named in a diagnostic note (see
+/// [`crate::util::set_diagnostic_sink`]) because the `NoDatabase` variant
+/// carries no payload.
+fn resolve_override_db(
+    env_path: Option<&str>,
+    cli_path: Option<&str>,
+) -> Option<Result<PathBuf, ItrError>> {
+    let (path, source) = match (cli_path, env_path) {
+        (Some(p), _) if !p.is_empty() => (p, "--db"),
+        (_, Some(p)) if !p.is_empty() => (p, "ITR_DB_PATH"),
+        _ => return None,
+    };
+    let p = Path::new(path);
+    if p.is_dir() {
+        let candidate = p.join(".itr.db");
+        if candidate.exists() {
+            return Some(Ok(candidate));
+        }
+        crate::util::diagnostic(&format!(
+            "ERROR: {source} points to '{path}', a directory with no .itr.db. Run 'itr init --db {path}' to create it."
+        ));
+        return Some(Err(ItrError::NoDatabase));
+    }
+    if p.exists() {
+        Some(Ok(PathBuf::from(path)))
+    } else {
+        crate::util::diagnostic(&format!(
+            "ERROR: {source} points to '{path}', which does not exist. Run 'itr init --db {path}' to create it."
+        ));
+        Some(Err(ItrError::NoDatabase))
+    }
+}
+
+/// Environment variable holding the passphrase of an encrypted database.
+pub const KEY_ENV: &str = "ITR_DB_KEY";
+
+/// Environment variable naming a shell command that prints the passphrase,
+/// such as a keychain lookup. Used when [`KEY_ENV`] is unset.
+pub const KEY_CMD_ENV: &str = "ITR_DB_KEY_CMD";
+
+/// The first 16 bytes of every plaintext `SQLite` file.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Whether `path` is an existing database that doesn't start with the `SQLite`
+/// header, which for an itr database means `SQLCipher` encrypted it.
+pub fn is_encrypted(path: &Path) -> bool {
+    use std::io::Read;
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .is_ok()
+        && &header != SQLITE_HEADER
+}
+
+/// The passphrase from [`KEY_ENV`], or else the output of [`KEY_CMD_ENV`]
+/// without its trailing newline.
+pub fn passphrase() -> Result<String, ItrError> {
+    if let Some(key) = env::var(KEY_ENV).ok().filter(|k| !k.is_empty()) {
+        return Ok(key);
+    }
+    let Some(cmd) = env::var(KEY_CMD_ENV).ok().filter(|c| !c.trim().is_empty()) else {
+        return Err(ItrError::Encryption(format!(
+            "no passphrase; set {KEY_ENV}, or {KEY_CMD_ENV} to a command that prints it"
+        )));
+    };
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let out = std::process::Command::new(shell)
+        .args([flag, &cmd])
+        .output()?;
+    if !out.status.success() {
+        return Err(ItrError::Encryption(format!(
+            "{KEY_CMD_ENV} command failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        )));
+    }
+    let key = String::from_utf8_lossy(&out.stdout)
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+    if key.is_empty() {
+        return Err(ItrError::Encryption(format!(
+            "{KEY_CMD_ENV} command printed nothing"
+        )));
+    }
+    Ok(key)
+}
+
+/// Key a fresh connection. `SQLCipher` only checks the key on first read, so
+/// read once here and turn a wrong key into a readable error.
+#[cfg(feature = "encryption")]
+fn apply_key(conn: &Connection) -> Result<(), ItrError> {
+    conn.pragma_update(None, "key", passphrase()?)?;
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|e| match e.sqlite_error_code() {
+            Some(ErrorCode::NotADatabase) => ItrError::Encryption(format!(
+                "wrong passphrase (from {KEY_ENV} or {KEY_CMD_ENV})"
+            )),
+            _ => ItrError::Db(e),
+        })
+}
+
+#[cfg(not(feature = "encryption"))]
+fn apply_key(_conn: &Connection) -> Result<(), ItrError> {
+    Err(no_encryption())
+}
+
+fn no_encryption() -> ItrError {
+    ItrError::Encryption(
+        "this itr was built without SQLCipher; reinstall with `cargo install --features encryption`"
+            .to_string(),
+    )
+}
+
+pub fn open_db(path: &Path) -> Result<Connection, ItrError> {
+    let conn = Connection::open(path)?;
+    // The key has to come before anything else reads the file.
+    if is_encrypted(path) {
+        apply_key(&conn)?;
+    }
+    // busy_timeout makes concurrent writers (e.g. parallel `itr claim`) wait
+    // for the write lock instead of failing immediately with SQLITE_BUSY.
+    conn.execute_batch(
+        "PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON; PRAGMA busy_timeout=5000;",
+    )?;
+    migrate_current_schema(&conn)?;
+    try_create_fts(&conn);
+    apply_config_pragmas(&conn);
+    Ok(conn)
+}
+
+/// Connection pragmas tunable with `itr config set`, as `(key, pragma)`.
+/// Applied on every `open_db`, after the built-in defaults above.
+pub const PRAGMA_KEYS: &[(&str, &str)] = &[
+    ("db.busy_timeout", "busy_timeout"),
+    ("db.synchronous", "synchronous"),
+    ("db.cache_size", "cache_size"),
+];
+
+/// Retry settings for [`begin_write`], as `(key, default)`.
+pub const RETRY_KEYS: &[(&str, u64)] = &[("db.busy_retries", 3), ("db.busy_backoff_ms", 100)];
+
+/// Normalize a value for one of [`PRAGMA_KEYS`] or [`RETRY_KEYS`], or
+/// explain why it is rejected. Pragma values are interpolated into a
+/// `PRAGMA` statement, so only integers and the fixed `synchronous` keywords
+/// get through.
+///
+/// - `db.busy_timeout`: milliseconds to wait on a locked database (>= 0).
+/// - `db.synchronous`: `OFF`, `NORMAL`, `FULL`, or `EXTRA` (any case).
+/// - `db.cache_size`: pages if positive, KiB if negative, as in `PRAGMA cache_size`.
+/// - `db.busy_retries`: extra attempts after the busy timeout expires (>= 0).
+/// - `db.busy_backoff_ms`: first sleep between attempts, doubled each time.
+/// - `db.search`: `walk-up` (default) or `cwd`, how [`find_db`] looks for
+///   `.itr.db`; and `db.fallback`, a database to use when it finds none.
+///   Both are read from the user-level config only (passed to [`find_db`]),
+///   since no database is open yet.
+pub fn validate_db_setting(key: &str, value: &str) -> Result<String, String> {
+    let value = value.trim();
+    match key {
+        "db.busy_retries" => value
+            .parse::<u32>()
+            .ok()
+            .filter(|n| *n <= 20)
+            .map(|n| n.to_string())
+            .ok_or_else(|| "expected a whole number of retries from 0 to 20".to_string()),
+        "db.busy_backoff_ms" => value
+            .parse::<u32>()
+            .map(|n| n.to_string())
+            .map_err(|_| "expected a non-negative number of milliseconds".to_string()),
+        "db.busy_timeout" => value
+            .parse::<u32>()
+            .map(|n| n.to_string())
+            .map_err(|_| "expected a non-negative number of milliseconds".to_string()),
+        "db.synchronous" => {
+            let upper = value.to_ascii_uppercase();
+            if ["OFF", "NORMAL", "FULL", "EXTRA"].contains(&upper.as_str()) {
+                Ok(upper)
+            } else {
+                Err("expected OFF, NORMAL, FULL, or EXTRA".to_string())
+            }
+        }
+        "db.cache_size" => value
+            .parse::<i64>()
+            .map(|n| n.to_string())
+            .map_err(|_| "expected an integer (pages, or KiB if negative)".to_string()),
+        "db.search" => match value.to_ascii_lowercase().as_str() {
+            v @ ("walk-up" | "cwd") => Ok(v.to_string()),
+            _ => Err("expected walk-up or cwd".to_string()),
+        },
+        "db.fallback" => Ok(value.to_string()),
+        _ => {
+            let keys: Vec<&str> = PRAGMA_KEYS
+                .iter()
+                .map(|(k, _)| *k)
+                .chain(RETRY_KEYS.iter().map(|(k, _)| *k))
+                .collect();
+            let valid = keys.join(", ");
+            Err(format!(
+                "unknown key{} (valid: {})",
+                crate::util::did_you_mean(key, &valid),
+                valid
+            ))
+        }
+    }
+}
+
+/// Apply stored `db.*` pragmas. Soft fallback: a bad stored value is skipped
+/// with a `REVIEW:` note and the built-in default stays in effect.
+fn apply_config_pragmas(conn: &Connection) {
+    for &(key, pragma) in PRAGMA_KEYS {
+        let Ok(Some(raw)) = config_get(conn, key) else {
+            continue;
+        };
+        let applied = validate_db_setting(key, &raw).and_then(|value| {
+            conn.execute_batch(&format!("PRAGMA {}={};", pragma, value))
+                .map_err(|e| e.to_string())
+        });
+        if let Err(e) = applied {
+            crate::util::diagnostic(&format!(
+                "REVIEW: config '{}={}' not applied: {}",
+                key, raw, e
+            ));
+        }
+    }
+}
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Enable `--verbose` diagnostics (currently: busy retries) for this process.
+pub fn set_verbose(on: bool) {
+    VERBOSE.store(on, Ordering::Relaxed);
+}
+
+/// Config key naming the project `itr project switch` made current.
+pub const CURRENT_PROJECT_KEY: &str = "project.current";
+
+/// Give this connection a layer of config defaults (the CLI passes the
+/// user-level `~/.config/itr/config.toml`). [`config_get`] falls back to it
+/// for keys the database doesn't set, so a per-project value always wins
+/// over the user's default. The layer lives in a `TEMP` table, so it is
+/// never written to the file; stored `db.*` pragmas are reapplied on top.
+pub fn set_config_defaults(
+    conn: &Connection,
+    values: &BTreeMap<String, String>,
+) -> Result<(), ItrError> {
+    conn.execute_batch(
+        "CREATE TEMP TABLE IF NOT EXISTS config_defaults (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+         DELETE FROM temp.config_defaults;",
+    )?;
+    let mut stmt = conn.prepare("INSERT INTO temp.config_defaults (key, value) VALUES (?1, ?2)")?;
+    for (key, value) in values {
+        stmt.execute(params![key, value])?;
+    }
+    apply_config_pragmas(conn);
+    Ok(())
+}
+
+/// One key from the layer set by [`set_config_defaults`]; `None` also when
+/// the connection has no layer.
+fn config_default(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM temp.config_defaults WHERE key = ?1",
+        params![key],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// Start a write transaction, retrying while the database is busy.
+///
+/// The transaction is IMMEDIATE, so the write lock is taken up front and the
+/// statements inside cannot hit `SQLITE_BUSY` halfway (a deferred transaction
+/// that reads first fails without waiting when another writer commits in
+/// between). `busy_timeout` already waits at `BEGIN`; when it runs out, this
+/// sleeps `db.busy_backoff_ms` (doubling) and tries again, up to
+/// `db.busy_retries` times. Under `--verbose` each retry is reported as a
+/// diagnostic note. Use it in place of `unchecked_transaction` for every write.
+///
+/// Inside an already-open transaction (the `--dry-run` wrapper, see
+/// [`dry_run`]) this opens a savepoint instead, so commands keep their own
+/// commit/rollback semantics while the outer transaction decides the fate
+/// of everything.
+pub fn begin_write(conn: &Connection) -> Result<WriteTx<'_>, ItrError> {
+    if !conn.is_autocommit() {
+        conn.execute_batch("SAVEPOINT itr_write")?;
+        return Ok(WriteTx {
+            conn,
+            nested: true,
+            done: false,
+        });
+    }
+    let mut attempt = 0u32;
+    let mut policy = None;
+    loop {
+        match conn.execute_batch("BEGIN IMMEDIATE") {
+            Ok(()) => {
+                return Ok(WriteTx {
+                    conn,
+                    nested: false,
+                    done: false,
+                })
+            }
+            Err(e) if is_busy(&e) => {
+                let (retries, backoff_ms) = *policy.get_or_insert_with(|| retry_policy(conn));
+                if attempt >= retries {
+                    if attempt > 0 && VERBOSE.load(Ordering::Relaxed) {
+                        crate::util::diagnostic(&format!(
+                            "RETRY: database still busy after {} retries",
+                            attempt
+                        ));
+                    }
+                    return Err(ItrError::Db(e));
+                }
+                attempt += 1;
+                let wait = backoff_ms.saturating_mul(1 << (attempt - 1).min(16));
+                if VERBOSE.load(Ordering::Relaxed) {
+                    crate::util::diagnostic(&format!(
+                        "RETRY: database busy, retry {}/{} in {}ms",
+                        attempt, retries, wait
+                    ));
+                }
+                std::thread::sleep(std::time::Duration::from_millis(wait));
+            }
+            Err(e) => return Err(ItrError::Db(e)),
+        }
+    }
+}
+
+/// A write transaction from [`begin_write`]. Derefs to the connection;
+/// dropping it without [`WriteTx::commit`] rolls back.
+#[derive(Debug)]
+pub struct WriteTx<'conn> {
+    conn: &'conn Connection,
+    nested: bool,
+    done: bool,
+}
+
+impl WriteTx<'_> {
+    pub fn commit(mut self) -> Result<(), ItrError> {
+        self.done = true;
+        self.conn.execute_batch(if self.nested {
+            "RELEASE itr_write"
+        } else {
+            "COMMIT"
+        })?;
+        Ok(())
+    }
+
+    pub fn rollback(mut self) -> Result<(), ItrError> {
+        self.done = true;
+        self.conn.execute_batch(if self.nested {
+            "ROLLBACK TO itr_write; RELEASE itr_write"
+        } else {
+            "ROLLBACK"
+        })?;
+        Ok(())
+    }
+}
+
+impl std::ops::Deref for WriteTx<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+    }
+}
+
+impl Drop for WriteTx<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            let _ = self.conn.execute_batch(if self.nested {
+                "ROLLBACK TO itr_write; RELEASE itr_write"
+            } else {
+                "ROLLBACK"
+            });
+        }
+    }
+}
+
+/// Run `f` inside a transaction that is always rolled back: `--dry-run`.
+/// Every `begin_write` inside becomes a savepoint, so `f` reads back its own
+/// writes (output, newly unblocked issues) exactly as a real run would, and
+/// none of it reaches the database.
+pub fn dry_run<T>(
+    conn: &Connection,
+    f: impl FnOnce() -> Result<T, ItrError>,
+) -> Result<T, ItrError> {
+    let outer = begin_write(conn)?;
+    let result = f();
+    outer.rollback()?;
+    result
+}
+
+fn is_busy(e: &rusqlite::Error) -> bool {
+    matches!(
+        e,
+        rusqlite::Error::SqliteFailure(f, _)
+            if matches!(f.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// `(retries, backoff_ms)` from config, falling back per key to
+/// [`RETRY_KEYS`] defaults. Reading config can itself be refused while the
+/// database is locked; the defaults cover that too.
+fn retry_policy(conn: &Connection) -> (u32, u64) {
+    let get = |key: &str, default: u64| -> u64 {
+        config_get(conn, key)
+            .ok()
+            .flatten()
+            .and_then(|raw| validate_db_setting(key, &raw).ok())
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    };
+    let (retries, backoff) = (RETRY_KEYS[0], RETRY_KEYS[1]);
+    (
+        u32::try_from(get(retries.0, retries.1)).unwrap_or(0),
+        get(backoff.0, backoff.1),
+    )
+}
+
+fn migrate_current_schema(conn: &Connection) -> Result<(), ItrError> {
+    migrate_add_skills(conn)?;
+    migrate_add_assigned_to(conn)?;
+    migrate_add_events(conn)?;
+    migrate_add_relations(conn)?;
+    migrate_add_branch(conn)?;
+    migrate_add_list_tables(conn)?;
+    migrate_add_tag_meta(conn)?;
+    migrate_add_urgency_cache(conn)?;
+    migrate_add_snapshots(conn)?;
+    migrate_add_sync_state(conn)?;
+    migrate_add_uuids(conn)?;
+    migrate_add_projects(conn)?;
+    migrate_add_issue_keys(conn)?;
+    migrate_add_agents(conn)?;
+    migrate_add_note_kind(conn)?;
+    migrate_add_sessions(conn)?;
+    migrate_add_issue_commits(conn)?;
+    migrate_add_review_status(conn)?;
+    migrate_add_estimate(conn)?;
+    migrate_add_external_blockers(conn)?;
+    migrate_add_severity(conn)?;
+    migrate_add_components(conn)?;
+    migrate_add_versions(conn)?;
+    migrate_add_repro(conn)?;
+    migrate_add_issue_references(conn)?;
+    Ok(())
+}
+
+fn migrate_add_skills(conn: &Connection) -> Result<(), ItrError> {
+    let has_skills: bool = conn
+        .prepare("PRAGMA table_info(issues)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|col| col.as_deref() == Ok("skills"));
+    if !has_skills {
+        conn.execute_batch("ALTER TABLE issues ADD COLUMN skills TEXT NOT NULL DEFAULT '[]';")?;
+    }
+    Ok(())
+}
+
+fn migrate_add_assigned_to(conn: &Connection) -> Result<(), ItrError> {
+    let has_col: bool = conn
+        .prepare("PRAGMA table_info(issues)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|col| col.as_deref() == Ok("assigned_to"));
+    if !has_col {
+        conn.execute_batch("ALTER TABLE issues ADD COLUMN assigned_to TEXT NOT NULL DEFAULT '';")?;
+    }
+    Ok(())
+}
+
+fn migrate_add_note_kind(conn: &Connection) -> Result<(), ItrError> {
+    let has_col: bool = conn
+        .prepare("PRAGMA table_info(notes)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|col| col.as_deref() == Ok("kind"));
+    if !has_col {
+        conn.execute_batch("ALTER TABLE notes ADD COLUMN kind TEXT NOT NULL DEFAULT '';")?;
+    }
+    Ok(())
+}
+
+fn migrate_add_branch(conn: &Connection) -> Result<(), ItrError> {
+    let has_col: bool = conn
+        .prepare("PRAGMA table_info(issues)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|col| col.as_deref() == Ok("branch"));
+    if !has_col {
+        conn.execute_batch("ALTER TABLE issues ADD COLUMN branch TEXT NOT NULL DEFAULT '';")?;
+    }
+    Ok(())
+}
+
+/// Story-point estimates for `itr plan-sprint`; NULL means unestimated.
+fn migrate_add_estimate(conn: &Connection) -> Result<(), ItrError> {
+    let has_col: bool = conn
+        .prepare("PRAGMA table_info(issues)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|col| col.as_deref() == Ok("estimate"));
+    if !has_col {
+        conn.execute_batch(
+            "ALTER TABLE issues ADD COLUMN estimate INTEGER CHECK (estimate >= 0);",
+        )?;
+    }
+    Ok(())
+}
+
+/// Bug severity, separate from scheduling priority; empty means unset.
+fn migrate_add_severity(conn: &Connection) -> Result<(), ItrError> {
+    let has_col: bool = conn
+        .prepare("PRAGMA table_info(issues)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|col| col.as_deref() == Ok("severity"));
+    if !has_col {
+        conn.execute_batch(
+            "ALTER TABLE issues ADD COLUMN severity TEXT NOT NULL DEFAULT ''
+             CHECK (severity IN ('', 'blocker', 'major', 'minor', 'trivial'));",
+        )?;
+    }
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_issues_severity ON issues(severity);")?;
+    Ok(())
+}
+
+/// The component registry and each issue's place in it.
+fn migrate_add_components(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS components (
+            id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+            name                TEXT NOT NULL UNIQUE,
+            default_assignee    TEXT NOT NULL DEFAULT '',
+            default_tags        TEXT NOT NULL DEFAULT '[]',
+            created_at          TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );",
+    )?;
+    let has_col: bool = conn
+        .prepare("PRAGMA table_info(issues)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|col| col.as_deref() == Ok("component_id"));
+    if !has_col {
+        conn.execute_batch(
+            "ALTER TABLE issues ADD COLUMN component_id INTEGER REFERENCES components(id) ON DELETE SET NULL;",
+        )?;
+    }
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_issues_component ON issues(component_id);")?;
+    Ok(())
+}
+
+/// The release a defect was seen in and the one that fixes it; empty means
+/// unset.
+fn migrate_add_versions(conn: &Connection) -> Result<(), ItrError> {
+    let cols: Vec<String> = conn
+        .prepare("PRAGMA table_info(issues)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<_, _>>()?;
+    for col in ["affects_version", "fixed_in_version"] {
+        if !cols.iter().any(|c| c == col) {
+            conn.execute_batch(&format!(
+                "ALTER TABLE issues ADD COLUMN {} TEXT NOT NULL DEFAULT '';",
+                col
+            ))?;
+        }
+    }
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_issues_affects_version ON issues(affects_version);",
+    )?;
+    Ok(())
+}
+
+/// Structured reproduction steps as a JSON object; empty means none.
+fn migrate_add_repro(conn: &Connection) -> Result<(), ItrError> {
+    let has_col: bool = conn
+        .prepare("PRAGMA table_info(issues)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|col| col.as_deref() == Ok("repro"));
+    if !has_col {
+        conn.execute_batch("ALTER TABLE issues ADD COLUMN repro TEXT NOT NULL DEFAULT '';")?;
+    }
+    Ok(())
+}
+
+/// Cross-references parsed from `#<id>` mentions (see
+/// [`refresh_references`]). Rows are derived data: every write to an issue's
+/// context or notes rebuilds that issue's rows. `target_id` is deliberately
+/// not a foreign key — a mention of an issue that doesn't exist yet starts
+/// counting once it does, since IDs are never reused.
+const ISSUE_REFERENCES: &str = "
+CREATE TABLE IF NOT EXISTS issue_references (
+    source_id   INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    target_id   INTEGER NOT NULL,
+    PRIMARY KEY (source_id, target_id)
+);
+CREATE INDEX IF NOT EXISTS idx_issue_references_target ON issue_references(target_id);
+";
+
+fn migrate_add_issue_references(conn: &Connection) -> Result<(), ItrError> {
+    let has_table: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='issue_references'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_table {
+        let tx = begin_write(conn)?;
+        tx.execute_batch(ISSUE_REFERENCES)?;
+        refresh_all_references(&tx)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// Join tables mirroring the `files` / `tags` JSON arrays. The JSON columns
+/// stay the source of truth for reads and writes; triggers keep the tables in
+/// step on every insert and update, so no write path has to know about them.
+const LIST_TABLES: &str = "
+CREATE TABLE IF NOT EXISTS issue_files (
+    issue_id    INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    path        TEXT NOT NULL,
+    PRIMARY KEY (issue_id, path)
+);
+CREATE TABLE IF NOT EXISTS issue_tags (
+    issue_id    INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    tag         TEXT NOT NULL,
+    PRIMARY KEY (issue_id, tag)
+);
+CREATE INDEX IF NOT EXISTS idx_issue_files_path ON issue_files(path);
+CREATE INDEX IF NOT EXISTS idx_issue_tags_tag ON issue_tags(tag);
+
+CREATE TRIGGER IF NOT EXISTS trg_issue_lists_ai AFTER INSERT ON issues BEGIN
+    -- INSERT OR REPLACE (import) may leave the replaced row's entries behind.
+    DELETE FROM issue_files WHERE issue_id = new.id;
+    DELETE FROM issue_tags WHERE issue_id = new.id;
+    INSERT OR IGNORE INTO issue_files(issue_id, path)
+        SELECT new.id, value FROM json_each(CASE WHEN json_valid(new.files) THEN new.files ELSE '[]' END);
+    INSERT OR IGNORE INTO issue_tags(issue_id, tag)
+        SELECT new.id, value FROM json_each(CASE WHEN json_valid(new.tags) THEN new.tags ELSE '[]' END);
+END;
+CREATE TRIGGER IF NOT EXISTS trg_issue_lists_files_au AFTER UPDATE OF files ON issues BEGIN
+    DELETE FROM issue_files WHERE issue_id = old.id;
+    INSERT OR IGNORE INTO issue_files(issue_id, path)
+        SELECT new.id, value FROM json_each(CASE WHEN json_valid(new.files) THEN new.files ELSE '[]' END);
+END;
+CREATE TRIGGER IF NOT EXISTS trg_issue_lists_tags_au AFTER UPDATE OF tags ON issues BEGIN
+    DELETE FROM issue_tags WHERE issue_id = old.id;
+    INSERT OR IGNORE INTO issue_tags(issue_id, tag)
+        SELECT new.id, value FROM json_each(CASE WHEN json_valid(new.tags) THEN new.tags ELSE '[]' END);
+END;
+";
+
+/// Backfill rows for databases created before the join tables existed.
+const LIST_TABLES_BACKFILL: &str = "
+INSERT OR IGNORE INTO issue_files(issue_id, path)
+    SELECT i.id, j.value FROM issues i,
+        json_each(CASE WHEN json_valid(i.files) THEN i.files ELSE '[]' END) j;
+INSERT OR IGNORE INTO issue_tags(issue_id, tag)
+    SELECT i.id, j.value FROM issues i,
+        json_each(CASE WHEN json_valid(i.tags) THEN i.tags ELSE '[]' END) j;
+";
+
+fn migrate_add_list_tables(conn: &Connection) -> Result<(), ItrError> {
+    // Triggers are checked rather than tables: a fresh `init` creates the
+    // tables from SCHEMA, but only this migration installs the triggers.
+    let has_triggers: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='trigger' AND name='trg_issue_lists_ai'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_triggers {
+        let tx = begin_write(conn)?;
+        tx.execute_batch(LIST_TABLES)?;
+        tx.execute_batch(LIST_TABLES_BACKFILL)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn migrate_add_tag_meta(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tags (
+            name        TEXT PRIMARY KEY,
+            description TEXT NOT NULL DEFAULT '',
+            color       TEXT NOT NULL DEFAULT ''
+        );",
+    )?;
+    Ok(())
+}
+
+fn migrate_add_agents(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS agents (
+            name          TEXT PRIMARY KEY,
+            skills        TEXT NOT NULL DEFAULT '[]',
+            description   TEXT NOT NULL DEFAULT '',
+            registered_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            last_seen_at  TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );",
+    )?;
+    Ok(())
+}
+
+fn migrate_add_sessions(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            agent       TEXT NOT NULL DEFAULT '',
+            started_at  TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            ended_at    TEXT,
+            summary     TEXT NOT NULL DEFAULT '',
+            issues      TEXT NOT NULL DEFAULT '[]'
+        );",
+    )?;
+    Ok(())
+}
+
+fn migrate_add_issue_commits(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS issue_commits (
+            issue_id    INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+            hash        TEXT NOT NULL,
+            subject     TEXT NOT NULL DEFAULT '',
+            relation    TEXT NOT NULL DEFAULT 'mention' CHECK(relation IN ('mention', 'close')),
+            linked_at   TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            PRIMARY KEY (issue_id, hash)
+        );",
+    )?;
+    Ok(())
+}
+
+/// Widen the `issues.status` CHECK to admit `review` in databases created
+/// before it existed. `SQLite` cannot alter a CHECK, so the stored table SQL is
+/// rewritten in place: the documented shortcut for loosening a constraint
+/// every existing row already satisfies. Bumping `schema_version` makes
+/// other connections reload the schema.
+fn migrate_add_review_status(conn: &Connection) -> Result<(), ItrError> {
+    const OLD: &str = "'in-progress', 'done'";
+    let sql: String = conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'issues'",
+        [],
+        |row| row.get(0),
+    )?;
+    if sql.contains("'review'") || !sql.contains(OLD) {
+        return Ok(());
+    }
+    let version: i64 = conn.query_row("PRAGMA schema_version", [], |row| row.get(0))?;
+    let tx = begin_write(conn)?;
+    tx.execute_batch("PRAGMA writable_schema = ON;")?;
+    tx.execute(
+        "UPDATE sqlite_master SET sql = ?1 WHERE type = 'table' AND name = 'issues'",
+        params![sql.replace(OLD, "'in-progress', 'review', 'done'")],
+    )?;
+    tx.execute_batch(&format!(
+        "PRAGMA schema_version = {}; PRAGMA writable_schema = OFF;",
+        version + 1
+    ))?;
+    tx.commit()
+}
+
+fn migrate_add_snapshots(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS snapshots (
+            name        TEXT PRIMARY KEY,
+            data        TEXT NOT NULL,
+            created_at  TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );",
+    )?;
+    Ok(())
+}
+
+fn migrate_add_sync_state(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS sync_state (
+            remote        TEXT PRIMARY KEY,
+            pushed_event  INTEGER NOT NULL DEFAULT 0,
+            pushed_issue  INTEGER NOT NULL DEFAULT 0,
+            pulled_event  INTEGER NOT NULL DEFAULT 0,
+            pulled_issue  INTEGER NOT NULL DEFAULT 0,
+            synced_at     TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );",
+    )?;
+    Ok(())
+}
+
+/// A random (version 4) UUID, generated in SQL so triggers can assign one.
+macro_rules! new_uuid_sql {
+    () => {
+        "lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-' || substr('89ab', 1 + abs(random()) % 4, 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6)))"
+    };
+}
+
+/// Every issue and note gets a UUID on insert, whichever path inserts it.
+/// IDs are per-database autoincrement values, so two copies of a database
+/// hand out the same IDs to different issues; UUIDs let `import --merge`
+/// recognize the same issue under any ID. Writers that carry an identity
+/// over (import) replace the generated UUID afterwards.
+const UUIDS: &str = concat!(
+    "
+CREATE TABLE IF NOT EXISTS issue_uuids (
+    issue_id    INTEGER PRIMARY KEY REFERENCES issues(id) ON DELETE CASCADE,
+    uuid        TEXT NOT NULL UNIQUE
+);
+CREATE TABLE IF NOT EXISTS note_uuids (
+    note_id     INTEGER PRIMARY KEY REFERENCES notes(id) ON DELETE CASCADE,
+    uuid        TEXT NOT NULL UNIQUE
+);
+CREATE TRIGGER IF NOT EXISTS trg_issue_uuid_ai AFTER INSERT ON issues BEGIN
+    INSERT OR IGNORE INTO issue_uuids(issue_id, uuid) VALUES (new.id, ",
+    new_uuid_sql!(),
+    ");
+END;
+CREATE TRIGGER IF NOT EXISTS trg_note_uuid_ai AFTER INSERT ON notes BEGIN
+    INSERT OR IGNORE INTO note_uuids(note_id, uuid) VALUES (new.id, ",
+    new_uuid_sql!(),
+    ");
+END;
+INSERT OR IGNORE INTO issue_uuids(issue_id, uuid) SELECT id, ",
+    new_uuid_sql!(),
+    " FROM issues;
+INSERT OR IGNORE INTO note_uuids(note_id, uuid) SELECT id, ",
+    new_uuid_sql!(),
+    " FROM notes;
+"
+);
+
+fn migrate_add_uuids(conn: &Connection) -> Result<(), ItrError> {
+    // As with the list tables, the trigger is what a fresh `init` lacks.
+    let has_trigger: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='trigger' AND name='trg_issue_uuid_ai'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_trigger {
+        let tx = begin_write(conn)?;
+        tx.execute_batch(UUIDS)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn migrate_add_projects(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS projects (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            name        TEXT NOT NULL UNIQUE,
+            description TEXT NOT NULL DEFAULT '',
+            created_at  TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );",
+    )?;
+    let has_col: bool = conn
+        .prepare("PRAGMA table_info(issues)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|col| col.as_deref() == Ok("project_id"));
+    if !has_col {
+        conn.execute_batch(
+            "ALTER TABLE issues ADD COLUMN project_id INTEGER REFERENCES projects(id) ON DELETE SET NULL;",
+        )?;
+    }
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_issues_project ON issues(project_id);")?;
+    Ok(())
+}
+
+/// Human-readable issue keys (`APP-42`). A project with a `key_prefix` hands
+/// each issue that joins it, on insert or by moving in, the next number after
+/// the highest key with that prefix. Keys never change afterwards, so they
+/// survive moves, prefix changes, and import into a copy where the integer
+/// IDs differ.
+const ISSUE_KEYS: &str = "
+CREATE TABLE IF NOT EXISTS issue_keys (
+    issue_id    INTEGER PRIMARY KEY REFERENCES issues(id) ON DELETE CASCADE,
+    key         TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+CREATE TRIGGER IF NOT EXISTS trg_issue_key_ai AFTER INSERT ON issues
+WHEN new.project_id IS NOT NULL BEGIN
+    INSERT OR IGNORE INTO issue_keys(issue_id, key)
+    SELECT new.id, p.key_prefix || '-' || (
+        SELECT COALESCE(MAX(CAST(substr(k.key, length(p.key_prefix) + 2) AS INTEGER)), 0) + 1
+        FROM issue_keys k WHERE k.key LIKE p.key_prefix || '-%')
+    FROM projects p WHERE p.id = new.project_id AND p.key_prefix != '';
+END;
+CREATE TRIGGER IF NOT EXISTS trg_issue_key_au AFTER UPDATE OF project_id ON issues
+WHEN new.project_id IS NOT NULL BEGIN
+    INSERT OR IGNORE INTO issue_keys(issue_id, key)
+    SELECT new.id, p.key_prefix || '-' || (
+        SELECT COALESCE(MAX(CAST(substr(k.key, length(p.key_prefix) + 2) AS INTEGER)), 0) + 1
+        FROM issue_keys k WHERE k.key LIKE p.key_prefix || '-%')
+    FROM projects p WHERE p.id = new.project_id AND p.key_prefix != '';
+END;
+";
+
+fn migrate_add_issue_keys(conn: &Connection) -> Result<(), ItrError> {
+    let has_col: bool = conn
+        .prepare("PRAGMA table_info(projects)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|col| col.as_deref() == Ok("key_prefix"));
+    if !has_col {
+        conn.execute_batch("ALTER TABLE projects ADD COLUMN key_prefix TEXT NOT NULL DEFAULT '';")?;
+    }
+    conn.execute_batch(ISSUE_KEYS)?;
+    Ok(())
+}
+
+/// Per-issue cache of the urgency inputs that cost a query each: whether the
+/// issue blocks active work, whether it is blocked, and its note count, plus
+/// the score last computed from them. Age and coefficients are cheap and
+/// time- or config-dependent, so they are always applied fresh on read.
+///
+/// `dirty_at` is set by triggers whenever an input may have changed: any
+/// update to the issue (and to its dependency neighbours, whose blocked /
+/// blocking state follows its status), dependency edges, and notes. A dirty
+/// or missing row is recomputed on next read; `itr recompute` refreshes all.
+const URGENCY_CACHE: &str = "
+CREATE TABLE IF NOT EXISTS urgency_cache (
+    issue_id        INTEGER PRIMARY KEY REFERENCES issues(id) ON DELETE CASCADE,
+    blocking        INTEGER NOT NULL,
+    blocked         INTEGER NOT NULL,
+    note_count      INTEGER NOT NULL,
+    urgency         REAL NOT NULL,
+    computed_at     TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    dirty_at        TEXT
+);
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_issue AFTER UPDATE ON issues BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND (issue_id = new.id
+        OR issue_id IN (SELECT blocker_id FROM dependencies WHERE blocked_id = new.id)
+        OR issue_id IN (SELECT blocked_id FROM dependencies WHERE blocker_id = new.id));
+END;
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_dep_ai AFTER INSERT ON dependencies BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id IN (new.blocker_id, new.blocked_id);
+END;
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_dep_ad AFTER DELETE ON dependencies BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id IN (old.blocker_id, old.blocked_id);
+END;
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_note_ai AFTER INSERT ON notes BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id = new.issue_id;
+END;
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_note_ad AFTER DELETE ON notes BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id = old.issue_id;
+END;
+";
+
+fn migrate_add_urgency_cache(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute_batch(URGENCY_CACHE)?;
+    Ok(())
+}
+
+/// Blockers outside the tracker (an upstream release, a vendor fix) from
+/// `itr depend --on-external`. Each row blocks its issue exactly like an
+/// unfinished blocker issue until `itr resolve-external` deletes it; the
+/// triggers mark the issue's cached urgency inputs dirty on both.
+const EXTERNAL_BLOCKERS: &str = "
+CREATE TABLE IF NOT EXISTS external_blockers (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    issue_id        INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    description     TEXT NOT NULL,
+    url             TEXT NOT NULL DEFAULT '',
+    created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+CREATE INDEX IF NOT EXISTS idx_external_blockers_issue ON external_blockers(issue_id);
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_ext_ai AFTER INSERT ON external_blockers BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id = new.issue_id;
+END;
+CREATE TRIGGER IF NOT EXISTS trg_urgency_dirty_ext_ad AFTER DELETE ON external_blockers BEGIN
+    UPDATE urgency_cache SET dirty_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE dirty_at IS NULL AND issue_id = old.issue_id;
+END;
+";
+
+fn migrate_add_external_blockers(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute_batch(EXTERNAL_BLOCKERS)?;
+    Ok(())
+}
+
+fn migrate_add_events(conn: &Connection) -> Result<(), ItrError> {
+    let has_table: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='events'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_table {
+        conn.execute_batch(
+            "CREATE TABLE events (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                issue_id    INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+                field       TEXT NOT NULL,
+                old_value   TEXT NOT NULL DEFAULT '',
+                new_value   TEXT NOT NULL DEFAULT '',
+                agent       TEXT NOT NULL DEFAULT '',
+                created_at  TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            );",
+        )?;
+    }
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_events_issue ON events(issue_id);
+         CREATE INDEX IF NOT EXISTS idx_events_created ON events(created_at);",
+    )?;
+    Ok(())
+}
+
+fn migrate_add_relations(conn: &Connection) -> Result<(), ItrError> {
+    let has_table: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='relations'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_table {
+        conn.execute_batch(
+            "CREATE TABLE relations (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_id       INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+                target_id       INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+                relation_type   TEXT NOT NULL CHECK(relation_type IN ('duplicate', 'related', 'supersedes')),
+                created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                UNIQUE(source_id, target_id, relation_type)
+            );",
+        )?;
+    }
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_relations_source ON relations(source_id);
+         CREATE INDEX IF NOT EXISTS idx_relations_target ON relations(target_id);",
+    )?;
+    Ok(())
+}
+
+pub fn init_db(path: &Path) -> Result<Connection, ItrError> {
+    init_schema(Connection::open(path)?)
+}
+
+/// Like [`init_db`], but the new database is encrypted with [`passphrase`].
+/// Fails without creating anything when no passphrase is set or this build
+/// lacks the `encryption` feature.
+pub fn init_encrypted_db(path: &Path) -> Result<Connection, ItrError> {
+    if cfg!(not(feature = "encryption")) {
+        return Err(no_encryption());
+    }
+    let key = passphrase()?;
+    let conn = Connection::open(path)?;
+    conn.pragma_update(None, "key", key)?;
+    init_schema(conn)
+}
+
+fn init_schema(conn: Connection) -> Result<Connection, ItrError> {
+    conn.execute_batch(SCHEMA)?;
+    migrate_current_schema(&conn)?;
+    try_create_fts(&conn);
+    Ok(conn)
+}
+
+pub fn get_schema_sql() -> &'static str {
+    SCHEMA
+}
+
+// --- Issue CRUD ---
+
+#[allow(clippy::too_many_arguments)]
+pub fn insert_issue(
+    conn: &Connection,
+    title: &str,
+    priority: &str,
+    kind: &str,
+    context: &str,
+    files: &[String],
+    tags: &[String],
+    skills: &[String],
+    acceptance: &str,
+    parent_id: Option<i64>,
+    assigned_to: &str,
+) -> Result<Issue, ItrError> {
+    let files_json = serde_json::to_string(files)?;
+    let tags_json = serde_json::to_string(tags)?;
+    let skills_json = serde_json::to_string(skills)?;
+
+    conn.execute(
+        "INSERT INTO issues (title, priority, kind, context, files, tags, skills, acceptance, parent_id, assigned_to)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![title, priority, kind, context, files_json, tags_json, skills_json, acceptance, parent_id, assigned_to],
+    )?;
+
+    let id = conn.last_insert_rowid();
+    let issue = get_issue(conn, id)?;
+    fts_index_issue(conn, &issue);
+    refresh_references(conn, id)?;
+    Ok(issue)
+}
+
+pub fn get_issue(conn: &Connection, id: i64) -> Result<Issue, ItrError> {
+    conn.prepare_cached(
+        "SELECT id, title, status, priority, kind, context, files, tags, skills, acceptance, parent_id, close_reason, created_at, updated_at, assigned_to, branch, COALESCE((SELECT name FROM projects WHERE id = issues.project_id), ''), severity, COALESCE((SELECT name FROM components WHERE id = issues.component_id), ''), affects_version, fixed_in_version, estimate
+         FROM issues WHERE id = ?1",
+    )?
+    .query_row(params![id], row_to_issue)
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => ItrError::NotFound(id),
+        other => ItrError::Db(other),
+    })
+}
+
+pub fn issue_exists(conn: &Connection, id: i64) -> Result<bool, ItrError> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM issues WHERE id = ?1",
+        params![id],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+fn parse_json_array(s: String) -> Vec<String> {
+    serde_json::from_str(&s).unwrap_or_default()
+}
+
+/// Append an `AND column IN (?, ?, ...)` clause to the SQL string,
+/// pushing values into `param_values`. Returns the number of placeholders added.
+fn append_in_clause(
+    sql: &mut String,
+    param_values: &mut Vec<Box<dyn rusqlite::types::ToSql>>,
+    column: &str,
+    values: &[String],
+) {
+    let placeholders: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(i, _)| format!("?{}", param_values.len() + i + 1))
+        .collect();
+    sql.push_str(&format!(" AND {} IN ({})", column, placeholders.join(",")));
+    for v in values {
+        param_values.push(Box::new(v.clone()));
+    }
+}
+
+fn row_to_issue(row: &rusqlite::Row) -> rusqlite::Result<Issue> {
+    Ok(Issue {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        status: row.get(2)?,
+        priority: row.get(3)?,
+        kind: row.get(4)?,
+        context: row.get(5)?,
+        files: parse_json_array(row.get::<_, String>(6)?),
+        tags: parse_json_array(row.get::<_, String>(7)?),
+        skills: parse_json_array(row.get::<_, String>(8)?),
+        acceptance: row.get(9)?,
+        parent_id: row.get(10)?,
+        close_reason: row.get(11)?,
+        created_at: row.get(12)?,
+        updated_at: row.get(13)?,
+        assigned_to: row.get(14)?,
+        branch: row.get(15)?,
+        project: row.get(16)?,
+        severity: row.get(17)?,
+        component: row.get(18)?,
+        affects_version: row.get(19)?,
+        fixed_in_version: row.get(20)?,
+        estimate: row.get(21)?,
+    })
+}
+
+fn row_to_note(row: &rusqlite::Row) -> rusqlite::Result<Note> {
+    Ok(Note {
+        id: row.get(0)?,
+        issue_id: row.get(1)?,
+        content: row.get(2)?,
+        agent: row.get(3)?,
+        created_at: row.get(4)?,
+        kind: row.get(5)?,
+        uuid: String::new(),
+    })
+}
+
+fn row_to_event(row: &rusqlite::Row) -> rusqlite::Result<Event> {
+    Ok(Event {
+        id: row.get(0)?,
+        issue_id: row.get(1)?,
+        field: row.get(2)?,
+        old_value: row.get(3)?,
+        new_value: row.get(4)?,
+        agent: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn row_to_relation(row: &rusqlite::Row) -> rusqlite::Result<Relation> {
+    Ok(Relation {
+        id: row.get(0)?,
+        source_id: row.get(1)?,
+        target_id: row.get(2)?,
+        relation_type: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+pub fn list_issues(
+    conn: &Connection,
+    filter: &crate::models::ListFilter,
+) -> Result<Vec<Issue>, ItrError> {
+    let mut sql = String::from(
+        "SELECT id, title, status, priority, kind, context, files, tags, skills, acceptance, parent_id, close_reason, created_at, updated_at, assigned_to, branch, COALESCE((SELECT name FROM projects WHERE id = issues.project_id), ''), severity, COALESCE((SELECT name FROM components WHERE id = issues.component_id), ''), affects_version, fixed_in_version, estimate FROM issues WHERE 1=1",
+    );
+    let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+    if !filter.all {
+        if filter.statuses.is_empty() {
+            let defaults: Vec<String> = ACTIVE_STATUSES.iter().map(|s| (*s).to_string()).collect();
+            append_in_clause(&mut sql, &mut param_values, "status", &defaults);
+        } else {
+            append_in_clause(&mut sql, &mut param_values, "status", &filter.statuses);
+        }
+    }
+
+    if !filter.priorities.is_empty() {
+        append_in_clause(&mut sql, &mut param_values, "priority", &filter.priorities);
+    }
+    if !filter.severities.is_empty() {
+        append_in_clause(&mut sql, &mut param_values, "severity", &filter.severities);
+    }
+
+    if !filter.kinds.is_empty() {
+        append_in_clause(&mut sql, &mut param_values, "kind", &filter.kinds);
+    }
+    if !filter.components.is_empty() {
+        append_in_clause(
+            &mut sql,
+            &mut param_values,
+            "(SELECT name FROM components WHERE id = component_id)",
+            &filter.components,
+        );
+    }
+    if !filter.affects_versions.is_empty() {
+        append_in_clause(
+            &mut sql,
+            &mut param_values,
+            "affects_version",
+            &filter.affects_versions,
+        );
+    }
+    if !filter.fixed_in_versions.is_empty() {
+        append_in_clause(
+            &mut sql,
+            &mut param_values,
+            "fixed_in_version",
+            &filter.fixed_in_versions,
+        );
+    }
+
+    if let Some(pid) = filter.parent_id {
+        let p = param_values.len() + 1;
+        if filter.recursive {
+            let cte = subtree_cte(&format!("?{}", p));
+            sql.push_str(&format!(" AND id IN ({} SELECT id FROM subtree)", cte));
+        } else {
+            sql.push_str(&format!(" AND parent_id = ?{}", p));
+        }
+        param_values.push(Box::new(pid));
+    }
+
+    if let Some(ref agent) = filter.assigned_to {
+        let p = param_values.len() + 1;
+        sql.push_str(&format!(" AND assigned_to = ?{}", p));
+        param_values.push(Box::new(agent.clone()));
+    }
+
+    if let Some(ref project) = filter.project {
+        let p = param_values.len() + 1;
+        sql.push_str(&format!(
+            " AND project_id = (SELECT id FROM projects WHERE name = ?{})",
+            p
+        ));
+        param_values.push(Box::new(project.clone()));
+    }
+
+    // Tag and file filters hit the indexed join tables (AND logic).
+    for tag in &filter.tags {
+        let p = param_values.len() + 1;
+        sql.push_str(&format!(
+            " AND id IN (SELECT issue_id FROM issue_tags WHERE tag = ?{})",
+            p
+        ));
+        param_values.push(Box::new(tag.clone()));
+    }
+    for path in &filter.files {
+        let p = param_values.len() + 1;
+        sql.push_str(&format!(
+            " AND id IN (SELECT issue_id FROM issue_files WHERE path = ?{})",
+            p
+        ));
+        param_values.push(Box::new(path.clone()));
+    }
+    if !filter.tag_any.is_empty() {
+        let placeholders: Vec<String> = filter
+            .tag_any
+            .iter()
+            .enumerate()
+            .map(|(i, _)| format!("?{}", param_values.len() + i + 1))
+            .collect();
+        sql.push_str(&format!(
+            " AND id IN (SELECT issue_id FROM issue_tags WHERE tag IN ({}))",
+            placeholders.join(", ")
+        ));
+        for tag in &filter.tag_any {
+            param_values.push(Box::new(tag.clone()));
+        }
+    }
+
+    // Skills have no join table; match against the JSON array directly.
+    // Malformed JSON yields no elements, mirroring `row_to_issue`.
+    for skill in &filter.skills {
+        let p = param_values.len() + 1;
+        sql.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM json_each(CASE WHEN json_valid(skills) THEN skills ELSE '[]' END) WHERE value = ?{})",
+            p
+        ));
+        param_values.push(Box::new(skill.clone()));
+    }
+
+    if filter.blocked_only {
+        sql.push_str(&format!(" AND {}", BLOCKED_PREDICATE));
+    } else if !filter.include_blocked && !filter.all {
+        sql.push_str(&format!(" AND NOT {}", BLOCKED_PREDICATE));
+    }
+
+    // Deterministic base order: without an ORDER BY, SQLite is free to return
+    // rows in index-scan order, which makes in-memory stable sorts (urgency
+    // ties, priority ties) and unsorted callers nondeterministic (#171).
+    // Column sorts keep the ID as their tiebreaker for the same reason.
+    sql.push_str(match filter.sort.as_deref() {
+        Some("priority") => {
+            " ORDER BY CASE priority WHEN 'critical' THEN 0 WHEN 'high' THEN 1 WHEN 'medium' THEN 2 WHEN 'low' THEN 3 ELSE 4 END, id"
+        }
+        Some("created") => " ORDER BY created_at, id",
+        Some("updated") => " ORDER BY updated_at DESC, id DESC",
+        _ => " ORDER BY id",
+    });
+    if filter.limit.is_some() || filter.offset.is_some() {
+        // SQLite needs a LIMIT before OFFSET; -1 means unbounded.
+        let limit = filter
+            .limit
+            .map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+        let offset = filter
+            .offset
+            .map_or(0, |n| i64::try_from(n).unwrap_or(i64::MAX));
+        let p = param_values.len() + 1;
+        sql.push_str(&format!(" LIMIT ?{} OFFSET ?{}", p, p + 1));
+        param_values.push(Box::new(limit));
+        param_values.push(Box::new(offset));
+    }
+
+    let params_ref: Vec<&dyn rusqlite::types::ToSql> = param_values
+        .iter()
+        .map(std::convert::AsRef::as_ref)
+        .collect();
+    let mut stmt = conn.prepare(&sql)?;
+    let issues: Vec<Issue> = stmt
+        .query_map(params_ref.as_slice(), row_to_issue)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(issues)
+}
+
+/// `--sort` keys that `list_issues` can order by in SQL, so limit and offset
+/// apply before any row is materialized. Urgency is computed, so it can't.
+pub const SQL_SORTS: &[&str] = &["id", "priority", "created", "updated"];
+
+// Defined in `models` so the SQLite-free `storage` backend shares them.
+pub use crate::models::{is_terminal, ACTIVE_STATUSES};
+
+/// SQL predicate over `issues` that holds when the row has at least one
+/// non-terminal blocker or an external blocker; the set-based twin of
+/// [`is_blocked`].
+const BLOCKED_PREDICATE: &str = "(EXISTS (SELECT 1 FROM dependencies d JOIN issues b ON d.blocker_id = b.id WHERE d.blocked_id = issues.id AND b.status NOT IN ('done', 'wontfix')) OR EXISTS (SELECT 1 FROM external_blockers x WHERE x.issue_id = issues.id))";
+
+pub fn update_issue_field(
+    conn: &Connection,
+    id: i64,
+    field: &str,
+    value: &str,
+) -> Result<(), ItrError> {
+    const VALID_COLUMNS: &[&str] = &[
+        "title",
+        "status",
+        "priority",
+        "kind",
+        "context",
+        "files",
+        "tags",
+        "skills",
+        "acceptance",
+        "close_reason",
+        "assigned_to",
+        "branch",
+        "severity",
+        "affects_version",
+        "fixed_in_version",
+    ];
+    if !VALID_COLUMNS.contains(&field) {
+        return Err(ItrError::InvalidValue {
+            field: "column".to_string(),
+            value: field.to_string(),
+            valid: VALID_COLUMNS.join(", "),
+        });
+    }
+    if !issue_exists(conn, id)? {
+        return Err(ItrError::NotFound(id));
+    }
+    let sql = format!("UPDATE issues SET {} = ?1 WHERE id = ?2", field);
+    conn.execute(&sql, params![value, id])?;
+
+    // Re-index FTS for searchable fields
+    match field {
+        "title" | "context" | "acceptance" | "tags" | "files" | "skills" | "close_reason" => {
+            if let Ok(issue) = get_issue(conn, id) {
+                fts_index_issue(conn, &issue);
+            }
+        }
+        _ => {}
+    }
+    if field == "context" {
+        refresh_references(conn, id)?;
+    }
+    Ok(())
+}
+
+/// Result of an atomic claim attempt (see [`claim_issue`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimOutcome {
+    /// The compare-and-swap won: the issue moved `open` -> `in-progress`
+    /// (and was assigned, when an agent was supplied).
+    Claimed { prior_assigned_to: String },
+    /// The issue was not `open` (already claimed, done, or wontfix).
+    /// Nothing was modified; the observed state is returned for reporting.
+    NotOpen { status: String, assigned_to: String },
+}
+
+/// Atomically claim an issue: transition `open` -> `in-progress` and record
+/// the assignment in a single transaction.
+///
+/// The UPDATE is guarded with `AND status = 'open'` (compare-and-swap), so a
+/// concurrent claimer that already won leaves this call with 0 affected rows
+/// and a `NotOpen` outcome instead of silently stealing the issue. The
+/// transaction starts IMMEDIATE so the pre-read of status/assignee is made
+/// under the write lock and cannot go stale before the UPDATE.
+pub fn claim_issue(
+    conn: &Connection,
+    id: i64,
+    agent: Option<&str>,
+) -> Result<ClaimOutcome, ItrError> {
+    let tx = begin_write(conn)?;
+    let (status, assigned_to): (String, String) = tx
+        .query_row(
+            "SELECT status, assigned_to FROM issues WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => ItrError::NotFound(id),
+            other => ItrError::Db(other),
+        })?;
+
+    let rows = tx.execute(
+        "UPDATE issues SET status = 'in-progress' WHERE id = ?1 AND status = 'open'",
+        params![id],
+    )?;
+    if rows == 0 {
+        // Lost the race (or the issue is closed); leave everything untouched.
+        return Ok(ClaimOutcome::NotOpen {
+            status,
+            assigned_to,
+        });
+    }
+
+    record_event(&tx, id, "status", &status, "in-progress")?;
+    if let Some(name) = agent {
+        touch_agent(&tx, name)?;
+        if name != assigned_to {
+            record_event(&tx, id, "assigned_to", &assigned_to, name)?;
+            tx.execute(
+                "UPDATE issues SET assigned_to = ?1 WHERE id = ?2",
+                params![name, id],
+            )?;
+        }
+    }
+    tx.commit()?;
+    Ok(ClaimOutcome::Claimed {
+        prior_assigned_to: assigned_to,
+    })
+}
+
+pub fn update_issue_parent(
+    conn: &Connection,
+    id: i64,
+    parent_id: Option<i64>,
+) -> Result<(), ItrError> {
+    if !issue_exists(conn, id)? {
+        return Err(ItrError::NotFound(id));
+    }
+    // Guard at the db layer so every caller (CLI update, UI PATCH, future
+    // writers) gets the same parent-cycle protection (#159). Parent cycles
+    // are one of the few designated hard errors: any parent-chain traversal
+    // would loop forever on a self/descendant parent.
+    if let Some(pid) = parent_id {
+        if !issue_exists(conn, pid)? {
+            return Err(ItrError::NotFound(pid));
+        }
+        if is_self_or_descendant(conn, id, pid)? {
+            return Err(ItrError::CycleDetected(format!(
+                "parent_id: {} cannot be parent of {} (creates cycle)",
+                pid, id
+            )));
+        }
+    }
+    conn.execute(
+        "UPDATE issues SET parent_id = ?1 WHERE id = ?2",
+        params![parent_id, id],
+    )?;
+    Ok(())
+}
+
+/// Check if `candidate` is `id` itself or any descendant of `id` via `parent_id` edges.
+/// Used to prevent parent-cycle creation when setting `id`'s parent to `candidate`.
+/// Walks the same recursive CTE as [`get_descendant_ids`].
+pub fn is_self_or_descendant(conn: &Connection, id: i64, candidate: i64) -> Result<bool, ItrError> {
+    if id == candidate {
+        return Ok(true);
+    }
+    Ok(get_descendant_ids(conn, id)?
+        .iter()
+        .any(|(desc, _)| *desc == candidate))
+}
+
+/// Deepest `parent_id` nesting the recursive subtree queries will follow.
+/// `update_issue_parent` rejects cycles, but a database edited by hand may
+/// still contain one; the cap (plus the path check) keeps the CTE finite on
+/// a corrupted hierarchy instead of recursing forever.
+const MAX_TREE_DEPTH: i64 = 64;
+
+/// Recursive CTE producing `(id, depth, path)` for every descendant of the
+/// issue bound to the `root` placeholder (e.g. `"?1"`). `path` is the
+/// zero-padded ancestor chain, so ordering by it yields a depth-first walk
+/// with siblings in ID order.
+fn subtree_cte(root: &str) -> String {
+    format!(
+        "WITH RECURSIVE subtree(id, depth, path) AS (
+            SELECT id, 1, printf('/%010d', id) FROM issues WHERE parent_id = {root}
+            UNION ALL
+            SELECT c.id, s.depth + 1, s.path || printf('/%010d', c.id)
+            FROM issues c JOIN subtree s ON c.parent_id = s.id
+            WHERE s.depth < {MAX_TREE_DEPTH} AND instr(s.path, printf('/%010d', c.id)) = 0
+        )"
+    )
+}
+
+/// Every descendant of `root_id` — children, grandchildren, and so on through
+/// nested epics — as `(issue_id, depth)` pairs where depth 1 is a direct
+/// child. Rows come back depth-first (each child directly followed by its own
+/// subtree), siblings in ID order.
+pub fn get_descendant_ids(conn: &Connection, root_id: i64) -> Result<Vec<(i64, i64)>, ItrError> {
+    let sql = format!(
+        "{} SELECT id, depth FROM subtree ORDER BY path",
+        subtree_cte("?1")
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows: Vec<(i64, i64)> = stmt
+        .query_map(params![root_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Roll up status counts over the whole subtree below `root_id` (the root
+/// itself is not counted). Computed in one aggregate query over the same
+/// recursive CTE as [`get_descendant_ids`].
+pub fn subtree_progress(conn: &Connection, root_id: i64) -> Result<SubtreeProgress, ItrError> {
+    let sql = format!(
+        "{}
+         SELECT COUNT(*),
+                COALESCE(SUM(i.status = 'open'), 0),
+                COALESCE(SUM(i.status = 'in-progress'), 0),
+                COALESCE(SUM(i.status = 'done'), 0),
+                COALESCE(SUM(i.status = 'wontfix'), 0),
+                COALESCE(SUM(i.status = 'review'), 0)
+         FROM subtree s JOIN issues i ON i.id = s.id",
+        subtree_cte("?1")
+    );
+    let (total, open, in_progress, done, wontfix, review) =
+        conn.query_row(&sql, params![root_id], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        })?;
+    Ok(SubtreeProgress {
+        review,
+        ..SubtreeProgress::from_counts(total, open, in_progress, done, wontfix)
+    })
+}
+
+// --- Tags ---
+
+/// Per-tag issue counts from `issue_tags`, ordered by tag, with any
+/// `tags` metadata attached. `open` counts open, in-progress, and review issues;
+/// `closed` counts done and wontfix. Described tags no issue carries are
+/// listed with zero counts.
+pub fn tag_counts(conn: &Connection) -> Result<Vec<TagCount>, ItrError> {
+    let mut stmt = conn.prepare(
+        "WITH names(name) AS (SELECT tag FROM issue_tags UNION SELECT name FROM tags)
+         SELECT n.name,
+                COALESCE(SUM(i.status IN ('open', 'in-progress', 'review')), 0),
+                COALESCE(SUM(i.status IN ('done', 'wontfix')), 0),
+                COUNT(i.id),
+                COALESCE(m.description, ''),
+                COALESCE(m.color, '')
+         FROM names n
+         LEFT JOIN issue_tags t ON t.tag = n.name
+         LEFT JOIN issues i ON i.id = t.issue_id
+         LEFT JOIN tags m ON m.name = n.name
+         GROUP BY n.name
+         ORDER BY n.name",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(TagCount {
+                tag: row.get(0)?,
+                open: row.get(1)?,
+                closed: row.get(2)?,
+                total: row.get(3)?,
+                description: row.get(4)?,
+                color: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// IDs of every issue (any status) carrying `tag`, ascending.
+pub fn issue_ids_with_tag(conn: &Connection, tag: &str) -> Result<Vec<i64>, ItrError> {
+    let mut stmt =
+        conn.prepare("SELECT issue_id FROM issue_tags WHERE tag = ?1 ORDER BY issue_id")?;
+    let ids = stmt
+        .query_map(params![tag], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+/// Upsert a tag's metadata. `None` leaves that column unchanged; an empty
+/// string clears it. A row left with neither value is deleted.
+pub fn set_tag_meta(
+    conn: &Connection,
+    name: &str,
+    description: Option<&str>,
+    color: Option<&str>,
+) -> Result<(String, String), ItrError> {
+    conn.execute(
+        "INSERT INTO tags (name) VALUES (?1) ON CONFLICT(name) DO NOTHING",
+        params![name],
+    )?;
+    if let Some(d) = description {
+        conn.execute(
+            "UPDATE tags SET description = ?2 WHERE name = ?1",
+            params![name, d],
+        )?;
+    }
+    if let Some(c) = color {
+        conn.execute(
+            "UPDATE tags SET color = ?2 WHERE name = ?1",
+            params![name, c],
+        )?;
+    }
+    let meta: (String, String) = conn.query_row(
+        "SELECT description, color FROM tags WHERE name = ?1",
+        params![name],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    if meta.0.is_empty() && meta.1.is_empty() {
+        delete_tag_meta(conn, name)?;
+    }
+    Ok(meta)
+}
+
+/// Move `old`'s metadata to `new` on rename. Metadata already on `new` wins.
+pub fn rename_tag_meta(conn: &Connection, old: &str, new: &str) -> Result<(), ItrError> {
+    conn.execute(
+        "INSERT OR IGNORE INTO tags (name, description, color)
+         SELECT ?2, description, color FROM tags WHERE name = ?1",
+        params![old, new],
+    )?;
+    delete_tag_meta(conn, old)
+}
+
+pub fn delete_tag_meta(conn: &Connection, name: &str) -> Result<(), ItrError> {
+    conn.execute("DELETE FROM tags WHERE name = ?1", params![name])?;
+    Ok(())
+}
+
+// --- Snapshots ---
+
+/// Store `data` under `name`, replacing any snapshot of that name.
+pub fn put_snapshot(conn: &Connection, name: &str, data: &str) -> Result<(), ItrError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO snapshots (name, data) VALUES (?1, ?2)",
+        params![name, data],
+    )?;
+    Ok(())
+}
+
+/// `(data, created_at)` of a saved snapshot.
+pub fn get_snapshot(conn: &Connection, name: &str) -> Result<Option<(String, String)>, ItrError> {
+    Ok(conn
+        .query_row(
+            "SELECT data, created_at FROM snapshots WHERE name = ?1",
+            params![name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?)
+}
+
+/// `(name, created_at)` of every snapshot, oldest first.
+pub fn list_snapshots(conn: &Connection) -> Result<Vec<(String, String)>, ItrError> {
+    let mut stmt =
+        conn.prepare("SELECT name, created_at FROM snapshots ORDER BY created_at, name")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Returns whether a snapshot was deleted.
+pub fn delete_snapshot(conn: &Connection, name: &str) -> Result<bool, ItrError> {
+    Ok(conn.execute("DELETE FROM snapshots WHERE name = ?1", params![name])? > 0)
+}
+
+// --- Sync cursors ---
+
+/// Highest event and issue IDs already exchanged with one remote, in each
+/// direction. All zero before the first sync.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncCursors {
+    pub pushed_event: i64,
+    pub pushed_issue: i64,
+    pub pulled_event: i64,
+    pub pulled_issue: i64,
+}
+
+pub fn get_sync_cursors(conn: &Connection, remote: &str) -> Result<SyncCursors, ItrError> {
+    Ok(conn
+        .query_row(
+            "SELECT pushed_event, pushed_issue, pulled_event, pulled_issue
+             FROM sync_state WHERE remote = ?1",
+            params![remote],
+            |row| {
+                Ok(SyncCursors {
+                    pushed_event: row.get(0)?,
+                    pushed_issue: row.get(1)?,
+                    pulled_event: row.get(2)?,
+                    pulled_issue: row.get(3)?,
+                })
+            },
+        )
+        .optional()?
+        .unwrap_or_default())
+}
+
+pub fn set_sync_cursors(
+    conn: &Connection,
+    remote: &str,
+    cursors: &SyncCursors,
+) -> Result<(), ItrError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_state (remote, pushed_event, pushed_issue, pulled_event, pulled_issue)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            remote,
+            cursors.pushed_event,
+            cursors.pushed_issue,
+            cursors.pulled_event,
+            cursors.pulled_issue
+        ],
+    )?;
+    Ok(())
+}
+
+/// Highest issue ID ever assigned (0 when there are none).
+pub fn last_issue_id(conn: &Connection) -> Result<i64, ItrError> {
+    Ok(
+        conn.query_row("SELECT COALESCE(MAX(id), 0) FROM issues", [], |row| {
+            row.get(0)
+        })?,
+    )
+}
+
+/// Tag name → color for every tag with a color set.
+pub fn tag_colors(conn: &Connection) -> Result<Vec<(String, String)>, ItrError> {
+    let mut stmt = conn.prepare("SELECT name, color FROM tags WHERE color != ''")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+// --- Agents ---
+
+const AGENT_SELECT: &str =
+    "SELECT a.name, a.skills, a.description, a.registered_at, a.last_seen_at,
+        (SELECT json_group_array(id) FROM (SELECT id FROM issues
+            WHERE assigned_to = a.name AND status IN ('open', 'in-progress', 'review') ORDER BY id)),
+        (SELECT COUNT(*) FROM issues
+            WHERE assigned_to = a.name AND status IN ('done', 'wontfix'))
+     FROM agents a";
+
+fn row_to_agent(row: &rusqlite::Row) -> rusqlite::Result<Agent> {
+    let skills: String = row.get(1)?;
+    let assigned: String = row.get(5)?;
+    Ok(Agent {
+        name: row.get(0)?,
+        skills: serde_json::from_str(&skills).unwrap_or_default(),
+        description: row.get(2)?,
+        assigned: serde_json::from_str(&assigned).unwrap_or_default(),
+        closed: row.get(6)?,
+        registered_at: row.get(3)?,
+        last_seen_at: row.get(4)?,
+    })
+}
+
+/// Every registered agent with its workload, ordered by name.
+pub fn agents(conn: &Connection) -> Result<Vec<Agent>, ItrError> {
+    let mut stmt = conn.prepare(&format!("{} ORDER BY a.name", AGENT_SELECT))?;
+    let rows = stmt
+        .query_map([], row_to_agent)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// One registered agent; `None` when `name` never registered.
+pub fn get_agent(conn: &Connection, name: &str) -> Result<Option<Agent>, ItrError> {
+    Ok(conn
+        .query_row(
+            &format!("{} WHERE a.name = ?1", AGENT_SELECT),
+            params![name],
+            row_to_agent,
+        )
+        .optional()?)
+}
+
+/// Register `name`, or refresh an existing registration: `skills` and
+/// `description` replace the stored values when given, and `last_seen_at`
+/// moves to now either way. Returns whether the agent is new.
+pub fn register_agent(
+    conn: &Connection,
+    name: &str,
+    skills: Option<&[String]>,
+    description: Option<&str>,
+) -> Result<bool, ItrError> {
+    let existed = get_agent(conn, name)?.is_some();
+    let skills_json = skills.map(serde_json::to_string).transpose()?;
+    conn.execute(
+        "INSERT INTO agents (name, skills, description) VALUES (?1, COALESCE(?2, '[]'), COALESCE(?3, ''))
+         ON CONFLICT(name) DO UPDATE SET
+             skills = COALESCE(?2, skills),
+             description = COALESCE(?3, description),
+             last_seen_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+        params![name, skills_json, description],
+    )?;
+    Ok(!existed)
+}
+
+/// Mark a registered agent as seen now. Unregistered names (including the
+/// empty one) are left alone, so every write can call this.
+pub fn touch_agent(conn: &Connection, name: &str) -> Result<(), ItrError> {
+    if !name.is_empty() {
+        conn.execute(
+            "UPDATE agents SET last_seen_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE name = ?1",
+            params![name],
+        )?;
+    }
+    Ok(())
+}
+
+// --- Sessions ---
+
+const SESSION_SELECT: &str =
+    "SELECT id, agent, started_at, ended_at, summary, issues FROM sessions";
+
+fn row_to_session(row: &rusqlite::Row) -> rusqlite::Result<Session> {
+    let issues: String = row.get(5)?;
+    Ok(Session {
+        id: row.get(0)?,
+        agent: row.get(1)?,
+        started_at: row.get(2)?,
+        ended_at: row.get(3)?,
+        summary: row.get(4)?,
+        issues: serde_json::from_str(&issues).unwrap_or_default(),
+    })
+}
+
+/// `agent`'s session that has not ended yet, if any.
+pub fn open_session(conn: &Connection, agent: &str) -> Result<Option<Session>, ItrError> {
+    Ok(conn
+        .query_row(
+            &format!(
+                "{} WHERE agent = ?1 AND ended_at IS NULL ORDER BY id DESC LIMIT 1",
+                SESSION_SELECT
+            ),
+            params![agent],
+            row_to_session,
+        )
+        .optional()?)
+}
+
+/// Open a new session for `agent`, starting now.
+pub fn start_session(conn: &Connection, agent: &str) -> Result<Session, ItrError> {
+    conn.execute("INSERT INTO sessions (agent) VALUES (?1)", params![agent])?;
+    touch_agent(conn, agent)?;
+    Ok(conn.query_row(
+        &format!("{} WHERE id = ?1", SESSION_SELECT),
+        params![conn.last_insert_rowid()],
+        row_to_session,
+    )?)
+}
+
+/// Issues `agent` touched since `since`: any audited event it was the acting
+/// agent for, or any note it wrote. Ascending by ID.
+pub fn issues_touched_by(
+    conn: &Connection,
+    agent: &str,
+    since: &str,
+) -> Result<Vec<i64>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT issue_id FROM events WHERE agent = ?1 AND created_at >= ?2
+         UNION
+         SELECT issue_id FROM notes WHERE agent = ?1 AND created_at >= ?2
+         ORDER BY 1",
+    )?;
+    let ids = stmt
+        .query_map(params![agent, since], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+/// Close session `id` with its summary and touched issues.
+pub fn end_session(
+    conn: &Connection,
+    id: i64,
+    summary: &str,
+    issues: &[i64],
+) -> Result<Session, ItrError> {
+    conn.execute(
+        "UPDATE sessions SET ended_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), summary = ?2, issues = ?3
+         WHERE id = ?1",
+        params![id, summary, serde_json::to_string(issues)?],
+    )?;
+    Ok(conn.query_row(
+        &format!("{} WHERE id = ?1", SESSION_SELECT),
+        params![id],
+        row_to_session,
+    )?)
+}
+
+/// Sessions newest first, optionally for one agent.
+pub fn sessions(
+    conn: &Connection,
+    agent: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<Session>, ItrError> {
+    let limit = limit.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE ?1 IS NULL OR agent = ?1 ORDER BY id DESC LIMIT ?2",
+        SESSION_SELECT
+    ))?;
+    let rows = stmt
+        .query_map(params![agent, limit], row_to_session)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+// --- Commits ---
+
+/// Link commit `hash` to issue `issue_id`. Linking it again is a no-op,
+/// except that a `close` link replaces a `mention`. Returns whether anything
+/// changed.
+pub fn link_commit(
+    conn: &Connection,
+    issue_id: i64,
+    hash: &str,
+    subject: &str,
+    relation: &str,
+) -> Result<bool, ItrError> {
+    let changed = conn.execute(
+        "INSERT INTO issue_commits (issue_id, hash, subject, relation) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(issue_id, hash) DO UPDATE SET relation = excluded.relation
+         WHERE excluded.relation = 'close' AND relation <> 'close'",
+        params![issue_id, hash, subject, relation],
+    )?;
+    Ok(changed > 0)
+}
+
+/// The commits linked to `issue_id`, oldest link first.
+pub fn issue_commits(conn: &Connection, issue_id: i64) -> Result<Vec<IssueCommit>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT issue_id, hash, subject, relation, linked_at FROM issue_commits
+         WHERE issue_id = ?1 ORDER BY linked_at, rowid",
+    )?;
+    let rows = stmt
+        .query_map(params![issue_id], |row| {
+            Ok(IssueCommit {
+                issue_id: row.get(0)?,
+                hash: row.get(1)?,
+                subject: row.get(2)?,
+                relation: row.get(3)?,
+                linked_at: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// How many commits are linked to `issue_id`.
+pub fn commit_count(conn: &Connection, issue_id: i64) -> Result<usize, ItrError> {
+    let n: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM issue_commits WHERE issue_id = ?1",
+        params![issue_id],
+        |row| row.get(0),
+    )?;
+    Ok(usize::try_from(n).unwrap_or(0))
+}
+
+// --- Estimates ---
+
+/// The issue's estimate in points, or `None` when it has none.
+pub fn issue_estimate(conn: &Connection, issue_id: i64) -> Result<Option<i64>, ItrError> {
+    conn.query_row(
+        "SELECT estimate FROM issues WHERE id = ?1",
+        params![issue_id],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or(ItrError::NotFound(issue_id))
+}
+
+/// Set or clear (`None`) the issue's estimate.
+pub fn set_estimate(conn: &Connection, issue_id: i64, points: Option<i64>) -> Result<(), ItrError> {
+    if conn.execute(
+        "UPDATE issues SET estimate = ?1 WHERE id = ?2",
+        params![points, issue_id],
+    )? == 0
+    {
+        return Err(ItrError::NotFound(issue_id));
+    }
+    Ok(())
+}
+
+// --- Reproduction ---
+
+/// The issue's reproduction, or `None` when it has none. A stored value that
+/// no longer parses reads as none.
+pub fn issue_repro(conn: &Connection, issue_id: i64) -> Result<Option<Repro>, ItrError> {
+    let raw: String = conn
+        .query_row(
+            "SELECT repro FROM issues WHERE id = ?1",
+            params![issue_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or(ItrError::NotFound(issue_id))?;
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    Ok(serde_json::from_str(&raw).ok())
+}
+
+/// Set or clear (`None`, or an empty reproduction) the issue's reproduction.
+pub fn set_repro(conn: &Connection, issue_id: i64, repro: Option<&Repro>) -> Result<(), ItrError> {
+    let raw = match repro {
+        Some(r) if !r.is_empty() => serde_json::to_string(r)?,
+        _ => String::new(),
+    };
+    if conn.execute(
+        "UPDATE issues SET repro = ?1 WHERE id = ?2",
+        params![raw, issue_id],
+    )? == 0
+    {
+        return Err(ItrError::NotFound(issue_id));
+    }
+    Ok(())
+}
+
+// --- References ---
+
+/// Rebuild the `#<id>` references an issue makes from its context and notes
+/// (see [`crate::util::parse_issue_mentions`]). Called by every write to
+/// either; mentions of the issue itself are ignored.
+pub fn refresh_references(conn: &Connection, issue_id: i64) -> Result<(), ItrError> {
+    let context: Option<String> = conn
+        .query_row(
+            "SELECT context FROM issues WHERE id = ?1",
+            params![issue_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    conn.execute(
+        "DELETE FROM issue_references WHERE source_id = ?1",
+        params![issue_id],
+    )?;
+    let Some(context) = context else {
+        return Ok(());
+    };
+    let mut targets: Vec<i64> = crate::util::parse_issue_mentions(&context);
+    let mut stmt = conn.prepare_cached("SELECT content FROM notes WHERE issue_id = ?1")?;
+    for content in stmt.query_map(params![issue_id], |row| row.get::<_, String>(0))? {
+        targets.extend(crate::util::parse_issue_mentions(&content?));
+    }
+    let mut insert = conn.prepare_cached(
+        "INSERT OR IGNORE INTO issue_references (source_id, target_id) VALUES (?1, ?2)",
+    )?;
+    for target in targets.into_iter().filter(|&t| t != issue_id) {
+        insert.execute(params![issue_id, target])?;
+    }
+    Ok(())
+}
+
+/// [`refresh_references`] for every issue, for bulk writers (import, sync,
+/// merge) that insert rows directly.
+pub fn refresh_all_references(conn: &Connection) -> Result<(), ItrError> {
+    let ids: Vec<i64> = conn
+        .prepare("SELECT id FROM issues")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    for id in ids {
+        refresh_references(conn, id)?;
+    }
+    Ok(())
+}
+
+/// Issues whose context or notes mention `#<issue_id>`, ascending.
+pub fn referenced_by(conn: &Connection, issue_id: i64) -> Result<Vec<i64>, ItrError> {
+    let ids = conn
+        .prepare_cached(
+            "SELECT r.source_id FROM issue_references r JOIN issues i ON i.id = r.source_id
+             WHERE r.target_id = ?1 ORDER BY r.source_id",
+        )?
+        .query_map(params![issue_id], |row| row.get(0))?
+        .collect::<Result<Vec<i64>, _>>()?;
+    Ok(ids)
+}
+
+/// Every estimated issue's points, keyed by issue ID.
+pub fn issue_estimates(conn: &Connection) -> Result<HashMap<i64, i64>, ItrError> {
+    let mut stmt = conn.prepare("SELECT id, estimate FROM issues WHERE estimate IS NOT NULL")?;
+    let estimates = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(estimates)
+}
+
+// --- Projects ---
+
+/// Every project with its issue counts, ordered by name. `current` marks the
+/// one stored under [`CURRENT_PROJECT_KEY`].
+pub fn project_counts(conn: &Connection) -> Result<Vec<ProjectCount>, ItrError> {
+    let current = config_get(conn, CURRENT_PROJECT_KEY)?.unwrap_or_default();
+    let mut stmt = conn.prepare(
+        "SELECT p.name, p.description,
+                COALESCE(SUM(i.status IN ('open', 'in-progress', 'review')), 0),
+                COALESCE(SUM(i.status IN ('done', 'wontfix')), 0),
+                COUNT(i.id),
+                p.created_at,
+                p.key_prefix
+         FROM projects p
+         LEFT JOIN issues i ON i.project_id = p.id
+         GROUP BY p.id
+         ORDER BY p.name",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            let name: String = row.get(0)?;
+            Ok(ProjectCount {
+                current: name == current,
+                name,
+                description: row.get(1)?,
+                open: row.get(2)?,
+                closed: row.get(3)?,
+                total: row.get(4)?,
+                created_at: row.get(5)?,
+                key_prefix: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+pub fn project_exists(conn: &Connection, name: &str) -> Result<bool, ItrError> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM projects WHERE name = ?1",
+        params![name],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// Resolve an issue reference: a bare `45`, or a project-qualified
+/// `webapp/45`. IDs are global, so the qualifier only asserts where the
+/// issue lives — an unknown project, or an issue filed elsewhere, is an
+/// `InvalidValue` rather than a silent match. A bare ID isn't checked for
+/// existence; callers already report `NOT_FOUND` themselves. Anything that
+/// isn't numeric falls back to [`find_issue_by_name`].
+pub fn resolve_issue_ref(conn: &Connection, token: &str) -> Result<i64, ItrError> {
+    let token = token.trim();
+    let (project, raw_id) = match token.rsplit_once('/') {
+        Some((p, id)) => (Some(p.trim()), id.trim()),
+        None => (None, token),
+    };
+    let Ok(id) = raw_id.parse::<i64>() else {
+        return find_issue_by_name(conn, token)?.ok_or_else(|| ItrError::InvalidValue {
+            field: "id".to_string(),
+            value: token.to_string(),
+            valid: "an issue ID, project/ID, key, slug, or title prefix (e.g. 45, webapp/45, APP-45, fix-login)"
+                .to_string(),
+        });
+    };
+    let Some(project) = project else {
+        return Ok(id);
+    };
+    if !project_exists(conn, project)? {
+        return Err(ItrError::InvalidValue {
+            field: "project".to_string(),
+            value: project.to_string(),
+            valid: "an existing project (see `itr project list`)".to_string(),
+        });
+    }
+    let actual = get_issue(conn, id)?.project;
+    if actual != project {
+        return Err(ItrError::InvalidValue {
+            field: "id".to_string(),
+            value: token.to_string(),
+            valid: if actual.is_empty() {
+                format!("{} (issue {} has no project)", id, id)
+            } else {
+                format!("{}/{}", actual, id)
+            },
+        });
+    }
+    Ok(id)
+}
+
+/// Resolve a non-numeric issue reference by name: an issue key (`APP-42`),
+/// the slug of the title (`fix-login-timeout`), or a case-insensitive title
+/// prefix (`"login tim"`). A key wins outright. Otherwise an exact slug or
+/// title match beats a prefix match, and within either tier open and
+/// in-progress issues beat closed ones. `Ok(None)` when nothing matches;
+/// [`ItrError::Ambiguous`] listing the candidates when more than one issue
+/// survives.
+pub fn find_issue_by_name(conn: &Connection, query: &str) -> Result<Option<i64>, ItrError> {
+    let query = query.trim();
+    if let Some(id) = issue_id_by_key(conn, query)? {
+        return Ok(Some(id));
+    }
+    let query_slug = crate::util::slugify(query, usize::MAX);
+    if query_slug.is_empty() {
+        return Ok(None);
+    }
+    let query_lower = query.to_lowercase();
+    let mut stmt = conn.prepare_cached("SELECT id, title, status FROM issues ORDER BY id")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+    let mut exact = Vec::new();
+    let mut prefix = Vec::new();
+    for row in rows {
+        let (id, title, status) = row?;
+        let slug = crate::util::slugify(&title, usize::MAX);
+        if slug == query_slug || title.to_lowercase() == query_lower {
+            exact.push((id, title, status));
+        } else if slug.starts_with(&query_slug) || title.to_lowercase().starts_with(&query_lower) {
+            prefix.push((id, title, status));
+        }
+    }
+    let mut matches = if exact.is_empty() { prefix } else { exact };
+    if matches.iter().any(|(_, _, s)| !is_terminal(s)) {
+        matches.retain(|(_, _, s)| !is_terminal(s));
+    }
+    match matches.len() {
+        0 => Ok(None),
+        1 => Ok(Some(matches[0].0)),
+        _ => Err(ItrError::Ambiguous {
+            query: query.to_string(),
+            candidates: matches
+                .into_iter()
+                .map(|(id, title, _)| (id, title))
+                .collect(),
+        }),
+    }
+}
+
+/// Rewrite each comma-separated piece of raw ID tokens that is a
+/// `project/ID` or an issue key to its bare ID, so
+/// [`crate::util::parse_id_tokens`] only ever sees integers and ranges.
+/// Anything else passes through untouched.
+pub fn resolve_id_tokens(conn: &Connection, tokens: &[String]) -> Result<Vec<String>, ItrError> {
+    tokens
+        .iter()
+        .map(|token| {
+            let parts = token
+                .split(',')
+                .map(|part| {
+                    let qualified = part
+                        .rsplit_once('/')
+                        .is_some_and(|(_, id)| id.trim().parse::<i64>().is_ok());
+                    if qualified {
+                        return resolve_issue_ref(conn, part).map(|id| id.to_string());
+                    }
+                    Ok(issue_id_by_key(conn, part)?
+                        .map_or_else(|| part.to_string(), |id| id.to_string()))
+                })
+                .collect::<Result<Vec<_>, ItrError>>()?;
+            Ok(parts.join(","))
+        })
+        .collect()
+}
+
+/// Qualified `project/ID` forms for those of `ids` that live in a project
+/// other than `project`. Issues with no project stay bare and are omitted.
+pub fn cross_project_ids(
+    conn: &Connection,
+    project: &str,
+    ids: &[i64],
+) -> Result<BTreeMap<i64, String>, ItrError> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT p.name FROM issues i JOIN projects p ON p.id = i.project_id WHERE i.id = ?1",
+    )?;
+    let mut out = BTreeMap::new();
+    for &id in ids {
+        let name: Option<String> = stmt.query_row(params![id], |row| row.get(0)).optional()?;
+        if let Some(name) = name.filter(|n| n != project) {
+            out.insert(id, crate::util::qualified_id(&name, id));
+        }
+    }
+    Ok(out)
+}
+
+/// Create a project unless one with that name exists. Returns whether it
+/// was created; an existing project's description is left alone.
+pub fn add_project(conn: &Connection, name: &str, description: &str) -> Result<bool, ItrError> {
+    let inserted = conn.execute(
+        "INSERT INTO projects (name, description) VALUES (?1, ?2) ON CONFLICT(name) DO NOTHING",
+        params![name, description],
+    )?;
+    Ok(inserted > 0)
+}
+
+/// Set the key prefix of project `name` and hand a key to each of its issues
+/// that has none yet, in ID order. Keys already handed out keep their old
+/// prefix. Returns how many issues got a key.
+pub fn set_project_key_prefix(
+    conn: &Connection,
+    name: &str,
+    prefix: &str,
+) -> Result<usize, ItrError> {
+    conn.execute(
+        "UPDATE projects SET key_prefix = ?1 WHERE name = ?2",
+        params![prefix, name],
+    )?;
+    if prefix.is_empty() {
+        return Ok(0);
+    }
+    let unkeyed: Vec<i64> = conn
+        .prepare(
+            "SELECT i.id FROM issues i JOIN projects p ON p.id = i.project_id
+             WHERE p.name = ?1 AND i.id NOT IN (SELECT issue_id FROM issue_keys)
+             ORDER BY i.id",
+        )?
+        .query_map(params![name], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    let mut next = conn.query_row(
+        "SELECT COALESCE(MAX(CAST(substr(key, length(?1) + 2) AS INTEGER)), 0) + 1
+         FROM issue_keys WHERE key LIKE ?1 || '-%'",
+        params![prefix],
+        |row| row.get::<_, i64>(0),
+    )?;
+    for &id in &unkeyed {
+        conn.execute(
+            "INSERT INTO issue_keys (issue_id, key) VALUES (?1, ?2)",
+            params![id, format!("{}-{}", prefix, next)],
+        )?;
+        next += 1;
+    }
+    Ok(unkeyed.len())
+}
+
+/// The project other than `name` whose key prefix is `prefix`, if any.
+pub fn project_with_key_prefix(
+    conn: &Connection,
+    prefix: &str,
+    name: &str,
+) -> Result<Option<String>, ItrError> {
+    Ok(conn
+        .query_row(
+            "SELECT name FROM projects WHERE key_prefix = ?1 AND name != ?2",
+            params![prefix, name],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// The key prefix of project `name`; empty when it has none.
+pub fn project_key_prefix(conn: &Connection, name: &str) -> Result<String, ItrError> {
+    Ok(conn
+        .query_row(
+            "SELECT key_prefix FROM projects WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or_default())
+}
+
+/// The ID of project `name`, creating it first if needed; `None` for an
+/// empty name. Used by writers that copy issues between databases.
+pub fn ensure_project(conn: &Connection, name: &str) -> Result<Option<i64>, ItrError> {
+    if name.is_empty() {
+        return Ok(None);
+    }
+    add_project(conn, name, "")?;
+    let id = conn.query_row(
+        "SELECT id FROM projects WHERE name = ?1",
+        params![name],
+        |row| row.get(0),
+    )?;
+    Ok(Some(id))
+}
+
+/// Move an issue into `project` (`None` detaches it), creating the project
+/// if needed and recording a `project` event. Returns the previous project
+/// name, or `None` when nothing changed.
+pub fn set_issue_project(
+    conn: &Connection,
+    id: i64,
+    project: Option<&str>,
+) -> Result<Option<String>, ItrError> {
+    let old = get_issue(conn, id)?.project;
+    let new = project.unwrap_or("");
+    if old == new {
+        return Ok(None);
+    }
+    if let Some(name) = project {
+        add_project(conn, name, "")?;
+    }
+    conn.execute(
+        "UPDATE issues SET project_id = (SELECT id FROM projects WHERE name = ?1) WHERE id = ?2",
+        params![project, id],
+    )?;
+    record_event(conn, id, "project", &old, new)?;
+    Ok(Some(old))
+}
+
+/// File a just-inserted issue under an existing `project` without recording
+/// an event, as part of its creation. An unknown name leaves it unfiled.
+pub fn file_new_issue(conn: &Connection, id: i64, project: &str) -> Result<(), ItrError> {
+    conn.execute(
+        "UPDATE issues SET project_id = (SELECT id FROM projects WHERE name = ?1) WHERE id = ?2",
+        params![project, id],
+    )?;
+    Ok(())
+}
+
+// --- Components ---
+
+/// Every registered component with its routing defaults and issue counts,
+/// ordered by name.
+pub fn components(conn: &Connection) -> Result<Vec<Component>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT c.name, c.default_assignee, c.default_tags,
+                COALESCE(SUM(i.status IN ('open', 'in-progress', 'review')), 0),
+                COALESCE(SUM(i.status IN ('done', 'wontfix')), 0),
+                COUNT(i.id),
+                c.created_at
+         FROM components c
+         LEFT JOIN issues i ON i.component_id = c.id
+         GROUP BY c.id
+         ORDER BY c.name",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            let tags: String = row.get(2)?;
+            Ok(Component {
+                name: row.get(0)?,
+                default_assignee: row.get(1)?,
+                default_tags: serde_json::from_str(&tags).unwrap_or_default(),
+                open: row.get(3)?,
+                closed: row.get(4)?,
+                total: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// The registered component `name`, if any.
+pub fn get_component(conn: &Connection, name: &str) -> Result<Option<Component>, ItrError> {
+    Ok(components(conn)?.into_iter().find(|c| c.name == name))
+}
+
+/// Register component `name`, or update the defaults given for an existing
+/// one (`None` leaves a default alone). Returns whether it was created.
+pub fn upsert_component(
+    conn: &Connection,
+    name: &str,
+    default_assignee: Option<&str>,
+    default_tags: Option<&[String]>,
+) -> Result<bool, ItrError> {
+    let created = conn.execute(
+        "INSERT INTO components (name) VALUES (?1) ON CONFLICT(name) DO NOTHING",
+        params![name],
+    )? > 0;
+    if let Some(assignee) = default_assignee {
+        conn.execute(
+            "UPDATE components SET default_assignee = ?1 WHERE name = ?2",
+            params![assignee, name],
+        )?;
+    }
+    if let Some(tags) = default_tags {
+        conn.execute(
+            "UPDATE components SET default_tags = ?1 WHERE name = ?2",
+            params![serde_json::to_string(tags)?, name],
+        )?;
+    }
+    Ok(created)
+}
+
+/// Unregister component `name`. Its issues keep every other field and are
+/// left without a component. Returns how many issues were in it, or `None`
+/// when no such component exists.
+pub fn remove_component(conn: &Connection, name: &str) -> Result<Option<i64>, ItrError> {
+    let Some(component) = get_component(conn, name)? else {
+        return Ok(None);
+    };
+    for id in component_issue_ids(conn, name)? {
+        record_event(conn, id, "component", name, "")?;
+    }
+    conn.execute("DELETE FROM components WHERE name = ?1", params![name])?;
+    Ok(Some(component.total))
+}
+
+fn component_issue_ids(conn: &Connection, name: &str) -> Result<Vec<i64>, ItrError> {
+    let ids = conn
+        .prepare(
+            "SELECT i.id FROM issues i JOIN components c ON c.id = i.component_id
+             WHERE c.name = ?1 ORDER BY i.id",
+        )?
+        .query_map(params![name], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(ids)
+}
+
+/// The ID of component `name`, registering it first if needed; `None` for
+/// an empty name. Used by writers that copy issues between databases.
+pub fn ensure_component(conn: &Connection, name: &str) -> Result<Option<i64>, ItrError> {
+    if name.is_empty() {
+        return Ok(None);
+    }
+    upsert_component(conn, name, None, None)?;
+    let id = conn.query_row(
+        "SELECT id FROM components WHERE name = ?1",
+        params![name],
+        |row| row.get(0),
+    )?;
+    Ok(Some(id))
+}
+
+/// Put an issue in component `name` (empty takes it out) without recording
+/// an event; for new issues. The component must be registered.
+pub fn set_component_id(conn: &Connection, id: i64, name: &str) -> Result<(), ItrError> {
+    conn.execute(
+        "UPDATE issues SET component_id = (SELECT id FROM components WHERE name = ?1) WHERE id = ?2",
+        params![name, id],
+    )?;
+    Ok(())
+}
+
+/// Move an issue into component `name` (empty takes it out), recording a
+/// `component` event. Returns the previous component name, or `None` when
+/// nothing changed. The component must be registered.
+pub fn set_issue_component(
+    conn: &Connection,
+    id: i64,
+    name: &str,
+) -> Result<Option<String>, ItrError> {
+    let old = get_issue(conn, id)?.component;
+    if old == name {
+        return Ok(None);
+    }
+    set_component_id(conn, id, name)?;
+    record_event(conn, id, "component", &old, name)?;
+    Ok(Some(old))
+}
+
+// --- Dependencies ---
+
+pub fn add_dependency(
+    conn: &Connection,
+    blocker_id: i64,
+    blocked_id: i64,
+) -> Result<bool, ItrError> {
+    if !issue_exists(conn, blocker_id)? {
+        return Err(ItrError::NotFound(blocker_id));
+    }
+    if !issue_exists(conn, blocked_id)? {
+        return Err(ItrError::NotFound(blocked_id));
+    }
+
+    // Check for existing
+    let exists: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM dependencies WHERE blocker_id = ?1 AND blocked_id = ?2",
+        params![blocker_id, blocked_id],
+        |row| row.get(0),
+    )?;
+    if exists {
+        return Ok(false); // idempotent
+    }
+
+    // Cycle check: would adding blocker_id->blocked_id create a cycle?
+    // Check if blocked_id can already reach blocker_id via existing "blocks" edges.
+    // If so, adding this edge would create a cycle.
+    if has_path(conn, blocked_id, blocker_id)? {
+        return Err(ItrError::CycleDetected(format!(
+            "{} -> ... -> {}",
+            blocked_id, blocker_id
+        )));
+    }
+
+    conn.execute(
+        "INSERT INTO dependencies (blocker_id, blocked_id) VALUES (?1, ?2)",
+        params![blocker_id, blocked_id],
+    )?;
+    // Dependency edges are mutations like any other: record an audit event
+    // on the blocked issue so `itr log` shows who blocked it and when (#35
+    // lesson — every mutation path records its own event).
+    record_event(
+        conn,
+        blocked_id,
+        "dependency_added",
+        "",
+        &blocker_id.to_string(),
+    )?;
+    Ok(true)
+}
+
+/// Removes the `blocked_id <- blocker_id` edge. Returns whether an edge
+/// actually existed, so callers can distinguish a real removal from a no-op
+/// instead of reporting phantom state changes (#191).
+pub fn remove_dependency(
+    conn: &Connection,
+    blocker_id: i64,
+    blocked_id: i64,
+) -> Result<bool, ItrError> {
+    if !issue_exists(conn, blocker_id)? {
+        return Err(ItrError::NotFound(blocker_id));
+    }
+    if !issue_exists(conn, blocked_id)? {
+        return Err(ItrError::NotFound(blocked_id));
+    }
+    let deleted = conn.execute(
+        "DELETE FROM dependencies WHERE blocker_id = ?1 AND blocked_id = ?2",
+        params![blocker_id, blocked_id],
+    )?;
+    if deleted > 0 {
+        record_event(
+            conn,
+            blocked_id,
+            "dependency_removed",
+            &blocker_id.to_string(),
+            "",
+        )?;
+    }
+    Ok(deleted > 0)
+}
+
+/// Check if there's a path from `from_id` to `to_id` following blocker edges.
+/// i.e., `from_id` is blocked by X, X is blocked by Y, ... eventually reaches `to_id`.
+pub fn has_path(conn: &Connection, from_id: i64, to_id: i64) -> Result<bool, ItrError> {
+    let mut visited = std::collections::HashSet::new();
+    let mut queue = std::collections::VecDeque::new();
+    queue.push_back(from_id);
+
+    while let Some(current) = queue.pop_front() {
+        if current == to_id {
+            return Ok(true);
+        }
+        if !visited.insert(current) {
+            continue;
+        }
+        // Follow: what does `current` block? (current is a blocker_id, find blocked_ids)
+        let mut stmt =
+            conn.prepare_cached("SELECT blocked_id FROM dependencies WHERE blocker_id = ?1")?;
+        let blocked: Vec<i64> = stmt
+            .query_map(params![current], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        for b in blocked {
+            if !visited.contains(&b) {
+                queue.push_back(b);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Open, in-progress, and review issues naming `path` in their files,
+/// ascending.
+pub fn active_issues_with_file(conn: &Connection, path: &str) -> Result<Vec<i64>, ItrError> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT f.issue_id FROM issue_files f JOIN issues i ON i.id = f.issue_id
+         WHERE f.path = ?1 AND i.status IN ('open', 'in-progress', 'review') ORDER BY f.issue_id",
+    )?;
+    let ids = stmt
+        .query_map(params![path], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+pub fn get_blockers(conn: &Connection, issue_id: i64) -> Result<Vec<i64>, ItrError> {
+    let mut stmt =
+        conn.prepare_cached("SELECT blocker_id FROM dependencies WHERE blocked_id = ?1")?;
+    let ids: Vec<i64> = stmt
+        .query_map(params![issue_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+pub fn get_blocking(conn: &Connection, issue_id: i64) -> Result<Vec<i64>, ItrError> {
+    let mut stmt =
+        conn.prepare_cached("SELECT blocked_id FROM dependencies WHERE blocker_id = ?1")?;
+    let ids: Vec<i64> = stmt
+        .query_map(params![issue_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+/// Whether the issue has an unfinished blocker issue or an external blocker.
+pub fn is_blocked(conn: &Connection, issue_id: i64) -> Result<bool, ItrError> {
+    let count: i64 = conn
+        .prepare_cached(
+            "SELECT (SELECT COUNT(*) FROM dependencies d
+                     JOIN issues i ON d.blocker_id = i.id
+                     WHERE d.blocked_id = ?1
+                     AND i.status NOT IN ('done', 'wontfix'))
+                  + (SELECT COUNT(*) FROM external_blockers WHERE issue_id = ?1)",
+        )?
+        .query_row(params![issue_id], |row| row.get(0))?;
+    Ok(count > 0)
+}
+
+pub fn blocks_active_issues(conn: &Connection, issue_id: i64) -> Result<bool, ItrError> {
+    let count: i64 = conn
+        .prepare_cached(
+            "SELECT COUNT(*) FROM dependencies d
+             JOIN issues i ON d.blocked_id = i.id
+             WHERE d.blocker_id = ?1
+             AND i.status NOT IN ('done', 'wontfix')",
+        )?
+        .query_row(params![issue_id], |row| row.get(0))?;
+    Ok(count > 0)
+}
+
+/// Get issues that become unblocked when `closed_id` is resolved.
+pub fn get_newly_unblocked(
+    conn: &Connection,
+    closed_id: i64,
+) -> Result<Vec<(i64, String)>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT i.id, i.title FROM issues i
+         JOIN dependencies d ON d.blocked_id = i.id
+         WHERE d.blocker_id = ?1
+         AND i.status NOT IN ('done', 'wontfix')
+         AND NOT EXISTS (
+             SELECT 1 FROM dependencies d2
+             JOIN issues i2 ON d2.blocker_id = i2.id
+             WHERE d2.blocked_id = i.id
+             AND d2.blocker_id != ?1
+             AND i2.status NOT IN ('done', 'wontfix')
+         )
+         AND NOT EXISTS (SELECT 1 FROM external_blockers x WHERE x.issue_id = i.id)",
+    )?;
+    let results: Vec<(i64, String)> = stmt
+        .query_map(params![closed_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(results)
+}
+
+/// Remove all dependency edges where the given issue is the blocker.
+/// Called on close to auto-clean stale edges so `doctor --fix` isn't needed.
+pub fn remove_blocker_edges(conn: &Connection, blocker_id: i64) -> Result<usize, ItrError> {
+    let count = conn.execute(
+        "DELETE FROM dependencies WHERE blocker_id = ?1",
+        params![blocker_id],
+    )?;
+    Ok(count)
+}
+
+// --- External blockers ---
+
+/// Block `issue_id` on something outside the tracker, recording an
+/// `external_blocker_added` event.
+pub fn add_external_blocker(
+    conn: &Connection,
+    issue_id: i64,
+    description: &str,
+    url: &str,
+) -> Result<ExternalBlocker, ItrError> {
+    if !issue_exists(conn, issue_id)? {
+        return Err(ItrError::NotFound(issue_id));
+    }
+    conn.execute(
+        "INSERT INTO external_blockers (issue_id, description, url) VALUES (?1, ?2, ?3)",
+        params![issue_id, description, url],
+    )?;
+    let id = conn.last_insert_rowid();
+    record_event(conn, issue_id, "external_blocker_added", "", description)?;
+    external_blockers(conn, issue_id)?
+        .into_iter()
+        .find(|b| b.id == id)
+        .ok_or(ItrError::NotFound(issue_id))
+}
+
+/// External blockers on one issue, oldest first.
+pub fn external_blockers(
+    conn: &Connection,
+    issue_id: i64,
+) -> Result<Vec<ExternalBlocker>, ItrError> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT id, issue_id, description, url, created_at FROM external_blockers
+         WHERE issue_id = ?1 ORDER BY id",
+    )?;
+    let rows = stmt
+        .query_map(params![issue_id], |row| {
+            Ok(ExternalBlocker {
+                id: row.get(0)?,
+                issue_id: row.get(1)?,
+                description: row.get(2)?,
+                url: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Delete an external blocker, recording an `external_blocker_resolved`
+/// event on its issue.
+pub fn resolve_external_blocker(
+    conn: &Connection,
+    blocker: &ExternalBlocker,
+) -> Result<(), ItrError> {
+    conn.execute(
+        "DELETE FROM external_blockers WHERE id = ?1",
+        params![blocker.id],
+    )?;
+    record_event(
+        conn,
+        blocker.issue_id,
+        "external_blocker_resolved",
+        &blocker.description,
+        "",
+    )?;
+    Ok(())
+}
+
+// --- Notes ---
+
+pub fn add_note(
+    conn: &Connection,
+    issue_id: i64,
+    content: &str,
+    agent: &str,
+) -> Result<Note, ItrError> {
+    add_note_of_kind(conn, issue_id, content, agent, "")
+}
+
+/// Note kind written by `itr handoff`.
+pub const NOTE_KIND_HANDOFF: &str = "handoff";
+/// Note kind written by `itr session end` on each issue the session touched.
+pub const NOTE_KIND_SESSION: &str = "session";
+/// Note kind written by `itr review reject`.
+pub const NOTE_KIND_REVIEW: &str = "review";
+
+/// [`add_note`] for a typed note such as [`NOTE_KIND_HANDOFF`]; an empty
+/// `kind` is an ordinary note.
+pub fn add_note_of_kind(
+    conn: &Connection,
+    issue_id: i64,
+    content: &str,
+    agent: &str,
+    kind: &str,
+) -> Result<Note, ItrError> {
+    if !issue_exists(conn, issue_id)? {
+        return Err(ItrError::NotFound(issue_id));
+    }
+    conn.execute(
+        "INSERT INTO notes (issue_id, content, agent, kind) VALUES (?1, ?2, ?3, ?4)",
+        params![issue_id, content, agent, kind],
+    )?;
+    let id = conn.last_insert_rowid();
+    refresh_references(conn, issue_id)?;
+    // Mirror note_deleted/note_updated: adding a note is an audited mutation
+    // too, so multi-ID and bulk note operations show up in `itr log`.
+    record_event(conn, issue_id, "note_added", "", content)?;
+    touch_agent(conn, agent)?;
+    conn.query_row(
+        "SELECT id, issue_id, content, agent, created_at, kind FROM notes WHERE id = ?1",
+        params![id],
+        row_to_note,
+    )
+    .map_err(ItrError::Db)
+}
+
+pub fn get_notes(conn: &Connection, issue_id: i64) -> Result<Vec<Note>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, issue_id, content, agent, created_at, kind FROM notes WHERE issue_id = ?1 ORDER BY created_at ASC",
+    )?;
+    let notes: Vec<Note> = stmt
+        .query_map(params![issue_id], row_to_note)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(notes)
+}
+
+pub fn get_note(conn: &Connection, note_id: i64) -> Result<Note, ItrError> {
+    conn.query_row(
+        "SELECT id, issue_id, content, agent, created_at, kind FROM notes WHERE id = ?1",
+        params![note_id],
+        row_to_note,
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => ItrError::NotFound(note_id),
+        other => ItrError::Db(other),
+    })
+}
+
+pub fn delete_note(conn: &Connection, note_id: i64) -> Result<Note, ItrError> {
+    let note = get_note(conn, note_id)?;
+    conn.execute("DELETE FROM notes WHERE id = ?1", params![note_id])?;
+    refresh_references(conn, note.issue_id)?;
+    Ok(note)
+}
+
+pub fn update_note(conn: &Connection, note_id: i64, content: &str) -> Result<Note, ItrError> {
+    let existing = get_note(conn, note_id)?;
+    conn.execute(
+        "UPDATE notes SET content = ?1 WHERE id = ?2",
+        params![content, note_id],
+    )?;
+    refresh_references(conn, existing.issue_id)?;
+    get_note(conn, note_id)
+}
+
+pub fn count_notes(conn: &Connection, issue_id: i64) -> Result<i64, ItrError> {
+    let count: i64 = conn
+        .prepare_cached("SELECT COUNT(*) FROM notes WHERE issue_id = ?1")?
+        .query_row(params![issue_id], |row| row.get(0))?;
+    Ok(count)
+}
+
+// --- Urgency cache ---
+
+pub use crate::urgency::UrgencyInputs;
+
+/// Drop every cached row, forcing the next read of each issue to requery.
+pub fn clear_urgency_cache(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute("DELETE FROM urgency_cache", [])?;
+    Ok(())
+}
+
+/// Clean cached inputs for an issue, or `None` when missing or dirty.
+pub fn cached_urgency_inputs(
+    conn: &Connection,
+    issue_id: i64,
+) -> Result<Option<UrgencyInputs>, ItrError> {
+    match conn.query_row(
+        "SELECT blocking, blocked, note_count FROM urgency_cache
+         WHERE issue_id = ?1 AND dirty_at IS NULL",
+        params![issue_id],
+        |row| {
+            Ok(UrgencyInputs {
+                blocking: row.get(0)?,
+                blocked: row.get(1)?,
+                note_count: row.get(2)?,
+            })
+        },
+    ) {
+        Ok(inputs) => Ok(Some(inputs)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(ItrError::Db(e)),
+    }
+}
+
+/// Store fresh inputs and the score computed from them, clearing `dirty_at`.
+pub fn store_urgency_cache(
+    conn: &Connection,
+    issue_id: i64,
+    inputs: UrgencyInputs,
+    urgency: f64,
+) -> Result<(), ItrError> {
+    conn.execute(
+        "INSERT INTO urgency_cache (issue_id, blocking, blocked, note_count, urgency)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(issue_id) DO UPDATE SET
+            blocking = excluded.blocking, blocked = excluded.blocked,
+            note_count = excluded.note_count, urgency = excluded.urgency,
+            computed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), dirty_at = NULL",
+        params![
+            issue_id,
+            inputs.blocking,
+            inputs.blocked,
+            inputs.note_count,
+            urgency
+        ],
+    )?;
+    Ok(())
+}
+
+/// Dependency edges, external blockers, and note counts for every issue,
+/// loaded in four aggregate queries so list-style commands can score N issues without 3N
+/// round trips.
+#[derive(Debug, Default)]
+pub struct IssueRelations {
+    blockers: HashMap<i64, Vec<i64>>,
+    blocking: HashMap<i64, Vec<i64>>,
+    blocked_active: HashSet<i64>,
+    blocking_active: HashSet<i64>,
+    note_counts: HashMap<i64, i64>,
+}
+
+impl IssueRelations {
+    /// Same as [`get_blockers`] for `issue_id`.
+    pub fn blockers(&self, issue_id: i64) -> Vec<i64> {
+        self.blockers.get(&issue_id).cloned().unwrap_or_default()
+    }
+
+    /// Same as [`get_blocking`] for `issue_id`.
+    pub fn blocking(&self, issue_id: i64) -> Vec<i64> {
+        self.blocking.get(&issue_id).cloned().unwrap_or_default()
+    }
+
+    /// Same as [`is_blocked`] for `issue_id`.
+    pub fn is_blocked(&self, issue_id: i64) -> bool {
+        self.blocked_active.contains(&issue_id)
+    }
+
+    pub fn urgency_inputs(&self, issue_id: i64) -> UrgencyInputs {
+        UrgencyInputs {
+            blocking: self.blocking_active.contains(&issue_id),
+            blocked: self.blocked_active.contains(&issue_id),
+            note_count: self.note_counts.get(&issue_id).copied().unwrap_or(0),
+        }
+    }
+}
+
+pub fn load_issue_relations(conn: &Connection) -> Result<IssueRelations, ItrError> {
+    let mut rel = IssueRelations::default();
+
+    // Edges with the blocker's status: who blocks whom, and whether it counts.
+    let mut stmt = conn.prepare(
+        "SELECT d.blocked_id, d.blocker_id, i.status NOT IN ('done', 'wontfix')
+         FROM dependencies d JOIN issues i ON d.blocker_id = i.id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get(2)?))
+    })?;
+    for row in rows {
+        let (blocked, blocker, active): (i64, i64, bool) = row?;
+        rel.blockers.entry(blocked).or_default().push(blocker);
+        if active {
+            rel.blocked_active.insert(blocked);
+        }
+    }
+
+    // The same edges seen from the blocker, with the blocked issue's status.
+    let mut stmt = conn.prepare(
+        "SELECT d.blocker_id, d.blocked_id, i.status NOT IN ('done', 'wontfix')
+         FROM dependencies d JOIN issues i ON d.blocked_id = i.id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get(2)?))
+    })?;
+    for row in rows {
+        let (blocker, blocked, active): (i64, i64, bool) = row?;
+        rel.blocking.entry(blocker).or_default().push(blocked);
+        if active {
+            rel.blocking_active.insert(blocker);
+        }
+    }
+
+    // External blockers always count.
+    let mut stmt = conn.prepare("SELECT DISTINCT issue_id FROM external_blockers")?;
+    for id in stmt.query_map([], |row| row.get::<_, i64>(0))? {
+        rel.blocked_active.insert(id?);
+    }
+
+    let mut stmt = conn.prepare("SELECT issue_id, COUNT(*) FROM notes GROUP BY issue_id")?;
+    rel.note_counts = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    Ok(rel)
+}
+
+// --- Search ---
+
+/// Escape SQL LIKE wildcards (`%`, `_`) and the escape character itself so a
+/// user-supplied term matches only literal occurrences. Must be paired with
+/// `ESCAPE '\'` on the LIKE clause.
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+pub fn search_issue_ids(
+    conn: &Connection,
+    terms: &[String],
+    statuses: &[String],
+    priorities: &[String],
+    kinds: &[String],
+    all: bool,
+) -> Result<Vec<i64>, ItrError> {
+    if terms.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut sql = String::from(
+        "SELECT DISTINCT i.id FROM issues i LEFT JOIN notes n ON n.issue_id = i.id WHERE 1=1",
+    );
+    let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> =
+        Vec::with_capacity(terms.len() * 8);
+
+    // Each term must match at least one searchable field
+    for term in terms {
+        let pattern = format!("%{}%", escape_like(term));
+        let base = param_values.len();
+        let p1 = base + 1;
+        let p2 = base + 2;
+        let p3 = base + 3;
+        let p4 = base + 4;
+        let p5 = base + 5;
+        let p6 = base + 6;
+        let p7 = base + 7;
+        let p8 = base + 8;
+        sql.push_str(&format!(
+            " AND (i.title LIKE ?{} ESCAPE '\\' OR i.context LIKE ?{} ESCAPE '\\' OR i.acceptance LIKE ?{} ESCAPE '\\' OR i.close_reason LIKE ?{} ESCAPE '\\' OR i.tags LIKE ?{} ESCAPE '\\' OR i.files LIKE ?{} ESCAPE '\\' OR i.skills LIKE ?{} ESCAPE '\\' OR n.content LIKE ?{} ESCAPE '\\')",
+            p1, p2, p3, p4, p5, p6, p7, p8
+        ));
+        for _ in 0..8 {
+            param_values.push(Box::new(pattern.clone()));
+        }
+    }
+
+    // Status filter
+    if !all {
+        if statuses.is_empty() {
+            let defaults: Vec<String> = ACTIVE_STATUSES.iter().map(|s| (*s).to_string()).collect();
+            append_in_clause(&mut sql, &mut param_values, "i.status", &defaults);
+        } else {
+            append_in_clause(&mut sql, &mut param_values, "i.status", statuses);
+        }
+    }
+
+    if !priorities.is_empty() {
+        append_in_clause(&mut sql, &mut param_values, "i.priority", priorities);
+    }
+
+    if !kinds.is_empty() {
+        append_in_clause(&mut sql, &mut param_values, "i.kind", kinds);
+    }
+
+    let params_ref: Vec<&dyn rusqlite::types::ToSql> = param_values
+        .iter()
+        .map(std::convert::AsRef::as_ref)
+        .collect();
+    let mut stmt = conn.prepare(&sql)?;
+    let ids: Vec<i64> = stmt
+        .query_map(params_ref.as_slice(), |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+pub fn search_note_issue_ids(
+    conn: &Connection,
+    terms: &[String],
+    statuses: &[String],
+    priorities: &[String],
+    kinds: &[String],
+    all: bool,
+) -> Result<Vec<i64>, ItrError> {
+    if terms.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut sql = String::from(
+        "SELECT DISTINCT i.id FROM notes n JOIN issues i ON i.id = n.issue_id WHERE 1=1",
+    );
+    let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::with_capacity(terms.len());
+
+    for term in terms {
+        let p = param_values.len() + 1;
+        sql.push_str(&format!(" AND n.content LIKE ?{} ESCAPE '\\'", p));
+        param_values.push(Box::new(format!("%{}%", escape_like(term))));
+    }
+
+    if !all {
+        if statuses.is_empty() {
+            let defaults: Vec<String> = ACTIVE_STATUSES.iter().map(|s| (*s).to_string()).collect();
+            append_in_clause(&mut sql, &mut param_values, "i.status", &defaults);
+        } else {
+            append_in_clause(&mut sql, &mut param_values, "i.status", statuses);
+        }
+    }
+
+    if !priorities.is_empty() {
+        append_in_clause(&mut sql, &mut param_values, "i.priority", priorities);
+    }
+
+    if !kinds.is_empty() {
+        append_in_clause(&mut sql, &mut param_values, "i.kind", kinds);
+    }
+
+    let params_ref: Vec<&dyn rusqlite::types::ToSql> = param_values
+        .iter()
+        .map(std::convert::AsRef::as_ref)
+        .collect();
+    let mut stmt = conn.prepare(&sql)?;
+    let ids: Vec<i64> = stmt
+        .query_map(params_ref.as_slice(), |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+// --- Config ---
+
+/// A config value: the database's own setting, else the connection's
+/// defaults (see [`set_config_defaults`]).
+pub fn config_get(conn: &Connection, key: &str) -> Result<Option<String>, ItrError> {
+    match conn.query_row(
+        "SELECT value FROM config WHERE key = ?1",
+        params![key],
+        |row| row.get::<_, String>(0),
+    ) {
+        Ok(val) => Ok(Some(val)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(config_default(conn, key)),
+        Err(e) => Err(ItrError::Db(e)),
+    }
+}
+
+pub fn config_set(conn: &Connection, key: &str, value: &str) -> Result<(), ItrError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO config (key, value) VALUES (?1, ?2)",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+pub fn config_delete(conn: &Connection, key: &str) -> Result<(), ItrError> {
+    conn.execute("DELETE FROM config WHERE key = ?1", params![key])?;
+    Ok(())
+}
+
+pub fn config_list(conn: &Connection) -> Result<Vec<(String, String)>, ItrError> {
+    let mut stmt = conn.prepare("SELECT key, value FROM config ORDER BY key")?;
+    let rows: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+pub fn config_reset(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute("DELETE FROM config", [])?;
+    Ok(())
+}
+
+// --- All issues (for export, stats, etc.) ---
+
+pub fn all_issues(conn: &Connection) -> Result<Vec<Issue>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, title, status, priority, kind, context, files, tags, skills, acceptance, parent_id, close_reason, created_at, updated_at, assigned_to, branch, COALESCE((SELECT name FROM projects WHERE id = issues.project_id), ''), severity, COALESCE((SELECT name FROM components WHERE id = issues.component_id), ''), affects_version, fixed_in_version, estimate
+         FROM issues ORDER BY id",
+    )?;
+    let issues: Vec<Issue> = stmt
+        .query_map([], row_to_issue)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(issues)
+}
+
+pub fn all_dependencies(conn: &Connection) -> Result<Vec<(i64, i64)>, ItrError> {
+    let mut stmt = conn.prepare("SELECT blocker_id, blocked_id FROM dependencies")?;
+    let deps: Vec<(i64, i64)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(deps)
+}
+
+#[allow(dead_code)]
+pub fn all_notes(conn: &Connection) -> Result<Vec<Note>, ItrError> {
+    let mut stmt = conn
+        .prepare("SELECT id, issue_id, content, agent, created_at, kind FROM notes ORDER BY id")?;
+    let notes: Vec<Note> = stmt
+        .query_map([], row_to_note)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(notes)
+}
+
+// --- UUIDs ---
+
+pub fn issue_uuid(conn: &Connection, issue_id: i64) -> Result<String, ItrError> {
+    Ok(conn
+        .query_row(
+            "SELECT uuid FROM issue_uuids WHERE issue_id = ?1",
+            params![issue_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or_default())
+}
+
+pub fn issue_id_by_uuid(conn: &Connection, uuid: &str) -> Result<Option<i64>, ItrError> {
+    Ok(conn
+        .query_row(
+            "SELECT issue_id FROM issue_uuids WHERE uuid = ?1",
+            params![uuid],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// The issue's key (`APP-42`), or empty when its project never had a prefix.
+pub fn issue_key(conn: &Connection, issue_id: i64) -> Result<String, ItrError> {
+    Ok(conn
+        .query_row(
+            "SELECT key FROM issue_keys WHERE issue_id = ?1",
+            params![issue_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or_default())
+}
+
+/// The issue holding `key`, matched case-insensitively.
+pub fn issue_id_by_key(conn: &Connection, key: &str) -> Result<Option<i64>, ItrError> {
+    if crate::util::parse_issue_key(key).is_none() {
+        return Ok(None);
+    }
+    Ok(conn
+        .query_row(
+            "SELECT issue_id FROM issue_keys WHERE key = ?1",
+            params![key.trim()],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Give an issue the key it has in another copy, replacing any it was
+/// handed here. Returns false, leaving the issue's own key, when the key is
+/// malformed or a different issue here already has it.
+pub fn set_issue_key(conn: &Connection, issue_id: i64, key: &str) -> Result<bool, ItrError> {
+    let Some((prefix, number)) = crate::util::parse_issue_key(key) else {
+        return Ok(false);
+    };
+    match issue_id_by_key(conn, key)? {
+        Some(id) if id != issue_id => return Ok(false),
+        Some(_) => return Ok(true),
+        None => {}
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO issue_keys (issue_id, key) VALUES (?1, ?2)",
+        params![issue_id, format!("{}-{}", prefix, number)],
+    )?;
+    Ok(true)
+}
+
+/// Give an issue the UUID it has in another copy. Returns false, leaving the
+/// issue's own UUID, when a different issue here already has that one.
+pub fn set_issue_uuid(conn: &Connection, issue_id: i64, uuid: &str) -> Result<bool, ItrError> {
+    match issue_id_by_uuid(conn, uuid)? {
+        Some(id) if id != issue_id => return Ok(false),
+        Some(_) => return Ok(true),
+        None => {}
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO issue_uuids (issue_id, uuid) VALUES (?1, ?2)",
+        params![issue_id, uuid],
+    )?;
+    Ok(true)
+}
+
+/// UUIDs of an issue's notes, by note ID.
+pub fn note_uuids(conn: &Connection, issue_id: i64) -> Result<HashMap<i64, String>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT u.note_id, u.uuid FROM note_uuids u JOIN notes n ON n.id = u.note_id
+         WHERE n.issue_id = ?1",
+    )?;
+    let uuids = stmt
+        .query_map(params![issue_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(uuids)
+}
+
+pub fn note_uuid_exists(conn: &Connection, uuid: &str) -> Result<bool, ItrError> {
+    Ok(conn.query_row(
+        "SELECT COUNT(*) > 0 FROM note_uuids WHERE uuid = ?1",
+        params![uuid],
+        |row| row.get(0),
+    )?)
+}
+
+pub fn set_note_uuid(conn: &Connection, note_id: i64, uuid: &str) -> Result<(), ItrError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO note_uuids (note_id, uuid) VALUES (?1, ?2)",
+        params![note_id, uuid],
+    )?;
+    Ok(())
+}
+
+// --- Events (Audit Log) ---
+
+/// Environment variable naming the acting agent.
+pub const AGENT_ENV: &str = "ITR_AGENT";
+/// Config key naming the acting agent when [`AGENT_ENV`] is unset; most
+/// useful in the global config, where it identifies one user everywhere.
+pub const AGENT_KEY: &str = "agent.name";
+
+/// Who is acting, for claims, notes, and the audit log: `ITR_AGENT`, else
+/// `agent.name` (this database's, then the global config's), else empty.
+/// An explicit `--agent` wins over both; callers check it first.
+pub fn current_agent(conn: &Connection) -> String {
+    env::var(AGENT_ENV)
+        .ok()
+        .filter(|a| !a.trim().is_empty())
+        .or_else(|| config_get(conn, AGENT_KEY).ok().flatten())
+        .map(|a| a.trim().to_string())
+        .unwrap_or_default()
+}
+
+pub fn record_event(
+    conn: &Connection,
+    issue_id: i64,
+    field: &str,
+    old_value: &str,
+    new_value: &str,
+) -> Result<(), ItrError> {
+    let agent = current_agent(conn);
+    conn.execute(
+        "INSERT INTO events (issue_id, field, old_value, new_value, agent)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![issue_id, field, old_value, new_value, agent],
+    )?;
+    touch_agent(conn, &agent)
+}
+
+/// Record an event that happened at `at` rather than now, such as a change
+/// carried over from another copy by `import --merge`.
+pub fn record_event_at(
+    conn: &Connection,
+    issue_id: i64,
+    field: &str,
+    old_value: &str,
+    new_value: &str,
+    at: &str,
+) -> Result<(), ItrError> {
+    let agent = current_agent(conn);
+    conn.execute(
+        "INSERT INTO events (issue_id, field, old_value, new_value, agent, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![issue_id, field, old_value, new_value, agent, at],
+    )?;
+    Ok(())
+}
+
+/// When each field of an issue last changed, from the event log. Fields with
+/// no event still have the value they were created with.
+pub fn field_times(conn: &Connection, issue_id: i64) -> Result<BTreeMap<String, String>, ItrError> {
+    let mut stmt = conn
+        .prepare("SELECT field, MAX(created_at) FROM events WHERE issue_id = ?1 GROUP BY field")?;
+    let times = stmt
+        .query_map(params![issue_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    Ok(times)
+}
+
+pub fn get_events_for_issue(conn: &Connection, issue_id: i64) -> Result<Vec<Event>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, issue_id, field, old_value, new_value, agent, created_at
+         FROM events WHERE issue_id = ?1 ORDER BY created_at ASC",
+    )?;
+    let events: Vec<Event> = stmt
+        .query_map(params![issue_id], row_to_event)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(events)
+}
+
+pub fn get_recent_events(
+    conn: &Connection,
+    limit: usize,
+    since: Option<&str>,
+) -> Result<Vec<Event>, ItrError> {
+    let (sql, param_values): (String, Vec<Box<dyn rusqlite::types::ToSql>>) =
+        if let Some(since_ts) = since {
+            (
+                "SELECT id, issue_id, field, old_value, new_value, agent, created_at
+                 FROM events WHERE created_at >= ?1 ORDER BY created_at DESC LIMIT ?2"
+                    .to_string(),
+                vec![Box::new(since_ts.to_string()), Box::new(limit as i64)],
+            )
+        } else {
+            (
+                "SELECT id, issue_id, field, old_value, new_value, agent, created_at
+                 FROM events ORDER BY created_at DESC LIMIT ?1"
+                    .to_string(),
+                vec![Box::new(limit as i64)],
+            )
+        };
+    let params_ref: Vec<&dyn rusqlite::types::ToSql> = param_values
+        .iter()
+        .map(std::convert::AsRef::as_ref)
+        .collect();
+    let mut stmt = conn.prepare(&sql)?;
+    let events: Vec<Event> = stmt
+        .query_map(params_ref.as_slice(), row_to_event)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(events)
+}
+
+/// Fetch events with every filter applied in SQL before the limit (#170).
+///
+/// Returns the newest matching events first. Filters: optional issue scope,
+/// optional `created_at >= since`, optional exact agent match. Filtering
+/// before `LIMIT` is the point — limiting first would hide older matching
+/// events behind newer non-matching ones.
+pub fn get_events_filtered(
+    conn: &Connection,
+    issue_id: Option<i64>,
+    limit: usize,
+    since: Option<&str>,
+    agent: Option<&str>,
+) -> Result<Vec<Event>, ItrError> {
+    let mut sql = String::from(
+        "SELECT id, issue_id, field, old_value, new_value, agent, created_at FROM events",
+    );
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+    if let Some(id) = issue_id {
+        values.push(Box::new(id));
+        clauses.push(format!("issue_id = ?{}", values.len()));
+    }
+    if let Some(ts) = since {
+        values.push(Box::new(ts.to_string()));
+        clauses.push(format!("created_at >= ?{}", values.len()));
+    }
+    if let Some(name) = agent {
+        values.push(Box::new(name.to_string()));
+        clauses.push(format!("agent = ?{}", values.len()));
+    }
+    if !clauses.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&clauses.join(" AND "));
+    }
+    values.push(Box::new(limit as i64));
+    sql.push_str(&format!(
+        " ORDER BY created_at DESC, id DESC LIMIT ?{}",
+        values.len()
+    ));
+
+    let params_ref: Vec<&dyn rusqlite::types::ToSql> =
+        values.iter().map(std::convert::AsRef::as_ref).collect();
+    let mut stmt = conn.prepare(&sql)?;
+    let events: Vec<Event> = stmt
+        .query_map(params_ref.as_slice(), row_to_event)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(events)
+}
+
+/// Events with an ID above `after_id` (and `created_at >= since`, when
+/// given), oldest first. Event IDs only grow, so the last ID seen is a
+/// resumable cursor; timestamps have one-second resolution and are not.
+pub fn get_events_after(
+    conn: &Connection,
+    after_id: i64,
+    since: Option<&str>,
+) -> Result<Vec<Event>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, issue_id, field, old_value, new_value, agent, created_at
+         FROM events WHERE id > ?1 AND (?2 IS NULL OR created_at >= ?2)
+         ORDER BY id ASC",
+    )?;
+    let events: Vec<Event> = stmt
+        .query_map(params![after_id, since], row_to_event)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(events)
+}
+
+/// Highest event ID recorded so far (0 when there are none).
+pub fn last_event_id(conn: &Connection) -> Result<i64, ItrError> {
+    Ok(
+        conn.query_row("SELECT COALESCE(MAX(id), 0) FROM events", [], |row| {
+            row.get(0)
+        })?,
+    )
+}
+
+// --- Relations ---
+
+pub fn add_relation(
+    conn: &Connection,
+    source_id: i64,
+    target_id: i64,
+    relation_type: &str,
+) -> Result<bool, ItrError> {
+    if source_id == target_id {
+        return Err(ItrError::InvalidValue {
+            field: "relation".to_string(),
+            value: "self".to_string(),
+            valid: "source and target must be different issues".to_string(),
+        });
+    }
+    if !issue_exists(conn, source_id)? {
+        return Err(ItrError::NotFound(source_id));
+    }
+    if !issue_exists(conn, target_id)? {
+        return Err(ItrError::NotFound(target_id));
+    }
+
+    let exists: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM relations WHERE source_id = ?1 AND target_id = ?2 AND relation_type = ?3",
+        params![source_id, target_id, relation_type],
+        |row| row.get(0),
+    )?;
+    if exists {
+        return Ok(false);
+    }
+
+    conn.execute(
+        "INSERT INTO relations (source_id, target_id, relation_type) VALUES (?1, ?2, ?3)",
+        params![source_id, target_id, relation_type],
+    )?;
+
+    record_event(
+        conn,
+        source_id,
+        "relation_added",
+        "",
+        &format!("{}:{}", relation_type, target_id),
+    )?;
+    Ok(true)
+}
+
+/// Removes relations between a pair of issues, matching the pair in EITHER
+/// direction — `get_relations` displays both directions, so unrelate must
+/// accept the pair however the caller saw it (#186). An optional
+/// `relation_type` filter limits removal to one type; with `None`, every
+/// typed link between the pair is removed. Returns the removed relations so
+/// callers can report exactly what was deleted (type + stored direction).
+pub fn remove_relation(
+    conn: &Connection,
+    issue_id: i64,
+    other_id: i64,
+    relation_type: Option<&str>,
+) -> Result<Vec<Relation>, ItrError> {
+    if !issue_exists(conn, issue_id)? {
+        return Err(ItrError::NotFound(issue_id));
+    }
+    if !issue_exists(conn, other_id)? {
+        return Err(ItrError::NotFound(other_id));
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT id, source_id, target_id, relation_type, created_at
+         FROM relations
+         WHERE ((source_id = ?1 AND target_id = ?2)
+             OR (source_id = ?2 AND target_id = ?1))
+           AND (?3 IS NULL OR relation_type = ?3)
+         ORDER BY id",
+    )?;
+    let matched: Vec<Relation> = stmt
+        .query_map(params![issue_id, other_id, relation_type], row_to_relation)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for relation in &matched {
+        conn.execute("DELETE FROM relations WHERE id = ?1", params![relation.id])?;
+        // Mirror relation_added's `type:target` value so the audit log keeps
+        // enough detail to reconstruct exactly which typed link was removed.
+        record_event(
+            conn,
+            relation.source_id,
+            "relation_removed",
+            &format!("{}:{}", relation.relation_type, relation.target_id),
+            "",
+        )?;
+    }
+    Ok(matched)
+}
+
+pub fn get_relations(conn: &Connection, issue_id: i64) -> Result<Vec<Relation>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, source_id, target_id, relation_type, created_at
+         FROM relations WHERE source_id = ?1 OR target_id = ?1
+         ORDER BY created_at ASC",
+    )?;
+    let relations: Vec<Relation> = stmt
+        .query_map(params![issue_id], row_to_relation)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(relations)
+}
+
+pub fn all_relations(conn: &Connection) -> Result<Vec<Relation>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, source_id, target_id, relation_type, created_at
+         FROM relations ORDER BY id",
+    )?;
+    let relations: Vec<Relation> = stmt
+        .query_map([], row_to_relation)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(relations)
+}
+
+// --- FTS5 Full-Text Search ---
+
+pub fn has_fts(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='issues_fts'",
+        [],
+        |row| row.get::<_, bool>(0),
+    )
+    .unwrap_or(false)
+}
+
+/// FTS5 table definition. `contentless_delete=1` (`SQLite >= 3.43`) allows rows
+/// to be removed by rowid alone, without knowing the previously indexed
+/// values. That keeps delete-then-insert reindexing correct for every writer,
+/// including `INSERT OR REPLACE` (which bypasses delete triggers when
+/// recursive triggers are off, as they are by default).
+const FTS_CREATE: &str = "CREATE VIRTUAL TABLE IF NOT EXISTS issues_fts USING fts5(
+    title, context, acceptance, tags_text, files_text, skills_text, close_reason,
+    content='', contentless_delete=1
+);";
+
+/// Triggers that keep `issues_fts` in sync with every write path to `issues`,
+/// including raw SQL writers that never call `fts_index_issue` (e.g. the UI's
+/// dangerous SQL mode). The JSON-array columns (tags/files/skills) are indexed
+/// as their raw JSON text; the default unicode61 tokenizer treats punctuation
+/// as separators, so the tokens are identical to space-joined values.
+/// `UPDATE OF` is restricted to searchable columns so the `updated_at`
+/// touch trigger and status-only updates skip the reindex.
+const FTS_TRIGGERS: &str = "
+CREATE TRIGGER IF NOT EXISTS issues_fts_ai AFTER INSERT ON issues BEGIN
+    DELETE FROM issues_fts WHERE rowid = new.id;
+    INSERT INTO issues_fts(rowid, title, context, acceptance, tags_text, files_text, skills_text, close_reason)
+    VALUES (new.id, new.title, new.context, new.acceptance, new.tags, new.files, new.skills, new.close_reason);
+END;
+CREATE TRIGGER IF NOT EXISTS issues_fts_ad AFTER DELETE ON issues BEGIN
+    DELETE FROM issues_fts WHERE rowid = old.id;
+END;
+CREATE TRIGGER IF NOT EXISTS issues_fts_au AFTER UPDATE OF title, context, acceptance, tags, files, skills, close_reason ON issues BEGIN
+    DELETE FROM issues_fts WHERE rowid = old.id;
+    INSERT INTO issues_fts(rowid, title, context, acceptance, tags_text, files_text, skills_text, close_reason)
+    VALUES (new.id, new.title, new.context, new.acceptance, new.tags, new.files, new.skills, new.close_reason);
+END;
+";
+
+const FTS_DROP: &str = "
+DROP TRIGGER IF EXISTS issues_fts_ai;
+DROP TRIGGER IF EXISTS issues_fts_ad;
+DROP TRIGGER IF EXISTS issues_fts_au;
+DROP TABLE IF EXISTS issues_fts;
+";
+
+/// Returns true if an `issues_fts` table exists but predates the
+/// `contentless_delete` + trigger design. The legacy contentless table could
+/// not remove a rowid's old tokens, so updates left stale terms searchable.
+fn fts_is_legacy(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type='table' AND name='issues_fts'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .map(|sql| !sql.contains("contentless_delete"))
+    .unwrap_or(false)
+}
+
+/// Attempt to create the FTS5 virtual table and its sync triggers. Silently
+/// does nothing if FTS5 is unavailable (search falls back to LIKE). A legacy
+/// stale-token index is dropped and rebuilt in place.
+fn try_create_fts(conn: &Connection) {
+    if fts_is_legacy(conn) {
+        let _ = conn.execute_batch(FTS_DROP);
+    }
+    let existed = has_fts(conn);
+    if conn.execute_batch(FTS_CREATE).is_err() || !has_fts(conn) {
+        return; // FTS5 unavailable; search uses the LIKE fallback.
+    }
+    let _ = conn.execute_batch(FTS_TRIGGERS);
+    if !existed {
+        // Fresh or migrated index: populate from current issues.
+        if let Ok(issues) = all_issues(conn) {
+            for issue in &issues {
+                fts_index_issue(conn, issue);
+            }
+        }
+    }
+}
+
+/// (Re)index a single issue into FTS. The triggers installed by
+/// `try_create_fts` already cover every SQL write path; this remains the
+/// public entry point for callers that want to force a row's entry (e.g.
+/// import, reindex). Delete-then-insert is idempotent because
+/// `contentless_delete=1` removes by rowid without needing old values.
+pub fn fts_index_issue(conn: &Connection, issue: &Issue) {
+    if !has_fts(conn) {
+        return;
+    }
+    let tags_text = issue.tags.join(" ");
+    let files_text = issue.files.join(" ");
+    let skills_text = issue.skills.join(" ");
+
+    let result = conn
+        .execute("DELETE FROM issues_fts WHERE rowid = ?1", params![issue.id])
+        .and_then(|_| {
+            conn.execute(
+                "INSERT INTO issues_fts(rowid, title, context, acceptance, tags_text, files_text, skills_text, close_reason)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![issue.id, issue.title, issue.context, issue.acceptance, tags_text, files_text, skills_text, issue.close_reason],
+            )
+        });
+    if let Err(e) = result {
+        crate::util::diagnostic(&format!(
+            "REVIEW: failed to update search index for issue #{}: {} (run `itr reindex` to rebuild)",
+            issue.id, e
+        ));
+    }
+}
+
+/// Rebuild the entire FTS index from scratch.
+pub fn fts_rebuild(conn: &Connection) -> Result<(), ItrError> {
+    // Drop table and triggers, then recreate; try_create_fts repopulates
+    // the fresh index from the issues table.
+    conn.execute_batch(FTS_DROP)?;
+    try_create_fts(conn);
+
+    if !has_fts(conn) {
+        return Err(ItrError::InvalidValue {
+            field: "fts5".to_string(),
+            value: "unavailable".to_string(),
+            valid: "SQLite must be compiled with FTS5 support".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Search using FTS5 MATCH. Returns issue IDs sorted by rank.
+pub fn fts_search(conn: &Connection, query: &str) -> Result<Vec<i64>, ItrError> {
+    // Escape FTS5 special characters and build OR query for each term
+    let terms: Vec<&str> = query.split_whitespace().collect();
+    if terms.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // Build FTS5 query: each term is quoted, joined with AND
+    let fts_query: String = terms
+        .iter()
+        .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+
+    let mut stmt =
+        conn.prepare("SELECT rowid FROM issues_fts WHERE issues_fts MATCH ?1 ORDER BY rank")?;
+    let ids: Vec<i64> = stmt
+        .query_map(params![fts_query], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+/// Open a fresh in-memory database with the full schema, migrations, and FTS
+/// applied. Shared test fixture for unit tests across command modules.
+#[cfg(any(test, feature = "test-support"))]
+pub fn open_test_db() -> Connection {
+    let conn = Connection::open_in_memory().expect("open in-memory db");
+    conn.execute_batch(SCHEMA).expect("apply schema");
+    migrate_current_schema(&conn).expect("apply migrations");
+    try_create_fts(&conn);
+    conn
+}
+
+/// Insert a medium-priority task titled `title`, every other field empty,
+/// and return its ID. Shared test fixture.
+#[cfg(any(test, feature = "test-support"))]
+pub fn insert_test_issue(conn: &Connection, title: &str) -> i64 {
+    insert_test_issue_with(conn, title, "task", None)
+}
+
+/// [`insert_test_issue`] with a `kind` and an optional parent.
+#[cfg(any(test, feature = "test-support"))]
+pub fn insert_test_issue_with(
+    conn: &Connection,
+    title: &str,
+    kind: &str,
+    parent: Option<i64>,
+) -> i64 {
+    insert_issue(
+        conn,
+        title,
+        "medium",
+        kind,
+        "",
+        &[],
+        &[],
+        &[],
+        "",
+        parent,
+        "",
+    )
+    .expect("insert test issue")
+    .id
+}
+
+/// A freshly initialized database file in the temp dir, unique to this
+/// process and `name`, for tests that need a second connection (a watcher
+/// and a writer). Delete it with [`remove_test_db`].
+#[cfg(any(test, feature = "test-support"))]
+pub fn temp_test_db(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("itr-unit-{}-{}.db", std::process::id(), name));
+    remove_test_db(&path);
+    drop(init_db(&path).expect("init temp test db"));
+    path
+}
+
+/// Delete a [`temp_test_db`] file along with its WAL and shared-memory files.
+#[cfg(any(test, feature = "test-support"))]
+pub fn remove_test_db(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(format!("{}-wal", path.display()));
+    let _ = std::fs::remove_file(format!("{}-shm", path.display()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_conn() -> Connection {
+        let conn = open_test_db();
+        assert!(has_fts(&conn), "bundled SQLite must support FTS5");
+        conn
+    }
+
+    fn add(conn: &Connection, title: &str) -> Issue {
+        insert_issue(
+            conn,
+            title,
+            "medium",
+            "task",
+            "",
+            &[],
+            &[],
+            &[],
+            "",
+            None,
+            "",
+        )
+        .unwrap()
+    }
+
+    // --- issue lookup by slug or title prefix ---
+
+    #[test]
+    fn find_issue_by_name_prefers_exact_then_active() {
+        let conn = test_conn();
+        let login = add(&conn, "Fix login timeout");
+        let logout = add(&conn, "Fix logout button");
+        let old = add(&conn, "Login page redesign");
+        update_issue_field(&conn, old.id, "status", "done").unwrap();
+        let page = add(&conn, "Login page");
+
+        assert_eq!(
+            find_issue_by_name(&conn, "fix-login-timeout").unwrap(),
+            Some(login.id)
+        );
+        assert_eq!(
+            find_issue_by_name(&conn, "fix logi").unwrap(),
+            Some(login.id)
+        );
+        // Exact title beats the prefix match on the closed redesign.
+        assert_eq!(
+            find_issue_by_name(&conn, "login page").unwrap(),
+            Some(page.id)
+        );
+        assert_eq!(find_issue_by_name(&conn, "nothing here").unwrap(), None);
+        assert_eq!(resolve_issue_ref(&conn, "fix-logout").unwrap(), logout.id);
+
+        match find_issue_by_name(&conn, "fix").unwrap_err() {
+            ItrError::Ambiguous { candidates, .. } => {
+                let ids: Vec<i64> = candidates.iter().map(|(id, _)| *id).collect();
+                assert_eq!(ids, vec![login.id, logout.id]);
+            }
+            other => panic!("expected AMBIGUOUS, got {other:?}"),
+        }
+    }
+
+    // --- issue keys ---
+
+    #[test]
+    fn issue_keys_follow_the_project_prefix_and_never_change() {
+        let conn = test_conn();
+        add_project(&conn, "app", "").unwrap();
+        add_project(&conn, "web", "").unwrap();
+        let early = add(&conn, "before the prefix");
+        set_issue_project(&conn, early.id, Some("app")).unwrap();
+        assert_eq!(issue_key(&conn, early.id).unwrap(), "");
+
+        assert_eq!(set_project_key_prefix(&conn, "app", "APP").unwrap(), 1);
+        assert_eq!(issue_key(&conn, early.id).unwrap(), "APP-1");
+
+        conn.execute(
+            "INSERT INTO issues (title, project_id) SELECT 'filed in app', id FROM projects WHERE name = 'app'",
+            [],
+        )
+        .unwrap();
+        let scoped = get_issue(&conn, conn.last_insert_rowid()).unwrap();
+        assert_eq!(issue_key(&conn, scoped.id).unwrap(), "APP-2");
+
+        let moved = add(&conn, "moved in");
+        set_issue_project(&conn, moved.id, Some("app")).unwrap();
+        assert_eq!(issue_key(&conn, moved.id).unwrap(), "APP-3");
+
+        // Moving out keeps the key; a new prefix only affects new keys.
+        set_issue_project(&conn, moved.id, Some("web")).unwrap();
+        set_project_key_prefix(&conn, "app", "CORE").unwrap();
+        assert_eq!(issue_key(&conn, moved.id).unwrap(), "APP-3");
+        assert_eq!(resolve_issue_ref(&conn, "app-3").unwrap(), moved.id);
+        assert_eq!(
+            resolve_id_tokens(&conn, &["APP-1,APP-2".to_string(), "9".to_string()]).unwrap(),
+            vec![format!("{},{}", early.id, scoped.id), "9".to_string()]
+        );
+
+        assert!(!set_issue_key(&conn, early.id, "APP-2").unwrap());
+        assert!(set_issue_key(&conn, early.id, "app-9").unwrap());
+        assert_eq!(issue_key(&conn, early.id).unwrap(), "APP-9");
+    }
+
+    // --- #152: FTS staleness on field updates ---
+
+    #[test]
+    fn fts_update_title_removes_stale_tokens() {
+        let conn = test_conn();
+        let issue = add(&conn, "alpha widget");
+        assert_eq!(fts_search(&conn, "widget").unwrap(), vec![issue.id]);
+
+        update_issue_field(&conn, issue.id, "title", "gadget beta").unwrap();
+
+        assert!(
+            fts_search(&conn, "widget").unwrap().is_empty(),
+            "old title token must not be searchable after update"
+        );
+        assert!(fts_search(&conn, "alpha").unwrap().is_empty());
+        assert_eq!(fts_search(&conn, "gadget").unwrap(), vec![issue.id]);
+        assert_eq!(fts_search(&conn, "beta").unwrap(), vec![issue.id]);
+    }
+
+    #[test]
+    fn fts_reflects_updates_to_all_searchable_fields() {
+        let cases = [
+            ("context", "oldctx", "newctx", "oldctx", "newctx"),
+            ("acceptance", "oldacc", "newacc", "oldacc", "newacc"),
+            (
+                "close_reason",
+                "oldreason",
+                "newreason",
+                "oldreason",
+                "newreason",
+            ),
+            ("tags", r#"["oldtag"]"#, r#"["newtag"]"#, "oldtag", "newtag"),
+            (
+                "files",
+                r#"["src/oldfile.rs"]"#,
+                r#"["src/newfile.rs"]"#,
+                "oldfile",
+                "newfile",
+            ),
+            (
+                "skills",
+                r#"["oldskill"]"#,
+                r#"["newskill"]"#,
+                "oldskill",
+                "newskill",
+            ),
+        ];
+        for (field, old_value, new_value, old_term, new_term) in cases {
+            let conn = test_conn();
+            let issue = add(&conn, "plain title");
+            update_issue_field(&conn, issue.id, field, old_value).unwrap();
+            assert_eq!(
+                fts_search(&conn, old_term).unwrap(),
+                vec![issue.id],
+                "field {field} should be indexed"
+            );
+
+            update_issue_field(&conn, issue.id, field, new_value).unwrap();
+            assert!(
+                fts_search(&conn, old_term).unwrap().is_empty(),
+                "stale {field} token must not be searchable after update"
+            );
+            assert_eq!(fts_search(&conn, new_term).unwrap(), vec![issue.id]);
+        }
+    }
+
+    #[test]
+    fn fts_stays_fresh_on_direct_sql_update() {
+        // Writers that bypass the db helpers (e.g. UI dangerous SQL mode)
+        // are covered by the sync triggers.
+        let conn = test_conn();
+        let issue = add(&conn, "trigger coverage check");
+        conn.execute(
+            "UPDATE issues SET title = 'zebra crossing' WHERE id = ?1",
+            params![issue.id],
+        )
+        .unwrap();
+
+        assert!(fts_search(&conn, "coverage").unwrap().is_empty());
+        assert_eq!(fts_search(&conn, "zebra").unwrap(), vec![issue.id]);
+    }
+
+    #[test]
+    fn fts_insert_or_replace_reindexes() {
+        // The import path uses INSERT OR REPLACE, whose implicit delete does
+        // not fire delete triggers; the insert trigger's delete-by-rowid
+        // must still clear lingering tokens.
+        let conn = test_conn();
+        let issue = add(&conn, "original tokens here");
+        conn.execute(
+            "INSERT OR REPLACE INTO issues (id, title) VALUES (?1, 'replacement text')",
+            params![issue.id],
+        )
+        .unwrap();
+
+        assert!(fts_search(&conn, "original").unwrap().is_empty());
+        assert_eq!(fts_search(&conn, "replacement").unwrap(), vec![issue.id]);
+    }
+
+    #[test]
+    fn fts_delete_removes_entry_and_count_stays_in_sync() {
+        let conn = test_conn();
+        let a = add(&conn, "first issue");
+        let b = add(&conn, "second issue");
+        conn.execute("DELETE FROM issues WHERE id = ?1", params![a.id])
+            .unwrap();
+
+        assert!(fts_search(&conn, "first").unwrap().is_empty());
+        assert_eq!(fts_search(&conn, "second").unwrap(), vec![b.id]);
+        // doctor's fts_stale check compares row counts; deletes must not skew it.
+        let fts_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM issues_fts", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(fts_count, 1);
+    }
+
+    #[test]
+    fn fts_legacy_contentless_table_is_migrated() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        migrate_current_schema(&conn).unwrap();
+        // Recreate the legacy index design and its stale-token failure mode.
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE issues_fts USING fts5(
+                title, context, acceptance, tags_text, files_text, skills_text, close_reason,
+                content='', content_rowid=id
+            );",
+        )
+        .unwrap();
+        conn.execute("INSERT INTO issues (title) VALUES ('legacy alpha')", [])
+            .unwrap();
+        let id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT OR REPLACE INTO issues_fts(rowid, title, context, acceptance, tags_text, files_text, skills_text, close_reason)
+             VALUES (?1, 'legacy alpha', '', '', '', '', '', '')",
+            params![id],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE issues SET title = 'fresh beta' WHERE id = ?1",
+            params![id],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO issues_fts(rowid, title, context, acceptance, tags_text, files_text, skills_text, close_reason)
+             VALUES (?1, 'fresh beta', '', '', '', '', '', '')",
+            params![id],
+        )
+        .unwrap();
+        // Legacy bug: the old token is still searchable.
+        assert_eq!(fts_search(&conn, "legacy").unwrap(), vec![id]);
+
+        // Opening the DB migrates and rebuilds the index.
+        try_create_fts(&conn);
+        let sql: String = conn
+            .query_row(
+                "SELECT sql FROM sqlite_master WHERE type='table' AND name='issues_fts'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(sql.contains("contentless_delete"));
+        assert!(fts_search(&conn, "legacy").unwrap().is_empty());
+        assert_eq!(fts_search(&conn, "fresh").unwrap(), vec![id]);
+        // And the new triggers keep it fresh from now on.
+        conn.execute(
+            "UPDATE issues SET title = 'final gamma' WHERE id = ?1",
+            params![id],
+        )
+        .unwrap();
+        assert!(fts_search(&conn, "fresh").unwrap().is_empty());
+        assert_eq!(fts_search(&conn, "gamma").unwrap(), vec![id]);
+    }
+
+    // --- #182: LIKE wildcard escaping ---
+
+    #[test]
+    fn escape_like_escapes_wildcards() {
+        assert_eq!(escape_like("100%"), "100\\%");
+        assert_eq!(escape_like("snake_case"), "snake\\_case");
+        assert_eq!(escape_like("back\\slash"), "back\\\\slash");
+        assert_eq!(escape_like("plain"), "plain");
+    }
+
+    #[test]
+    fn search_percent_matches_only_literal() {
+        let conn = test_conn();
+        let _fast = add(&conn, "make it 100x faster");
+        let pct = add(&conn, "reach 100% coverage");
+        let terms = vec!["100%".to_string()];
+        let ids = search_issue_ids(&conn, &terms, &[], &[], &[], true).unwrap();
+        assert_eq!(ids, vec![pct.id], "'100%' must not match '100x'");
+    }
+
+    #[test]
+    fn search_underscore_matches_only_literal() {
+        let conn = test_conn();
+        let snake = add(&conn, "rename snake_case helper");
+        let _other = add(&conn, "rename snakeXcase helper");
+        let terms = vec!["snake_case".to_string()];
+        let ids = search_issue_ids(&conn, &terms, &[], &[], &[], true).unwrap();
+        assert_eq!(ids, vec![snake.id], "'_' must not act as a wildcard");
+    }
+
+    #[test]
+    fn search_backslash_matches_literally() {
+        let conn = test_conn();
+        let bs = add(&conn, "fix back\\slash handling");
+        let _other = add(&conn, "fix backXslash handling");
+        let terms = vec!["back\\slash".to_string()];
+        let ids = search_issue_ids(&conn, &terms, &[], &[], &[], true).unwrap();
+        assert_eq!(ids, vec![bs.id]);
+    }
+
+    #[test]
+    fn note_search_percent_matches_only_literal() {
+        let conn = test_conn();
+        let a = add(&conn, "first plain");
+        let b = add(&conn, "second plain");
+        add_note(&conn, a.id, "this is 100x faster", "").unwrap();
+        add_note(&conn, b.id, "hit 100% done", "").unwrap();
+        let terms = vec!["100%".to_string()];
+        let ids = search_note_issue_ids(&conn, &terms, &[], &[], &[], true).unwrap();
+        assert_eq!(ids, vec![b.id], "'100%' must not match a '100x' note");
+    }
+
+    // --- #154 / #172: atomic compare-and-swap claim ---
+
+    fn events_for(conn: &Connection, id: i64, field: &str) -> Vec<Event> {
+        get_events_for_issue(conn, id)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.field == field)
+            .collect()
+    }
+
+    #[test]
+    fn claim_issue_transitions_open_and_assigns_atomically() {
+        let conn = test_conn();
+        let issue = add(&conn, "claim me");
+
+        let outcome = claim_issue(&conn, issue.id, Some("agent-a")).unwrap();
+        assert_eq!(
+            outcome,
+            ClaimOutcome::Claimed {
+                prior_assigned_to: String::new()
+            }
+        );
+
+        let after = get_issue(&conn, issue.id).unwrap();
+        assert_eq!(after.status, "in-progress");
+        assert_eq!(after.assigned_to, "agent-a");
+        // Both the status transition and the assignment are audit-logged.
+        assert_eq!(events_for(&conn, issue.id, "status").len(), 1);
+        assert_eq!(events_for(&conn, issue.id, "assigned_to").len(), 1);
+    }
+
+    #[test]
+    fn claim_issue_refuses_done_issue_without_mutation() {
+        let conn = test_conn();
+        let issue = add(&conn, "already finished");
+        update_issue_field(&conn, issue.id, "status", "done").unwrap();
+
+        let outcome = claim_issue(&conn, issue.id, Some("agent-a")).unwrap();
+        assert_eq!(
+            outcome,
+            ClaimOutcome::NotOpen {
+                status: "done".to_string(),
+                assigned_to: String::new()
+            }
+        );
+
+        let after = get_issue(&conn, issue.id).unwrap();
+        assert_eq!(after.status, "done", "claim must not resurrect done issues");
+        assert_eq!(after.assigned_to, "");
+        assert!(events_for(&conn, issue.id, "status").is_empty());
+        assert!(events_for(&conn, issue.id, "assigned_to").is_empty());
+    }
+
+    #[test]
+    fn claim_issue_reports_current_holder_of_in_progress_issue() {
+        let conn = test_conn();
+        let issue = add(&conn, "someone else's work");
+        assert!(matches!(
+            claim_issue(&conn, issue.id, Some("agent-a")).unwrap(),
+            ClaimOutcome::Claimed { .. }
+        ));
+
+        // A second claimer loses the CAS and learns who holds the issue.
+        let outcome = claim_issue(&conn, issue.id, Some("agent-b")).unwrap();
+        assert_eq!(
+            outcome,
+            ClaimOutcome::NotOpen {
+                status: "in-progress".to_string(),
+                assigned_to: "agent-a".to_string()
+            }
+        );
+        let after = get_issue(&conn, issue.id).unwrap();
+        assert_eq!(after.assigned_to, "agent-a", "loser must not steal");
+    }
+
+    #[test]
+    fn claim_issue_missing_id_is_not_found() {
+        let conn = test_conn();
+        assert!(matches!(
+            claim_issue(&conn, 999, None),
+            Err(ItrError::NotFound(999))
+        ));
+    }
+
+    #[test]
+    fn concurrent_claims_yield_distinct_winners() {
+        use std::collections::HashSet;
+        use std::sync::{Arc, Barrier};
+
+        const N: usize = 6;
+        let dir = std::env::temp_dir().join(format!(
+            "itr-claim-race-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("race.itr.db");
+
+        let ids: Vec<i64> = {
+            let conn = init_db(&db_path).unwrap();
+            (0..N)
+                .map(|i| add(&conn, &format!("racy issue {i}")).id)
+                .collect()
+        };
+
+        let barrier = Arc::new(Barrier::new(N));
+        let mut handles = Vec::new();
+        for n in 0..N {
+            let barrier = Arc::clone(&barrier);
+            let db_path = db_path.clone();
+            let ids = ids.clone();
+            handles.push(std::thread::spawn(move || -> Option<i64> {
+                let conn = open_db(&db_path).unwrap();
+                let agent = format!("racer-{n}");
+                barrier.wait();
+                // Mirrors claim-next: walk candidates, CAS each, keep the win.
+                for &id in &ids {
+                    if let ClaimOutcome::Claimed { .. } =
+                        claim_issue(&conn, id, Some(&agent)).unwrap()
+                    {
+                        return Some(id);
+                    }
+                }
+                None
+            }));
+        }
+
+        let winners: Vec<Option<i64>> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        let claimed: HashSet<i64> = winners.iter().filter_map(|w| *w).collect();
+        assert_eq!(
+            claimed.len(),
+            N,
+            "each claimer must win a distinct issue, got {winners:?}"
+        );
+        assert_eq!(claimed, ids.iter().copied().collect::<HashSet<_>>());
+
+        let conn = open_db(&db_path).unwrap();
+        for &id in &ids {
+            let issue = get_issue(&conn, id).unwrap();
+            assert_eq!(issue.status, "in-progress");
+            assert!(issue.assigned_to.starts_with("racer-"));
+            // Exactly one status transition per issue: no double claims.
+            assert_eq!(events_for(&conn, id, "status").len(), 1);
+        }
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // --- #171: list_issues has a deterministic base order ---
+
+    #[test]
+    fn list_issues_returns_rows_in_id_order() {
+        let conn = test_conn();
+        let a = add(&conn, "first").id;
+        let b = add(&conn, "second").id;
+        let c = add(&conn, "third").id;
+        // Mixed statuses so a status-index scan would group rows by status
+        // instead of id without the explicit ORDER BY.
+        update_issue_field(&conn, a, "status", "in-progress").unwrap();
+        update_issue_field(&conn, c, "status", "in-progress").unwrap();
+
+        let filter = crate::models::ListFilter {
+            statuses: vec!["in-progress".to_string(), "open".to_string()],
+            include_blocked: true,
+            ..crate::models::ListFilter::default()
+        };
+        let ids: Vec<i64> = list_issues(&conn, &filter)
+            .unwrap()
+            .iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(ids, vec![a, b, c], "base order must be id ascending");
+    }
+
+    // --- #159: parent-cycle guard enforced in the db layer ---
+
+    #[test]
+    fn update_issue_parent_rejects_self_parent() {
+        let conn = test_conn();
+        let issue = add(&conn, "self parent");
+        assert!(
+            matches!(
+                update_issue_parent(&conn, issue.id, Some(issue.id)),
+                Err(ItrError::CycleDetected(_))
+            ),
+            "setting an issue as its own parent must be a cycle error"
+        );
+        assert_eq!(get_issue(&conn, issue.id).unwrap().parent_id, None);
+    }
+
+    #[test]
+    fn update_issue_parent_rejects_descendant_parent() {
+        let conn = test_conn();
+        let root = add(&conn, "root").id;
+        let child = add(&conn, "child").id;
+        let grandchild = add(&conn, "grandchild").id;
+        update_issue_parent(&conn, child, Some(root)).unwrap();
+        update_issue_parent(&conn, grandchild, Some(child)).unwrap();
+
+        assert!(
+            matches!(
+                update_issue_parent(&conn, root, Some(grandchild)),
+                Err(ItrError::CycleDetected(_))
+            ),
+            "a descendant must not become its ancestor's parent"
+        );
+        assert_eq!(get_issue(&conn, root).unwrap().parent_id, None);
+
+        // Legal re-parenting still works after the rejected attempt.
+        update_issue_parent(&conn, grandchild, Some(root)).unwrap();
+        assert_eq!(get_issue(&conn, grandchild).unwrap().parent_id, Some(root));
+    }
+
+    #[test]
+    fn update_issue_parent_rejects_missing_parent() {
+        let conn = test_conn();
+        let issue = add(&conn, "orphan");
+        assert!(matches!(
+            update_issue_parent(&conn, issue.id, Some(999)),
+            Err(ItrError::NotFound(999))
+        ));
+    }
+
+    // --- Nested epic subtrees (recursive CTE) ---
+
+    #[test]
+    fn descendants_are_depth_first_across_nested_epics() {
+        let conn = test_conn();
+        let root = add(&conn, "root").id;
+        let sub = add(&conn, "sub epic").id;
+        let sibling = add(&conn, "sibling").id;
+        let leaf = add(&conn, "leaf").id;
+        update_issue_parent(&conn, sub, Some(root)).unwrap();
+        update_issue_parent(&conn, sibling, Some(root)).unwrap();
+        update_issue_parent(&conn, leaf, Some(sub)).unwrap();
+
+        assert_eq!(
+            get_descendant_ids(&conn, root).unwrap(),
+            vec![(sub, 1), (leaf, 2), (sibling, 1)],
+            "each child is followed by its own subtree before the next sibling"
+        );
+        assert!(get_descendant_ids(&conn, leaf).unwrap().is_empty());
+    }
+
+    #[test]
+    fn subtree_progress_counts_every_level() {
+        let conn = test_conn();
+        let root = add(&conn, "root").id;
+        let sub = add(&conn, "sub").id;
+        let a = add(&conn, "a").id;
+        let b = add(&conn, "b").id;
+        update_issue_parent(&conn, sub, Some(root)).unwrap();
+        update_issue_parent(&conn, a, Some(sub)).unwrap();
+        update_issue_parent(&conn, b, Some(sub)).unwrap();
+        update_issue_field(&conn, a, "status", "done").unwrap();
+        update_issue_field(&conn, b, "status", "in-progress").unwrap();
+
+        let p = subtree_progress(&conn, root).unwrap();
+        assert_eq!(
+            (p.total, p.open, p.in_progress, p.done, p.wontfix),
+            (3, 1, 1, 1, 0)
+        );
+        assert!((p.percent_complete - 33.3).abs() < f64::EPSILON);
+        assert_eq!(
+            subtree_progress(&conn, a).unwrap(),
+            SubtreeProgress::default()
+        );
+    }
+
+    #[test]
+    fn subtree_queries_terminate_on_hand_edited_parent_cycle() {
+        let conn = test_conn();
+        let a = add(&conn, "a").id;
+        let b = add(&conn, "b").id;
+        // Bypass the write-path guard, as a hand-edited database would.
+        conn.execute(
+            "UPDATE issues SET parent_id = ?1 WHERE id = ?2",
+            params![b, a],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE issues SET parent_id = ?1 WHERE id = ?2",
+            params![a, b],
+        )
+        .unwrap();
+
+        assert_eq!(get_descendant_ids(&conn, a).unwrap(), vec![(b, 1), (a, 2)]);
+        assert_eq!(subtree_progress(&conn, a).unwrap().total, 2);
+    }
+
+    #[test]
+    fn list_issues_recursive_parent_includes_grandchildren() {
+        let conn = test_conn();
+        let root = add(&conn, "root").id;
+        let child = add(&conn, "child").id;
+        let grandchild = add(&conn, "grandchild").id;
+        update_issue_parent(&conn, child, Some(root)).unwrap();
+        update_issue_parent(&conn, grandchild, Some(child)).unwrap();
+
+        let ids = |recursive| -> Vec<i64> {
+            list_issues(
+                &conn,
+                &crate::models::ListFilter {
+                    parent_id: Some(root),
+                    recursive,
+                    ..crate::models::ListFilter::default()
+                },
+            )
+            .unwrap()
+            .iter()
+            .map(|i| i.id)
+            .collect()
+        };
+        assert_eq!(ids(false), vec![child]);
+        assert_eq!(ids(true), vec![child, grandchild]);
+    }
+
+    // --- issue_files / issue_tags join tables ---
+
+    fn tagged(conn: &Connection, title: &str, tags: &[&str], files: &[&str]) -> i64 {
+        let tags: Vec<String> = tags.iter().map(|t| (*t).to_string()).collect();
+        let files: Vec<String> = files.iter().map(|f| (*f).to_string()).collect();
+        insert_issue(
+            conn,
+            title,
+            "medium",
+            "task",
+            "",
+            &files,
+            &tags,
+            &[],
+            "",
+            None,
+            "",
+        )
+        .unwrap()
+        .id
+    }
+
+    fn matching(conn: &Connection, filter: crate::models::ListFilter) -> Vec<i64> {
+        list_issues(conn, &filter)
+            .unwrap()
+            .iter()
+            .map(|i| i.id)
+            .collect()
+    }
+
+    #[test]
+    fn list_issues_filters_tags_and_files_via_join_tables() {
+        let conn = test_conn();
+        let a = tagged(&conn, "a", &["api", "db"], &["src/db.rs"]);
+        let b = tagged(&conn, "b", &["api"], &["src/cli.rs"]);
+        tagged(&conn, "c", &["ui"], &[]);
+
+        let by_tags = |tags: &[&str], tag_any: &[&str]| {
+            matching(
+                &conn,
+                crate::models::ListFilter {
+                    tags: tags.iter().map(|t| (*t).to_string()).collect(),
+                    tag_any: tag_any.iter().map(|t| (*t).to_string()).collect(),
+                    ..crate::models::ListFilter::default()
+                },
+            )
+        };
+        assert_eq!(by_tags(&["api"], &[]), vec![a, b]);
+        assert_eq!(by_tags(&["api", "db"], &[]), vec![a]);
+        assert_eq!(by_tags(&[], &["db", "cli"]), vec![a]);
+        let files = matching(
+            &conn,
+            crate::models::ListFilter {
+                files: vec!["src/cli.rs".to_string()],
+                ..crate::models::ListFilter::default()
+            },
+        );
+        assert_eq!(files, vec![b]);
+    }
+
+    #[test]
+    fn list_issues_filters_skills_and_blocked_in_sql() {
+        let conn = test_conn();
+        let add = |title: &str, skills: &[&str]| {
+            let skills: Vec<String> = skills.iter().map(|s| (*s).to_string()).collect();
+            insert_issue(
+                &conn,
+                title,
+                "medium",
+                "task",
+                "",
+                &[],
+                &[],
+                &skills,
+                "",
+                None,
+                "",
+            )
+            .unwrap()
+            .id
+        };
+        let a = add("a", &["rust", "sql"]);
+        let b = add("b", &["rust"]);
+        let c = add("c", &[]);
+        conn.execute(
+            "UPDATE issues SET skills = 'not json' WHERE id = ?1",
+            params![c],
+        )
+        .unwrap();
+        add_dependency(&conn, a, b).unwrap();
+
+        let list = |skills: &[&str], blocked_only: bool, include_blocked: bool| {
+            matching(
+                &conn,
+                crate::models::ListFilter {
+                    skills: skills.iter().map(|s| (*s).to_string()).collect(),
+                    blocked_only,
+                    include_blocked,
+                    ..crate::models::ListFilter::default()
+                },
+            )
+        };
+        assert_eq!(list(&["rust"], false, true), vec![a, b]);
+        assert_eq!(list(&["rust", "sql"], false, true), vec![a]);
+        assert_eq!(list(&[], false, false), vec![a, c]);
+        assert_eq!(list(&[], true, false), vec![b]);
+
+        // A terminal blocker no longer blocks.
+        update_issue_field(&conn, a, "status", "done").unwrap();
+        assert_eq!(list(&[], false, false), vec![b, c]);
+        assert!(list(&[], true, false).is_empty());
+    }
+
+    #[test]
+    fn join_tables_follow_updates_replaces_and_deletes() {
+        let conn = test_conn();
+        let id = tagged(&conn, "a", &["old"], &["a.rs"]);
+        update_issue_field(&conn, id, "tags", r#"["new"]"#).unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO issues (id, title, files) VALUES (?1, 'a', '[\"b.rs\"]')",
+            params![id],
+        )
+        .unwrap();
+        let rows = |sql: &str| -> Vec<String> {
+            conn.prepare(sql)
+                .unwrap()
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+        assert!(rows("SELECT tag FROM issue_tags").is_empty());
+        assert_eq!(rows("SELECT path FROM issue_files"), vec!["b.rs"]);
+
+        conn.execute("DELETE FROM issues WHERE id = ?1", params![id])
+            .unwrap();
+        assert!(rows("SELECT path FROM issue_files").is_empty());
+    }
+
+    #[test]
+    fn migrate_list_tables_backfills_existing_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE issues (id INTEGER PRIMARY KEY AUTOINCREMENT, title TEXT NOT NULL,
+                 files TEXT NOT NULL DEFAULT '[]', tags TEXT NOT NULL DEFAULT '[]');
+             INSERT INTO issues (title, files, tags) VALUES ('legacy', '[\"x.rs\"]', '[\"t1\",\"t2\"]');
+             INSERT INTO issues (title, tags) VALUES ('broken', 'not json');",
+        )
+        .unwrap();
+        migrate_add_list_tables(&conn).unwrap();
+        let tags: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM issue_tags WHERE issue_id = 1",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        let files: i64 = conn
+            .query_row("SELECT COUNT(*) FROM issue_files", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!((tags, files), (2, 1));
+    }
+
+    #[test]
+    fn migrate_review_status_widens_the_status_check() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE issues (id INTEGER PRIMARY KEY AUTOINCREMENT, title TEXT NOT NULL,
+                 status TEXT NOT NULL DEFAULT 'open'
+                 CHECK (status IN ('open', 'in-progress', 'done', 'wontfix')));
+             INSERT INTO issues (title) VALUES ('legacy');",
+        )
+        .unwrap();
+        assert!(conn
+            .execute("UPDATE issues SET status = 'review'", [])
+            .is_err());
+        migrate_add_review_status(&conn).unwrap();
+        migrate_add_review_status(&conn).unwrap();
+        conn.execute("UPDATE issues SET status = 'review'", [])
+            .unwrap();
+        assert!(conn
+            .execute("UPDATE issues SET status = 'bogus'", [])
+            .is_err());
+    }
+
+    // --- #186: unrelate is direction-aware and type-aware ---
+
+    #[test]
+    fn remove_relation_matches_reverse_direction() {
+        let conn = test_conn();
+        let a = add(&conn, "rel a").id;
+        let b = add(&conn, "rel b").id;
+        add_relation(&conn, a, b, "related").unwrap();
+
+        // The pair is passed in the opposite direction from how it is stored;
+        // get_relations shows both sides, so removal must match both too.
+        let removed = remove_relation(&conn, b, a, None).unwrap();
+        assert_eq!(removed.len(), 1, "reverse-direction pair must match");
+        assert_eq!(removed[0].source_id, a);
+        assert_eq!(removed[0].target_id, b);
+        assert_eq!(removed[0].relation_type, "related");
+        assert!(get_relations(&conn, a).unwrap().is_empty());
+    }
+
+    #[test]
+    fn remove_relation_type_filter_leaves_other_types_intact() {
+        let conn = test_conn();
+        let a = add(&conn, "typed a").id;
+        let b = add(&conn, "typed b").id;
+        add_relation(&conn, a, b, "related").unwrap();
+        add_relation(&conn, a, b, "duplicate").unwrap();
+
+        let removed = remove_relation(&conn, a, b, Some("duplicate")).unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].relation_type, "duplicate");
+
+        let remaining = get_relations(&conn, a).unwrap();
+        assert_eq!(remaining.len(), 1, "other typed links must survive");
+        assert_eq!(remaining[0].relation_type, "related");
+    }
+
+    #[test]
+    fn remove_relation_without_filter_reports_every_removed_link() {
+        let conn = test_conn();
+        let a = add(&conn, "multi a").id;
+        let b = add(&conn, "multi b").id;
+        add_relation(&conn, a, b, "related").unwrap();
+        add_relation(&conn, b, a, "duplicate").unwrap();
+
+        let removed = remove_relation(&conn, a, b, None).unwrap();
+        let mut types: Vec<&str> = removed.iter().map(|r| r.relation_type.as_str()).collect();
+        types.sort_unstable();
+        assert_eq!(
+            types,
+            vec!["duplicate", "related"],
+            "unfiltered removal must report every typed link in both directions"
+        );
+        assert!(get_relations(&conn, a).unwrap().is_empty());
+    }
+
+    #[test]
+    fn remove_relation_no_match_returns_empty() {
+        let conn = test_conn();
+        let a = add(&conn, "lonely a").id;
+        let b = add(&conn, "lonely b").id;
+        assert!(remove_relation(&conn, a, b, None).unwrap().is_empty());
+
+        add_relation(&conn, a, b, "related").unwrap();
+        assert!(
+            remove_relation(&conn, a, b, Some("duplicate"))
+                .unwrap()
+                .is_empty(),
+            "type filter that matches nothing must remove nothing"
+        );
+        assert_eq!(get_relations(&conn, a).unwrap().len(), 1);
+
+        assert!(matches!(
+            remove_relation(&conn, a, 999, None),
+            Err(ItrError::NotFound(999))
+        ));
+    }
+
+    // --- #191: remove_dependency reports whether an edge existed ---
+
+    #[test]
+    fn remove_dependency_reports_whether_an_edge_existed() {
+        let conn = test_conn();
+        let blocker = add(&conn, "blocker").id;
+        let blocked = add(&conn, "blocked").id;
+
+        assert!(
+            !remove_dependency(&conn, blocker, blocked).unwrap(),
+            "removing a nonexistent edge must report a no-op"
+        );
+
+        add_dependency(&conn, blocker, blocked).unwrap();
+        assert!(remove_dependency(&conn, blocker, blocked).unwrap());
+        assert!(
+            !remove_dependency(&conn, blocker, blocked).unwrap(),
+            "second removal of the same edge must report a no-op"
+        );
+    }
+
+    // --- #160: explicit DB overrides are validated, never auto-created ---
+
+    fn missing_db_path(tag: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "itr-no-such-{tag}-{}-{}.db",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    fn nonexistent_cli_override_is_rejected_without_creating_a_file() {
+        let path = missing_db_path("cli");
+        let resolved = resolve_override_db(None, Some(path.to_str().unwrap()));
+        assert!(
+            matches!(resolved, Some(Err(ItrError::NoDatabase))),
+            "a missing --db path must be NO_DATABASE, got {resolved:?}"
+        );
+        assert!(
+            std::fs::metadata(&path).is_err(),
+            "the failed resolution must not leave a junk file on disk"
+        );
+    }
+
+    #[test]
+    fn nonexistent_env_override_is_rejected_without_creating_a_file() {
+        let path = missing_db_path("env");
+        let resolved = resolve_override_db(Some(path.to_str().unwrap()), None);
+        assert!(
+            matches!(resolved, Some(Err(ItrError::NoDatabase))),
+            "a missing ITR_DB_PATH must be NO_DATABASE, got {resolved:?}"
+        );
+        assert!(
+            std::fs::metadata(&path).is_err(),
+            "the failed resolution must not leave a junk file on disk"
+        );
+    }
+
+    #[test]
+    fn empty_env_override_falls_through_to_walk_up() {
+        // Empty ITR_DB_PATH is "unset": resolution must defer (None), not
+        // open a SQLite temp database.
+        assert!(resolve_override_db(Some(""), None).is_none());
+        // ...and an empty env var must not mask a real --db override.
+        let dir = std::env::temp_dir().join(format!(
+            "itr-empty-env-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join(".itr.db");
+        drop(init_db(&db_path).unwrap());
+        let resolved = resolve_override_db(Some(""), Some(db_path.to_str().unwrap()));
+        assert!(matches!(resolved, Some(Ok(p)) if p == db_path));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn open_db_applies_configured_pragmas_and_skips_bad_ones() {
+        let dir = std::env::temp_dir().join(format!(
+            "itr-pragmas-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join(".itr.db");
+        let conn = init_db(&db_path).unwrap();
+        config_set(&conn, "db.busy_timeout", "1234").unwrap();
+        config_set(&conn, "db.synchronous", "FULL").unwrap();
+        config_set(&conn, "db.cache_size", "not a number").unwrap();
+        drop(conn);
+
+        let conn = open_db(&db_path).unwrap();
+        let pragma = |name: &str| -> i64 {
+            conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(pragma("busy_timeout"), 1234);
+        assert_eq!(pragma("synchronous"), 2, "FULL");
+        assert_eq!(pragma("cache_size"), -2000, "bad value keeps the default");
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            validate_db_setting("db.synchronous", " normal ").unwrap(),
+            "NORMAL"
+        );
+        assert!(validate_db_setting("db.synchronous", "OFF; DROP TABLE issues").is_err());
+        assert!(validate_db_setting("db.busy_timeout", "-5").is_err());
+        assert!(validate_db_setting("db.journal_mode", "DELETE").is_err());
+    }
+
+    #[test]
+    fn config_defaults_sit_under_the_database_config() {
+        let conn = open_test_db();
+        assert_eq!(config_get(&conn, "urgency.age").unwrap(), None);
+
+        let defaults = BTreeMap::from([
+            ("urgency.age".to_string(), "3".to_string()),
+            ("urgency.blocking".to_string(), "9".to_string()),
+            ("db.busy_timeout".to_string(), "4321".to_string()),
+        ]);
+        config_set(&conn, "urgency.blocking", "7").unwrap();
+        set_config_defaults(&conn, &defaults).unwrap();
+        assert_eq!(
+            config_get(&conn, "urgency.age").unwrap().as_deref(),
+            Some("3")
+        );
+        assert_eq!(
+            config_get(&conn, "urgency.blocking").unwrap().as_deref(),
+            Some("7"),
+            "the database's own value wins"
+        );
+        let timeout: i64 = conn
+            .query_row("PRAGMA busy_timeout", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(timeout, 4321);
+        assert!(
+            !config_list(&conn)
+                .unwrap()
+                .iter()
+                .any(|(k, _)| k == "urgency.age"),
+            "defaults are never stored"
+        );
+
+        // Installing again replaces the layer.
+        set_config_defaults(&conn, &BTreeMap::new()).unwrap();
+        assert_eq!(config_get(&conn, "urgency.age").unwrap(), None);
+    }
+
+    #[test]
+    fn begin_write_retries_until_the_lock_is_released() {
+        let dir = std::env::temp_dir().join(format!(
+            "itr-busy-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join(".itr.db");
+        drop(init_db(&db_path).unwrap());
+        let contender = open_db(&db_path).unwrap();
+        contender.execute_batch("PRAGMA busy_timeout=0;").unwrap();
+
+        // No retries: the held lock surfaces immediately as a busy error.
+        config_set(&contender, "db.busy_retries", "0").unwrap();
+        let holder = open_db(&db_path).unwrap();
+        let held = begin_write(&holder).unwrap();
+        let err = begin_write(&contender).unwrap_err();
+        assert!(matches!(&err, ItrError::Db(e) if is_busy(e)), "{}", err);
+        held.rollback().unwrap();
+
+        // With retries, a lock released mid-backoff is acquired.
+        config_set(&contender, "db.busy_retries", "10").unwrap();
+        config_set(&contender, "db.busy_backoff_ms", "5").unwrap();
+        drop(holder);
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let holder_path = db_path.clone();
+        let release = std::thread::spawn(move || {
+            let holder = open_db(&holder_path).unwrap();
+            let held = begin_write(&holder).unwrap();
+            locked_tx.send(()).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(30));
+            held.commit().unwrap();
+        });
+        locked_rx.recv().unwrap();
+        let tx = begin_write(&contender).unwrap();
+        tx.commit().unwrap();
+        release.join().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn empty_cli_override_falls_through() {
+        // Empty --db is "unset": with no env either, resolution defers (None)
+        // to the walk-up finder rather than erroring or opening a temp db.
+        assert!(resolve_override_db(None, Some("")).is_none());
+        assert!(resolve_override_db(Some(""), Some("")).is_none());
+    }
+
+    #[test]
+    fn cli_override_wins_over_env() {
+        // P2: an explicit --db must beat an ambient ITR_DB_PATH so a control
+        // plane can keep env on its own tracker and target another per call.
+        let dir = std::env::temp_dir().join(format!(
+            "itr-cli-wins-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let a = dir.join("A");
+        let b = dir.join("B");
+        std::fs::create_dir_all(&a).unwrap();
+        std::fs::create_dir_all(&b).unwrap();
+        let a_db = a.join(".itr.db");
+        let b_db = b.join(".itr.db");
+        drop(init_db(&a_db).unwrap());
+        drop(init_db(&b_db).unwrap());
+
+        let resolved =
+            resolve_override_db(Some(a_db.to_str().unwrap()), Some(b_db.to_str().unwrap()));
+        assert!(
+            matches!(resolved, Some(Ok(ref p)) if *p == b_db),
+            "--db must win over ITR_DB_PATH, got {resolved:?}"
+        );
+        // Empty --db still yields the env, not the walk-up.
+        let env_wins = resolve_override_db(Some(a_db.to_str().unwrap()), Some(""));
+        assert!(matches!(env_wins, Some(Ok(ref p)) if *p == a_db));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn directory_override_resolves_to_itr_db_inside() {
+        // P1: a directory address opens <dir>/.itr.db; a file address is
+        // used verbatim; an empty dir (no .itr.db) is rejected without
+        // creating a file.
+        let dir = std::env::temp_dir().join(format!(
+            "itr-dir-override-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join(".itr.db");
+        drop(init_db(&db_path).unwrap());
+
+        // Directory form → <dir>/.itr.db
+        let from_dir = resolve_override_db(None, Some(dir.to_str().unwrap()));
+        assert!(matches!(from_dir, Some(Ok(ref p)) if *p == db_path));
+        // db_path_for is the shared address→file mapping.
+        assert_eq!(db_path_for(dir.to_str().unwrap()), db_path);
+        assert_eq!(
+            db_path_for(db_path.to_str().unwrap()),
+            db_path,
+            "a file path is used verbatim"
+        );
+
+        // A sibling directory with no .itr.db is rejected, nothing created.
+        let empty = dir.join("empty");
+        std::fs::create_dir_all(&empty).unwrap();
+        let rejected = resolve_override_db(None, Some(empty.to_str().unwrap()));
+        assert!(matches!(rejected, Some(Err(ItrError::NoDatabase))));
+        assert!(
+            std::fs::metadata(empty.join(".itr.db")).is_err(),
+            "a directory with no .itr.db must not have one created"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn existing_override_path_resolves() {
+        let dir = std::env::temp_dir().join(format!(
+            "itr-existing-override-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join(".itr.db");
+        drop(init_db(&db_path).unwrap());
+
+        let from_env = resolve_override_db(Some(db_path.to_str().unwrap()), None);
+        assert!(matches!(from_env, Some(Ok(ref p)) if *p == db_path));
+        let from_cli = resolve_override_db(None, Some(db_path.to_str().unwrap()));
+        assert!(matches!(from_cli, Some(Ok(ref p)) if *p == db_path));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // --- #170: event filters apply in SQL before the limit ---
+
+    fn insert_event_at(conn: &Connection, issue_id: i64, agent: &str, created_at: &str) {
+        conn.execute(
+            "INSERT INTO events (issue_id, field, old_value, new_value, agent, created_at)
+             VALUES (?1, 'status', 'open', 'in-progress', ?2, ?3)",
+            params![issue_id, agent, created_at],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn get_events_filtered_applies_since_to_issue_scope() {
+        let conn = test_conn();
+        let id = add(&conn, "since target").id;
+        insert_event_at(&conn, id, "alice", "2026-01-01T00:00:00Z");
+        insert_event_at(&conn, id, "alice", "2026-03-01T00:00:00Z");
+
+        let events =
+            get_events_filtered(&conn, Some(id), 50, Some("2026-02-01T00:00:00Z"), None).unwrap();
+        assert_eq!(events.len(), 1, "--since must filter issue-scoped events");
+        assert_eq!(events[0].created_at, "2026-03-01T00:00:00Z");
+
+        let future =
+            get_events_filtered(&conn, Some(id), 50, Some("2099-01-01T00:00:00Z"), None).unwrap();
+        assert!(future.is_empty(), "a future --since must yield no events");
+    }
+
+    #[test]
+    fn get_events_filtered_applies_agent_before_limit() {
+        let conn = test_conn();
+        let id = add(&conn, "agent target").id;
+        // alice's event is older than the 3 newest events overall.
+        insert_event_at(&conn, id, "alice", "2026-01-01T00:00:00Z");
+        insert_event_at(&conn, id, "bob", "2026-01-02T00:00:00Z");
+        insert_event_at(&conn, id, "bob", "2026-01-03T00:00:00Z");
+        insert_event_at(&conn, id, "bob", "2026-01-04T00:00:00Z");
+
+        let events = get_events_filtered(&conn, None, 3, None, Some("alice")).unwrap();
+        assert_eq!(
+            events.len(),
+            1,
+            "agent filter must run before LIMIT, not after"
+        );
+        assert_eq!(events[0].agent, "alice");
+    }
+
+    #[test]
+    fn get_events_filtered_limits_to_newest_matches() {
+        let conn = test_conn();
+        let id = add(&conn, "limit target").id;
+        insert_event_at(&conn, id, "alice", "2026-01-01T00:00:00Z");
+        insert_event_at(&conn, id, "alice", "2026-01-02T00:00:00Z");
+        insert_event_at(&conn, id, "alice", "2026-01-03T00:00:00Z");
+
+        let events = get_events_filtered(&conn, Some(id), 2, None, None).unwrap();
+        let stamps: Vec<&str> = events.iter().map(|e| e.created_at.as_str()).collect();
+        assert_eq!(
+            stamps,
+            vec!["2026-01-03T00:00:00Z", "2026-01-02T00:00:00Z"],
+            "limit must keep the newest matches, newest first"
+        );
+    }
+
+    #[test]
+    fn agent_name_setting_attributes_events_when_itr_agent_is_unset() {
+        if env::var_os(AGENT_ENV).is_some() {
+            return; // the environment wins; nothing to observe here
+        }
+        let conn = test_conn();
+        let id = add(&conn, "attributed").id;
+        config_set(&conn, AGENT_KEY, " carol ").unwrap();
+        assert_eq!(current_agent(&conn), "carol");
+
+        record_event(&conn, id, "status", "open", "in-progress").unwrap();
+        let events = get_events_for_issue(&conn, id).unwrap();
+        assert_eq!(events.last().unwrap().agent, "carol");
+    }
+
+    #[test]
+    fn load_issue_relations_matches_per_issue_queries() {
+        let conn = open_test_db();
+        let add = |title: &str| {
+            insert_issue(
+                &conn,
+                title,
+                "medium",
+                "task",
+                "",
+                &[],
+                &[],
+                &[],
+                "",
+                None,
+                "",
+            )
+            .unwrap()
+            .id
+        };
+        let (a, b, c, d) = (add("a"), add("b"), add("c"), add("d"));
+        add_dependency(&conn, a, b).unwrap();
+        add_dependency(&conn, c, b).unwrap();
+        add_dependency(&conn, b, d).unwrap();
+        update_issue_field(&conn, c, "status", "done").unwrap();
+        add_note(&conn, b, "one", "").unwrap();
+        add_note(&conn, b, "two", "").unwrap();
+
+        let rel = load_issue_relations(&conn).unwrap();
+        for id in [a, b, c, d] {
+            let mut blockers = rel.blockers(id);
+            let mut expected = get_blockers(&conn, id).unwrap();
+            blockers.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(blockers, expected, "blockers of #{}", id);
+            assert_eq!(rel.blocking(id), get_blocking(&conn, id).unwrap());
+            assert_eq!(
+                rel.urgency_inputs(id),
+                UrgencyInputs {
+                    blocking: blocks_active_issues(&conn, id).unwrap(),
+                    blocked: is_blocked(&conn, id).unwrap(),
+                    note_count: count_notes(&conn, id).unwrap(),
+                },
+                "inputs of #{}",
+                id
+            );
+        }
+        assert!(rel.is_blocked(b) && !rel.is_blocked(a));
+    }
+
+    #[test]
+    fn references_follow_context_and_notes() {
+        let conn = open_test_db();
+        let add = |title: &str, context: &str| {
+            insert_issue(
+                &conn,
+                title,
+                "medium",
+                "task",
+                context,
+                &[],
+                &[],
+                &[],
+                "",
+                None,
+                "",
+            )
+            .unwrap()
+            .id
+        };
+        let a = add("a", "");
+        let b = add("b", &format!("split out of #{}; see #{} and #99", a, a));
+        assert_eq!(referenced_by(&conn, a).unwrap(), vec![b]);
+
+        let c = add("c", "");
+        let note = add_note(&conn, c, &format!("same cause as #{} and #{}", a, c), "").unwrap();
+        assert_eq!(referenced_by(&conn, a).unwrap(), vec![b, c]);
+        assert!(
+            referenced_by(&conn, c).unwrap().is_empty(),
+            "self-mentions are dropped"
+        );
+
+        update_issue_field(&conn, b, "context", "unrelated").unwrap();
+        update_note(&conn, note.id, &format!("actually #{}", b)).unwrap();
+        assert!(referenced_by(&conn, a).unwrap().is_empty());
+        assert_eq!(referenced_by(&conn, b).unwrap(), vec![c]);
+        delete_note(&conn, note.id).unwrap();
+        assert!(referenced_by(&conn, b).unwrap().is_empty());
+
+        // A mention of an issue that doesn't exist yet counts once it does.
+        assert_eq!(add("d", ""), 4);
+        let mentioner = add("e", "follow-up in #6");
+        let later = add("f", "");
+        assert_eq!(referenced_by(&conn, later).unwrap(), vec![mentioner]);
+
+        conn.execute("DELETE FROM issue_references", []).unwrap();
+        refresh_all_references(&conn).unwrap();
+        assert_eq!(referenced_by(&conn, later).unwrap(), vec![mentioner]);
+    }
+
+    // --- Encrypted databases ---
+
+    #[test]
+    fn encryption_is_detected_from_the_file_header() {
+        let dir = missing_db_path("encrypted");
+        std::fs::create_dir_all(&dir).unwrap();
+        let plain = dir.join("plain.db");
+        drop(init_db(&plain).unwrap());
+        assert!(!is_encrypted(&plain));
+        assert!(!is_encrypted(&dir.join("absent.db")));
+
+        // SQLCipher pages start with a random salt, never the SQLite magic.
+        let cipher = dir.join("cipher.db");
+        std::fs::write(&cipher, [0x5a_u8; 4096]).unwrap();
+        assert!(is_encrypted(&cipher));
+        if env::var_os(KEY_ENV).is_none() && env::var_os(KEY_CMD_ENV).is_none() {
+            assert!(matches!(open_db(&cipher), Err(ItrError::Encryption(_))));
+            let fresh = dir.join("fresh.db");
+            assert!(matches!(
+                init_encrypted_db(&fresh),
+                Err(ItrError::Encryption(_))
+            ));
+            assert!(!fresh.exists(), "a failed --encrypt must not leave a file");
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::db;
+use crate::error::ItrError;
+use crate::models::{Issue, IssueDetail, IssueSummary};
+use crate::urgency::{
+    compute_urgency, compute_urgency_from_inputs, compute_urgency_with_breakdown, UrgencyConfig,
+};
+use rusqlite::Connection;
+use std::cmp::Ordering;
+
+/// Build an `IssueSummary` for a single issue: compute urgency, resolve blockers.
+///
+/// Borrowing wrapper around [`build_issue_summary_owned`]. Prefer the owned
+/// variant when the caller owns the [`Issue`] (e.g. iterating `Vec<Issue>` with
+/// `into_iter()`) — it avoids cloning every string/vec field. This wrapper
+/// exists for callers that only have a borrow (e.g. `summary.rs` which iterates
+/// a slice and reuses each `Issue` afterwards).
+pub fn build_issue_summary(
+    conn: &Connection,
+    issue: &Issue,
+    config: &UrgencyConfig,
+) -> IssueSummary {
+    build_issue_summary_owned(conn, issue.clone(), config)
+}
+
+/// Owned-input variant of [`build_issue_summary`].
+///
+/// Moves the [`Issue`]'s string and vec fields directly into the resulting
+/// [`IssueSummary`], avoiding the per-field `.clone()` storm that the borrowing
+/// wrapper incurs. Use this from any caller that has ownership of the `Issue`.
+pub fn build_issue_summary_owned(
+    conn: &Connection,
+    issue: Issue,
+    config: &UrgencyConfig,
+) -> IssueSummary {
+    let urg = compute_urgency(&issue, config, conn);
+    let blocked_by = db::get_blockers(conn, issue.id).unwrap_or_default();
+    let blocks = db::get_blocking(conn, issue.id).unwrap_or_default();
+    let is_blocked = db::is_blocked(conn, issue.id).unwrap_or(false);
+    assemble_summary(issue, urg, is_blocked, blocked_by, blocks)
+}
+
+/// Summaries for many issues at once. Dependency edges and note counts are
+/// loaded for the whole database in a few aggregate queries (see
+/// [`db::load_issue_relations`]) instead of several queries per issue, which
+/// is what keeps `itr list` fast on large databases. A failed load degrades
+/// to the per-issue path with a `REVIEW:` note.
+pub fn build_issue_summaries(
+    conn: &Connection,
+    issues: Vec<Issue>,
+    config: &UrgencyConfig,
+) -> Vec<IssueSummary> {
+    let relations = match db::load_issue_relations(conn) {
+        Ok(r) => r,
+        Err(e) => {
+            crate::util::diagnostic(&format!(
+                "REVIEW: batch dependency load failed (scoring issues one by one): {}",
+                e
+            ));
+            return issues
+                .into_iter()
+                .map(|i| build_issue_summary_owned(conn, i, config))
+                .collect();
+        }
+    };
+    issues
+        .into_iter()
+        .map(|issue| {
+            let (urg, _) =
+                compute_urgency_from_inputs(&issue, config, relations.urgency_inputs(issue.id));
+            let is_blocked = relations.is_blocked(issue.id);
+            let blocked_by = relations.blockers(issue.id);
+            let blocks = relations.blocking(issue.id);
+            assemble_summary(issue, urg, is_blocked, blocked_by, blocks)
+        })
+        .collect()
+}
+
+fn assemble_summary(
+    issue: Issue,
+    urgency: f64,
+    is_blocked: bool,
+    blocked_by: Vec<i64>,
+    blocks: Vec<i64>,
+) -> IssueSummary {
+    IssueSummary {
+        id: issue.id,
+        title: issue.title,
+        status: issue.status,
+        priority: issue.priority,
+        kind: issue.kind,
+        urgency,
+        is_blocked,
+        blocked_by,
+        blocks,
+        tags: issue.tags,
+        files: issue.files,
+        skills: issue.skills,
+        acceptance: issue.acceptance,
+        context: issue.context,
+        parent_id: issue.parent_id,
+        close_reason: issue.close_reason,
+        assigned_to: issue.assigned_to,
+        branch: issue.branch,
+        project: issue.project,
+        severity: issue.severity,
+        component: issue.component,
+        affects_version: issue.affects_version,
+        fixed_in_version: issue.fixed_in_version,
+        created_at: issue.created_at,
+        updated_at: issue.updated_at,
+    }
+}
+
+/// Summaries for every descendant of `root_id`, nested epics included, in
+/// the depth-first order of [`db::get_descendant_ids`]. Each summary keeps its
+/// `parent_id`, so consumers can rebuild the nesting from the flat list.
+/// Returns `None` for a childless issue, matching the `children` contract.
+pub fn build_subtree_summaries(
+    conn: &Connection,
+    root_id: i64,
+    config: &UrgencyConfig,
+) -> Result<Option<Vec<IssueSummary>>, ItrError> {
+    let mut issues = Vec::new();
+    for (id, _depth) in db::get_descendant_ids(conn, root_id)? {
+        issues.push(db::get_issue(conn, id)?);
+    }
+    let summaries = build_issue_summaries(conn, issues, config);
+    Ok(if summaries.is_empty() {
+        None
+    } else {
+        Some(summaries)
+    })
+}
+
+/// Sort by urgency descending (highest first).
+pub fn sort_by_urgency_desc<T: HasUrgency>(items: &mut [T]) {
+    items.sort_by(|a, b| {
+        b.urgency_val()
+            .partial_cmp(&a.urgency_val())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+/// Trait for types that have an urgency score.
+pub trait HasUrgency {
+    fn urgency_val(&self) -> f64;
+}
+
+impl HasUrgency for IssueSummary {
+    fn urgency_val(&self) -> f64 {
+        self.urgency
+    }
+}
+
+impl HasUrgency for crate::models::SearchResult {
+    fn urgency_val(&self) -> f64 {
+        self.urgency
+    }
+}
+
+/// Build an `IssueDetail` for a single issue using standard DB lookups.
+/// `children` and `relations` default to empty — callers that need them set
+/// the fields on the returned struct afterward, or use the `get` handler directly.
+pub fn build_issue_detail(
+    conn: &Connection,
+    issue: Issue,
+    config: &UrgencyConfig,
+) -> Result<IssueDetail, ItrError> {
+    let (urgency, urgency_breakdown) = compute_urgency_with_breakdown(&issue, config, conn);
+    let blocked_by = db::get_blockers(conn, issue.id)?;
+    let blocks = db::get_blocking(conn, issue.id)?;
+    let is_blocked = db::is_blocked(conn, issue.id)?;
+    let notes = db::get_notes(conn, issue.id)?;
+    let linked: Vec<i64> = blocked_by.iter().chain(&blocks).copied().collect();
+    let qualified_ids = db::cross_project_ids(conn, &issue.project, &linked)?;
+    let key = db::issue_key(conn, issue.id)?;
+    let external_blockers = db::external_blockers(conn, issue.id)?;
+    let repro = db::issue_repro(conn, issue.id)?;
+    Ok(IssueDetail {
+        issue,
+        urgency,
+        blocked_by,
+        blocks,
+        is_blocked,
+        notes,
+        urgency_breakdown: Some(urgency_breakdown),
+        children: None,
+        progress: None,
+        relations: vec![],
+        qualified_ids,
+        notes_omitted: None,
+        blocked_by_issues: vec![],
+        key,
+        commits: 0,
+        external_blockers,
+        repro,
+        referenced_by: vec![],
+    })
+}
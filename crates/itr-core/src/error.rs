@@ -0,0 +1,124 @@
+#[derive(Debug, thiserror::Error)]
+pub enum ItrError {
+    #[error("Issue {0} not found")]
+    NotFound(i64),
+
+    #[error("Cycle detected: {0}")]
+    CycleDetected(String),
+
+    #[error(
+        "Invalid value for {field}: '{value}'{}. Valid: {valid}",
+        crate::util::did_you_mean(value, valid)
+    )]
+    InvalidValue {
+        field: String,
+        value: String,
+        valid: String,
+    },
+
+    #[error("No .itr.db found. Run 'itr init' to create one.")]
+    NoDatabase,
+
+    #[cfg(feature = "sqlite")]
+    #[error("Database error: {0}")]
+    Db(#[from] rusqlite::Error),
+
+    #[error("JSON parse error: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Upgrade failed: {0}")]
+    UpgradeFailed(String),
+
+    #[error("At least one filter is required for bulk operations")]
+    NoFilters,
+
+    #[error("Aborted: {0}")]
+    Aborted(String),
+
+    #[error("Sync failed: {0}")]
+    SyncFailed(String),
+
+    #[error("Encrypted database: {0}")]
+    Encryption(String),
+
+    #[error(
+        "Ambiguous issue reference '{query}': matches {}",
+        format_candidates(candidates)
+    )]
+    Ambiguous {
+        query: String,
+        candidates: Vec<(i64, String)>,
+    },
+}
+
+fn format_candidates(candidates: &[(i64, String)]) -> String {
+    candidates
+        .iter()
+        .map(|(id, title)| format!("#{} {}", id, title))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl ItrError {
+    /// Process exit status for this error: one per class a script may want to
+    /// branch on, and 1 for runtime failures (database, IO, sync, upgrade).
+    /// 2 is left to clap's usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ItrError::NotFound(_) => 3,
+            ItrError::CycleDetected(_) => 4,
+            ItrError::InvalidValue { .. } => 5,
+            ItrError::NoFilters => 5,
+            ItrError::Parse(_) => 5,
+            ItrError::Ambiguous { .. } => 6,
+            ItrError::NoDatabase => 7,
+            ItrError::Aborted(_) => 8,
+            #[cfg(feature = "sqlite")]
+            ItrError::Db(_) => 1,
+            ItrError::Io(_) => 1,
+            ItrError::UpgradeFailed(_) => 1,
+            ItrError::SyncFailed(_) => 1,
+            ItrError::Encryption(_) => 1,
+        }
+    }
+
+    /// The value an `INVALID_VALUE` error's input was probably meant to be,
+    /// when `valid` lists a close one (also shown in the message).
+    pub fn suggestion(&self) -> Option<&str> {
+        match self {
+            ItrError::InvalidValue { value, valid, .. } => crate::util::suggest_valid(value, valid),
+            _ => None,
+        }
+    }
+
+    /// ` (did you mean '<value>'?)` for [`Self::suggestion`], or an empty
+    /// string; for `REVIEW:` notes built from a validation error.
+    pub fn did_you_mean(&self) -> String {
+        match self {
+            ItrError::InvalidValue { value, valid, .. } => crate::util::did_you_mean(value, valid),
+            _ => String::new(),
+        }
+    }
+
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            ItrError::NotFound(_) => "NOT_FOUND",
+            ItrError::CycleDetected(_) => "CYCLE_DETECTED",
+            ItrError::InvalidValue { .. } => "INVALID_VALUE",
+            ItrError::NoDatabase => "NO_DATABASE",
+            #[cfg(feature = "sqlite")]
+            ItrError::Db(_) => "DB_ERROR",
+            ItrError::Parse(_) => "PARSE_ERROR",
+            ItrError::Io(_) => "IO_ERROR",
+            ItrError::UpgradeFailed(_) => "UPGRADE_FAILED",
+            ItrError::NoFilters => "NO_FILTERS",
+            ItrError::Aborted(_) => "ABORTED",
+            ItrError::SyncFailed(_) => "SYNC_FAILED",
+            ItrError::Encryption(_) => "ENCRYPTION_FAILED",
+            ItrError::Ambiguous { .. } => "AMBIGUOUS",
+        }
+    }
+}
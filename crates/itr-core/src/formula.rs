@@ -0,0 +1,317 @@
+//! Tiny arithmetic expression language for `urgency.formula`.
+//!
+//! Grammar (usual precedence, left-associative):
+//!
+//! ```text
+//! expr    := term (('+' | '-') term)*
+//! term    := unary (('*' | '/') unary)*
+//! unary   := '-' unary | primary
+//! primary := NUMBER | NAME | NAME '(' expr (',' expr)* ')' | '(' expr ')'
+//! ```
+//!
+//! Names must be one of [`VARIABLES`]; functions are `min`, `max`, `abs`, and
+//! `clamp(x, lo, hi)`. Everything is checked at parse time, so a formula that
+//! parses always evaluates. Division by zero yields `0` rather than an
+//! infinity that would poison sorting.
+
+/// Names a formula may reference. The first nine are the weighted urgency
+/// components (the same values `urgency_breakdown` reports, `0` when the
+/// component does not apply); the rest are raw inputs.
+pub const VARIABLES: &[&str] = &[
+    "priority",
+    "severity",
+    "kind",
+    "blocking",
+    "blocked",
+    "age",
+    "in_progress",
+    "has_acceptance",
+    "notes",
+    "age_days",
+    "note_count",
+];
+
+const FUNCTIONS: &[(&str, usize)] = &[("min", 0), ("max", 0), ("abs", 1), ("clamp", 3)];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Num(f64),
+    Var(String),
+    Neg(Box<Expr>),
+    Bin(char, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+/// A parsed `urgency.formula`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formula {
+    expr: Expr,
+}
+
+impl Formula {
+    /// Parse `src`, returning a human-readable message on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```text
+    /// use itr::formula::Formula;
+    /// let f = Formula::parse("priority*2 + blocking*1.5 + age").unwrap();
+    /// assert_eq!(f.eval(|name| if name == "priority" { 3.0 } else { 0.0 }), 6.0);
+    /// assert!(Formula::parse("priority +").is_err());
+    /// ```
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let tokens = tokenize(src)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.expr()?;
+        match parser.peek() {
+            None => Ok(Self { expr }),
+            Some(t) => Err(format!("unexpected '{}'", t)),
+        }
+    }
+
+    /// Evaluate with `var` supplying each variable's value.
+    pub fn eval(&self, var: impl Fn(&str) -> f64) -> f64 {
+        eval(&self.expr, &var)
+    }
+}
+
+fn eval(expr: &Expr, var: &impl Fn(&str) -> f64) -> f64 {
+    match expr {
+        Expr::Num(n) => *n,
+        Expr::Var(name) => var(name),
+        Expr::Neg(e) => -eval(e, var),
+        Expr::Bin(op, l, r) => {
+            let (l, r) = (eval(l, var), eval(r, var));
+            match op {
+                '+' => l + r,
+                '-' => l - r,
+                '*' => l * r,
+                _ if r == 0.0 => 0.0,
+                _ => l / r,
+            }
+        }
+        Expr::Call(name, args) => {
+            let vals: Vec<f64> = args.iter().map(|a| eval(a, var)).collect();
+            match name.as_str() {
+                "min" => vals.into_iter().fold(f64::INFINITY, f64::min),
+                "max" => vals.into_iter().fold(f64::NEG_INFINITY, f64::max),
+                "abs" => vals[0].abs(),
+                // clamp with lo > hi would panic in f64::clamp; order them.
+                _ => vals[0].max(vals[1].min(vals[2])).min(vals[1].max(vals[2])),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Name(String),
+    Sym(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Num(n) => write!(f, "{}", n),
+            Token::Name(s) => write!(f, "{}", s),
+            Token::Sym(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = src.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut num = String::new();
+            while let Some(&d) = chars.peek() {
+                if d.is_ascii_digit() || d == '.' {
+                    num.push(d);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let n = num
+                .parse::<f64>()
+                .map_err(|_| format!("invalid number '{}'", num))?;
+            tokens.push(Token::Num(n));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut name = String::new();
+            while let Some(&d) = chars.peek() {
+                if d.is_ascii_alphanumeric() || d == '_' {
+                    name.push(d);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Name(name));
+        } else if "+-*/(),".contains(c) {
+            tokens.push(Token::Sym(c));
+            chars.next();
+        } else {
+            return Err(format!("unexpected character '{}'", c));
+        }
+    }
+    if tokens.is_empty() {
+        return Err("empty formula".to_string());
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, sym: char) -> bool {
+        if self.peek() == Some(&Token::Sym(sym)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, sym: char) -> Result<(), String> {
+        if self.eat(sym) {
+            Ok(())
+        } else {
+            match self.peek() {
+                Some(t) => Err(format!("expected '{}' but found '{}'", sym, t)),
+                None => Err(format!("expected '{}' at end of formula", sym)),
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = if self.eat('+') {
+                '+'
+            } else if self.eat('-') {
+                '-'
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Expr::Bin(op, Box::new(lhs), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = if self.eat('*') {
+                '*'
+            } else if self.eat('/') {
+                '/'
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Expr::Bin(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat('-') {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| "formula ends unexpectedly".to_string())?;
+        self.pos += 1;
+        match token {
+            Token::Num(n) => Ok(Expr::Num(n)),
+            Token::Sym('(') => {
+                let inner = self.expr()?;
+                self.expect(')')?;
+                Ok(inner)
+            }
+            Token::Name(name) if self.peek() == Some(&Token::Sym('(')) => {
+                self.pos += 1;
+                let Some(&(_, arity)) = FUNCTIONS.iter().find(|(f, _)| *f == name) else {
+                    return Err(format!(
+                        "unknown function '{}' (valid: min, max, abs, clamp)",
+                        name
+                    ));
+                };
+                let mut args = vec![self.expr()?];
+                while self.eat(',') {
+                    args.push(self.expr()?);
+                }
+                self.expect(')')?;
+                if arity != 0 && args.len() != arity {
+                    return Err(format!(
+                        "{}() takes {} argument(s), got {}",
+                        name,
+                        arity,
+                        args.len()
+                    ));
+                }
+                Ok(Expr::Call(name, args))
+            }
+            Token::Name(name) => {
+                if VARIABLES.contains(&name.as_str()) {
+                    Ok(Expr::Var(name))
+                } else {
+                    Err(format!(
+                        "unknown variable '{}' (valid: {})",
+                        name,
+                        VARIABLES.join(", ")
+                    ))
+                }
+            }
+            Token::Sym(c) => Err(format!("unexpected '{}'", c)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval_with(src: &str, vars: &[(&str, f64)]) -> f64 {
+        Formula::parse(src).unwrap().eval(|name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map_or(0.0, |(_, v)| *v)
+        })
+    }
+
+    #[test]
+    fn precedence_unary_and_functions() {
+        let vars = [("priority", 6.0), ("blocking", 8.0), ("age", 1.0)];
+        assert!((eval_with("priority*2 + blocking*1.5 + age", &vars) - 25.0).abs() < 1e-9);
+        assert!((eval_with("-(priority - age) * 2", &vars) - -10.0).abs() < 1e-9);
+        assert!((eval_with("max(priority, blocking, 3)", &vars) - 8.0).abs() < 1e-9);
+        assert!((eval_with("clamp(blocking, 0, 5)", &vars) - 5.0).abs() < 1e-9);
+        assert!(eval_with("priority / blocked", &vars).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parse_errors_name_the_problem() {
+        let err = |src: &str| Formula::parse(src).unwrap_err();
+        assert!(err("priority +").contains("ends unexpectedly"));
+        assert!(err("priorty * 2").contains("unknown variable 'priorty'"));
+        assert!(err("pow(age, 2)").contains("unknown function"));
+        assert!(err("clamp(age, 1)").contains("takes 3"));
+        assert!(err("(age").contains("expected ')'"));
+        assert!(err("age $ 2").contains("unexpected character"));
+        assert!(err("   ").contains("empty"));
+    }
+}
@@ -0,0 +1,49 @@
+//! Core of the `itr` issue tracker: the database schema and queries ([`db`]),
+//! data types ([`models`]), urgency scoring ([`urgency`]), and a typed store
+//! API ([`store::ItrStore`]) for tools that embed the tracker instead of
+//! shelling out to the CLI and parsing its stdout.
+//!
+//! The SQLite-backed modules sit behind the default `sqlite` feature. Without
+//! it the crate builds for `wasm32-unknown-unknown`, and [`storage`] offers a
+//! read-only query API over any [`storage::Storage`] backend, such as an
+//! in-memory copy of an `itr export`. The `async` feature adds
+//! [`async_store::AsyncItrStore`], the same API as futures for tokio servers.
+//!
+//! ```no_run
+//! # #[cfg(feature = "sqlite")]
+//! # fn main() -> Result<(), itr_core::error::ItrError> {
+//! use itr_core::store::{ItrStore, NewIssue};
+//!
+//! let store = ItrStore::open(std::path::Path::new(".itr.db"))?;
+//! let issue = store.add(NewIssue::new("Fix login timeout"))?;
+//! for summary in store.ready()? {
+//!     println!("{} {}", summary.id, summary.title);
+//! }
+//! store.close(issue.issue.id, Some("fixed".to_string()), false)?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "sqlite"))]
+//! # fn main() {}
+//! ```
+
+#[cfg(feature = "async")]
+pub mod async_store;
+#[cfg(feature = "sqlite")]
+pub mod db;
+#[cfg(feature = "sqlite")]
+pub mod detail;
+pub mod error;
+pub mod formula;
+pub mod models;
+pub mod normalize;
+#[cfg(feature = "sqlite")]
+pub mod pool;
+pub mod storage;
+#[cfg(feature = "sqlite")]
+pub mod store;
+pub mod urgency;
+pub mod util;
+
+pub use error::ItrError;
+#[cfg(feature = "sqlite")]
+pub use store::{ItrStore, NewIssue};
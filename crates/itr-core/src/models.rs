@@ -0,0 +1,927 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// JSON Schema for a serialized model, e.g. `json_schema::<IssueDetail>()`.
+#[cfg(feature = "schema")]
+pub fn json_schema<T: schemars::JsonSchema>() -> serde_json::Value {
+    schemars::schema_for!(T).to_value()
+}
+
+/// Statuses `list` and `search` show without `--status` or `--all`: every
+/// issue not yet closed.
+pub const ACTIVE_STATUSES: &[&str] = &["open", "in-progress", "review"];
+
+/// Whether `status` closes an issue (`done` or `wontfix`), i.e. is not one
+/// of the [`ACTIVE_STATUSES`].
+#[must_use]
+pub fn is_terminal(status: &str) -> bool {
+    matches!(status, "done" | "wontfix")
+}
+
+/// Filter parameters for `db::list_issues()`.
+#[derive(Debug, Default, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ListFilter {
+    pub statuses: Vec<String>,
+    pub priorities: Vec<String>,
+    pub kinds: Vec<String>,
+    pub severities: Vec<String>,
+    /// Component names (OR logic), see `itr component`.
+    pub components: Vec<String>,
+    /// Versions an issue was seen in (OR logic), see `--affects-version`.
+    pub affects_versions: Vec<String>,
+    /// Versions an issue is fixed in (OR logic).
+    pub fixed_in_versions: Vec<String>,
+    pub tags: Vec<String>,
+    pub tag_any: Vec<String>,
+    /// Exact file paths an issue must reference (AND logic).
+    pub files: Vec<String>,
+    pub skills: Vec<String>,
+    pub blocked_only: bool,
+    pub include_blocked: bool,
+    pub parent_id: Option<i64>,
+    /// With `parent_id`: match every descendant, not just direct children.
+    pub recursive: bool,
+    pub assigned_to: Option<String>,
+    /// Project name; `None` matches issues in any project (or none).
+    pub project: Option<String>,
+    pub all: bool,
+    /// Column sort applied in SQL: one of [`crate::db::SQL_SORTS`]. `None`
+    /// (or any other value) keeps ascending ID order.
+    pub sort: Option<String>,
+    /// Applied in SQL after sorting. Only meaningful with a column sort;
+    /// callers sorting in memory (e.g. by urgency) must truncate themselves,
+    /// as `ItrStore::list` and `ReadStore::list` do.
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Issue {
+    pub id: i64,
+    pub title: String,
+    pub status: String,
+    pub priority: String,
+    pub kind: String,
+    pub context: String,
+    pub files: Vec<String>,
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    pub acceptance: String,
+    pub parent_id: Option<i64>,
+    #[serde(default)]
+    pub assigned_to: String,
+    pub close_reason: String,
+    /// Git branch recorded by `itr branch` (empty when none).
+    #[serde(default)]
+    pub branch: String,
+    /// Name of the issue's project (empty when none), see `itr project`.
+    #[serde(default)]
+    pub project: String,
+    /// How bad the defect is (`blocker`, `major`, `minor`, `trivial`),
+    /// independent of priority; empty when unset.
+    #[serde(default)]
+    pub severity: String,
+    /// Name of the issue's component (empty when none), see `itr component`.
+    #[serde(default)]
+    pub component: String,
+    /// The release the issue was observed in; empty when unset.
+    #[serde(default)]
+    pub affects_version: String,
+    /// The release that fixes it; empty when unset.
+    #[serde(default)]
+    pub fixed_in_version: String,
+    /// Estimate in points (see `itr update --estimate`); omitted when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Note {
+    pub id: i64,
+    pub issue_id: i64,
+    pub content: String,
+    pub agent: String,
+    pub created_at: String,
+    /// `handoff` for notes written by `itr handoff`, `review` for rejections
+    /// from `itr review reject`; empty for ordinary notes.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub kind: String,
+    /// Stable identity across copies; filled in by `itr export` only.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub uuid: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IssueDetail {
+    #[serde(flatten)]
+    pub issue: Issue,
+    pub urgency: f64,
+    pub blocked_by: Vec<i64>,
+    pub blocks: Vec<i64>,
+    pub is_blocked: bool,
+    pub notes: Vec<Note>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub urgency_breakdown: Option<UrgencyBreakdown>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<IssueSummary>>,
+    /// Status rollup over every descendant (nested epics included). Only set
+    /// alongside `children`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<SubtreeProgress>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relations: Vec<Relation>,
+    /// `blocked_by`/`blocks`/`referenced_by` entries filed in another project
+    /// than this issue, keyed by ID with their qualified `project/ID` form.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub qualified_ids: BTreeMap<i64, String>,
+    /// How many notes `get --no-notes` or `--notes-last` left out. Only set
+    /// when one of them was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes_omitted: Option<usize>,
+    /// `blocked_by` with each blocker's title and status, from
+    /// `get --with-blockers`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by_issues: Vec<BlockerRef>,
+    /// Human-readable key (`APP-42`) from the project's key prefix; empty
+    /// when the issue has none.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub key: String,
+    /// How many commits are linked to the issue (see `itr commits`). Only
+    /// counted by `get`.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub commits: usize,
+    /// Blockers outside the tracker from `itr depend --on-external`; each
+    /// keeps `is_blocked` true until `itr resolve-external` clears it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_blockers: Vec<ExternalBlocker>,
+    /// Structured reproduction (see `itr repro`); omitted when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repro: Option<Repro>,
+    /// Issues whose context or notes mention this one as `#<id>`, ascending.
+    /// Only filled by `get`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub referenced_by: Vec<i64>,
+}
+
+fn is_zero<T: Default + PartialEq>(n: &T) -> bool {
+    *n == T::default()
+}
+
+/// How to reproduce a bug, set with `itr repro` or a `repro` object in
+/// `add --stdin-json`. Every part is optional.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct Repro {
+    #[serde(default)]
+    pub steps: Vec<String>,
+    #[serde(default)]
+    pub expected: String,
+    #[serde(default)]
+    pub actual: String,
+    #[serde(default)]
+    pub environment: String,
+}
+
+impl Repro {
+    /// Trim every part and drop blank steps.
+    #[must_use]
+    pub fn normalized(self) -> Self {
+        Repro {
+            steps: self
+                .steps
+                .iter()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            expected: self.expected.trim().to_string(),
+            actual: self.actual.trim().to_string(),
+            environment: self.environment.trim().to_string(),
+        }
+    }
+
+    /// True when no part has anything but whitespace.
+    pub fn is_empty(&self) -> bool {
+        self.steps.iter().all(|s| s.trim().is_empty())
+            && self.expected.trim().is_empty()
+            && self.actual.trim().is_empty()
+            && self.environment.trim().is_empty()
+    }
+}
+
+/// Something outside the tracker an issue waits on, such as an upstream
+/// release, added with `itr depend <ID> --on-external "..." [--url ...]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ExternalBlocker {
+    pub id: i64,
+    pub issue_id: i64,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub url: String,
+    pub created_at: String,
+}
+
+/// A blocker inlined into an [`IssueDetail`] by `get --with-blockers`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BlockerRef {
+    pub id: i64,
+    pub title: String,
+    pub status: String,
+}
+
+/// `itr brief` output: one issue's detail plus the context a worker agent
+/// needs to pick it up cold. `notes` in `detail` holds only the most recent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Brief {
+    #[serde(flatten)]
+    pub detail: IssueDetail,
+    /// How many notes the issue has in total.
+    pub notes_total: usize,
+    /// The parent (usually an epic), with its progress when it has children.
+    pub parent: Option<BriefIssue>,
+    /// Every issue in `blocked_by`, with its context and latest note.
+    pub blockers: Vec<BriefIssue>,
+    /// The issue's files, each with the other open or in-progress issues
+    /// that name it.
+    pub related_files: Vec<FileHotspot>,
+}
+
+/// A neighbouring issue inside a [`Brief`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BriefIssue {
+    pub id: i64,
+    pub title: String,
+    pub status: String,
+    pub priority: String,
+    pub kind: String,
+    pub assigned_to: String,
+    pub context: String,
+    pub acceptance: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<SubtreeProgress>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_note: Option<Note>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IssueSummary {
+    pub id: i64,
+    pub title: String,
+    pub status: String,
+    pub priority: String,
+    pub kind: String,
+    pub urgency: f64,
+    pub is_blocked: bool,
+    pub blocked_by: Vec<i64>,
+    pub tags: Vec<String>,
+    pub files: Vec<String>,
+    pub skills: Vec<String>,
+    pub acceptance: String,
+    /// Flat `Issue` fields carried so `list --fields X` reports the same value
+    /// `get` does. No `skip_serializing_if` on any of them: they mirror `Issue`
+    /// /`IssueDetail` exactly (which always serialize these), so a missing value
+    /// is explicit `null`/`""`/`[]`, never an absent key — the split-brain that
+    /// #216 fixed for `parent_id` and this extends to every flat field.
+    #[serde(default)]
+    pub context: String,
+    pub parent_id: Option<i64>,
+    #[serde(default)]
+    pub close_reason: String,
+    /// Mirror of `blocked_by` (already carried): issues this one blocks.
+    #[serde(default)]
+    pub blocks: Vec<i64>,
+    #[serde(default)]
+    pub assigned_to: String,
+    #[serde(default)]
+    pub branch: String,
+    #[serde(default)]
+    pub project: String,
+    #[serde(default)]
+    pub severity: String,
+    #[serde(default)]
+    pub component: String,
+    #[serde(default)]
+    pub affects_version: String,
+    #[serde(default)]
+    pub fixed_in_version: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Aggregate status counts for all issues below a node in the parent tree.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SubtreeProgress {
+    pub total: i64,
+    pub open: i64,
+    pub in_progress: i64,
+    /// Descendants awaiting review; omitted when there are none.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub review: i64,
+    pub done: i64,
+    pub wontfix: i64,
+    /// Share of descendants that are closed (done or wontfix), 0-100, rounded
+    /// to one decimal. An empty subtree reports 0.
+    pub percent_complete: f64,
+}
+
+impl SubtreeProgress {
+    pub fn from_counts(total: i64, open: i64, in_progress: i64, done: i64, wontfix: i64) -> Self {
+        let percent_complete = if total > 0 {
+            #[allow(clippy::cast_precision_loss)]
+            let pct = (done + wontfix) as f64 * 100.0 / total as f64;
+            (pct * 10.0).round() / 10.0
+        } else {
+            0.0
+        };
+        SubtreeProgress {
+            total,
+            open,
+            in_progress,
+            review: 0,
+            done,
+            wontfix,
+            percent_complete,
+        }
+    }
+
+    /// Closed descendants (done + wontfix).
+    pub fn closed(&self) -> i64 {
+        self.done + self.wontfix
+    }
+}
+
+/// One row of `itr urgency`: an issue's score and how it was assembled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrgencyReport {
+    pub id: i64,
+    pub title: String,
+    pub status: String,
+    pub priority: String,
+    pub kind: String,
+    pub urgency: f64,
+    pub urgency_breakdown: UrgencyBreakdown,
+}
+
+/// One row of `itr tag list`: issue counts for a tag across all statuses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub open: i64,
+    pub closed: i64,
+    pub total: i64,
+    /// Set by `itr tag describe` (empty when none).
+    pub description: String,
+    pub color: String,
+}
+
+/// A registered agent (`itr agent register`) and its current workload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Agent {
+    pub name: String,
+    /// Lowercased skill tags, matched against issues' `skills`.
+    pub skills: Vec<String>,
+    pub description: String,
+    /// IDs of the open and in-progress issues assigned to this agent.
+    pub assigned: Vec<i64>,
+    /// How many issues assigned to this agent are done or wontfix.
+    pub closed: i64,
+    pub registered_at: String,
+    /// Last claim, note, or audited write by this agent.
+    pub last_seen_at: String,
+}
+
+/// A commit linked to an issue by the post-commit hook (`itr git link`) or
+/// `itr git scan`. `relation` is `close` when the commit message closes the
+/// issue and `mention` otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueCommit {
+    pub issue_id: i64,
+    pub hash: String,
+    pub subject: String,
+    pub relation: String,
+    pub linked_at: String,
+}
+
+/// A recent commit touching an issue's files, from `itr activity`. `files`
+/// lists only the issue's paths the commit changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileCommit {
+    pub hash: String,
+    pub author: String,
+    pub date: String,
+    pub subject: String,
+    pub files: Vec<String>,
+}
+
+/// A proposed sprint from `itr plan-sprint`: the picked issues in the
+/// order they were planned, and the points each agent ends up with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SprintPlan {
+    /// Points per agent.
+    pub capacity: i64,
+    pub agents: usize,
+    pub planned_points: i64,
+    /// Points planned for each agent, first agent first.
+    pub agent_points: Vec<i64>,
+    /// Open and in-progress issues that did not make the sprint.
+    pub left_out: usize,
+    pub items: Vec<SprintItem>,
+}
+
+/// One issue in a [`SprintPlan`]. `agent` is a 1-based slot, not a
+/// registered agent; `after` lists the in-sprint issues that block it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SprintItem {
+    pub id: i64,
+    pub title: String,
+    pub priority: String,
+    pub urgency: f64,
+    pub points: i64,
+    /// Whether `points` came from an estimate rather than the default.
+    pub estimated: bool,
+    pub agent: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<i64>,
+}
+
+/// The outcome of `itr assign --auto`: who got which ready issue, what was
+/// left unassigned, and each registered agent's active load afterwards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoAssignPlan {
+    pub assigned: Vec<AutoAssignment>,
+    pub skipped: Vec<AutoAssignment>,
+    pub load: BTreeMap<String, usize>,
+}
+
+/// One ready issue considered by `itr assign --auto`. `agent` is empty for a
+/// skipped issue; `reason` says why it went where it did, or nowhere.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoAssignment {
+    pub id: i64,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub agent: String,
+    pub reason: String,
+}
+
+/// A work session from `itr session start`; `ended_at` is `None` while it
+/// is still open, and `issues` is filled in by `itr session end`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub id: i64,
+    pub agent: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub summary: String,
+    /// IDs of the issues the agent touched (events or notes) during the session.
+    pub issues: Vec<i64>,
+}
+
+/// One row of `itr project list`: a project with its issue counts, on the
+/// same open/closed split as [`TagCount`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectCount {
+    pub name: String,
+    pub description: String,
+    pub open: i64,
+    pub closed: i64,
+    pub total: i64,
+    /// Whether this is the `project.current` set by `itr project switch`.
+    pub current: bool,
+    pub created_at: String,
+    /// Prefix of the issue keys handed out here (`APP` in `APP-42`); empty
+    /// when the project has none.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub key_prefix: String,
+}
+
+/// One row of `itr component list`: a component's routing defaults and its
+/// issue counts, on the same open/closed split as [`ProjectCount`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Component {
+    pub name: String,
+    /// Assignee given to unassigned issues filed in or moved to the component.
+    pub default_assignee: String,
+    /// Tags added to issues filed in or moved to the component.
+    pub default_tags: Vec<String>,
+    pub open: i64,
+    pub closed: i64,
+    pub total: i64,
+    pub created_at: String,
+}
+
+/// One row of `itr files`: a path and the matching issues that list it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHotspot {
+    pub path: String,
+    pub count: i64,
+    pub issue_ids: Vec<i64>,
+}
+
+/// One row of `itr versions`: an affected version and its open issues, with
+/// how many of them already have a fix version.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionReport {
+    pub version: String,
+    pub open: i64,
+    pub fix_scheduled: i64,
+    pub issue_ids: Vec<i64>,
+}
+
+/// One node of `itr tree` output: an issue plus its nested children.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeNode {
+    pub id: i64,
+    pub title: String,
+    pub status: String,
+    pub kind: String,
+    pub priority: String,
+    /// Rollup over this node's subtree; absent for leaves.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<SubtreeProgress>,
+    pub children: Vec<TreeNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct UrgencyBreakdown {
+    pub components: Vec<(String, f64)>,
+    /// Why `next` picked this issue for the agent's skills (see `next
+    /// --skills`); not part of the score.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skill_match: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnblockedIssue {
+    pub id: i64,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchAddInput {
+    pub title: String,
+    #[serde(default = "default_priority")]
+    pub priority: String,
+    #[serde(default = "default_kind")]
+    pub kind: String,
+    /// Status to land in instead of `open`, e.g. `in-progress` for imported
+    /// work that is already under way.
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub context: String,
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    #[serde(default)]
+    pub acceptance: String,
+    /// Accepts both `parent_id` and `parent` — agents naturally mirror the
+    /// `--parent` CLI flag in JSON payloads (#150).
+    #[serde(default, alias = "parent")]
+    pub parent_id: Option<i64>,
+    #[serde(default)]
+    pub assigned_to: String,
+    #[serde(default)]
+    pub severity: String,
+    #[serde(default)]
+    pub component: String,
+    #[serde(default)]
+    pub affects_version: String,
+    #[serde(default)]
+    pub fixed_in_version: String,
+    #[serde(default)]
+    pub repro: Option<Repro>,
+    /// Estimate in points, as a whole number.
+    #[serde(default)]
+    pub estimate: Option<i64>,
+    #[serde(default)]
+    pub blocked_by: Vec<serde_json::Value>,
+    /// Name other items in the same batch use to depend on this one, as
+    /// `"ref:<name>"` in `blocked_by`.
+    #[serde(default, rename = "ref")]
+    pub ref_name: Option<String>,
+    /// Notes added to the new issue, in order, by the current agent.
+    #[serde(default)]
+    pub notes: Vec<String>,
+}
+
+pub(crate) fn default_priority() -> String {
+    "medium".to_string()
+}
+
+pub(crate) fn default_kind() -> String {
+    "task".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GraphOutput {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GraphNode {
+    pub id: i64,
+    pub title: String,
+    pub status: String,
+    pub urgency: f64,
+    pub is_blocked: bool,
+    /// Omitted when the issue has no project, so graphs of a database that
+    /// never used projects keep their historical shape.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub project: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GraphEdge {
+    pub from: i64,
+    pub to: i64,
+    #[serde(rename = "type")]
+    pub edge_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Stats {
+    pub total: i64,
+    pub by_status: std::collections::HashMap<String, i64>,
+    pub by_priority: std::collections::HashMap<String, i64>,
+    pub by_kind: std::collections::HashMap<String, i64>,
+    pub blocked: i64,
+    pub ready: i64,
+    pub avg_urgency: f64,
+    pub by_skills: std::collections::HashMap<String, i64>,
+    pub by_assignee: std::collections::HashMap<String, i64>,
+    /// Active issues per component; registered components with none show 0.
+    pub by_component: std::collections::HashMap<String, i64>,
+    pub oldest_open: Option<OldestOpen>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OldestOpen {
+    pub id: i64,
+    pub title: String,
+    pub days_old: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub id: i64,
+    pub title: String,
+    pub status: String,
+    pub priority: String,
+    pub kind: String,
+    pub urgency: f64,
+    pub is_blocked: bool,
+    pub blocked_by: Vec<i64>,
+    pub tags: Vec<String>,
+    pub files: Vec<String>,
+    pub skills: Vec<String>,
+    pub acceptance: String,
+    #[serde(default)]
+    pub assigned_to: String,
+    pub matched_fields: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_snippets: Option<std::collections::HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkResult {
+    pub action: String,
+    pub count: usize,
+    pub ids: Vec<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unblocked: Vec<UnblockedIssue>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[allow(clippy::struct_field_names)]
+pub struct Relation {
+    pub id: i64,
+    pub source_id: i64,
+    pub target_id: i64,
+    pub relation_type: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub id: i64,
+    pub issue_id: i64,
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
+    pub agent: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchNoteInput {
+    pub id: i64,
+    pub text: String,
+    #[serde(default)]
+    pub agent: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchCloseInput {
+    pub id: i64,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(default)]
+    pub wontfix: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchUpdateInput {
+    pub id: i64,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[serde(default)]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub context: Option<String>,
+    #[serde(default)]
+    pub assigned_to: Option<String>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+    #[serde(default)]
+    pub add_skills: Vec<String>,
+    #[serde(default)]
+    pub remove_skills: Vec<String>,
+    /// Re-parent (`"parent_id": N`) or clear (`"parent_id": null`) the parent
+    /// epic; an absent key leaves it unchanged. Accepts both `parent_id` and
+    /// `parent` — agents naturally mirror the `--parent` CLI flag in JSON
+    /// payloads (#150).
+    #[serde(default, alias = "parent", deserialize_with = "parent_change")]
+    pub parent_id: ParentChange,
+    /// `"no_parent": true` also clears the parent — mirrors `--no-parent`.
+    #[serde(default)]
+    pub no_parent: bool,
+}
+
+/// Tri-state parent directive for a batch update item: an absent key must
+/// mean "leave alone" while an explicit JSON `null` means "clear".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum ParentChange {
+    #[default]
+    Unchanged,
+    Clear,
+    Set(i64),
+}
+
+/// Map a *present* `parent_id` key to `Set`/`Clear`. An absent key never
+/// reaches this function — `#[serde(default)]` yields `Unchanged` instead.
+fn parent_change<'de, D>(de: D) -> Result<ParentChange, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Option::<i64>::deserialize(de)? {
+        Some(id) => ParentChange::Set(id),
+        None => ParentChange::Clear,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchItemResult {
+    pub id: i64,
+    pub outcome: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unblocked: Vec<UnblockedIssue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<IssueDetail>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub ok: usize,
+    pub error: usize,
+    pub review: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub action: String,
+    pub results: Vec<BatchItemResult>,
+    pub summary: BatchSummary,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+    /// Set by `itr apply` when an operation failed and nothing was written.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rolled_back: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportData {
+    pub issue: Issue,
+    pub notes: Vec<Note>,
+    pub blocked_by: Vec<i64>,
+    #[serde(default)]
+    pub events: Vec<Event>,
+    #[serde(default)]
+    pub relations: Vec<Relation>,
+    /// Stable identity across copies. Empty in exports from before UUIDs.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub uuid: String,
+    /// Human-readable key (`APP-42`), kept by import even when the ID is not.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub key: String,
+    /// Structured reproduction (see `itr repro`); omitted when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repro: Option<Repro>,
+    /// When each field last changed, for last-write-wins `import --merge`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub field_times: BTreeMap<String, String>,
+    /// Issues this one blocks (the other direction of `blocked_by`). Only
+    /// in `--full` exports, where it lets import restore edges to issues
+    /// outside a partial export.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<i64>,
+    /// Direct children, in `--full` exports, for checking the parent links
+    /// survived the round trip.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<i64>,
+}
+
+/// The `format` value of an [`ExportHeader`].
+pub const EXPORT_FORMAT: &str = "itr-export";
+
+/// The newest export format version this build reads and writes.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Leads an `itr export --full`: the first line of JSONL, or the fields
+/// around `items` in JSON. Plain exports have no header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportHeader {
+    pub format: String,
+    pub version: u32,
+    /// The `config` table, every key including state like the current
+    /// project.
+    #[serde(default)]
+    pub config: BTreeMap<String, String>,
+    /// Tags with a description or color, as `itr tag list` reports them.
+    /// Import ignores the counts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<TagCount>,
+    /// The agent registry, as `itr agent list` reports it. Import ignores
+    /// the workload.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<Agent>,
+    /// Registered components with their defaults, as `itr component list`
+    /// reports them. Import ignores the counts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<Component>,
+}
+
+impl Default for ExportHeader {
+    fn default() -> Self {
+        Self {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            config: BTreeMap::new(),
+            tags: Vec::new(),
+            agents: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+}
+
+/// A `--full` export in JSON: the header with the items inside it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FullExport {
+    #[serde(flatten)]
+    pub header: ExportHeader,
+    pub items: Vec<ExportData>,
+}
@@ -68,12 +68,14 @@ pub fn normalize_kind(k: &str) -> String {
     }
 }
 
-/// Map a user-supplied status string onto one of the four canonical buckets
-/// (`open`, `in-progress`, `done`, `wontfix`) using case-insensitive synonyms.
+/// Map a user-supplied status string onto one of the five canonical buckets
+/// (`open`, `in-progress`, `review`, `done`, `wontfix`) using case-insensitive
+/// synonyms.
 ///
 /// The hyphenated form `in-progress` is the canonical wire value; common
 /// underscore / no-separator variants (`in_progress`, `inprogress`, `wip`,
-/// `started`, `progress`) all collapse onto it.
+/// `started`, `progress`) all collapse onto it, and `in-review`/`reviewing`
+/// onto `review`.
 ///
 /// # Examples
 ///
@@ -83,16 +85,47 @@ pub fn normalize_kind(k: &str) -> String {
 /// assert_eq!(normalize_status("backlog"), "open");
 /// assert_eq!(normalize_status("wip"), "in-progress");
 /// assert_eq!(normalize_status("in_progress"), "in-progress");
+/// assert_eq!(normalize_status("In-Review"), "review");
 /// assert_eq!(normalize_status("resolved"), "done");
 /// assert_eq!(normalize_status("cancelled"), "wontfix");
 /// ```
 pub fn normalize_status(s: &str) -> String {
     match s.to_lowercase().as_str() {
-        "open" | "in-progress" | "done" | "wontfix" => s.to_lowercase(),
+        "open" | "in-progress" | "review" | "done" | "wontfix" => s.to_lowercase(),
         "todo" | "new" | "backlog" => "open".to_string(),
         "closed" | "resolved" | "fixed" => "done".to_string(),
         "cancelled" | "canceled" => "wontfix".to_string(),
         "wip" | "started" | "progress" | "in_progress" | "inprogress" => "in-progress".to_string(),
+        "in-review" | "in_review" | "inreview" | "reviewing" | "needs-review" => {
+            "review".to_string()
+        }
+        _ => s.to_lowercase(),
+    }
+}
+
+/// Map a user-supplied severity string onto one of the four canonical
+/// buckets (`blocker`, `major`, `minor`, `trivial`) using case-insensitive
+/// synonyms, including the `S1`–`S4` scale QA tools use.
+///
+/// Severity is how bad a defect is, independent of when it gets scheduled
+/// (priority). Unknown inputs are returned lowercased and unchanged, for
+/// `validate_severity` to reject.
+///
+/// # Examples
+///
+/// ```text
+/// use itr::normalize::normalize_severity;
+/// assert_eq!(normalize_severity("Major"), "major");
+/// assert_eq!(normalize_severity("S1"), "blocker");
+/// assert_eq!(normalize_severity("cosmetic"), "trivial");
+/// ```
+pub fn normalize_severity(s: &str) -> String {
+    match s.to_lowercase().as_str() {
+        "blocker" | "major" | "minor" | "trivial" => s.to_lowercase(),
+        "s1" | "showstopper" | "crash" => "blocker".to_string(),
+        "s2" | "severe" => "major".to_string(),
+        "s3" | "moderate" => "minor".to_string(),
+        "s4" | "cosmetic" => "trivial".to_string(),
         _ => s.to_lowercase(),
     }
 }
@@ -150,7 +183,25 @@ pub fn validate_kind(k: &str) -> Result<(), ItrError> {
     }
 }
 
-/// Accept a status only if it is one of the four canonical values.
+/// Accept a severity only if it is one of the four canonical values.
+///
+/// Pair with `normalize_severity`. An issue with no severity stores the
+/// empty string, which callers handle before validating.
+pub fn validate_severity(s: &str) -> Result<(), ItrError> {
+    match s {
+        "blocker" | "major" | "minor" | "trivial" => Ok(()),
+        _ => Err(ItrError::InvalidValue {
+            field: "severity".to_string(),
+            value: s.to_string(),
+            valid: SEVERITIES.to_string(),
+        }),
+    }
+}
+
+/// The canonical severities, worst first, as listed in messages.
+pub const SEVERITIES: &str = "blocker, major, minor, trivial";
+
+/// Accept a status only if it is one of the five canonical values.
 ///
 /// Pair with `normalize_status`. Rejected values produce
 /// `ItrError::InvalidValue`.
@@ -166,11 +217,11 @@ pub fn validate_kind(k: &str) -> Result<(), ItrError> {
 /// ```
 pub fn validate_status(s: &str) -> Result<(), ItrError> {
     match s {
-        "open" | "in-progress" | "done" | "wontfix" => Ok(()),
+        "open" | "in-progress" | "review" | "done" | "wontfix" => Ok(()),
         _ => Err(ItrError::InvalidValue {
             field: "status".to_string(),
             value: s.to_string(),
-            valid: "open, in-progress, done, wontfix".to_string(),
+            valid: "open, in-progress, review, done, wontfix".to_string(),
         }),
     }
 }
@@ -194,8 +245,9 @@ fn normalize_filter_values(
     for value in values {
         let canon = normalize(value);
         if validate(&canon).is_err() {
+            let hint = crate::util::did_you_mean(&canon, valid);
             notes.push(format!(
-                "REVIEW: {field} filter '{value}' not recognized; it will match nothing. Valid: {valid}"
+                "REVIEW: {field} filter '{value}' not recognized{hint}; it will match nothing. Valid: {valid}"
             ));
         }
         normalized.push(canon);
@@ -211,7 +263,7 @@ pub fn normalize_status_filters(values: &[String]) -> (Vec<String>, Vec<String>)
         normalize_status,
         validate_status,
         "status",
-        "open, in-progress, done, wontfix",
+        "open, in-progress, review, done, wontfix",
     )
 }
 
@@ -239,15 +291,43 @@ pub fn normalize_kind_filters(values: &[String]) -> (Vec<String>, Vec<String>) {
     )
 }
 
+/// Normalize severity read-filter values (`s2` → `major`, `cosmetic` →
+/// `trivial`, ...). Returns `(normalized_values, review_notes)`.
+pub fn normalize_severity_filters(values: &[String]) -> (Vec<String>, Vec<String>) {
+    normalize_filter_values(
+        values,
+        normalize_severity,
+        validate_severity,
+        "severity",
+        SEVERITIES,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use proptest::prelude::*;
 
-    // The four canonical values for each field.
+    #[test]
+    fn severity_synonyms_normalize_and_validate() {
+        for (raw, canon) in [
+            ("BLOCKER", "blocker"),
+            ("s2", "major"),
+            ("Moderate", "minor"),
+            ("cosmetic", "trivial"),
+        ] {
+            assert_eq!(normalize_severity(raw), canon);
+            assert_eq!(normalize_severity(canon), canon);
+            assert!(validate_severity(canon).is_ok());
+        }
+        assert!(validate_severity(&normalize_severity("high")).is_err());
+        assert!(validate_severity("").is_err());
+    }
+
+    // The canonical values for each field.
     const CANONICAL_PRIORITIES: &[&str] = &["critical", "high", "medium", "low"];
     const CANONICAL_KINDS: &[&str] = &["bug", "feature", "task", "epic"];
-    const CANONICAL_STATUSES: &[&str] = &["open", "in-progress", "done", "wontfix"];
+    const CANONICAL_STATUSES: &[&str] = &["open", "in-progress", "review", "done", "wontfix"];
 
     proptest! {
         // --- normalize_priority ---
@@ -432,7 +512,7 @@ mod tests {
         assert_eq!(values, vec!["bogus"], "unknown values pass through");
         assert_eq!(notes.len(), 1);
         assert!(notes[0].starts_with("REVIEW: status filter 'bogus'"));
-        assert!(notes[0].contains("open, in-progress, done, wontfix"));
+        assert!(notes[0].contains("open, in-progress, review, done, wontfix"));
 
         let (_, notes) = normalize_priority_filters(&["bogus".to_string()]);
         assert!(notes[0].contains("critical, high, medium, low"));
@@ -0,0 +1,208 @@
+//! Connections for long-running server modes: one writer plus a few
+//! read-only readers on the same WAL database.
+//!
+//! WAL lets readers run while a write transaction is open, so requests that
+//! only read never queue behind a slow write. The database allows a single
+//! writer at a time anyway; funnelling writes through one connection turns lock
+//! contention into a cheap mutex wait instead of `busy_timeout` retries.
+
+use crate::db;
+use crate::error::ItrError;
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+/// A writer connection and a fixed set of reader connections. Shared across
+/// request threads by reference.
+#[derive(Debug)]
+pub struct ConnectionPool {
+    writer: Mutex<Connection>,
+    readers: Mutex<Vec<Connection>>,
+    reader_count: usize,
+    returned: Condvar,
+}
+
+impl ConnectionPool {
+    /// Open `path` once for writes and `readers` more times for reads.
+    /// Readers are `query_only`, so a route that writes by mistake fails
+    /// instead of racing the writer. An in-memory database can't be shared
+    /// between connections; wrap it with [`ConnectionPool::from_connection`].
+    pub fn open(path: &Path, readers: usize) -> Result<Self, ItrError> {
+        Self::open_with_defaults(path, readers, &BTreeMap::new())
+    }
+
+    /// [`ConnectionPool::open`], with every connection given the config
+    /// `defaults` (see [`db::set_config_defaults`]).
+    pub fn open_with_defaults(
+        path: &Path,
+        readers: usize,
+        defaults: &BTreeMap<String, String>,
+    ) -> Result<Self, ItrError> {
+        let writer = db::open_db(path)?;
+        db::set_config_defaults(&writer, defaults)?;
+        let mut conns = Vec::with_capacity(readers);
+        for _ in 0..readers {
+            let conn = db::open_db(path)?;
+            // Before `query_only`, which refuses the TEMP table write too.
+            db::set_config_defaults(&conn, defaults)?;
+            conn.execute_batch("PRAGMA query_only=ON;")?;
+            conns.push(conn);
+        }
+        Ok(ConnectionPool {
+            writer: Mutex::new(writer),
+            readers: Mutex::new(conns),
+            reader_count: readers,
+            returned: Condvar::new(),
+        })
+    }
+
+    /// A pool over one existing connection: reads and writes take turns on it.
+    pub fn from_connection(conn: Connection) -> Self {
+        ConnectionPool {
+            writer: Mutex::new(conn),
+            readers: Mutex::new(Vec::new()),
+            reader_count: 0,
+            returned: Condvar::new(),
+        }
+    }
+
+    /// Number of dedicated reader connections.
+    pub fn reader_count(&self) -> usize {
+        self.reader_count
+    }
+
+    /// A connection for reads, waiting for a free reader if all are busy.
+    /// Without readers this is [`ConnectionPool::write`].
+    pub fn read(&self) -> PooledConnection<'_> {
+        if self.reader_count == 0 {
+            return self.write();
+        }
+        let mut free = self.readers.lock().unwrap_or_else(PoisonError::into_inner);
+        loop {
+            if let Some(conn) = free.pop() {
+                return PooledConnection::Reader {
+                    pool: self,
+                    conn: Some(conn),
+                };
+            }
+            free = self
+                .returned
+                .wait(free)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// The writer connection, held exclusively until the guard drops.
+    pub fn write(&self) -> PooledConnection<'_> {
+        // A panic mid-request leaves no open transaction behind (writes roll
+        // back on drop), so a poisoned writer is still usable.
+        PooledConnection::Writer(self.writer.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// A connection borrowed from a [`ConnectionPool`]; readers go back to the
+/// pool on drop.
+pub enum PooledConnection<'a> {
+    Reader {
+        pool: &'a ConnectionPool,
+        conn: Option<Connection>,
+    },
+    Writer(MutexGuard<'a, Connection>),
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            PooledConnection::Reader { conn, .. } => {
+                conn.as_ref().expect("reader is present until drop")
+            }
+            PooledConnection::Writer(guard) => guard,
+        }
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let PooledConnection::Reader { pool, conn } = self {
+            if let Some(conn) = conn.take() {
+                pool.readers
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push(conn);
+                pool.returned.notify_one();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "itr-pool-{tag}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(".itr.db");
+        drop(db::init_db(&path).unwrap());
+        path
+    }
+
+    #[test]
+    fn readers_see_committed_writes_and_refuse_to_write() {
+        let path = temp_db("rw");
+        let pool = ConnectionPool::open(&path, 2).unwrap();
+        assert_eq!(pool.reader_count(), 2);
+
+        let writer = pool.write();
+        let tx = db::begin_write(&writer).unwrap();
+        db::config_set(&tx, "pool.test", "1").unwrap();
+        // WAL: a reader is not blocked by the open write transaction and
+        // does not see it until commit.
+        assert_eq!(db::config_get(&pool.read(), "pool.test").unwrap(), None);
+        tx.commit().unwrap();
+        drop(writer);
+        assert_eq!(
+            db::config_get(&pool.read(), "pool.test")
+                .unwrap()
+                .as_deref(),
+            Some("1")
+        );
+
+        assert!(db::config_set(&pool.read(), "pool.test", "2").is_err());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn read_waits_for_a_returned_reader() {
+        let path = temp_db("wait");
+        let pool = ConnectionPool::open(&path, 1).unwrap();
+        let held = pool.read();
+        std::thread::scope(|s| {
+            let waiter = s.spawn(|| db::config_get(&pool.read(), "missing").unwrap());
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            assert!(!waiter.is_finished());
+            drop(held);
+            assert_eq!(waiter.join().unwrap(), None);
+        });
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn single_connection_pool_shares_the_writer() {
+        let pool = ConnectionPool::from_connection(db::init_db(Path::new(":memory:")).unwrap());
+        assert_eq!(pool.reader_count(), 0);
+        db::config_set(&pool.write(), "pool.test", "1").unwrap();
+        assert!(db::config_get(&pool.read(), "pool.test").unwrap().is_some());
+    }
+}
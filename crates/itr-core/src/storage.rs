@@ -0,0 +1,773 @@
+//! Read-only queries over a pluggable storage backend.
+//!
+//! [`crate::store::ItrStore`] is tied to a `rusqlite` connection, which can't be
+//! linked on every target (notably `wasm32-unknown-unknown`). The [`Storage`]
+//! trait abstracts the handful of reads the query layer needs, and
+//! [`ReadStore`] answers `list`/`ready`/`get`/`graph` from them with the same
+//! filters, urgency scoring, and ordering the CLI uses. [`MemoryStorage`]
+//! loads an `itr export` into memory; a browser dashboard can implement the
+//! trait over OPFS or any other persistence it has.
+
+use crate::error::ItrError;
+use crate::models::{
+    is_terminal, ExportData, ExportHeader, FullExport, GraphEdge, GraphNode, GraphOutput, Issue,
+    IssueDetail, IssueSummary, ListFilter, Note, Relation, EXPORT_FORMAT, EXPORT_VERSION,
+};
+use crate::urgency::{compute_urgency_from_inputs, UrgencyConfig, UrgencyInputs};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// The reads [`ReadStore`] is built on. Implementations only hand back raw
+/// rows; filtering, scoring, and sorting happen in [`ReadStore`].
+pub trait Storage {
+    /// Every issue, in ascending ID order.
+    fn issues(&self) -> Result<Vec<Issue>, ItrError>;
+    /// Every dependency edge as `(blocker_id, blocked_id)`.
+    fn dependencies(&self) -> Result<Vec<(i64, i64)>, ItrError>;
+    /// Notes on one issue, oldest first.
+    fn notes(&self, issue_id: i64) -> Result<Vec<Note>, ItrError>;
+    /// Note count per issue; issues without notes may be absent.
+    fn note_counts(&self) -> Result<HashMap<i64, i64>, ItrError>;
+    /// Every relation, in ascending ID order.
+    fn relations(&self) -> Result<Vec<Relation>, ItrError>;
+    /// A raw `config` value (e.g. `urgency.priority.high`), `None` when unset.
+    fn config_value(&self, key: &str) -> Result<Option<String>, ItrError>;
+}
+
+#[cfg(feature = "sqlite")]
+impl Storage for rusqlite::Connection {
+    fn issues(&self) -> Result<Vec<Issue>, ItrError> {
+        crate::db::all_issues(self)
+    }
+
+    fn dependencies(&self) -> Result<Vec<(i64, i64)>, ItrError> {
+        crate::db::all_dependencies(self)
+    }
+
+    fn notes(&self, issue_id: i64) -> Result<Vec<Note>, ItrError> {
+        crate::db::get_notes(self, issue_id)
+    }
+
+    fn note_counts(&self) -> Result<HashMap<i64, i64>, ItrError> {
+        let mut stmt = self.prepare("SELECT issue_id, COUNT(*) FROM notes GROUP BY issue_id")?;
+        let counts = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(counts)
+    }
+
+    fn relations(&self) -> Result<Vec<Relation>, ItrError> {
+        crate::db::all_relations(self)
+    }
+
+    fn config_value(&self, key: &str) -> Result<Option<String>, ItrError> {
+        crate::db::config_get(self, key)
+    }
+}
+
+impl<S: Storage + ?Sized> Storage for &S {
+    fn issues(&self) -> Result<Vec<Issue>, ItrError> {
+        (**self).issues()
+    }
+
+    fn dependencies(&self) -> Result<Vec<(i64, i64)>, ItrError> {
+        (**self).dependencies()
+    }
+
+    fn notes(&self, issue_id: i64) -> Result<Vec<Note>, ItrError> {
+        (**self).notes(issue_id)
+    }
+
+    fn note_counts(&self) -> Result<HashMap<i64, i64>, ItrError> {
+        (**self).note_counts()
+    }
+
+    fn relations(&self) -> Result<Vec<Relation>, ItrError> {
+        (**self).relations()
+    }
+
+    fn config_value(&self, key: &str) -> Result<Option<String>, ItrError> {
+        (**self).config_value(key)
+    }
+}
+
+/// Parse the output of `itr export`: a JSON array, a `--full` JSON object,
+/// or JSONL whose first line may be an [`ExportHeader`]. A header from a
+/// newer format version is an error rather than a partial read.
+pub fn parse_export(input: &str) -> Result<(Option<ExportHeader>, Vec<ExportData>), ItrError> {
+    let input = input.trim();
+    if input.starts_with('[') {
+        return Ok((None, serde_json::from_str(input)?));
+    }
+    // A pretty-printed `--full` JSON object spans many lines; JSONL with a
+    // single item also parses as one document but has no `items`.
+    if let Ok(full) = serde_json::from_str::<FullExport>(input) {
+        check_header(&full.header)?;
+        return Ok((Some(full.header), full.items));
+    }
+    let mut lines = input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+    let mut header = None;
+    if let Some(first) = lines.peek() {
+        let value: serde_json::Value = serde_json::from_str(first)?;
+        if value.get("format").is_some() {
+            let parsed: ExportHeader = serde_json::from_value(value)?;
+            check_header(&parsed)?;
+            header = Some(parsed);
+            lines.next();
+        }
+    }
+    let items = lines.map(serde_json::from_str).collect::<Result<_, _>>()?;
+    Ok((header, items))
+}
+
+fn check_header(header: &ExportHeader) -> Result<(), ItrError> {
+    if header.format != EXPORT_FORMAT || header.version > EXPORT_VERSION {
+        return Err(ItrError::InvalidValue {
+            field: "export format".to_string(),
+            value: format!("{} v{}", header.format, header.version),
+            valid: format!("{} v{} or older", EXPORT_FORMAT, EXPORT_VERSION),
+        });
+    }
+    Ok(())
+}
+
+/// A whole tracker held in memory, typically loaded from `itr export`.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    issues: Vec<Issue>,
+    dependencies: Vec<(i64, i64)>,
+    notes: HashMap<i64, Vec<Note>>,
+    relations: Vec<Relation>,
+    config: HashMap<String, String>,
+}
+
+impl MemoryStorage {
+    /// Load the output of `itr export` in either format: a JSON array
+    /// (`--format json`) or one object per line (the JSONL default). A
+    /// `--full` export's config comes along.
+    pub fn from_export(input: &str) -> Result<Self, ItrError> {
+        let (header, items) = parse_export(input)?;
+        let mut storage = Self::from_items(items);
+        if let Some(header) = header {
+            storage.config.extend(header.config);
+        }
+        Ok(storage)
+    }
+
+    /// Build from already-parsed export records.
+    pub fn from_items(items: Vec<ExportData>) -> Self {
+        let mut storage = Self::default();
+        let mut seen_relations = HashSet::new();
+        for item in items {
+            let id = item.issue.id;
+            for blocker in item.blocked_by {
+                storage.dependencies.push((blocker, id));
+            }
+            // A relation is exported with both of its endpoints.
+            for rel in item.relations {
+                if seen_relations.insert(rel.id) {
+                    storage.relations.push(rel);
+                }
+            }
+            let mut notes = item.notes;
+            notes.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+            storage.notes.insert(id, notes);
+            storage.issues.push(item.issue);
+        }
+        storage.issues.sort_by_key(|i| i.id);
+        storage.relations.sort_by_key(|r| r.id);
+        storage
+    }
+
+    /// Set a config value, e.g. an urgency coefficient. Only `--full`
+    /// exports carry the `config` table, so a dashboard applies overrides
+    /// this way.
+    pub fn set_config(&mut self, key: &str, value: &str) {
+        self.config.insert(key.to_string(), value.to_string());
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn issues(&self) -> Result<Vec<Issue>, ItrError> {
+        Ok(self.issues.clone())
+    }
+
+    fn dependencies(&self) -> Result<Vec<(i64, i64)>, ItrError> {
+        Ok(self.dependencies.clone())
+    }
+
+    fn notes(&self, issue_id: i64) -> Result<Vec<Note>, ItrError> {
+        Ok(self.notes.get(&issue_id).cloned().unwrap_or_default())
+    }
+
+    fn note_counts(&self) -> Result<HashMap<i64, i64>, ItrError> {
+        Ok(self
+            .notes
+            .iter()
+            .map(|(id, notes)| (*id, notes.len() as i64))
+            .collect())
+    }
+
+    fn relations(&self) -> Result<Vec<Relation>, ItrError> {
+        Ok(self.relations.clone())
+    }
+
+    fn config_value(&self, key: &str) -> Result<Option<String>, ItrError> {
+        Ok(self.config.get(key).cloned())
+    }
+}
+
+/// Read-only query API over a [`Storage`] backend. Results match what
+/// [`crate::store::ItrStore`] returns for the same data.
+pub struct ReadStore<S: Storage> {
+    storage: S,
+}
+
+impl<S: Storage> ReadStore<S> {
+    pub fn new(storage: S) -> Self {
+        Self { storage }
+    }
+
+    /// The backend, for reads this API doesn't cover.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Issues matching `filter`. Without a column sort they come back
+    /// highest urgency first, like `itr list`, and `limit`/`offset` page
+    /// through that order.
+    pub fn list(&self, filter: &ListFilter) -> Result<Vec<IssueSummary>, ItrError> {
+        let issues = self.storage.issues()?;
+        let index = self.index(&issues)?;
+        let config = self.config();
+
+        let subtree = filter
+            .parent_id
+            .filter(|_| filter.recursive)
+            .map(|root| descendants(&issues, root));
+        let mut matched: Vec<Issue> = issues
+            .into_iter()
+            .filter(|issue| matches(issue, filter, &index, subtree.as_ref()))
+            .collect();
+
+        match filter.sort.as_deref() {
+            Some("priority") => {
+                matched.sort_by_key(|i| (priority_rank(&i.priority), i.id));
+            }
+            Some("created") => {
+                matched.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+            }
+            Some("updated") => {
+                matched.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then(b.id.cmp(&a.id)));
+            }
+            _ => {}
+        }
+        let mut summaries: Vec<IssueSummary> = matched
+            .into_iter()
+            .map(|issue| index.summary(issue, &config))
+            .collect();
+        if filter.sort.is_none() {
+            summaries.sort_by(|a, b| b.urgency.partial_cmp(&a.urgency).unwrap_or(Ordering::Equal));
+        }
+        // Page after sorting, so `limit`/`offset` follow the urgency order.
+        Ok(summaries
+            .into_iter()
+            .skip(filter.offset.unwrap_or(0))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    /// Open and in-progress issues with no active blockers, highest urgency
+    /// first. Issues in `review` are left out, as in `itr ready`.
+    pub fn ready(&self) -> Result<Vec<IssueSummary>, ItrError> {
+        self.list(&ListFilter {
+            statuses: vec!["open".to_string(), "in-progress".to_string()],
+            ..ListFilter::default()
+        })
+    }
+
+    /// Full detail for one issue: urgency breakdown, dependencies, notes,
+    /// and relations.
+    pub fn get(&self, id: i64) -> Result<IssueDetail, ItrError> {
+        let issues = self.storage.issues()?;
+        let index = self.index(&issues)?;
+        let projects: HashMap<i64, String> =
+            issues.iter().map(|i| (i.id, i.project.clone())).collect();
+        let issue = issues
+            .into_iter()
+            .find(|i| i.id == id)
+            .ok_or(ItrError::NotFound(id))?;
+        let (blocked_by, blocks) = (index.blockers(id), index.blocking(id));
+        let qualified_ids = blocked_by
+            .iter()
+            .chain(&blocks)
+            .filter_map(|other| {
+                let name = projects.get(other)?;
+                (!name.is_empty() && *name != issue.project)
+                    .then(|| (*other, crate::util::qualified_id(name, *other)))
+            })
+            .collect();
+        let (urgency, breakdown) =
+            compute_urgency_from_inputs(&issue, &self.config(), index.inputs(id));
+        let notes = self.storage.notes(id)?;
+        let mut relations: Vec<Relation> = self
+            .storage
+            .relations()?
+            .into_iter()
+            .filter(|r| r.source_id == id || r.target_id == id)
+            .collect();
+        relations.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(IssueDetail {
+            issue,
+            urgency,
+            blocked_by,
+            blocks,
+            is_blocked: index.blocked_active.contains(&id),
+            notes,
+            urgency_breakdown: Some(breakdown),
+            children: None,
+            progress: None,
+            relations,
+            qualified_ids,
+            notes_omitted: None,
+            blocked_by_issues: vec![],
+            key: String::new(),
+            commits: 0,
+            external_blockers: vec![],
+            repro: None,
+            referenced_by: vec![],
+        })
+    }
+
+    /// Dependency and relation graph over active issues (every issue with
+    /// `all`), like `itr graph`.
+    pub fn graph(&self, all: bool) -> Result<GraphOutput, ItrError> {
+        let issues = self.storage.issues()?;
+        let deps = self.storage.dependencies()?;
+        let index = Index::build(&issues, deps.clone(), self.storage.note_counts()?);
+        let config = self.config();
+
+        let shown: Vec<&Issue> = issues
+            .iter()
+            .filter(|i| all || !is_terminal(&i.status))
+            .collect();
+        let issue_ids: HashSet<i64> = shown.iter().map(|i| i.id).collect();
+
+        let nodes = shown
+            .iter()
+            .map(|i| {
+                let (urgency, _) = compute_urgency_from_inputs(i, &config, index.inputs(i.id));
+                GraphNode {
+                    id: i.id,
+                    title: i.title.clone(),
+                    status: i.status.clone(),
+                    urgency,
+                    is_blocked: index.blocked_active.contains(&i.id),
+                    project: i.project.clone(),
+                }
+            })
+            .collect();
+
+        let mut edges: Vec<GraphEdge> = deps
+            .iter()
+            .filter(|(blocker, blocked)| issue_ids.contains(blocker) && issue_ids.contains(blocked))
+            .map(|(blocker, blocked)| GraphEdge {
+                from: *blocker,
+                to: *blocked,
+                edge_type: "blocks".to_string(),
+            })
+            .collect();
+        for rel in self.storage.relations()? {
+            if issue_ids.contains(&rel.source_id) && issue_ids.contains(&rel.target_id) {
+                edges.push(GraphEdge {
+                    from: rel.source_id,
+                    to: rel.target_id,
+                    edge_type: rel.relation_type,
+                });
+            }
+        }
+
+        Ok(GraphOutput { nodes, edges })
+    }
+
+    fn index(&self, issues: &[Issue]) -> Result<Index, ItrError> {
+        Ok(Index::build(
+            issues,
+            self.storage.dependencies()?,
+            self.storage.note_counts()?,
+        ))
+    }
+
+    fn config(&self) -> UrgencyConfig {
+        UrgencyConfig::from_lookup(|key| self.storage.config_value(key).ok().flatten())
+    }
+}
+
+/// Dependency edges and note counts for every issue, the in-memory twin of
+/// `db::load_issue_relations`.
+#[derive(Default)]
+struct Index {
+    blockers: HashMap<i64, Vec<i64>>,
+    blocking: HashMap<i64, Vec<i64>>,
+    blocked_active: HashSet<i64>,
+    blocking_active: HashSet<i64>,
+    note_counts: HashMap<i64, i64>,
+}
+
+impl Index {
+    fn build(issues: &[Issue], deps: Vec<(i64, i64)>, note_counts: HashMap<i64, i64>) -> Self {
+        let active: HashSet<i64> = issues
+            .iter()
+            .filter(|i| !is_terminal(&i.status))
+            .map(|i| i.id)
+            .collect();
+        let mut index = Self {
+            note_counts,
+            ..Self::default()
+        };
+        for (blocker, blocked) in deps {
+            index.blockers.entry(blocked).or_default().push(blocker);
+            index.blocking.entry(blocker).or_default().push(blocked);
+            if active.contains(&blocker) {
+                index.blocked_active.insert(blocked);
+            }
+            if active.contains(&blocked) {
+                index.blocking_active.insert(blocker);
+            }
+        }
+        index
+    }
+
+    fn blockers(&self, id: i64) -> Vec<i64> {
+        self.blockers.get(&id).cloned().unwrap_or_default()
+    }
+
+    fn blocking(&self, id: i64) -> Vec<i64> {
+        self.blocking.get(&id).cloned().unwrap_or_default()
+    }
+
+    fn inputs(&self, id: i64) -> UrgencyInputs {
+        UrgencyInputs {
+            blocking: self.blocking_active.contains(&id),
+            blocked: self.blocked_active.contains(&id),
+            note_count: self.note_counts.get(&id).copied().unwrap_or(0),
+        }
+    }
+
+    fn summary(&self, issue: Issue, config: &UrgencyConfig) -> IssueSummary {
+        let (urgency, _) = compute_urgency_from_inputs(&issue, config, self.inputs(issue.id));
+        IssueSummary {
+            urgency,
+            is_blocked: self.blocked_active.contains(&issue.id),
+            blocked_by: self.blockers(issue.id),
+            blocks: self.blocking(issue.id),
+            id: issue.id,
+            title: issue.title,
+            status: issue.status,
+            priority: issue.priority,
+            kind: issue.kind,
+            tags: issue.tags,
+            files: issue.files,
+            skills: issue.skills,
+            acceptance: issue.acceptance,
+            context: issue.context,
+            parent_id: issue.parent_id,
+            close_reason: issue.close_reason,
+            assigned_to: issue.assigned_to,
+            branch: issue.branch,
+            project: issue.project,
+            severity: issue.severity,
+            component: issue.component,
+            affects_version: issue.affects_version,
+            fixed_in_version: issue.fixed_in_version,
+            created_at: issue.created_at,
+            updated_at: issue.updated_at,
+        }
+    }
+}
+
+fn priority_rank(priority: &str) -> u8 {
+    match priority {
+        "critical" => 0,
+        "high" => 1,
+        "medium" => 2,
+        "low" => 3,
+        _ => 4,
+    }
+}
+
+/// Every issue below `root` in the parent tree, cycle-safe.
+fn descendants(issues: &[Issue], root: i64) -> HashSet<i64> {
+    let mut found = HashSet::new();
+    let mut frontier = vec![root];
+    while let Some(parent) = frontier.pop() {
+        for issue in issues.iter().filter(|i| i.parent_id == Some(parent)) {
+            if issue.id != root && found.insert(issue.id) {
+                frontier.push(issue.id);
+            }
+        }
+    }
+    found
+}
+
+/// The `ListFilter` predicate, mirroring `db::list_issues`.
+fn matches(
+    issue: &Issue,
+    filter: &ListFilter,
+    index: &Index,
+    subtree: Option<&HashSet<i64>>,
+) -> bool {
+    if !filter.all {
+        let status_ok = if filter.statuses.is_empty() {
+            !is_terminal(&issue.status)
+        } else {
+            filter.statuses.contains(&issue.status)
+        };
+        if !status_ok {
+            return false;
+        }
+    }
+    if !filter.priorities.is_empty() && !filter.priorities.contains(&issue.priority) {
+        return false;
+    }
+    if !filter.kinds.is_empty() && !filter.kinds.contains(&issue.kind) {
+        return false;
+    }
+    if !filter.severities.is_empty() && !filter.severities.contains(&issue.severity) {
+        return false;
+    }
+    if !filter.components.is_empty() && !filter.components.contains(&issue.component) {
+        return false;
+    }
+    if !filter.affects_versions.is_empty()
+        && !filter.affects_versions.contains(&issue.affects_version)
+    {
+        return false;
+    }
+    if !filter.fixed_in_versions.is_empty()
+        && !filter.fixed_in_versions.contains(&issue.fixed_in_version)
+    {
+        return false;
+    }
+    if let Some(pid) = filter.parent_id {
+        let in_scope = match subtree {
+            Some(ids) => ids.contains(&issue.id),
+            None => issue.parent_id == Some(pid),
+        };
+        if !in_scope {
+            return false;
+        }
+    }
+    if let Some(agent) = &filter.assigned_to {
+        if &issue.assigned_to != agent {
+            return false;
+        }
+    }
+    if let Some(project) = &filter.project {
+        if &issue.project != project {
+            return false;
+        }
+    }
+    if !filter.tags.iter().all(|t| issue.tags.contains(t))
+        || !filter.files.iter().all(|f| issue.files.contains(f))
+        || !filter.skills.iter().all(|s| issue.skills.contains(s))
+    {
+        return false;
+    }
+    if !filter.tag_any.is_empty() && !filter.tag_any.iter().any(|t| issue.tags.contains(t)) {
+        return false;
+    }
+    let blocked = index.blocked_active.contains(&issue.id);
+    if filter.blocked_only {
+        blocked
+    } else {
+        filter.include_blocked || filter.all || !blocked
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::store::{ItrStore, NewIssue};
+
+    /// `itr export` of a store, as JSONL.
+    fn export(store: &ItrStore) -> String {
+        let conn = store.connection();
+        db::all_issues(conn)
+            .unwrap()
+            .into_iter()
+            .map(|issue| {
+                let id = issue.id;
+                serde_json::to_string(&ExportData {
+                    issue,
+                    notes: db::get_notes(conn, id).unwrap(),
+                    blocked_by: db::get_blockers(conn, id).unwrap(),
+                    events: vec![],
+                    relations: db::get_relations(conn, id).unwrap(),
+                    uuid: String::new(),
+                    key: String::new(),
+                    repro: None,
+                    field_times: std::collections::BTreeMap::new(),
+                    blocks: vec![],
+                    children: vec![],
+                })
+                .unwrap()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn ids(summaries: &[IssueSummary]) -> Vec<i64> {
+        summaries.iter().map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn memory_storage_answers_like_the_sqlite_store() {
+        let store = ItrStore::open_in_memory().unwrap();
+        let epic = store
+            .add(NewIssue {
+                kind: "epic".to_string(),
+                ..NewIssue::new("Epic")
+            })
+            .unwrap()
+            .issue
+            .id;
+        let a = store
+            .add(NewIssue {
+                priority: "critical".to_string(),
+                parent_id: Some(epic),
+                tags: vec!["ui".to_string()],
+                ..NewIssue::new("A")
+            })
+            .unwrap()
+            .issue
+            .id;
+        let b = store
+            .add(NewIssue {
+                parent_id: Some(epic),
+                ..NewIssue::new("B")
+            })
+            .unwrap()
+            .issue
+            .id;
+        let c = store.add(NewIssue::new("C")).unwrap().issue.id;
+        store.depend(b, a).unwrap();
+        store.add_note(a, "started", "agent").unwrap();
+        store.close(c, Some("done".to_string()), false).unwrap();
+
+        let mem = ReadStore::new(MemoryStorage::from_export(&export(&store)).unwrap());
+        let sql = ReadStore::new(store.connection());
+
+        for filter in [
+            ListFilter::default(),
+            ListFilter {
+                all: true,
+                ..ListFilter::default()
+            },
+            ListFilter {
+                blocked_only: true,
+                ..ListFilter::default()
+            },
+            ListFilter {
+                parent_id: Some(epic),
+                include_blocked: true,
+                ..ListFilter::default()
+            },
+            ListFilter {
+                tags: vec!["ui".to_string()],
+                ..ListFilter::default()
+            },
+            ListFilter {
+                all: true,
+                sort: Some("priority".to_string()),
+                limit: Some(2),
+                ..ListFilter::default()
+            },
+        ] {
+            let expected = store.list(&filter).unwrap();
+            assert_eq!(ids(&mem.list(&filter).unwrap()), ids(&expected));
+            assert_eq!(ids(&sql.list(&filter).unwrap()), ids(&expected));
+        }
+
+        let detail = mem.get(b).unwrap();
+        assert!(detail.is_blocked);
+        assert_eq!(detail.blocked_by, vec![a]);
+        // Age is measured at call time, so allow for the clock moving.
+        assert!((detail.urgency - store.get(b).unwrap().urgency).abs() < 1e-6);
+        assert_eq!(mem.get(a).unwrap().notes.len(), 1);
+        assert!(matches!(mem.get(999), Err(ItrError::NotFound(999))));
+
+        let graph = mem.graph(false).unwrap();
+        let expected = store.graph(false).unwrap();
+        assert_eq!(graph.nodes.len(), expected.nodes.len());
+        assert_eq!(graph.edges.len(), expected.edges.len());
+    }
+
+    #[test]
+    fn list_pages_by_urgency_without_a_column_sort() {
+        let store = ItrStore::open_in_memory().unwrap();
+        let mut added = Vec::new();
+        for priority in ["low", "critical", "high"] {
+            let issue = store
+                .add(NewIssue {
+                    priority: priority.to_string(),
+                    ..NewIssue::new(priority)
+                })
+                .unwrap()
+                .issue;
+            added.push(issue.id);
+        }
+        let (low, critical, high) = (added[0], added[1], added[2]);
+        let mem = ReadStore::new(MemoryStorage::from_export(&export(&store)).unwrap());
+
+        let first = ListFilter {
+            limit: Some(1),
+            ..ListFilter::default()
+        };
+        assert_eq!(ids(&mem.list(&first).unwrap()), vec![critical]);
+        let second = ListFilter {
+            limit: Some(1),
+            offset: Some(1),
+            ..ListFilter::default()
+        };
+        assert_eq!(ids(&mem.list(&second).unwrap()), vec![high]);
+        let by_id = ListFilter {
+            sort: Some("created".to_string()),
+            limit: Some(1),
+            ..ListFilter::default()
+        };
+        assert_eq!(ids(&mem.list(&by_id).unwrap()), vec![low]);
+    }
+
+    #[test]
+    fn ready_leaves_out_review_issues() {
+        let store = ItrStore::open_in_memory().unwrap();
+        let open = store.add(NewIssue::new("open")).unwrap().issue.id;
+        let review = store.add(NewIssue::new("review")).unwrap().issue.id;
+        store.update_field(review, "status", "review").unwrap();
+        let mem = ReadStore::new(MemoryStorage::from_export(&export(&store)).unwrap());
+
+        assert_eq!(ids(&mem.ready().unwrap()), vec![open]);
+        assert_eq!(
+            ids(&ReadStore::new(store.connection()).ready().unwrap()),
+            vec![open]
+        );
+    }
+
+    #[test]
+    fn from_export_accepts_a_json_array() {
+        let store = ItrStore::open_in_memory().unwrap();
+        store.add(NewIssue::new("Only")).unwrap();
+        let items: Vec<serde_json::Value> = export(&store)
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        let json = serde_json::to_string_pretty(&items).unwrap();
+        let mem = ReadStore::new(MemoryStorage::from_export(&json).unwrap());
+        assert_eq!(mem.ready().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,892 @@
+//! Typed, print-free API over an `.itr.db`: [`ItrStore`] plus the shared
+//! write paths the CLI commands are built on. Every method returns its result
+//! (or an [`ItrError`]) instead of printing; soft fallbacks are recorded as
+//! `REVIEW:` notes on the affected issue, exactly as the CLI does.
+
+use crate::db;
+use crate::detail::{build_issue_detail, build_issue_summaries, sort_by_urgency_desc};
+use crate::error::ItrError;
+use crate::models::{
+    default_kind, default_priority, GraphEdge, GraphNode, GraphOutput, Issue, IssueDetail,
+    IssueSummary, ListFilter, Note, Repro, UnblockedIssue,
+};
+use crate::normalize::{
+    self, validate_kind, validate_priority, validate_severity, validate_status,
+};
+use crate::urgency::{self, UrgencyConfig};
+use rusqlite::Connection;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Input for [`add_issue`] / [`ItrStore::add`]. `review_notes` carries
+/// `REVIEW:` notes accumulated while parsing (invalid `blocked_by` tokens,
+/// unrecognized JSON fields, ...); they are attached to the new issue.
+///
+/// Deserializes from the same object shape as an `add --stdin-json` payload:
+/// only `title` is required, and `blocked_by` is accepted for
+/// `blocked_by_ids`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewIssue {
+    pub title: String,
+    #[serde(default = "default_priority")]
+    pub priority: String,
+    #[serde(default = "default_kind")]
+    pub kind: String,
+    #[serde(default)]
+    pub context: String,
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    #[serde(default)]
+    pub acceptance: String,
+    #[serde(default, alias = "parent")]
+    pub parent_id: Option<i64>,
+    #[serde(default)]
+    pub assigned_to: String,
+    /// `blocker`, `major`, `minor`, or `trivial` (synonyms accepted); empty
+    /// for none.
+    #[serde(default)]
+    pub severity: String,
+    /// Registered component name, see `itr component`; empty for none.
+    #[serde(default)]
+    pub component: String,
+    /// Release the issue was seen in; empty for none.
+    #[serde(default)]
+    pub affects_version: String,
+    /// Release that fixes it; empty for none.
+    #[serde(default)]
+    pub fixed_in_version: String,
+    /// Structured reproduction; `None` (or empty) for none.
+    #[serde(default)]
+    pub repro: Option<Repro>,
+    /// Estimate in points; `None` for none.
+    #[serde(default)]
+    pub estimate: Option<i64>,
+    /// Existing project to file the issue under (the CLI passes its
+    /// `--project` / `project.current` scope); empty for none.
+    #[serde(skip)]
+    pub project: String,
+    #[serde(default, alias = "blocked_by")]
+    pub blocked_by_ids: Vec<i64>,
+    #[serde(default)]
+    pub review_notes: Vec<String>,
+}
+
+impl NewIssue {
+    /// A medium-priority task with no other fields set.
+    pub fn new(title: impl Into<String>) -> Self {
+        NewIssue {
+            title: title.into(),
+            priority: "medium".to_string(),
+            kind: "task".to_string(),
+            context: String::new(),
+            files: Vec::new(),
+            tags: Vec::new(),
+            skills: Vec::new(),
+            acceptance: String::new(),
+            parent_id: None,
+            assigned_to: String::new(),
+            severity: String::new(),
+            component: String::new(),
+            affects_version: String::new(),
+            fixed_in_version: String::new(),
+            repro: None,
+            estimate: None,
+            project: String::new(),
+            blocked_by_ids: Vec::new(),
+            review_notes: Vec::new(),
+        }
+    }
+}
+
+/// Validate, insert, and link a parsed add request. Returns the detail of the
+/// created issue. Soft fallbacks: unrecognized priority/kind default with a
+/// REVIEW note; a nonexistent parent creates the issue parentless with a
+/// REVIEW note (#167). A nonexistent `blocked_by` ID remains a hard `NotFound`
+/// error (whole insert rolls back), matching the documented CLI contract.
+pub fn add_issue(conn: &Connection, req: NewIssue) -> Result<IssueDetail, ItrError> {
+    let mut review_notes = req.review_notes;
+
+    let priority = normalize::normalize_priority(&req.priority);
+    let kind = normalize::normalize_kind(&req.kind);
+
+    let priority = match validate_priority(&priority) {
+        Ok(()) => priority,
+        Err(e) => {
+            review_notes.push(format!(
+                "REVIEW: priority '{}' not recognized{}, defaulted to 'medium'. Valid: critical, high, medium, low",
+                priority,
+                e.did_you_mean()
+            ));
+            "medium".to_string()
+        }
+    };
+    let kind = match validate_kind(&kind) {
+        Ok(()) => kind,
+        Err(e) => {
+            review_notes.push(format!(
+                "REVIEW: kind '{}' not recognized{}, defaulted to 'task'. Valid: bug, feature, task, epic",
+                kind,
+                e.did_you_mean()
+            ));
+            "task".to_string()
+        }
+    };
+    let severity = severity_or_review(&req.severity, &mut review_notes);
+
+    let tx = db::begin_write(conn)?;
+
+    // Soft fallback (#167): a parent that doesn't exist would otherwise
+    // surface as a raw FOREIGN KEY constraint error.
+    let parent_id = match req.parent_id {
+        Some(p) if !db::issue_exists(&tx, p)? => {
+            review_notes.push(format!(
+                "REVIEW: parent {p} not found; issue created without a parent"
+            ));
+            None
+        }
+        other => other,
+    };
+
+    let mut tags_vec = req.tags;
+    let mut assigned_to = req.assigned_to;
+    let component = apply_component(
+        &tx,
+        &req.component,
+        &mut assigned_to,
+        &mut tags_vec,
+        &mut review_notes,
+    )?;
+    if !review_notes.is_empty() && !tags_vec.contains(&"_needs_review".to_string()) {
+        tags_vec.push("_needs_review".to_string());
+    }
+
+    let mut issue = db::insert_issue(
+        &tx,
+        &req.title,
+        &priority,
+        &kind,
+        &req.context,
+        &req.files,
+        &tags_vec,
+        &req.skills,
+        &req.acceptance,
+        parent_id,
+        &assigned_to,
+    )?;
+    if !req.project.is_empty() {
+        db::file_new_issue(&tx, issue.id, &req.project)?;
+        issue.project = db::get_issue(&tx, issue.id)?.project;
+    }
+    if !severity.is_empty() {
+        db::update_issue_field(&tx, issue.id, "severity", &severity)?;
+        issue.severity = severity;
+    }
+    if !component.is_empty() {
+        db::set_component_id(&tx, issue.id, &component)?;
+        issue.component = component;
+    }
+    set_versions(&tx, &mut issue, &req.affects_version, &req.fixed_in_version)?;
+    if let Some(repro) = req.repro {
+        db::set_repro(&tx, issue.id, Some(&repro.normalized()))?;
+    }
+    if req.estimate.is_some() {
+        db::set_estimate(&tx, issue.id, req.estimate)?;
+        issue.estimate = req.estimate;
+    }
+
+    // Add review notes
+    for note_text in &review_notes {
+        db::add_note(&tx, issue.id, note_text, "itr")?;
+    }
+
+    // Add dependencies
+    for blocker_id in &req.blocked_by_ids {
+        db::add_dependency(&tx, *blocker_id, issue.id)?;
+    }
+
+    tx.commit()?;
+
+    // Build detail for output
+    let config = UrgencyConfig::load(conn);
+    build_issue_detail(conn, issue, &config)
+}
+
+/// Record a new issue's affected and fixed-in versions, trimmed; blank
+/// leaves either unset.
+pub fn set_versions(
+    conn: &Connection,
+    issue: &mut Issue,
+    affects_version: &str,
+    fixed_in_version: &str,
+) -> Result<(), ItrError> {
+    let affects_version = affects_version.trim();
+    if !affects_version.is_empty() {
+        db::update_issue_field(conn, issue.id, "affects_version", affects_version)?;
+        issue.affects_version = affects_version.to_string();
+    }
+    let fixed_in_version = fixed_in_version.trim();
+    if !fixed_in_version.is_empty() {
+        db::update_issue_field(conn, issue.id, "fixed_in_version", fixed_in_version)?;
+        issue.fixed_in_version = fixed_in_version.to_string();
+    }
+    Ok(())
+}
+
+/// Normalize a requested severity for a new issue. Blank means none; an
+/// unrecognized value is left unset with a REVIEW note rather than guessed.
+pub fn severity_or_review(raw: &str, review_notes: &mut Vec<String>) -> String {
+    let severity = normalize::normalize_severity(raw.trim());
+    if severity.is_empty() {
+        return severity;
+    }
+    match validate_severity(&severity) {
+        Ok(()) => severity,
+        Err(e) => {
+            review_notes.push(format!(
+                "REVIEW: severity '{}' not recognized{}, left unset. Valid: {}",
+                severity,
+                e.did_you_mean(),
+                normalize::SEVERITIES
+            ));
+            String::new()
+        }
+    }
+}
+
+/// Resolve the component requested for a new issue and route it: an empty
+/// assignee takes the component's default assignee, and its default tags are
+/// added. Returns the component name to set, empty for none; an unregistered
+/// name is left off with a REVIEW note.
+pub fn apply_component(
+    conn: &Connection,
+    raw: &str,
+    assigned_to: &mut String,
+    tags: &mut Vec<String>,
+    review_notes: &mut Vec<String>,
+) -> Result<String, ItrError> {
+    let name = raw.trim();
+    if name.is_empty() {
+        return Ok(String::new());
+    }
+    let Some(component) = db::get_component(conn, name)? else {
+        review_notes.push(unknown_component_note(conn, name, "left unset")?);
+        return Ok(String::new());
+    };
+    if assigned_to.is_empty() {
+        assigned_to.clone_from(&component.default_assignee);
+    }
+    for tag in component.default_tags {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    Ok(component.name)
+}
+
+/// `REVIEW:` note for a component name that is not registered, saying what
+/// was done instead (`left unset`, `kept 'api'`).
+pub fn unknown_component_note(
+    conn: &Connection,
+    name: &str,
+    outcome: &str,
+) -> Result<String, ItrError> {
+    let names: Vec<String> = db::components(conn)?.into_iter().map(|c| c.name).collect();
+    let valid = if names.is_empty() {
+        "none yet; register one with `itr component add`".to_string()
+    } else {
+        names.join(", ")
+    };
+    Ok(format!(
+        "REVIEW: component '{}' is not registered, {}. Valid: {}",
+        name, outcome, valid
+    ))
+}
+
+/// Apply all close writes (optional duplicate relation, status event, status
+/// flip, optional `close_reason` event + field, dependency-edge cleanup)
+/// inside a single transaction so a mid-close failure leaves the issue fully
+/// unchanged, and build the output detail from the updated state before
+/// committing.
+pub fn close_issue(
+    conn: &Connection,
+    id: i64,
+    reason: Option<String>,
+    wontfix: bool,
+    duplicate_of: Option<i64>,
+) -> Result<(IssueDetail, Vec<(i64, String)>), ItrError> {
+    let reason = reason.unwrap_or_default();
+
+    let status = if wontfix { "wontfix" } else { "done" };
+
+    let tx = db::begin_write(conn)?;
+
+    if let Some(dup_id) = duplicate_of {
+        db::add_relation(&tx, id, dup_id, "duplicate")?;
+    }
+
+    // Capture old values for event recording
+    let old_issue = db::get_issue(&tx, id)?;
+
+    db::record_event(&tx, id, "status", &old_issue.status, status)?;
+    db::update_issue_field(&tx, id, "status", status)?;
+    if !reason.is_empty() {
+        db::record_event(&tx, id, "close_reason", &old_issue.close_reason, &reason)?;
+        db::update_issue_field(&tx, id, "close_reason", &reason)?;
+    }
+
+    // Auto-clean dependency edges where this issue was the blocker
+    let unblocked = db::get_newly_unblocked(&tx, id)?;
+    db::remove_blocker_edges(&tx, id)?;
+
+    // Build the output detail from the updated state
+    let issue = db::get_issue(&tx, id)?;
+    let config = UrgencyConfig::load(&tx);
+    let detail = build_issue_detail(&tx, issue, &config)?;
+
+    tx.commit()?;
+    Ok((detail, unblocked))
+}
+
+/// Nodes for issues not yet closed (every issue with `all`), joined by
+/// `blocks` edges and relation edges whose ends are both in the node set.
+/// `project` keeps only that project's issues.
+pub fn dependency_graph(
+    conn: &Connection,
+    all: bool,
+    project: Option<String>,
+) -> Result<GraphOutput, ItrError> {
+    let issues = if all {
+        let mut issues = db::all_issues(conn)?;
+        if let Some(project) = project {
+            issues.retain(|i| i.project == project);
+        }
+        issues
+    } else {
+        db::list_issues(
+            conn,
+            &ListFilter {
+                include_blocked: true,
+                project,
+                ..ListFilter::default()
+            },
+        )?
+    };
+
+    let config = UrgencyConfig::load(conn);
+    let deps = db::all_dependencies(conn)?;
+    let relations = db::load_issue_relations(conn)?;
+
+    let issue_ids: HashSet<i64> = issues.iter().map(|i| i.id).collect();
+
+    let nodes: Vec<GraphNode> = issues
+        .iter()
+        .map(|i| {
+            let (urg, _) =
+                urgency::compute_urgency_from_inputs(i, &config, relations.urgency_inputs(i.id));
+            let is_blocked = relations.is_blocked(i.id);
+            GraphNode {
+                id: i.id,
+                title: i.title.clone(),
+                status: i.status.clone(),
+                urgency: urg,
+                is_blocked,
+                project: i.project.clone(),
+            }
+        })
+        .collect();
+
+    let mut edges: Vec<GraphEdge> = deps
+        .iter()
+        .filter(|(blocker, blocked)| issue_ids.contains(blocker) && issue_ids.contains(blocked))
+        .map(|(blocker, blocked)| GraphEdge {
+            from: *blocker,
+            to: *blocked,
+            edge_type: "blocks".to_string(),
+        })
+        .collect();
+
+    // Add relation edges
+    let relations = db::all_relations(conn)?;
+    for rel in &relations {
+        if issue_ids.contains(&rel.source_id) && issue_ids.contains(&rel.target_id) {
+            edges.push(GraphEdge {
+                from: rel.source_id,
+                to: rel.target_id,
+                edge_type: rel.relation_type.clone(),
+            });
+        }
+    }
+
+    Ok(GraphOutput { nodes, edges })
+}
+
+/// The part of `graph` within `depth` hops of `center`, following edges of
+/// every type in both directions. Node and edge order is kept; a `center`
+/// that isn't a node yields an empty graph.
+pub fn graph_neighborhood(graph: GraphOutput, center: i64, depth: usize) -> GraphOutput {
+    let mut adjacent: HashMap<i64, Vec<i64>> = HashMap::new();
+    for edge in &graph.edges {
+        adjacent.entry(edge.from).or_default().push(edge.to);
+        adjacent.entry(edge.to).or_default().push(edge.from);
+    }
+    let mut kept: HashSet<i64> = HashSet::new();
+    if graph.nodes.iter().any(|n| n.id == center) {
+        kept.insert(center);
+    }
+    let mut frontier: Vec<i64> = kept.iter().copied().collect();
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for id in frontier {
+            for &neighbor in adjacent.get(&id).into_iter().flatten() {
+                if kept.insert(neighbor) {
+                    next.push(neighbor);
+                }
+            }
+        }
+        frontier = next;
+    }
+    GraphOutput {
+        nodes: graph
+            .nodes
+            .into_iter()
+            .filter(|n| kept.contains(&n.id))
+            .collect(),
+        edges: graph
+            .edges
+            .into_iter()
+            .filter(|e| kept.contains(&e.from) && kept.contains(&e.to))
+            .collect(),
+    }
+}
+
+/// `graph` without the `blocks` edges implied by a longer chain of `blocks`
+/// edges: when 1 blocks 2 and 2 blocks 3, the edge from 1 to 3 goes. Relation
+/// edges and every node are kept.
+pub fn graph_transitive_reduction(mut graph: GraphOutput) -> GraphOutput {
+    let mut blocks: HashMap<i64, Vec<i64>> = HashMap::new();
+    for edge in graph.edges.iter().filter(|e| e.edge_type == "blocks") {
+        blocks.entry(edge.from).or_default().push(edge.to);
+    }
+    // Reachable from `from` through at least two edges, so not via the direct one.
+    let implied = |from: i64, to: i64| {
+        let mut seen: HashSet<i64> = HashSet::new();
+        let mut stack: Vec<i64> = blocks[&from].iter().copied().filter(|&n| n != to).collect();
+        while let Some(id) = stack.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            for &next in blocks.get(&id).into_iter().flatten() {
+                if next == to {
+                    return true;
+                }
+                stack.push(next);
+            }
+        }
+        false
+    };
+    graph
+        .edges
+        .retain(|e| e.edge_type != "blocks" || !implied(e.from, e.to));
+    graph
+}
+
+/// Only the nodes with no incoming `blocks` edge (`roots`) or with no
+/// outgoing one (leaves), and the edges among them.
+pub fn graph_ends(mut graph: GraphOutput, roots: bool) -> GraphOutput {
+    let linked: HashSet<i64> = graph
+        .edges
+        .iter()
+        .filter(|e| e.edge_type == "blocks")
+        .map(|e| if roots { e.to } else { e.from })
+        .collect();
+    graph.nodes.retain(|n| !linked.contains(&n.id));
+    let kept: HashSet<i64> = graph.nodes.iter().map(|n| n.id).collect();
+    graph
+        .edges
+        .retain(|e| kept.contains(&e.from) && kept.contains(&e.to));
+    graph
+}
+
+/// Fields [`ItrStore::update_field`] accepts. List-valued fields (tags,
+/// files, skills) and the parent link have their own merge rules and stay on
+/// the CLI `update` path.
+pub const UPDATABLE_FIELDS: &[&str] = &[
+    "title",
+    "status",
+    "priority",
+    "kind",
+    "context",
+    "acceptance",
+    "assigned_to",
+];
+
+/// An open tracker database. Owns one connection; every write runs in its
+/// own transaction (see [`db::begin_write`]).
+#[derive(Debug)]
+pub struct ItrStore {
+    conn: Connection,
+}
+
+impl ItrStore {
+    /// Open an existing database, applying pending migrations.
+    pub fn open(path: &Path) -> Result<Self, ItrError> {
+        Ok(ItrStore {
+            conn: db::open_db(path)?,
+        })
+    }
+
+    /// Create (or upgrade) a database at `path`.
+    pub fn init(path: &Path) -> Result<Self, ItrError> {
+        Ok(ItrStore {
+            conn: db::init_db(path)?,
+        })
+    }
+
+    /// A fresh database that lives only as long as the store.
+    pub fn open_in_memory() -> Result<Self, ItrError> {
+        Self::init(Path::new(":memory:"))
+    }
+
+    /// The underlying connection, for the `db` functions this API does not wrap.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    pub fn add(&self, issue: NewIssue) -> Result<IssueDetail, ItrError> {
+        add_issue(&self.conn, issue)
+    }
+
+    /// Detail for one issue, including notes and relations.
+    pub fn get(&self, id: i64) -> Result<IssueDetail, ItrError> {
+        let issue = db::get_issue(&self.conn, id)?;
+        let config = UrgencyConfig::load(&self.conn);
+        let mut detail = build_issue_detail(&self.conn, issue, &config)?;
+        detail.relations = db::get_relations(&self.conn, id)?;
+        Ok(detail)
+    }
+
+    /// Issues matching `filter`. Without a column sort they come back
+    /// highest urgency first, like `itr list`, and `limit`/`offset` page
+    /// through that order rather than the SQL one.
+    pub fn list(&self, filter: &ListFilter) -> Result<Vec<IssueSummary>, ItrError> {
+        if filter.sort.is_some() {
+            let issues = db::list_issues(&self.conn, filter)?;
+            let config = UrgencyConfig::load(&self.conn);
+            return Ok(build_issue_summaries(&self.conn, issues, &config));
+        }
+        let unpaged = ListFilter {
+            limit: None,
+            offset: None,
+            ..filter.clone()
+        };
+        let issues = db::list_issues(&self.conn, &unpaged)?;
+        let config = UrgencyConfig::load(&self.conn);
+        let mut summaries = build_issue_summaries(&self.conn, issues, &config);
+        sort_by_urgency_desc(&mut summaries);
+        Ok(summaries
+            .into_iter()
+            .skip(filter.offset.unwrap_or(0))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    /// Open and in-progress issues with no active blockers, highest urgency
+    /// first. Issues in `review` are left out, as in `itr ready`.
+    pub fn ready(&self) -> Result<Vec<IssueSummary>, ItrError> {
+        self.list(&ListFilter {
+            statuses: vec!["open".to_string(), "in-progress".to_string()],
+            ..ListFilter::default()
+        })
+    }
+
+    /// The highest-urgency open, unblocked issue, optionally restricted to
+    /// issues needing one of `skills` — what `itr next` would pick.
+    pub fn next(&self, skills: &[String]) -> Result<Option<IssueDetail>, ItrError> {
+        let candidates = self.list(&ListFilter {
+            statuses: vec!["open".to_string()],
+            skills: skills.to_vec(),
+            ..ListFilter::default()
+        })?;
+        candidates.first().map(|top| self.get(top.id)).transpose()
+    }
+
+    /// Dependency and relation graph; see [`dependency_graph`].
+    pub fn graph(&self, all: bool) -> Result<GraphOutput, ItrError> {
+        dependency_graph(&self.conn, all, None)
+    }
+
+    /// Set one of [`UPDATABLE_FIELDS`], recording a history event. Status,
+    /// priority, and kind are normalized and must be valid.
+    pub fn update_field(&self, id: i64, field: &str, value: &str) -> Result<IssueDetail, ItrError> {
+        self.update_fields(id, &[(field, value)])
+    }
+
+    /// [`ItrStore::update_field`] for several fields in one transaction: any
+    /// invalid field or value rejects the whole update.
+    pub fn update_fields(
+        &self,
+        id: i64,
+        changes: &[(&str, &str)],
+    ) -> Result<IssueDetail, ItrError> {
+        let mut normalized = Vec::with_capacity(changes.len());
+        for &(field, value) in changes {
+            let value = match field {
+                "status" => normalize::normalize_status(value),
+                "priority" => normalize::normalize_priority(value),
+                "kind" => normalize::normalize_kind(value),
+                _ if UPDATABLE_FIELDS.contains(&field) => value.to_string(),
+                _ => {
+                    return Err(ItrError::InvalidValue {
+                        field: "field".to_string(),
+                        value: field.to_string(),
+                        valid: UPDATABLE_FIELDS.join(", "),
+                    })
+                }
+            };
+            match field {
+                "status" => validate_status(&value)?,
+                "priority" => validate_priority(&value)?,
+                "kind" => validate_kind(&value)?,
+                _ => {}
+            }
+            normalized.push((field, value));
+        }
+
+        let tx = db::begin_write(&self.conn)?;
+        let old = serde_json::to_value(db::get_issue(&tx, id)?)?;
+        for (field, value) in &normalized {
+            let old = old[*field].as_str().unwrap_or_default();
+            db::record_event(&tx, id, field, old, value)?;
+            db::update_issue_field(&tx, id, field, value)?;
+        }
+        tx.commit()?;
+        self.get(id)
+    }
+
+    /// Close as done (or wontfix), returning the closed issue and the issues
+    /// it no longer blocks.
+    pub fn close(
+        &self,
+        id: i64,
+        reason: Option<String>,
+        wontfix: bool,
+    ) -> Result<(IssueDetail, Vec<UnblockedIssue>), ItrError> {
+        let (detail, unblocked) = close_issue(&self.conn, id, reason, wontfix, None)?;
+        let unblocked = unblocked
+            .into_iter()
+            .map(|(id, title)| UnblockedIssue { id, title })
+            .collect();
+        Ok((detail, unblocked))
+    }
+
+    /// Mark `blocked` as blocked by `on`. Returns `false` if the edge already
+    /// existed; a cycle is [`ItrError::CycleDetected`].
+    pub fn depend(&self, blocked: i64, on: i64) -> Result<bool, ItrError> {
+        let tx = db::begin_write(&self.conn)?;
+        let added = db::add_dependency(&tx, on, blocked)?;
+        tx.commit()?;
+        Ok(added)
+    }
+
+    pub fn add_note(&self, id: i64, text: &str, agent: &str) -> Result<Note, ItrError> {
+        let tx = db::begin_write(&self.conn)?;
+        let note = db::add_note(&tx, id, text, agent)?;
+        tx.commit()?;
+        Ok(note)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ready_leaves_out_review_issues() {
+        let store = ItrStore::open_in_memory().unwrap();
+        let open = store.add(NewIssue::new("open")).unwrap().issue.id;
+        let wip = store.add(NewIssue::new("wip")).unwrap().issue.id;
+        let review = store.add(NewIssue::new("review")).unwrap().issue.id;
+        store.update_field(wip, "status", "in-progress").unwrap();
+        store.update_field(review, "status", "review").unwrap();
+
+        let mut ready: Vec<i64> = store.ready().unwrap().iter().map(|s| s.id).collect();
+        ready.sort_unstable();
+        assert_eq!(ready, vec![open, wip]);
+        let listed = store.list(&ListFilter::default()).unwrap();
+        assert!(listed.iter().any(|s| s.id == review));
+    }
+
+    #[test]
+    fn store_round_trips_add_depend_close() {
+        let store = ItrStore::open_in_memory().unwrap();
+        let blocker = store.add(NewIssue::new("blocker")).unwrap().issue.id;
+        let mut input = NewIssue::new("blocked");
+        input.priority = "HIGH".to_string();
+        let blocked = store.add(input).unwrap().issue.id;
+        assert!(store.depend(blocked, blocker).unwrap());
+
+        let ready: Vec<i64> = store.ready().unwrap().iter().map(|s| s.id).collect();
+        assert_eq!(ready, vec![blocker]);
+        assert_eq!(store.next(&[]).unwrap().unwrap().issue.id, blocker);
+        let graph = store.graph(false).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!((graph.edges[0].from, graph.edges[0].to), (blocker, blocked));
+
+        let (closed, unblocked) = store.close(blocker, Some("done".into()), false).unwrap();
+        assert_eq!(closed.issue.status, "done");
+        assert_eq!(unblocked.len(), 1);
+        assert_eq!(unblocked[0].id, blocked);
+
+        let detail = store.update_field(blocked, "status", "wip").unwrap();
+        assert_eq!(detail.issue.status, "in-progress");
+        assert_eq!(detail.issue.priority, "high");
+        assert!(matches!(
+            store.update_field(blocked, "tags", "x"),
+            Err(ItrError::InvalidValue { .. })
+        ));
+        assert!(matches!(store.get(999), Err(ItrError::NotFound(999))));
+    }
+
+    #[test]
+    fn add_files_under_the_requested_project() {
+        let store = ItrStore::open_in_memory().unwrap();
+        db::add_project(store.connection(), "api", "").unwrap();
+        db::set_project_key_prefix(store.connection(), "api", "API").unwrap();
+        let mut input = NewIssue::new("filed");
+        input.project = "api".to_string();
+        let filed = store.add(input).unwrap();
+        assert_eq!(filed.issue.project, "api");
+        assert_eq!(filed.key, "API-1");
+        let loose = store.add(NewIssue::new("loose")).unwrap().issue.id;
+
+        let in_api = store
+            .list(&ListFilter {
+                project: Some("api".to_string()),
+                ..ListFilter::default()
+            })
+            .unwrap();
+        assert_eq!(in_api.len(), 1);
+        assert_eq!(in_api[0].id, filed.issue.id);
+        // Without a project every issue is listed.
+        assert_eq!(store.list(&ListFilter::default()).unwrap().len(), 2);
+        assert_eq!(store.get(loose).unwrap().issue.project, "");
+    }
+
+    #[test]
+    fn list_pages_by_urgency_without_a_column_sort() {
+        let store = ItrStore::open_in_memory().unwrap();
+        for (title, priority) in [("low", "low"), ("critical", "critical"), ("high", "high")] {
+            let mut input = NewIssue::new(title);
+            input.priority = priority.to_string();
+            store.add(input).unwrap();
+        }
+        let titles = |filter: ListFilter| -> Vec<String> {
+            store
+                .list(&filter)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.title)
+                .collect()
+        };
+        assert_eq!(
+            titles(ListFilter {
+                limit: Some(1),
+                ..ListFilter::default()
+            }),
+            vec!["critical"]
+        );
+        assert_eq!(
+            titles(ListFilter {
+                limit: Some(1),
+                offset: Some(1),
+                ..ListFilter::default()
+            }),
+            vec!["high"]
+        );
+        assert_eq!(
+            titles(ListFilter {
+                sort: Some("id".to_string()),
+                limit: Some(1),
+                ..ListFilter::default()
+            }),
+            vec!["low"],
+            "a column sort still pages in SQL"
+        );
+    }
+
+    #[test]
+    fn graph_neighborhood_follows_edges_both_ways_up_to_depth() {
+        let store = ItrStore::open_in_memory().unwrap();
+        let ids: Vec<i64> = (0..5)
+            .map(|n| {
+                store
+                    .add(NewIssue::new(format!("issue {n}")))
+                    .unwrap()
+                    .issue
+                    .id
+            })
+            .collect();
+        // 0 -> 1 -> 2 -> 3, and 4 on its own.
+        for pair in ids[..4].windows(2) {
+            assert!(store.depend(pair[1], pair[0]).unwrap());
+        }
+        let node_ids = |graph: &GraphOutput| graph.nodes.iter().map(|n| n.id).collect::<Vec<_>>();
+
+        let near = graph_neighborhood(store.graph(false).unwrap(), ids[1], 1);
+        assert_eq!(node_ids(&near), ids[..3]);
+        assert_eq!(near.edges.len(), 2);
+        let far = graph_neighborhood(store.graph(false).unwrap(), ids[1], 2);
+        assert_eq!(node_ids(&far), ids[..4]);
+        let alone = graph_neighborhood(store.graph(false).unwrap(), ids[4], 3);
+        assert_eq!(node_ids(&alone), vec![ids[4]]);
+        assert!(alone.edges.is_empty());
+    }
+
+    #[test]
+    fn graph_reduction_drops_only_implied_blocks_edges() {
+        let store = ItrStore::open_in_memory().unwrap();
+        let ids: Vec<i64> = (0..4)
+            .map(|n| {
+                store
+                    .add(NewIssue::new(format!("issue {n}")))
+                    .unwrap()
+                    .issue
+                    .id
+            })
+            .collect();
+        // 0 -> 1 -> 2, plus the shortcut 0 -> 2; 3 hangs off 0 alone.
+        assert!(store.depend(ids[1], ids[0]).unwrap());
+        assert!(store.depend(ids[2], ids[1]).unwrap());
+        assert!(store.depend(ids[2], ids[0]).unwrap());
+        assert!(store.depend(ids[3], ids[0]).unwrap());
+        let edges = |graph: &GraphOutput| {
+            let mut pairs: Vec<(i64, i64)> = graph.edges.iter().map(|e| (e.from, e.to)).collect();
+            pairs.sort_unstable();
+            pairs
+        };
+
+        let reduced = graph_transitive_reduction(store.graph(false).unwrap());
+        assert_eq!(reduced.nodes.len(), 4);
+        assert_eq!(
+            edges(&reduced),
+            vec![(ids[0], ids[1]), (ids[0], ids[3]), (ids[1], ids[2])]
+        );
+
+        let roots = graph_ends(store.graph(false).unwrap(), true);
+        assert_eq!(
+            roots.nodes.iter().map(|n| n.id).collect::<Vec<_>>(),
+            vec![ids[0]]
+        );
+        let leaves = graph_ends(store.graph(false).unwrap(), false);
+        assert_eq!(
+            leaves.nodes.iter().map(|n| n.id).collect::<Vec<_>>(),
+            vec![ids[2], ids[3]]
+        );
+        assert!(leaves.edges.is_empty());
+    }
+}
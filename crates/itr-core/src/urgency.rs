@@ -1,6 +1,9 @@
+#[cfg(feature = "sqlite")]
 use crate::db;
+use crate::formula::Formula;
 use crate::models::{Issue, UrgencyBreakdown};
 use crate::util;
+#[cfg(feature = "sqlite")]
 use rusqlite::Connection;
 
 /// Coefficient table for the urgency formula.
@@ -26,6 +29,10 @@ pub struct UrgencyConfig {
     pub priority_high: f64,
     pub priority_medium: f64,
     pub priority_low: f64,
+    pub severity_blocker: f64,
+    pub severity_major: f64,
+    pub severity_minor: f64,
+    pub severity_trivial: f64,
     pub blocking: f64,
     pub blocked: f64,
     pub age: f64,
@@ -36,8 +43,14 @@ pub struct UrgencyConfig {
     pub kind_epic: f64,
     pub in_progress: f64,
     pub notes_count: f64,
+    /// Optional `urgency.formula` that combines the weighted components
+    /// instead of summing them. `None` keeps the additive model.
+    pub formula: Option<Formula>,
 }
 
+/// Config key holding the optional urgency formula expression.
+pub const FORMULA_KEY: &str = "urgency.formula";
+
 impl Default for UrgencyConfig {
     fn default() -> Self {
         Self {
@@ -45,6 +58,10 @@ impl Default for UrgencyConfig {
             priority_high: 6.0,
             priority_medium: 3.0,
             priority_low: 1.0,
+            severity_blocker: 6.0,
+            severity_major: 3.0,
+            severity_minor: 0.0,
+            severity_trivial: -1.0,
             blocking: 8.0,
             blocked: -10.0,
             age: 2.0,
@@ -55,6 +72,7 @@ impl Default for UrgencyConfig {
             kind_epic: -2.0,
             in_progress: 4.0,
             notes_count: 0.5,
+            formula: None,
         }
     }
 }
@@ -63,8 +81,8 @@ impl UrgencyConfig {
     /// Build a config seeded with defaults, then overlay any per-key overrides
     /// found in the database's `config` table.
     ///
-    /// Unknown keys are ignored and unparseable values emit a `REVIEW:` note
-    /// on stderr — defaults stay in place either way. This is the standard
+    /// Unknown keys are ignored and unparseable values emit a `REVIEW:`
+    /// diagnostic note — defaults stay in place either way. This is the standard
     /// soft-fallback behavior for the urgency system: misconfiguration
     /// degrades to defaults rather than failing the command.
     ///
@@ -76,42 +94,61 @@ impl UrgencyConfig {
     /// let cfg = UrgencyConfig::load(&conn);
     /// assert!(cfg.priority_critical >= 0.0);
     /// ```
+    #[cfg(feature = "sqlite")]
     pub fn load(conn: &Connection) -> Self {
-        let mut config = Self::default();
+        Self::from_lookup(|key| db::config_get(conn, key).ok().flatten())
+    }
 
-        // We'll load each key individually since we can't easily iterate mut refs
-        Self::load_key(
-            conn,
-            "urgency.priority.critical",
-            &mut config.priority_critical,
-        );
-        Self::load_key(conn, "urgency.priority.high", &mut config.priority_high);
-        Self::load_key(conn, "urgency.priority.medium", &mut config.priority_medium);
-        Self::load_key(conn, "urgency.priority.low", &mut config.priority_low);
-        Self::load_key(conn, "urgency.blocking", &mut config.blocking);
-        Self::load_key(conn, "urgency.blocked", &mut config.blocked);
-        Self::load_key(conn, "urgency.age", &mut config.age);
-        Self::load_key(conn, "urgency.has_acceptance", &mut config.has_acceptance);
-        Self::load_key(conn, "urgency.kind.bug", &mut config.kind_bug);
-        Self::load_key(conn, "urgency.kind.feature", &mut config.kind_feature);
-        Self::load_key(conn, "urgency.kind.task", &mut config.kind_task);
-        Self::load_key(conn, "urgency.kind.epic", &mut config.kind_epic);
-        Self::load_key(conn, "urgency.in_progress", &mut config.in_progress);
-        Self::load_key(conn, "urgency.notes_count", &mut config.notes_count);
+    /// [`UrgencyConfig::load`] over any key/value source, for storage
+    /// backends without a `config` table (see [`crate::storage::Storage`]).
+    /// `lookup` returns the raw config value for a key, or `None` when unset.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let mut config = Self::default();
 
-        config
-    }
+        for (key, target) in [
+            ("urgency.priority.critical", &mut config.priority_critical),
+            ("urgency.priority.high", &mut config.priority_high),
+            ("urgency.priority.medium", &mut config.priority_medium),
+            ("urgency.priority.low", &mut config.priority_low),
+            ("urgency.severity.blocker", &mut config.severity_blocker),
+            ("urgency.severity.major", &mut config.severity_major),
+            ("urgency.severity.minor", &mut config.severity_minor),
+            ("urgency.severity.trivial", &mut config.severity_trivial),
+            ("urgency.blocking", &mut config.blocking),
+            ("urgency.blocked", &mut config.blocked),
+            ("urgency.age", &mut config.age),
+            ("urgency.has_acceptance", &mut config.has_acceptance),
+            ("urgency.kind.bug", &mut config.kind_bug),
+            ("urgency.kind.feature", &mut config.kind_feature),
+            ("urgency.kind.task", &mut config.kind_task),
+            ("urgency.kind.epic", &mut config.kind_epic),
+            ("urgency.in_progress", &mut config.in_progress),
+            ("urgency.notes_count", &mut config.notes_count),
+        ] {
+            if let Some(val) = lookup(key) {
+                match val.parse::<f64>() {
+                    Ok(v) => *target = v,
+                    Err(_) => crate::util::diagnostic(&format!(
+                        "REVIEW: config value '{}' for '{}' is not numeric; urgency engine is using the default {}",
+                        val, key, target
+                    )),
+                }
+            }
+        }
 
-    fn load_key(conn: &Connection, key: &str, target: &mut f64) {
-        if let Ok(Some(val)) = db::config_get(conn, key) {
-            match val.parse::<f64>() {
-                Ok(v) => *target = v,
-                Err(_) => eprintln!(
-                    "REVIEW: config value '{}' for '{}' is not numeric; urgency engine is using the default {}",
-                    val, key, target
-                ),
+        if let Some(src) = lookup(FORMULA_KEY) {
+            if !src.trim().is_empty() {
+                match Formula::parse(&src) {
+                    Ok(f) => config.formula = Some(f),
+                    Err(e) => crate::util::diagnostic(&format!(
+                        "REVIEW: '{}' does not parse ({}); urgency engine is using the additive model",
+                        FORMULA_KEY, e
+                    )),
+                }
             }
         }
+
+        config
     }
 
     /// Return the default coefficient table as a list of
@@ -135,6 +172,10 @@ impl UrgencyConfig {
             ("urgency.priority.high", d.priority_high),
             ("urgency.priority.medium", d.priority_medium),
             ("urgency.priority.low", d.priority_low),
+            ("urgency.severity.blocker", d.severity_blocker),
+            ("urgency.severity.major", d.severity_major),
+            ("urgency.severity.minor", d.severity_minor),
+            ("urgency.severity.trivial", d.severity_trivial),
             ("urgency.blocking", d.blocking),
             ("urgency.blocked", d.blocked),
             ("urgency.age", d.age),
@@ -165,41 +206,12 @@ impl UrgencyConfig {
     pub fn closest_key(key: &str) -> Option<&'static str> {
         Self::defaults_map()
             .iter()
-            .map(|(k, _)| (*k, levenshtein(key, k)))
+            .map(|(k, _)| (*k, util::levenshtein(key, k)))
             .min_by_key(|(_, dist)| *dist)
             .map(|(k, _)| k)
     }
 }
 
-/// Classic two-row Levenshtein edit distance over bytes.
-///
-/// Hand-rolled to keep the dependency footprint at zero; config keys are
-/// short ASCII, so byte-wise comparison is exact enough for suggestions.
-fn levenshtein(a: &str, b: &str) -> usize {
-    let a = a.as_bytes();
-    let b = b.as_bytes();
-    if a.is_empty() {
-        return b.len();
-    }
-    if b.is_empty() {
-        return a.len();
-    }
-
-    let mut prev: Vec<usize> = (0..=b.len()).collect();
-    let mut curr = vec![0usize; b.len() + 1];
-
-    for (i, &ca) in a.iter().enumerate() {
-        curr[0] = i + 1;
-        for (j, &cb) in b.iter().enumerate() {
-            let cost = usize::from(ca != cb);
-            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
-        }
-        std::mem::swap(&mut prev, &mut curr);
-    }
-
-    prev[b.len()]
-}
-
 /// Thin wrapper around [`compute_urgency_with_breakdown`] that returns just
 /// the scalar score.
 ///
@@ -216,6 +228,7 @@ fn levenshtein(a: &str, b: &str) -> usize {
 /// let score = compute_urgency(&issue, &cfg, &conn);
 /// assert!(score.is_finite());
 /// ```
+#[cfg(feature = "sqlite")]
 pub fn compute_urgency(issue: &Issue, config: &UrgencyConfig, conn: &Connection) -> f64 {
     let (score, _) = compute_urgency_with_breakdown(issue, config, conn);
     score
@@ -223,10 +236,13 @@ pub fn compute_urgency(issue: &Issue, config: &UrgencyConfig, conn: &Connection)
 
 /// Score an issue and return both the total and the per-component breakdown.
 ///
-/// Urgency is always computed fresh from the current state of the issue and
-/// its relations — it is never persisted. The components combined are:
+/// The DB-derived inputs (blocking, blocked, note count) come from the
+/// `urgency_cache` table when it holds a clean row for the issue; otherwise
+/// they are queried and the cache is refreshed. Age and coefficients are
+/// always applied fresh. The components combined are:
 ///
 /// - `priority.<bucket>` — coefficient lookup keyed by priority
+/// - `severity.<bucket>` — coefficient lookup keyed by severity, only when set
 /// - `kind.<bucket>` — coefficient lookup keyed by kind (epics may be negative)
 /// - `blocking` — added when this issue blocks any other active issue
 /// - `blocked` — subtracted when this issue is blocked
@@ -235,8 +251,13 @@ pub fn compute_urgency(issue: &Issue, config: &UrgencyConfig, conn: &Connection)
 /// - `has_acceptance` — added when the acceptance field is non-empty
 /// - `notes` — `config.notes_count * min(notes / 6, 1)`
 ///
-/// DB lookup failures degrade to neutral defaults with a `REVIEW:` note on
-/// stderr — the scorer never panics or errors out a list command.
+/// With an `urgency.formula` configured, the score is the formula evaluated
+/// over those component values instead of their sum, and a trailing
+/// `formula` component carries the difference so the breakdown still sums
+/// to the score.
+///
+/// DB lookup failures degrade to neutral defaults with a `REVIEW:`
+/// diagnostic note — the scorer never panics or errors out a list command.
 ///
 /// # Examples
 ///
@@ -249,13 +270,41 @@ pub fn compute_urgency(issue: &Issue, config: &UrgencyConfig, conn: &Connection)
 /// let total: f64 = breakdown.components.iter().map(|(_, v)| v).sum();
 /// assert!((total - score).abs() < 1e-9);
 /// ```
+#[cfg(feature = "sqlite")]
 pub fn compute_urgency_with_breakdown(
     issue: &Issue,
     config: &UrgencyConfig,
     conn: &Connection,
+) -> (f64, UrgencyBreakdown) {
+    let (inputs, cache_miss) = urgency_inputs(conn, issue.id);
+    let (score, breakdown) = compute_urgency_from_inputs(issue, config, inputs);
+    if cache_miss {
+        // Best effort: a read-only database just keeps missing the cache.
+        let _ = db::store_urgency_cache(conn, issue.id, inputs, score);
+    }
+    (score, breakdown)
+}
+
+/// DB-derived urgency inputs for one issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UrgencyInputs {
+    pub blocking: bool,
+    pub blocked: bool,
+    pub note_count: i64,
+}
+
+/// Score an issue from already-loaded DB inputs, touching no database.
+///
+/// Batch callers load every issue's inputs at once with
+/// [`db::load_issue_relations`] and score through here; single-issue callers
+/// go through [`compute_urgency_with_breakdown`], which reads the cache.
+pub fn compute_urgency_from_inputs(
+    issue: &Issue,
+    config: &UrgencyConfig,
+    inputs: UrgencyInputs,
 ) -> (f64, UrgencyBreakdown) {
     let mut score = 0.0;
-    let mut components = Vec::with_capacity(7);
+    let mut components = Vec::with_capacity(8);
 
     // Priority
     let priority_val = match issue.priority.as_str() {
@@ -268,6 +317,19 @@ pub fn compute_urgency_with_breakdown(
     score += priority_val;
     components.push((format!("priority.{}", issue.priority), priority_val));
 
+    // Severity, when the issue has one
+    let severity_val = match issue.severity.as_str() {
+        "blocker" => config.severity_blocker,
+        "major" => config.severity_major,
+        "minor" => config.severity_minor,
+        "trivial" => config.severity_trivial,
+        _ => 0.0,
+    };
+    if !issue.severity.is_empty() {
+        score += severity_val;
+        components.push((format!("severity.{}", issue.severity), severity_val));
+    }
+
     // Kind
     let kind_val = match issue.kind.as_str() {
         "bug" => config.kind_bug,
@@ -280,27 +342,13 @@ pub fn compute_urgency_with_breakdown(
     components.push((format!("kind.{}", issue.kind), kind_val));
 
     // Blocking others
-    let is_blocking = db::blocks_active_issues(conn, issue.id).unwrap_or_else(|e| {
-        eprintln!(
-            "REVIEW: DB query failed checking if #{} blocks others (treating as not blocking): {}",
-            issue.id, e
-        );
-        false
-    });
-    if is_blocking {
+    if inputs.blocking {
         score += config.blocking;
         components.push(("blocking".to_string(), config.blocking));
     }
 
     // Blocked by others
-    let is_blocked = db::is_blocked(conn, issue.id).unwrap_or_else(|e| {
-        eprintln!(
-            "REVIEW: DB query failed checking if #{} is blocked (treating as not blocked): {}",
-            issue.id, e
-        );
-        false
-    });
-    if is_blocked {
+    if inputs.blocked {
         score += config.blocked;
         components.push(("blocked".to_string(), config.blocked));
     }
@@ -325,13 +373,7 @@ pub fn compute_urgency_with_breakdown(
     }
 
     // Notes count
-    let notes = db::count_notes(conn, issue.id).unwrap_or_else(|e| {
-        eprintln!(
-            "REVIEW: DB query failed counting notes for #{} (treating as 0): {}",
-            issue.id, e
-        );
-        0
-    });
+    let notes = inputs.note_count;
     let notes_factor = (notes as f64 / 6.0).min(1.0);
     let notes_val = config.notes_count * notes_factor;
     score += notes_val;
@@ -339,10 +381,79 @@ pub fn compute_urgency_with_breakdown(
         components.push(("notes".to_string(), notes_val));
     }
 
-    (score, UrgencyBreakdown { components })
+    if let Some(formula) = &config.formula {
+        let value = |name: &str| -> f64 {
+            match name {
+                "priority" => priority_val,
+                "severity" => severity_val,
+                "kind" => kind_val,
+                "age" => age_val,
+                "notes" => notes_val,
+                "age_days" => age_days.max(0.0),
+                "note_count" => notes as f64,
+                other => components
+                    .iter()
+                    .find(|(k, _)| k == other)
+                    .map_or(0.0, |(_, v)| *v),
+            }
+        };
+        let formula_score = formula.eval(value);
+        let adjustment = formula_score - score;
+        if adjustment != 0.0 {
+            components.push(("formula".to_string(), adjustment));
+        }
+        score = formula_score;
+    }
+
+    (
+        score,
+        UrgencyBreakdown {
+            components,
+            skill_match: None,
+        },
+    )
 }
 
-#[cfg(test)]
+/// Blocking/blocked/note-count inputs for `issue_id`, from the urgency cache
+/// when clean, else queried fresh. The flag reports a cache miss, so the
+/// caller can store the fresh inputs once the score is known.
+#[cfg(feature = "sqlite")]
+fn urgency_inputs(conn: &Connection, issue_id: i64) -> (UrgencyInputs, bool) {
+    if let Ok(Some(inputs)) = db::cached_urgency_inputs(conn, issue_id) {
+        return (inputs, false);
+    }
+    let blocking = db::blocks_active_issues(conn, issue_id).unwrap_or_else(|e| {
+        crate::util::diagnostic(&format!(
+            "REVIEW: DB query failed checking if #{} blocks others (treating as not blocking): {}",
+            issue_id, e
+        ));
+        false
+    });
+    let blocked = db::is_blocked(conn, issue_id).unwrap_or_else(|e| {
+        crate::util::diagnostic(&format!(
+            "REVIEW: DB query failed checking if #{} is blocked (treating as not blocked): {}",
+            issue_id, e
+        ));
+        false
+    });
+    let note_count = db::count_notes(conn, issue_id).unwrap_or_else(|e| {
+        crate::util::diagnostic(&format!(
+            "REVIEW: DB query failed counting notes for #{} (treating as 0): {}",
+            issue_id, e
+        ));
+        0
+    });
+    (
+        UrgencyInputs {
+            blocking,
+            blocked,
+            note_count,
+        },
+        true,
+    )
+}
+
+#[cfg(all(test, feature = "sqlite"))]
 mod tests {
     use super::*;
 
@@ -445,6 +556,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn formula_replaces_sum_and_breakdown_still_adds_up() {
+        let conn = test_conn();
+        db::config_set(&conn, FORMULA_KEY, "priority*2 + min(notes, 0) - blocked").unwrap();
+        let issue = add_issue(&conn, "high", "bug");
+        let config = UrgencyConfig::load(&conn);
+        let (score, breakdown) = compute_urgency_with_breakdown(&issue, &config, &conn);
+        assert!((score - 12.0).abs() < 1e-9, "6*2 + 0 - 0, got {score}");
+        let total: f64 = breakdown.components.iter().map(|(_, v)| v).sum();
+        assert!((total - score).abs() < 1e-9);
+        assert!(component(&breakdown, "formula").is_some());
+    }
+
+    #[test]
+    fn unparseable_formula_falls_back_to_additive() {
+        let conn = test_conn();
+        db::config_set(&conn, FORMULA_KEY, "priority +").unwrap();
+        assert!(UrgencyConfig::load(&conn).formula.is_none());
+    }
+
     // --- #183: load keeps defaults when a stored value is not numeric ---
 
     #[test]
@@ -469,13 +600,4 @@ mod tests {
             Some("urgency.notes_count")
         );
     }
-
-    #[test]
-    fn levenshtein_basics() {
-        assert_eq!(levenshtein("", ""), 0);
-        assert_eq!(levenshtein("abc", ""), 3);
-        assert_eq!(levenshtein("", "abc"), 3);
-        assert_eq!(levenshtein("kitten", "sitting"), 3);
-        assert_eq!(levenshtein("same", "same"), 0);
-    }
 }
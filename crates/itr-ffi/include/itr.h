@@ -0,0 +1,70 @@
+/*
+ * C ABI for the itr issue tracker (crates/itr-ffi).
+ *
+ * Every function returning `char *` returns a JSON string owned by the
+ * library: the result on success, or {"error": "...", "code": "..."} on
+ * failure, with the same codes as `itr -f json` (NOT_FOUND, INVALID_VALUE,
+ * PARSE_ERROR, CYCLE_DETECTED, DB_ERROR, ...). Release it with
+ * itr_string_free(). A handle is not thread-safe; use one per thread.
+ */
+#ifndef ITR_H
+#define ITR_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct ItrHandle ItrHandle;
+
+/*
+ * Open the database at `path`, creating it when `create` is non-zero.
+ * Returns NULL on failure; `error_out` (may be NULL) then receives an error
+ * JSON string.
+ */
+ItrHandle *itr_open(const char *path, int32_t create, char **error_out);
+
+/* Close a handle. NULL is a no-op. */
+void itr_free(ItrHandle *handle);
+
+/* Release a string returned by this library. NULL is a no-op. */
+void itr_string_free(char *s);
+
+/*
+ * JSON array of issue summaries, highest urgency first unless "sort" names a
+ * column; "limit" and "offset" page through that order. `filter_json` may be
+ * NULL or an object with any of: "status", "priority", "kind", "tag",
+ * "skill" (string arrays), "assigned_to" (string), "parent" (id),
+ * "blocked", "all" (bools), "sort" ("id"|"priority"|"created"|"updated"),
+ * "limit", "offset".
+ */
+char *itr_list(const ItrHandle *handle, const char *filter_json);
+
+/* Issue detail: notes, blockers, relations, urgency breakdown. */
+char *itr_get(const ItrHandle *handle, int64_t id);
+
+/*
+ * Create an issue from an `itr add --stdin-json` style object; only "title"
+ * is required. Returns the new issue's detail.
+ */
+char *itr_add(const ItrHandle *handle, const char *issue_json);
+
+/*
+ * Apply {"field": "value", ...} in one transaction. Fields: title, status,
+ * priority, kind, context, acceptance, assigned_to.
+ */
+char *itr_update(const ItrHandle *handle, int64_t id, const char *changes_json);
+
+/*
+ * Close as done, or wontfix when `wontfix` is non-zero; `reason` may be
+ * NULL. The result carries an "unblocked" array when other issues became
+ * ready.
+ */
+char *itr_close(const ItrHandle *handle, int64_t id, const char *reason, int32_t wontfix);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ITR_H */
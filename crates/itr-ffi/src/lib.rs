@@ -0,0 +1,330 @@
+//! C ABI over [`itr_core::ItrStore`] for editor plugins and non-Rust agent
+//! runtimes that want the tracker in-process. The declarations live in
+//! `include/itr.h`.
+//!
+//! Every call that returns `char *` returns JSON: the result on success, or
+//! `{"error": "...", "code": "NOT_FOUND"}` (the same codes as the CLI's
+//! `-f json` errors) on failure. Release returned strings with
+//! [`itr_string_free`] and handles with [`itr_free`].
+
+use itr_core::error::ItrError;
+use itr_core::models::ListFilter;
+use itr_core::store::{ItrStore, NewIssue};
+use serde::Deserialize;
+use std::ffi::{c_char, CStr, CString};
+use std::path::Path;
+
+/// Opaque handle returned by [`itr_open`].
+pub struct ItrHandle {
+    store: ItrStore,
+}
+
+/// `itr_list` filter object. Keys mirror the `itr list` flags; `{}` lists
+/// open, in-progress, and review issues, blocked ones included, highest
+/// urgency first.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ListInput {
+    status: Vec<String>,
+    priority: Vec<String>,
+    kind: Vec<String>,
+    tag: Vec<String>,
+    skill: Vec<String>,
+    assigned_to: Option<String>,
+    parent: Option<i64>,
+    blocked: bool,
+    all: bool,
+    sort: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+impl From<ListInput> for ListFilter {
+    fn from(input: ListInput) -> Self {
+        ListFilter {
+            statuses: input.status,
+            priorities: input.priority,
+            kinds: input.kind,
+            tags: input.tag,
+            skills: input.skill,
+            assigned_to: input.assigned_to,
+            parent_id: input.parent,
+            blocked_only: input.blocked,
+            include_blocked: true,
+            all: input.all,
+            sort: input.sort,
+            limit: input.limit,
+            offset: input.offset,
+            ..ListFilter::default()
+        }
+    }
+}
+
+fn invalid(field: &str, value: &str, valid: &str) -> ItrError {
+    ItrError::InvalidValue {
+        field: field.to_string(),
+        value: value.to_string(),
+        valid: valid.to_string(),
+    }
+}
+
+fn error_json(err: &ItrError) -> String {
+    serde_json::json!({ "error": err.to_string(), "code": err.error_code() }).to_string()
+}
+
+/// Hand a JSON string to the caller. Interior NULs cannot occur in
+/// `serde_json` output, so the fallback is unreachable in practice.
+fn to_c(json: String) -> *mut c_char {
+    CString::new(json)
+        .unwrap_or_else(|_| CString::from(c"{\"error\":\"interior NUL\",\"code\":\"IO_ERROR\"}"))
+        .into_raw()
+}
+
+fn respond(result: Result<serde_json::Value, ItrError>) -> *mut c_char {
+    to_c(match result {
+        Ok(value) => value.to_string(),
+        Err(e) => error_json(&e),
+    })
+}
+
+/// Borrow a C string argument; `NULL` reads as `None`.
+///
+/// # Safety
+/// `ptr` must be `NULL` or a valid NUL-terminated string.
+unsafe fn arg<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, ItrError> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map(Some)
+        .map_err(|_| invalid(name, "<non-UTF-8>", "UTF-8 text"))
+}
+
+/// # Safety
+/// `handle` must be `NULL` or a live pointer from [`itr_open`].
+unsafe fn store<'a>(handle: *const ItrHandle) -> Result<&'a ItrStore, ItrError> {
+    handle
+        .as_ref()
+        .map(|h| &h.store)
+        .ok_or_else(|| invalid("handle", "NULL", "a handle from itr_open"))
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Result<serde_json::Value, ItrError> {
+    Ok(serde_json::to_value(value)?)
+}
+
+/// Open the database at `path` (created if `create` is non-zero). Returns
+/// `NULL` on failure; when `error_out` is non-`NULL` it then receives an
+/// error JSON string to release with [`itr_string_free`].
+///
+/// # Safety
+/// `path` must be a valid NUL-terminated string; `error_out` must be `NULL`
+/// or point to writable storage for one pointer.
+#[no_mangle]
+pub unsafe extern "C" fn itr_open(
+    path: *const c_char,
+    create: i32,
+    error_out: *mut *mut c_char,
+) -> *mut ItrHandle {
+    let opened = arg(path, "path").and_then(|p| {
+        let p = p.ok_or_else(|| invalid("path", "NULL", "a database path"))?;
+        if create != 0 {
+            ItrStore::init(Path::new(p))
+        } else {
+            ItrStore::open(Path::new(p))
+        }
+    });
+    match opened {
+        Ok(store) => Box::into_raw(Box::new(ItrHandle { store })),
+        Err(e) => {
+            if !error_out.is_null() {
+                *error_out = to_c(error_json(&e));
+            }
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Close a handle from [`itr_open`]. `NULL` is a no-op.
+///
+/// # Safety
+/// `handle` must be `NULL` or a pointer from [`itr_open`] not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn itr_free(handle: *mut ItrHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Release a string returned by any `itr_*` call. `NULL` is a no-op.
+///
+/// # Safety
+/// `s` must be `NULL` or a string returned by this library, not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn itr_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// List issues. `filter_json` is a filter object (see `include/itr.h`) or
+/// `NULL` for the default view. Returns a JSON array of issue summaries.
+///
+/// # Safety
+/// `handle` must come from [`itr_open`]; `filter_json` must be `NULL` or a
+/// valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn itr_list(
+    handle: *const ItrHandle,
+    filter_json: *const c_char,
+) -> *mut c_char {
+    respond((|| {
+        let store = store(handle)?;
+        let input: ListInput = match arg(filter_json, "filter")? {
+            Some(json) => serde_json::from_str(json)?,
+            None => ListInput::default(),
+        };
+        to_value(&store.list(&input.into())?)
+    })())
+}
+
+/// Issue detail (notes, dependencies, relations, urgency breakdown).
+///
+/// # Safety
+/// `handle` must come from [`itr_open`].
+#[no_mangle]
+pub unsafe extern "C" fn itr_get(handle: *const ItrHandle, id: i64) -> *mut c_char {
+    respond(store(handle).and_then(|s| to_value(&s.get(id)?)))
+}
+
+/// Create an issue from a JSON object shaped like an `add --stdin-json`
+/// payload (`title` required). Returns the new issue's detail.
+///
+/// # Safety
+/// `handle` must come from [`itr_open`]; `issue_json` must be a valid
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn itr_add(
+    handle: *const ItrHandle,
+    issue_json: *const c_char,
+) -> *mut c_char {
+    respond((|| {
+        let store = store(handle)?;
+        let json = arg(issue_json, "issue")?.unwrap_or("null");
+        let issue: NewIssue = serde_json::from_str(json)?;
+        to_value(&store.add(issue)?)
+    })())
+}
+
+/// Apply `{"field": "value", ...}` to one issue in a single transaction.
+/// Fields: `title`, `status`, `priority`, `kind`, `context`, `acceptance`,
+/// `assigned_to`. Returns the updated detail.
+///
+/// # Safety
+/// `handle` must come from [`itr_open`]; `changes_json` must be a valid
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn itr_update(
+    handle: *const ItrHandle,
+    id: i64,
+    changes_json: *const c_char,
+) -> *mut c_char {
+    respond((|| {
+        let store = store(handle)?;
+        let json = arg(changes_json, "changes")?.unwrap_or("null");
+        let changes: serde_json::Map<String, serde_json::Value> = serde_json::from_str(json)?;
+        let mut pairs = Vec::with_capacity(changes.len());
+        for (field, value) in &changes {
+            let value = value
+                .as_str()
+                .ok_or_else(|| invalid(field, &value.to_string(), "a string"))?;
+            pairs.push((field.as_str(), value));
+        }
+        to_value(&store.update_fields(id, &pairs)?)
+    })())
+}
+
+/// Close an issue as done, or wontfix when `wontfix` is non-zero. `reason`
+/// may be `NULL`. Returns the detail plus an `unblocked` array when closing
+/// it unblocked other issues, like `itr close -f json`.
+///
+/// # Safety
+/// `handle` must come from [`itr_open`]; `reason` must be `NULL` or a valid
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn itr_close(
+    handle: *const ItrHandle,
+    id: i64,
+    reason: *const c_char,
+    wontfix: i32,
+) -> *mut c_char {
+    respond((|| {
+        let store = store(handle)?;
+        let reason = arg(reason, "reason")?.map(str::to_string);
+        let (detail, unblocked) = store.close(id, reason, wontfix != 0)?;
+        let mut value = to_value(&detail)?;
+        if !unblocked.is_empty() {
+            value["unblocked"] = to_value(&unblocked)?;
+        }
+        Ok(value)
+    })())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(ptr: *mut c_char) -> serde_json::Value {
+        assert!(!ptr.is_null());
+        let value = unsafe { serde_json::from_str(CStr::from_ptr(ptr).to_str().unwrap()).unwrap() };
+        unsafe { itr_string_free(ptr) };
+        value
+    }
+
+    #[test]
+    fn json_round_trip_through_the_c_abi() {
+        let path = CString::new(":memory:").unwrap();
+        let handle = unsafe { itr_open(path.as_ptr(), 1, std::ptr::null_mut()) };
+        assert!(!handle.is_null());
+
+        let blocker = call(unsafe { itr_add(handle, c"{\"title\":\"blocker\"}".as_ptr()) });
+        let blocker_id = blocker["id"].as_i64().unwrap();
+        let blocked = CString::new(format!(
+            "{{\"title\":\"blocked\",\"priority\":\"urgent\",\"blocked_by\":[{blocker_id}]}}"
+        ))
+        .unwrap();
+        let blocked = call(unsafe { itr_add(handle, blocked.as_ptr()) });
+        let blocked_id = blocked["id"].as_i64().unwrap();
+        assert_eq!(blocked["priority"], "critical");
+
+        let listed = call(unsafe { itr_list(handle, c"{\"blocked\":true}".as_ptr()) });
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+        assert_eq!(listed[0]["id"], blocked_id);
+
+        let updated =
+            call(unsafe { itr_update(handle, blocked_id, c"{\"status\":\"wip\"}".as_ptr()) });
+        assert_eq!(updated["status"], "in-progress");
+
+        let closed = call(unsafe { itr_close(handle, blocker_id, c"done".as_ptr(), 0) });
+        assert_eq!(closed["status"], "done");
+        assert_eq!(closed["unblocked"][0]["id"], blocked_id);
+
+        let missing = call(unsafe { itr_get(handle, 999) });
+        assert_eq!(missing["code"], "NOT_FOUND");
+        let bad = call(unsafe { itr_update(handle, blocked_id, c"{\"tags\":\"x\"}".as_ptr()) });
+        assert_eq!(bad["code"], "INVALID_VALUE");
+
+        unsafe { itr_free(handle) };
+    }
+
+    #[test]
+    fn open_failure_reports_error_json() {
+        let path = CString::new("/nonexistent-dir/for/itr/.itr.db").unwrap();
+        let mut err: *mut c_char = std::ptr::null_mut();
+        let handle = unsafe { itr_open(path.as_ptr(), 0, &raw mut err) };
+        assert!(handle.is_null());
+        assert!(call(err)["code"].is_string());
+        assert_eq!(call(unsafe { itr_get(handle, 1) })["code"], "INVALID_VALUE");
+    }
+}
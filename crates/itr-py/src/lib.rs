@@ -0,0 +1,369 @@
+//! Python bindings over [`itr_core::ItrStore`], imported as `itr`:
+//!
+//! ```python
+//! import itr
+//!
+//! store = itr.Store(".itr.db")
+//! issue = store.add("Fix login timeout", priority="high", tags=["auth"])
+//! for summary in store.list(status=["open"], tag=["auth"]):
+//!     print(summary["id"], summary["urgency"])
+//! store.close(issue["id"], "fixed")
+//! ```
+//!
+//! Results are plain dicts and lists with the same keys as `itr -f json`.
+//! Failures raise `itr.ItrError(message, code)`, where `code` is the CLI's
+//! error code (`NOT_FOUND`, `INVALID_VALUE`, ...).
+
+use itr_core::error::ItrError as CoreError;
+use itr_core::models::ListFilter;
+use itr_core::store::{ItrStore, NewIssue};
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::path::Path;
+
+pyo3::create_exception!(
+    itr,
+    ItrError,
+    PyException,
+    "An itr operation failed; args are (message, code)."
+);
+
+fn py_err(err: &CoreError) -> PyErr {
+    ItrError::new_err((err.to_string(), err.error_code()))
+}
+
+/// Convert any serializable result into native Python objects by way of
+/// `json.loads`, so key names and value shapes match `itr -f json` exactly.
+fn to_py<T: serde::Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(|e| py_err(&e.into()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+/// An open `.itr.db`. Not shareable across threads; open one per thread.
+#[pyclass(unsendable, module = "itr")]
+struct Store {
+    inner: ItrStore,
+}
+
+#[pymethods]
+impl Store {
+    /// Open the database at `path`; `create=True` creates it if missing.
+    #[new]
+    #[pyo3(signature = (path, create = false))]
+    fn new(path: &str, create: bool) -> PyResult<Self> {
+        let opened = if create {
+            ItrStore::init(Path::new(path))
+        } else {
+            ItrStore::open(Path::new(path))
+        };
+        opened.map(|inner| Store { inner }).map_err(|e| py_err(&e))
+    }
+
+    /// A throwaway database that lives as long as the store.
+    #[staticmethod]
+    fn in_memory() -> PyResult<Self> {
+        ItrStore::open_in_memory()
+            .map(|inner| Store { inner })
+            .map_err(|e| py_err(&e))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        title, *, priority = "medium", kind = "task", context = "", files = None,
+        tags = None, skills = None, acceptance = "", parent_id = None,
+        assigned_to = "", blocked_by = None
+    ))]
+    fn add(
+        &self,
+        py: Python<'_>,
+        title: &str,
+        priority: &str,
+        kind: &str,
+        context: &str,
+        files: Option<Vec<String>>,
+        tags: Option<Vec<String>>,
+        skills: Option<Vec<String>>,
+        acceptance: &str,
+        parent_id: Option<i64>,
+        assigned_to: &str,
+        blocked_by: Option<Vec<i64>>,
+    ) -> PyResult<PyObject> {
+        let issue = NewIssue {
+            priority: priority.to_string(),
+            kind: kind.to_string(),
+            context: context.to_string(),
+            files: files.unwrap_or_default(),
+            tags: tags.unwrap_or_default(),
+            skills: skills
+                .unwrap_or_default()
+                .iter()
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            acceptance: acceptance.to_string(),
+            parent_id,
+            assigned_to: assigned_to.to_string(),
+            blocked_by_ids: blocked_by.unwrap_or_default(),
+            ..NewIssue::new(title)
+        };
+        let detail = self.inner.add(issue).map_err(|e| py_err(&e))?;
+        to_py(py, &detail)
+    }
+
+    /// Issue summaries, highest urgency first unless `sort` names a column
+    /// (`id`, `priority`, `created`, `updated`); `limit` and `offset` page
+    /// through that order. Keywords mirror `itr list`.
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        *, status = None, priority = None, kind = None, tag = None, skill = None,
+        assigned_to = None, parent = None, blocked = false, all = false,
+        sort = None, limit = None, offset = None
+    ))]
+    fn list(
+        &self,
+        py: Python<'_>,
+        status: Option<Vec<String>>,
+        priority: Option<Vec<String>>,
+        kind: Option<Vec<String>>,
+        tag: Option<Vec<String>>,
+        skill: Option<Vec<String>>,
+        assigned_to: Option<String>,
+        parent: Option<i64>,
+        blocked: bool,
+        all: bool,
+        sort: Option<String>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> PyResult<PyObject> {
+        let filter = ListFilter {
+            statuses: status.unwrap_or_default(),
+            priorities: priority.unwrap_or_default(),
+            kinds: kind.unwrap_or_default(),
+            tags: tag.unwrap_or_default(),
+            skills: skill.unwrap_or_default(),
+            assigned_to,
+            parent_id: parent,
+            blocked_only: blocked,
+            include_blocked: true,
+            all,
+            sort,
+            limit,
+            offset,
+            ..ListFilter::default()
+        };
+        let summaries = self.inner.list(&filter).map_err(|e| py_err(&e))?;
+        to_py(py, &summaries)
+    }
+
+    /// Open and in-progress issues with no active blockers.
+    fn ready(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let summaries = self.inner.ready().map_err(|e| py_err(&e))?;
+        to_py(py, &summaries)
+    }
+
+    fn get(&self, py: Python<'_>, id: i64) -> PyResult<PyObject> {
+        let detail = self.inner.get(id).map_err(|e| py_err(&e))?;
+        to_py(py, &detail)
+    }
+
+    /// What `itr next` would pick, or `None` when nothing is eligible.
+    #[pyo3(signature = (*, skills = None))]
+    fn next(&self, py: Python<'_>, skills: Option<Vec<String>>) -> PyResult<PyObject> {
+        let skills = skills.unwrap_or_default();
+        let next = self.inner.next(&skills).map_err(|e| py_err(&e))?;
+        to_py(py, &next)
+    }
+
+    /// Set string fields in one transaction, e.g. `update(4, status="wip")`.
+    #[pyo3(signature = (id, **fields))]
+    fn update(
+        &self,
+        py: Python<'_>,
+        id: i64,
+        fields: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<PyObject> {
+        let mut changes: Vec<(String, String)> = Vec::new();
+        if let Some(fields) = fields {
+            for (key, value) in fields {
+                changes.push((key.extract()?, value.extract()?));
+            }
+        }
+        let pairs: Vec<(&str, &str)> = changes
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        let detail = self
+            .inner
+            .update_fields(id, &pairs)
+            .map_err(|e| py_err(&e))?;
+        to_py(py, &detail)
+    }
+
+    /// Close as done (or wontfix). The result carries an `unblocked` list
+    /// of the issues this close made ready.
+    #[pyo3(signature = (id, reason = None, *, wontfix = false))]
+    fn close(
+        &self,
+        py: Python<'_>,
+        id: i64,
+        reason: Option<String>,
+        wontfix: bool,
+    ) -> PyResult<PyObject> {
+        let (detail, unblocked) = self
+            .inner
+            .close(id, reason, wontfix)
+            .map_err(|e| py_err(&e))?;
+        let mut value = serde_json::to_value(&detail).map_err(|e| py_err(&e.into()))?;
+        value["unblocked"] = serde_json::to_value(&unblocked).map_err(|e| py_err(&e.into()))?;
+        to_py(py, &value)
+    }
+
+    /// Mark `id` as blocked by `on`. `False` if the edge already existed.
+    fn depend(&self, id: i64, on: i64) -> PyResult<bool> {
+        self.inner.depend(id, on).map_err(|e| py_err(&e))
+    }
+
+    #[pyo3(signature = (id, text, agent = ""))]
+    fn note(&self, py: Python<'_>, id: i64, text: &str, agent: &str) -> PyResult<PyObject> {
+        let note = self
+            .inner
+            .add_note(id, text, agent)
+            .map_err(|e| py_err(&e))?;
+        to_py(py, &note)
+    }
+
+    /// `{"nodes": [...], "edges": [...]}`, as in `itr graph -f json`.
+    #[pyo3(signature = (*, all = false))]
+    fn graph(&self, py: Python<'_>, all: bool) -> PyResult<PyObject> {
+        let graph = self.inner.graph(all).map_err(|e| py_err(&e))?;
+        to_py(py, &graph)
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "itr")]
+fn itr_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Store>()?;
+    m.add("ItrError", m.py().get_type::<ItrError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyList;
+
+    #[test]
+    fn store_methods_return_native_python_objects() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let store = Store::in_memory().unwrap();
+            let blocker = store
+                .add(
+                    py, "blocker", "high", "bug", "", None, None, None, "", None, "", None,
+                )
+                .unwrap();
+            let blocker = blocker.bind(py).downcast::<PyDict>().unwrap().clone();
+            let blocker_id: i64 = blocker.get_item("id").unwrap().unwrap().extract().unwrap();
+            let kind: String = blocker
+                .get_item("kind")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(kind, "bug");
+
+            let blocked = store
+                .add(
+                    py,
+                    "blocked",
+                    "medium",
+                    "task",
+                    "",
+                    None,
+                    None,
+                    None,
+                    "",
+                    None,
+                    "",
+                    Some(vec![blocker_id]),
+                )
+                .unwrap();
+            let blocked_id: i64 = blocked.bind(py).get_item("id").unwrap().extract().unwrap();
+
+            let listed = store
+                .list(
+                    py, None, None, None, None, None, None, None, true, false, None, None, None,
+                )
+                .unwrap();
+            let listed = listed.bind(py).downcast::<PyList>().unwrap().clone();
+            assert_eq!(listed.len(), 1);
+
+            let next = store.next(py, None).unwrap();
+            let next_id: i64 = next.bind(py).get_item("id").unwrap().extract().unwrap();
+            assert_eq!(next_id, blocker_id);
+
+            let closed = store
+                .close(py, blocker_id, Some("fixed".into()), false)
+                .unwrap();
+            let unblocked = closed.bind(py).get_item("unblocked").unwrap();
+            let first: i64 = unblocked
+                .get_item(0)
+                .unwrap()
+                .get_item("id")
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(first, blocked_id);
+
+            let graph = store.graph(py, true).unwrap();
+            assert_eq!(graph.bind(py).get_item("nodes").unwrap().len().unwrap(), 2);
+
+            let err = store.get(py, 999).unwrap_err();
+            assert!(err.is_instance_of::<ItrError>(py));
+            let code: String = err
+                .value(py)
+                .getattr("args")
+                .unwrap()
+                .get_item(1)
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(code, "NOT_FOUND");
+        });
+    }
+
+    #[test]
+    fn list_limit_and_offset_page_in_urgency_order() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let store = Store::in_memory().unwrap();
+            for (title, priority) in [("low", "low"), ("critical", "critical"), ("high", "high")] {
+                store
+                    .add(
+                        py, title, priority, "task", "", None, None, None, "", None, "", None,
+                    )
+                    .unwrap();
+            }
+            let page = |limit, offset| -> Vec<String> {
+                let listed = store
+                    .list(
+                        py, None, None, None, None, None, None, None, false, false, None, limit,
+                        offset,
+                    )
+                    .unwrap();
+                listed
+                    .bind(py)
+                    .downcast::<PyList>()
+                    .unwrap()
+                    .iter()
+                    .map(|item| item.get_item("title").unwrap().extract().unwrap())
+                    .collect()
+            };
+            assert_eq!(page(Some(1), None), vec!["critical"]);
+            assert_eq!(page(Some(1), Some(1)), vec!["high"]);
+            assert_eq!(page(None, Some(2)), vec!["low"]);
+        });
+    }
+}
@@ -0,0 +1,157 @@
+//! WebAssembly bindings for browser dashboards that open an exported tracker
+//! entirely client-side.
+//!
+//! A [`Dashboard`] loads the output of `itr export` (JSONL or `--format
+//! json`) into memory and answers the read commands through
+//! [`itr_core::storage::ReadStore`], so filters, urgency, and ordering match
+//! the CLI. Every method returns a JSON string; failures throw a JSON string
+//! `{"error": "...", "code": "NOT_FOUND"}` with the CLI's error codes.
+//!
+//! Build with `wasm-pack build crates/itr-wasm --target web`, or
+//! `cargo build -p itr-wasm --target wasm32-unknown-unknown` plus
+//! `wasm-bindgen`.
+
+use itr_core::error::ItrError;
+use itr_core::models::ListFilter;
+use itr_core::storage::{MemoryStorage, ReadStore};
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+/// `list` filter object, the same keys as the C ABI's `itr_list`: `{}` lists
+/// open, in-progress, and review issues, blocked ones included, highest
+/// urgency first; `limit` and `offset` page through that order.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ListInput {
+    status: Vec<String>,
+    priority: Vec<String>,
+    kind: Vec<String>,
+    tag: Vec<String>,
+    skill: Vec<String>,
+    assigned_to: Option<String>,
+    parent: Option<i64>,
+    blocked: bool,
+    all: bool,
+    sort: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+impl From<ListInput> for ListFilter {
+    fn from(input: ListInput) -> Self {
+        ListFilter {
+            statuses: input.status,
+            priorities: input.priority,
+            kinds: input.kind,
+            tags: input.tag,
+            skills: input.skill,
+            assigned_to: input.assigned_to,
+            parent_id: input.parent,
+            blocked_only: input.blocked,
+            include_blocked: true,
+            all: input.all,
+            sort: input.sort,
+            limit: input.limit,
+            offset: input.offset,
+            ..ListFilter::default()
+        }
+    }
+}
+
+fn error_json(err: &ItrError) -> String {
+    serde_json::json!({ "error": err.to_string(), "code": err.error_code() }).to_string()
+}
+
+fn respond<T: serde::Serialize>(result: Result<T, ItrError>) -> Result<String, String> {
+    result
+        .and_then(|value| Ok(serde_json::to_string(&value)?))
+        .map_err(|e| error_json(&e))
+}
+
+/// A read-only tracker loaded from an export.
+#[wasm_bindgen]
+pub struct Dashboard {
+    store: ReadStore<MemoryStorage>,
+}
+
+#[wasm_bindgen]
+impl Dashboard {
+    /// Load the text of `itr export`, in either format.
+    #[wasm_bindgen(constructor)]
+    pub fn new(export: &str) -> Result<Dashboard, String> {
+        MemoryStorage::from_export(export)
+            .map(|storage| Dashboard {
+                store: ReadStore::new(storage),
+            })
+            .map_err(|e| error_json(&e))
+    }
+
+    /// Issues matching a filter object (see `ListInput`); `"{}"` or an empty
+    /// string uses the defaults.
+    pub fn list(&self, filter: &str) -> Result<String, String> {
+        respond((|| {
+            let input: ListInput = if filter.trim().is_empty() {
+                ListInput::default()
+            } else {
+                serde_json::from_str(filter)?
+            };
+            self.store.list(&input.into())
+        })())
+    }
+
+    /// Open and in-progress issues with no active blockers, highest urgency
+    /// first; `review` issues are left out.
+    pub fn ready(&self) -> Result<String, String> {
+        respond(self.store.ready())
+    }
+
+    /// Full detail for one issue.
+    pub fn get(&self, id: u32) -> Result<String, String> {
+        respond(self.store.get(i64::from(id)))
+    }
+
+    /// Dependency and relation graph; `all` includes closed issues.
+    pub fn graph(&self, all: bool) -> Result<String, String> {
+        respond(self.store.graph(all))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{"issue":{"id":1,"title":"Schema","status":"open","priority":"high","kind":"task","context":"","files":[],"tags":["db"],"skills":[],"acceptance":"","parent_id":null,"assigned_to":"","close_reason":"","created_at":"2026-01-01T00:00:00Z","updated_at":"2026-01-01T00:00:00Z"},"notes":[],"blocked_by":[]}
+{"issue":{"id":2,"title":"API","status":"open","priority":"critical","kind":"feature","context":"","files":[],"tags":[],"skills":[],"acceptance":"","parent_id":null,"assigned_to":"","close_reason":"","created_at":"2026-01-02T00:00:00Z","updated_at":"2026-01-02T00:00:00Z"},"notes":[],"blocked_by":[1]}
+"#;
+
+    fn ids(json: &str) -> Vec<i64> {
+        let items: Vec<serde_json::Value> = serde_json::from_str(json).unwrap();
+        items.iter().map(|i| i["id"].as_i64().unwrap()).collect()
+    }
+
+    #[test]
+    fn dashboard_queries_an_export() {
+        let dash = Dashboard::new(EXPORT).unwrap();
+        assert_eq!(ids(&dash.ready().unwrap()), vec![1]);
+        assert_eq!(ids(&dash.list("{}").unwrap()).len(), 2);
+        assert_eq!(ids(&dash.list(r#"{"tag":["db"]}"#).unwrap()), vec![1]);
+
+        let detail: serde_json::Value = serde_json::from_str(&dash.get(2).unwrap()).unwrap();
+        assert_eq!(detail["is_blocked"], true);
+        assert_eq!(detail["blocked_by"], serde_json::json!([1]));
+
+        let graph: serde_json::Value = serde_json::from_str(&dash.graph(false).unwrap()).unwrap();
+        assert_eq!(graph["edges"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn errors_carry_cli_codes() {
+        let dash = Dashboard::new(EXPORT).unwrap();
+        let err: serde_json::Value = serde_json::from_str(&dash.get(9).unwrap_err()).unwrap();
+        assert_eq!(err["code"], "NOT_FOUND");
+        let err: serde_json::Value =
+            serde_json::from_str(&dash.list(r#"{"bogus":1}"#).unwrap_err()).unwrap();
+        assert_eq!(err["code"], "PARSE_ERROR");
+        assert!(Dashboard::new("not json").is_err());
+    }
+}
@@ -5,8 +5,8 @@ Do NOT use full paths like ~/.cargo/bin/itr or ./target/release/itr.
 
 ### Setup
 
-Set `ITR_AGENT=<your-name>` in your environment to identify yourself for claims, notes, and audit log entries.
-Use `-f json` for all machine-parseable output. Use `--fields id,title,urgency,status` to reduce token usage.
+Set `ITR_AGENT=<your-name>` in your environment (or `itr config --global set agent.name <your-name>`) to identify yourself for claims, notes, and audit log entries.
+Use `-f json` for all machine-parseable output, or set `ITR_FORMAT=json` once for every call. Use `--fields id,title,urgency,status` to reduce token usage. JSON responses are wrapped as `{"api_version":1,"ok":true,"data":...}` (errors: `"ok":false` with `error` and `code`); read `data`, and treat a new `api_version` as a breaking change. `--raw` prints the bare response.
 
 To address a specific project's tracker, pass `--db <path>` where `<path>` is either a `.itr.db` file or the project's root directory (a directory resolves to `<dir>/.itr.db`). This lets you operate on any project by root path without `cd`-ing into it. An explicit `--db` always wins over an ambient `ITR_DB_PATH`, so you can keep `ITR_DB_PATH` on your own tracker and still target another project per call: `itr --db /work/projectA close 42 "done"`.
 
@@ -23,39 +23,61 @@ itr close <ID> "reason"        # Close when done
 ### Command Reference
 
 **Discovery:**
-- `itr ready` — List unblocked, non-terminal issues sorted by urgency
+- `itr ready` — List unblocked open and in-progress issues sorted by urgency
+- `itr plan-sprint --capacity 20pts --agents 3` — Propose a sprint from the queue by urgency within each agent's points (estimates from `itr update <ID> --estimate N`); read-only, confirm before assigning
 - `itr next` — Get single highest-urgency unblocked issue
 - `itr next --claim` / `itr claim` — Claim it (set in-progress + assign)
+- `itr next --skills rust,db` — Prefer issues matching your skills (default: your registered agent's skills); `SKILL_MATCH:` says why it was picked
+- `itr brief <ID>` / `itr brief --next` — Everything a worker needs for one issue (blockers, parent, shared files, recent notes); paste `-f json` into a sub-agent prompt
+- `itr urgency [ID]` — Why `next` chose what it chose: per-component breakdown for one issue, or all open issues by score
 - `itr search "<query>"` — Search across all fields (title, context, acceptance, tags, files, skills, notes). Uses FTS5 when available, falls back to case-insensitive substring matching. Multi-word queries: each term must match somewhere (AND logic, any field)
-- `itr list` — List issues with filtering (--status, --priority, --kind, --tag, --skill, --assigned-to)
-- `itr get <ID>` — Full detail for a single issue
-- `itr get <ID>,<ID>,...` (repeated IDs, comma lists, or ranges like `5-8`) — Batched detail for several issues in one call: JSON is an array of detail objects; compact is blank-line-separated per-issue blocks. Missing IDs become REVIEW notes on stderr (found issues still return, exit 0); duplicates are fetched once
+- `itr list` — List issues with filtering (--status, --priority, --kind, --severity, --component, --affects-version, --fixed-in-version, --tag, --file, --skill, --assigned-to); omitted flags fall back to `defaults.list.<flag>` config keys
+- `itr get <ID>` — Full detail for a single issue. `itr get fix-login` or `itr close "login timeout"` names an issue by title slug or title prefix instead; several matches fail with code `AMBIGUOUS` and a `candidates` list — retry with an ID
+- `itr get <ID>,<ID>,...` or `itr get --ids <ID>,<ID>` (repeated IDs, comma lists, or ranges like `5-8`) — Batched detail for several issues in one call: JSON is an array of detail objects; compact is blank-line-separated per-issue blocks. Missing IDs become REVIEW notes on stderr (found issues still return, exit 0); duplicates are fetched once
+- `itr get <ID> --no-notes` / `--notes-last N` / `--with-blockers` — Fetch only what you need: drop or trim notes (`notes_omitted` counts the rest), or inline each blocker's title and status (`blocked_by_issues`). `get` also lists `referenced_by`: issues whose context or notes mention it as `#<ID>` — write `#12` in context or notes to leave a findable backlink without `depend`/`relate`
 - `itr show` — Alias: no args = list, with ID(s) = get
-- `itr stats` — Project health summary
-- `itr graph` — Dependency graph (DOT format in pretty mode)
+- `itr stats` — Project health summary; `--trend weekly|daily|monthly` for created/closed/open counts per period
+- `itr snapshot save <NAME>` / `itr diff <NAME> [current]` — Save the backlog before a session, then list what the session changed (issues added/closed/updated/deleted, blocked/unblocked, dependency edges added/removed). Also `snapshot list`, `snapshot rm <NAME>`
+- `itr metrics` — Project health as Prometheus gauges (also `GET /metrics` on `itr ui`)
+- `itr agent register [name] --skill rust,sql` / `itr agent list` / `itr agent show [name]` — Agent registry: skills, assigned work, last-seen time; `assign` and `claim` flag missing skills once agents are registered
+- `itr project add <name>` / `itr project switch [<name>]` / `itr project list` / `itr project move <name> <IDs>` — Several components in one database; `--project <name>` on any command scopes listings, search, stats, and new issues to one project. `itr project key <name> APP` gives its issues keys like `APP-42` that work anywhere an ID does
+- `itr component add <name> --assignee <agent> --tags a,b` / `itr component list` / `itr component remove <name>` — Route issues by area: `--component <name>` on add or update fills in the component's default assignee (when unassigned) and tags
+- `itr tag list` / `itr tag rename <old> <new>` / `itr tag rm <tag>` — Tag hygiene across all issues (rename and rm are atomic and audited); `itr tag describe <tag> -d TEXT --color red` sets metadata shown by `tag list`
+- `itr escalate [--dry-run]` — Bump priorities of open issues untouched for `escalate.<priority>_after_days` days (config keys for critical/high/medium); recorded as priority events
+- `itr stale [--dry-run]` — Tag open issues idle for `stale.after_days` days as `stale` (and lower them to `stale.downgrade_to` if set); recorded as tags/priority events
+- `itr files` — Open issues per file path, busiest first (`--status`, `--kind`)
+- `itr versions` — Open issues per affected version, with how many have a fix version
+- `itr graph` — Dependency graph (DOT format in pretty mode); `--around <ID> --depth <N>` for one issue's neighborhood, `--reduce` to drop implied edges, `--roots-only`/`--leaves-only`, `--ascii` for a layered text layout
+- `itr tree [ID]` — Epic/child hierarchy with per-subtree progress (`--all` includes closed)
+- `itr roadmap` — Epics and their children as a Mermaid gantt chart (`-f json` for the schedule)
 
 **CRUD:**
-- `itr add "<title>"` — Create issue (-p priority, -k kind, -c context/--body, --tags, --skills, --files, -a acceptance, --blocked-by, --parent, --assigned-to). Also accepts `--title` as a flag alias for the positional title.
-- `itr update <ID>` — Update fields (--status, --priority, --title, --context, --add-tag, --remove-tag, --add-skill, --remove-skill, --add-file, --remove-file)
+- `itr add "<title>"` — Create issue (-p priority, -k kind, -c context/--body, --tags, --skills, --files, -a acceptance, --blocked-by, --parent, --assigned-to, --severity blocker|major|minor|trivial, --component, --affects-version, --fixed-in-version, --estimate N). Also accepts `--title` as a flag alias for the positional title. Omitted priority, tags, and context come from `defaults.add.<kind>.<field>` config keys when set. `--from-markdown PLAN.md` creates one issue per `- [ ] title` line in one go: indented tasks become children, `- [x]` are created closed, `#tag` and `!high` in a line set tags and priority
+- `itr update <ID>` — Update fields (--status, --priority, --title, --context, --add-tag, --remove-tag, --add-skill, --remove-skill, --add-file, --remove-file, --estimate, --severity, --component, --affects-version, --fixed-in-version)
+- `itr repro <ID>` — Show a bug's reproduction; `--stdin-json` sets it from `{"steps": [...], "expected", "actual", "environment"}`, `--edit` opens it in `$EDITOR`, `--clear` removes it. `add --stdin-json` takes the same `repro` object
 - `itr close <ID>... ["reason"]` — Close (--reason, --wontfix, --duplicate-of). Takes multiple IDs: `itr close 12,14,17 "fixed in a1b2c3d"` or `itr close 5-8` — never loop `itr close` over a list
 
 **Notes & Audit:**
 - `itr note <ID>... "text"` — Append timestamped note (--agent for attribution). Takes multiple IDs: `itr note 55 56 57 "verified end-to-end"`
 - `itr log [ID]` — View event history (--limit, --since). Every mutation is audited, including notes, dependency edges, relations, and all multi-ID/bulk forms
+- `itr events [--since TS] [--follow] [--count N]` — The event log (status/priority/field changes, notes) oldest first, one per line (NDJSON with `-f json`). `--follow` streams new events as other agents record them; a bare `--follow` starts at the end of the log
+- `itr watch [--count N] [--interval MS]` — Stream issue changes from other agents as they commit: added/updated/closed/reopened/deleted/blocked/unblocked, one per line (NDJSON with `-f json`). Orchestrators can wait on `itr -f json watch` instead of polling `itr ready`
 
 **Dependencies & Relations:**
-- `itr depend <ID>... --on <ID>` — Add blocker(s): `itr depend 5-8 --on 200` blocks all of 5..8 on 200
+- `itr depend <ID>... --on <ID>` — Add blocker(s): `itr depend 5-8 --on 200` blocks all of 5..8 on 200. IDs may be project-qualified to block across projects: `itr depend 12 --on webapp/45`
 - `itr undepend <ID> --on <ID>` — Remove blocker
+- `itr depend <ID> --on-external "waiting for upstream 1.4" [--url URL]` — Block on something outside the repo; `itr resolve-external <ID> [--blocker N]` clears it
 - `itr relate <ID>... --to <ID> --type duplicate|related|supersedes` — Create relation(s): `itr relate 124-132 --to 53 --type related`
 - `itr unrelate <ID> --from <ID>` — Remove relation
 
 **Multi-ID syntax** (close/note/relate/depend, plus get/show): IDs may be repeated (`1 2 3`), comma-separated (`1,2,3`), or inclusive ranges (`5-8`), in any mix. All writes run in one transaction; a missing ID is skipped with a `REVIEW:` note and the rest proceed (exit 0 if at least one succeeded). `claim` is deliberately single-ID. NEVER write `for id in ...; do itr <verb> "$id"; done` — one command does it.
 
 **Bulk Operations:**
-- `itr batch add` (alias: `batch create`) — Bulk-create from JSON array on stdin. Item fields mirror the `add` flags; `parent` and `parent_id` are both accepted; `blocked_by` takes integer IDs, "N" strings, or "@N" intra-batch references. Malformed items and unresolvable parents/blockers soft-fall per item instead of failing the batch. `--dry-run` validates the payload and prints the same per-item verdicts (including resolved priority/kind defaults) without writing anything
+- `itr batch add` (alias: `batch create`) — Bulk-create from JSON array or JSONL (one object per line) on stdin. Item fields mirror the `add` flags, plus `status` (e.g. "in-progress" for work already under way) and `notes` (array of strings added after creation); `parent` and `parent_id` are both accepted; `blocked_by` takes integer IDs, "N" strings, "@N" intra-batch references, or "ref:<name>" for the item with `"ref": "<name>"` (survives reordering). Malformed items and unresolvable parents/blockers soft-fall per item instead of failing the batch. `--dry-run` validates the payload and prints the same per-item verdicts (including resolved priority/kind defaults) without writing anything
 - `itr batch close` — Bulk-close from JSON array on stdin (per-issue reasons, soft fallback, --dry-run)
 - `itr batch update` — Bulk-update from JSON array on stdin (per-issue changes, soft fallback, --dry-run). Item fields mirror the `update` flags, including `parent_id` (alias `parent`) to re-parent; `"parent_id": null` or `"no_parent": true` clears the parent. A missing parent or would-be cycle keeps the existing parent with a review note
 - `itr batch note` — Bulk-note from JSON array `[{id, text, agent?}]` on stdin (--dry-run)
+- `itr apply` — Commit a whole session atomically: JSON array of `{"op": "add"|"update"|"close"|"depend"|"note", ...}` on stdin, fields as in the batch verbs (`depend` is `{id, on}`). "@N" in `id`/`on`/`parent`/`blocked_by` is the issue operation N created or touched; "ref:<name>" is an earlier add with that `ref`. Any error rolls back everything (exit 8, later ops `skipped`); --dry-run
 - `itr bulk close` — Close all matching filters (--reason, --wontfix, --status, --priority, --kind, --tag, --skill, --assigned-to, --dry-run)
 - `itr bulk update` — Update matching issues (--set-status, --set-priority, --add-tag, --dry-run)
 - `itr bulk relate` — Relate all matching filters to a target: `itr bulk relate --kind bug --status open --to 53 --type related` (--dry-run; self-edges skipped)
@@ -66,19 +88,41 @@ Which one do I want? `bulk <verb>` when a filter describes the targets; `itr <ve
 
 **Assignment:**
 - `itr assign <ID> <agent>` — Assign issue to agent
+- `itr assign --auto [--max-load N] [--dry-run]` — Orchestrators: distribute the unassigned ready queue across registered agents by skills, load, and file overlap, and print the plan
 - `itr unassign <ID>` — Unassign issue
+- `itr handoff <ID> --to <agent> "summary"` — Pass in-progress work to another agent with a handoff note
+- `itr update <ID> --status review` — Hand finished work to a reviewer instead of closing it; `itr review list` shows the queue, `itr review approve <ID>` closes it as done, `itr review reject <ID> "what to change"` reopens it with a `review` note
+- `itr session start` / `itr session end --summary "..."` — Bracket a work session; end notes the summary on every issue you touched; `itr session list` is the standup
 - `itr claim` — Claim next (alias for `next --claim`)
 
 **Maintenance:**
-- `itr init [--agents-md]` — Create database (optionally write AGENTS.md)
-- `itr schema` — Print database schema
+- `itr init [--agents-md [--target FILE] [--agents-template FILE]] [--encrypt] [--config FILE] [--demo]` — Create database (optionally write or refresh the instructions block in AGENTS.md or each `--target`; `--encrypt` needs ITR_DB_KEY or ITR_DB_KEY_CMD; applies a checked-in `.itr.toml`; `--demo` seeds an example project into an empty database)
+- `itr schema` — Print database schema; `itr schema --json [--type issue|detail|stats|graph]` prints JSON Schema for the `data` of JSON responses
 - `itr agent-info` — Print this guide
+- `itr git install-hooks [--force]` — Commit hooks: tag commits with the active issue (`Itr-Issue: #N` trailer; `ITR_ISSUE` overrides), link each commit to it, and note it
+- `itr branch <ID> [--checkout]` — Print (and record on the issue) a branch name like `task/12-fix-login-timeout`; `--checkout` switches to it
+- `itr scan [PATH] [--dry-run]` — File TODO/FIXME/HACK comments as `code-todo` issues (respects .gitignore); re-scans match comments by fingerprint, update moved ones, and report open issues whose comment is gone
+- `itr git scan [--range A..B]` — Close issues named by `closes #N` / `fixes #N` / `Itr-Close: N` in commit messages (default: last 20 commits); already-closed issues are skipped, newly unblocked work is reported; every named issue gets the commit linked
+- `itr gate [--max-critical N] [--touching-staged]` — Exit 1 when more than N (default 0) open critical issues exist, or only those whose files are staged; for pre-commit hooks and CI (`-f gha` annotates findings in GitHub Actions, as for `lint` and `doctor`)
+- `itr git link <ID> [REV]` — Link a commit (default HEAD) to an issue as evidence of the work
+- `itr activity <ID> [--since WHEN] [-n N]` — Recent commits touching the issue's files (default: since it was created); check before picking up an issue
+- `itr commits <ID>` — List an issue's linked commits (`mention` or `close`); `itr get` shows `COMMITS: n`
 - `itr skill [install|path] [--scope user|project]` — Emit or install the Claude Code skill that briefs agents on `itr` (see Agent Onboarding below)
-- `itr doctor [--fix]` — Database integrity checks
+- `itr lint [--all]` — Flag issues missing acceptance criteria (features), files (bugs), or context, long titles, and childless epics; `lint.*` config keys tune the rules; exits 1 on findings
+- `itr doctor [--fix]` — Database integrity checks; `--fix` also vacuums, analyzes, and checkpoints the WAL when flagged; `--fix-cycles suggested` drops the fewest edges that break dependency cycles
 - `itr ui [--db PATH] [--port PORT] [--no-open] [--allow-dangerous]` — Local browser UI for human issue editing
 - `itr config list|get|set|reset` — Per-project configuration
-- `itr export [--export-format json|jsonl]` / `itr import [--file, --merge]` — Data portability
-- `itr reindex` — Rebuild full-text search index
+- `itr config export > .itr.toml` / `itr config import <FILE>` — Copy tuned settings between projects (TOML or JSON)
+- `itr config --global set format.default json` — User-level defaults in `~/.config/itr/config.toml`, under every database's own settings (also `color.mode`, `db.search`, `db.fallback`); `format.default` can also be set per database, and `ITR_FORMAT=json` overrides both
+- `itr config set notify.desktop true` — Desktop notification listing issues unblocked by `close`/`undepend` (binaries built with `--features notify`)
+- `itr config set webhook.url <URL>` — POST `{action, id, issue, fields, at}` for each issue change a command makes (same actions as `watch`; `webhook.timeout_ms`, default 5000). Failed deliveries print `REVIEW:` and never fail the command
+- `itr export [--export-format json|jsonl|org|todotxt|html] [--out DIR] [--status, --kind, --tag, --ids, --subtree <ID>, --since, --full]` / `itr import [--file, --import-format jsonl|json|todotxt, --merge]` — Data portability; `--merge` matches issues by UUID and keeps the later edit of each field
+- `itr sync --remote <URL> [--token T] [--prefer local|remote]` — Exchange changes with another instance's `itr ui`; fields changed on both sides are reported as CONFLICT lines
+- `itr mirror write|read [--path P]` — Keep `.itr/issues.jsonl` (committable, one line per issue) in sync with the database; run `read` after pulling or merging. `itr mirror merge %O %A %B` is its git merge driver
+- `itr merge-db <OTHER.db> [--dry-run]` — Merge another checkout's database: issues get new IDs (title + created_at matches reuse the existing issue), notes and dependencies follow; prints the ID mapping
+- `-q, --quiet` — Print only the ID a mutating command touched (`ID=$(itr add "x" -q)`); link commands print nothing and `UNBLOCKED:` lines are dropped. JSON is unchanged
+- `--dry-run` on `add`, `update`, `close`, `depend`, `import`, `merge-db`, `mirror read` — Run the command, print its would-be output (including `UNBLOCKED:` lines), then roll everything back
+- `itr reindex` — Rebuild full-text search index and `#ID` cross-references
 - `itr upgrade` — Rebuild itr from source
 
 ### Local UI
@@ -122,19 +166,20 @@ itr ready -f json --fields id,title,priority
 itr stats -f json --fields total,by_status
 ```
 `--fields` works on all four formats for issue lists and honors the requested order: oneline emits tab-separated columns (list values join with ","), pretty builds its table from the list, JSON re-serializes keys in the given order. It also filters JSON output for issue/search/batch commands plus top-level keys for `stats`, `graph`, and `log` JSON. The few combinations with no field filtering (issue-detail pretty, search pretty/oneline, DOT graphs, non-JSON stats/log/batch) emit a `REVIEW:` note to stderr and print unfiltered output.
-Valid fields: id, title, status, priority, kind, created_at, updated_at, context, files, tags, skills, acceptance, parent_id, assigned_to, close_reason, urgency, blocked_by, blocks, notes, relations.
+Valid fields: id, title, status, priority, kind, created_at, updated_at, context, files, tags, skills, acceptance, parent_id, assigned_to, close_reason, branch, project, severity, component, affects_version, fixed_in_version, repro, referenced_by, urgency, blocked_by, blocks, notes, relations.
 Stats/graph/log JSON also accept their own top-level keys (e.g. total, by_status, nodes, edges, issue_id, field).
 
 ### Urgency Scoring
 
-Issues are ranked by a computed urgency score (never stored, always fresh). Components:
+Issues are ranked by a computed urgency score (inputs cached per issue and invalidated on writes; `itr recompute` rebuilds). Components:
 - `urgency.priority.critical`=10, `urgency.priority.high`=6, `urgency.priority.medium`=3, `urgency.priority.low`=1
+- `urgency.severity.blocker`=6, `urgency.severity.major`=3, `urgency.severity.minor`=0, `urgency.severity.trivial`=-1 (only when `--severity` is set)
 - `urgency.kind.bug`=2, `urgency.kind.feature`=0, `urgency.kind.task`=0, `urgency.kind.epic`=-2
 - `urgency.blocking`=8 (blocks other active issues), `urgency.blocked`=-10 (blocked by others)
 - `urgency.age`=2 (scaled by days/10, capped at 1.0)
 - `urgency.in_progress`=4, `urgency.has_acceptance`=1, `urgency.notes_count`=0.5
 
-Override via `itr config set <key> <value>`. View breakdown with `itr get <ID> -f json` (urgency_breakdown field).
+Override via `itr config set <key> <value>`. `urgency.formula` (e.g. `"priority*2 + blocking + age + blocked"`) replaces the sum with an expression over the component values (+ `age_days`, `note_count`; `min`/`max`/`abs`/`clamp`); empty clears it. View breakdown with `itr get <ID> -f json` (urgency_breakdown field).
 View all config keys: `itr config list`.
 
 ### Skills Filtering
@@ -155,8 +200,10 @@ itr claim --skill rust --skill sql # Issues needing both
 
 ### Error Handling
 
-- Exit 0: success (including empty result sets — empty array `[]` in JSON)
-- Exit 1: error (not found, validation, DB error, cycle detection)
-- stdout: always parseable data (or empty). stderr: always errors. No interactive prompts ever.
+- Exit 0: success (including empty result sets — `data` is an empty array `[]` in JSON)
+- Exit 1: runtime error (DB, IO); 3 not found; 4 cycle; 5 validation; 6 ambiguous reference; 7 no database
+- `--fail-on empty` exits 9 on an empty result; `--fail-on blocked` exits 10 when `next`/`claim`/`ready` find nothing because every matching issue is blocked
+- Errors name what you probably meant: `(did you mean 'high'?)` for a typo'd value (JSON `suggestion`), nearby and recent issues for a missing ID (JSON `candidates`)
+- stdout: always parseable data (or empty). stderr: always errors. No interactive prompts without a TTY: destructive commands only ask `[y/N]` when stdin and stderr are terminals (`--yes` skips it).
 - All timestamps are UTC ISO 8601.
 "#;
@@ -1,36 +1,95 @@
+use crate::completion;
+use crate::util;
 use clap::{Parser, Subcommand, ValueEnum};
+use clap_complete::ArgValueCandidates;
+
+/// Value parser for arguments naming one issue: an ID, `project/ID`, key, or
+/// name, resolved once the database is open. ID lists and ranges are
+/// rejected here so single-issue verbs stay a parse error (exit 2) for them.
+fn single_issue_ref(raw: &str) -> Result<String, String> {
+    if raw.contains(',') || (util::is_id_token(raw) && raw.trim().parse::<i64>().is_err()) {
+        return Err("expected one issue (ID, key, or name), not a list or range".to_string());
+    }
+    Ok(raw.to_string())
+}
 
+// Global flags are independent switches, not a state machine.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Parser)]
 #[command(name = "itr", about = "Agent-first issue tracker CLI", version = env!("ITR_VERSION"))]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
 
-    /// Output format: compact|json|pretty|oneline
-    #[arg(short, long, default_value = "compact", global = true)]
-    pub format: String,
+    /// Output format: compact|json|pretty|oneline|gha [default: `ITR_FORMAT`,
+    /// else `format.default` from config, else compact]
+    #[arg(short, long, global = true)]
+    pub format: Option<String>,
 
     /// Override database path (skips walk-up search)
     #[arg(long, global = true)]
     pub db: Option<String>,
 
-    /// Suppress non-essential output
+    /// Print only the ID a mutating command touched; JSON is unchanged
     #[arg(short, long, global = true)]
     pub quiet: bool,
 
+    /// Print JSON bare, without the `api_version`/`ok`/`data` envelope
+    #[arg(long, global = true)]
+    pub raw: bool,
+
+    /// Exit non-zero when there is nothing to return: `empty` exits 9 (10 when
+    /// only blocked issues match), `blocked` only 10
+    #[arg(long, global = true, value_enum, default_value_t = FailOn::None)]
+    pub fail_on: FailOn,
+
+    /// Print diagnostics to stderr, such as retries while the database is busy
+    #[arg(long, global = true)]
+    pub verbose: bool,
+
+    /// Skip confirmation prompts for destructive operations
+    #[arg(short, long, global = true)]
+    pub yes: bool,
+
     /// Comma-separated list of fields to include in output (all formats;
     /// oneline/pretty/compact honor the requested order)
     #[arg(long, global = true)]
     pub fields: Option<String>,
+
+    /// Scope to one project: listings, search, and stats only see its issues,
+    /// and new issues join it (default: the one set by `itr project switch`)
+    #[arg(long, global = true)]
+    pub project: Option<String>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Initialize a new .itr.db database
     Init {
-        /// Also append itr instructions to AGENTS.md
+        /// Also write itr instructions to AGENTS.md (or each --target), updating an earlier block in place
         #[arg(long)]
         agents_md: bool,
+
+        /// Instructions file to write instead of AGENTS.md, relative to the database's directory (repeatable), e.g. CLAUDE.md or .cursorrules
+        #[arg(long, value_name = "FILE", requires = "agents_md")]
+        target: Vec<String>,
+
+        /// Instructions template to use instead of the `agents.template` config value or the built-in text; `{{command}}` and `{{db}}` are filled in
+        #[arg(long, value_name = "FILE", requires = "agents_md")]
+        agents_template: Option<String>,
+
+        /// Encrypt the new database with the passphrase from `ITR_DB_KEY` or `ITR_DB_KEY_CMD` (needs the `encryption` build)
+        #[arg(long)]
+        encrypt: bool,
+
+        /// Apply settings from this `config export` file (default: a `.itr.toml`
+        /// next to a newly created database)
+        #[arg(long, value_name = "FILE")]
+        config: Option<String>,
+
+        /// Seed an empty database with an example project to explore
+        #[arg(long)]
+        demo: bool,
     },
 
     /// Create a new issue
@@ -43,9 +102,9 @@ pub enum Commands {
         #[arg(long = "title", hide = true)]
         title_flag: Option<String>,
 
-        /// Priority: critical|high|medium|low
-        #[arg(short, long, default_value = "medium")]
-        priority: String,
+        /// Priority: critical|high|medium|low [default: kind template, else medium]
+        #[arg(short, long)]
+        priority: Option<String>,
 
         /// Kind: bug|feature|task|epic
         #[arg(short, long, default_value = "task")]
@@ -68,7 +127,7 @@ pub enum Commands {
         tags: Option<String>,
 
         /// Tag (repeatable)
-        #[arg(short, long)]
+        #[arg(short, long, add = ArgValueCandidates::new(completion::tags))]
         tag: Vec<String>,
 
         /// Comma-separated skills (agent capabilities required)
@@ -88,16 +147,44 @@ pub enum Commands {
         blocked_by: Option<String>,
 
         /// Parent epic ID
-        #[arg(long)]
-        parent: Option<i64>,
+        #[arg(value_parser = single_issue_ref, long)]
+        parent: Option<String>,
 
         /// Assign to agent
         #[arg(long)]
         assigned_to: Option<String>,
 
+        /// Severity (impact, separate from priority): blocker|major|minor|trivial
+        #[arg(long)]
+        severity: Option<String>,
+
+        /// Registered component; fills an unset assignee and adds tags from its defaults
+        #[arg(long)]
+        component: Option<String>,
+
+        /// Version the issue was seen in (e.g. 1.4.2)
+        #[arg(long, value_name = "VERSION")]
+        affects_version: Option<String>,
+
+        /// Version that fixes, or will fix, the issue
+        #[arg(long, value_name = "VERSION")]
+        fixed_in_version: Option<String>,
+
+        /// Estimate in points for `plan-sprint` (e.g. 3 or 3pts)
+        #[arg(long)]
+        estimate: Option<String>,
+
         /// Read a JSON issue object from stdin
         #[arg(long)]
         stdin_json: bool,
+
+        /// Create one issue per `- [ ] title` line of a Markdown checklist; nesting sets the parent, `#tag` and `!priority` annotate
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["title", "title_flag", "stdin_json", "context", "files", "file", "acceptance", "blocked_by", "severity", "estimate"])]
+        from_markdown: Option<String>,
+
+        /// Run inside a transaction, print the would-be result, then roll back
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// List issues with filtering
@@ -118,14 +205,34 @@ pub enum Commands {
         #[arg(short, long)]
         kind: Vec<String>,
 
+        /// Filter by severity (repeatable)
+        #[arg(long)]
+        severity: Vec<String>,
+
+        /// Filter by component (repeatable, matches any)
+        #[arg(long)]
+        component: Vec<String>,
+
+        /// Filter by affected version (repeatable, matches any)
+        #[arg(long, value_name = "VERSION")]
+        affects_version: Vec<String>,
+
+        /// Filter by fix version (repeatable, matches any)
+        #[arg(long, value_name = "VERSION")]
+        fixed_in_version: Vec<String>,
+
         /// Filter by tag (repeatable, AND logic)
-        #[arg(long, visible_alias = "tags")]
+        #[arg(long, visible_alias = "tags", add = ArgValueCandidates::new(completion::tags))]
         tag: Vec<String>,
 
         /// Filter by tag with OR logic (repeatable, matches any)
         #[arg(long)]
         tag_any: Vec<String>,
 
+        /// Filter by referenced file path (repeatable, AND logic, exact match)
+        #[arg(long)]
+        file: Vec<String>,
+
         /// Filter by skill (repeatable, AND logic)
         #[arg(long)]
         skill: Vec<String>,
@@ -139,33 +246,59 @@ pub enum Commands {
         include_blocked: bool,
 
         /// Show children of an epic
-        #[arg(long)]
-        parent: Option<i64>,
+        #[arg(value_parser = single_issue_ref, long)]
+        parent: Option<String>,
+
+        /// With --parent: include every descendant (nested epics), not just direct children
+        #[arg(long, requires = "parent")]
+        recursive: bool,
 
         /// Filter by assignee
         #[arg(long)]
         assigned_to: Option<String>,
 
-        /// Sort by: urgency|priority|created|updated|id
-        #[arg(long, default_value = "urgency")]
-        sort: String,
+        /// Sort by: urgency|priority|created|updated|id [default: urgency, or `defaults.list.sort`]
+        #[arg(long)]
+        sort: Option<String>,
 
-        /// Max results
+        /// Max results [default: `defaults.list.limit` if set]
         #[arg(short = 'n', long)]
         limit: Option<usize>,
+
+        /// Skip this many results (after sorting) before applying --limit
+        #[arg(long)]
+        offset: Option<usize>,
     },
 
     /// Get full detail for one or more issues
     Get {
-        /// Issue ID(s) — repeat, comma-separate, or use inclusive ranges (e.g. 1,2,5-8)
-        #[arg(value_name = "ID", required = true, num_args = 1..)]
+        /// Issue ID(s) — repeat, comma-separate, or use inclusive ranges (e.g. 1,2,5-8);
+        /// a non-numeric argument names an issue by title slug or title prefix
+        #[arg(value_name = "ID", required_unless_present = "id_list", num_args = 1.., add = ArgValueCandidates::new(completion::issue_ids))]
         ids: Vec<String>,
+
+        /// More issue IDs as a comma list (e.g. --ids 12,14,19), for callers that build one argument
+        #[arg(long = "ids", value_name = "IDS")]
+        id_list: Vec<String>,
+
+        /// Leave notes out
+        #[arg(long, conflicts_with = "notes_last")]
+        no_notes: bool,
+
+        /// Only the newest N notes
+        #[arg(long, value_name = "N")]
+        notes_last: Option<usize>,
+
+        /// Inline each blocker's title and status
+        #[arg(long)]
+        with_blockers: bool,
     },
 
     /// Update an issue
     Update {
         /// Issue ID
-        id: i64,
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
 
         /// New status
         #[arg(short, long)]
@@ -200,7 +333,7 @@ pub enum Commands {
         tags: Option<String>,
 
         /// Replace tag (repeatable)
-        #[arg(short, long)]
+        #[arg(short, long, add = ArgValueCandidates::new(completion::tags))]
         tag: Vec<String>,
 
         /// Replace skills list (comma-separated)
@@ -216,8 +349,8 @@ pub enum Commands {
         acceptance: Option<String>,
 
         /// Set parent epic
-        #[arg(long)]
-        parent: Option<i64>,
+        #[arg(value_parser = single_issue_ref, long)]
+        parent: Option<String>,
 
         /// Clear parent epic (sets `parent_id` to NULL)
         #[arg(long, conflicts_with = "parent")]
@@ -228,11 +361,11 @@ pub enum Commands {
         assigned_to: Option<String>,
 
         /// Append a tag (repeatable)
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(completion::tags))]
         add_tag: Vec<String>,
 
         /// Remove a tag (repeatable)
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(completion::tags))]
         remove_tag: Vec<String>,
 
         /// Append a file (repeatable)
@@ -250,14 +383,58 @@ pub enum Commands {
         /// Remove a skill (repeatable)
         #[arg(long)]
         remove_skill: Vec<String>,
+
+        /// Estimate in points for `plan-sprint` (e.g. 3 or 3pts; `none` clears it)
+        #[arg(long)]
+        estimate: Option<String>,
+
+        /// Severity: blocker|major|minor|trivial (`none` clears it)
+        #[arg(long)]
+        severity: Option<String>,
+
+        /// Move into a registered component, routing it by the component's defaults (`none` clears it)
+        #[arg(long)]
+        component: Option<String>,
+
+        /// Version the issue was seen in (`none` clears it)
+        #[arg(long, value_name = "VERSION")]
+        affects_version: Option<String>,
+
+        /// Version that fixes, or will fix, the issue (`none` clears it)
+        #[arg(long, value_name = "VERSION")]
+        fixed_in_version: Option<String>,
+
+        /// Run inside a transaction, print the would-be result, then roll back
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show or set an issue's reproduction: steps, expected, actual, environment
+    Repro {
+        /// Issue ID — bare, or project-qualified
+        #[arg(add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
+
+        /// Replace it with a JSON object `{"steps": [...], "expected", "actual", "environment"}` read from stdin
+        #[arg(long, conflicts_with_all = ["edit", "clear"])]
+        stdin_json: bool,
+
+        /// Edit it as JSON in `$VISUAL` or `$EDITOR`
+        #[arg(long, conflicts_with = "clear")]
+        edit: bool,
+
+        /// Remove it
+        #[arg(long)]
+        clear: bool,
     },
 
     /// Close one or more issues (shorthand for update --status done)
     Close {
         /// Issue ID(s) — repeat, comma-separate, or use ranges (e.g. 12,14 or 5-8) —
         /// optionally followed by a close reason. The first non-ID token starts the
-        /// reason; use --reason for a purely numeric reason.
-        #[arg(value_name = "ID... [REASON]", required = true, num_args = 1..)]
+        /// reason; use --reason for a purely numeric reason. With no leading ID, the
+        /// first argument names the issue by title slug or title prefix.
+        #[arg(value_name = "ID... [REASON]", required = true, num_args = 1.., add = ArgValueCandidates::new(completion::issue_ids))]
         args: Vec<String>,
 
         /// Close reason (unambiguous flag form of the positional reason)
@@ -269,18 +446,22 @@ pub enum Commands {
         wontfix: bool,
 
         /// Close as duplicate of another issue (creates relation + closes)
+        #[arg(value_parser = single_issue_ref, long)]
+        duplicate_of: Option<String>,
+
+        /// Run inside a transaction, print the would-be result, then roll back
         #[arg(long)]
-        duplicate_of: Option<i64>,
+        dry_run: bool,
     },
 
     /// Append a note to one or more issues
     Note {
         /// Issue ID(s) — repeat, comma-separate, or use ranges (e.g. 55 56 57 or 5-8) —
         /// followed by the note text. The first non-ID token starts the text.
-        #[arg(value_name = "ID... TEXT", required = true, num_args = 1..)]
+        #[arg(value_name = "ID... TEXT", required = true, num_args = 1.., add = ArgValueCandidates::new(completion::issue_ids))]
         args: Vec<String>,
 
-        /// Agent/session identifier
+        /// Agent/session identifier (falls back to `ITR_AGENT`, then config `agent.name`)
         #[arg(long, default_value = "")]
         agent: String,
     },
@@ -304,22 +485,52 @@ pub enum Commands {
     #[command(visible_alias = "deps")]
     Depend {
         /// Issue ID(s) that will be blocked — repeat, comma-separate, or use ranges
-        #[arg(value_name = "ID", required = true, num_args = 1..)]
+        #[arg(value_name = "ID", required = true, num_args = 1.., add = ArgValueCandidates::new(completion::issue_ids))]
         ids: Vec<String>,
 
-        /// Issue ID that blocks them
+        /// Issue ID that blocks them — bare, or project-qualified (`webapp/45`)
+        #[arg(
+            long,
+            required_unless_present = "on_external",
+            conflicts_with = "on_external"
+        )]
+        on: Option<String>,
+
+        /// Block them on something outside the tracker instead, described in
+        /// words (e.g. "waiting for upstream release 1.4"); clear it with
+        /// `itr resolve-external`
+        #[arg(long, value_name = "DESCRIPTION")]
+        on_external: Option<String>,
+
+        /// Link for the external blocker (release page, upstream issue)
+        #[arg(long, requires = "on_external")]
+        url: Option<String>,
+
+        /// Run inside a transaction, print the would-be result, then roll back
         #[arg(long)]
-        on: i64,
+        dry_run: bool,
+    },
+
+    /// Clear external blockers added with `itr depend --on-external`
+    ResolveExternal {
+        /// Issue ID that was blocked — bare, or project-qualified
+        #[arg(add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
+
+        /// External blocker ID to clear (from `itr get`); default: all of them
+        #[arg(long)]
+        blocker: Option<i64>,
     },
 
     /// Remove a dependency
     Undepend {
-        /// Issue ID that was blocked
-        id: i64,
+        /// Issue ID that was blocked — bare, or project-qualified
+        #[arg(add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
 
-        /// Issue ID that was blocking it
+        /// Issue ID that was blocking it — bare, or project-qualified
         #[arg(long)]
-        on: i64,
+        on: String,
     },
 
     /// Get the highest-urgency unblocked issue
@@ -332,7 +543,11 @@ pub enum Commands {
         #[arg(long)]
         skill: Vec<String>,
 
-        /// Agent name for assignment (falls back to `ITR_AGENT` env var)
+        /// Skills to prefer, comma-separated (default: the registered agent's skills)
+        #[arg(long)]
+        skills: Option<String>,
+
+        /// Agent name for assignment (falls back to `ITR_AGENT`, then config `agent.name`)
         #[arg(long)]
         agent: Option<String>,
 
@@ -341,6 +556,25 @@ pub enum Commands {
         assigned_to: Option<String>,
     },
 
+    /// Task packet for handing an issue to a worker agent: detail, blockers, parent, files, recent notes
+    Brief {
+        /// Issue ID (or use --next)
+        #[arg(value_parser = single_issue_ref, required_unless_present = "next", add = ArgValueCandidates::new(completion::issue_ids))]
+        id: Option<String>,
+
+        /// Brief the issue `itr next` would pick, without claiming it
+        #[arg(long, conflicts_with = "id")]
+        next: bool,
+
+        /// With --next: filter by skill (repeatable, AND logic)
+        #[arg(long, requires = "next")]
+        skill: Vec<String>,
+
+        /// How many of the most recent notes to include
+        #[arg(long, default_value_t = 5)]
+        notes: usize,
+    },
+
     /// List all unblocked, non-terminal issues by urgency
     Ready {
         /// Max results
@@ -360,12 +594,30 @@ pub enum Commands {
         assigned_to: Option<String>,
     },
 
+    /// Propose a sprint from the open and in-progress queue by urgency, within estimate capacity
+    PlanSprint {
+        /// Points each agent can take on (e.g. 20 or 20pts)
+        #[arg(long)]
+        capacity: String,
+
+        /// Agents sharing the sprint
+        #[arg(long, default_value_t = 1)]
+        agents: usize,
+    },
+
     /// Per-item operations from JSON stdin (add/close/update/note with individual control)
     Batch {
         #[command(subcommand)]
         action: BatchAction,
     },
 
+    /// Run mixed add/update/close/depend/note operations from JSON stdin in one transaction (all or nothing)
+    Apply {
+        /// Run every operation, print the results, then roll back
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Filter-based operations (same change to all matching issues)
     Bulk {
         #[command(subcommand)]
@@ -377,30 +629,222 @@ pub enum Commands {
         /// Include resolved issues
         #[arg(long)]
         all: bool,
+
+        /// Only issues within --depth hops of this one, following edges both ways
+        #[arg(long, value_name = "ID", value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        around: Option<String>,
+
+        /// Hops from the --around issue
+        #[arg(long, default_value_t = 1, requires = "around")]
+        depth: usize,
+
+        /// Draw a layered text layout instead of NODE/EDGE lines or DOT
+        #[arg(long)]
+        ascii: bool,
+
+        /// Drop blocking edges implied by a longer chain of blocking edges
+        #[arg(long)]
+        reduce: bool,
+
+        /// Only issues nothing in the graph blocks
+        #[arg(long, conflicts_with = "leaves_only")]
+        roots_only: bool,
+
+        /// Only issues that block nothing in the graph
+        #[arg(long)]
+        leaves_only: bool,
+    },
+
+    /// Raise the priority of aging open issues per the configured escalation policy
+    Escalate {
+        /// Show what would be escalated without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Tag long-untouched open issues `stale` per the configured stale policy
+    Stale {
+        /// Show what would be tagged without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Check issues against the configured quality policies; exits 1 on findings
+    Lint {
+        /// Check closed issues too, not just open and in-progress ones
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Fail (exit 1) when open critical issues exist; for pre-commit hooks and CI
+    Gate {
+        /// Critical issues allowed before the gate fails
+        #[arg(long, default_value_t = 0)]
+        max_critical: usize,
+
+        /// Count only critical issues whose files are in the staged changes
+        #[arg(long)]
+        touching_staged: bool,
+    },
+
+    /// Show the per-component urgency breakdown for one issue, or every open issue by score
+    Urgency {
+        /// Issue ID (any status); omit to score every open issue
+        #[arg(value_parser = single_issue_ref, conflicts_with = "all", add = ArgValueCandidates::new(completion::issue_ids))]
+        id: Option<String>,
+
+        /// Score every open and in-progress issue (the default without an ID)
+        #[arg(long)]
+        all: bool,
+
+        /// Max issues to show
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+    },
+
+    /// Rebuild the cached urgency inputs (blocking, blocked, note count) for every issue
+    Recompute,
+
+    /// Report which file paths carry the most open issues
+    Files {
+        /// Filter by status (repeatable; default: open + in-progress)
+        #[arg(short, long)]
+        status: Vec<String>,
+
+        /// Filter by kind (repeatable)
+        #[arg(short, long)]
+        kind: Vec<String>,
+
+        /// Max paths to report
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+    },
+
+    /// Report open issues per affected version, with how many have a fix version
+    Versions,
+
+    /// Show the epic/child hierarchy with per-subtree progress
+    Tree {
+        /// Root the tree at this issue (default: every top-level parent)
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: Option<String>,
+
+        /// Include closed issues
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Lay out epics and their children on a timeline as a Mermaid gantt chart
+    Roadmap {
+        /// Include closed epics
+        #[arg(long)]
+        all: bool,
     },
 
     /// Project health summary
-    Stats,
+    Stats {
+        /// Created, closed, and open counts per period instead of the summary
+        #[arg(long, value_enum, value_name = "PERIOD")]
+        trend: Option<TrendPeriod>,
+
+        /// Periods to show with --trend, ending with the current one
+        #[arg(long, default_value_t = 8, requires = "trend")]
+        periods: usize,
+    },
+
+    /// Project health as Prometheus gauges (text exposition format)
+    Metrics,
 
     /// Project narrative for session start (combines stats + ready + recent activity)
     Summary,
 
     /// Export the full database
     Export {
-        /// Export format: jsonl|json
+        /// Export format: jsonl|json|org|todotxt|html
         #[arg(long, default_value = "jsonl")]
         export_format: String,
+
+        /// Only issues with this status (repeatable)
+        #[arg(short, long)]
+        status: Vec<String>,
+
+        /// Only issues of this kind (repeatable)
+        #[arg(short, long)]
+        kind: Vec<String>,
+
+        /// Only issues with this tag (repeatable, AND logic)
+        #[arg(long, add = ArgValueCandidates::new(completion::tags))]
+        tag: Vec<String>,
+
+        /// Only these issue IDs — repeat, comma-separate, or use ranges (e.g. 1,2,5-8)
+        #[arg(long)]
+        ids: Vec<String>,
+
+        /// Only this issue and everything under it (e.g. an epic's subtree)
+        #[arg(value_parser = single_issue_ref, long, add = ArgValueCandidates::new(completion::issue_ids))]
+        subtree: Option<String>,
+
+        /// Only issues updated at or after this timestamp (ISO 8601)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Add a versioned header with the config table, and blocks/children per issue
+        #[arg(long)]
+        full: bool,
+
+        /// Directory for `--export-format html` [default: itr-report]
+        #[arg(long, value_name = "DIR")]
+        out: Option<String>,
     },
 
-    /// Import issues from JSONL or JSON
+    /// Import issues from JSONL, JSON, or todo.txt
     Import {
         /// Input file path (or stdin)
         #[arg(long)]
         file: Option<String>,
 
+        /// Input format: jsonl|json|todotxt (jsonl and json are told apart by content)
+        #[arg(long, default_value = "jsonl")]
+        import_format: String,
+
         /// Skip issues whose IDs already exist (default: replace them)
         #[arg(long)]
         merge: bool,
+
+        /// Run inside a transaction, print the would-be result, then roll back
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Merge issues, notes, and dependencies from another itr database
+    MergeDb {
+        /// The other .itr.db file (or a directory containing one)
+        other: String,
+
+        /// Run inside a transaction, print the would-be result, then roll back
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Keep a mergeable `.itr/issues.jsonl` copy of the backlog in the repo
+    Mirror {
+        #[command(subcommand)]
+        action: MirrorAction,
+    },
+
+    /// Push and pull issue changes with another itr instance's `itr ui` server
+    Sync {
+        /// Base URL of the remote `itr ui`, such as `http://127.0.0.1:8787`
+        #[arg(long)]
+        remote: String,
+
+        /// The remote UI session token (falls back to `ITR_SYNC_TOKEN` env var)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Resolve conflicts in favor of one side (default: each side keeps its own value)
+        #[arg(long, value_enum)]
+        prefer: Option<SyncPrefer>,
     },
 
     /// Run database integrity checks
@@ -408,6 +852,11 @@ pub enum Commands {
         /// Auto-fix safe issues
         #[arg(long)]
         fix: bool,
+
+        /// Break dependency cycles by dropping edges: `interactive` asks which
+        /// edge of each cycle to drop, `suggested` drops the suggested ones
+        #[arg(long, value_name = "MODE")]
+        fix_cycles: Option<CycleFixMode>,
     },
 
     /// Start a local browser UI for editing the itr database
@@ -429,8 +878,43 @@ pub enum Commands {
         allow_dangerous: bool,
     },
 
+    /// Stream issue changes (added/updated/closed/unblocked...) as they happen
+    Watch {
+        /// Milliseconds between checks for new commits
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+
+        /// Exit after this many events (default: run until interrupted)
+        #[arg(long)]
+        count: Option<usize>,
+    },
+
+    /// Print the event log as one line per event, oldest first (NDJSON with -f json)
+    Events {
+        /// Only events recorded at or after this timestamp (ISO 8601)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Keep running and print new events as other processes record them
+        #[arg(long)]
+        follow: bool,
+
+        /// Milliseconds between checks for new events with --follow
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+
+        /// Exit after this many events
+        #[arg(long)]
+        count: Option<usize>,
+    },
+
     /// Manage per-project configuration
     Config {
+        /// Read and write the user-level config file
+        /// (`~/.config/itr/config.toml`) instead of this database's settings
+        #[arg(long, global = true)]
+        global: bool,
+
         #[command(subcommand)]
         action: ConfigAction,
     },
@@ -445,8 +929,118 @@ pub enum Commands {
         action: Option<SkillAction>,
     },
 
-    /// Dump the current database schema
-    Schema,
+    /// Print (and record) a git branch name for an issue, e.g. task/12-fix-login-timeout
+    Branch {
+        /// Issue ID
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
+
+        /// Switch to the branch, creating it if needed
+        #[arg(long)]
+        checkout: bool,
+    },
+
+    /// Save named copies of the backlog to compare later with `itr diff`
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+
+    /// Show what changed between two snapshots, or a snapshot and now
+    Diff {
+        /// Snapshot to compare from
+        from: String,
+
+        /// Snapshot to compare to, or `current` for the live database
+        #[arg(default_value = "current")]
+        to: String,
+    },
+
+    /// Manage tags across all issues (list, rename, rm)
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+
+    /// Manage projects that share this database (add, switch, list, move)
+    Project {
+        #[command(subcommand)]
+        action: ProjectAction,
+    },
+
+    /// Manage the components issues are routed to, with default assignees and tags (add, list, remove)
+    Component {
+        #[command(subcommand)]
+        action: ComponentAction,
+    },
+
+    /// Registry of agents working this database: skills and last-seen time
+    Agent {
+        #[command(subcommand)]
+        action: AgentAction,
+    },
+
+    /// Work sessions: track the issues an agent touches and summarize them on end
+    Session {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
+
+    /// Git integration (commit hooks)
+    Git {
+        #[command(subcommand)]
+        action: GitAction,
+    },
+
+    /// List the commits linked to an issue by the git hooks or `itr git scan`
+    Commits {
+        /// Issue ID
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
+    },
+
+    /// Show recent git commits touching an issue's files
+    Activity {
+        /// Issue ID
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
+
+        /// Only commits after this date, in any form `git log --since` takes
+        /// (default: when the issue was created)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Max commits to report
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// File TODO/FIXME/HACK comments as `code-todo` issues, updating ones already filed
+    Scan {
+        /// File or directory to scan (default: the current directory); .gitignore is respected
+        path: Option<String>,
+
+        /// Run inside a transaction, print the would-be result, then roll back
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Dump the current database schema, or with --json the JSON Schema of itr's output
+    Schema {
+        /// Print JSON Schema for the models `-f json` emits instead of the SQL schema
+        #[arg(long)]
+        json: bool,
+
+        /// Only this model's schema [default: every model, keyed by name]
+        #[arg(long = "type", value_enum, requires = "json")]
+        model: Option<SchemaModel>,
+    },
+
+    /// Print a shell completion script; issue IDs, tags, and config keys complete from the database
+    Completions {
+        /// Shell to generate for
+        shell: CompletionShell,
+    },
 
     /// Rebuild and reinstall itr from source
     Upgrade {
@@ -463,13 +1057,18 @@ pub enum Commands {
     #[command(visible_alias = "start")]
     Claim {
         /// Optional issue ID to claim directly (single ID only — claiming is deliberate)
-        id: Option<i64>,
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: Option<String>,
 
         /// Filter by skill (repeatable, AND logic)
         #[arg(long)]
         skill: Vec<String>,
 
-        /// Agent name for assignment (falls back to `ITR_AGENT` env var)
+        /// Skills to prefer, comma-separated (default: the registered agent's skills)
+        #[arg(long)]
+        skills: Option<String>,
+
+        /// Agent name for assignment (falls back to `ITR_AGENT`, then config `agent.name`)
         #[arg(long)]
         agent: Option<String>,
 
@@ -481,22 +1080,62 @@ pub enum Commands {
     /// Assign an issue to an agent
     Assign {
         /// Issue ID
-        id: i64,
+        #[arg(value_parser = single_issue_ref, required_unless_present = "auto", add = ArgValueCandidates::new(completion::issue_ids))]
+        id: Option<String>,
 
         /// Agent name
-        agent: String,
+        #[arg(required_unless_present = "auto")]
+        agent: Option<String>,
+
+        /// Spread the unassigned ready queue over registered agents by skills, load, and files
+        #[arg(long, conflicts_with_all = ["id", "agent"])]
+        auto: bool,
+
+        /// With --auto: never give an agent more than this many active issues
+        #[arg(long, requires = "auto")]
+        max_load: Option<usize>,
+
+        /// With --auto: print the plan, then roll the assignments back
+        #[arg(long, requires = "auto")]
+        dry_run: bool,
     },
 
     /// Unassign an issue
     Unassign {
         /// Issue ID
-        id: i64,
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
+    },
+
+    /// Hand an issue to another agent: reassign it, claim it for them, and leave a handoff note
+    Handoff {
+        /// Issue ID
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
+
+        /// Agent taking over
+        #[arg(long)]
+        to: String,
+
+        /// What the next agent needs to know
+        summary: String,
+
+        /// Agent handing off (falls back to `ITR_AGENT`, then config `agent.name`)
+        #[arg(long, default_value = "")]
+        agent: String,
+    },
+
+    /// Review queue: list issues in `review`, approve them to done, or reject them back to open
+    Review {
+        #[command(subcommand)]
+        action: ReviewAction,
     },
 
     /// View event history (audit log)
     Log {
         /// Issue ID (omit for recent events across all issues)
-        id: Option<i64>,
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: Option<String>,
 
         /// Max events to show
         #[arg(short = 'n', long, default_value = "50")]
@@ -514,12 +1153,12 @@ pub enum Commands {
     /// Create a relation between issues
     Relate {
         /// Source issue ID(s) — repeat, comma-separate, or use ranges (e.g. 124-132)
-        #[arg(value_name = "ID", required = true, num_args = 1..)]
+        #[arg(value_name = "ID", required = true, num_args = 1.., add = ArgValueCandidates::new(completion::issue_ids))]
         ids: Vec<String>,
 
         /// Target issue ID
-        #[arg(long)]
-        to: i64,
+        #[arg(value_parser = single_issue_ref, long)]
+        to: String,
 
         /// Relation type: duplicate|related|supersedes
         #[arg(long, visible_alias = "type", default_value = "related")]
@@ -529,18 +1168,19 @@ pub enum Commands {
     /// Remove a relation between two issues
     Unrelate {
         /// Source issue ID
-        id: i64,
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
 
         /// Target issue ID
-        #[arg(long)]
-        from: i64,
+        #[arg(value_parser = single_issue_ref, long)]
+        from: String,
 
         /// Only remove this relation type: duplicate|related|supersedes (default: all types)
         #[arg(long, visible_alias = "type")]
         relation_type: Option<String>,
     },
 
-    /// Rebuild the full-text search index
+    /// Rebuild the full-text search index and `#ID` cross-references
     Reindex,
 
     /// Search issues by text across all fields
@@ -584,7 +1224,7 @@ pub enum Commands {
     /// Show issues or get detail for one or more issues
     Show {
         /// Issue ID(s) — repeat or comma-separate; omit to list all non-terminal issues
-        #[arg(value_name = "ID", num_args = 0..)]
+        #[arg(value_name = "ID", num_args = 0.., add = ArgValueCandidates::new(completion::issue_ids))]
         ids: Vec<String>,
         /// Include all statuses (done, wontfix)
         #[arg(long)]
@@ -594,7 +1234,7 @@ pub enum Commands {
 
 #[derive(Subcommand)]
 pub enum BatchAction {
-    /// Bulk-create issues from JSON array on stdin
+    /// Bulk-create issues from a JSON array or JSONL (one object per line) on stdin
     #[command(visible_alias = "create")]
     Add {
         /// Validate the payload and print per-item verdicts without writing
@@ -708,8 +1348,8 @@ pub enum BulkAction {
     /// Relate all issues matching filters to a target issue
     Relate {
         /// Target issue ID
-        #[arg(long)]
-        to: i64,
+        #[arg(value_parser = single_issue_ref, long)]
+        to: String,
 
         /// Relation type: duplicate|related|supersedes
         #[arg(long, visible_alias = "type", default_value = "related")]
@@ -746,9 +1386,9 @@ pub enum BulkAction {
 
     /// Make all issues matching filters blocked by --on
     Depend {
-        /// Issue ID that blocks the matched issues
+        /// Issue ID that blocks the matched issues — bare, or project-qualified
         #[arg(long)]
-        on: i64,
+        on: String,
 
         /// Filter by status
         #[arg(long)]
@@ -784,7 +1424,7 @@ pub enum BulkAction {
         /// Note content
         text: String,
 
-        /// Agent/session identifier
+        /// Agent/session identifier (falls back to `ITR_AGENT`, then config `agent.name`)
         #[arg(long, default_value = "")]
         agent: String,
 
@@ -837,6 +1477,325 @@ pub enum SkillAction {
     },
 }
 
+#[derive(Subcommand)]
+pub enum GitAction {
+    /// Install prepare-commit-msg/commit-msg/post-commit hooks that tag commits
+    /// with the active issue (`Itr-Issue: #N` trailer) and note the commit on it
+    InstallHooks {
+        /// Overwrite existing hooks not installed by itr
+        #[arg(long)]
+        force: bool,
+    },
+    /// Link a commit to an issue (the post-commit hook runs this for the
+    /// `Itr-Issue` trailer); see `itr commits`
+    Link {
+        /// Issue ID
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
+
+        /// Commit to link [default: HEAD]
+        rev: Option<String>,
+    },
+    /// Close issues referenced by commit messages (`closes #12`, `fixes #3`,
+    /// `Itr-Close: 12`), using the commit hash as the close reason
+    Scan {
+        /// Git revision range to scan (e.g. HEAD~20.., v1.2..main); default: last 20 commits
+        #[arg(long)]
+        range: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TagAction {
+    /// List every tag in use with open/closed issue counts
+    List,
+    /// Rename a tag on every issue that carries it (one transaction)
+    Rename {
+        /// Existing tag
+        #[arg(add = ArgValueCandidates::new(completion::tags))]
+        old: String,
+        /// Replacement tag (merged if an issue already has it)
+        new: String,
+    },
+    /// Remove a tag (and its description/color) from every issue that carries it
+    Rm {
+        /// Tag to remove
+        #[arg(add = ArgValueCandidates::new(completion::tags))]
+        tag: String,
+    },
+    /// Set a tag's description and color; with neither flag, show them
+    Describe {
+        /// Tag to describe
+        #[arg(add = ArgValueCandidates::new(completion::tags))]
+        tag: String,
+
+        /// Description shown in `tag list` (empty string clears)
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Color for pretty output: black, red, green, yellow, blue, magenta,
+        /// cyan, white, gray, or #rrggbb (empty string clears)
+        #[arg(long)]
+        color: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProjectAction {
+    /// Create a project
+    Add {
+        /// Project name (no commas)
+        name: String,
+
+        /// Description shown in `project list`
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Key prefix for the project's issues (e.g. APP gives APP-1, APP-2, ...)
+        #[arg(long, value_name = "PREFIX")]
+        key: Option<String>,
+    },
+    /// Set the key prefix of a project's issues and key the ones without a key ("" stops new keys)
+    Key {
+        /// Project name
+        name: String,
+
+        /// Key prefix: a letter, then letters or digits (e.g. APP)
+        prefix: String,
+    },
+    /// Make a project current for later commands; with no name, clear it
+    Switch {
+        /// Project to switch to
+        name: Option<String>,
+    },
+    /// List projects with open/closed issue counts
+    List,
+    /// Move issues into a project (an empty name detaches them)
+    Move {
+        /// Target project (created if missing)
+        #[arg(value_name = "PROJECT")]
+        target: String,
+
+        /// Issue IDs to move
+        #[arg(value_parser = single_issue_ref, required = true, num_args = 1..)]
+        ids: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ComponentAction {
+    /// Register a component, or change the defaults of an existing one
+    Add {
+        /// Component name (no commas)
+        name: String,
+
+        /// Assignee for unassigned issues filed in or moved to the component ("" clears it)
+        #[arg(long)]
+        assignee: Option<String>,
+
+        /// Comma-separated tags added to issues filed in or moved to the component ("" clears them)
+        #[arg(long)]
+        tags: Option<String>,
+    },
+    /// List components with their defaults and open/closed issue counts
+    List,
+    /// Unregister a component; its issues are left without one
+    #[command(visible_alias = "rm")]
+    Remove {
+        /// Component name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AgentAction {
+    /// Register an agent, or refresh one: given skills and description replace the stored ones
+    Register {
+        /// Agent name (default: `ITR_AGENT`, then config `agent.name`)
+        name: Option<String>,
+
+        /// Skill the agent has (repeatable or comma-separated)
+        #[arg(long)]
+        skill: Vec<String>,
+
+        /// Description shown in `agent show`
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+    /// List registered agents with their workload and last-seen time
+    List,
+    /// Show one agent's skills and assigned issues
+    Show {
+        /// Agent name (default: `ITR_AGENT`, then config `agent.name`)
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SessionAction {
+    /// Start a session for an agent (continues the open one if there is one)
+    Start {
+        /// Agent name (falls back to `ITR_AGENT`, then config `agent.name`)
+        #[arg(long)]
+        agent: Option<String>,
+    },
+    /// End the agent's open session: record the touched issues and add the summary to each
+    End {
+        /// What the session accomplished
+        #[arg(long)]
+        summary: String,
+
+        /// Agent name (falls back to `ITR_AGENT`, then config `agent.name`)
+        #[arg(long)]
+        agent: Option<String>,
+    },
+    /// List sessions, newest first (a standup view of who touched what)
+    List {
+        /// Only this agent's sessions
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Maximum sessions to show
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ReviewAction {
+    /// List issues awaiting review, most urgent first
+    List,
+    /// Approve an issue in review: close it as done
+    Approve {
+        /// Issue ID
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
+
+        /// Close reason (default: "Approved in review")
+        reason: Option<String>,
+    },
+    /// Reject an issue in review: reopen it with a note saying what to change
+    Reject {
+        /// Issue ID
+        #[arg(value_parser = single_issue_ref, add = ArgValueCandidates::new(completion::issue_ids))]
+        id: String,
+
+        /// What needs to change (required)
+        note: String,
+
+        /// Reviewer (falls back to `ITR_AGENT`, then config `agent.name`)
+        #[arg(long, default_value = "")]
+        agent: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// Save the current issues and dependencies under a name
+    Save {
+        /// Snapshot name (no spaces; `current` is reserved)
+        name: String,
+    },
+    /// List saved snapshots, oldest first
+    List,
+    /// Delete a saved snapshot
+    Rm {
+        /// Snapshot name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MirrorAction {
+    /// Write every issue to the mirror file, one sorted line per issue
+    Write {
+        /// Mirror file (default: .itr/issues.jsonl next to the database)
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Update the database to match the mirror file
+    Read {
+        /// Mirror file (default: .itr/issues.jsonl next to the database)
+        #[arg(long)]
+        path: Option<String>,
+
+        /// Run inside a transaction, print the would-be result, then roll back
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Three-way merge of mirror files, for use as a git merge driver
+    Merge {
+        /// Common ancestor version (git's %O)
+        base: String,
+        /// This branch's version, overwritten with the result (git's %A)
+        ours: String,
+        /// The other branch's version (git's %B)
+        theirs: String,
+    },
+}
+
+/// Which side wins an `itr sync` conflict.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SyncPrefer {
+    /// Overwrite the remote's value with this database's
+    Local,
+    /// Overwrite this database's value with the remote's
+    Remote,
+}
+
+/// Period length for `itr stats --trend`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TrendPeriod {
+    /// Calendar days (UTC)
+    Daily,
+    /// ISO weeks, Monday to Sunday
+    Weekly,
+    /// Calendar months
+    Monthly,
+}
+
+/// Which non-error outcomes `--fail-on` turns into a non-zero exit: `empty`
+/// any empty result set, `blocked` only a `next`/`ready` pick that came back
+/// empty because every matching issue is blocked.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum FailOn {
+    #[default]
+    None,
+    Empty,
+    Blocked,
+}
+
+/// Output model for `itr schema --json --type`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SchemaModel {
+    /// An issue's stored fields (the `list`/`export` issue record)
+    Issue,
+    /// `get`/`add`/`update`/`close` detail, with urgency, notes, and links
+    Detail,
+    /// `stats`
+    Stats,
+    /// `graph`
+    Graph,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Elvish,
+    Fish,
+    Powershell,
+    Zsh,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum CycleFixMode {
+    /// Ask which edge to drop for each cycle (the suggested edge when stdin
+    /// or stderr is not a terminal, or with --yes)
+    Interactive,
+    /// Drop the suggested edges without asking
+    Suggested,
+}
+
 #[derive(Copy, Clone, ValueEnum)]
 pub enum SkillScope {
     /// ~/.claude/skills/itr/SKILL.md
@@ -850,9 +1809,28 @@ pub enum ConfigAction {
     /// List all settings
     List,
     /// Get a config value
-    Get { key: String },
+    Get {
+        #[arg(add = ArgValueCandidates::new(completion::config_keys))]
+        key: String,
+    },
     /// Set a config value
-    Set { key: String, value: String },
+    Set {
+        #[arg(add = ArgValueCandidates::new(completion::config_keys))]
+        key: String,
+        value: String,
+    },
     /// Restore all defaults
     Reset,
+    /// Print this database's settings (not defaults) to copy into another
+    /// project or check into the repo
+    Export {
+        /// Export format: toml|json
+        #[arg(long, default_value = "toml")]
+        export_format: String,
+    },
+    /// Apply settings from a TOML or JSON file written by `config export`
+    Import {
+        /// Path to the settings file
+        file: String,
+    },
 }
@@ -0,0 +1,117 @@
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::FileCommit;
+use rusqlite::Connection;
+use std::process::Command;
+
+/// `itr activity <ID> [--since WHEN] [-n N]` — the most recent commits that
+/// touched any of the issue's `files`, newest first, so whoever picks the
+/// issue up sees what changed underneath it. `since` defaults to the issue's
+/// creation time. Paths are taken from the top of the work tree, as `itr
+/// scan` records them, whatever directory `itr` runs in.
+pub fn run(
+    conn: &Connection,
+    id: i64,
+    since: Option<&str>,
+    limit: usize,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let issue = db::get_issue(conn, id)?;
+    let since = since.unwrap_or(&issue.created_at);
+    if issue.files.is_empty() {
+        error::print_empty(
+            fmt.is_json(),
+            &format!("Issue {} lists no files to check for activity.", id),
+        );
+        return Ok(());
+    }
+
+    let commits = read_file_commits(&issue.files, since, limit)?;
+    if commits.is_empty() {
+        error::print_empty(
+            fmt.is_json(),
+            &format!(
+                "No commits touched the files of issue {} since {}.",
+                id, since
+            ),
+        );
+        return Ok(());
+    }
+    format::print_output(
+        &format::format_activity(id, since, &commits, issue.files.len(), fmt),
+        fmt,
+    );
+    Ok(())
+}
+
+/// Run `git log --since` limited to `files` and parse each commit with the
+/// subset of `files` it changed.
+fn read_file_commits(
+    files: &[String],
+    since: &str,
+    limit: usize,
+) -> Result<Vec<FileCommit>, ItrError> {
+    let output = Command::new("git")
+        .args(["log", "--name-only", "--format=%x1e%H%x1f%an%x1f%aI%x1f%s"])
+        .arg(format!("--since={}", since))
+        .arg(format!("-n{}", limit))
+        .arg("--")
+        .args(files.iter().map(|f| format!(":(top,literal){}", f)))
+        .output()?;
+    if !output.status.success() {
+        return Err(ItrError::InvalidValue {
+            field: "since".to_string(),
+            value: since.to_string(),
+            valid: format!(
+                "a date git understands, inside a git repository (git: {})",
+                String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+            ),
+        });
+    }
+    Ok(parse_log(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git log --name-only` output whose records start with `\x1e` and
+/// whose header fields are separated by `\x1f`.
+fn parse_log(out: &str) -> Vec<FileCommit> {
+    out.split('\x1e')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let mut fields = lines.next()?.splitn(4, '\x1f');
+            Some(FileCommit {
+                hash: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+                files: lines
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_log_splits_headers_and_file_lists() {
+        let out =
+            "\x1eaaa\x1fAnn\x1f2026-10-02T10:00:00+00:00\x1fFix: parser\n\nsrc/a.rs\nsrc/b.rs\n\
+                   \x1ebbb\x1fBo\x1f2026-10-01T09:00:00+00:00\x1fStart\n\nsrc/a.rs\n";
+        let commits = parse_log(out);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].subject, "Fix: parser");
+        assert_eq!(commits[0].files, vec!["src/a.rs", "src/b.rs"]);
+        assert_eq!(commits[1].author, "Bo");
+        assert_eq!(commits[1].files, vec!["src/a.rs"]);
+        assert!(parse_log("").is_empty());
+    }
+}
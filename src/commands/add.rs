@@ -1,31 +1,85 @@
-use crate::commands::batch::{parse_add_item, parse_blocked_by_entry, BlockedByRef};
-use crate::commands::build_issue_detail;
+use crate::commands::batch::{self, parse_add_item, parse_blocked_by_entry, BlockedByRef};
+use crate::commands::{build_issue_detail, policy, project};
 use crate::db;
 use crate::error::ItrError;
 use crate::format::{self, Format};
-use crate::models::IssueDetail;
-use crate::normalize::{self, validate_kind, validate_priority};
+use crate::models::{BatchItemResult, BatchResult};
+use crate::normalize;
+use crate::store::{self, NewIssue};
 use crate::urgency::UrgencyConfig;
 use crate::util;
 use rusqlite::Connection;
+use std::fs;
 use std::io::{self, Read};
 
-/// Fully parsed `add` input, independent of whether it came from CLI flags or
-/// a `--stdin-json` payload. `review_notes` carries REVIEW notes accumulated
-/// during parsing (invalid `blocked_by` tokens, unrecognized JSON fields, ...).
-pub(crate) struct AddRequest {
-    pub title: String,
-    pub priority: String,
-    pub kind: String,
-    pub context: String,
-    pub files: Vec<String>,
-    pub tags: Vec<String>,
-    pub skills: Vec<String>,
-    pub acceptance: String,
-    pub parent_id: Option<i64>,
-    pub assigned_to: String,
-    pub blocked_by_ids: Vec<i64>,
-    pub review_notes: Vec<String>,
+/// Prefix of the config keys holding per-kind templates for `itr add`
+/// (`defaults.add.bug.priority=high`, `defaults.add.bug.context=...`).
+pub const DEFAULTS_PREFIX: &str = "defaults.add.";
+/// Fields a `defaults.add.<kind>.<field>` key can fill in.
+pub const DEFAULT_FIELDS: &[&str] = &["priority", "tags", "context"];
+
+/// Check a `defaults.add.<kind>.<field>` value for `config set`, returning
+/// what to store: a normalized priority, a comma-separated tag list, or the
+/// context skeleton verbatim.
+pub fn validate_default(key: &str, value: &str) -> Result<String, String> {
+    let rest = key.strip_prefix(DEFAULTS_PREFIX).unwrap_or(key);
+    let Some((kind, field)) = rest.split_once('.') else {
+        return Err(format!(
+            "expected {}<kind>.<field> (fields: {})",
+            DEFAULTS_PREFIX,
+            DEFAULT_FIELDS.join(", ")
+        ));
+    };
+    if normalize::validate_kind(kind).is_err() {
+        return Err(format!(
+            "'{}' is not a kind (valid: bug, feature, task, epic)",
+            kind
+        ));
+    }
+    match field {
+        "priority" => {
+            let priority = normalize::normalize_priority(value.trim());
+            normalize::validate_priority(&priority)
+                .map(|()| priority)
+                .map_err(|_| "expected critical, high, medium, or low".to_string())
+        }
+        "tags" => Ok(util::parse_comma_list(value).join(",")),
+        "context" => Ok(value.to_string()),
+        _ => Err(format!(
+            "unknown field '{}' (valid: {})",
+            field,
+            DEFAULT_FIELDS.join(", ")
+        )),
+    }
+}
+
+/// Fill the fields the caller left out from the issue kind's
+/// `defaults.add.<kind>.*` template. Anything given explicitly wins, even an
+/// explicit `medium` priority.
+fn apply_template(
+    conn: &Connection,
+    request: &mut NewIssue,
+    priority_given: bool,
+) -> Result<(), ItrError> {
+    let kind = normalize::normalize_kind(&request.kind);
+    let default =
+        |field: &str| db::config_get(conn, &format!("{}{}.{}", DEFAULTS_PREFIX, kind, field));
+    if !priority_given {
+        if let Some(priority) = default("priority")? {
+            request.priority = priority;
+        }
+    }
+    if request.tags.is_empty() {
+        if let Some(tags) = default("tags")? {
+            request.tags = util::parse_comma_list(&tags);
+        }
+    }
+    if request.context.trim().is_empty() {
+        if let Some(context) = default("context")? {
+            request.context = context;
+        }
+    }
+    Ok(())
 }
 
 fn parse_blocked_by_tokens(blocked_by: Option<String>) -> (Vec<i64>, Vec<String>) {
@@ -52,7 +106,7 @@ fn parse_blocked_by_tokens(blocked_by: Option<String>) -> (Vec<i64>, Vec<String>
 /// item, including string/integer `blocked_by` entries (#165) and the
 /// `parent` alias for `parent_id` (#150). Unresolvable tokens become REVIEW
 /// notes instead of being silently dropped.
-fn parse_stdin_json(input: &str) -> Result<AddRequest, ItrError> {
+fn parse_stdin_json(input: &str) -> Result<NewIssue, ItrError> {
     let value: serde_json::Value = serde_json::from_str(input)?;
     let (data, mut review_notes) = parse_add_item(&value)?;
 
@@ -63,13 +117,28 @@ fn parse_stdin_json(input: &str) -> Result<AddRequest, ItrError> {
             Ok(BlockedByRef::BatchIndex(idx)) => review_notes.push(format!(
                 "REVIEW: blocked_by '@{idx}' was ignored; '@N' batch references are only valid in batch add"
             )),
+            Ok(BlockedByRef::Name(name)) => review_notes.push(format!(
+                "REVIEW: blocked_by 'ref:{name}' was ignored; 'ref:' batch references are only valid in batch add"
+            )),
             Err(token) => review_notes.push(format!(
                 "REVIEW: blocked_by '{token}' is not a valid issue ID and was ignored. Valid: integer issue IDs"
             )),
         }
     }
+    // `status` and `notes` share the batch item shape but only batch add
+    // applies them; never drop them silently.
+    for (field, given) in [
+        ("status", data.status.is_some()),
+        ("notes", !data.notes.is_empty()),
+    ] {
+        if given {
+            review_notes.push(format!(
+                "REVIEW: '{field}' was ignored; it is only applied by batch add"
+            ));
+        }
+    }
 
-    Ok(AddRequest {
+    Ok(NewIssue {
         title: data.title,
         priority: data.priority,
         kind: data.kind,
@@ -85,98 +154,272 @@ fn parse_stdin_json(input: &str) -> Result<AddRequest, ItrError> {
         acceptance: data.acceptance,
         parent_id: data.parent_id,
         assigned_to: data.assigned_to,
+        severity: data.severity,
+        component: data.component,
+        affects_version: data.affects_version,
+        fixed_in_version: data.fixed_in_version,
+        repro: data.repro,
+        estimate: data.estimate,
+        project: project::scope().unwrap_or_default(),
         blocked_by_ids,
         review_notes,
     })
 }
 
-/// Validate, insert, and link a parsed add request. Returns the detail of the
-/// created issue. Soft fallbacks: unrecognized priority/kind default with a
-/// REVIEW note; a nonexistent parent creates the issue parentless with a
-/// REVIEW note (#167). A nonexistent `blocked_by` ID remains a hard `NotFound`
-/// error (whole insert rolls back), matching the documented CLI contract.
-pub(crate) fn execute(conn: &Connection, req: AddRequest) -> Result<IssueDetail, ItrError> {
-    let mut review_notes = req.review_notes;
-
-    let priority = normalize::normalize_priority(&req.priority);
-    let kind = normalize::normalize_kind(&req.kind);
-
-    let priority = match validate_priority(&priority) {
-        Ok(()) => priority,
-        Err(_) => {
-            review_notes.push(format!(
-                "REVIEW: priority '{}' not recognized, defaulted to 'medium'. Valid: critical, high, medium, low",
-                priority
-            ));
-            "medium".to_string()
-        }
-    };
-    let kind = match validate_kind(&kind) {
-        Ok(()) => kind,
-        Err(_) => {
-            review_notes.push(format!(
-                "REVIEW: kind '{}' not recognized, defaulted to 'task'. Valid: bug, feature, task, epic",
-                kind
-            ));
-            "task".to_string()
+/// A comma-separated flag plus its repeatable single-value form, trimmed
+/// (and lowercased for skills), blanks dropped.
+fn merge_list(list: Option<String>, repeated: Vec<String>, lower: bool) -> Vec<String> {
+    let fold = |s: &str| {
+        if lower {
+            s.trim().to_lowercase()
+        } else {
+            s.trim().to_string()
         }
     };
+    let mut out: Vec<String> = list
+        .as_deref()
+        .map(|l| l.split(',').map(fold).filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    out.extend(repeated.iter().map(|s| fold(s)).filter(|s| !s.is_empty()));
+    out
+}
 
-    let tx = conn.unchecked_transaction()?;
+/// One checkbox line of a Markdown task list.
+#[derive(Debug, PartialEq)]
+struct MarkdownTask {
+    title: String,
+    /// `- [x]` rather than `- [ ]`.
+    done: bool,
+    tags: Vec<String>,
+    priority: Option<String>,
+    /// Index of the nearest less-indented task above this one.
+    parent: Option<usize>,
+}
 
-    // Soft fallback (#167): a parent that doesn't exist would otherwise
-    // surface as a raw FOREIGN KEY constraint error.
-    let parent_id = match req.parent_id {
-        Some(p) if !db::issue_exists(&tx, p)? => {
-            review_notes.push(format!(
-                "REVIEW: parent {p} not found; issue created without a parent"
+/// Fields `add --from-markdown` gives every task, from the other `add` flags.
+/// `parent` only applies to top-level tasks; a `!priority` annotation wins
+/// over `priority`, and `#tags` add to `tags`.
+pub struct MarkdownDefaults {
+    pub priority: Option<String>,
+    pub kind: String,
+    pub tags: Vec<String>,
+    pub skills: Vec<String>,
+    pub parent: Option<i64>,
+    pub assigned_to: String,
+    pub component: String,
+    pub affects_version: String,
+    pub fixed_in_version: String,
+}
+
+/// Collect `- [ ] title` / `- [x] title` lines (`*`, `+`, and `1.` markers
+/// too), skipping fenced code blocks. Indentation (a tab counts as four
+/// spaces) nests a task under the nearest less-indented task above it.
+/// `#word` tokens become tags and `!word` the priority; the rest is the title.
+fn parse_markdown_tasks(text: &str) -> (Vec<MarkdownTask>, Vec<String>) {
+    let mut tasks: Vec<MarkdownTask> = Vec::new();
+    let mut notes = Vec::new();
+    // (indent, task index) of the tasks that can still take children.
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut in_fence = false;
+
+    for (n, raw) in text.lines().enumerate() {
+        let body = raw.trim_start();
+        if body.starts_with("```") || body.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let Some((done, rest)) = checkbox(body) else {
+            continue;
+        };
+        let indent: usize = raw[..raw.len() - body.len()]
+            .chars()
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum();
+
+        let mut title = Vec::new();
+        let mut tags = Vec::new();
+        let mut priority = None;
+        for word in rest.split_whitespace() {
+            let annotation = |sigil: char| {
+                word.strip_prefix(sigil)
+                    .map(|w| w.trim_end_matches([',', '.', ';', ':']))
+                    .filter(|w| w.starts_with(|c: char| c.is_alphabetic()))
+            };
+            if let Some(tag) = annotation('#') {
+                tags.push(tag.to_string());
+            } else if let Some(p) = annotation('!') {
+                priority = Some(p.to_string());
+            } else {
+                title.push(word);
+            }
+        }
+        if title.is_empty() {
+            notes.push(format!(
+                "REVIEW: line {}: task has no title; skipped",
+                n + 1
             ));
-            None
+            continue;
         }
-        other => other,
-    };
 
-    let mut tags_vec = req.tags;
-    if !review_notes.is_empty() && !tags_vec.contains(&"_needs_review".to_string()) {
-        tags_vec.push("_needs_review".to_string());
+        while open.last().is_some_and(|&(i, _)| i >= indent) {
+            open.pop();
+        }
+        tasks.push(MarkdownTask {
+            title: title.join(" "),
+            done,
+            tags,
+            priority,
+            parent: open.last().map(|&(_, idx)| idx),
+        });
+        open.push((indent, tasks.len() - 1));
     }
+    (tasks, notes)
+}
 
-    let issue = db::insert_issue(
-        &tx,
-        &req.title,
-        &priority,
-        &kind,
-        &req.context,
-        &req.files,
-        &tags_vec,
-        &req.skills,
-        &req.acceptance,
-        parent_id,
-        &req.assigned_to,
-    )?;
+/// Split a list item into whether its box is checked and the text after it.
+fn checkbox(line: &str) -> Option<(bool, &str)> {
+    let item = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+        .or_else(|| {
+            let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            (digits > 0)
+                .then(|| &line[digits..])
+                .and_then(|r| r.strip_prefix(". ").or_else(|| r.strip_prefix(") ")))
+        })?
+        .trim_start();
+    let (done, rest) = if let Some(rest) = item.strip_prefix("[ ]") {
+        (false, rest)
+    } else if let Some(rest) = item
+        .strip_prefix("[x]")
+        .or_else(|| item.strip_prefix("[X]"))
+    {
+        (true, rest)
+    } else {
+        return None;
+    };
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some((done, rest))
+}
 
-    // Add review notes
-    for note_text in &review_notes {
-        db::add_note(&tx, issue.id, note_text, "itr")?;
+/// `itr add --from-markdown <file>` — create one issue per task line of a
+/// Markdown checklist in one transaction (see [`parse_markdown_tasks`]).
+/// Nested tasks become children of the task above them; checked tasks are
+/// created and closed. Each task goes through the same template, policy,
+/// and soft-fallback path as a single `add`.
+fn run_from_markdown(
+    conn: &Connection,
+    path: &str,
+    defaults: MarkdownDefaults,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let text = fs::read_to_string(path)?;
+    let (tasks, notes) = parse_markdown_tasks(&text);
+    for note in &notes {
+        eprintln!("{}", note);
+    }
+    if tasks.is_empty() {
+        return Err(ItrError::InvalidValue {
+            field: "from-markdown".to_string(),
+            value: path.to_string(),
+            valid: "a Markdown file with `- [ ] title` task lines".to_string(),
+        });
     }
 
-    // Add dependencies
-    for blocker_id in &req.blocked_by_ids {
-        db::add_dependency(&tx, *blocker_id, issue.id)?;
+    let tx = db::begin_write(conn)?;
+    let mut created: Vec<i64> = Vec::with_capacity(tasks.len());
+    for task in &tasks {
+        let mut tags = defaults.tags.clone();
+        for tag in &task.tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        let mut request = NewIssue {
+            title: task.title.clone(),
+            priority: task
+                .priority
+                .clone()
+                .or_else(|| defaults.priority.clone())
+                .unwrap_or_else(|| "medium".to_string()),
+            kind: defaults.kind.clone(),
+            context: String::new(),
+            files: vec![],
+            tags,
+            skills: defaults.skills.clone(),
+            acceptance: String::new(),
+            parent_id: match task.parent {
+                Some(idx) => Some(created[idx]),
+                None => defaults.parent,
+            },
+            assigned_to: defaults.assigned_to.clone(),
+            severity: String::new(),
+            component: defaults.component.clone(),
+            affects_version: defaults.affects_version.clone(),
+            fixed_in_version: defaults.fixed_in_version.clone(),
+            repro: None,
+            estimate: None,
+            project: project::scope().unwrap_or_default(),
+            blocked_by_ids: vec![],
+            review_notes: vec![],
+        };
+        let priority_given = task.priority.is_some() || defaults.priority.is_some();
+        apply_template(&tx, &mut request, priority_given)?;
+        policy::check_required(
+            &tx,
+            &request.kind,
+            &request.context,
+            &request.acceptance,
+            false,
+        )?;
+        let id = store::add_issue(&tx, request)?.issue.id;
+        if task.done {
+            store::close_issue(&tx, id, None, false, None)?;
+        }
+        created.push(id);
     }
 
+    let config = UrgencyConfig::load(&tx);
+    let mut results = Vec::with_capacity(created.len());
+    for &id in &created {
+        let detail = build_issue_detail(&tx, db::get_issue(&tx, id)?, &config)?;
+        let review_notes: Vec<String> = detail
+            .notes
+            .iter()
+            .filter(|n| n.agent == "itr")
+            .map(|n| n.content.clone())
+            .collect();
+        results.push(BatchItemResult {
+            id,
+            outcome: batch::outcome(&review_notes),
+            error: None,
+            notes: review_notes,
+            unblocked: vec![],
+            issue: Some(detail),
+        });
+    }
     tx.commit()?;
 
-    // Build detail for output
-    let config = UrgencyConfig::load(conn);
-    build_issue_detail(conn, issue, &config)
+    let summary = batch::build_summary(&results);
+    let result = BatchResult {
+        action: "add_markdown".to_string(),
+        results,
+        summary,
+        dry_run: false,
+        rolled_back: false,
+    };
+    format::print_output(&format::format_batch_result(&result, fmt), fmt);
+    Ok(())
 }
 
 #[allow(clippy::too_many_arguments)]
 pub fn run(
     conn: &Connection,
     title: Option<String>,
-    priority: &str,
+    priority: Option<String>,
     kind: &str,
     context: Option<String>,
     files: Option<String>,
@@ -189,47 +432,48 @@ pub fn run(
     blocked_by: Option<String>,
     parent: Option<i64>,
     assigned_to: Option<String>,
+    severity: Option<String>,
+    component: Option<String>,
+    affects_version: Option<String>,
+    fixed_in_version: Option<String>,
+    estimate: Option<String>,
     stdin_json: bool,
+    from_markdown: Option<String>,
     fmt: Format,
 ) -> Result<(), ItrError> {
-    let request = if stdin_json {
+    if let Some(path) = from_markdown {
+        let defaults = MarkdownDefaults {
+            priority,
+            kind: kind.to_string(),
+            tags: merge_list(tags, tag, false),
+            skills: merge_list(skills, skill, true),
+            parent,
+            assigned_to: assigned_to.unwrap_or_default(),
+            component: component.unwrap_or_default(),
+            affects_version: affects_version.unwrap_or_default(),
+            fixed_in_version: fixed_in_version.unwrap_or_default(),
+        };
+        return run_from_markdown(conn, &path, defaults, fmt);
+    }
+    let priority_given;
+    let mut request = if stdin_json {
         let mut input = String::new();
         io::stdin().read_to_string(&mut input)?;
-        parse_stdin_json(&input)?
+        let request = parse_stdin_json(&input)?;
+        priority_given = serde_json::from_str::<serde_json::Value>(&input)?
+            .get("priority")
+            .is_some();
+        request
     } else {
+        priority_given = priority.is_some();
         let title = title.ok_or_else(|| ItrError::InvalidValue {
             field: "title".to_string(),
             value: String::new(),
             valid: "non-empty string".to_string(),
         })?;
-        let mut files_vec: Vec<String> = files
-            .as_deref()
-            .map(util::parse_comma_list)
-            .unwrap_or_default();
-        files_vec.extend(
-            file.into_iter()
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty()),
-        );
-        let mut tags_vec: Vec<String> = tags
-            .as_deref()
-            .map(util::parse_comma_list)
-            .unwrap_or_default();
-        tags_vec.extend(
-            tag.into_iter()
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty()),
-        );
-        let mut skills_vec: Vec<String> = skills
-            .as_deref()
-            .map(util::parse_comma_list_lower)
-            .unwrap_or_default();
-        skills_vec.extend(
-            skill
-                .into_iter()
-                .map(|s| s.trim().to_lowercase())
-                .filter(|s| !s.is_empty()),
-        );
+        let files_vec = merge_list(files, file, false);
+        let tags_vec = merge_list(tags, tag, false);
+        let skills_vec = merge_list(skills, skill, true);
         let (blocked_by_ids, invalid_blocked_by) = parse_blocked_by_tokens(blocked_by);
         let review_notes: Vec<String> = invalid_blocked_by
             .iter()
@@ -240,9 +484,9 @@ pub fn run(
                 )
             })
             .collect();
-        AddRequest {
+        NewIssue {
             title,
-            priority: priority.to_string(),
+            priority: priority.unwrap_or_else(|| "medium".to_string()),
             kind: kind.to_string(),
             context: context.unwrap_or_default(),
             files: files_vec,
@@ -251,19 +495,39 @@ pub fn run(
             acceptance: acceptance.unwrap_or_default(),
             parent_id: parent,
             assigned_to: assigned_to.unwrap_or_default(),
+            severity: severity.unwrap_or_default(),
+            component: component.unwrap_or_default(),
+            affects_version: affects_version.unwrap_or_default(),
+            fixed_in_version: fixed_in_version.unwrap_or_default(),
+            repro: None,
+            estimate: estimate
+                .as_deref()
+                .map(super::update::parse_estimate)
+                .transpose()?
+                .flatten(),
+            project: project::scope().unwrap_or_default(),
             blocked_by_ids,
             review_notes,
         }
     };
 
-    let detail = execute(conn, request)?;
-    println!("{}", format::format_issue_detail(&detail, fmt));
+    apply_template(conn, &mut request, priority_given)?;
+    policy::check_required(
+        conn,
+        &request.kind,
+        &request.context,
+        &request.acceptance,
+        request.repro.as_ref().is_some_and(|r| !r.is_empty()),
+    )?;
+    let detail = store::add_issue(conn, request)?;
+    format::print_output(&super::format_issue_result(&detail, fmt), fmt);
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db;
     use crate::db::open_test_db;
 
     fn seed(conn: &Connection, title: &str) -> i64 {
@@ -284,8 +548,8 @@ mod tests {
         .id
     }
 
-    fn request(title: &str) -> AddRequest {
-        AddRequest {
+    fn request(title: &str) -> NewIssue {
+        NewIssue {
             title: title.to_string(),
             priority: "medium".to_string(),
             kind: "task".to_string(),
@@ -296,6 +560,13 @@ mod tests {
             acceptance: String::new(),
             parent_id: None,
             assigned_to: String::new(),
+            severity: String::new(),
+            component: String::new(),
+            affects_version: String::new(),
+            fixed_in_version: String::new(),
+            repro: None,
+            estimate: None,
+            project: String::new(),
             blocked_by_ids: vec![],
             review_notes: vec![],
         }
@@ -310,7 +581,7 @@ mod tests {
         seed(&conn, "two");
         let three = seed(&conn, "three");
         let req = parse_stdin_json(r#"{"title":"t","blocked_by":["3"]}"#).unwrap();
-        let detail = execute(&conn, req).unwrap();
+        let detail = store::add_issue(&conn, req).unwrap();
         assert_eq!(detail.blocked_by, vec![three]);
         assert!(detail.is_blocked);
     }
@@ -322,17 +593,26 @@ mod tests {
         let b = seed(&conn, "b");
         let req =
             parse_stdin_json(&format!(r#"{{"title":"t","blocked_by":["{a}",{b}]}}"#)).unwrap();
-        let detail = execute(&conn, req).unwrap();
+        let detail = store::add_issue(&conn, req).unwrap();
         let mut blockers = detail.blocked_by.clone();
         blockers.sort_unstable();
         assert_eq!(blockers, vec![a, b]);
     }
 
+    #[test]
+    fn stdin_json_estimate_is_stored() {
+        let conn = open_test_db();
+        let req = parse_stdin_json(r#"{"title":"t","estimate":3}"#).unwrap();
+        let detail = store::add_issue(&conn, req).unwrap();
+        assert_eq!(detail.issue.estimate, Some(3));
+        assert_eq!(db::issue_estimate(&conn, detail.issue.id).unwrap(), Some(3));
+    }
+
     #[test]
     fn stdin_json_invalid_blocked_by_reviewed_not_dropped() {
         let conn = open_test_db();
         let req = parse_stdin_json(r#"{"title":"t","blocked_by":["junk","@0"]}"#).unwrap();
-        let detail = execute(&conn, req).unwrap();
+        let detail = store::add_issue(&conn, req).unwrap();
         assert!(detail.blocked_by.is_empty());
         assert!(detail.issue.tags.contains(&"_needs_review".to_string()));
         let notes: Vec<&str> = detail.notes.iter().map(|n| n.content.as_str()).collect();
@@ -347,7 +627,7 @@ mod tests {
         let conn = open_test_db();
         let epic = seed(&conn, "Epic");
         let req = parse_stdin_json(&format!(r#"{{"title":"child","parent":{epic}}}"#)).unwrap();
-        let detail = execute(&conn, req).unwrap();
+        let detail = store::add_issue(&conn, req).unwrap();
         assert_eq!(detail.issue.parent_id, Some(epic));
     }
 
@@ -355,11 +635,71 @@ mod tests {
     fn stdin_json_unknown_field_emits_review_note() {
         let conn = open_test_db();
         let req = parse_stdin_json(r#"{"title":"t","priorty":"high"}"#).unwrap();
-        let detail = execute(&conn, req).unwrap();
+        let detail = store::add_issue(&conn, req).unwrap();
         assert!(detail.issue.tags.contains(&"_needs_review".to_string()));
         assert!(detail.notes.iter().any(|n| n.content.contains("priorty")));
     }
 
+    #[test]
+    fn stdin_json_severity_is_normalized_or_reviewed() {
+        let conn = open_test_db();
+        let req =
+            parse_stdin_json(r#"{"title":"t","priority":"low","severity":"showstopper"}"#).unwrap();
+        let detail = store::add_issue(&conn, req).unwrap();
+        assert_eq!(detail.issue.severity, "blocker");
+        assert_eq!(detail.issue.priority, "low");
+        assert_eq!(
+            db::get_issue(&conn, detail.issue.id).unwrap().severity,
+            "blocker"
+        );
+
+        let req = parse_stdin_json(r#"{"title":"t","severity":"awful"}"#).unwrap();
+        let detail = store::add_issue(&conn, req).unwrap();
+        assert_eq!(detail.issue.severity, "");
+        assert!(detail
+            .notes
+            .iter()
+            .any(|n| n.content.contains("severity 'awful'")));
+    }
+
+    #[test]
+    fn markdown_tasks_nest_by_indent_and_read_annotations() {
+        let text = "# Plan\n\
+            - [ ] Auth overhaul #auth !high\n\
+            \x20   - [x] Login form #ui\n\
+            \t- [ ] Session tokens\n\
+            * [ ] Docs\n\
+            - [ ] #lonely\n\
+            ```\n- [ ] fenced\n```\n\
+            1. [ ] Numbered\n\
+            - [] not a box\n";
+        let (tasks, notes) = parse_markdown_tasks(text);
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Auth overhaul",
+                "Login form",
+                "Session tokens",
+                "Docs",
+                "Numbered"
+            ]
+        );
+        assert_eq!(tasks[0].tags, vec!["auth"]);
+        assert_eq!(tasks[0].priority.as_deref(), Some("high"));
+        assert!(tasks[1].done && !tasks[0].done);
+        let parents: Vec<Option<usize>> = tasks.iter().map(|t| t.parent).collect();
+        assert_eq!(parents, vec![None, Some(0), Some(0), None, None]);
+        assert_eq!(notes, vec!["REVIEW: line 6: task has no title; skipped"]);
+    }
+
+    #[test]
+    fn stdin_json_batch_only_fields_reviewed_not_dropped() {
+        let req = parse_stdin_json(r#"{"title":"t","status":"wip","notes":["x"]}"#).unwrap();
+        assert_eq!(req.review_notes.len(), 2, "{:?}", req.review_notes);
+        assert!(req.review_notes[0].contains("'status' was ignored"));
+    }
+
     // --- #167: nonexistent --parent gets soft fallback, not a FK error ---
 
     #[test]
@@ -367,7 +707,7 @@ mod tests {
         let conn = open_test_db();
         let mut req = request("orphan");
         req.parent_id = Some(9999);
-        let detail = execute(&conn, req).unwrap();
+        let detail = store::add_issue(&conn, req).unwrap();
         assert_eq!(detail.issue.parent_id, None);
         assert!(detail.issue.tags.contains(&"_needs_review".to_string()));
         assert!(detail
@@ -382,11 +722,47 @@ mod tests {
         let epic = seed(&conn, "Epic");
         let mut req = request("child");
         req.parent_id = Some(epic);
-        let detail = execute(&conn, req).unwrap();
+        let detail = store::add_issue(&conn, req).unwrap();
         assert_eq!(detail.issue.parent_id, Some(epic));
         assert!(detail.notes.is_empty());
     }
 
+    #[test]
+    fn kind_template_fills_only_omitted_fields() {
+        let conn = open_test_db();
+        db::config_set(&conn, "defaults.add.bug.priority", "high").unwrap();
+        db::config_set(&conn, "defaults.add.bug.tags", "triage").unwrap();
+        db::config_set(&conn, "defaults.add.bug.context", "Repro:\nExpected:").unwrap();
+
+        let mut req = request("crash");
+        req.kind = "defect".to_string();
+        apply_template(&conn, &mut req, false).unwrap();
+        assert_eq!(req.priority, "high");
+        assert_eq!(req.tags, vec!["triage"]);
+        assert_eq!(req.context, "Repro:\nExpected:");
+
+        let mut req = request("crash");
+        req.kind = "bug".to_string();
+        req.context = "Seen on save".to_string();
+        req.tags = vec!["ui".to_string()];
+        apply_template(&conn, &mut req, true).unwrap();
+        assert_eq!(
+            (req.priority.as_str(), req.tags, req.context.as_str()),
+            ("medium", vec!["ui".to_string()], "Seen on save")
+        );
+
+        let mut task = request("chore");
+        apply_template(&conn, &mut task, false).unwrap();
+        assert_eq!(task.priority, "medium", "other kinds are untouched");
+
+        assert_eq!(
+            validate_default("defaults.add.bug.priority", "P0").as_deref(),
+            Ok("critical")
+        );
+        assert!(validate_default("defaults.add.story.tags", "x").is_err());
+        assert!(validate_default("defaults.add.bug.owner", "x").is_err());
+    }
+
     // --- documented CLI contract: missing blocked_by ID stays a hard error ---
 
     #[test]
@@ -394,7 +770,7 @@ mod tests {
         let conn = open_test_db();
         let mut req = request("blocked");
         req.blocked_by_ids = vec![999];
-        let err = execute(&conn, req).unwrap_err();
+        let err = store::add_issue(&conn, req).unwrap_err();
         assert!(matches!(err, ItrError::NotFound(999)));
         assert!(
             !db::issue_exists(&conn, 1).unwrap(),
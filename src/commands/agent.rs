@@ -0,0 +1,195 @@
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::Issue;
+use crate::util;
+use rusqlite::Connection;
+
+/// `itr agent register [name] [--skill S]... [--description D]` — record an
+/// agent, or refresh one. Given skills and description replace the stored
+/// ones; omitted ones are kept. Either way the agent is marked seen now.
+pub fn register(
+    conn: &Connection,
+    name: Option<&str>,
+    skills: &[String],
+    description: Option<&str>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let name = resolve_name(conn, name)?;
+    let skills: Option<Vec<String>> = (!skills.is_empty()).then(|| {
+        let mut parsed: Vec<String> = Vec::new();
+        for skill in skills.iter().flat_map(|s| util::parse_comma_list_lower(s)) {
+            if !parsed.contains(&skill) {
+                parsed.push(skill);
+            }
+        }
+        parsed
+    });
+    let created = db::register_agent(conn, &name, skills.as_deref(), description.map(str::trim))?;
+    let agent = require(conn, &name)?;
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "action": "agent_register",
+                "created": created,
+                "agent": agent,
+            });
+            format::println_json(&out.to_string());
+        }
+        _ if format::quiet(fmt) => println!("{}", agent.name),
+        _ => println!(
+            "AGENT:{} SKILLS:{}",
+            format::escape_line_value(&agent.name),
+            agent.skills.join(",")
+        ),
+    }
+    Ok(())
+}
+
+/// `itr agent list` — every registered agent with its workload.
+pub fn list(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
+    let agents = db::agents(conn)?;
+    if agents.is_empty() {
+        error::print_empty(fmt.is_json(), "No agents registered.");
+        return Ok(());
+    }
+    format::print_output(&format::format_agents(&agents, fmt), fmt);
+    Ok(())
+}
+
+/// `itr agent show [name]` — one agent's skills and assigned issues.
+pub fn show(conn: &Connection, name: Option<&str>, fmt: Format) -> Result<(), ItrError> {
+    let name = resolve_name(conn, name)?;
+    let agent = require(conn, &name)?;
+    format::print_output(&format::format_agent(&agent, fmt), fmt);
+    Ok(())
+}
+
+/// `REVIEW:` notes for handing `issue` to `agent` (by claim or assign).
+/// Silent until the registry is in use; then an unregistered agent, or one
+/// missing skills the issue lists, is flagged — but never refused.
+pub fn fit_notes(conn: &Connection, agent: &str, issue: &Issue) -> Result<Vec<String>, ItrError> {
+    let agents = db::agents(conn)?;
+    if agents.is_empty() || agent.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(registered) = agents.iter().find(|a| a.name == agent) else {
+        return Ok(vec![format!(
+            "REVIEW: agent '{}' is not registered; see `itr agent list` or run `itr agent register {}`",
+            agent, agent
+        )]);
+    };
+    let missing: Vec<&str> = issue
+        .skills
+        .iter()
+        .filter(|s| !registered.skills.contains(s))
+        .map(String::as_str)
+        .collect();
+    if missing.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(vec![format!(
+        "REVIEW: agent '{}' lacks skill(s) {} that issue {} lists",
+        agent,
+        missing.join(", "),
+        issue.id
+    )])
+}
+
+/// The explicit name, else the acting agent (`ITR_AGENT`, `agent.name`).
+pub(crate) fn resolve_name(conn: &Connection, name: Option<&str>) -> Result<String, ItrError> {
+    let name = match name.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(n) => n.to_string(),
+        None => db::current_agent(conn),
+    };
+    if name.is_empty() {
+        return Err(ItrError::InvalidValue {
+            field: "agent".to_string(),
+            value: String::new(),
+            valid: "an agent name, or set ITR_AGENT / `agent.name`".to_string(),
+        });
+    }
+    Ok(name)
+}
+
+fn require(conn: &Connection, name: &str) -> Result<crate::models::Agent, ItrError> {
+    if let Some(agent) = db::get_agent(conn, name)? {
+        return Ok(agent);
+    }
+    let names: Vec<String> = db::agents(conn)?.into_iter().map(|a| a.name).collect();
+    Err(ItrError::InvalidValue {
+        field: "agent".to_string(),
+        value: name.to_string(),
+        valid: if names.is_empty() {
+            "none yet; register one with `itr agent register`".to_string()
+        } else {
+            names.join(", ")
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_tracks_skills_workload_and_fit() {
+        let conn = db::open_test_db();
+        let skills = ["Rust, sql".to_string()];
+        register(&conn, Some("bob"), &skills, Some("backend"), Format::Json).unwrap();
+        // Re-registering without skills keeps them; a new description wins.
+        register(&conn, Some(" bob "), &[], Some("api"), Format::Json).unwrap();
+        let bob = db::get_agent(&conn, "bob").unwrap().unwrap();
+        assert_eq!(bob.skills, ["rust", "sql"]);
+        assert_eq!(bob.description, "api");
+
+        let issue = db::insert_issue(
+            &conn,
+            "needs go",
+            "medium",
+            "task",
+            "",
+            &[],
+            &[],
+            &["rust".to_string(), "go".to_string()],
+            "",
+            None,
+            "bob",
+        )
+        .unwrap();
+        let bob = db::get_agent(&conn, "bob").unwrap().unwrap();
+        assert_eq!(
+            (bob.assigned.as_slice(), bob.closed),
+            ([issue.id].as_slice(), 0)
+        );
+
+        let notes = fit_notes(&conn, "bob", &issue).unwrap();
+        assert!(notes[0].contains("lacks skill(s) go"), "{notes:?}");
+        let notes = fit_notes(&conn, "carol", &issue).unwrap();
+        assert!(notes[0].contains("'carol' is not registered"), "{notes:?}");
+        assert!(matches!(
+            show(&conn, Some("carol"), Format::Json),
+            Err(ItrError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn fit_notes_stay_quiet_without_a_registry() {
+        let conn = db::open_test_db();
+        let issue = db::insert_issue(
+            &conn,
+            "x",
+            "medium",
+            "task",
+            "",
+            &[],
+            &[],
+            &["go".to_string()],
+            "",
+            None,
+            "",
+        )
+        .unwrap();
+        assert!(fit_notes(&conn, "anyone", &issue).unwrap().is_empty());
+    }
+}
@@ -1,13 +1,13 @@
 use crate::agent_docs::AGENT_DOCS;
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
 
 #[allow(clippy::unnecessary_wraps)]
 pub fn run(fmt: Format) -> Result<(), ItrError> {
     match fmt {
         Format::Json => {
             let out = serde_json::json!({ "guide": AGENT_DOCS });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             print!("{}", AGENT_DOCS);
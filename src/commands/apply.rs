@@ -0,0 +1,305 @@
+use crate::commands::batch::{self, ParsedItem};
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::models::{
+    BatchAddInput, BatchCloseInput, BatchItemResult, BatchNoteInput, BatchResult, BatchUpdateInput,
+};
+use crate::urgency::UrgencyConfig;
+use crate::util;
+use rusqlite::Connection;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Operations `itr apply` understands, in help order.
+const OPS: &str = "add, update, close, depend, note";
+
+/// Fields that may hold an `"@N"` reference to an earlier operation.
+const REF_KEYS: &[&str] = &["id", "on", "parent_id", "parent"];
+
+/// JSON keys recognized by [`DependInput`].
+const DEPEND_KNOWN_KEYS: &[&str] = &["id", "on"];
+
+/// A `depend` operation: `id` becomes blocked by `on`.
+#[derive(Debug, Deserialize)]
+struct DependInput {
+    id: i64,
+    on: i64,
+}
+
+/// `itr apply [--dry-run]` — run a JSON array of operations from stdin, each
+/// `{"op": "add" | "update" | "close" | "depend" | "note", ...}` with the
+/// fields of the matching batch verb (`depend` takes `id` and `on`), in one
+/// transaction. `"@N"` in `id`, `on`, `parent_id`, `parent`, or `blocked_by`
+/// names the issue operation N created or touched; `"ref:<name>"` names the
+/// issue of the earlier add whose `ref` is `name`.
+///
+/// All or nothing: soft fallbacks still apply per operation (`review`
+/// outcomes), but the first `error` outcome rolls everything back, marks the
+/// rest `skipped`, and exits 8 (`ABORTED`) after printing the results.
+pub fn run(conn: &Connection, dry_run: bool, fmt: Format) -> Result<(), ItrError> {
+    let input = batch::read_stdin()?;
+    let (result, failure) = run_core(conn, &input, dry_run)?;
+    format::print_output(&format::format_batch_result(&result, fmt), fmt);
+    match failure {
+        Some(msg) => Err(ItrError::Aborted(format!("{msg}; nothing was applied"))),
+        None => Ok(()),
+    }
+}
+
+/// Apply the operations, returning the per-op results and, when one failed,
+/// why. Nothing is committed on failure or under `dry_run`.
+fn run_core(
+    conn: &Connection,
+    input: &str,
+    dry_run: bool,
+) -> Result<(BatchResult, Option<String>), ItrError> {
+    let values: Vec<Value> = serde_json::from_str(input)?;
+
+    let tx = db::begin_write(conn)?;
+    let config = UrgencyConfig::load(&tx);
+
+    // `touched[N]` is the issue operation N created or changed, for `@N`.
+    let mut touched: Vec<Option<i64>> = Vec::with_capacity(values.len());
+    // Each add's `ref` name, mapped to its operation index.
+    let mut refs: HashMap<String, usize> = HashMap::new();
+    let mut results: Vec<BatchItemResult> = Vec::with_capacity(values.len());
+    let mut failure = None;
+
+    for (idx, raw) in values.iter().enumerate() {
+        if failure.is_some() {
+            results.push(BatchItemResult {
+                id: batch::item_id(raw),
+                outcome: "skipped".to_string(),
+                error: None,
+                notes: vec![],
+                unblocked: vec![],
+                issue: None,
+            });
+            continue;
+        }
+        let op = raw.get("op").and_then(Value::as_str).unwrap_or_default();
+        let result = match apply_op(&tx, idx, op, raw, &touched, &mut refs, &config) {
+            Ok(result) => result,
+            Err(e) => batch::error_result(batch::item_id(raw), e.to_string()),
+        };
+        if let Some(error) = result
+            .error
+            .as_deref()
+            .filter(|_| result.outcome == "error")
+        {
+            let name = if op.is_empty() { "?" } else { op };
+            let error = error.trim_start_matches(&format!("item {idx}: "));
+            failure = Some(format!("operation {idx} ({name}) failed: {error}"));
+        }
+        touched.push(Some(result.id).filter(|id| *id > 0));
+        results.push(result);
+    }
+
+    let rolled_back = failure.is_some();
+    if !dry_run && !rolled_back {
+        tx.commit()?;
+    }
+
+    let summary = batch::build_summary(&results);
+    Ok((
+        BatchResult {
+            action: "apply".to_string(),
+            results,
+            summary,
+            dry_run,
+            rolled_back,
+        },
+        failure,
+    ))
+}
+
+/// Run one operation inside the shared transaction. An `Err` from the store
+/// (a cycle, say) is turned into an `error` outcome by the caller.
+fn apply_op(
+    tx: &Connection,
+    idx: usize,
+    op: &str,
+    raw: &Value,
+    touched: &[Option<i64>],
+    refs: &mut HashMap<String, usize>,
+    config: &UrgencyConfig,
+) -> Result<BatchItemResult, ItrError> {
+    let mut value = raw.clone();
+    if let Some(map) = value.as_object_mut() {
+        map.remove("op");
+    }
+    if let Err(msg) = resolve_refs(&mut value, touched, refs) {
+        return Ok(batch::error_result(batch::item_id(raw), msg));
+    }
+
+    match op {
+        "add" => {
+            let parsed: ParsedItem<BatchAddInput> =
+                batch::parse_item(idx, &value, batch::BATCH_ADD_KNOWN_KEYS);
+            let (mut item, mut notes) = match parsed {
+                Ok(item) => item,
+                Err(error_result) => return Ok(error_result),
+            };
+            batch::claim_ref(refs, &item, idx, &mut notes);
+            let id = batch::insert_add_item(tx, &mut item, &mut notes)?;
+            batch::link_blockers(tx, &item.blocked_by, id, touched, refs, &mut notes)?;
+            batch::added_result(tx, id, notes, config)
+        }
+        "update" => {
+            match batch::parse_item::<BatchUpdateInput>(idx, &value, batch::BATCH_UPDATE_KNOWN_KEYS)
+            {
+                Ok((item, notes)) => batch::update_item(tx, item, notes),
+                Err(error_result) => Ok(error_result),
+            }
+        }
+        "close" => {
+            match batch::parse_item::<BatchCloseInput>(idx, &value, batch::BATCH_CLOSE_KNOWN_KEYS) {
+                Ok((item, notes)) => batch::close_item(tx, item, notes),
+                Err(error_result) => Ok(error_result),
+            }
+        }
+        "note" => {
+            match batch::parse_item::<BatchNoteInput>(idx, &value, batch::BATCH_NOTE_KNOWN_KEYS) {
+                Ok((item, notes)) => batch::note_item(tx, item, notes),
+                Err(error_result) => Ok(error_result),
+            }
+        }
+        "depend" => match batch::parse_item::<DependInput>(idx, &value, DEPEND_KNOWN_KEYS) {
+            Ok((item, review_notes)) => {
+                let created = db::add_dependency(tx, item.on, item.id)?;
+                batch::flag_for_review(tx, item.id, &review_notes)?;
+                let verb = if created {
+                    "blocked"
+                } else {
+                    "already blocked"
+                };
+                let mut notes = vec![format!("{verb} by {}", item.on)];
+                notes.extend_from_slice(&review_notes);
+                Ok(BatchItemResult {
+                    id: item.id,
+                    outcome: batch::outcome(&review_notes),
+                    error: None,
+                    notes,
+                    unblocked: vec![],
+                    issue: None,
+                })
+            }
+            Err(error_result) => Ok(error_result),
+        },
+        "" => Ok(batch::error_result(
+            batch::item_id(raw),
+            format!("item {idx}: missing \"op\" (valid: {OPS})"),
+        )),
+        _ => Ok(batch::error_result(
+            batch::item_id(raw),
+            format!(
+                "item {idx}: unknown op '{op}'{} (valid: {OPS})",
+                util::did_you_mean(op, OPS)
+            ),
+        )),
+    }
+}
+
+/// Replace each `"@N"` or `"ref:<name>"` in [`REF_KEYS`] with the issue
+/// that earlier operation touched. `blocked_by` entries are resolved by the
+/// add path itself.
+fn resolve_refs(
+    value: &mut Value,
+    touched: &[Option<i64>],
+    refs: &HashMap<String, usize>,
+) -> Result<(), String> {
+    let Some(map) = value.as_object_mut() else {
+        return Ok(());
+    };
+    for key in REF_KEYS {
+        let Some(token) = map.get(*key).and_then(Value::as_str) else {
+            continue;
+        };
+        let index = if let Some(n) = token.strip_prefix('@') {
+            n.parse::<usize>().ok()
+        } else if let Some(name) = token.strip_prefix("ref:") {
+            refs.get(name.trim()).copied()
+        } else {
+            continue;
+        };
+        match index.and_then(|i| touched.get(i).copied().flatten()) {
+            Some(id) => {
+                map.insert((*key).to_string(), Value::from(id));
+            }
+            None if touched.is_empty() => {
+                return Err(format!(
+                    "{key} '{token}' does not refer to an earlier operation (there are none)"
+                ));
+            }
+            None => {
+                return Err(format!(
+                    "{key} '{token}' does not refer to an earlier operation. Valid: @0 to @{}",
+                    touched.len() - 1
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    fn count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM issues", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn references_earlier_operations_and_commits_together() {
+        let conn = open_test_db();
+        let input = r#"[
+            {"op": "add", "title": "Epic", "kind": "epic", "ref": "epic"},
+            {"op": "add", "title": "Child", "parent": "@0"},
+            {"op": "depend", "id": "@1", "on": "@0"},
+            {"op": "note", "id": "@1", "text": "started"},
+            {"op": "close", "id": "ref:epic", "reason": "shipped"}
+        ]"#;
+        let (result, failure) = run_core(&conn, input, false).unwrap();
+        assert!(failure.is_none(), "{failure:?}");
+        assert!(!result.rolled_back);
+        assert_eq!(result.summary.ok, 5);
+
+        let (epic, child) = (result.results[0].id, result.results[1].id);
+        assert_eq!(db::get_issue(&conn, child).unwrap().parent_id, Some(epic));
+        assert_eq!(db::get_issue(&conn, epic).unwrap().status, "done");
+        assert_eq!(db::get_notes(&conn, child).unwrap().len(), 1);
+        assert_eq!(result.results[4].unblocked[0].id, child);
+    }
+
+    #[test]
+    fn first_error_rolls_everything_back() {
+        let conn = open_test_db();
+        let input = r#"[
+            {"op": "add", "title": "Kept?"},
+            {"op": "close", "id": 99},
+            {"op": "add", "title": "Never run"}
+        ]"#;
+        let (result, failure) = run_core(&conn, input, false).unwrap();
+        assert_eq!(
+            failure.as_deref(),
+            Some("operation 1 (close) failed: Issue 99 not found")
+        );
+        assert!(result.rolled_back);
+        let outcomes: Vec<&str> = result.results.iter().map(|r| r.outcome.as_str()).collect();
+        assert_eq!(outcomes, vec!["ok", "error", "skipped"]);
+        assert_eq!(count(&conn), 0, "nothing is committed");
+
+        for bad in [
+            r#"[{"op": "clsoe", "id": 1}]"#,
+            r#"[{"op": "note", "id": "@0", "text": "x"}]"#,
+        ] {
+            let (_, failure) = run_core(&conn, bad, false).unwrap();
+            assert!(failure.is_some(), "{bad}");
+        }
+    }
+}
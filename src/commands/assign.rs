@@ -1,41 +1,331 @@
 use crate::commands::build_issue_detail;
+use crate::commands::project;
 use crate::db;
-use crate::error::ItrError;
+use crate::error::{self, ItrError};
 use crate::format::{self, Format};
-use crate::urgency::UrgencyConfig;
+use crate::models::{AutoAssignPlan, AutoAssignment, Issue, ListFilter};
+use crate::urgency::{compute_urgency_from_inputs, UrgencyConfig};
 use rusqlite::Connection;
 
 pub fn run_assign(conn: &Connection, id: i64, agent: &str, fmt: Format) -> Result<(), ItrError> {
-    let old_issue = db::get_issue(conn, id)?;
+    let tx = db::begin_write(conn)?;
+    let old_issue = db::get_issue(&tx, id)?;
 
-    db::record_event(conn, id, "assigned_to", &old_issue.assigned_to, agent)?;
-    db::update_issue_field(conn, id, "assigned_to", agent)?;
-    db::add_note(conn, id, &format!("Assigned to {}", agent), "itr")?;
+    db::record_event(&tx, id, "assigned_to", &old_issue.assigned_to, agent)?;
+    db::update_issue_field(&tx, id, "assigned_to", agent)?;
+    db::add_note(&tx, id, &format!("Assigned to {}", agent), "itr")?;
+    tx.commit()?;
+    for note in super::agent::fit_notes(conn, agent, &old_issue)? {
+        eprintln!("{}", note);
+    }
 
     print_detail(conn, id, fmt)
 }
 
 pub fn run_unassign(conn: &Connection, id: i64, fmt: Format) -> Result<(), ItrError> {
-    let issue = db::get_issue(conn, id)?;
+    let tx = db::begin_write(conn)?;
+    let issue = db::get_issue(&tx, id)?;
 
-    db::record_event(conn, id, "assigned_to", &issue.assigned_to, "")?;
+    db::record_event(&tx, id, "assigned_to", &issue.assigned_to, "")?;
     if !issue.assigned_to.is_empty() {
         db::add_note(
-            conn,
+            &tx,
             id,
             &format!("Unassigned from {}", issue.assigned_to),
             "itr",
         )?;
     }
-    db::update_issue_field(conn, id, "assigned_to", "")?;
+    db::update_issue_field(&tx, id, "assigned_to", "")?;
+    tx.commit()?;
 
     print_detail(conn, id, fmt)
 }
 
-fn print_detail(conn: &Connection, id: i64, fmt: Format) -> Result<(), ItrError> {
+/// `itr assign --auto [--max-load N]` — spread the unassigned ready queue
+/// over the registered agents, most urgent issue first. An issue goes only
+/// to an agent with every skill it lists; among those, the one with the
+/// fewest active issues wins. An issue whose files overlap files another
+/// agent is already working on stays with that agent (or is skipped), so
+/// two workers never edit the same paths. All assignments are written in
+/// one transaction.
+pub fn run_auto(conn: &Connection, max_load: Option<usize>, fmt: Format) -> Result<(), ItrError> {
+    let agents = db::agents(conn)?;
+    if agents.is_empty() {
+        return Err(ItrError::InvalidValue {
+            field: "agent".to_string(),
+            value: String::new(),
+            valid: "a registered agent to assign to; run `itr agent register <name> --skill S`"
+                .to_string(),
+        });
+    }
+    let active = db::list_issues(
+        conn,
+        &ListFilter {
+            include_blocked: true,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    let config = UrgencyConfig::load(conn);
+    let relations = db::load_issue_relations(conn)?;
+
+    let mut workers: Vec<Worker> = agents
+        .into_iter()
+        .map(|a| Worker {
+            held: active
+                .iter()
+                .filter(|i| i.assigned_to == a.name)
+                .flat_map(|i| i.files.iter().cloned())
+                .collect(),
+            load: a.assigned.len(),
+            name: a.name,
+            skills: a.skills,
+        })
+        .collect();
+    let mut queue: Vec<(f64, Issue)> = active
+        .into_iter()
+        .filter(|i| {
+            i.assigned_to.is_empty()
+                && matches!(i.status.as_str(), "open" | "in-progress")
+                && !relations.is_blocked(i.id)
+        })
+        .map(|i| {
+            let urgency =
+                compute_urgency_from_inputs(&i, &config, relations.urgency_inputs(i.id)).0;
+            (urgency, i)
+        })
+        .collect();
+    queue.sort_by(|a, b| {
+        b.0.partial_cmp(&a.0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.1.id.cmp(&b.1.id))
+    });
+    let queue: Vec<Issue> = queue.into_iter().map(|(_, i)| i).collect();
+    if queue.is_empty() {
+        error::print_empty(fmt.is_json(), "No unassigned ready issues to assign.");
+        return Ok(());
+    }
+
+    let plan = distribute(&mut workers, &queue, max_load);
+    let tx = db::begin_write(conn)?;
+    for a in &plan.assigned {
+        db::record_event(&tx, a.id, "assigned_to", "", &a.agent)?;
+        db::update_issue_field(&tx, a.id, "assigned_to", &a.agent)?;
+        db::add_note(&tx, a.id, &format!("Assigned to {} (auto)", a.agent), "itr")?;
+    }
+    tx.commit()?;
+    format::print_output(&format::format_auto_assign(&plan, fmt), fmt);
+    Ok(())
+}
+
+/// A registered agent as `distribute` sees it.
+struct Worker {
+    name: String,
+    skills: Vec<String>,
+    /// Active issues assigned, including ones handed out in this run.
+    load: usize,
+    /// Files of those issues.
+    held: Vec<String>,
+}
+
+/// Assign each issue of `queue` (most urgent first) to a worker, updating
+/// the workers' load and held files as it goes.
+fn distribute(workers: &mut [Worker], queue: &[Issue], max_load: Option<usize>) -> AutoAssignPlan {
+    let mut assigned = Vec::new();
+    let mut skipped = Vec::new();
+    for issue in queue {
+        let fits = |w: &Worker| {
+            issue
+                .skills
+                .iter()
+                .all(|s| w.skills.contains(&s.to_lowercase()))
+        };
+        let full = |w: &Worker| max_load.is_some_and(|max| w.load >= max);
+        let owners: Vec<usize> = (0..workers.len())
+            .filter(|&w| {
+                workers[w]
+                    .held
+                    .iter()
+                    .any(|h| issue.files.iter().any(|f| overlaps(h, f)))
+            })
+            .collect();
+
+        let outcome = match owners.as_slice() {
+            [] => {
+                let pick = (0..workers.len())
+                    .filter(|&w| fits(&workers[w]) && !full(&workers[w]))
+                    .min_by_key(|&w| (workers[w].load, w));
+                match pick {
+                    Some(w) if issue.skills.is_empty() => {
+                        Ok((w, format!("least loaded ({} active)", workers[w].load)))
+                    }
+                    Some(w) => Ok((
+                        w,
+                        format!(
+                            "has skill(s) {}; {} active",
+                            issue.skills.join(", "),
+                            workers[w].load
+                        ),
+                    )),
+                    None if workers.iter().any(&fits) => {
+                        Err("every agent with the skills it needs is at max load".to_string())
+                    }
+                    None => Err(format!(
+                        "no registered agent has skill(s) {}",
+                        issue.skills.join(", ")
+                    )),
+                }
+            }
+            [w] => {
+                let w = *w;
+                let name = &workers[w].name;
+                if !fits(&workers[w]) {
+                    Err(format!(
+                        "its files overlap work held by {}, who lacks skill(s) {}",
+                        name,
+                        issue.skills.join(", ")
+                    ))
+                } else if full(&workers[w]) {
+                    Err(format!(
+                        "its files overlap work held by {}, who is at max load",
+                        name
+                    ))
+                } else {
+                    Ok((w, "already working on its files".to_string()))
+                }
+            }
+            _ => Err(format!(
+                "its files overlap work held by {}",
+                owners
+                    .iter()
+                    .map(|&w| workers[w].name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        };
+
+        match outcome {
+            Ok((w, reason)) => {
+                workers[w].load += 1;
+                workers[w].held.extend(issue.files.iter().cloned());
+                assigned.push(AutoAssignment {
+                    id: issue.id,
+                    title: issue.title.clone(),
+                    agent: workers[w].name.clone(),
+                    reason,
+                });
+            }
+            Err(reason) => skipped.push(AutoAssignment {
+                id: issue.id,
+                title: issue.title.clone(),
+                agent: String::new(),
+                reason,
+            }),
+        }
+    }
+    AutoAssignPlan {
+        assigned,
+        skipped,
+        load: workers.iter().map(|w| (w.name.clone(), w.load)).collect(),
+    }
+}
+
+/// Whether two `files` entries name the same path, or one is a directory
+/// holding the other.
+fn overlaps(a: &str, b: &str) -> bool {
+    let (a, b) = (a.trim_end_matches('/'), b.trim_end_matches('/'));
+    let inside = |path: &str, dir: &str| {
+        path.strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+    };
+    a == b || inside(a, b) || inside(b, a)
+}
+
+pub(crate) fn print_detail(conn: &Connection, id: i64, fmt: Format) -> Result<(), ItrError> {
     let issue = db::get_issue(conn, id)?;
     let config = UrgencyConfig::load(conn);
     let detail = build_issue_detail(conn, issue, &config)?;
-    println!("{}", format::format_issue_detail(&detail, fmt));
+    format::print_output(&super::format_issue_result(&detail, fmt), fmt);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(name: &str, skills: &[&str], load: usize, held: &[&str]) -> Worker {
+        Worker {
+            name: name.to_string(),
+            skills: skills.iter().map(ToString::to_string).collect(),
+            load,
+            held: held.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn distribute_matches_skills_balances_load_and_avoids_file_overlap() {
+        let conn = db::open_test_db();
+        let issue = |title: &str, files: &[&str], skills: &[&str]| {
+            let files: Vec<String> = files.iter().map(ToString::to_string).collect();
+            let skills: Vec<String> = skills.iter().map(ToString::to_string).collect();
+            db::insert_issue(
+                &conn,
+                title,
+                "medium",
+                "task",
+                "",
+                &files,
+                &[],
+                &skills,
+                "",
+                None,
+                "",
+            )
+            .unwrap()
+        };
+        let queue = vec![
+            issue("Migrate schema", &[], &["sql"]),
+            issue("Parser bug", &["src/parse.rs"], &[]),
+            issue("Docs", &[], &[]),
+            issue("Shared file", &["src/db/mod.rs"], &[]),
+            issue("GPU kernel", &[], &["cuda"]),
+            issue("Tests", &[], &[]),
+            issue("Lint", &[], &[]),
+        ];
+        let mut workers = vec![
+            worker("ann", &["sql"], 1, &["src/parse.rs"]),
+            worker("bo", &[], 0, &["src/db/"]),
+        ];
+        let plan = distribute(&mut workers, &queue, Some(3));
+
+        let got: Vec<(i64, &str)> = plan
+            .assigned
+            .iter()
+            .map(|a| (a.id, a.agent.as_str()))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                (queue[0].id, "ann"),
+                (queue[1].id, "ann"),
+                (queue[2].id, "bo"),
+                (queue[3].id, "bo"),
+                (queue[5].id, "bo"),
+            ]
+        );
+        assert_eq!(plan.assigned[1].reason, "already working on its files");
+        let skipped: Vec<i64> = plan.skipped.iter().map(|s| s.id).collect();
+        assert_eq!(skipped, vec![queue[4].id, queue[6].id]);
+        assert!(plan.skipped[0].reason.contains("skill(s) cuda"));
+        assert!(plan.skipped[1].reason.contains("at max load"));
+        assert_eq!(plan.load["ann"], 3);
+        assert_eq!(plan.load["bo"], 3);
+    }
+
+    #[test]
+    fn overlaps_matches_paths_and_directories() {
+        assert!(overlaps("src/db/", "src/db/mod.rs"));
+        assert!(overlaps("src/a.rs", "src/a.rs"));
+        assert!(!overlaps("src/a", "src/ab.rs"));
+    }
+}
@@ -4,23 +4,25 @@ use crate::db;
 use crate::error::ItrError;
 use crate::format::{self, Format};
 use crate::models::{
-    BatchAddInput, BatchCloseInput, BatchItemResult, BatchNoteInput, BatchResult, BatchSummary,
-    BatchUpdateInput, ParentChange, UnblockedIssue,
+    is_terminal, BatchAddInput, BatchCloseInput, BatchItemResult, BatchNoteInput, BatchResult,
+    BatchSummary, BatchUpdateInput, ParentChange, UnblockedIssue,
 };
 use crate::normalize;
 use crate::normalize::{validate_kind, validate_priority, validate_status};
 use crate::urgency::UrgencyConfig;
 use crate::util;
 use rusqlite::Connection;
+use std::collections::HashMap;
 use std::io::{self, Read};
 
 /// JSON keys recognized by [`BatchAddInput`] (including serde aliases).
 /// Keep in sync with the struct definition in `models.rs` — anything else in
 /// an item is reported via a REVIEW note instead of being silently dropped.
-const BATCH_ADD_KNOWN_KEYS: &[&str] = &[
+pub(crate) const BATCH_ADD_KNOWN_KEYS: &[&str] = &[
     "title",
     "priority",
     "kind",
+    "status",
     "context",
     "files",
     "tags",
@@ -29,12 +31,20 @@ const BATCH_ADD_KNOWN_KEYS: &[&str] = &[
     "parent_id",
     "parent",
     "assigned_to",
+    "severity",
+    "component",
+    "affects_version",
+    "fixed_in_version",
+    "repro",
+    "estimate",
     "blocked_by",
+    "ref",
+    "notes",
 ];
 
 /// JSON keys recognized by [`BatchUpdateInput`] (including serde aliases).
 /// Keep in sync with the struct definition in `models.rs` (#212).
-const BATCH_UPDATE_KNOWN_KEYS: &[&str] = &[
+pub(crate) const BATCH_UPDATE_KNOWN_KEYS: &[&str] = &[
     "id",
     "status",
     "priority",
@@ -52,10 +62,10 @@ const BATCH_UPDATE_KNOWN_KEYS: &[&str] = &[
 ];
 
 /// JSON keys recognized by [`BatchCloseInput`] (#212).
-const BATCH_CLOSE_KNOWN_KEYS: &[&str] = &["id", "reason", "wontfix"];
+pub(crate) const BATCH_CLOSE_KNOWN_KEYS: &[&str] = &["id", "reason", "wontfix"];
 
 /// JSON keys recognized by [`BatchNoteInput`] (#212).
-const BATCH_NOTE_KNOWN_KEYS: &[&str] = &["id", "text", "agent"];
+pub(crate) const BATCH_NOTE_KNOWN_KEYS: &[&str] = &["id", "text", "agent"];
 
 /// REVIEW notes for any keys of `value` not in `known_keys` — the shared
 /// "never silently swallow input" check behind every batch verb (#150, #212).
@@ -63,10 +73,11 @@ fn unknown_key_notes(value: &serde_json::Value, known_keys: &[&str]) -> Vec<Stri
     let Some(map) = value.as_object() else {
         return vec![];
     };
+    let known = known_keys.join(", ");
     let unknown: Vec<String> = map
         .keys()
         .filter(|k| !known_keys.contains(&k.as_str()))
-        .cloned()
+        .map(|k| format!("{}{}", k, util::did_you_mean(k, &known)))
         .collect();
     if unknown.is_empty() {
         return vec![];
@@ -74,7 +85,7 @@ fn unknown_key_notes(value: &serde_json::Value, known_keys: &[&str]) -> Vec<Stri
     vec![format!(
         "REVIEW: unrecognized field(s) ignored: {}. Known fields: {}",
         unknown.join(", "),
-        known_keys.join(", ")
+        known
     )]
 }
 
@@ -85,6 +96,8 @@ pub(crate) enum BlockedByRef {
     Id(i64),
     /// An `"@N"` reference to the N-th item of the same batch.
     BatchIndex(usize),
+    /// A `"ref:<name>"` reference to the batch item whose `ref` is `name`.
+    Name(String),
 }
 
 /// Parse one `blocked_by` JSON value. `Err` carries the display form of the
@@ -97,6 +110,13 @@ pub(crate) fn parse_blocked_by_entry(dep: &serde_json::Value) -> Result<BlockedB
                 .map(BlockedByRef::BatchIndex)
                 .map_err(|_| s.to_string());
         }
+        if let Some(name) = s.strip_prefix("ref:") {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(s.to_string());
+            }
+            return Ok(BlockedByRef::Name(name.to_string()));
+        }
         return s
             .trim()
             .parse::<i64>()
@@ -126,7 +146,7 @@ pub(crate) fn parse_add_item(
 /// error.
 /// One deserialized batch item plus its unknown-key REVIEW notes, or the
 /// per-item `error` outcome when the item failed to parse at all.
-type ParsedItem<T> = Result<(T, Vec<String>), BatchItemResult>;
+pub(crate) type ParsedItem<T> = Result<(T, Vec<String>), BatchItemResult>;
 
 fn parse_each<T: serde::de::DeserializeOwned>(
     input: &str,
@@ -136,25 +156,57 @@ fn parse_each<T: serde::de::DeserializeOwned>(
     Ok(values
         .into_iter()
         .enumerate()
-        .map(|(idx, value)| {
-            serde_json::from_value::<T>(value.clone())
-                .map(|item| (item, unknown_key_notes(&value, known_keys)))
-                .map_err(|e| BatchItemResult {
-                    id: value
-                        .get("id")
-                        .and_then(serde_json::Value::as_i64)
-                        .unwrap_or(0),
-                    outcome: "error".to_string(),
-                    error: Some(format!("item {idx}: {e}")),
-                    notes: vec![],
-                    unblocked: vec![],
-                    issue: None,
-                })
-        })
+        .map(|(idx, value)| parse_item(idx, &value, known_keys))
         .collect())
 }
 
-fn read_stdin() -> Result<String, ItrError> {
+/// Deserialize the item at input index `idx`; see [`parse_each`].
+#[allow(clippy::result_large_err)]
+pub(crate) fn parse_item<T: serde::de::DeserializeOwned>(
+    idx: usize,
+    value: &serde_json::Value,
+    known_keys: &[&str],
+) -> ParsedItem<T> {
+    serde_json::from_value::<T>(value.clone())
+        .map(|item| (item, unknown_key_notes(value, known_keys)))
+        .map_err(|e| error_result(item_id(value), format!("item {idx}: {e}")))
+}
+
+/// The `id` of a raw item, or 0 when it has none.
+pub(crate) fn item_id(value: &serde_json::Value) -> i64 {
+    value
+        .get("id")
+        .and_then(serde_json::Value::as_i64)
+        .unwrap_or(0)
+}
+
+/// A per-item `error` outcome.
+pub(crate) fn error_result(id: i64, error: String) -> BatchItemResult {
+    BatchItemResult {
+        id,
+        outcome: "error".to_string(),
+        error: Some(error),
+        notes: vec![],
+        unblocked: vec![],
+        issue: None,
+    }
+}
+
+/// Parse a JSON array, or JSONL with one item per non-blank line (the shape
+/// `itr export` streams). A line that is not JSON is a hard parse error.
+fn parse_array_or_lines(input: &str) -> Result<Vec<serde_json::Value>, ItrError> {
+    let input = input.trim();
+    if input.is_empty() || input.starts_with('[') {
+        return Ok(serde_json::from_str(input)?);
+    }
+    Ok(input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?)
+}
+
+pub(crate) fn read_stdin() -> Result<String, ItrError> {
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
     Ok(input)
@@ -173,10 +225,36 @@ fn ensure_needs_review_tag(conn: &Connection, id: i64) -> Result<(), ItrError> {
     Ok(())
 }
 
+/// Record an item's REVIEW notes on its issue and tag it `_needs_review`;
+/// nothing when there are none.
+pub(crate) fn flag_for_review(
+    conn: &Connection,
+    id: i64,
+    review_notes: &[String],
+) -> Result<(), ItrError> {
+    if review_notes.is_empty() {
+        return Ok(());
+    }
+    ensure_needs_review_tag(conn, id)?;
+    for note_text in review_notes {
+        db::add_note(conn, id, note_text, "itr")?;
+    }
+    Ok(())
+}
+
+pub(crate) fn outcome(review_notes: &[String]) -> String {
+    if review_notes.is_empty() {
+        "ok"
+    } else {
+        "review"
+    }
+    .to_string()
+}
+
 pub fn run_add(conn: &Connection, dry_run: bool, fmt: Format) -> Result<(), ItrError> {
     let input = read_stdin()?;
     let batch_result = run_add_core(conn, &input, dry_run)?;
-    println!("{}", format::format_batch_result(&batch_result, fmt));
+    format::print_output(&format::format_batch_result(&batch_result, fmt), fmt);
     Ok(())
 }
 
@@ -186,7 +264,7 @@ pub fn run_add(conn: &Connection, dry_run: bool, fmt: Format) -> Result<(), ItrE
 /// defaults and `@N` dependency resolution) match the real run while nothing
 /// is written.
 fn run_add_core(conn: &Connection, input: &str, dry_run: bool) -> Result<BatchResult, ItrError> {
-    let values: Vec<serde_json::Value> = serde_json::from_str(input)?;
+    let values = parse_array_or_lines(input)?;
 
     // Parse each item individually; a malformed item is reported as a
     // per-item error outcome while the valid items still get created.
@@ -196,8 +274,10 @@ fn run_add_core(conn: &Connection, input: &str, dry_run: bool) -> Result<BatchRe
         .map(|(idx, value)| parse_add_item(value).map_err(|e| format!("item {idx}: {e}")))
         .collect();
 
+    let refs = collect_refs(&mut parsed);
+
     // Use a transaction
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
 
     // First pass: create all issues with soft fallback. `created[idx]` is
     // None when the item at that input index failed to parse.
@@ -207,66 +287,10 @@ fn run_add_core(conn: &Connection, input: &str, dry_run: bool) -> Result<BatchRe
             created.push(None);
             continue;
         };
-
-        item.priority = normalize::normalize_priority(&item.priority);
-        item.kind = normalize::normalize_kind(&item.kind);
-
-        if validate_priority(&item.priority).is_err() {
-            review_notes.push(format!(
-                "REVIEW: priority '{}' not recognized, defaulted to 'medium'. Valid: critical, high, medium, low",
-                item.priority
-            ));
-            item.priority = "medium".to_string();
-        }
-        if validate_kind(&item.kind).is_err() {
-            review_notes.push(format!(
-                "REVIEW: kind '{}' not recognized, defaulted to 'task'. Valid: bug, feature, task, epic",
-                item.kind
-            ));
-            item.kind = "task".to_string();
-        }
-
-        // Soft fallback (#167): a parent that doesn't exist would otherwise
-        // surface as a raw FOREIGN KEY error and abort the whole batch.
-        if let Some(p) = item.parent_id {
-            if !db::issue_exists(&tx, p)? {
-                review_notes.push(format!(
-                    "REVIEW: parent {p} not found; issue created without a parent"
-                ));
-                item.parent_id = None;
-            }
-        }
-
-        let mut tags = item.tags.clone();
-        if !review_notes.is_empty() && !tags.contains(&"_needs_review".to_string()) {
-            tags.push("_needs_review".to_string());
-        }
-
-        let skills: Vec<String> = item
-            .skills
-            .iter()
-            .map(|s| s.trim().to_lowercase())
-            .filter(|s| !s.is_empty())
-            .collect();
-        let issue = db::insert_issue(
-            &tx,
-            &item.title,
-            &item.priority,
-            &item.kind,
-            &item.context,
-            &item.files,
-            &tags,
-            &skills,
-            &item.acceptance,
-            item.parent_id,
-            &item.assigned_to,
-        )?;
-        created.push(Some(issue.id));
+        created.push(Some(insert_add_item(&tx, item, review_notes)?));
     }
 
-    // Second pass: create dependencies. An unresolvable entry skips that
-    // edge with a REVIEW note instead of aborting the batch (#164). Cycles
-    // remain hard errors (CLAUDE.md: cycle detection cannot recover).
+    // Second pass: create dependencies, so `@N` may point at any item.
     for (idx, entry) in parsed.iter_mut().enumerate() {
         let Ok((item, review_notes)) = entry else {
             continue;
@@ -274,90 +298,29 @@ fn run_add_core(conn: &Connection, input: &str, dry_run: bool) -> Result<BatchRe
         let Some(blocked_id) = created[idx] else {
             continue;
         };
-        for dep in &item.blocked_by {
-            let blocker_id = match parse_blocked_by_entry(dep) {
-                Ok(BlockedByRef::Id(n)) => n,
-                Ok(BlockedByRef::BatchIndex(i)) => match created.get(i).copied().flatten() {
-                    Some(id) => id,
-                    None => {
-                        review_notes.push(format!(
-                            "REVIEW: blocked_by '@{i}' does not refer to a created batch item; dependency skipped. Valid: @0 to @{}",
-                            created.len().saturating_sub(1)
-                        ));
-                        continue;
-                    }
-                },
-                Err(token) => {
-                    review_notes.push(format!(
-                        "REVIEW: blocked_by '{token}' is not a valid issue ID or '@N' batch reference; dependency skipped"
-                    ));
-                    continue;
-                }
-            };
-            match db::add_dependency(&tx, blocker_id, blocked_id) {
-                Ok(_) => {}
-                Err(ItrError::NotFound(missing)) => {
-                    review_notes.push(format!(
-                        "REVIEW: blocked_by {missing} not found; dependency skipped"
-                    ));
-                }
-                Err(e) => return Err(e),
-            }
-        }
+        link_blockers(
+            &tx,
+            &item.blocked_by,
+            blocked_id,
+            &created,
+            &refs,
+            review_notes,
+        )?;
     }
 
     // Third pass: persist review notes (and the `_needs_review` tag for
-    // items whose notes were only discovered during dependency resolution).
-    for (idx, entry) in parsed.iter().enumerate() {
-        let Ok((_, review_notes)) = entry else {
-            continue;
-        };
-        let Some(id) = created[idx] else {
-            continue;
-        };
-        if !review_notes.is_empty() {
-            ensure_needs_review_tag(&tx, id)?;
-        }
-        for note_text in review_notes {
-            db::add_note(&tx, id, note_text, "itr")?;
-        }
-    }
-
-    // Build results with issue details from the transaction state, so the
-    // dry-run path reports exactly what a committed run would have created.
+    // items whose notes were only discovered during dependency resolution),
+    // then build results with issue details from the transaction state, so
+    // the dry-run path reports exactly what a committed run would have
+    // created.
     let config = UrgencyConfig::load(&tx);
     let mut results: Vec<BatchItemResult> = Vec::with_capacity(parsed.len());
     for (idx, entry) in parsed.iter().enumerate() {
         match (entry, created[idx]) {
             (Ok((_, review_notes)), Some(id)) => {
-                let issue = db::get_issue(&tx, id)?;
-                let detail = build_issue_detail(&tx, issue, &config)?;
-
-                let outcome = if review_notes.is_empty() {
-                    "ok"
-                } else {
-                    "review"
-                };
-
-                results.push(BatchItemResult {
-                    id,
-                    outcome: outcome.to_string(),
-                    error: None,
-                    notes: review_notes.clone(),
-                    unblocked: vec![],
-                    issue: Some(detail),
-                });
-            }
-            (Err(msg), _) => {
-                results.push(BatchItemResult {
-                    id: 0,
-                    outcome: "error".to_string(),
-                    error: Some(msg.clone()),
-                    notes: vec![],
-                    unblocked: vec![],
-                    issue: None,
-                });
+                results.push(added_result(&tx, id, review_notes.clone(), &config)?);
             }
+            (Err(msg), _) => results.push(error_result(0, msg.clone())),
             (Ok(_), None) => unreachable!("parsed batch add item without a created issue id"),
         }
     }
@@ -372,117 +335,278 @@ fn run_add_core(conn: &Connection, input: &str, dry_run: bool) -> Result<BatchRe
         results,
         summary,
         dry_run,
+        rolled_back: false,
     })
 }
 
-pub fn run_close(conn: &Connection, dry_run: bool, fmt: Format) -> Result<(), ItrError> {
-    let input = read_stdin()?;
-    let batch_result = run_close_core(conn, &input, dry_run)?;
-    println!("{}", format::format_batch_result(&batch_result, fmt));
-    Ok(())
-}
+/// Insert one add item with soft fallback for its priority, kind, status,
+/// and parent, then its inline notes; corrections are appended to
+/// `review_notes`. Returns the new ID.
+pub(crate) fn insert_add_item(
+    conn: &Connection,
+    item: &mut BatchAddInput,
+    review_notes: &mut Vec<String>,
+) -> Result<i64, ItrError> {
+    item.priority = normalize::normalize_priority(&item.priority);
+    item.kind = normalize::normalize_kind(&item.kind);
+
+    if let Err(e) = validate_priority(&item.priority) {
+        review_notes.push(format!(
+            "REVIEW: priority '{}' not recognized{}, defaulted to 'medium'. Valid: critical, high, medium, low",
+            item.priority,
+            e.did_you_mean()
+        ));
+        item.priority = "medium".to_string();
+    }
+    if let Err(e) = validate_kind(&item.kind) {
+        review_notes.push(format!(
+            "REVIEW: kind '{}' not recognized{}, defaulted to 'task'. Valid: bug, feature, task, epic",
+            item.kind,
+            e.did_you_mean()
+        ));
+        item.kind = "task".to_string();
+    }
+    let severity = crate::store::severity_or_review(&item.severity, review_notes);
+    let status = match item.status.as_deref() {
+        None => None,
+        Some(s) => {
+            let normalized = normalize::normalize_status(s);
+            match validate_status(&normalized) {
+                Ok(()) => Some(normalized).filter(|s| s != "open"),
+                Err(e) => {
+                    review_notes.push(format!(
+                        "REVIEW: status '{}' not recognized{}, defaulted to 'open'. Valid: open, in-progress, review, done, wontfix",
+                        s,
+                        e.did_you_mean()
+                    ));
+                    None
+                }
+            }
+        }
+    };
 
-fn run_close_core(conn: &Connection, input: &str, dry_run: bool) -> Result<BatchResult, ItrError> {
-    let items = parse_each::<BatchCloseInput>(input, BATCH_CLOSE_KNOWN_KEYS)?;
+    // Soft fallback (#167): a parent that doesn't exist would otherwise
+    // surface as a raw FOREIGN KEY error and abort the whole batch.
+    if let Some(p) = item.parent_id {
+        if !db::issue_exists(conn, p)? {
+            review_notes.push(format!(
+                "REVIEW: parent {p} not found; issue created without a parent"
+            ));
+            item.parent_id = None;
+        }
+    }
 
-    let tx = conn.unchecked_transaction()?;
+    let mut tags = item.tags.clone();
+    let component = crate::store::apply_component(
+        conn,
+        &item.component,
+        &mut item.assigned_to,
+        &mut tags,
+        review_notes,
+    )?;
+    if !review_notes.is_empty() && !tags.contains(&"_needs_review".to_string()) {
+        tags.push("_needs_review".to_string());
+    }
 
-    let mut results: Vec<BatchItemResult> = Vec::with_capacity(items.len());
+    let skills: Vec<String> = item
+        .skills
+        .iter()
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let mut issue = db::insert_issue(
+        conn,
+        &item.title,
+        &item.priority,
+        &item.kind,
+        &item.context,
+        &item.files,
+        &tags,
+        &skills,
+        &item.acceptance,
+        item.parent_id,
+        &item.assigned_to,
+    )?;
+    if let Some(project) = crate::commands::project::scope() {
+        db::file_new_issue(conn, issue.id, &project)?;
+    }
 
-    for entry in items {
-        let (item, review_notes) = match entry {
-            Ok(item) => item,
-            Err(error_result) => {
-                results.push(error_result);
-                continue;
-            }
+    if !severity.is_empty() {
+        db::update_issue_field(conn, issue.id, "severity", &severity)?;
+    }
+    if !component.is_empty() {
+        db::set_component_id(conn, issue.id, &component)?;
+    }
+    crate::store::set_versions(
+        conn,
+        &mut issue,
+        &item.affects_version,
+        &item.fixed_in_version,
+    )?;
+    if let Some(repro) = &item.repro {
+        db::set_repro(conn, issue.id, Some(&repro.clone().normalized()))?;
+    }
+    if item.estimate.is_some() {
+        db::set_estimate(conn, issue.id, item.estimate)?;
+    }
+    if let Some(status) = status {
+        db::record_event(conn, issue.id, "status", &issue.status, &status)?;
+        db::update_issue_field(conn, issue.id, "status", &status)?;
+    }
+    let agent = super::note::resolve_agent(conn, "");
+    for text in &item.notes {
+        if text.trim().is_empty() {
+            review_notes.push("REVIEW: empty note skipped".to_string());
+            continue;
+        }
+        db::add_note(conn, issue.id, text, &agent)?;
+    }
+    Ok(issue.id)
+}
+
+/// Map each item's `ref` name to its input index, so `ref:<name>` can point
+/// forward like `@N`. The first item to use a name keeps it; later ones get
+/// a REVIEW note.
+fn collect_refs(
+    parsed: &mut [Result<(BatchAddInput, Vec<String>), String>],
+) -> HashMap<String, usize> {
+    let mut refs = HashMap::new();
+    for (idx, entry) in parsed.iter_mut().enumerate() {
+        let Ok((item, review_notes)) = entry else {
+            continue;
         };
+        claim_ref(&mut refs, item, idx, review_notes);
+    }
+    refs
+}
 
-        // Try to get the issue
-        let issue = match db::get_issue(&tx, item.id) {
-            Ok(i) => i,
-            Err(ItrError::NotFound(_)) => {
-                results.push(BatchItemResult {
-                    id: item.id,
-                    outcome: "error".to_string(),
-                    error: Some(format!("Issue {} not found", item.id)),
-                    notes: review_notes,
-                    unblocked: vec![],
-                    issue: None,
-                });
+/// Record `item`'s `ref` name as pointing at input index `idx`, unless an
+/// earlier item already uses it.
+pub(crate) fn claim_ref(
+    refs: &mut HashMap<String, usize>,
+    item: &BatchAddInput,
+    idx: usize,
+    review_notes: &mut Vec<String>,
+) {
+    let Some(name) = item.ref_name.as_deref().map(str::trim) else {
+        return;
+    };
+    if name.is_empty() {
+        return;
+    }
+    if let Some(first) = refs.get(name) {
+        review_notes.push(format!(
+            "REVIEW: ref '{name}' is already used by item {first}; 'ref:{name}' keeps pointing there"
+        ));
+    } else {
+        refs.insert(name.to_string(), idx);
+    }
+}
+
+/// Make `blocked_id` blocked by each `blocked_by` entry. `@N` resolves to
+/// `created[N]` and `ref:<name>` to `created[refs[name]]`. An unresolvable
+/// entry skips that edge with a REVIEW note instead of aborting the batch
+/// (#164). Cycles remain hard errors (CLAUDE.md: cycle detection cannot
+/// recover).
+pub(crate) fn link_blockers(
+    conn: &Connection,
+    blocked_by: &[serde_json::Value],
+    blocked_id: i64,
+    created: &[Option<i64>],
+    refs: &HashMap<String, usize>,
+    review_notes: &mut Vec<String>,
+) -> Result<(), ItrError> {
+    for dep in blocked_by {
+        let blocker_id = match parse_blocked_by_entry(dep) {
+            Ok(BlockedByRef::Id(n)) => n,
+            Ok(BlockedByRef::BatchIndex(i)) => match created.get(i).copied().flatten() {
+                Some(id) => id,
+                None => {
+                    review_notes.push(format!(
+                        "REVIEW: blocked_by '@{i}' does not refer to a created batch item; dependency skipped. Valid: @0 to @{}",
+                        created.len().saturating_sub(1)
+                    ));
+                    continue;
+                }
+            },
+            Ok(BlockedByRef::Name(name)) => {
+                match refs
+                    .get(&name)
+                    .and_then(|&i| created.get(i).copied().flatten())
+                {
+                    Some(id) => id,
+                    None => {
+                        let mut known: Vec<&str> = refs.keys().map(String::as_str).collect();
+                        known.sort_unstable();
+                        let known = known.join(", ");
+                        review_notes.push(format!(
+                            "REVIEW: blocked_by 'ref:{name}' does not name a created batch item{}; dependency skipped. Known refs: {}",
+                            util::did_you_mean(&name, &known),
+                            if known.is_empty() { "none" } else { &known }
+                        ));
+                        continue;
+                    }
+                }
+            }
+            Err(token) => {
+                review_notes.push(format!(
+                    "REVIEW: blocked_by '{token}' is not a valid issue ID, '@N', or 'ref:<name>' batch reference; dependency skipped"
+                ));
                 continue;
             }
-            Err(e) => return Err(e),
         };
-
-        // Unknown payload keys still close the issue (accept partial valid
-        // input) but flag the item for review (#212).
-        if !review_notes.is_empty() {
-            ensure_needs_review_tag(&tx, item.id)?;
-            for note_text in &review_notes {
-                db::add_note(&tx, item.id, note_text, "itr")?;
+        match db::add_dependency(conn, blocker_id, blocked_id) {
+            Ok(_) => {}
+            Err(ItrError::NotFound(missing)) => {
+                review_notes.push(format!(
+                    "REVIEW: blocked_by {missing} not found; dependency skipped"
+                ));
             }
+            Err(e) => return Err(e),
         }
-        let outcome = if review_notes.is_empty() {
-            "ok"
-        } else {
-            "review"
-        };
+    }
+    Ok(())
+}
 
-        // Already closed — idempotent ok
-        if issue.status == "done" || issue.status == "wontfix" {
-            let mut notes = vec![format!("Already {}", issue.status)];
-            notes.extend(review_notes);
-            results.push(BatchItemResult {
-                id: item.id,
-                outcome: outcome.to_string(),
-                error: None,
-                notes,
-                unblocked: vec![],
-                issue: None,
-            });
-            continue;
-        }
+/// Flag a created issue for review if needed and report it, with its detail.
+pub(crate) fn added_result(
+    conn: &Connection,
+    id: i64,
+    review_notes: Vec<String>,
+    config: &UrgencyConfig,
+) -> Result<BatchItemResult, ItrError> {
+    flag_for_review(conn, id, &review_notes)?;
+    let detail = build_issue_detail(conn, db::get_issue(conn, id)?, config)?;
+    Ok(BatchItemResult {
+        id,
+        outcome: outcome(&review_notes),
+        error: None,
+        notes: review_notes,
+        unblocked: vec![],
+        issue: Some(detail),
+    })
+}
 
-        let status = if item.wontfix { "wontfix" } else { "done" };
+pub fn run_close(conn: &Connection, dry_run: bool, fmt: Format) -> Result<(), ItrError> {
+    let input = read_stdin()?;
+    let batch_result = run_close_core(conn, &input, dry_run)?;
+    format::print_output(&format::format_batch_result(&batch_result, fmt), fmt);
+    Ok(())
+}
 
-        db::record_event(&tx, item.id, "status", &issue.status, status)?;
-        db::update_issue_field(&tx, item.id, "status", status)?;
+fn run_close_core(conn: &Connection, input: &str, dry_run: bool) -> Result<BatchResult, ItrError> {
+    let items = parse_each::<BatchCloseInput>(input, BATCH_CLOSE_KNOWN_KEYS)?;
 
-        if !item.reason.is_empty() {
-            db::record_event(
-                &tx,
-                item.id,
-                "close_reason",
-                &issue.close_reason,
-                &item.reason,
-            )?;
-            db::update_issue_field(&tx, item.id, "close_reason", &item.reason)?;
-        }
+    let tx = db::begin_write(conn)?;
 
-        // Check for newly unblocked issues, then auto-clean stale edges
-        let unblocked_list = db::get_newly_unblocked(&tx, item.id)?;
-        db::remove_blocker_edges(&tx, item.id)?;
-        let unblocked: Vec<UnblockedIssue> = unblocked_list
-            .into_iter()
-            .map(|(id, title)| UnblockedIssue { id, title })
-            .collect();
+    let mut results: Vec<BatchItemResult> = Vec::with_capacity(items.len());
 
-        let mut notes = if item.reason.is_empty() {
-            vec![]
-        } else {
-            vec![item.reason.clone()]
+    for entry in items {
+        let result = match entry {
+            Ok((item, review_notes)) => close_item(&tx, item, review_notes)?,
+            Err(error_result) => error_result,
         };
-        notes.extend(review_notes);
-
-        results.push(BatchItemResult {
-            id: item.id,
-            outcome: outcome.to_string(),
-            error: None,
-            notes,
-            unblocked,
-            issue: None,
-        });
+        results.push(result);
     }
 
     if !dry_run {
@@ -495,210 +619,30 @@ fn run_close_core(conn: &Connection, input: &str, dry_run: bool) -> Result<Batch
         results,
         summary,
         dry_run,
+        rolled_back: false,
     })
 }
 
 pub fn run_update(conn: &Connection, dry_run: bool, fmt: Format) -> Result<(), ItrError> {
     let input = read_stdin()?;
     let batch_result = run_update_core(conn, &input, dry_run)?;
-    println!("{}", format::format_batch_result(&batch_result, fmt));
+    format::print_output(&format::format_batch_result(&batch_result, fmt), fmt);
     Ok(())
 }
 
 fn run_update_core(conn: &Connection, input: &str, dry_run: bool) -> Result<BatchResult, ItrError> {
     let items = parse_each::<BatchUpdateInput>(input, BATCH_UPDATE_KNOWN_KEYS)?;
 
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
 
     let mut results: Vec<BatchItemResult> = Vec::with_capacity(items.len());
 
     for entry in items {
-        let (item, mut review_notes) = match entry {
-            Ok(item) => item,
-            Err(error_result) => {
-                results.push(error_result);
-                continue;
-            }
+        let result = match entry {
+            Ok((item, review_notes)) => update_item(&tx, item, review_notes)?,
+            Err(error_result) => error_result,
         };
-
-        // Try to get the issue
-        let issue = match db::get_issue(&tx, item.id) {
-            Ok(i) => i,
-            Err(ItrError::NotFound(_)) => {
-                results.push(BatchItemResult {
-                    id: item.id,
-                    outcome: "error".to_string(),
-                    error: Some(format!("Issue {} not found", item.id)),
-                    notes: review_notes,
-                    unblocked: vec![],
-                    issue: None,
-                });
-                continue;
-            }
-            Err(e) => return Err(e),
-        };
-
-        let mut new_status: Option<String> = None;
-
-        // Handle status
-        if let Some(ref s) = item.status {
-            let normalized = normalize::normalize_status(s);
-            match validate_status(&normalized) {
-                Ok(()) => {
-                    db::record_event(&tx, item.id, "status", &issue.status, &normalized)?;
-                    db::update_issue_field(&tx, item.id, "status", &normalized)?;
-                    new_status = Some(normalized);
-                }
-                Err(_) => {
-                    review_notes.push(format!(
-                        "status '{}' not recognized, kept '{}'. Valid: open, in-progress, done, wontfix",
-                        s, issue.status
-                    ));
-                }
-            }
-        }
-
-        // Handle priority
-        if let Some(ref p) = item.priority {
-            let normalized = normalize::normalize_priority(p);
-            match validate_priority(&normalized) {
-                Ok(()) => {
-                    db::record_event(&tx, item.id, "priority", &issue.priority, &normalized)?;
-                    db::update_issue_field(&tx, item.id, "priority", &normalized)?;
-                }
-                Err(_) => {
-                    review_notes.push(format!(
-                        "priority '{}' not recognized, kept '{}'. Valid: critical, high, medium, low",
-                        p, issue.priority
-                    ));
-                }
-            }
-        }
-
-        // Handle kind
-        if let Some(ref k) = item.kind {
-            let normalized = normalize::normalize_kind(k);
-            match validate_kind(&normalized) {
-                Ok(()) => {
-                    db::record_event(&tx, item.id, "kind", &issue.kind, &normalized)?;
-                    db::update_issue_field(&tx, item.id, "kind", &normalized)?;
-                }
-                Err(_) => {
-                    review_notes.push(format!(
-                        "kind '{}' not recognized, kept '{}'. Valid: bug, feature, task, epic",
-                        k, issue.kind
-                    ));
-                }
-            }
-        }
-
-        // Handle title
-        if let Some(ref t) = item.title {
-            db::record_event(&tx, item.id, "title", &issue.title, t)?;
-            db::update_issue_field(&tx, item.id, "title", t)?;
-        }
-
-        // Handle context
-        if let Some(ref c) = item.context {
-            db::record_event(&tx, item.id, "context", &issue.context, c)?;
-            db::update_issue_field(&tx, item.id, "context", c)?;
-        }
-
-        // Handle assigned_to
-        if let Some(ref a) = item.assigned_to {
-            db::record_event(&tx, item.id, "assigned_to", &issue.assigned_to, a)?;
-            db::update_issue_field(&tx, item.id, "assigned_to", a)?;
-        }
-
-        // Handle add_tags / remove_tags (audited in JSON-array format, #187)
-        if !item.add_tags.is_empty() || !item.remove_tags.is_empty() {
-            let current = db::get_issue(&tx, item.id)?.tags;
-            let updated = util::apply_tags(current.clone(), &item.add_tags, &item.remove_tags);
-            persist_list_field(&tx, item.id, "tags", &current, &updated)?;
-        }
-
-        // Handle add_skills / remove_skills (audited in JSON-array format, #187)
-        if !item.add_skills.is_empty() || !item.remove_skills.is_empty() {
-            let current = db::get_issue(&tx, item.id)?.skills;
-            let updated =
-                util::apply_skills(current.clone(), &item.add_skills, &item.remove_skills);
-            persist_list_field(&tx, item.id, "skills", &current, &updated)?;
-        }
-
-        // Handle parent changes: `"parent_id": N` re-parents, `"parent_id":
-        // null` or `"no_parent": true` clears — mirroring `itr update
-        // --parent/--no-parent`, but with soft-fallback review notes where
-        // the single-update path hard-errors (missing parent, cycle).
-        let parent_change = if item.no_parent {
-            if matches!(item.parent_id, ParentChange::Set(_)) {
-                review_notes.push(
-                    "both parent_id and no_parent set; parent unchanged. Use one of parent_id: <ID> or no_parent: true".to_string(),
-                );
-                ParentChange::Unchanged
-            } else {
-                ParentChange::Clear
-            }
-        } else {
-            item.parent_id
-        };
-        let old_parent = issue.parent_id.map(|p| p.to_string()).unwrap_or_default();
-        match parent_change {
-            ParentChange::Unchanged => {}
-            ParentChange::Set(pid) => {
-                if !db::issue_exists(&tx, pid)? {
-                    review_notes.push(format!("parent {pid} not found; parent unchanged"));
-                } else if db::is_self_or_descendant(&tx, item.id, pid)? {
-                    review_notes.push(format!(
-                        "parent {} would create a cycle with {}; parent unchanged",
-                        pid, item.id
-                    ));
-                } else if old_parent != pid.to_string() {
-                    db::record_event(&tx, item.id, "parent_id", &old_parent, &pid.to_string())?;
-                    db::update_issue_parent(&tx, item.id, Some(pid))?;
-                }
-            }
-            ParentChange::Clear => {
-                if !old_parent.is_empty() {
-                    db::record_event(&tx, item.id, "parent_id", &old_parent, "")?;
-                    db::update_issue_parent(&tx, item.id, None)?;
-                }
-            }
-        }
-
-        // Add _needs_review tag and notes if any field was auto-corrected
-        if !review_notes.is_empty() {
-            ensure_needs_review_tag(&tx, item.id)?;
-            for note_text in &review_notes {
-                db::add_note(&tx, item.id, note_text, "itr")?;
-            }
-        }
-
-        // Check for newly unblocked issues if status changed to terminal
-        let unblocked = match new_status.as_deref() {
-            Some("done" | "wontfix") => {
-                let list = db::get_newly_unblocked(&tx, item.id)?;
-                db::remove_blocker_edges(&tx, item.id)?;
-                list.into_iter()
-                    .map(|(id, title)| UnblockedIssue { id, title })
-                    .collect()
-            }
-            _ => vec![],
-        };
-
-        let outcome = if review_notes.is_empty() {
-            "ok"
-        } else {
-            "review"
-        };
-
-        results.push(BatchItemResult {
-            id: item.id,
-            outcome: outcome.to_string(),
-            error: None,
-            notes: review_notes,
-            unblocked,
-            issue: None,
-        });
+        results.push(result);
     }
 
     if !dry_run {
@@ -711,77 +655,30 @@ fn run_update_core(conn: &Connection, input: &str, dry_run: bool) -> Result<Batc
         results,
         summary,
         dry_run,
+        rolled_back: false,
     })
 }
 
 pub fn run_note(conn: &Connection, dry_run: bool, fmt: Format) -> Result<(), ItrError> {
     let input = read_stdin()?;
     let batch_result = run_note_core(conn, &input, dry_run)?;
-    println!("{}", format::format_batch_result(&batch_result, fmt));
+    format::print_output(&format::format_batch_result(&batch_result, fmt), fmt);
     Ok(())
 }
 
 fn run_note_core(conn: &Connection, input: &str, dry_run: bool) -> Result<BatchResult, ItrError> {
     let items = parse_each::<BatchNoteInput>(input, BATCH_NOTE_KNOWN_KEYS)?;
 
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
 
     let mut results: Vec<BatchItemResult> = Vec::with_capacity(items.len());
 
     for entry in items {
-        let (item, review_notes) = match entry {
-            Ok(item) => item,
-            Err(error_result) => {
-                results.push(error_result);
-                continue;
-            }
+        let result = match entry {
+            Ok((item, review_notes)) => note_item(&tx, item, review_notes)?,
+            Err(error_result) => error_result,
         };
-
-        // Resolve agent: input agent field, else ITR_AGENT env, else empty
-        let agent = if item.agent.is_empty() {
-            std::env::var("ITR_AGENT").unwrap_or_default()
-        } else {
-            item.agent.clone()
-        };
-
-        match db::add_note(&tx, item.id, &item.text, &agent) {
-            Ok(note) => {
-                // Unknown payload keys still add the note (accept partial
-                // valid input) but flag the item for review (#212).
-                if !review_notes.is_empty() {
-                    ensure_needs_review_tag(&tx, item.id)?;
-                    for note_text in &review_notes {
-                        db::add_note(&tx, item.id, note_text, "itr")?;
-                    }
-                }
-                let outcome = if review_notes.is_empty() {
-                    "ok"
-                } else {
-                    "review"
-                };
-                let mut notes = vec![note.content];
-                notes.extend(review_notes);
-                results.push(BatchItemResult {
-                    id: item.id,
-                    outcome: outcome.to_string(),
-                    error: None,
-                    notes,
-                    unblocked: vec![],
-                    issue: None,
-                });
-            }
-            Err(ItrError::NotFound(_)) => {
-                results.push(BatchItemResult {
-                    id: item.id,
-                    outcome: "error".to_string(),
-                    error: Some(format!("Issue {} not found", item.id)),
-                    notes: review_notes,
-                    unblocked: vec![],
-                    issue: None,
-                });
-            }
-            Err(e) => return Err(e),
-        }
+        results.push(result);
     }
 
     if !dry_run {
@@ -794,10 +691,297 @@ fn run_note_core(conn: &Connection, input: &str, dry_run: bool) -> Result<BatchR
         results,
         summary,
         dry_run,
+        rolled_back: false,
+    })
+}
+
+/// Close one issue, reporting the issues it unblocked. A missing issue is
+/// an `error` outcome; an already closed one is an idempotent `ok`.
+pub(crate) fn close_item(
+    conn: &Connection,
+    item: BatchCloseInput,
+    review_notes: Vec<String>,
+) -> Result<BatchItemResult, ItrError> {
+    // Try to get the issue
+    let issue = match db::get_issue(conn, item.id) {
+        Ok(i) => i,
+        Err(ItrError::NotFound(_)) => return Ok(not_found(item.id, review_notes)),
+        Err(e) => return Err(e),
+    };
+
+    // Unknown payload keys still close the issue (accept partial valid
+    // input) but flag the item for review (#212).
+    flag_for_review(conn, item.id, &review_notes)?;
+    let outcome = outcome(&review_notes);
+
+    // Already closed — idempotent ok
+    if is_terminal(&issue.status) {
+        let mut notes = vec![format!("Already {}", issue.status)];
+        notes.extend(review_notes);
+        return Ok(BatchItemResult {
+            id: item.id,
+            outcome,
+            error: None,
+            notes,
+            unblocked: vec![],
+            issue: None,
+        });
+    }
+
+    let status = if item.wontfix { "wontfix" } else { "done" };
+
+    db::record_event(conn, item.id, "status", &issue.status, status)?;
+    db::update_issue_field(conn, item.id, "status", status)?;
+
+    if !item.reason.is_empty() {
+        db::record_event(
+            conn,
+            item.id,
+            "close_reason",
+            &issue.close_reason,
+            &item.reason,
+        )?;
+        db::update_issue_field(conn, item.id, "close_reason", &item.reason)?;
+    }
+
+    // Check for newly unblocked issues, then auto-clean stale edges
+    let unblocked_list = db::get_newly_unblocked(conn, item.id)?;
+    db::remove_blocker_edges(conn, item.id)?;
+    let unblocked: Vec<UnblockedIssue> = unblocked_list
+        .into_iter()
+        .map(|(id, title)| UnblockedIssue { id, title })
+        .collect();
+
+    let mut notes = if item.reason.is_empty() {
+        vec![]
+    } else {
+        vec![item.reason.clone()]
+    };
+    notes.extend(review_notes);
+
+    Ok(BatchItemResult {
+        id: item.id,
+        outcome,
+        error: None,
+        notes,
+        unblocked,
+        issue: None,
+    })
+}
+
+/// Apply one update with soft fallback: invalid values are kept out and
+/// reported as review notes. A missing issue is an `error` outcome.
+pub(crate) fn update_item(
+    conn: &Connection,
+    item: BatchUpdateInput,
+    mut review_notes: Vec<String>,
+) -> Result<BatchItemResult, ItrError> {
+    // Try to get the issue
+    let issue = match db::get_issue(conn, item.id) {
+        Ok(i) => i,
+        Err(ItrError::NotFound(_)) => return Ok(not_found(item.id, review_notes)),
+        Err(e) => return Err(e),
+    };
+
+    let mut new_status: Option<String> = None;
+
+    // Handle status
+    if let Some(ref s) = item.status {
+        let normalized = normalize::normalize_status(s);
+        match validate_status(&normalized) {
+            Ok(()) => {
+                db::record_event(conn, item.id, "status", &issue.status, &normalized)?;
+                db::update_issue_field(conn, item.id, "status", &normalized)?;
+                new_status = Some(normalized);
+            }
+            Err(e) => {
+                review_notes.push(format!(
+                    "status '{}' not recognized{}, kept '{}'. Valid: open, in-progress, review, done, wontfix",
+                    s,
+                    e.did_you_mean(),
+                    issue.status
+                ));
+            }
+        }
+    }
+
+    // Handle priority
+    if let Some(ref p) = item.priority {
+        let normalized = normalize::normalize_priority(p);
+        match validate_priority(&normalized) {
+            Ok(()) => {
+                db::record_event(conn, item.id, "priority", &issue.priority, &normalized)?;
+                db::update_issue_field(conn, item.id, "priority", &normalized)?;
+            }
+            Err(e) => {
+                review_notes.push(format!(
+                    "priority '{}' not recognized{}, kept '{}'. Valid: critical, high, medium, low",
+                    p,
+                    e.did_you_mean(),
+                    issue.priority
+                ));
+            }
+        }
+    }
+
+    // Handle kind
+    if let Some(ref k) = item.kind {
+        let normalized = normalize::normalize_kind(k);
+        match validate_kind(&normalized) {
+            Ok(()) => {
+                db::record_event(conn, item.id, "kind", &issue.kind, &normalized)?;
+                db::update_issue_field(conn, item.id, "kind", &normalized)?;
+            }
+            Err(e) => {
+                review_notes.push(format!(
+                    "kind '{}' not recognized{}, kept '{}'. Valid: bug, feature, task, epic",
+                    k,
+                    e.did_you_mean(),
+                    issue.kind
+                ));
+            }
+        }
+    }
+
+    // Handle title
+    if let Some(ref t) = item.title {
+        db::record_event(conn, item.id, "title", &issue.title, t)?;
+        db::update_issue_field(conn, item.id, "title", t)?;
+    }
+
+    // Handle context
+    if let Some(ref c) = item.context {
+        db::record_event(conn, item.id, "context", &issue.context, c)?;
+        db::update_issue_field(conn, item.id, "context", c)?;
+    }
+
+    // Handle assigned_to
+    if let Some(ref a) = item.assigned_to {
+        db::record_event(conn, item.id, "assigned_to", &issue.assigned_to, a)?;
+        db::update_issue_field(conn, item.id, "assigned_to", a)?;
+    }
+
+    // Handle add_tags / remove_tags (audited in JSON-array format, #187)
+    if !item.add_tags.is_empty() || !item.remove_tags.is_empty() {
+        let current = db::get_issue(conn, item.id)?.tags;
+        let updated = util::apply_tags(current.clone(), &item.add_tags, &item.remove_tags);
+        persist_list_field(conn, item.id, "tags", &current, &updated)?;
+    }
+
+    // Handle add_skills / remove_skills (audited in JSON-array format, #187)
+    if !item.add_skills.is_empty() || !item.remove_skills.is_empty() {
+        let current = db::get_issue(conn, item.id)?.skills;
+        let updated = util::apply_skills(current.clone(), &item.add_skills, &item.remove_skills);
+        persist_list_field(conn, item.id, "skills", &current, &updated)?;
+    }
+
+    // Handle parent changes: `"parent_id": N` re-parents, `"parent_id":
+    // null` or `"no_parent": true` clears — mirroring `itr update
+    // --parent/--no-parent`, but with soft-fallback review notes where
+    // the single-update path hard-errors (missing parent, cycle).
+    let parent_change = if item.no_parent {
+        if matches!(item.parent_id, ParentChange::Set(_)) {
+            review_notes.push(
+                "both parent_id and no_parent set; parent unchanged. Use one of parent_id: <ID> or no_parent: true".to_string(),
+            );
+            ParentChange::Unchanged
+        } else {
+            ParentChange::Clear
+        }
+    } else {
+        item.parent_id
+    };
+    let old_parent = issue.parent_id.map(|p| p.to_string()).unwrap_or_default();
+    match parent_change {
+        ParentChange::Unchanged => {}
+        ParentChange::Set(pid) => {
+            if !db::issue_exists(conn, pid)? {
+                review_notes.push(format!("parent {pid} not found; parent unchanged"));
+            } else if db::is_self_or_descendant(conn, item.id, pid)? {
+                review_notes.push(format!(
+                    "parent {} would create a cycle with {}; parent unchanged",
+                    pid, item.id
+                ));
+            } else if old_parent != pid.to_string() {
+                db::record_event(conn, item.id, "parent_id", &old_parent, &pid.to_string())?;
+                db::update_issue_parent(conn, item.id, Some(pid))?;
+            }
+        }
+        ParentChange::Clear => {
+            if !old_parent.is_empty() {
+                db::record_event(conn, item.id, "parent_id", &old_parent, "")?;
+                db::update_issue_parent(conn, item.id, None)?;
+            }
+        }
+    }
+
+    // Add _needs_review tag and notes if any field was auto-corrected
+    flag_for_review(conn, item.id, &review_notes)?;
+
+    // Check for newly unblocked issues if status changed to terminal
+    let unblocked = match new_status.as_deref() {
+        Some("done" | "wontfix") => {
+            let list = db::get_newly_unblocked(conn, item.id)?;
+            db::remove_blocker_edges(conn, item.id)?;
+            list.into_iter()
+                .map(|(id, title)| UnblockedIssue { id, title })
+                .collect()
+        }
+        _ => vec![],
+    };
+
+    let outcome = outcome(&review_notes);
+
+    Ok(BatchItemResult {
+        id: item.id,
+        outcome,
+        error: None,
+        notes: review_notes,
+        unblocked,
+        issue: None,
     })
 }
 
-fn build_summary(results: &[BatchItemResult]) -> BatchSummary {
+/// Add one note. A missing issue is an `error` outcome.
+pub(crate) fn note_item(
+    conn: &Connection,
+    item: BatchNoteInput,
+    review_notes: Vec<String>,
+) -> Result<BatchItemResult, ItrError> {
+    // Resolve agent: input agent field, else ITR_AGENT / agent.name
+    let agent = super::note::resolve_agent(conn, &item.agent);
+
+    match db::add_note(conn, item.id, &item.text, &agent) {
+        Ok(note) => {
+            // Unknown payload keys still add the note (accept partial
+            // valid input) but flag the item for review (#212).
+            flag_for_review(conn, item.id, &review_notes)?;
+            let outcome = outcome(&review_notes);
+            let mut notes = vec![note.content];
+            notes.extend(review_notes);
+            Ok(BatchItemResult {
+                id: item.id,
+                outcome,
+                error: None,
+                notes,
+                unblocked: vec![],
+                issue: None,
+            })
+        }
+        Err(ItrError::NotFound(_)) => Ok(not_found(item.id, review_notes)),
+        Err(e) => Err(e),
+    }
+}
+
+/// The `error` outcome for an item whose issue does not exist.
+fn not_found(id: i64, review_notes: Vec<String>) -> BatchItemResult {
+    BatchItemResult {
+        notes: review_notes,
+        ..error_result(id, format!("Issue {} not found", id))
+    }
+}
+
+pub(crate) fn build_summary(results: &[BatchItemResult]) -> BatchSummary {
     let mut ok = 0;
     let mut error = 0;
     let mut review = 0;
@@ -848,6 +1032,70 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn add_reads_jsonl_with_status_and_inline_notes() {
+        let conn = open_test_db();
+        let input = concat!(
+            r#"{"title":"Port parser","status":"wip","notes":["half done","tests next"]}"#,
+            "\n\n",
+            r#"{"title":"Odd","status":"paused","notes":[" "],"blocked_by":["@0"]}"#,
+            "\n",
+        );
+        let result = run_add_core(&conn, input, false).unwrap();
+        assert_eq!(result.summary.total, 2);
+
+        let first = result.results[0].id;
+        assert_eq!(result.results[0].outcome, "ok");
+        assert_eq!(db::get_issue(&conn, first).unwrap().status, "in-progress");
+        assert_eq!(note_contents(&conn, first), vec!["half done", "tests next"]);
+        let events = db::get_events_for_issue(&conn, first).unwrap();
+        assert!(events
+            .iter()
+            .any(|e| e.field == "status" && e.new_value == "in-progress"));
+
+        let second = &result.results[1];
+        assert_eq!(second.outcome, "review");
+        assert_eq!(db::get_issue(&conn, second.id).unwrap().status, "open");
+        assert_eq!(second.notes.len(), 2, "{:?}", second.notes);
+        assert!(db::is_blocked(&conn, second.id).unwrap());
+
+        assert!(run_add_core(&conn, "{\"title\":\"ok\"}\nnot json\n", false).is_err());
+    }
+
+    #[test]
+    fn add_sets_estimate() {
+        let conn = open_test_db();
+        let result = run_add_core(&conn, r#"[{"title":"Sized","estimate":5}]"#, false).unwrap();
+        assert_eq!(result.results[0].outcome, "ok");
+        assert_eq!(
+            db::issue_estimate(&conn, result.results[0].id).unwrap(),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn add_blocked_by_resolves_ref_names_in_any_order() {
+        let conn = open_test_db();
+        let input = r#"[
+            {"title":"API","blocked_by":["ref:schema"]},
+            {"title":"Schema","ref":"schema"},
+            {"title":"Again","ref":"schema","blocked_by":["ref:shema"]}
+        ]"#;
+        let result = run_add_core(&conn, input, false).unwrap();
+        let (api, schema) = (result.results[0].id, result.results[1].id);
+        assert_eq!(result.results[0].outcome, "ok");
+        assert_eq!(
+            result.results[0].issue.as_ref().unwrap().blocked_by,
+            vec![schema]
+        );
+        assert!(db::is_blocked(&conn, api).unwrap());
+
+        let again = &result.results[2];
+        assert_eq!(again.outcome, "review");
+        assert!(again.notes[0].contains("already used by item 1"));
+        assert!(again.notes[1].contains("did you mean 'schema'?"));
+    }
+
     // --- #150: `parent` (CLI-flag spelling) must not be silently dropped ---
 
     #[test]
@@ -1479,6 +1727,14 @@ mod tests {
             parse_blocked_by_entry(&serde_json::json!("@2")),
             Ok(BlockedByRef::BatchIndex(2))
         ));
+        assert_eq!(
+            parse_blocked_by_entry(&serde_json::json!("ref: schema ")),
+            Ok(BlockedByRef::Name("schema".to_string()))
+        );
+        assert_eq!(
+            parse_blocked_by_entry(&serde_json::json!("ref:")),
+            Err("ref:".to_string())
+        );
         assert_eq!(
             parse_blocked_by_entry(&serde_json::json!("junk")),
             Err("junk".to_string())
@@ -0,0 +1,128 @@
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::models::Issue;
+use crate::util;
+use rusqlite::Connection;
+use std::process::Command;
+
+/// Longest title slug used in a generated branch name.
+const BRANCH_SLUG_MAX: usize = 40;
+
+/// `itr branch <ID> [--checkout]` — print the git branch name for an issue,
+/// `<kind>/<id>-<title-slug>` (e.g. `task/12-fix-login-timeout`), and record
+/// it on the issue. An issue that already has a recorded branch keeps it, so
+/// retitling an issue never orphans its branch. With `checkout`, the branch
+/// is switched to, and created first if it does not exist.
+pub fn run(conn: &Connection, id: i64, checkout: bool, fmt: Format) -> Result<(), ItrError> {
+    let issue = db::get_issue(conn, id)?;
+    let name = if issue.branch.is_empty() {
+        branch_name(&issue)
+    } else {
+        issue.branch.clone()
+    };
+
+    if checkout {
+        checkout_branch(&name)?;
+    }
+
+    if issue.branch != name {
+        let tx = db::begin_write(conn)?;
+        db::record_event(&tx, id, "branch", &issue.branch, &name)?;
+        db::update_issue_field(&tx, id, "branch", &name)?;
+        tx.commit()?;
+    }
+
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "id": id,
+                "branch": name,
+                "checked_out": checkout,
+            });
+            format::println_json(&out.to_string());
+        }
+        _ => println!("{}", name),
+    }
+    Ok(())
+}
+
+/// Derive `<kind>/<id>-<slug>`; an untitled-looking issue (nothing sluggable)
+/// gets just `<kind>/<id>`.
+fn branch_name(issue: &Issue) -> String {
+    let slug = util::slugify(&issue.title, BRANCH_SLUG_MAX);
+    if slug.is_empty() {
+        format!("{}/{}", issue.kind, issue.id)
+    } else {
+        format!("{}/{}-{}", issue.kind, issue.id, slug)
+    }
+}
+
+/// Switch to `name`, creating it from `HEAD` when it does not exist yet.
+fn checkout_branch(name: &str) -> Result<(), ItrError> {
+    let exists = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("refs/heads/{}", name))
+        .output()?
+        .status
+        .success();
+    let mut cmd = Command::new("git");
+    cmd.arg("checkout").arg("--quiet");
+    if !exists {
+        cmd.arg("-b");
+    }
+    let output = cmd.arg(name).output()?;
+    if !output.status.success() {
+        return Err(ItrError::Io(std::io::Error::other(format!(
+            "git checkout {} failed: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+        ))));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(conn: &Connection, title: &str, kind: &str) -> Issue {
+        db::insert_issue(conn, title, "medium", kind, "", &[], &[], &[], "", None, "")
+            .expect("insert issue")
+    }
+
+    #[test]
+    fn branch_name_uses_kind_id_and_slug() {
+        let conn = db::open_test_db();
+        let issue = add(&conn, "Fix login timeout", "bug");
+        assert_eq!(
+            branch_name(&issue),
+            format!("bug/{}-fix-login-timeout", issue.id)
+        );
+        let bare = add(&conn, "???", "task");
+        assert_eq!(branch_name(&bare), format!("task/{}", bare.id));
+    }
+
+    #[test]
+    fn run_records_branch_once_and_keeps_it_after_retitle() {
+        let conn = db::open_test_db();
+        let issue = add(&conn, "Fix login timeout", "task");
+        run(&conn, issue.id, false, Format::Compact).unwrap();
+        let expected = format!("task/{}-fix-login-timeout", issue.id);
+        assert_eq!(db::get_issue(&conn, issue.id).unwrap().branch, expected);
+
+        db::update_issue_field(&conn, issue.id, "title", "Something else").unwrap();
+        run(&conn, issue.id, false, Format::Compact).unwrap();
+        assert_eq!(db::get_issue(&conn, issue.id).unwrap().branch, expected);
+        let events: Vec<_> = db::get_events_for_issue(&conn, issue.id)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.field == "branch")
+            .collect();
+        assert_eq!(events.len(), 1, "re-running must not re-record");
+    }
+}
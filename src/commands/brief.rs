@@ -0,0 +1,170 @@
+use crate::commands::build_issue_detail;
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::{Brief, BriefIssue, FileHotspot, Issue};
+use crate::urgency::UrgencyConfig;
+use rusqlite::Connection;
+
+/// `itr brief <ID>` / `itr brief --next` — the task packet an orchestrator
+/// hands a worker agent: the issue's detail with its last `notes` notes,
+/// every blocker with its context and latest note, the parent's summary and
+/// progress, and the other active issues that touch the same files.
+///
+/// `--next` briefs the issue `itr next` would pick (same `--skill` filter)
+/// without claiming it.
+pub fn run(
+    conn: &Connection,
+    id: Option<i64>,
+    skills: Vec<String>,
+    notes: usize,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let config = UrgencyConfig::load(conn);
+    let issue = match id {
+        Some(id) => db::get_issue(conn, id)?,
+        None => {
+            let Some(issue) = super::next::candidates(conn, skills.clone(), None, &config)?
+                .into_iter()
+                .next()
+            else {
+                error::print_empty_blocked(
+                    fmt.is_json(),
+                    "No eligible issues found.",
+                    super::next::blocked_count(conn, skills, None)?,
+                );
+                return Ok(());
+            };
+            issue
+        }
+    };
+    let brief = build(conn, issue, notes, &config)?;
+    format::print_output(&format::format_brief(&brief, fmt), fmt);
+    Ok(())
+}
+
+fn build(
+    conn: &Connection,
+    issue: Issue,
+    notes: usize,
+    config: &UrgencyConfig,
+) -> Result<Brief, ItrError> {
+    let mut detail = build_issue_detail(conn, issue, config)?;
+    detail.urgency_breakdown = None;
+    detail.relations = db::get_relations(conn, detail.issue.id)?;
+    let notes_total = detail.notes.len();
+    detail.notes.drain(..notes_total.saturating_sub(notes));
+
+    let parent = match detail.issue.parent_id {
+        Some(pid) => {
+            let parent = db::get_issue(conn, pid)?;
+            let progress = Some(db::subtree_progress(conn, pid)?).filter(|p| p.total > 0);
+            Some(brief_issue(parent, progress, None))
+        }
+        None => None,
+    };
+
+    let mut blockers = Vec::with_capacity(detail.blocked_by.len());
+    for &bid in &detail.blocked_by {
+        let last_note = db::get_notes(conn, bid)?.pop();
+        blockers.push(brief_issue(db::get_issue(conn, bid)?, None, last_note));
+    }
+
+    let mut related_files = Vec::with_capacity(detail.issue.files.len());
+    for path in &detail.issue.files {
+        let issue_ids: Vec<i64> = db::active_issues_with_file(conn, path)?
+            .into_iter()
+            .filter(|&other| other != detail.issue.id)
+            .collect();
+        related_files.push(FileHotspot {
+            path: path.clone(),
+            count: i64::try_from(issue_ids.len()).unwrap_or(i64::MAX),
+            issue_ids,
+        });
+    }
+
+    Ok(Brief {
+        detail,
+        notes_total,
+        parent,
+        blockers,
+        related_files,
+    })
+}
+
+fn brief_issue(
+    issue: Issue,
+    progress: Option<crate::models::SubtreeProgress>,
+    last_note: Option<crate::models::Note>,
+) -> BriefIssue {
+    BriefIssue {
+        id: issue.id,
+        title: issue.title,
+        status: issue.status,
+        priority: issue.priority,
+        kind: issue.kind,
+        assigned_to: issue.assigned_to,
+        context: issue.context,
+        acceptance: issue.acceptance,
+        progress,
+        last_note,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(conn: &Connection, title: &str, kind: &str, parent: Option<i64>, files: &[&str]) -> i64 {
+        let files: Vec<String> = files.iter().map(ToString::to_string).collect();
+        db::insert_issue(
+            conn,
+            title,
+            "medium",
+            kind,
+            "ctx",
+            &files,
+            &[],
+            &[],
+            "",
+            parent,
+            "",
+        )
+        .unwrap()
+        .id
+    }
+
+    #[test]
+    fn brief_gathers_parent_blockers_files_and_recent_notes() {
+        let conn = db::open_test_db();
+        let epic = add(&conn, "epic", "epic", None, &[]);
+        let task = add(&conn, "task", "task", Some(epic), &["src/a.rs", "src/b.rs"]);
+        let blocker = add(&conn, "blocker", "task", None, &["src/a.rs"]);
+        db::add_dependency(&conn, blocker, task).unwrap();
+        db::add_note(&conn, blocker, "halfway there", "alice").unwrap();
+        for n in 0..4 {
+            db::add_note(&conn, task, &format!("note {n}"), "bob").unwrap();
+        }
+
+        let config = UrgencyConfig::load(&conn);
+        let brief = build(&conn, db::get_issue(&conn, task).unwrap(), 2, &config).unwrap();
+
+        assert_eq!(brief.notes_total, 4);
+        let notes: Vec<&str> = brief
+            .detail
+            .notes
+            .iter()
+            .map(|n| n.content.as_str())
+            .collect();
+        assert_eq!(notes, ["note 2", "note 3"]);
+        let parent = brief.parent.unwrap();
+        assert_eq!((parent.id, parent.progress.unwrap().total), (epic, 1));
+        assert_eq!(brief.blockers[0].id, blocker);
+        assert_eq!(
+            brief.blockers[0].last_note.as_ref().unwrap().content,
+            "halfway there"
+        );
+        assert_eq!(brief.related_files[0].issue_ids, [blocker]);
+        assert!(brief.related_files[1].issue_ids.is_empty());
+    }
+}
@@ -1,8 +1,10 @@
+use crate::commands::project;
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
 use crate::models::{BulkResult, ListFilter, UnblockedIssue};
 use crate::normalize;
+use crate::util;
 use rusqlite::Connection;
 use std::collections::HashSet;
 
@@ -52,6 +54,7 @@ fn resolve_filter_ids(
             skills,
             include_blocked: true,
             assigned_to,
+            project: project::scope(),
             ..ListFilter::default()
         },
     )?;
@@ -79,7 +82,10 @@ pub fn run_close(
     let mut all_unblocked = Vec::new();
 
     if !dry_run {
-        let tx = conn.unchecked_transaction()?;
+        if !ids.is_empty() {
+            util::confirm(&format!("Close {} issue(s)?", ids.len()))?;
+        }
+        let tx = db::begin_write(conn)?;
         for id in &ids {
             let old_issue = db::get_issue(&tx, *id)?;
             db::record_event(&tx, *id, "status", &old_issue.status, close_status)?;
@@ -174,8 +180,9 @@ fn run_update_core(
     // Mirrors batch update's keep-current semantics.
     let set_status = match set_status.map(|s| normalize::normalize_status(&s)) {
         Some(s) if normalize::validate_status(&s).is_err() => {
+            let hint = util::did_you_mean(&s, "open, in-progress, review, done, wontfix");
             review_notes.push(format!(
-                "REVIEW: status '{s}' not recognized; kept each issue's current status. Valid: open, in-progress, done, wontfix"
+                "REVIEW: status '{s}' not recognized{hint}; kept each issue's current status. Valid: open, in-progress, review, done, wontfix"
             ));
             None
         }
@@ -183,8 +190,9 @@ fn run_update_core(
     };
     let set_priority = match set_priority.map(|p| normalize::normalize_priority(&p)) {
         Some(p) if normalize::validate_priority(&p).is_err() => {
+            let hint = util::did_you_mean(&p, "critical, high, medium, low");
             review_notes.push(format!(
-                "REVIEW: priority '{p}' not recognized; kept each issue's current priority. Valid: critical, high, medium, low"
+                "REVIEW: priority '{p}' not recognized{hint}; kept each issue's current priority. Valid: critical, high, medium, low"
             ));
             None
         }
@@ -197,7 +205,7 @@ fn run_update_core(
     let cleanup_blockers = matches!(set_status.as_deref(), Some("done" | "wontfix"));
 
     if !dry_run {
-        let tx = conn.unchecked_transaction()?;
+        let tx = db::begin_write(conn)?;
         for id in &ids {
             let old_issue = db::get_issue(&tx, *id)?;
             if let Some(ref s) = set_status {
@@ -272,7 +280,7 @@ pub fn run_relate(
         return Err(ItrError::NotFound(to));
     }
 
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
     let mut links: Vec<(i64, bool)> = Vec::new();
     for &id in &ids {
         if id == to {
@@ -300,7 +308,7 @@ pub fn run_relate(
                 "ids": links.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
                 "dry_run": dry_run,
             });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             for (id, created) in &links {
@@ -348,7 +356,7 @@ pub fn run_depend(
         return Err(ItrError::NotFound(on));
     }
 
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
     let mut edges: Vec<(i64, bool)> = Vec::new();
     for &id in &ids {
         if id == on {
@@ -375,7 +383,7 @@ pub fn run_depend(
                 "ids": edges.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
                 "dry_run": dry_run,
             });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             for (id, _) in &edges {
@@ -422,10 +430,10 @@ pub fn run_note(
             valid: "non-empty string".to_string(),
         });
     }
-    let agent = super::note::resolve_agent(agent);
+    let agent = super::note::resolve_agent(conn, agent);
     let ids = resolve_filter_ids(conn, status, priority, kind, tag, skill, assigned_to)?;
 
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
     let mut notes = Vec::new();
     for &id in &ids {
         notes.push(db::add_note(&tx, id, text, &agent)?);
@@ -442,7 +450,7 @@ pub fn run_note(
                 "ids": notes.iter().map(|n| n.issue_id).collect::<Vec<_>>(),
                 "dry_run": dry_run,
             });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             for note in &notes {
@@ -466,7 +474,12 @@ pub fn run_note(
 fn print_result(result: &BulkResult, fmt: Format) {
     match fmt {
         Format::Json => {
-            println!("{}", serde_json::to_string(result).unwrap_or_default());
+            format::print_json(&serde_json::to_string(result).unwrap_or_default());
+        }
+        _ if format::quiet(fmt) => {
+            for id in &result.ids {
+                println!("{}", id);
+            }
         }
         _ => {
             println!(
@@ -606,7 +619,7 @@ mod tests {
         assert!(
             notes[0].contains("REVIEW")
                 && notes[0].contains("'bogus'")
-                && notes[0].contains("open, in-progress, done, wontfix"),
+                && notes[0].contains("open, in-progress, review, done, wontfix"),
             "note must name the bad value and list valid statuses: {notes:?}"
         );
         assert_eq!(
@@ -3,19 +3,24 @@ use crate::db;
 use crate::error::ItrError;
 use crate::format::{self, Format};
 use crate::models::IssueDetail;
+use crate::notify;
 use crate::urgency::UrgencyConfig;
 use crate::util;
 use rusqlite::Connection;
 
+pub(crate) use crate::store::close_issue;
+
 pub fn run(
     conn: &Connection,
     id: i64,
     reason: Option<String>,
     wontfix: bool,
+    duplicate_of: Option<i64>,
     fmt: Format,
 ) -> Result<(), ItrError> {
-    let (detail, unblocked) = close_issue(conn, id, reason, wontfix)?;
+    let (detail, unblocked) = close_issue(conn, id, reason, wontfix, duplicate_of)?;
     print_detail_with_unblocked(&detail, &unblocked, fmt);
+    notify::unblocked(conn, &unblocked);
     Ok(())
 }
 
@@ -26,7 +31,7 @@ pub fn run(
 ///   contract, including the hard `NOT_FOUND` error for a missing issue.
 /// - Multiple unique IDs: all closes run in one transaction with per-ID soft
 ///   fallback — a missing ID emits `REVIEW: id N not found; skipped` and the
-///   rest proceed. Exit 0 if at least one close succeeded, exit 1 if none did.
+///   rest proceed. Exit 0 if at least one close succeeded, exit 5 (`INVALID_VALUE`) if none did.
 pub fn run_multi(
     conn: &Connection,
     id_tokens: &[String],
@@ -35,7 +40,7 @@ pub fn run_multi(
     duplicate_of: Option<i64>,
     fmt: Format,
 ) -> Result<(), ItrError> {
-    let parsed = util::parse_id_tokens(id_tokens);
+    let parsed = util::parse_id_tokens(&db::resolve_id_tokens(conn, id_tokens)?);
     for note in &parsed.notes {
         eprintln!("{}", note);
     }
@@ -65,10 +70,7 @@ pub fn run_multi(
         // Single-ID contract: unchanged behavior, hard NOT_FOUND on a missing
         // issue, duplicate relation recorded before the close.
         let id = parsed.ids[0];
-        if let Some(dup_id) = duplicate_of {
-            db::add_relation(conn, id, dup_id, "duplicate")?;
-        }
-        return run(conn, id, reason, wontfix, fmt);
+        return run(conn, id, reason, wontfix, duplicate_of, fmt);
     }
 
     let (results, skipped, review_notes) =
@@ -87,6 +89,11 @@ pub fn run_multi(
         });
     }
     print_multi(&results, fmt);
+    let unblocked: Vec<(i64, String)> = results
+        .into_iter()
+        .flat_map(|(_, unblocked)| unblocked)
+        .collect();
+    notify::unblocked(conn, &unblocked);
     Ok(())
 }
 
@@ -113,7 +120,7 @@ fn close_many(
     let reason = reason.unwrap_or_default();
     let status = if wontfix { "wontfix" } else { "done" };
 
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
     // A missing --duplicate-of target can never soft-recover: fail before
     // touching anything, matching the single-ID behavior.
     if let Some(dup_id) = duplicate_of {
@@ -194,7 +201,7 @@ fn print_multi(results: &[(IssueDetail, Vec<(i64, String)>)], fmt: Format) {
             let blocks: Vec<String> = results
                 .iter()
                 .map(|(detail, unblocked)| {
-                    let mut block = format::format_issue_detail(detail, fmt);
+                    let mut block = super::format_issue_result(detail, fmt);
                     let unblocked_str = format::format_unblocked(unblocked, fmt);
                     if !unblocked_str.is_empty() {
                         block.push('\n');
@@ -203,50 +210,12 @@ fn print_multi(results: &[(IssueDetail, Vec<(i64, String)>)], fmt: Format) {
                     block
                 })
                 .collect();
-            println!("{}", blocks.join("\n\n"));
+            let sep = if format::quiet(fmt) { "\n" } else { "\n\n" };
+            println!("{}", blocks.join(sep));
         }
     }
 }
 
-/// Apply all close writes (status event, status flip, optional `close_reason`
-/// event + field, dependency-edge cleanup) inside a single transaction so a
-/// mid-close failure leaves the issue fully unchanged, and build the output
-/// detail from the updated state before committing.
-fn close_issue(
-    conn: &Connection,
-    id: i64,
-    reason: Option<String>,
-    wontfix: bool,
-) -> Result<(IssueDetail, Vec<(i64, String)>), ItrError> {
-    let reason = reason.unwrap_or_default();
-
-    let status = if wontfix { "wontfix" } else { "done" };
-
-    let tx = conn.unchecked_transaction()?;
-
-    // Capture old values for event recording
-    let old_issue = db::get_issue(&tx, id)?;
-
-    db::record_event(&tx, id, "status", &old_issue.status, status)?;
-    db::update_issue_field(&tx, id, "status", status)?;
-    if !reason.is_empty() {
-        db::record_event(&tx, id, "close_reason", &old_issue.close_reason, &reason)?;
-        db::update_issue_field(&tx, id, "close_reason", &reason)?;
-    }
-
-    // Auto-clean dependency edges where this issue was the blocker
-    let unblocked = db::get_newly_unblocked(&tx, id)?;
-    db::remove_blocker_edges(&tx, id)?;
-
-    // Build the output detail from the updated state
-    let issue = db::get_issue(&tx, id)?;
-    let config = UrgencyConfig::load(&tx);
-    let detail = build_issue_detail(&tx, issue, &config)?;
-
-    tx.commit()?;
-    Ok((detail, unblocked))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,6 +243,22 @@ mod tests {
         .id
     }
 
+    #[test]
+    fn failed_close_rolls_back_duplicate_relation() {
+        let conn = test_conn();
+        let id = insert_issue(&conn, "dup");
+        let original = insert_issue(&conn, "original");
+        conn.execute_batch(
+            "CREATE TRIGGER fail_close BEFORE UPDATE OF status ON issues
+             BEGIN SELECT RAISE(ABORT, 'boom'); END;",
+        )
+        .unwrap();
+
+        assert!(run(&conn, id, None, false, Some(original), Format::Compact).is_err());
+        assert!(db::all_relations(&conn).unwrap().is_empty());
+        assert_eq!(db::get_issue(&conn, id).unwrap().status, "open");
+    }
+
     #[test]
     fn close_applies_all_writes_and_reports_unblocked() {
         let conn = test_conn();
@@ -282,7 +267,7 @@ mod tests {
         db::add_dependency(&conn, blocker, blocked).expect("add dependency");
 
         let (detail, unblocked) =
-            close_issue(&conn, blocker, Some("all done".to_string()), false).expect("close");
+            close_issue(&conn, blocker, Some("all done".to_string()), false, None).expect("close");
 
         assert_eq!(detail.issue.status, "done");
         assert_eq!(detail.issue.close_reason, "all done");
@@ -458,7 +443,7 @@ mod tests {
         )
         .expect("create failure trigger");
 
-        let result = close_issue(&conn, blocker, Some("all done".to_string()), false);
+        let result = close_issue(&conn, blocker, Some("all done".to_string()), false, None);
         assert!(result.is_err(), "injected failure must propagate");
 
         // All-or-nothing: the issue must be exactly as before the close.
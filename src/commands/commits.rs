@@ -0,0 +1,54 @@
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use rusqlite::Connection;
+
+/// `itr commits <id>` — list the git commits linked to an issue, oldest
+/// link first. Links come from the post-commit hook (`itr git link`) and
+/// from `itr git close-from-commits`; a `close` link outranks a `mention`.
+pub fn run(conn: &Connection, id: i64, fmt: Format) -> Result<(), ItrError> {
+    db::get_issue(conn, id)?;
+    let commits = db::issue_commits(conn, id)?;
+    if commits.is_empty() {
+        error::print_empty(
+            fmt.is_json(),
+            &format!("No commits linked to issue {}.", id),
+        );
+        return Ok(());
+    }
+    format::print_output(&format::format_commits(&commits, fmt), fmt);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db;
+
+    #[test]
+    fn close_link_outranks_mention_and_duplicates_are_noops() {
+        let conn = db::open_test_db();
+        let id = db::insert_issue(
+            &conn,
+            "Fix parser",
+            "medium",
+            "task",
+            "",
+            &[],
+            &[],
+            &[],
+            "",
+            None,
+            "",
+        )
+        .unwrap()
+        .id;
+        assert!(db::link_commit(&conn, id, "abc1234", "wip", "mention").unwrap());
+        assert!(!db::link_commit(&conn, id, "abc1234", "wip", "mention").unwrap());
+        assert!(db::link_commit(&conn, id, "abc1234", "wip", "close").unwrap());
+        assert!(!db::link_commit(&conn, id, "abc1234", "wip", "mention").unwrap());
+        let commits = db::issue_commits(&conn, id).unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].relation, "close");
+        assert_eq!(db::commit_count(&conn, id).unwrap(), 1);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::cli::CompletionShell;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use clap_complete::env::{Bash, Elvish, EnvCompleter, Fish, Powershell, Zsh};
+
+/// `itr completions <shell>` — print the script that registers `itr` with
+/// the shell. The script calls back into `COMPLETE=<shell> itr` on every
+/// <TAB>, so subcommands and flags always match the installed binary and
+/// issue IDs, tags, and config keys come from the current database. Meant
+/// to be sourced at shell startup rather than saved, so an upgrade can't
+/// leave a stale script behind.
+pub fn run(shell: CompletionShell, fmt: Format) -> Result<(), ItrError> {
+    // The callback runs whatever `itr` the user typed, as clap's own
+    // `COMPLETE=<shell> itr` registration does.
+    let bin = std::env::args_os().next().map_or_else(
+        || "itr".to_string(),
+        |arg| arg.to_string_lossy().into_owned(),
+    );
+    let (name, script) = registration(shell, &bin)?;
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({ "shell": name, "script": script });
+            format::print_json(&out.to_string());
+        }
+        _ => print!("{}", script),
+    }
+    Ok(())
+}
+
+/// The shell's canonical name and its registration script calling `bin`.
+fn registration(shell: CompletionShell, bin: &str) -> Result<(&'static str, String), ItrError> {
+    let completer: &dyn EnvCompleter = match shell {
+        CompletionShell::Bash => &Bash,
+        CompletionShell::Elvish => &Elvish,
+        CompletionShell::Fish => &Fish,
+        CompletionShell::Powershell => &Powershell,
+        CompletionShell::Zsh => &Zsh,
+    };
+    let mut script = Vec::new();
+    completer.write_registration("COMPLETE", "itr", "itr", bin, &mut script)?;
+    Ok((
+        completer.name(),
+        String::from_utf8_lossy(&script).into_owned(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+
+    #[test]
+    fn every_shell_registers_a_callback_into_itr() {
+        for &shell in CompletionShell::value_variants() {
+            let (name, script) = registration(shell, "/opt/bin/itr").unwrap();
+            let expected = shell.to_possible_value().unwrap();
+            assert_eq!(name, expected.get_name());
+            assert!(script.contains("/opt/bin/itr"), "{name}: {script}");
+            assert!(script.contains("COMPLETE"), "{name}: {script}");
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::util;
+use rusqlite::Connection;
+
+/// `itr component add <name> [--assignee A] [--tags a,b]` — register a
+/// component, or update the defaults given for one that exists. Issues filed
+/// in or moved to it take the default assignee when they have none and gain
+/// the default tags.
+pub fn add(
+    conn: &Connection,
+    name: &str,
+    assignee: Option<&str>,
+    tags: Option<&str>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let name = validate_name(name)?;
+    let assignee = assignee.map(str::trim);
+    let tags = tags.map(util::parse_comma_list);
+    let tx = db::begin_write(conn)?;
+    let created = db::upsert_component(&tx, &name, assignee, tags.as_deref())?;
+    let component = db::get_component(&tx, &name)?.ok_or_else(|| ItrError::InvalidValue {
+        field: "component".to_string(),
+        value: name.clone(),
+        valid: "a non-empty name without commas".to_string(),
+    })?;
+    tx.commit()?;
+    if !created && assignee.is_none() && tags.is_none() {
+        eprintln!(
+            "REVIEW: component '{}' already exists; nothing changed",
+            name
+        );
+    }
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "action": "component_add",
+                "component": component.name,
+                "created": created,
+                "default_assignee": component.default_assignee,
+                "default_tags": component.default_tags,
+            });
+            format::println_json(&out.to_string());
+        }
+        _ if format::quiet(fmt) => println!("{}", component.name),
+        _ => println!("{}", format::component_line(&component)),
+    }
+    Ok(())
+}
+
+/// `itr component list` — every component with its defaults and open/closed
+/// issue counts.
+pub fn list(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
+    let components = db::components(conn)?;
+    if components.is_empty() {
+        error::print_empty(fmt.is_json(), "No components.");
+        return Ok(());
+    }
+    format::print_output(&format::format_components(&components, fmt), fmt);
+    Ok(())
+}
+
+/// `itr component remove <name>` — unregister a component. Its issues keep
+/// their assignee and tags and are left without a component, each with a
+/// `component` event.
+pub fn remove(conn: &Connection, name: &str, fmt: Format) -> Result<(), ItrError> {
+    let name = name.trim();
+    let tx = db::begin_write(conn)?;
+    let Some(detached) = db::remove_component(&tx, name)? else {
+        return Err(ItrError::InvalidValue {
+            field: "component".to_string(),
+            value: name.to_string(),
+            valid: registered(&tx)?,
+        });
+    };
+    tx.commit()?;
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "action": "component_remove",
+                "component": name,
+                "detached": detached,
+            });
+            format::println_json(&out.to_string());
+        }
+        _ => println!(
+            "REMOVED:{} DETACHED:{}",
+            format::escape_line_value(name),
+            detached
+        ),
+    }
+    Ok(())
+}
+
+/// Registered component names for an `INVALID_VALUE` message.
+fn registered(conn: &Connection) -> Result<String, ItrError> {
+    let names: Vec<String> = db::components(conn)?.into_iter().map(|c| c.name).collect();
+    Ok(if names.is_empty() {
+        "none yet; register one with `itr component add`".to_string()
+    } else {
+        names.join(", ")
+    })
+}
+
+fn validate_name(name: &str) -> Result<String, ItrError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed.contains(',') || trimmed == "none" {
+        return Err(ItrError::InvalidValue {
+            field: "component".to_string(),
+            value: name.to_string(),
+            valid: "a non-empty name without commas, other than `none`".to_string(),
+        });
+    }
+    Ok(trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::update::{run_core, UpdateRequest};
+    use crate::models::ListFilter;
+    use crate::store::{self, NewIssue};
+
+    #[test]
+    fn components_route_new_and_moved_issues() {
+        let conn = db::open_test_db();
+        add(
+            &conn,
+            "parser",
+            Some("ann"),
+            Some("lang, syntax"),
+            Format::Json,
+        )
+        .unwrap();
+        db::upsert_component(&conn, "ui", None, None).unwrap();
+
+        let filed = store::add_issue(
+            &conn,
+            NewIssue {
+                component: "parser".to_string(),
+                tags: vec!["syntax".to_string()],
+                ..NewIssue::new("Bad escape")
+            },
+        )
+        .unwrap()
+        .issue;
+        assert_eq!(filed.component, "parser");
+        assert_eq!(filed.assigned_to, "ann");
+        assert_eq!(filed.tags, vec!["syntax".to_string(), "lang".to_string()]);
+
+        // An explicit assignee wins; an unknown component is left off.
+        let explicit = store::add_issue(
+            &conn,
+            NewIssue {
+                component: "parser".to_string(),
+                assigned_to: "bo".to_string(),
+                ..NewIssue::new("Slow lexer")
+            },
+        )
+        .unwrap()
+        .issue;
+        assert_eq!(explicit.assigned_to, "bo");
+        let typo = store::add_issue(
+            &conn,
+            NewIssue {
+                component: "parsr".to_string(),
+                ..NewIssue::new("Typo")
+            },
+        )
+        .unwrap();
+        assert_eq!(typo.issue.component, "");
+        assert!(typo.notes[0].content.contains("component 'parsr'"));
+
+        run_core(
+            &conn,
+            typo.issue.id,
+            UpdateRequest {
+                component: Some("parser".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let moved = db::get_issue(&conn, typo.issue.id).unwrap();
+        assert_eq!(
+            (moved.component.as_str(), moved.assigned_to.as_str()),
+            ("parser", "ann")
+        );
+
+        let in_parser = db::list_issues(
+            &conn,
+            &ListFilter {
+                components: vec!["parser".to_string()],
+                ..ListFilter::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(in_parser.len(), 3);
+        assert_eq!(db::components(&conn).unwrap()[0].open, 3);
+
+        remove(&conn, "parser", Format::Json).unwrap();
+        let detached = db::get_issue(&conn, filed.id).unwrap();
+        assert_eq!(detached.component, "");
+        assert_eq!(detached.assigned_to, "ann");
+        assert!(matches!(
+            remove(&conn, "parser", Format::Json),
+            Err(ItrError::InvalidValue { ref field, .. }) if field == "component"
+        ));
+    }
+}
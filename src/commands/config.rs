@@ -1,28 +1,57 @@
+use super::add;
+use super::escalate::ESCALATION_KEYS;
+use super::lint;
+use super::list;
+use super::policy;
+use super::stale;
+use crate::cli::ConfigAction;
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
-use crate::urgency::UrgencyConfig;
+use crate::format::{self, Format};
+use crate::formula::Formula;
+use crate::global_config;
+use crate::normalize;
+use crate::notify;
+use crate::urgency::{UrgencyConfig, FORMULA_KEY};
+use crate::util;
+use crate::webhook;
 use rusqlite::Connection;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
 
+/// `itr config list`: every urgency coefficient plus any other stored key,
+/// each marked with where its value comes from. Database settings (` *`)
+/// win over the global config (` (global)`), which wins over defaults.
 pub fn run_list(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
     let stored = db::config_list(conn)?;
+    let global = global_config::values();
     let defaults = UrgencyConfig::defaults_map();
 
     // Merge: show defaults with overrides
-    let mut entries: Vec<(String, String, bool)> = Vec::with_capacity(defaults.len()); // (key, value, is_custom)
+    let mut entries: Vec<(String, String, &str)> = Vec::with_capacity(defaults.len()); // (key, value, marker)
 
     for (key, default_val) in &defaults {
         let stored_val = stored.iter().find(|(k, _)| k == key);
-        match stored_val {
-            Some((_, v)) => entries.push((key.to_string(), v.clone(), true)),
-            None => entries.push((key.to_string(), format!("{}", default_val), false)),
+        match (stored_val, global.get(*key)) {
+            (Some((_, v)), _) => entries.push((key.to_string(), v.clone(), " *")),
+            (None, Some(v)) => entries.push((key.to_string(), v.clone(), " (global)")),
+            (None, None) => entries.push((key.to_string(), format!("{}", default_val), "")),
         }
     }
 
-    // Also include any non-urgency config entries
+    // Also include the formula (when set) and any non-urgency config entries
+    let extra = |key: &str, val: &str| {
+        (key == FORMULA_KEY && !val.is_empty()) || !key.starts_with("urgency.")
+    };
     for (key, val) in &stored {
-        if !key.starts_with("urgency.") {
-            entries.push((key.clone(), val.clone(), true));
+        if extra(key, val) {
+            entries.push((key.clone(), val.clone(), " *"));
+        }
+    }
+    for (key, val) in &global {
+        if extra(key, val) && !stored.iter().any(|(k, _)| k == key) {
+            entries.push((key.clone(), val.clone(), " (global)"));
         }
     }
 
@@ -32,11 +61,10 @@ pub fn run_list(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
                 .iter()
                 .map(|(k, v, _)| (k.clone(), serde_json::Value::String(v.clone())))
                 .collect();
-            println!("{}", serde_json::to_string(&map)?);
+            format::print_json(&serde_json::to_string(&map)?);
         }
         _ => {
-            for (key, val, is_custom) in &entries {
-                let marker = if *is_custom { " *" } else { "" };
+            for (key, val, marker) in &entries {
                 println!("{}={}{}", key, val, marker);
             }
         }
@@ -67,7 +95,7 @@ pub fn run_get(conn: &Connection, key: &str, fmt: Format) -> Result<(), ItrError
     match fmt {
         Format::Json => {
             let out = serde_json::json!({ "key": key, "value": value });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             println!("{}={}", key, value);
@@ -97,8 +125,249 @@ struct SetValidation {
 /// - unknown key: skip the write and warn with a "did you mean" suggestion
 ///   derived from [`UrgencyConfig::defaults_map`].
 ///
-/// Non-urgency keys are stored verbatim with no checks.
+/// `urgency.formula` is parsed up front; an expression that does not parse is
+/// skipped with the parser's message (an empty value clears the formula).
+///
+/// `escalate.*` keys must be one of [`ESCALATION_KEYS`] with a non-negative
+/// number of days; anything else is skipped with a `REVIEW:` note.
+/// `stale.after_days` takes the same, and `stale.downgrade_to` a priority
+/// (stored normalized; empty turns the downgrade off).
+///
+/// `db.*` keys (connection pragmas and busy-retry settings) are checked by
+/// [`db::validate_db_setting`] and stored normalized; they take effect on the
+/// next invocation.
+///
+/// `webhook.url` must be an http(s) URL (empty turns delivery off) and
+/// `webhook.timeout_ms` a positive number; see [`webhook::validate_setting`].
+/// `notify.desktop` is a boolean, stored as `true`/`false`.
+///
+/// `lint.*` kind lists are stored normalized, `lint.max_title_length` is a
+/// count, and `lint.childless_epics` a boolean; see [`lint::validate_setting`].
+/// `policy.require.*` keys take kind lists the same way.
+/// `defaults.add.<kind>.<field>` keys are checked by [`add::validate_default`].
+///
+/// `format.default` must name an output format and `color.mode` be `auto`,
+/// `always`, or `never`.
+///
+/// Other keys are stored verbatim with no checks.
 fn validate_set(conn: &Connection, key: &str, value: &str) -> Result<SetValidation, ItrError> {
+    validate_value(&|k| db::config_get(conn, k), key, value)
+}
+
+/// [`validate_set`] against any store; `current` looks up the value in
+/// effect today (used by `config --global set`).
+fn validate_value(
+    current: &dyn Fn(&str) -> Result<Option<String>, ItrError>,
+    key: &str,
+    value: &str,
+) -> Result<SetValidation, ItrError> {
+    let choice = match key {
+        global_config::FORMAT_KEY => Some(&["compact", "json", "pretty", "oneline"][..]),
+        global_config::COLOR_KEY => Some(&["auto", "always", "never"][..]),
+        _ => None,
+    };
+    if let Some(valid) = choice {
+        let normalized = value.trim().to_ascii_lowercase();
+        return Ok(if valid.contains(&normalized.as_str()) {
+            SetValidation {
+                store_value: Some(normalized),
+                warnings: Vec::new(),
+            }
+        } else {
+            SetValidation {
+                store_value: None,
+                warnings: vec![format!(
+                    "REVIEW: '{}' for '{}' ignored{}. Valid: {}",
+                    value,
+                    key,
+                    util::did_you_mean(value, &valid.join(", ")),
+                    valid.join(", ")
+                )],
+            }
+        });
+    }
+
+    if key.starts_with("db.") {
+        return Ok(match db::validate_db_setting(key, value) {
+            Ok(normalized) => SetValidation {
+                store_value: Some(normalized),
+                warnings: Vec::new(),
+            },
+            Err(e) => SetValidation {
+                store_value: None,
+                warnings: vec![format!("REVIEW: '{}' for '{}' ignored: {}", value, key, e)],
+            },
+        });
+    }
+
+    if [
+        "webhook.",
+        "notify.",
+        "lint.",
+        "policy.",
+        add::DEFAULTS_PREFIX,
+    ]
+    .iter()
+    .any(|prefix| key.starts_with(prefix))
+    {
+        let checked = if key.starts_with("webhook.") {
+            webhook::validate_setting(key, value)
+        } else if key.starts_with("lint.") {
+            lint::validate_setting(key, value)
+        } else if key.starts_with("policy.") {
+            policy::validate_setting(key, value)
+        } else if key.starts_with(add::DEFAULTS_PREFIX) {
+            add::validate_default(key, value)
+        } else {
+            notify::validate_setting(key, value)
+        };
+        return Ok(match checked {
+            Ok(normalized) => SetValidation {
+                store_value: Some(normalized),
+                warnings: Vec::new(),
+            },
+            Err(e) => SetValidation {
+                store_value: None,
+                warnings: vec![format!("REVIEW: '{}' for '{}' ignored: {}", value, key, e)],
+            },
+        });
+    }
+
+    if key.starts_with("escalate.") {
+        let known = ESCALATION_KEYS.iter().any(|(_, k)| *k == key);
+        let valid_days = value.trim().parse::<f64>().is_ok_and(|d| d >= 0.0);
+        let warning = if !known {
+            let keys: Vec<&str> = ESCALATION_KEYS.iter().map(|(_, k)| *k).collect();
+            Some(format!(
+                "REVIEW: unknown escalation key '{}' ignored. Valid: {}",
+                key,
+                keys.join(", ")
+            ))
+        } else if !valid_days {
+            Some(format!(
+                "REVIEW: '{}' for '{}' is not a non-negative number of days; ignored",
+                value, key
+            ))
+        } else {
+            None
+        };
+        return Ok(match warning {
+            Some(w) => SetValidation {
+                store_value: None,
+                warnings: vec![w],
+            },
+            None => SetValidation {
+                store_value: Some(value.trim().to_string()),
+                warnings: Vec::new(),
+            },
+        });
+    }
+
+    if key.starts_with("stale.") {
+        let trimmed = value.trim();
+        let result = match key {
+            stale::AFTER_DAYS_KEY => trimmed
+                .parse::<f64>()
+                .ok()
+                .filter(|d| *d >= 0.0)
+                .map(|_| trimmed.to_string())
+                .ok_or_else(|| {
+                    format!(
+                        "REVIEW: '{}' for '{}' is not a non-negative number of days; ignored",
+                        value, key
+                    )
+                }),
+            stale::DOWNGRADE_KEY if trimmed.is_empty() => Ok(String::new()),
+            stale::DOWNGRADE_KEY => {
+                let priority = normalize::normalize_priority(trimmed);
+                normalize::validate_priority(&priority)
+                    .map(|()| priority)
+                    .map_err(|_| {
+                        format!(
+                            "REVIEW: '{}' for '{}' ignored. Valid: critical, high, medium, low",
+                            value, key
+                        )
+                    })
+            }
+            _ => Err(format!(
+                "REVIEW: unknown stale key '{}' ignored. Valid: {}, {}",
+                key,
+                stale::AFTER_DAYS_KEY,
+                stale::DOWNGRADE_KEY
+            )),
+        };
+        return Ok(match result {
+            Ok(normalized) => SetValidation {
+                store_value: Some(normalized),
+                warnings: Vec::new(),
+            },
+            Err(w) => SetValidation {
+                store_value: None,
+                warnings: vec![w],
+            },
+        });
+    }
+
+    if let Some(flag) = key.strip_prefix(list::DEFAULTS_PREFIX) {
+        let trimmed = value.trim();
+        let warning = if !list::DEFAULT_FLAGS.contains(&flag) {
+            Some(format!(
+                "REVIEW: unknown list default '{}' ignored. Valid: {}",
+                key,
+                list::DEFAULT_FLAGS
+                    .iter()
+                    .map(|f| format!("{}{}", list::DEFAULTS_PREFIX, f))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        } else if flag == "limit" && trimmed.parse::<usize>().is_err() {
+            Some(format!(
+                "REVIEW: '{}' for '{}' is not a count; ignored",
+                value, key
+            ))
+        } else if flag == "sort" && !list::SORTS.contains(&trimmed) {
+            Some(format!(
+                "REVIEW: '{}' for '{}' ignored. Valid: {}",
+                value,
+                key,
+                list::SORTS.join(", ")
+            ))
+        } else {
+            None
+        };
+        return Ok(match warning {
+            Some(w) => SetValidation {
+                store_value: None,
+                warnings: vec![w],
+            },
+            None => SetValidation {
+                store_value: Some(trimmed.to_string()),
+                warnings: Vec::new(),
+            },
+        });
+    }
+
+    if key == FORMULA_KEY {
+        if value.trim().is_empty() {
+            return Ok(SetValidation {
+                store_value: Some(String::new()),
+                warnings: Vec::new(),
+            });
+        }
+        return Ok(match Formula::parse(value) {
+            Ok(_) => SetValidation {
+                store_value: Some(value.to_string()),
+                warnings: Vec::new(),
+            },
+            Err(e) => SetValidation {
+                store_value: None,
+                warnings: vec![format!(
+                    "REVIEW: urgency formula '{}' ignored: {}. The previous formula (if any) stays in effect",
+                    value, e
+                )],
+            },
+        });
+    }
     if !key.starts_with("urgency.") {
         return Ok(SetValidation {
             store_value: Some(value.to_string()),
@@ -118,7 +387,7 @@ fn validate_set(conn: &Connection, key: &str, value: &str) -> Result<SetValidati
                 // Soft fallback: keep whatever the engine is effectively
                 // using today (a previously stored numeric override, else
                 // the default) so display and behavior stay in sync.
-                let effective = db::config_get(conn, key)?
+                let effective = current(key)?
                     .and_then(|v| v.parse::<f64>().ok())
                     .unwrap_or(*default_val);
                 Ok(SetValidation {
@@ -147,6 +416,14 @@ fn validate_set(conn: &Connection, key: &str, value: &str) -> Result<SetValidati
 }
 
 pub fn run_set(conn: &Connection, key: &str, value: &str, fmt: Format) -> Result<(), ItrError> {
+    if global_config::GLOBAL_ONLY_KEYS.contains(&key) {
+        eprintln!(
+            "REVIEW: '{}' is read before any database is open, so it only works in the global config: `itr config --global set {} {}`",
+            key, key, value
+        );
+        print_ignored(key, value, fmt);
+        return Ok(());
+    }
     let validation = validate_set(conn, key, value)?;
     for warning in &validation.warnings {
         eprintln!("{}", warning);
@@ -158,16 +435,7 @@ pub fn run_set(conn: &Connection, key: &str, value: &str, fmt: Format) -> Result
             v.as_str()
         }
         None => {
-            match fmt {
-                Format::Json => {
-                    let out =
-                        serde_json::json!({ "action": "ignored", "key": key, "value": value });
-                    println!("{}", out);
-                }
-                _ => {
-                    println!("IGNORED: {}={}", key, value);
-                }
-            }
+            print_ignored(key, value, fmt);
             return Ok(());
         }
     };
@@ -175,8 +443,9 @@ pub fn run_set(conn: &Connection, key: &str, value: &str, fmt: Format) -> Result
     match fmt {
         Format::Json => {
             let out = serde_json::json!({ "action": "set", "key": key, "value": stored });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
+        _ if format::quiet(fmt) => {}
         _ => {
             println!("SET: {}={}", key, stored);
         }
@@ -185,13 +454,27 @@ pub fn run_set(conn: &Connection, key: &str, value: &str, fmt: Format) -> Result
     Ok(())
 }
 
+fn print_ignored(key: &str, value: &str, fmt: Format) {
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({ "action": "ignored", "key": key, "value": value });
+            format::print_json(&out.to_string());
+        }
+        _ if format::quiet(fmt) => {}
+        _ => {
+            println!("IGNORED: {}={}", key, value);
+        }
+    }
+}
+
 pub fn run_reset(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
+    util::confirm("Reset all configuration to defaults?")?;
     db::config_reset(conn)?;
 
     match fmt {
         Format::Json => {
             let out = serde_json::json!({ "action": "reset" });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             println!("CONFIG: Reset to defaults");
@@ -201,6 +484,271 @@ pub fn run_reset(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
     Ok(())
 }
 
+/// Keys that record this checkout's state rather than how the project is
+/// tuned; `config export` leaves them out and `config import` skips them.
+const STATE_KEYS: &[&str] = &[db::CURRENT_PROJECT_KEY];
+
+/// `itr config export`: the database's own settings (not defaults, not the
+/// global layer), as TOML or JSON.
+pub fn run_export(conn: &Connection, export_format: &str) -> Result<(), ItrError> {
+    let values: BTreeMap<String, String> = db::config_list(conn)?
+        .into_iter()
+        .filter(|(k, _)| !STATE_KEYS.contains(&k.as_str()))
+        .collect();
+    print_export(
+        &values,
+        export_format,
+        "itr project settings; apply with `itr config import <file>`.",
+    )
+}
+
+fn print_export(
+    values: &BTreeMap<String, String>,
+    export_format: &str,
+    header: &str,
+) -> Result<(), ItrError> {
+    match export_format.trim().to_ascii_lowercase().as_str() {
+        "json" => println!("{}", serde_json::to_string_pretty(values)?),
+        other => {
+            if other != "toml" {
+                eprintln!(
+                    "REVIEW: unknown export format '{}'; writing TOML. Valid: toml, json",
+                    export_format
+                );
+            }
+            print!("{}", global_config::render(values, header));
+        }
+    }
+    Ok(())
+}
+
+/// Read a `config export` file: JSON when it ends in `.json` or starts with
+/// `{`, TOML otherwise.
+pub fn read_settings_file(path: &Path) -> Result<BTreeMap<String, String>, ItrError> {
+    let text = fs::read_to_string(path)?;
+    let invalid = |valid: String| ItrError::InvalidValue {
+        field: "config file".to_string(),
+        value: path.display().to_string(),
+        valid,
+    };
+    let is_json =
+        path.extension().is_some_and(|e| e == "json") || text.trim_start().starts_with('{');
+    if !is_json {
+        return global_config::parse(&text).map_err(invalid);
+    }
+    let object: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&text)?;
+    object
+        .into_iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(s) => Ok((key, s)),
+            serde_json::Value::Number(_) | serde_json::Value::Bool(_) => {
+                Ok((key, value.to_string()))
+            }
+            _ => Err(invalid(format!(
+                "'{}' must be a string, number, or boolean",
+                key
+            ))),
+        })
+        .collect()
+}
+
+/// What `config import` wrote, and which keys it skipped (each with a
+/// `REVIEW:` note on stderr).
+#[derive(Default)]
+pub struct Imported {
+    pub set: BTreeMap<String, String>,
+    pub ignored: Vec<String>,
+}
+
+/// Apply `values` to the database in one transaction, with the same checks
+/// and soft fallbacks as `config set`. Also used by `itr init`.
+pub fn import_settings(
+    conn: &Connection,
+    values: &BTreeMap<String, String>,
+) -> Result<Imported, ItrError> {
+    let tx = db::begin_write(conn)?;
+    let mut out = Imported::default();
+    for (key, value) in values {
+        if global_config::GLOBAL_ONLY_KEYS.contains(&key.as_str())
+            || STATE_KEYS.contains(&key.as_str())
+        {
+            eprintln!("REVIEW: '{}' is not a project setting; skipped", key);
+            out.ignored.push(key.clone());
+            continue;
+        }
+        let validation = validate_set(&tx, key, value)?;
+        for warning in &validation.warnings {
+            eprintln!("{}", warning);
+        }
+        match validation.store_value {
+            Some(stored) => {
+                db::config_set(&tx, key, &stored)?;
+                out.set.insert(key.clone(), stored);
+            }
+            None => out.ignored.push(key.clone()),
+        }
+    }
+    tx.commit()?;
+    Ok(out)
+}
+
+/// `itr config import <file>`.
+pub fn run_import(conn: &Connection, file: &str, fmt: Format) -> Result<(), ItrError> {
+    let values = read_settings_file(Path::new(file))?;
+    let imported = import_settings(conn, &values)?;
+    print_imported(&imported, file, None, fmt);
+    Ok(())
+}
+
+fn print_imported(imported: &Imported, file: &str, scope: Option<&str>, fmt: Format) {
+    match fmt {
+        Format::Json => {
+            let mut out = serde_json::json!({
+                "action": "import",
+                "file": file,
+                "set": imported.set,
+                "ignored": imported.ignored,
+            });
+            if let Some(scope) = scope {
+                out["scope"] = serde_json::Value::from(scope);
+            }
+            format::print_json(&out.to_string());
+        }
+        _ => {
+            for (key, value) in &imported.set {
+                println!("SET: {}={}", key, value);
+            }
+            println!(
+                "IMPORTED: {} setting(s) from {}{}",
+                imported.set.len(),
+                format::escape_line_value(file),
+                if imported.ignored.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({} ignored)", imported.ignored.len())
+                }
+            );
+        }
+    }
+}
+
+/// `itr config --global <action>`: the same verbs against the user-level
+/// file (see [`global_config`]). No database is needed.
+pub fn run_global(action: ConfigAction, fmt: Format) -> Result<(), ItrError> {
+    let mut values = global_config::read()?;
+    let path = global_config::path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+    match action {
+        ConfigAction::List => match fmt {
+            Format::Json => format::print_json(&serde_json::to_string(&values)?),
+            _ if values.is_empty() => println!("No global config ({})", path),
+            _ => {
+                for (key, val) in &values {
+                    println!("{}={}", key, val);
+                }
+            }
+        },
+        ConfigAction::Get { key } => {
+            let Some(value) = values.get(&key) else {
+                return Err(ItrError::InvalidValue {
+                    field: "global config key".to_string(),
+                    value: key,
+                    valid: "Use 'itr config --global list' to see the keys set".to_string(),
+                });
+            };
+            match fmt {
+                Format::Json => {
+                    let out = serde_json::json!({ "key": key, "value": value, "scope": "global" });
+                    format::print_json(&out.to_string());
+                }
+                _ => println!("{}={}", key, value),
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            if let Some(existing) = global_config::conflicting_key(&values, &key) {
+                return Err(ItrError::InvalidValue {
+                    field: "global config key".to_string(),
+                    value: key.clone(),
+                    valid: format!("a key that doesn't nest with '{}'", existing),
+                });
+            }
+            let validation = validate_value(&|k| Ok(values.get(k).cloned()), &key, &value)?;
+            for warning in &validation.warnings {
+                eprintln!("{}", warning);
+            }
+            let Some(stored) = validation.store_value else {
+                print_ignored(&key, &value, fmt);
+                return Ok(());
+            };
+            values.insert(key.clone(), stored.clone());
+            global_config::write(&values)?;
+            match fmt {
+                Format::Json => {
+                    let out = serde_json::json!({
+                        "action": "set",
+                        "key": key,
+                        "value": stored,
+                        "scope": "global",
+                    });
+                    format::print_json(&out.to_string());
+                }
+                _ => println!("SET: {}={}", key, stored),
+            }
+        }
+        ConfigAction::Export { export_format } => {
+            print_export(
+                &values,
+                &export_format,
+                "itr user-level defaults; apply with `itr config --global import <file>`.",
+            )?;
+        }
+        ConfigAction::Import { file } => {
+            let mut imported = Imported::default();
+            for (key, value) in read_settings_file(Path::new(&file))? {
+                if STATE_KEYS.contains(&key.as_str()) {
+                    eprintln!("REVIEW: '{}' is not a setting; skipped", key);
+                    imported.ignored.push(key);
+                    continue;
+                }
+                if let Some(existing) = global_config::conflicting_key(&values, &key) {
+                    eprintln!(
+                        "REVIEW: '{}' nests with '{}' and can't be stored beside it; skipped",
+                        key, existing
+                    );
+                    imported.ignored.push(key);
+                    continue;
+                }
+                let validation = validate_value(&|k| Ok(values.get(k).cloned()), &key, &value)?;
+                for warning in &validation.warnings {
+                    eprintln!("{}", warning);
+                }
+                match validation.store_value {
+                    Some(stored) => {
+                        values.insert(key.clone(), stored.clone());
+                        imported.set.insert(key, stored);
+                    }
+                    None => imported.ignored.push(key),
+                }
+            }
+            global_config::write(&values)?;
+            print_imported(&imported, &file, Some("global"), fmt);
+        }
+        ConfigAction::Reset => {
+            util::confirm(&format!("Delete the global config file {}?", path))?;
+            global_config::remove()?;
+            match fmt {
+                Format::Json => {
+                    let out = serde_json::json!({ "action": "reset", "scope": "global" });
+                    format::print_json(&out.to_string());
+                }
+                _ => println!("CONFIG: Removed {}", format::escape_line_value(&path)),
+            }
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,6 +761,71 @@ mod tests {
 
     // --- #183: validate urgency.* keys and values at set time ---
 
+    #[test]
+    fn escalation_keys_require_known_key_and_days() {
+        let conn = test_conn();
+        let ok = validate_set(&conn, "escalate.high_after_days", "14").unwrap();
+        assert_eq!(ok.store_value.as_deref(), Some("14"));
+        let bad = validate_set(&conn, "escalate.high_after_days", "-1").unwrap();
+        assert!(bad.store_value.is_none());
+        let unknown = validate_set(&conn, "escalate.low_after_days", "5").unwrap();
+        assert!(unknown.store_value.is_none());
+        assert!(unknown.warnings[0].contains("escalate.high_after_days"));
+    }
+
+    #[test]
+    fn stale_keys_take_days_and_a_normalized_priority() {
+        let conn = test_conn();
+        let days = validate_set(&conn, "stale.after_days", " 60 ").unwrap();
+        assert_eq!(days.store_value.as_deref(), Some("60"));
+        let priority = validate_set(&conn, "stale.downgrade_to", "P2").unwrap();
+        assert_eq!(priority.store_value.as_deref(), Some("medium"));
+        let off = validate_set(&conn, "stale.downgrade_to", "").unwrap();
+        assert_eq!(off.store_value.as_deref(), Some(""));
+        assert!(validate_set(&conn, "stale.downgrade_to", "soon")
+            .unwrap()
+            .store_value
+            .is_none());
+        let unknown = validate_set(&conn, "stale.days", "5").unwrap();
+        assert!(unknown.warnings[0].contains("stale.after_days"));
+    }
+
+    #[test]
+    fn list_defaults_require_known_flag_and_valid_value() {
+        let conn = test_conn();
+        let ok = validate_set(&conn, "defaults.list.status", " open,in-progress ").unwrap();
+        assert_eq!(ok.store_value.as_deref(), Some("open,in-progress"));
+        assert!(validate_set(&conn, "defaults.list.limit", "20")
+            .unwrap()
+            .store_value
+            .is_some());
+        for (key, value) in [
+            ("defaults.list.limit", "twenty"),
+            ("defaults.list.sort", "age"),
+            ("defaults.list.colour", "red"),
+        ] {
+            let bad = validate_set(&conn, key, value).unwrap();
+            assert!(bad.store_value.is_none(), "{} = {}", key, value);
+            assert!(bad.warnings[0].starts_with("REVIEW:"));
+        }
+    }
+
+    #[test]
+    fn urgency_formula_is_parsed_before_storing() {
+        let conn = test_conn();
+        let ok = validate_set(&conn, FORMULA_KEY, "priority*2 + age").unwrap();
+        assert_eq!(ok.store_value.as_deref(), Some("priority*2 + age"));
+        assert!(ok.warnings.is_empty());
+
+        let bad = validate_set(&conn, FORMULA_KEY, "priority * urgency").unwrap();
+        assert!(bad.store_value.is_none());
+        assert!(
+            bad.warnings[0].contains("unknown variable 'urgency'"),
+            "warning: {}",
+            bad.warnings[0]
+        );
+    }
+
     #[test]
     fn bogus_value_for_known_urgency_key_warns_and_falls_back_to_default() {
         let conn = test_conn();
@@ -307,4 +920,31 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn import_applies_settings_and_skips_checkout_state() {
+        let conn = test_conn();
+        let dir = std::env::temp_dir().join(format!("itr-config-import-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("settings.json");
+        fs::write(
+            &file,
+            r#"{"urgency.blocking": 9, "project.current": "api", "color.mode": "never"}"#,
+        )
+        .unwrap();
+
+        let values = read_settings_file(&file).unwrap();
+        assert_eq!(values["urgency.blocking"], "9");
+        let imported = import_settings(&conn, &values).unwrap();
+        assert_eq!(imported.set.len(), 1);
+        assert_eq!(imported.ignored, ["color.mode", "project.current"]);
+        assert_eq!(
+            db::config_get(&conn, "urgency.blocking")
+                .unwrap()
+                .as_deref(),
+            Some("9")
+        );
+        assert_eq!(db::config_get(&conn, "project.current").unwrap(), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,11 +1,12 @@
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
 use crate::util;
 use rusqlite::Connection;
 
 /// `itr depend <ID>... --on N` — one or more blocked-issue IDs, repeated,
-/// comma-separated, or inclusive `A-B` ranges.
+/// comma-separated, or inclusive `A-B` ranges. A project-qualified
+/// `webapp/45` is checked against that project and used as its bare ID.
 ///
 /// - Exactly one unique ID: unchanged single-issue contract (hard `NOT_FOUND`
 ///   on either side, hard `CYCLE_DETECTED`).
@@ -13,13 +14,15 @@ use rusqlite::Connection;
 ///   soft fallback — a missing ID emits `REVIEW: id N not found; skipped`,
 ///   and an ID equal to `--on` skips the self-edge. Cycles stay hard errors
 ///   (cycle detection cannot recover) and roll the whole invocation back.
-///   Exit 0 if at least one edge was processed, exit 1 if none were.
+///   Exit 0 if at least one edge was processed, exit 5 (`INVALID_VALUE`) if none were.
 pub fn run_multi(
     conn: &Connection,
     id_tokens: &[String],
     on: i64,
     fmt: Format,
 ) -> Result<(), ItrError> {
+    let id_tokens = db::resolve_id_tokens(conn, id_tokens)?;
+    let id_tokens = id_tokens.as_slice();
     let parsed = util::parse_id_tokens(id_tokens);
     for note in &parsed.notes {
         eprintln!("{}", note);
@@ -56,7 +59,7 @@ pub fn run_multi(
         return Err(ItrError::NotFound(on));
     }
 
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
     let mut edges: Vec<(i64, bool)> = Vec::new();
     for &id in &parsed.ids {
         if id == on {
@@ -97,8 +100,9 @@ pub fn run_multi(
                     })
                 })
                 .collect();
-            println!("{}", serde_json::Value::Array(arr));
+            format::print_json(&serde_json::Value::Array(arr).to_string());
         }
+        _ if format::quiet(fmt) => {}
         _ => {
             for (id, _) in &edges {
                 println!("DEPEND: {} blocked by {}", id, on);
@@ -109,7 +113,10 @@ pub fn run_multi(
 }
 
 pub fn run(conn: &Connection, id: i64, on: i64, fmt: Format) -> Result<(), ItrError> {
-    let created = db::add_dependency(conn, on, id)?;
+    // The existence and cycle checks, insert, and audit event land together.
+    let tx = db::begin_write(conn)?;
+    let created = db::add_dependency(&tx, on, id)?;
+    tx.commit()?;
 
     match fmt {
         Format::Json => {
@@ -119,8 +126,9 @@ pub fn run(conn: &Connection, id: i64, on: i64, fmt: Format) -> Result<(), ItrEr
                 "blocker_id": on,
                 "created": created,
             });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
+        _ if format::quiet(fmt) => {}
         _ => {
             println!("DEPEND: {} blocked by {}", id, on);
         }
@@ -132,11 +140,12 @@ pub fn run(conn: &Connection, id: i64, on: i64, fmt: Format) -> Result<(), ItrEr
 pub fn run_undepend(conn: &Connection, id: i64, on: i64, fmt: Format) -> Result<(), ItrError> {
     // Capture pre-state so UNBLOCKED only fires on a real blocked->unblocked
     // transition caused by this command, never on a no-op (#191).
-    let was_blocked = db::is_blocked(conn, id)?;
-    let removed = db::remove_dependency(conn, on, id)?;
+    let tx = db::begin_write(conn)?;
+    let was_blocked = db::is_blocked(&tx, id)?;
+    let removed = db::remove_dependency(&tx, on, id)?;
 
-    let unblocked = if removed && was_blocked && !db::is_blocked(conn, id)? {
-        let issue = db::get_issue(conn, id)?;
+    let unblocked = if removed && was_blocked && !db::is_blocked(&tx, id)? {
+        let issue = db::get_issue(&tx, id)?;
         if issue.status != "done" && issue.status != "wontfix" {
             vec![(issue.id, issue.title)]
         } else {
@@ -145,6 +154,7 @@ pub fn run_undepend(conn: &Connection, id: i64, on: i64, fmt: Format) -> Result<
     } else {
         vec![]
     };
+    tx.commit()?;
 
     match fmt {
         Format::Json => {
@@ -154,8 +164,9 @@ pub fn run_undepend(conn: &Connection, id: i64, on: i64, fmt: Format) -> Result<
                 "blocker_id": on,
                 "removed": removed,
             });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
+        _ if format::quiet(fmt) => {}
         _ => {
             if removed {
                 println!("UNDEPEND: {} no longer blocked by {}", id, on);
@@ -166,11 +177,179 @@ pub fn run_undepend(conn: &Connection, id: i64, on: i64, fmt: Format) -> Result<
     }
 
     if !unblocked.is_empty() {
-        let unblocked_str = crate::format::format_unblocked(&unblocked, fmt);
+        let unblocked_str = format::format_unblocked(&unblocked, fmt);
+        if !unblocked_str.is_empty() {
+            format::print_output(&unblocked_str, fmt);
+        }
+    }
+    crate::notify::unblocked(conn, &unblocked);
+
+    Ok(())
+}
+
+/// `itr depend <ID>... --on-external "..." [--url URL]` — block each issue on
+/// something outside the tracker. IDs parse as for [`run_multi`]; with
+/// several, a missing ID is skipped with a `REVIEW:` note, and all blockers
+/// land in one transaction.
+pub fn run_external(
+    conn: &Connection,
+    id_tokens: &[String],
+    description: &str,
+    url: &str,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let description = description.trim();
+    if description.is_empty() {
+        return Err(ItrError::InvalidValue {
+            field: "on_external".to_string(),
+            value: description.to_string(),
+            valid: "non-empty description of what the issue waits on".to_string(),
+        });
+    }
+    let id_tokens = db::resolve_id_tokens(conn, id_tokens)?;
+    let parsed = util::parse_id_tokens(&id_tokens);
+    for note in &parsed.notes {
+        eprintln!("{}", note);
+    }
+    for token in &parsed.invalid {
+        eprintln!("REVIEW: ignoring non-integer issue ID '{}'", token);
+    }
+    if parsed.ids.is_empty() {
+        return Err(ItrError::InvalidValue {
+            field: "id".to_string(),
+            value: id_tokens.join(","),
+            valid: "integer issue IDs, repeated, comma-separated, or ranges".to_string(),
+        });
+    }
+
+    let single = parsed.ids.len() == 1;
+    let tx = db::begin_write(conn)?;
+    let mut added = Vec::new();
+    for &id in &parsed.ids {
+        match db::add_external_blocker(&tx, id, description, url.trim()) {
+            Ok(blocker) => added.push(blocker),
+            Err(ItrError::NotFound(_)) if !single => {
+                eprintln!("REVIEW: id {} not found; skipped", id);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    if added.is_empty() {
+        return Err(ItrError::InvalidValue {
+            field: "id".to_string(),
+            value: id_tokens.join(","),
+            valid: "at least one existing issue ID".to_string(),
+        });
+    }
+    tx.commit()?;
+
+    match fmt {
+        Format::Json => {
+            let arr: Vec<serde_json::Value> = added
+                .iter()
+                .map(|b| {
+                    serde_json::json!({
+                        "action": "depend_external",
+                        "blocked_id": b.issue_id,
+                        "external": b,
+                    })
+                })
+                .collect();
+            let out = if single {
+                arr.into_iter().next().unwrap_or_default()
+            } else {
+                serde_json::Value::Array(arr)
+            };
+            format::print_json(&out.to_string());
+        }
+        _ if format::quiet(fmt) => {}
+        _ => {
+            for b in &added {
+                println!(
+                    "DEPEND: {} blocked by {}",
+                    b.issue_id,
+                    format::external_blocker_line(b)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `itr resolve-external <ID> [--blocker N]` — clear one external blocker,
+/// or all of the issue's, and report the issue as unblocked when nothing
+/// else holds it.
+pub fn run_resolve_external(
+    conn: &Connection,
+    id: i64,
+    blocker: Option<i64>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let tx = db::begin_write(conn)?;
+    let issue = db::get_issue(&tx, id)?;
+    let mut blockers = db::external_blockers(&tx, id)?;
+    if let Some(bid) = blocker {
+        if !blockers.iter().any(|b| b.id == bid) {
+            let ids: Vec<String> = blockers.iter().map(|b| b.id.to_string()).collect();
+            return Err(ItrError::InvalidValue {
+                field: "blocker".to_string(),
+                value: bid.to_string(),
+                valid: if ids.is_empty() {
+                    format!("none: issue {} has no external blockers", id)
+                } else {
+                    format!("an external blocker on issue {}: {}", id, ids.join(", "))
+                },
+            });
+        }
+        blockers.retain(|b| b.id == bid);
+    }
+    let was_blocked = db::is_blocked(&tx, id)?;
+    for b in &blockers {
+        db::resolve_external_blocker(&tx, b)?;
+    }
+    let unblocked = if !blockers.is_empty()
+        && was_blocked
+        && !db::is_blocked(&tx, id)?
+        && issue.status != "done"
+        && issue.status != "wontfix"
+    {
+        vec![(issue.id, issue.title)]
+    } else {
+        vec![]
+    };
+    tx.commit()?;
+
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "action": "resolve_external",
+                "blocked_id": id,
+                "resolved": blockers,
+            });
+            format::print_json(&out.to_string());
+        }
+        _ if format::quiet(fmt) => {}
+        _ => {
+            if blockers.is_empty() {
+                println!("RESOLVE_EXTERNAL:not_found {} has no external blockers", id);
+            }
+            for b in &blockers {
+                println!(
+                    "RESOLVE_EXTERNAL: {} no longer blocked by {}",
+                    id,
+                    format::external_blocker_line(b)
+                );
+            }
+        }
+    }
+
+    if !unblocked.is_empty() {
+        let unblocked_str = format::format_unblocked(&unblocked, fmt);
         if !unblocked_str.is_empty() {
-            println!("{}", unblocked_str);
+            format::print_output(&unblocked_str, fmt);
         }
     }
+    crate::notify::unblocked(conn, &unblocked);
 
     Ok(())
 }
@@ -266,4 +445,68 @@ mod tests {
         let err = run_multi(&conn, &["999".to_string()], 1, Format::Compact).unwrap_err();
         assert!(matches!(err, ItrError::NotFound(_)));
     }
+
+    #[test]
+    fn qualified_ids_resolve_across_projects() {
+        let conn = db::open_test_db();
+        let api = seed(&conn, "api");
+        let web = seed(&conn, "web");
+        db::set_issue_project(&conn, api, Some("api")).unwrap();
+        db::set_issue_project(&conn, web, Some("webapp")).unwrap();
+
+        let on = db::resolve_issue_ref(&conn, &format!("webapp/{}", web)).unwrap();
+        run_multi(&conn, &[format!("api/{}", api)], on, Format::Compact).expect("depend");
+        assert_eq!(db::get_blockers(&conn, api).unwrap(), vec![web]);
+        let issue = db::get_issue(&conn, api).unwrap();
+        let config = crate::urgency::UrgencyConfig::default();
+        let detail = itr_core::detail::build_issue_detail(&conn, issue, &config).unwrap();
+        assert_eq!(detail.qualified_ids[&web], format!("webapp/{}", web));
+
+        // The qualifier must name the issue's real project.
+        for bad in [format!("api/{}", web), format!("nope/{}", web)] {
+            assert!(matches!(
+                db::resolve_issue_ref(&conn, &bad),
+                Err(ItrError::InvalidValue { .. })
+            ));
+        }
+        // Cycle detection doesn't stop at a project boundary.
+        let err = run(&conn, web, api, Format::Compact).unwrap_err();
+        assert!(matches!(err, ItrError::CycleDetected(_)));
+    }
+
+    #[test]
+    fn external_blocker_blocks_until_resolved() {
+        let conn = db::open_test_db();
+        let blocker = seed(&conn, "blocker");
+        let a = seed(&conn, "a");
+        run_multi(&conn, &[a.to_string()], blocker, Format::Json).unwrap();
+        run_external(
+            &conn,
+            &[a.to_string()],
+            " upstream 1.4 ",
+            "https://example.com",
+            Format::Json,
+        )
+        .unwrap();
+        let external = db::external_blockers(&conn, a).unwrap();
+        assert_eq!(external.len(), 1);
+        assert_eq!(external[0].description, "upstream 1.4");
+
+        // Closing the blocker issue leaves the external one in force.
+        db::update_issue_field(&conn, blocker, "status", "done").unwrap();
+        assert!(db::is_blocked(&conn, a).unwrap());
+        assert!(db::get_newly_unblocked(&conn, blocker).unwrap().is_empty());
+
+        assert!(matches!(
+            run_resolve_external(&conn, a, Some(external[0].id + 1), Format::Json),
+            Err(ItrError::InvalidValue { ref field, .. }) if field == "blocker"
+        ));
+        run_resolve_external(&conn, a, None, Format::Json).unwrap();
+        assert!(!db::is_blocked(&conn, a).unwrap());
+        assert!(db::external_blockers(&conn, a).unwrap().is_empty());
+        let events = db::get_events_for_issue(&conn, a).unwrap();
+        assert!(events
+            .iter()
+            .any(|e| e.field == "external_blocker_resolved" && e.old_value == "upstream 1.4"));
+    }
 }
@@ -1,7 +1,11 @@
+use crate::cli::CycleFixMode;
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
+use crate::util;
 use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::io::{self, BufRead, Write};
 
 /// Machine-readable code reported on stderr when problems remain after a
 /// doctor run. Remaining problems are a diagnostic outcome, not a bad
@@ -9,8 +13,34 @@ use rusqlite::{params, Connection};
 /// codes like `INVALID_VALUE` (see src/error.rs).
 const PROBLEMS_REMAIN_CODE: &str = "DOCTOR_PROBLEMS_REMAIN";
 
-pub fn run(conn: &Connection, fix: bool, fmt: Format) -> Result<(), ItrError> {
-    let report = diagnose(conn, fix)?;
+/// Hours a registered agent may go unseen before its in-progress issues count
+/// as abandoned claims.
+const CLAIM_TIMEOUT_HOURS: i64 = 24;
+
+/// WAL size above which doctor suggests a checkpoint. The built-in
+/// auto-checkpoint keeps the log near 4 MiB (1000 pages) but never shrinks
+/// the file, so only a log well past that means checkpoints are starved.
+const WAL_WARN_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Free pages that make a `VACUUM` worthwhile: at least this many, and at
+/// least a quarter of the file.
+const BLOAT_MIN_FREE_PAGES: i64 = 256;
+
+/// Edge subsets tried when searching for the fewest edges that break a group
+/// of cycles; past this the suggestion falls back to a greedy pick.
+const CYCLE_SEARCH_BUDGET: usize = 20_000;
+
+/// Issue count from which missing planner statistics are worth an `ANALYZE`;
+/// below it every query is fast regardless of plan.
+const ANALYZE_MIN_ISSUES: i64 = 1000;
+
+pub fn run(
+    conn: &Connection,
+    fix: bool,
+    fix_cycles: Option<CycleFixMode>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let report = diagnose(conn, fix, fix_cycles)?;
 
     // Output
     match fmt {
@@ -23,8 +53,34 @@ pub fn run(conn: &Connection, fix: bool, fmt: Format) -> Result<(), ItrError> {
                 })).collect::<Vec<_>>(),
                 "fixed": report.fixed,
                 "clean": report.remaining.is_empty(),
+                "wal_bytes": report.wal_bytes,
             });
-            println!("{}", out);
+            format::print_json(&out.to_string());
+        }
+        _ if format::gha(fmt) => {
+            for p in &report.remaining {
+                println!(
+                    "{}",
+                    format::gha_command(
+                        "error",
+                        None,
+                        &format!("itr doctor ({})", p.kind),
+                        &p.message
+                    )
+                );
+            }
+            for f in &report.fixed {
+                println!(
+                    "{}",
+                    format::gha_command("notice", None, "itr doctor", &format!("Fixed: {}", f))
+                );
+            }
+            if report.problems.is_empty() {
+                println!(
+                    "{}",
+                    format::gha_command("notice", None, "itr doctor", "All clean")
+                );
+            }
         }
         _ => {
             if report.problems.is_empty() {
@@ -38,6 +94,9 @@ pub fn run(conn: &Connection, fix: bool, fmt: Format) -> Result<(), ItrError> {
                     println!("FIXED: {}", f);
                 }
             }
+            if let Some(bytes) = report.wal_bytes {
+                println!("WAL: {} bytes", bytes);
+            }
         }
     }
 
@@ -47,7 +106,9 @@ pub fn run(conn: &Connection, fix: bool, fmt: Format) -> Result<(), ItrError> {
         if fmt.is_json() {
             eprintln!(
                 "{}",
-                serde_json::json!({ "error": msg, "code": PROBLEMS_REMAIN_CODE })
+                format::error_envelope(
+                    serde_json::json!({ "error": msg, "code": PROBLEMS_REMAIN_CODE })
+                )
             );
         } else {
             eprintln!("ERROR: {}", msg);
@@ -64,15 +125,25 @@ struct DoctorReport {
     fixed: Vec<String>,
     /// Problems still present after any repairs were applied.
     remaining: Vec<Problem>,
+    /// Size of the `-wal` file after the run; `None` when there is none.
+    wal_bytes: Option<u64>,
 }
 
-fn diagnose(conn: &Connection, fix: bool) -> Result<DoctorReport, ItrError> {
+fn diagnose(
+    conn: &Connection,
+    fix: bool,
+    fix_cycles: Option<CycleFixMode>,
+) -> Result<DoctorReport, ItrError> {
     let problems = detect_problems(conn)?;
-    let fixed = if fix {
+    let mut fixed = if fix {
         apply_fixes(conn, &problems)?
     } else {
         Vec::new()
     };
+    if let Some(mode) = fix_cycles {
+        let ask = matches!(mode, CycleFixMode::Interactive) && util::interactive();
+        fixed.extend(break_cycles(conn, ask, &mut io::stdin().lock())?);
+    }
     // Re-scan after repairs so the exit code reflects what actually remains.
     let remaining = if fixed.is_empty() {
         problems.clone()
@@ -83,6 +154,7 @@ fn diagnose(conn: &Connection, fix: bool) -> Result<DoctorReport, ItrError> {
         problems,
         fixed,
         remaining,
+        wal_bytes: wal_bytes(conn),
     })
 }
 
@@ -122,10 +194,26 @@ fn detect_problems(conn: &Connection) -> Result<Vec<Problem>, ItrError> {
     }
 
     // 2. Circular dependency detection
-    for cycle in find_cycles(conn)? {
+    for group in cycle_groups(&db::all_dependencies(conn)?) {
+        let label = if group.cycles.len() == 1 {
+            "Cycle"
+        } else {
+            "Cycles"
+        };
+        let cycles: Vec<String> = group.cycles.iter().map(|c| cycle_path(c)).collect();
+        let commands: Vec<String> = group
+            .suggested
+            .iter()
+            .map(|(blocker, blocked)| format!("itr undepend {} --on {}", blocked, blocker))
+            .collect();
         problems.push(Problem {
             kind: "circular_dependency".to_string(),
-            message: format!("Cycle: {}", cycle),
+            message: format!(
+                "{}: {}; suggested fix: {}",
+                label,
+                cycles.join("; "),
+                commands.join(", ")
+            ),
             fixable: false,
         });
     }
@@ -139,6 +227,18 @@ fn detect_problems(conn: &Connection) -> Result<Vec<Problem>, ItrError> {
         });
     }
 
+    // 3b. In-progress issues held by a registered agent that has gone quiet
+    for claim in find_abandoned_claims(conn, CLAIM_TIMEOUT_HOURS)? {
+        problems.push(Problem {
+            kind: "abandoned_claim".to_string(),
+            message: format!(
+                "Issue {} \"{}\" is in-progress for agent '{}', last seen {}",
+                claim.id, claim.title, claim.agent, claim.last_seen_at
+            ),
+            fixable: true,
+        });
+    }
+
     // 4. Epics with no children
     for (id, title) in find_empty_epics(conn)? {
         problems.push(Problem {
@@ -179,6 +279,123 @@ fn detect_problems(conn: &Connection) -> Result<Vec<Problem>, ItrError> {
         }
     }
 
+    // 7. files/tags that aren't JSON string arrays (read back as empty)
+    for (id, column, raw) in find_malformed_lists(conn)? {
+        problems.push(Problem {
+            kind: "malformed_json".to_string(),
+            message: format!(
+                "Issue {} {} is not a JSON string array and reads as empty; raw value: \"{}\"",
+                id,
+                column,
+                format::escape_quoted_value(&raw)
+            ),
+            fixable: true,
+        });
+    }
+
+    // 8. Notes whose issue no longer exists
+    for (note_id, issue_id) in find_orphaned_notes(conn)? {
+        problems.push(Problem {
+            kind: "orphaned_note".to_string(),
+            message: format!("Note {} references missing issue {}", note_id, issue_id),
+            fixable: true,
+        });
+    }
+
+    // 9. Timestamps in the future
+    for (id, column, value) in find_future_timestamps(conn)? {
+        problems.push(Problem {
+            kind: "future_timestamp".to_string(),
+            message: format!("Issue {} {} {} is in the future", id, column, value),
+            fixable: false,
+        });
+    }
+
+    // 10. Created after last update
+    for (id, created_at, updated_at) in find_created_after_updated(conn)? {
+        problems.push(Problem {
+            kind: "created_after_updated".to_string(),
+            message: format!(
+                "Issue {} created_at {} is after updated_at {}",
+                id, created_at, updated_at
+            ),
+            fixable: false,
+        });
+    }
+
+    // 11. Open issues whose titles differ only in case, punctuation, or spacing
+    for (ids, title) in find_duplicate_titles(conn)? {
+        let list: Vec<String> = ids.iter().map(ToString::to_string).collect();
+        problems.push(Problem {
+            kind: "duplicate_title".to_string(),
+            message: format!(
+                "Open issues {} have near-duplicate titles: \"{}\"",
+                list.join(", "),
+                title
+            ),
+            fixable: false,
+        });
+    }
+
+    // 12. SQLite page-level corruption
+    for message in integrity_errors(conn)? {
+        problems.push(Problem {
+            kind: "integrity_error".to_string(),
+            message,
+            fixable: false,
+        });
+    }
+
+    // 13. Foreign keys not covered by the orphan checks above
+    for (table, rowid, parent) in find_foreign_key_violations(conn)? {
+        problems.push(Problem {
+            kind: "foreign_key_violation".to_string(),
+            message: format!(
+                "Row {} in {} references a missing {} row",
+                rowid, table, parent
+            ),
+            fixable: false,
+        });
+    }
+
+    // 14. Write-ahead log that checkpoints aren't draining
+    if let Some(bytes) = wal_bytes(conn).filter(|b| *b > WAL_WARN_BYTES) {
+        problems.push(Problem {
+            kind: "wal_large".to_string(),
+            message: format!(
+                "WAL file is {} bytes; a checkpoint would fold it into the database",
+                bytes
+            ),
+            fixable: true,
+        });
+    }
+
+    // 15. Free pages left behind by deletes
+    let (free, total) = free_pages(conn)?;
+    if free >= BLOAT_MIN_FREE_PAGES && free * 4 >= total {
+        problems.push(Problem {
+            kind: "db_bloat".to_string(),
+            message: format!(
+                "{} of {} database pages are free; VACUUM would reclaim them",
+                free, total
+            ),
+            fixable: true,
+        });
+    }
+
+    // 16. Query planner statistics never gathered on a large database
+    let issue_count: i64 = conn.query_row("SELECT COUNT(*) FROM issues", [], |row| row.get(0))?;
+    if issue_count >= ANALYZE_MIN_ISSUES && !has_planner_stats(conn)? {
+        problems.push(Problem {
+            kind: "stats_missing".to_string(),
+            message: format!(
+                "No planner statistics for {} issues; ANALYZE would gather them",
+                issue_count
+            ),
+            fixable: true,
+        });
+    }
+
     Ok(problems)
 }
 
@@ -203,11 +420,50 @@ fn apply_fixes(conn: &Connection, problems: &[Problem]) -> Result<Vec<String>, I
         ));
     }
 
+    if problems.iter().any(|p| p.kind == "abandoned_claim") {
+        let released = release_abandoned_claims(conn)?;
+        fixed.push(format!(
+            "Released {} abandoned claims back to open",
+            released
+        ));
+    }
+
+    let orphaned_notes = problems
+        .iter()
+        .filter(|p| p.kind == "orphaned_note")
+        .count();
+    if orphaned_notes > 0 {
+        fix_orphaned_notes(conn)?;
+        fixed.push(format!("Removed {} orphaned notes", orphaned_notes));
+    }
+
+    if problems.iter().any(|p| p.kind == "malformed_json") {
+        let reset = fix_malformed_lists(conn)?;
+        fixed.push(format!("Reset {} malformed files/tags values to []", reset));
+    }
+
     if problems.iter().any(|p| p.kind == "fts_stale") {
         db::fts_rebuild(conn)?;
         fixed.push("Rebuilt FTS index".to_string());
     }
 
+    // Maintenance last: VACUUM rewrites the file the repairs above touched,
+    // and the checkpoint then folds whatever they logged.
+    if problems.iter().any(|p| p.kind == "db_bloat") {
+        conn.execute_batch("VACUUM")?;
+        fixed.push("Vacuumed the database".to_string());
+    }
+
+    if problems.iter().any(|p| p.kind == "stats_missing") {
+        conn.execute_batch("ANALYZE")?;
+        fixed.push("Gathered planner statistics (ANALYZE)".to_string());
+    }
+
+    if problems.iter().any(|p| p.kind == "wal_large") {
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        fixed.push("Checkpointed and truncated the WAL".to_string());
+    }
+
     Ok(fixed)
 }
 
@@ -240,21 +496,427 @@ fn fix_orphaned_deps(conn: &Connection) -> Result<(), ItrError> {
     Ok(())
 }
 
-fn find_cycles(conn: &Connection) -> Result<Vec<String>, ItrError> {
-    // Simple cycle detection: for each dependency, check if there's a reverse path
-    let deps = db::all_dependencies(conn)?;
-    let mut cycles = Vec::new();
+fn find_orphaned_notes(conn: &Connection) -> Result<Vec<(i64, i64)>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT n.id, n.issue_id FROM notes n
+         WHERE NOT EXISTS (SELECT 1 FROM issues WHERE id = n.issue_id)
+         ORDER BY n.id",
+    )?;
+    let results: Vec<(i64, i64)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(results)
+}
+
+fn fix_orphaned_notes(conn: &Connection) -> Result<(), ItrError> {
+    conn.execute(
+        "DELETE FROM notes WHERE NOT EXISTS (SELECT 1 FROM issues WHERE id = notes.issue_id)",
+        [],
+    )?;
+    Ok(())
+}
 
-    for (blocker, blocked) in &deps {
-        // Check if blocked can reach blocker
-        if db::has_path(conn, *blocked, *blocker)? {
-            let cycle_str = format!("{} -> ... -> {}", blocker, blocked);
-            if !cycles.contains(&cycle_str) {
-                cycles.push(cycle_str);
+/// `(issue id, column, value)` for every `created_at`/`updated_at` later
+/// than the current time.
+fn find_future_timestamps(conn: &Connection) -> Result<Vec<(i64, &'static str, String)>, ItrError> {
+    let mut future = Vec::new();
+    for column in ["created_at", "updated_at"] {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, {0} FROM issues WHERE julianday({0}) > julianday('now') ORDER BY id",
+            column
+        ))?;
+        let rows: Vec<(i64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        future.extend(rows.into_iter().map(|(id, value)| (id, column, value)));
+    }
+    future.sort_by_key(|(id, _, _)| *id);
+    Ok(future)
+}
+
+fn find_created_after_updated(conn: &Connection) -> Result<Vec<(i64, String, String)>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, created_at, updated_at FROM issues
+         WHERE julianday(created_at) > julianday(updated_at)
+         ORDER BY id",
+    )?;
+    let results: Vec<(i64, String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(results)
+}
+
+/// Lowercased alphanumeric words of a title, so "Fix login bug." and
+/// "fix  Login-bug" compare equal.
+fn title_key(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Groups of two or more open issues sharing a [`title_key`], as
+/// `(ids, first title)` in order of the lowest ID.
+fn find_duplicate_titles(conn: &Connection) -> Result<Vec<(Vec<i64>, String)>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, title FROM issues WHERE status NOT IN ('done', 'wontfix') ORDER BY id",
+    )?;
+    let rows: Vec<(i64, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut groups: Vec<(String, Vec<i64>, String)> = Vec::new();
+    for (id, title) in rows {
+        let key = title_key(&title);
+        if key.is_empty() {
+            continue;
+        }
+        match groups.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((_, ids, _)) => ids.push(id),
+            None => groups.push((key, vec![id], title)),
+        }
+    }
+    Ok(groups
+        .into_iter()
+        .filter(|(_, ids, _)| ids.len() > 1)
+        .map(|(_, ids, title)| (ids, title))
+        .collect())
+}
+
+/// Messages from `PRAGMA integrity_check`, which reports a single `ok` row
+/// for a healthy file.
+fn integrity_errors(conn: &Connection) -> Result<Vec<String>, ItrError> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let rows: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows.into_iter().filter(|r| r != "ok").collect())
+}
+
+/// `(table, rowid, parent table)` from `PRAGMA foreign_key_check`, minus the
+/// `dependencies` and `notes` rows already reported as orphans.
+fn find_foreign_key_violations(conn: &Connection) -> Result<Vec<(String, i64, String)>, ItrError> {
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let rows: Vec<(String, Option<i64>, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows
+        .into_iter()
+        .filter(|(table, _, _)| table != "dependencies" && table != "notes")
+        .map(|(table, rowid, parent)| (table, rowid.unwrap_or_default(), parent))
+        .collect())
+}
+
+/// Size of the database's `-wal` file, if it is file-backed and has one.
+fn wal_bytes(conn: &Connection) -> Option<u64> {
+    let path = conn.path().filter(|p| !p.is_empty())?;
+    std::fs::metadata(format!("{}-wal", path))
+        .ok()
+        .map(|m| m.len())
+}
+
+/// `(free pages, total pages)` of the main database file.
+fn free_pages(conn: &Connection) -> Result<(i64, i64), ItrError> {
+    let free: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+    let total: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    Ok((free, total))
+}
+
+/// Whether `ANALYZE` has ever run, i.e. `sqlite_stat1` exists.
+fn has_planner_stats(conn: &Connection) -> Result<bool, ItrError> {
+    let n: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'sqlite_stat1'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(n > 0)
+}
+
+/// Issue list columns checked by [`find_malformed_lists`].
+const LIST_COLUMNS: [&str; 2] = ["files", "tags"];
+
+/// `(issue id, column, raw value)` for every `files`/`tags` value that
+/// doesn't parse as a JSON string array. Reads treat those as empty, so the
+/// data is silently lost until someone looks here.
+fn find_malformed_lists(conn: &Connection) -> Result<Vec<(i64, &'static str, String)>, ItrError> {
+    let mut stmt = conn.prepare("SELECT id, files, tags FROM issues ORDER BY id")?;
+    let rows: Vec<(i64, String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut malformed = Vec::new();
+    for (id, files, tags) in rows {
+        for (column, raw) in LIST_COLUMNS.into_iter().zip([files, tags]) {
+            if serde_json::from_str::<Vec<String>>(&raw).is_err() {
+                malformed.push((id, column, raw));
+            }
+        }
+    }
+    Ok(malformed)
+}
+
+/// Rewrite every malformed `files`/`tags` value to `[]`; returns how many.
+fn fix_malformed_lists(conn: &Connection) -> Result<usize, ItrError> {
+    let malformed = find_malformed_lists(conn)?;
+    for (id, column, _) in &malformed {
+        conn.execute(
+            &format!("UPDATE issues SET {} = '[]' WHERE id = ?1", column),
+            params![id],
+        )?;
+    }
+    Ok(malformed.len())
+}
+
+/// Dependency cycles that share issues, with the fewest edges whose removal
+/// breaks every one of them.
+struct CycleGroup {
+    /// Each cycle as issue IDs in blocking order, starting and ending at its
+    /// lowest ID.
+    cycles: Vec<Vec<i64>>,
+    /// `(blocker, blocked)` edges to drop.
+    suggested: Vec<(i64, i64)>,
+}
+
+/// `1 -> 2 -> 3 -> 1`.
+fn cycle_path(cycle: &[i64]) -> String {
+    cycle
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+fn adjacency(edges: &[(i64, i64)]) -> BTreeMap<i64, Vec<i64>> {
+    let mut adj: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    for &(blocker, blocked) in edges {
+        adj.entry(blocker).or_default().push(blocked);
+    }
+    for targets in adj.values_mut() {
+        targets.sort_unstable();
+    }
+    adj
+}
+
+/// Shortest blocking path `from -> ... -> to`, both ends included.
+fn shortest_path(adj: &BTreeMap<i64, Vec<i64>>, from: i64, to: i64) -> Option<Vec<i64>> {
+    let mut parent: HashMap<i64, i64> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            let mut path = vec![to];
+            let mut node = to;
+            while node != from {
+                node = parent[&node];
+                path.push(node);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for &next in adj.get(&current).into_iter().flatten() {
+            if next != from && !parent.contains_key(&next) {
+                parent.insert(next, current);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// The shortest cycle through each edge that lies on one, deduplicated and
+/// rotated to start at its lowest ID.
+fn shortest_cycles(edges: &[(i64, i64)]) -> Vec<Vec<i64>> {
+    let adj = adjacency(edges);
+    let mut cycles: BTreeSet<Vec<i64>> = BTreeSet::new();
+    for &(blocker, blocked) in edges {
+        let Some(mut cycle) = shortest_path(&adj, blocked, blocker) else {
+            continue;
+        };
+        let start = cycle
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, id)| **id)
+            .map_or(0, |(i, _)| i);
+        cycle.rotate_left(start);
+        cycle.push(cycle[0]);
+        cycles.insert(cycle);
+    }
+    cycles.into_iter().collect()
+}
+
+fn cycle_edges(cycle: &[i64]) -> impl Iterator<Item = (i64, i64)> + '_ {
+    cycle.windows(2).map(|w| (w[0], w[1]))
+}
+
+/// Group cycles that share an issue and suggest edges to drop for each group.
+fn cycle_groups(deps: &[(i64, i64)]) -> Vec<CycleGroup> {
+    let mut groups: Vec<(BTreeSet<i64>, Vec<Vec<i64>>)> = Vec::new();
+    for cycle in shortest_cycles(deps) {
+        let mut nodes: BTreeSet<i64> = cycle.iter().copied().collect();
+        let mut cycles = vec![cycle];
+        let mut i = 0;
+        while i < groups.len() {
+            if groups[i].0.is_disjoint(&nodes) {
+                i += 1;
+            } else {
+                let (other_nodes, other_cycles) = groups.remove(i);
+                nodes.extend(other_nodes);
+                cycles.splice(0..0, other_cycles);
+            }
+        }
+        groups.push((nodes, cycles));
+    }
+    groups.sort_by_key(|(nodes, _)| nodes.first().copied());
+    groups
+        .into_iter()
+        .map(|(nodes, mut cycles)| {
+            cycles.sort();
+            let mut edges: Vec<(i64, i64)> = deps
+                .iter()
+                .copied()
+                .filter(|(a, b)| nodes.contains(a) && nodes.contains(b))
+                .collect();
+            edges.sort_unstable();
+            edges.dedup();
+            CycleGroup {
+                suggested: fewest_breaking_edges(&edges),
+                cycles,
+            }
+        })
+        .collect()
+}
+
+/// Smallest set of `edges` whose removal leaves them acyclic, found by trying
+/// subsets in increasing size; past [`CYCLE_SEARCH_BUDGET`] tries, greedily
+/// drop the edge on the most remaining cycles instead.
+fn fewest_breaking_edges(edges: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let n = edges.len();
+    let mut tried = 0;
+    for k in 1..=n {
+        let mut pick: Vec<usize> = (0..k).collect();
+        loop {
+            tried += 1;
+            if tried > CYCLE_SEARCH_BUDGET {
+                return greedy_breaking_edges(edges);
+            }
+            let kept: Vec<(i64, i64)> = (0..n)
+                .filter(|i| !pick.contains(i))
+                .map(|i| edges[i])
+                .collect();
+            if shortest_cycles(&kept).is_empty() {
+                return pick.iter().map(|&i| edges[i]).collect();
+            }
+            // Advance to the next k-subset in lexicographic order.
+            let Some(i) = (0..k).rev().find(|&i| pick[i] < n - k + i) else {
+                break;
+            };
+            pick[i] += 1;
+            for j in i + 1..k {
+                pick[j] = pick[j - 1] + 1;
+            }
+        }
+    }
+    edges.to_vec()
+}
+
+fn greedy_breaking_edges(edges: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut kept = edges.to_vec();
+    let mut dropped = Vec::new();
+    loop {
+        let cycles = shortest_cycles(&kept);
+        let mut counts: BTreeMap<(i64, i64), usize> = BTreeMap::new();
+        for edge in cycles.iter().flat_map(|c| cycle_edges(c)) {
+            *counts.entry(edge).or_default() += 1;
+        }
+        // Most cycles first; ties go to the lowest edge for stable output.
+        let Some((&edge, _)) = counts.iter().max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0))) else {
+            return dropped;
+        };
+        kept.retain(|e| *e != edge);
+        dropped.push(edge);
+    }
+}
+
+/// Drop one edge per cycle until none remain: the suggested edge, or the one
+/// the user picks when `ask` is set. Cycles are re-detected after every drop,
+/// so each answer sees the graph as it now stands.
+fn break_cycles(
+    conn: &Connection,
+    ask: bool,
+    input: &mut impl BufRead,
+) -> Result<Vec<String>, ItrError> {
+    let mut fixed = Vec::new();
+    let mut skipped: BTreeSet<Vec<i64>> = BTreeSet::new();
+    loop {
+        let groups = cycle_groups(&db::all_dependencies(conn)?);
+        let Some((cycle, suggested)) = groups.iter().find_map(|g| {
+            let cycle = g.cycles.iter().find(|c| !skipped.contains(*c))?;
+            let suggested = cycle_edges(cycle).find(|e| g.suggested.contains(e))?;
+            Some((cycle.clone(), suggested))
+        }) else {
+            return Ok(fixed);
+        };
+        let edge = if ask {
+            match prompt_cycle_edge(&cycle, suggested, input)? {
+                Some(edge) => edge,
+                None => {
+                    skipped.insert(cycle);
+                    continue;
+                }
+            }
+        } else {
+            suggested
+        };
+        db::remove_dependency(conn, edge.0, edge.1)?;
+        fixed.push(format!(
+            "Removed dependency {} -> {} to break cycle {}",
+            edge.0,
+            edge.1,
+            cycle_path(&cycle)
+        ));
+    }
+}
+
+/// Ask on stderr which edge of `cycle` to drop: a number picks one, Enter
+/// takes the suggestion, `s` skips the cycle (`None`).
+fn prompt_cycle_edge(
+    cycle: &[i64],
+    suggested: (i64, i64),
+    input: &mut impl BufRead,
+) -> Result<Option<(i64, i64)>, ItrError> {
+    let edges: Vec<(i64, i64)> = cycle_edges(cycle).collect();
+    eprintln!("Cycle: {}", cycle_path(cycle));
+    for (i, (blocker, blocked)) in edges.iter().enumerate() {
+        let marker = if (*blocker, *blocked) == suggested {
+            " (suggested)"
+        } else {
+            ""
+        };
+        eprintln!("  [{}] {} -> {}{}", i + 1, blocker, blocked, marker);
+    }
+    loop {
+        eprint!(
+            "Drop which edge? [1-{}, Enter = suggested, s = skip] ",
+            edges.len()
+        );
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            return Ok(None);
+        }
+        match answer.trim() {
+            "" => return Ok(Some(suggested)),
+            "s" | "S" => return Ok(None),
+            choice => {
+                if let Some(edge) = choice
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| edges.get(n.wrapping_sub(1)))
+                {
+                    return Ok(Some(*edge));
+                }
             }
         }
     }
-    Ok(cycles)
 }
 
 fn find_stuck_in_progress(
@@ -275,6 +937,52 @@ fn find_stuck_in_progress(
     Ok(results)
 }
 
+struct AbandonedClaim {
+    id: i64,
+    title: String,
+    agent: String,
+    last_seen_at: String,
+}
+
+/// In-progress issues assigned to a registered agent not seen for more than
+/// `max_hours`. Unregistered names have no `last_seen_at` to judge by.
+fn find_abandoned_claims(
+    conn: &Connection,
+    max_hours: i64,
+) -> Result<Vec<AbandonedClaim>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT i.id, i.title, a.name, a.last_seen_at FROM issues i
+         JOIN agents a ON a.name = i.assigned_to
+         WHERE i.status = 'in-progress'
+         AND (julianday('now') - julianday(a.last_seen_at)) * 24 > ?1
+         ORDER BY i.id",
+    )?;
+    let results: Vec<AbandonedClaim> = stmt
+        .query_map(params![max_hours], |row| {
+            Ok(AbandonedClaim {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                agent: row.get(2)?,
+                last_seen_at: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(results)
+}
+
+/// Reopen every abandoned claim and clear its assignment, with events, so
+/// `next` offers it again. Returns how many were released.
+fn release_abandoned_claims(conn: &Connection) -> Result<usize, ItrError> {
+    let claims = find_abandoned_claims(conn, CLAIM_TIMEOUT_HOURS)?;
+    for claim in &claims {
+        db::record_event(conn, claim.id, "status", "in-progress", "open")?;
+        db::update_issue_field(conn, claim.id, "status", "open")?;
+        db::record_event(conn, claim.id, "assigned_to", &claim.agent, "")?;
+        db::update_issue_field(conn, claim.id, "assigned_to", "")?;
+    }
+    Ok(claims.len())
+}
+
 fn find_empty_epics(conn: &Connection) -> Result<Vec<(i64, String)>, ItrError> {
     let mut stmt = conn.prepare(
         "SELECT i.id, i.title FROM issues i
@@ -349,7 +1057,7 @@ mod tests {
         let conn = test_conn();
         seed_stale_done_blocker(&conn);
 
-        let result = run(&conn, true, Format::Compact);
+        let result = run(&conn, true, None, Format::Compact);
         assert!(
             result.is_ok(),
             "doctor --fix that repaired everything must exit 0: {:?}",
@@ -362,7 +1070,7 @@ mod tests {
         let conn = test_conn();
         seed_stale_done_blocker(&conn);
 
-        let report = diagnose(&conn, true).unwrap();
+        let report = diagnose(&conn, true, None).unwrap();
         assert_eq!(report.problems.len(), 1);
         assert_eq!(report.problems[0].kind, "done_blocker");
         assert_eq!(
@@ -384,7 +1092,7 @@ mod tests {
         insert_issue(&conn, "lonely epic", "epic", "open");
         seed_stale_done_blocker(&conn);
 
-        let report = diagnose(&conn, true).unwrap();
+        let report = diagnose(&conn, true, None).unwrap();
         assert_eq!(report.problems.len(), 2);
         assert_eq!(report.fixed.len(), 1);
         assert_eq!(report.remaining.len(), 1);
@@ -409,7 +1117,7 @@ mod tests {
         let conn = test_conn();
         seed_stale_done_blocker(&conn);
 
-        let report = diagnose(&conn, false).unwrap();
+        let report = diagnose(&conn, false, None).unwrap();
         assert!(report.fixed.is_empty());
         assert_eq!(report.remaining.len(), 1);
 
@@ -421,15 +1129,238 @@ mod tests {
         );
     }
 
+    #[test]
+    fn malformed_list_columns_are_reported_with_raw_value_and_reset_by_fix() {
+        let conn = test_conn();
+        let id = insert_issue(&conn, "corrupt", "task", "open");
+        conn.execute(
+            "UPDATE issues SET files = '[\"src/a.rs\"', tags = '{\"x\":1}' WHERE id = ?1",
+            params![id],
+        )
+        .unwrap();
+
+        let report = diagnose(&conn, true, None).unwrap();
+        let kinds: Vec<&str> = report.problems.iter().map(|p| p.kind.as_str()).collect();
+        assert_eq!(kinds, ["malformed_json", "malformed_json"]);
+        assert!(
+            report.problems[0].message.contains(r#"[\"src/a.rs\""#),
+            "{}",
+            report.problems[0].message
+        );
+        assert!(report.remaining.is_empty());
+        let (files, tags): (String, String) = conn
+            .query_row(
+                "SELECT files, tags FROM issues WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((files.as_str(), tags.as_str()), ("[]", "[]"));
+    }
+
+    #[test]
+    fn orphan_notes_bad_timestamps_and_duplicate_titles_get_their_own_kinds() {
+        let conn = test_conn();
+        // Imported rows keep their own timestamps (the update trigger would
+        // overwrite updated_at), so seed them with a plain INSERT.
+        conn.execute_batch(
+            "INSERT INTO issues (title, status, created_at, updated_at) VALUES
+             ('Fix login bug', 'open', '2024-01-01T00:00:00Z', '2999-01-01T00:00:00Z'),
+             ('fix  Login-bug.', 'open', '2024-02-01T00:00:00Z', '2024-01-01T00:00:00Z'),
+             ('Fix login bug', 'done', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z');",
+        )
+        .unwrap();
+        conn.execute_batch(
+            "PRAGMA foreign_keys=OFF;
+             INSERT INTO notes (issue_id, content) VALUES (999, 'lost');",
+        )
+        .unwrap();
+
+        let report = diagnose(&conn, true, None).unwrap();
+        let kinds: Vec<&str> = report.problems.iter().map(|p| p.kind.as_str()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "orphaned_note",
+                "future_timestamp",
+                "created_after_updated",
+                "duplicate_title"
+            ]
+        );
+        assert!(
+            report.problems[3].message.contains("1, 2"),
+            "{}",
+            report.problems[3].message
+        );
+        assert_eq!(report.fixed, vec!["Removed 1 orphaned notes".to_string()]);
+        let remaining: Vec<&str> = report.remaining.iter().map(|p| p.kind.as_str()).collect();
+        assert_eq!(
+            remaining,
+            [
+                "future_timestamp",
+                "created_after_updated",
+                "duplicate_title"
+            ]
+        );
+    }
+
+    #[test]
+    fn foreign_key_violations_outside_deps_and_notes_are_reported() {
+        let conn = test_conn();
+        conn.execute_batch(
+            "PRAGMA foreign_keys=OFF;
+             INSERT INTO issues (title, parent_id) VALUES ('lost child', 999);",
+        )
+        .unwrap();
+
+        let report = diagnose(&conn, false, None).unwrap();
+        let kinds: Vec<&str> = report.problems.iter().map(|p| p.kind.as_str()).collect();
+        assert_eq!(kinds, ["foreign_key_violation"]);
+        assert_eq!(
+            report.problems[0].message,
+            "Row 1 in issues references a missing issues row"
+        );
+    }
+
+    #[test]
+    fn bloat_and_missing_stats_are_fixed_by_vacuum_and_analyze() {
+        let conn = test_conn();
+        conn.execute_batch(
+            "CREATE TABLE scratch (b BLOB);
+             WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 400)
+             INSERT INTO scratch SELECT zeroblob(4096) FROM n;
+             DROP TABLE scratch;
+             WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1000)
+             INSERT INTO issues (title) SELECT 'issue ' || i FROM n;",
+        )
+        .unwrap();
+
+        let report = diagnose(&conn, true, None).unwrap();
+        let kinds: Vec<&str> = report.problems.iter().map(|p| p.kind.as_str()).collect();
+        assert_eq!(kinds, ["db_bloat", "stats_missing"]);
+        assert_eq!(
+            report.fixed,
+            [
+                "Vacuumed the database",
+                "Gathered planner statistics (ANALYZE)"
+            ]
+        );
+        assert!(report.remaining.is_empty());
+        assert_eq!(report.wal_bytes, None);
+    }
+
+    #[test]
+    fn cycle_groups_list_full_paths_and_the_fewest_edges_to_drop() {
+        // Two cycles sharing 2 -> 3, plus a separate two-issue cycle.
+        let deps = [
+            (1, 2),
+            (2, 3),
+            (3, 1),
+            (3, 4),
+            (4, 2),
+            (7, 8),
+            (8, 7),
+            (5, 6),
+        ];
+        let groups = cycle_groups(&deps);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].cycles, vec![vec![1, 2, 3, 1], vec![2, 3, 4, 2]]);
+        assert_eq!(groups[0].suggested, vec![(2, 3)]);
+        assert_eq!(groups[1].cycles, vec![vec![7, 8, 7]]);
+        assert_eq!(groups[1].suggested, vec![(7, 8)]);
+
+        // Two disjoint cycles through shared issue 1 need two edges.
+        let groups = cycle_groups(&[(1, 2), (2, 1), (1, 3), (3, 1)]);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].suggested.len(), 2);
+    }
+
+    #[test]
+    fn cycle_problem_names_the_path_and_the_undepend_command() {
+        let conn = test_conn();
+        let a = insert_issue(&conn, "a", "task", "open");
+        let b = insert_issue(&conn, "b", "task", "open");
+        let c = insert_issue(&conn, "c", "task", "open");
+        insert_dep(&conn, a, b);
+        insert_dep(&conn, b, c);
+        insert_dep(&conn, c, a);
+
+        let report = diagnose(&conn, false, None).unwrap();
+        assert_eq!(report.problems.len(), 1);
+        assert_eq!(
+            report.problems[0].message,
+            "Cycle: 1 -> 2 -> 3 -> 1; suggested fix: itr undepend 2 --on 1"
+        );
+    }
+
+    #[test]
+    fn fix_cycles_drops_the_chosen_or_suggested_edge() {
+        let conn = test_conn();
+        for title in ["a", "b", "c", "d"] {
+            insert_issue(&conn, title, "task", "open");
+        }
+        insert_dep(&conn, 1, 2);
+        insert_dep(&conn, 2, 1);
+        insert_dep(&conn, 3, 4);
+        insert_dep(&conn, 4, 3);
+
+        // An out-of-range answer re-asks; "2" picks 2 -> 1; "s" skips 3 <-> 4.
+        let fixed = break_cycles(&conn, true, &mut "9\n2\ns\n".as_bytes()).unwrap();
+        assert_eq!(
+            fixed,
+            ["Removed dependency 2 -> 1 to break cycle 1 -> 2 -> 1"]
+        );
+        assert_eq!(db::all_dependencies(&conn).unwrap().len(), 3);
+
+        let report = diagnose(&conn, false, Some(CycleFixMode::Suggested)).unwrap();
+        assert_eq!(
+            report.fixed,
+            ["Removed dependency 3 -> 4 to break cycle 3 -> 4 -> 3"]
+        );
+        assert!(report.remaining.is_empty());
+    }
+
+    #[test]
+    fn claims_of_unseen_agents_are_released_by_fix() {
+        let conn = test_conn();
+        conn.execute_batch(
+            "INSERT INTO agents (name, last_seen_at) VALUES
+             ('gone', '2020-01-01T00:00:00Z'), ('active', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+             INSERT INTO issues (title, status, assigned_to) VALUES
+             ('held by gone', 'in-progress', 'gone'),
+             ('held by active', 'in-progress', 'active'),
+             ('open for gone', 'open', 'gone');",
+        )
+        .unwrap();
+
+        let report = diagnose(&conn, true, None).unwrap();
+        let kinds: Vec<&str> = report.problems.iter().map(|p| p.kind.as_str()).collect();
+        assert_eq!(kinds, ["abandoned_claim"]);
+        assert_eq!(
+            report.problems[0].message,
+            "Issue 1 \"held by gone\" is in-progress for agent 'gone', last seen 2020-01-01T00:00:00Z"
+        );
+        assert_eq!(report.fixed, ["Released 1 abandoned claims back to open"]);
+        assert!(report.remaining.is_empty());
+        let (status, assigned): (String, String) = conn
+            .query_row(
+                "SELECT status, assigned_to FROM issues WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((status.as_str(), assigned.as_str()), ("open", ""));
+    }
+
     #[test]
     fn clean_database_has_no_failure() {
         let conn = test_conn();
         insert_issue(&conn, "healthy issue", "task", "open");
 
-        let report = diagnose(&conn, false).unwrap();
+        let report = diagnose(&conn, false, None).unwrap();
         assert!(report.problems.is_empty());
         assert!(report.remaining.is_empty());
         assert_eq!(failure_message(&report, false), None);
-        run(&conn, false, Format::Compact).unwrap();
+        run(&conn, false, None, Format::Compact).unwrap();
     }
 }
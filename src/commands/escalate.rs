@@ -0,0 +1,221 @@
+use crate::commands::project;
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::models::ListFilter;
+use crate::util;
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// Priorities an issue can be escalated *to*, highest first, with the config
+/// key holding the age threshold (in days) for each.
+pub const ESCALATION_KEYS: &[(&str, &str)] = &[
+    ("critical", "escalate.critical_after_days"),
+    ("high", "escalate.high_after_days"),
+    ("medium", "escalate.medium_after_days"),
+];
+
+/// One priority bump performed (or planned, under `--dry-run`).
+#[derive(Debug, Clone, serde::Serialize)]
+struct Escalation {
+    id: i64,
+    title: String,
+    from: String,
+    to: String,
+    age_days: f64,
+}
+
+/// `itr escalate [--dry-run]` — apply the `escalate.*_after_days` policy:
+/// an open or in-progress issue whose priority has not changed for at least
+/// N days is raised to the highest priority whose threshold it meets. Age
+/// counts from the last recorded `priority` event, falling back to
+/// `created_at`, so a deliberate downgrade restarts the clock.
+///
+/// Each bump records a `priority` event. With no policy configured the
+/// command is a no-op with a `REVIEW:` note.
+pub fn run(conn: &Connection, dry_run: bool, fmt: Format) -> Result<(), ItrError> {
+    let policy = load_policy(conn);
+    if policy.is_empty() {
+        eprintln!(
+            "REVIEW: no escalation policy configured; set e.g. 'itr config set escalate.high_after_days 14'"
+        );
+    }
+
+    let tx = db::begin_write(conn)?;
+    let escalations = if policy.is_empty() {
+        Vec::new()
+    } else {
+        plan(&tx, &policy)?
+    };
+    if !dry_run {
+        for e in &escalations {
+            db::record_event(&tx, e.id, "priority", &e.from, &e.to)?;
+            db::update_issue_field(&tx, e.id, "priority", &e.to)?;
+        }
+    }
+    tx.commit()?;
+
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "action": "escalate",
+                "count": escalations.len(),
+                "results": escalations,
+                "dry_run": dry_run,
+            });
+            format::println_json(&out.to_string());
+        }
+        _ if format::quiet(fmt) => {
+            for e in &escalations {
+                println!("{}", e.id);
+            }
+        }
+        _ => {
+            for e in &escalations {
+                println!(
+                    "ESCALATED:{} {} -> {} AGE:{:.0}d \"{}\"{}",
+                    e.id,
+                    e.from,
+                    e.to,
+                    e.age_days,
+                    format::escape_quoted_value(&e.title),
+                    if dry_run { " (dry-run)" } else { "" }
+                );
+            }
+            if escalations.is_empty() {
+                println!("ESCALATE: 0 issues");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read the configured thresholds as `(priority, days)`, highest priority
+/// first. Non-numeric or negative values are skipped with a `REVIEW:` note.
+fn load_policy(conn: &Connection) -> Vec<(&'static str, f64)> {
+    let mut policy = Vec::new();
+    for &(priority, key) in ESCALATION_KEYS {
+        let Ok(Some(raw)) = db::config_get(conn, key) else {
+            continue;
+        };
+        match raw.trim().parse::<f64>() {
+            Ok(days) if days >= 0.0 => policy.push((priority, days)),
+            _ => eprintln!(
+                "REVIEW: '{}' for '{}' is not a non-negative number of days; rule skipped",
+                raw, key
+            ),
+        }
+    }
+    policy
+}
+
+fn plan(conn: &Connection, policy: &[(&str, f64)]) -> Result<Vec<Escalation>, ItrError> {
+    let last_change = last_priority_change(conn)?;
+    let issues = db::list_issues(
+        conn,
+        &ListFilter {
+            include_blocked: true,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    let mut out = Vec::new();
+    for issue in issues {
+        let since = last_change
+            .get(&issue.id)
+            .filter(|t| t.as_str() > issue.created_at.as_str())
+            .unwrap_or(&issue.created_at);
+        let age_days = util::days_since(since);
+        let target = policy
+            .iter()
+            .find(|(to, days)| age_days >= *days && rank(to) < rank(&issue.priority));
+        if let Some(&(to, _)) = target {
+            out.push(Escalation {
+                id: issue.id,
+                title: issue.title,
+                from: issue.priority,
+                to: to.to_string(),
+                age_days,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Latest `priority` event timestamp per issue.
+fn last_priority_change(conn: &Connection) -> Result<HashMap<i64, String>, ItrError> {
+    let mut stmt = conn.prepare(
+        "SELECT issue_id, MAX(created_at) FROM events WHERE field = 'priority' GROUP BY issue_id",
+    )?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(rows)
+}
+
+/// Lower is more urgent; unknown priorities sort last.
+fn rank(priority: &str) -> u8 {
+    match priority {
+        "critical" => 0,
+        "high" => 1,
+        "medium" => 2,
+        "low" => 3,
+        _ => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(conn: &Connection, priority: &str, days_old: i64) -> i64 {
+        let id = db::insert_test_issue(conn, "old");
+        db::update_issue_field(conn, id, "priority", priority).unwrap();
+        conn.execute(
+            "UPDATE issues SET created_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?2) WHERE id = ?1",
+            rusqlite::params![id, format!("-{} days", days_old)],
+        )
+        .unwrap();
+        id
+    }
+
+    fn priority(conn: &Connection, id: i64) -> String {
+        db::get_issue(conn, id).unwrap().priority
+    }
+
+    #[test]
+    fn escalates_to_highest_met_threshold_and_records_event() {
+        let conn = db::open_test_db();
+        db::config_set(&conn, "escalate.high_after_days", "14").unwrap();
+        db::config_set(&conn, "escalate.critical_after_days", "30").unwrap();
+        let young = add(&conn, "low", 3);
+        let mid = add(&conn, "low", 20);
+        let ancient = add(&conn, "medium", 40);
+        let already = add(&conn, "critical", 40);
+
+        run(&conn, false, Format::Compact).unwrap();
+        assert_eq!(priority(&conn, young), "low");
+        assert_eq!(priority(&conn, mid), "high");
+        assert_eq!(priority(&conn, ancient), "critical");
+        assert_eq!(priority(&conn, already), "critical");
+        let events = db::get_events_for_issue(&conn, mid).unwrap();
+        assert!(events
+            .iter()
+            .any(|e| e.field == "priority" && e.old_value == "low" && e.new_value == "high"));
+    }
+
+    #[test]
+    fn recent_priority_change_restarts_clock_and_dry_run_writes_nothing() {
+        let conn = db::open_test_db();
+        db::config_set(&conn, "escalate.high_after_days", "14").unwrap();
+        let downgraded = add(&conn, "low", 30);
+        db::record_event(&conn, downgraded, "priority", "high", "low").unwrap();
+        let stale = add(&conn, "medium", 30);
+
+        run(&conn, true, Format::Compact).unwrap();
+        assert_eq!(priority(&conn, stale), "medium", "dry run must not write");
+        run(&conn, false, Format::Compact).unwrap();
+        assert_eq!(priority(&conn, downgraded), "low");
+        assert_eq!(priority(&conn, stale), "high");
+    }
+}
@@ -0,0 +1,160 @@
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::models::Event;
+use rusqlite::Connection;
+use std::io::{ErrorKind, Write};
+use std::time::Duration;
+
+pub fn run(
+    conn: &Connection,
+    since: Option<String>,
+    follow: bool,
+    interval_ms: u64,
+    count: Option<usize>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let mut stdout = std::io::stdout();
+    let result = stream(
+        conn,
+        since.as_deref(),
+        follow.then(|| Duration::from_millis(interval_ms)),
+        count,
+        |event| {
+            let line = format::format_event_line(event, fmt);
+            writeln!(stdout, "{}", format::stream_line(line, fmt))?;
+            stdout.flush()
+        },
+    );
+    match result {
+        // The reader went away (`itr events --follow | head -1`): stop quietly.
+        Err(ItrError::Io(e)) if e.kind() == ErrorKind::BrokenPipe => Ok(()),
+        other => other,
+    }
+}
+
+/// Hand recorded events to `emit`, oldest first, stopping after `count`.
+/// Without `follow` that is the history since `since` (all of it when
+/// `None`). With `follow`, keep polling every interval for events committed
+/// by other processes; history is replayed first only when `since` is given,
+/// so a bare `--follow` starts at the current end of the log.
+pub(crate) fn stream(
+    conn: &Connection,
+    since: Option<&str>,
+    follow: Option<Duration>,
+    count: Option<usize>,
+    mut emit: impl FnMut(&Event) -> std::io::Result<()>,
+) -> Result<(), ItrError> {
+    let mut emitted = 0;
+    let mut send = |event: &Event| -> Result<bool, ItrError> {
+        if count.is_some_and(|n| emitted >= n) {
+            return Ok(false);
+        }
+        emit(event)?;
+        emitted += 1;
+        Ok(count.is_none_or(|n| emitted < n))
+    };
+
+    let mut cursor = db::last_event_id(conn)?;
+    if follow.is_none() || since.is_some() {
+        for event in db::get_events_after(conn, 0, since)? {
+            if !send(&event)? {
+                return Ok(());
+            }
+            cursor = event.id;
+        }
+    }
+    let Some(interval) = follow else {
+        return Ok(());
+    };
+
+    let mut version = super::watch::data_version(conn)?;
+    loop {
+        std::thread::sleep(interval);
+        let current = super::watch::data_version(conn)?;
+        if current == version {
+            continue;
+        }
+        version = current;
+        for event in db::get_events_after(conn, cursor, None)? {
+            if !send(&event)? {
+                return Ok(());
+            }
+            cursor = event.id;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::params;
+    use std::path::Path;
+
+    fn collect(
+        conn: &Connection,
+        since: Option<&str>,
+        follow: Option<Duration>,
+        count: Option<usize>,
+    ) -> Vec<Event> {
+        let mut seen = Vec::new();
+        stream(conn, since, follow, count, |e| {
+            seen.push(e.clone());
+            Ok(())
+        })
+        .unwrap();
+        seen
+    }
+
+    #[test]
+    fn history_is_oldest_first_and_honors_since() {
+        let conn = db::init_db(Path::new(":memory:")).unwrap();
+        let id = db::insert_test_issue(&conn, "target");
+        for (value, at) in [
+            ("in-progress", "2026-01-01T00:00:00Z"),
+            ("done", "2026-02-01T00:00:00Z"),
+        ] {
+            conn.execute(
+                "INSERT INTO events (issue_id, field, old_value, new_value, created_at)
+                 VALUES (?1, 'status', '', ?2, ?3)",
+                params![id, value, at],
+            )
+            .unwrap();
+        }
+
+        let all: Vec<String> = collect(&conn, None, None, None)
+            .into_iter()
+            .map(|e| e.new_value)
+            .collect();
+        assert_eq!(all, vec!["in-progress", "done"]);
+        let recent = collect(&conn, Some("2026-01-15"), None, None);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].new_value, "done");
+        assert_eq!(collect(&conn, None, None, Some(1)).len(), 1);
+        assert!(format::format_event_line(&recent[0], Format::Json).starts_with('{'));
+    }
+
+    #[test]
+    fn follow_skips_history_and_streams_new_events() {
+        let path = db::temp_test_db("events-follow");
+        let conn = db::open_db(&path).unwrap();
+        let id = db::insert_test_issue(&conn, "target");
+        db::record_event(&conn, id, "priority", "medium", "high").unwrap();
+
+        let writer_path = path.clone();
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            let conn = db::open_db(&writer_path).unwrap();
+            db::record_event(&conn, id, "status", "open", "done").unwrap();
+        });
+        let seen = collect(&conn, None, Some(Duration::from_millis(20)), Some(1));
+        writer.join().unwrap();
+
+        assert_eq!(seen.len(), 1);
+        assert_eq!(
+            (seen[0].field.as_str(), seen[0].new_value.as_str()),
+            ("status", "done")
+        );
+        db::remove_test_db(&path);
+    }
+}
@@ -1,31 +1,374 @@
+use super::{html_report, todotxt};
 use crate::db;
 use crate::error::ItrError;
-use crate::models::ExportData;
+use crate::models::{ExportData, ExportHeader, FullExport, Issue};
+use crate::normalize;
+use crate::util;
 use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
 
-pub fn run(conn: &Connection, export_format: &str) -> Result<(), ItrError> {
-    let issues = db::all_issues(conn)?;
+/// Which issues `itr export` writes. Every field narrows the set (AND
+/// logic); the default exports everything.
+#[derive(Debug, Default)]
+pub struct ExportFilter {
+    /// Any of these statuses.
+    pub statuses: Vec<String>,
+    /// Any of these kinds.
+    pub kinds: Vec<String>,
+    /// Every one of these tags.
+    pub tags: Vec<String>,
+    /// `--ids` tokens: comma lists and inclusive ranges.
+    pub ids: Vec<String>,
+    /// This issue and all of its descendants.
+    pub subtree: Option<i64>,
+    /// `updated_at` at or after this ISO 8601 timestamp or date.
+    pub since: Option<String>,
+}
+
+/// Every issue `filter` selects with its notes, edges, events, relations,
+/// UUIDs, and field change times, as `itr export` writes them. `full` adds
+/// the `blocks` and `children` lists.
+pub(crate) fn items(
+    conn: &Connection,
+    filter: &ExportFilter,
+    full: bool,
+) -> Result<Vec<ExportData>, ItrError> {
+    let all = db::all_issues(conn)?;
+    let mut children: HashMap<i64, Vec<i64>> = HashMap::new();
+    if full {
+        for issue in &all {
+            if let Some(parent) = issue.parent_id {
+                children.entry(parent).or_default().push(issue.id);
+            }
+        }
+    }
+    let issues = select(all, filter)?;
 
     let mut export_items: Vec<ExportData> = Vec::with_capacity(issues.len());
     for issue in issues {
-        let notes = db::get_notes(conn, issue.id)?;
+        let mut notes = db::get_notes(conn, issue.id)?;
+        let mut note_uuids = db::note_uuids(conn, issue.id)?;
+        for note in &mut notes {
+            note.uuid = note_uuids.remove(&note.id).unwrap_or_default();
+        }
         let blocked_by = db::get_blockers(conn, issue.id)?;
         let events = db::get_events_for_issue(conn, issue.id)?;
         let relations = db::get_relations(conn, issue.id)?;
+        let uuid = db::issue_uuid(conn, issue.id)?;
+        let key = db::issue_key(conn, issue.id)?;
+        let repro = db::issue_repro(conn, issue.id)?;
+        let field_times = db::field_times(conn, issue.id)?;
+        let blocks = if full {
+            db::get_blocking(conn, issue.id)?
+        } else {
+            vec![]
+        };
+        let children = children.remove(&issue.id).unwrap_or_default();
         export_items.push(ExportData {
             issue,
             notes,
             blocked_by,
             events,
             relations,
+            uuid,
+            key,
+            repro,
+            field_times,
+            blocks,
+            children,
         });
     }
+    Ok(export_items)
+}
+
+/// Apply `filter` to `issues`, keeping ID order. Non-canonical status/kind
+/// values and unparseable `--ids` tokens are `REVIEW:`ed on stderr and match
+/// nothing; a missing `--subtree` root is an error.
+fn select(issues: Vec<Issue>, filter: &ExportFilter) -> Result<Vec<Issue>, ItrError> {
+    let (statuses, status_notes) = normalize::normalize_status_filters(&filter.statuses);
+    let (kinds, kind_notes) = normalize::normalize_kind_filters(&filter.kinds);
+    for note in status_notes.iter().chain(&kind_notes) {
+        eprintln!("{}", note);
+    }
+
+    let ids: Option<HashSet<i64>> = if filter.ids.is_empty() {
+        None
+    } else {
+        let parsed = util::parse_id_tokens(&filter.ids);
+        for note in &parsed.notes {
+            eprintln!("{}", note);
+        }
+        if !parsed.invalid.is_empty() {
+            eprintln!(
+                "REVIEW: --ids ignored non-numeric token(s): {}",
+                parsed.invalid.join(", ")
+            );
+        }
+        Some(parsed.ids.into_iter().collect())
+    };
+
+    let subtree: Option<HashSet<i64>> = match filter.subtree {
+        None => None,
+        Some(root) => {
+            if !issues.iter().any(|i| i.id == root) {
+                return Err(ItrError::NotFound(root));
+            }
+            let mut members = HashSet::from([root]);
+            // Reparenting can put a child at a lower ID than its parent, so
+            // one pass in ID order may miss it; repeat until nothing is added.
+            loop {
+                let before = members.len();
+                for issue in &issues {
+                    if issue.parent_id.is_some_and(|p| members.contains(&p)) {
+                        members.insert(issue.id);
+                    }
+                }
+                if members.len() == before {
+                    break;
+                }
+            }
+            Some(members)
+        }
+    };
+
+    Ok(issues
+        .into_iter()
+        .filter(|i| statuses.is_empty() || statuses.contains(&i.status))
+        .filter(|i| kinds.is_empty() || kinds.contains(&i.kind))
+        .filter(|i| filter.tags.iter().all(|t| i.tags.contains(t)))
+        .filter(|i| ids.as_ref().is_none_or(|ids| ids.contains(&i.id)))
+        .filter(|i| subtree.as_ref().is_none_or(|s| s.contains(&i.id)))
+        .filter(|i| {
+            filter
+                .since
+                .as_deref()
+                .is_none_or(|since| i.updated_at.as_str() >= since)
+        })
+        .collect())
+}
+
+/// Render `items` as an Emacs org file: one heading per issue, children
+/// nested under their parent when it is exported too. Status becomes the TODO
+/// keyword, priority `[#A]`-`[#D]`, tags heading tags, and notes a
+/// `:LOGBOOK:` of "Note taken on" entries. Times are UTC.
+fn org(items: &[ExportData]) -> String {
+    let mut out = String::from(
+        "#+TITLE: itr backlog\n#+TODO: TODO IN-PROGRESS REVIEW | DONE WONTFIX\n#+PRIORITIES: A D C\n",
+    );
+    let exported: HashSet<i64> = items.iter().map(|item| item.issue.id).collect();
+    let mut children: HashMap<i64, Vec<&ExportData>> = HashMap::new();
+    let mut roots = Vec::new();
+    for item in items {
+        match item.issue.parent_id {
+            Some(parent) if exported.contains(&parent) => {
+                children.entry(parent).or_default().push(item);
+            }
+            _ => roots.push(item),
+        }
+    }
+    let mut written = HashSet::new();
+    let mut stack: Vec<(&ExportData, usize)> = roots.into_iter().rev().map(|i| (i, 1)).collect();
+    while let Some((item, depth)) = stack.pop() {
+        written.insert(item.issue.id);
+        org_entry(&mut out, item, depth);
+        if let Some(kids) = children.get(&item.issue.id) {
+            stack.extend(kids.iter().rev().map(|&kid| (kid, depth + 1)));
+        }
+    }
+    // A parent loop leaves its members unreachable from any root.
+    for item in items.iter().filter(|i| !written.contains(&i.issue.id)) {
+        org_entry(&mut out, item, 1);
+    }
+    out
+}
+
+fn org_entry(out: &mut String, item: &ExportData, depth: usize) {
+    let issue = &item.issue;
+    let keyword = match issue.status.as_str() {
+        "in-progress" => "IN-PROGRESS",
+        "review" => "REVIEW",
+        "done" => "DONE",
+        "wontfix" => "WONTFIX",
+        _ => "TODO",
+    };
+    let cookie = match issue.priority.as_str() {
+        "critical" => "A",
+        "high" => "B",
+        "low" => "D",
+        _ => "C",
+    };
+    let mut heading = format!(
+        "{} {} [#{}] {}",
+        "*".repeat(depth),
+        keyword,
+        cookie,
+        issue.title.replace('\n', " ")
+    );
+    if !issue.tags.is_empty() {
+        let tags: Vec<String> = issue.tags.iter().map(|t| org_tag(t)).collect();
+        heading.push_str(&format!(" :{}:", tags.join(":")));
+    }
+    out.push_str(&heading);
+    out.push('\n');
+
+    let indent = " ".repeat(depth + 1);
+    if matches!(keyword, "DONE" | "WONTFIX") {
+        let closed = item.field_times.get("status").unwrap_or(&issue.updated_at);
+        out.push_str(&format!("{}CLOSED: {}\n", indent, org_time(closed)));
+    }
+    out.push_str(&format!("{}:PROPERTIES:\n", indent));
+    let mut properties = vec![
+        ("ITR_ID", issue.id.to_string()),
+        ("ID", item.uuid.clone()),
+        ("KIND", issue.kind.clone()),
+        ("CREATED", org_time(&issue.created_at)),
+        ("ASSIGNED", issue.assigned_to.clone()),
+        ("PROJECT", issue.project.clone()),
+        ("BRANCH", issue.branch.clone()),
+        ("FILES", issue.files.join(" ")),
+        ("SKILLS", issue.skills.join(" ")),
+        (
+            "BLOCKED_BY",
+            item.blocked_by
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        ("CLOSE_REASON", issue.close_reason.replace('\n', " ")),
+    ];
+    properties.retain(|(_, value)| !value.is_empty());
+    for (name, value) in properties {
+        out.push_str(&format!("{}:{}: {}\n", indent, name, value));
+    }
+    out.push_str(&format!("{}:END:\n", indent));
+
+    if !item.notes.is_empty() {
+        out.push_str(&format!("{}:LOGBOOK:\n", indent));
+        for note in item.notes.iter().rev() {
+            let by = if note.agent.is_empty() {
+                String::new()
+            } else {
+                format!(" by {}", note.agent)
+            };
+            out.push_str(&format!(
+                "{}- Note taken on {}{} \\\\\n",
+                indent,
+                org_time(&note.created_at),
+                by
+            ));
+            org_text(out, &note.content, &format!("{}  ", indent));
+        }
+        out.push_str(&format!("{}:END:\n", indent));
+    }
+    org_text(out, &issue.context, &indent);
+    if !issue.acceptance.is_empty() {
+        out.push_str(&format!("{}Acceptance:\n", indent));
+        org_text(out, &issue.acceptance, &indent);
+    }
+}
 
+/// Body text indented under its heading, so a line starting with `*` can't
+/// open a new one.
+fn org_text(out: &mut String, text: &str, indent: &str) {
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            out.push('\n');
+        } else {
+            out.push_str(&format!("{}{}\n", indent, line));
+        }
+    }
+}
+
+/// Org tags allow letters, digits, `_`, `@`, `#`, and `%`.
+fn org_tag(tag: &str) -> String {
+    tag.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "_@#%".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// An inactive org timestamp, `[2026-01-02 Fri 10:30]`; unparseable input
+/// is kept as is inside the brackets.
+fn org_time(iso: &str) -> String {
+    match chrono::DateTime::parse_from_rfc3339(iso) {
+        Ok(t) => t
+            .with_timezone(&chrono::Utc)
+            .format("[%Y-%m-%d %a %H:%M]")
+            .to_string(),
+        Err(_) => format!("[{}]", iso),
+    }
+}
+
+/// The header of a `--full` export: the `config` table, tag metadata, and
+/// the agent and component registries.
+pub(crate) fn header(conn: &Connection) -> Result<ExportHeader, ItrError> {
+    Ok(ExportHeader {
+        config: db::config_list(conn)?.into_iter().collect(),
+        tags: db::tag_counts(conn)?
+            .into_iter()
+            .filter(|t| !t.description.is_empty() || !t.color.is_empty())
+            .collect(),
+        agents: db::agents(conn)?,
+        components: db::components(conn)?,
+        ..ExportHeader::default()
+    })
+}
+
+/// `itr export`. With `full`, a versioned [`header`] leads the output
+/// (JSONL) or wraps it (JSON). `org` writes
+/// an Emacs org file instead, `todotxt` a todo.txt list, and `html` a
+/// static site into `out`.
+pub fn run(
+    conn: &Connection,
+    export_format: &str,
+    filter: &ExportFilter,
+    full: bool,
+    out: Option<&str>,
+) -> Result<(), ItrError> {
+    let html = export_format == "html";
+    if out.is_some() && !html {
+        eprintln!("REVIEW: --out only applies to --export-format html; writing to stdout");
+    }
+    let export_items = items(conn, filter, full || html)?;
+    let header = if full { Some(header(conn)?) } else { None };
     match export_format {
-        "json" => {
-            println!("{}", serde_json::to_string_pretty(&export_items)?);
+        "html" => {
+            let dir = out.unwrap_or(html_report::DEFAULT_OUT);
+            let index = html_report::write(&export_items, std::path::Path::new(dir))?;
+            println!("EXPORTED: {} issues to {}", export_items.len(), index);
         }
+        "org" => {
+            if full {
+                eprintln!("REVIEW: --full has no effect with --export-format org");
+            }
+            print!("{}", org(&export_items));
+        }
+        "todotxt" => {
+            if full {
+                eprintln!("REVIEW: --full has no effect with --export-format todotxt");
+            }
+            print!("{}", todotxt::render(&export_items));
+        }
+        "json" => match header {
+            Some(header) => {
+                let full = FullExport {
+                    header,
+                    items: export_items,
+                };
+                println!("{}", serde_json::to_string_pretty(&full)?);
+            }
+            None => println!("{}", serde_json::to_string_pretty(&export_items)?),
+        },
         _ => {
+            if let Some(header) = &header {
+                println!("{}", serde_json::to_string(header)?);
+            }
             // JSONL: one item per line
             for item in &export_items {
                 println!("{}", serde_json::to_string(item)?);
@@ -35,3 +378,158 @@ pub fn run(conn: &Connection, export_format: &str) -> Result<(), ItrError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Note;
+    use std::collections::BTreeMap;
+
+    fn issue(id: i64, status: &str, kind: &str, parent_id: Option<i64>, tags: &[&str]) -> Issue {
+        Issue {
+            id,
+            title: format!("issue {}", id),
+            status: status.to_string(),
+            priority: "medium".to_string(),
+            kind: kind.to_string(),
+            context: String::new(),
+            files: vec![],
+            tags: tags.iter().map(ToString::to_string).collect(),
+            skills: vec![],
+            acceptance: String::new(),
+            parent_id,
+            assigned_to: String::new(),
+            close_reason: String::new(),
+            branch: String::new(),
+            project: String::new(),
+            severity: String::new(),
+            component: String::new(),
+            affects_version: String::new(),
+            fixed_in_version: String::new(),
+            estimate: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: format!("2026-01-{:02}T00:00:00Z", id),
+        }
+    }
+
+    fn selected(filter: &ExportFilter) -> Vec<i64> {
+        let issues = vec![
+            issue(1, "open", "task", Some(4), &["ui"]),
+            issue(2, "done", "bug", None, &["ui", "api"]),
+            issue(3, "open", "bug", Some(1), &[]),
+            issue(4, "in-progress", "epic", None, &["api"]),
+            issue(5, "open", "task", None, &["ui"]),
+        ];
+        select(issues, filter)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect()
+    }
+
+    #[test]
+    fn filters_narrow_the_export_together() {
+        assert_eq!(selected(&ExportFilter::default()), [1, 2, 3, 4, 5]);
+        let open_backlog = ExportFilter {
+            statuses: vec!["open".into(), "wip".into()],
+            ..ExportFilter::default()
+        };
+        assert_eq!(selected(&open_backlog), [1, 3, 4, 5]);
+        let tagged_bugs = ExportFilter {
+            kinds: vec!["bug".into()],
+            tags: vec!["ui".into(), "api".into()],
+            ..ExportFilter::default()
+        };
+        assert_eq!(selected(&tagged_bugs), [2]);
+        let ids_since = ExportFilter {
+            ids: vec!["1,3-5".into()],
+            since: Some("2026-01-04".into()),
+            ..ExportFilter::default()
+        };
+        assert_eq!(selected(&ids_since), [4, 5]);
+    }
+
+    #[test]
+    fn subtree_follows_children_at_lower_ids() {
+        let epic = ExportFilter {
+            subtree: Some(4),
+            ..ExportFilter::default()
+        };
+        assert_eq!(selected(&epic), [1, 3, 4]);
+
+        let missing = ExportFilter {
+            subtree: Some(99),
+            ..ExportFilter::default()
+        };
+        assert!(matches!(
+            select(vec![], &missing),
+            Err(ItrError::NotFound(99))
+        ));
+    }
+
+    #[test]
+    fn org_nests_children_and_logs_notes() {
+        let item = |issue: Issue| ExportData {
+            issue,
+            notes: vec![],
+            blocked_by: vec![],
+            events: vec![],
+            relations: vec![],
+            uuid: String::new(),
+            key: String::new(),
+            repro: None,
+            field_times: BTreeMap::new(),
+            blocks: vec![],
+            children: vec![],
+        };
+        let mut epic = item(issue(4, "in-progress", "epic", None, &["front-end"]));
+        epic.issue.priority = "critical".to_string();
+        let mut child = item(issue(1, "done", "task", Some(4), &[]));
+        child.blocked_by = vec![2];
+        child.field_times =
+            BTreeMap::from([("status".to_string(), "2026-01-05T09:30:00Z".to_string())]);
+        child.notes = vec![Note {
+            id: 1,
+            issue_id: 1,
+            content: "Shipped\n* not a heading".to_string(),
+            agent: "alice".to_string(),
+            created_at: "2026-01-05T09:00:00Z".to_string(),
+            uuid: String::new(),
+            kind: String::new(),
+        }];
+        let loose = item(issue(2, "open", "bug", Some(99), &[]));
+
+        assert_eq!(
+            org(&[child, loose, epic]),
+            "#+TITLE: itr backlog
+#+TODO: TODO IN-PROGRESS REVIEW | DONE WONTFIX
+#+PRIORITIES: A D C
+* TODO [#C] issue 2
+  :PROPERTIES:
+  :ITR_ID: 2
+  :KIND: bug
+  :CREATED: [2026-01-01 Thu 00:00]
+  :END:
+* IN-PROGRESS [#A] issue 4 :front_end:
+  :PROPERTIES:
+  :ITR_ID: 4
+  :KIND: epic
+  :CREATED: [2026-01-01 Thu 00:00]
+  :END:
+** DONE [#C] issue 1
+   CLOSED: [2026-01-05 Mon 09:30]
+   :PROPERTIES:
+   :ITR_ID: 1
+   :KIND: task
+   :CREATED: [2026-01-01 Thu 00:00]
+   :BLOCKED_BY: 2
+   :END:
+   :LOGBOOK:
+   - Note taken on [2026-01-05 Mon 09:00] by alice \\\\
+     Shipped
+     * not a heading
+   :END:
+"
+        );
+    }
+}
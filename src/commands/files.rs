@@ -0,0 +1,118 @@
+use crate::commands::project;
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::{FileHotspot, Issue, ListFilter};
+use crate::normalize;
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+
+/// `itr files` — aggregate the `files` arrays of matching issues into an
+/// issues-per-path report, busiest paths first. Defaults to active issues
+/// (open + in-progress); `--status`/`--kind` narrow it the same way they
+/// narrow `list`, including synonym normalization.
+pub fn run(
+    conn: &Connection,
+    statuses: &[String],
+    kinds: &[String],
+    limit: Option<usize>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let (statuses, status_notes) = normalize::normalize_status_filters(statuses);
+    let (kinds, kind_notes) = normalize::normalize_kind_filters(kinds);
+    for note in status_notes.iter().chain(&kind_notes) {
+        eprintln!("{}", note);
+    }
+
+    let issues = db::list_issues(
+        conn,
+        &ListFilter {
+            statuses,
+            kinds,
+            include_blocked: true,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    let mut hotspots = aggregate(&issues);
+    if let Some(n) = limit {
+        hotspots.truncate(n);
+    }
+
+    if hotspots.is_empty() {
+        error::print_empty(fmt.is_json(), "No files referenced by matching issues.");
+        return Ok(());
+    }
+    format::print_output(&format::format_file_hotspots(&hotspots, fmt), fmt);
+    Ok(())
+}
+
+/// Count issues per path, sorted by count descending then path ascending.
+/// A path listed twice on one issue counts that issue once.
+fn aggregate(issues: &[Issue]) -> Vec<FileHotspot> {
+    let mut by_path: BTreeMap<&str, Vec<i64>> = BTreeMap::new();
+    for issue in issues {
+        for path in &issue.files {
+            let ids = by_path.entry(path.as_str()).or_default();
+            if ids.last() != Some(&issue.id) {
+                ids.push(issue.id);
+            }
+        }
+    }
+    let mut hotspots: Vec<FileHotspot> = by_path
+        .into_iter()
+        .map(|(path, issue_ids)| FileHotspot {
+            path: path.to_string(),
+            count: issue_ids.len() as i64,
+            issue_ids,
+        })
+        .collect();
+    // Stable sort keeps the BTreeMap's path order among equal counts.
+    hotspots.sort_by(|a, b| b.count.cmp(&a.count));
+    hotspots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(conn: &Connection, title: &str, kind: &str, files: &[&str]) -> Issue {
+        let files: Vec<String> = files.iter().map(|f| (*f).to_string()).collect();
+        db::insert_issue(
+            conn,
+            title,
+            "medium",
+            kind,
+            "",
+            &files,
+            &[],
+            &[],
+            "",
+            None,
+            "",
+        )
+        .expect("insert issue")
+    }
+
+    #[test]
+    fn aggregate_sorts_by_count_then_path_and_dedups_per_issue() {
+        let conn = db::open_test_db();
+        let a = add(
+            &conn,
+            "a",
+            "task",
+            &["src/db.rs", "src/main.rs", "src/db.rs"],
+        );
+        let b = add(&conn, "b", "bug", &["src/db.rs", "src/cli.rs"]);
+        let hotspots = aggregate(&[a.clone(), b.clone()]);
+        let summary: Vec<(&str, i64)> = hotspots
+            .iter()
+            .map(|h| (h.path.as_str(), h.count))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("src/db.rs", 2), ("src/cli.rs", 1), ("src/main.rs", 1)]
+        );
+        assert_eq!(hotspots[0].issue_ids, vec![a.id, b.id]);
+    }
+}
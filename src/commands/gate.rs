@@ -0,0 +1,222 @@
+use crate::commands::project;
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::models::{Issue, ListFilter};
+use rusqlite::Connection;
+use std::process::Command;
+
+/// Code on stderr when the gate fails (JSON mode), like `LINT_FINDINGS`.
+const GATE_CODE: &str = "GATE_FAILED";
+
+/// `itr gate [--max-critical N] [--touching-staged]` — fail (exit 1, code
+/// `GATE_FAILED`) when more than `max_critical` open or in-progress critical
+/// issues exist, for pre-commit hooks and CI. With `touching_staged`, only
+/// critical issues listing a file in the staged changes count.
+pub fn run(
+    conn: &Connection,
+    max_critical: usize,
+    touching_staged: bool,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let critical = db::list_issues(
+        conn,
+        &ListFilter {
+            priorities: vec!["critical".to_string()],
+            include_blocked: true,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    let staged = if touching_staged {
+        Some(staged_files()?)
+    } else {
+        None
+    };
+    let counted: Vec<(&Issue, Vec<&str>)> = critical
+        .iter()
+        .filter_map(|issue| match &staged {
+            Some(staged) => {
+                let hits = touching(issue, staged);
+                (!hits.is_empty()).then_some((issue, hits))
+            }
+            None => Some((issue, Vec::new())),
+        })
+        .collect();
+    let passed = counted.len() <= max_critical;
+
+    match fmt {
+        Format::Json => {
+            let issues: Vec<serde_json::Value> = counted
+                .iter()
+                .map(|(issue, hits)| {
+                    serde_json::json!({ "id": issue.id, "title": issue.title, "files": hits })
+                })
+                .collect();
+            let mut out = serde_json::json!({
+                "passed": passed,
+                "critical": counted.len(),
+                "max_critical": max_critical,
+                "issues": issues,
+            });
+            if let Some(staged) = &staged {
+                out["staged"] = serde_json::json!(staged.len());
+            }
+            format::println_json(&out.to_string());
+        }
+        _ if format::gha(fmt) => {
+            let level = if passed { "warning" } else { "error" };
+            for (issue, hits) in &counted {
+                let file = hits
+                    .first()
+                    .copied()
+                    .or_else(|| issue.files.first().map(String::as_str));
+                println!(
+                    "{}",
+                    format::gha_command(
+                        level,
+                        file,
+                        "itr gate",
+                        &format!("Critical issue #{} is open: {}", issue.id, issue.title)
+                    )
+                );
+            }
+            if passed {
+                println!(
+                    "{}",
+                    format::gha_command(
+                        "notice",
+                        None,
+                        "itr gate",
+                        &format!("pass ({} critical, max {})", counted.len(), max_critical)
+                    )
+                );
+            }
+        }
+        _ => {
+            for (issue, hits) in &counted {
+                if hits.is_empty() {
+                    println!(
+                        "CRITICAL:{} \"{}\"",
+                        issue.id,
+                        format::escape_quoted_value(&issue.title)
+                    );
+                } else {
+                    println!(
+                        "CRITICAL:{} FILES:{} \"{}\"",
+                        issue.id,
+                        format::escape_line_value(&hits.join(",")),
+                        format::escape_quoted_value(&issue.title)
+                    );
+                }
+            }
+            println!(
+                "GATE: {} ({} critical, max {})",
+                if passed { "pass" } else { "fail" },
+                counted.len(),
+                max_critical
+            );
+        }
+    }
+
+    if !passed {
+        let msg = format!(
+            "gate failed: {} open critical issue(s){}, max {}",
+            counted.len(),
+            if touching_staged {
+                " touching staged files"
+            } else {
+                ""
+            },
+            max_critical
+        );
+        if fmt.is_json() {
+            eprintln!(
+                "{}",
+                format::error_envelope(serde_json::json!({ "error": msg, "code": GATE_CODE }))
+            );
+        } else {
+            eprintln!("ERROR: {}", msg);
+        }
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Paths in the index that differ from `HEAD`, relative to the top of the
+/// work tree (the form `itr scan` records).
+fn staged_files() -> Result<Vec<String>, ItrError> {
+    let output = Command::new("git")
+        .args(["diff", "--cached", "--name-only", "-z"])
+        .output()?;
+    if !output.status.success() {
+        return Err(ItrError::InvalidValue {
+            field: "touching-staged".to_string(),
+            value: "true".to_string(),
+            valid: format!(
+                "only inside a git repository (git: {})",
+                String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+            ),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// The issue's `files` entries matching a staged path, either exactly or
+/// as a directory containing it.
+fn touching<'a>(issue: &'a Issue, staged: &[String]) -> Vec<&'a str> {
+    issue
+        .files
+        .iter()
+        .map(String::as_str)
+        .filter(|file| {
+            let dir = file.trim_end_matches('/');
+            staged.iter().any(|path| {
+                path == file
+                    || path
+                        .strip_prefix(dir)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn touching_matches_exact_paths_and_directories() {
+        let conn = db::open_test_db();
+        let files = vec![
+            "src/a.rs".to_string(),
+            "docs/".to_string(),
+            "src/ab".to_string(),
+        ];
+        let issue = db::insert_issue(
+            &conn,
+            "Crash",
+            "critical",
+            "bug",
+            "",
+            &files,
+            &[],
+            &[],
+            "",
+            None,
+            "",
+        )
+        .unwrap();
+        let staged = vec!["src/a.rs".to_string(), "docs/guide.md".to_string()];
+        assert_eq!(touching(&issue, &staged), vec!["src/a.rs", "docs/"]);
+        assert!(touching(&issue, &["src/abc.rs".to_string()]).is_empty());
+    }
+}
@@ -1,47 +1,83 @@
-use super::build_issue_summary;
+use super::build_subtree_summaries;
 use crate::db;
 use crate::error::{self, ItrError};
 use crate::format::{self, Format};
-use crate::models::{IssueDetail, IssueSummary, ListFilter};
+use crate::models::{BlockerRef, IssueDetail};
 use crate::urgency::{self, UrgencyConfig};
 use crate::util;
 use rusqlite::Connection;
 
+/// How much of each issue `get` returns beyond the defaults.
+#[derive(Debug, Default)]
+pub struct GetOptions {
+    /// Leave every note out.
+    pub no_notes: bool,
+    /// Keep only the newest N notes.
+    pub notes_last: Option<usize>,
+    /// Inline each blocker's title and status.
+    pub with_blockers: bool,
+}
+
 /// Fetch the full [`IssueDetail`] for one issue: urgency breakdown, blockers,
-/// notes, relations, and (for epics) child summaries. This is the single
-/// source of detail used by both the single-ID and batched paths so the two
-/// can never drift.
-fn fetch_detail(conn: &Connection, id: i64) -> Result<IssueDetail, ItrError> {
+/// notes, relations, and (for epics) the full descendant subtree with its
+/// progress rollup. This is the single source of detail used by both the
+/// single-ID and batched paths so the two can never drift. `opts` trims the
+/// notes and inlines blockers.
+fn fetch_detail(
+    conn: &Connection,
+    id: i64,
+    config: &UrgencyConfig,
+    opts: &GetOptions,
+) -> Result<IssueDetail, ItrError> {
     let issue = db::get_issue(conn, id)?;
-    let config = UrgencyConfig::load(conn);
-    let (urg, breakdown) = urgency::compute_urgency_with_breakdown(&issue, &config, conn);
+    let (urg, breakdown) = urgency::compute_urgency_with_breakdown(&issue, config, conn);
     let blocked_by = db::get_blockers(conn, issue.id)?;
     let blocks = db::get_blocking(conn, issue.id)?;
     let is_blocked = db::is_blocked(conn, issue.id)?;
-    let notes = db::get_notes(conn, issue.id)?;
-
-    // If epic, get children
-    let children = if issue.kind == "epic" {
-        let child_issues = db::list_issues(
-            conn,
-            &ListFilter {
-                include_blocked: true,
-                parent_id: Some(issue.id),
-                all: true,
-                ..ListFilter::default()
-            },
-        )?;
-        let child_summaries: Vec<IssueSummary> = child_issues
+    let mut notes = db::get_notes(conn, issue.id)?;
+    let keep = if opts.no_notes {
+        Some(0)
+    } else {
+        opts.notes_last
+    };
+    let notes_omitted = keep.map(|keep| {
+        let omitted = notes.len().saturating_sub(keep);
+        notes.drain(..omitted);
+        omitted
+    });
+    let blocked_by_issues = if opts.with_blockers {
+        blocked_by
             .iter()
-            .map(|i| build_issue_summary(conn, i, &config))
-            .collect();
-        if child_summaries.is_empty() {
-            None
-        } else {
-            Some(child_summaries)
-        }
+            .map(|&bid| {
+                let blocker = db::get_issue(conn, bid)?;
+                Ok(BlockerRef {
+                    id: bid,
+                    title: blocker.title,
+                    status: blocker.status,
+                })
+            })
+            .collect::<Result<Vec<_>, ItrError>>()?
     } else {
-        None
+        vec![]
+    };
+    let referenced_by = db::referenced_by(conn, id)?;
+    let linked: Vec<i64> = blocked_by
+        .iter()
+        .chain(&blocks)
+        .chain(&referenced_by)
+        .copied()
+        .collect();
+    let qualified_ids = db::cross_project_ids(conn, &issue.project, &linked)?;
+    let key = db::issue_key(conn, issue.id)?;
+
+    // If epic, get the whole subtree (nested epics included) and its rollup
+    let (children, progress) = if issue.kind == "epic" {
+        (
+            build_subtree_summaries(conn, issue.id, config)?,
+            Some(db::subtree_progress(conn, issue.id)?),
+        )
+    } else {
+        (None, None)
     };
 
     Ok(IssueDetail {
@@ -53,7 +89,16 @@ fn fetch_detail(conn: &Connection, id: i64) -> Result<IssueDetail, ItrError> {
         notes,
         urgency_breakdown: Some(breakdown),
         children,
+        progress,
         relations: db::get_relations(conn, id)?,
+        qualified_ids,
+        notes_omitted,
+        blocked_by_issues,
+        key,
+        commits: db::commit_count(conn, id)?,
+        external_blockers: db::external_blockers(conn, id)?,
+        repro: db::issue_repro(conn, id)?,
+        referenced_by,
     })
 }
 
@@ -61,14 +106,20 @@ fn fetch_detail(conn: &Connection, id: i64) -> Result<IssueDetail, ItrError> {
 /// they are collected into the returned `missing` list (soft fallback, #136)
 /// while every other error still propagates. Order of `details` follows the
 /// request order of `ids`.
+///
+/// The whole batch is read in one transaction with the urgency config loaded
+/// once, so every detail reflects the same moment even while agents write.
 fn collect_details(
     conn: &Connection,
     ids: &[i64],
+    opts: &GetOptions,
 ) -> Result<(Vec<IssueDetail>, Vec<i64>), ItrError> {
+    let tx = conn.unchecked_transaction()?;
+    let config = UrgencyConfig::load(&tx);
     let mut details = Vec::with_capacity(ids.len());
     let mut missing = Vec::new();
     for &id in ids {
-        match fetch_detail(conn, id) {
+        match fetch_detail(&tx, id, &config, opts) {
             Ok(detail) => details.push(detail),
             Err(ItrError::NotFound(_)) => missing.push(id),
             Err(err) => return Err(err),
@@ -77,8 +128,25 @@ fn collect_details(
     Ok((details, missing))
 }
 
+/// Swap each argument that isn't ID-shaped for the ID of the issue it names
+/// (slug or title prefix, see [`db::find_issue_by_name`]). Unmatched names are
+/// left alone so the parser skips them with its usual REVIEW note; an
+/// ambiguous name is a hard `AMBIGUOUS` error.
+fn resolve_names(conn: &Connection, id_args: &[String]) -> Result<Vec<String>, ItrError> {
+    id_args
+        .iter()
+        .map(|arg| {
+            if util::is_id_token(arg) {
+                return Ok(arg.clone());
+            }
+            Ok(db::find_issue_by_name(conn, arg)?.map_or_else(|| arg.clone(), |id| id.to_string()))
+        })
+        .collect()
+}
+
 /// `itr get <ID>...` / `itr show <ID>...` — one or more issue IDs, repeated,
-/// comma-separated, or inclusive `A-B` ranges (#136).
+/// comma-separated, or inclusive `A-B` ranges (#136), issue keys (`APP-42`),
+/// or issue names (a slug like `fix-login` or an unambiguous title prefix).
 ///
 /// - Exactly one unique ID: byte-identical to the historical single-issue
 ///   contract, including the hard `NOT_FOUND` error for a missing issue.
@@ -89,14 +157,20 @@ fn collect_details(
 /// - Duplicate IDs are fetched once; unparseable tokens are skipped — both
 ///   with `REVIEW:` notes. A request with no parseable ID at all is a hard
 ///   `INVALID_VALUE`.
-pub fn run(conn: &Connection, id_args: &[String], fmt: Format) -> Result<(), ItrError> {
-    let parsed = util::parse_id_tokens(id_args);
+pub fn run(
+    conn: &Connection,
+    id_args: &[String],
+    opts: &GetOptions,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let id_args = db::resolve_id_tokens(conn, id_args)?;
+    let parsed = util::parse_id_tokens(&resolve_names(conn, &id_args)?);
     for note in &parsed.notes {
         eprintln!("{}", note);
     }
     for token in &parsed.invalid {
         eprintln!(
-            "REVIEW: ignoring non-integer issue ID '{}' — IDs may be repeated, comma-separated, or ranges (e.g. `itr get 1,2,5-8`)",
+            "REVIEW: ignoring '{}': not an issue ID or key, and no issue title matches it — IDs may be repeated, comma-separated, or ranges (e.g. `itr get 1,2,5-8`)",
             token
         );
     }
@@ -112,19 +186,19 @@ pub fn run(conn: &Connection, id_args: &[String], fmt: Format) -> Result<(), Itr
             field: "id".to_string(),
             value: id_args.join(","),
             valid:
-                "integer issue IDs, repeated, comma-separated, or ranges (e.g. `itr get 1,2,5-8`)"
+                "integer issue IDs, repeated, comma-separated, or ranges (e.g. `itr get 1,2,5-8`), or an issue key, slug, or title prefix"
                     .to_string(),
         });
     }
 
     if parsed.ids.len() == 1 {
         // Single-ID contract: unchanged bytes, hard NOT_FOUND on a missing issue.
-        let detail = fetch_detail(conn, parsed.ids[0])?;
-        println!("{}", format::format_issue_detail(&detail, fmt));
+        let detail = fetch_detail(conn, parsed.ids[0], &UrgencyConfig::load(conn), opts)?;
+        format::print_output(&format::format_issue_detail(&detail, fmt), fmt);
         return Ok(());
     }
 
-    let (details, missing) = collect_details(conn, &parsed.ids)?;
+    let (details, missing) = collect_details(conn, &parsed.ids, opts)?;
     for id in &missing {
         eprintln!("REVIEW: issue {} not found; skipped in batched get", id);
     }
@@ -132,7 +206,7 @@ pub fn run(conn: &Connection, id_args: &[String], fmt: Format) -> Result<(), Itr
         error::print_empty(fmt.is_json(), "No matching issues found.");
         return Ok(());
     }
-    println!("{}", format::format_issue_details(&details, fmt));
+    format::print_output(&format::format_issue_details(&details, fmt), fmt);
     Ok(())
 }
 
@@ -172,8 +246,8 @@ mod tests {
         let a = seed(&conn, "first");
         let b = seed(&conn, "second");
 
-        let (details, missing) =
-            collect_details(&conn, &[b, 999, a]).expect("batched fetch succeeds");
+        let (details, missing) = collect_details(&conn, &[b, 999, a], &GetOptions::default())
+            .expect("batched fetch succeeds");
         assert_eq!(
             details.iter().map(|d| d.issue.id).collect::<Vec<_>>(),
             vec![b, a],
@@ -186,11 +260,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn options_trim_notes_and_inline_blockers() {
+        let conn = db::open_test_db();
+        let blocker = seed(&conn, "blocker");
+        let id = seed(&conn, "blocked");
+        db::add_dependency(&conn, blocker, id).unwrap();
+        for text in ["one", "two", "three"] {
+            db::add_note(&conn, id, text, "").unwrap();
+        }
+        let config = UrgencyConfig::load(&conn);
+
+        let plain = fetch_detail(&conn, id, &config, &GetOptions::default()).unwrap();
+        assert_eq!((plain.notes.len(), plain.notes_omitted), (3, None));
+        assert!(plain.blocked_by_issues.is_empty());
+
+        let opts = GetOptions {
+            notes_last: Some(2),
+            with_blockers: true,
+            ..GetOptions::default()
+        };
+        let trimmed = fetch_detail(&conn, id, &config, &opts).unwrap();
+        let notes: Vec<&str> = trimmed.notes.iter().map(|n| n.content.as_str()).collect();
+        assert_eq!(notes, ["two", "three"]);
+        assert_eq!(trimmed.notes_omitted, Some(1));
+        assert_eq!(trimmed.blocked_by_issues[0].title, "blocker");
+
+        let opts = GetOptions {
+            no_notes: true,
+            ..GetOptions::default()
+        };
+        let bare = fetch_detail(&conn, id, &config, &opts).unwrap();
+        assert_eq!((bare.notes.len(), bare.notes_omitted), (0, Some(3)));
+    }
+
     #[test]
     fn collect_details_all_missing_is_empty_not_error() {
         let conn = db::open_test_db();
         seed(&conn, "only");
-        let (details, missing) = collect_details(&conn, &[998, 999]).expect("soft fallback");
+        let (details, missing) =
+            collect_details(&conn, &[998, 999], &GetOptions::default()).expect("soft fallback");
         assert!(details.is_empty());
         assert_eq!(missing, vec![998, 999]);
     }
@@ -199,14 +308,26 @@ mod tests {
     fn run_single_missing_id_stays_a_hard_not_found() {
         // Single-ID compatibility: `itr get 999` must still hard-error.
         let conn = db::open_test_db();
-        let err = run(&conn, &args(&["999"]), Format::Compact).unwrap_err();
+        let err = run(
+            &conn,
+            &args(&["999"]),
+            &GetOptions::default(),
+            Format::Compact,
+        )
+        .unwrap_err();
         assert!(matches!(err, ItrError::NotFound(999)));
     }
 
     #[test]
     fn run_with_no_parseable_ids_is_invalid_value() {
         let conn = db::open_test_db();
-        let err = run(&conn, &args(&["abc,def"]), Format::Compact).unwrap_err();
+        let err = run(
+            &conn,
+            &args(&["abc,def"]),
+            &GetOptions::default(),
+            Format::Compact,
+        )
+        .unwrap_err();
         assert!(matches!(err, ItrError::InvalidValue { .. }));
     }
 }
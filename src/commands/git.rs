@@ -0,0 +1,601 @@
+use super::close::close_issue;
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::is_terminal;
+use rusqlite::Connection;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// First-line marker identifying hooks written by `itr git install-hooks`.
+/// Hooks carrying it are overwritten freely on reinstall; anything else is
+/// treated as user-owned and left alone unless `--force` is given.
+const HOOK_MARKER: &str = "# itr-managed hook";
+
+/// Shell snippet resolving the active issue: `ITR_ISSUE` if set, else the most
+/// recently updated in-progress issue (assigned to `ITR_AGENT` when set).
+const ACTIVE_ISSUE_SH: &str = r#"id="${ITR_ISSUE:-$(itr list -s in-progress ${ITR_AGENT:+--assigned-to "$ITR_AGENT"} --sort updated -n 1 -f oneline --fields id 2>/dev/null)}""#;
+
+/// Hook scripts as `(file name, body)`. Every hook is a silent no-op when
+/// `itr` is not on PATH or no issue is active, so installing them never
+/// blocks a commit.
+fn hook_scripts() -> [(&'static str, String); 3] {
+    [
+        (
+            "prepare-commit-msg",
+            format!(
+                r#"#!/bin/sh
+{HOOK_MARKER} (installed by `itr git install-hooks`)
+# Appends the active issue as an `Itr-Issue: #N` trailer before the editor opens.
+case "$2" in merge|squash) exit 0 ;; esac
+command -v itr >/dev/null 2>&1 || exit 0
+grep -q '^Itr-Issue:' "$1" && exit 0
+{ACTIVE_ISSUE_SH}
+[ -n "$id" ] || exit 0
+git interpret-trailers --in-place --trailer "Itr-Issue: #$id" "$1"
+"#
+            ),
+        ),
+        (
+            "commit-msg",
+            format!(
+                r#"#!/bin/sh
+{HOOK_MARKER} (installed by `itr git install-hooks`)
+# Warns (never blocks) when the Itr-Issue trailer names an unknown issue.
+command -v itr >/dev/null 2>&1 || exit 0
+id=$(sed -n 's/^Itr-Issue: *#\{{0,1\}}\([0-9][0-9]*\).*/\1/p' "$1" | head -n 1)
+[ -n "$id" ] || exit 0
+itr get "$id" >/dev/null 2>&1 || echo "REVIEW: Itr-Issue #$id does not exist in this tracker" >&2
+exit 0
+"#
+            ),
+        ),
+        (
+            "post-commit",
+            format!(
+                r#"#!/bin/sh
+{HOOK_MARKER} (installed by `itr git install-hooks`)
+# Links the new commit to the issue named by its Itr-Issue trailer and notes it there.
+command -v itr >/dev/null 2>&1 || exit 0
+id=$(git log -1 --format='%(trailers:key=Itr-Issue,valueonly)' | sed -n 's/^#\{{0,1\}}\([0-9][0-9]*\).*/\1/p' | head -n 1)
+[ -n "$id" ] || exit 0
+itr git link "$id" HEAD >/dev/null 2>&1
+itr note "$id" "commit $(git rev-parse --short HEAD): $(git log -1 --format=%s)" >/dev/null 2>&1
+exit 0
+"#
+            ),
+        ),
+    ]
+}
+
+/// `itr git install-hooks [--force]` — write the itr commit hooks into the
+/// repository's hooks directory (honoring `core.hooksPath` and worktrees).
+pub fn install_hooks(force: bool, fmt: Format) -> Result<(), ItrError> {
+    let dir = hooks_dir()?;
+    let (installed, skipped) = write_hooks(&dir, force)?;
+    for path in &skipped {
+        eprintln!(
+            "REVIEW: {} exists and was not installed by itr. Re-run with --force to overwrite.",
+            path.display()
+        );
+    }
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "hooks_dir": dir.display().to_string(),
+                "installed": installed.iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
+                "skipped": skipped.iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
+            });
+            format::print_json(&out.to_string());
+        }
+        _ => {
+            for path in &installed {
+                println!("Installed git hook → {}", path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write each hook into `dir`, returning `(installed, skipped)` paths. An
+/// existing hook is replaced only if it carries [`HOOK_MARKER`] or `force`
+/// is set.
+fn write_hooks(dir: &Path, force: bool) -> Result<(Vec<PathBuf>, Vec<PathBuf>), ItrError> {
+    fs::create_dir_all(dir)?;
+    let mut installed = Vec::new();
+    let mut skipped = Vec::new();
+    for (name, body) in hook_scripts() {
+        let path = dir.join(name);
+        if path.exists() && !force {
+            let existing = fs::read_to_string(&path).unwrap_or_default();
+            if !existing.contains(HOOK_MARKER) {
+                skipped.push(path);
+                continue;
+            }
+        }
+        fs::write(&path, body)?;
+        make_executable(&path)?;
+        installed.push(path);
+    }
+    Ok((installed, skipped))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), ItrError> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::unnecessary_wraps)]
+fn make_executable(_path: &Path) -> Result<(), ItrError> {
+    Ok(())
+}
+
+/// Resolve the hooks directory via `git rev-parse --git-path hooks`, which
+/// accounts for `core.hooksPath` and linked worktrees.
+fn hooks_dir() -> Result<PathBuf, ItrError> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()?;
+    if !output.status.success() {
+        return Err(ItrError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Not inside a git repository (git rev-parse failed)",
+        )));
+    }
+    let rel = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(env::current_dir()?.join(rel))
+}
+
+/// Commits inspected by `itr git scan` when no `--range` is given.
+const DEFAULT_SCAN_DEPTH: usize = 20;
+
+/// Closing keywords recognized before `#N` references (GitHub-style).
+const CLOSE_KEYWORDS: &[&str] = &[
+    "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+];
+
+/// One commit as read from `git log`.
+struct CommitInfo {
+    hash: String,
+    short_hash: String,
+    message: String,
+}
+
+impl CommitInfo {
+    fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+}
+
+/// An issue closed (or skipped) by `itr git scan`.
+struct ScanClose {
+    id: i64,
+    title: String,
+    commit: String,
+    unblocked: Vec<(i64, String)>,
+}
+
+/// `itr git link <ID> [REV]` — link commit `rev` (default `HEAD`) to issue
+/// `id`, as the post-commit hook does for the `Itr-Issue` trailer. Linking a
+/// commit twice is a no-op.
+pub fn link(conn: &Connection, id: i64, rev: Option<&str>, fmt: Format) -> Result<(), ItrError> {
+    let rev = rev.unwrap_or("HEAD");
+    let output = Command::new("git")
+        .args(["log", "-1", "--format=%H%x1f%h%x1f%s", rev, "--"])
+        .output()?;
+    let record = String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string();
+    let mut fields = record.splitn(3, '\x1f');
+    let (Some(hash), Some(short_hash), Some(subject)) =
+        (fields.next(), fields.next(), fields.next())
+    else {
+        return Err(ItrError::InvalidValue {
+            field: "rev".to_string(),
+            value: rev.to_string(),
+            valid: format!(
+                "a commit inside a git repository (git: {})",
+                String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+            ),
+        });
+    };
+    let issue = db::get_issue(conn, id)?;
+    let linked = db::link_commit(conn, id, hash, subject, "mention")?;
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "action": "link",
+                "id": id,
+                "hash": hash,
+                "subject": subject,
+                "linked": linked,
+            });
+            format::print_json(&out.to_string());
+        }
+        _ if format::quiet(fmt) => println!("{}", id),
+        _ => println!(
+            "LINKED:{} COMMIT:{} \"{}\"",
+            issue.id,
+            short_hash,
+            format::escape_quoted_value(subject)
+        ),
+    }
+    Ok(())
+}
+
+/// `itr git scan [--range A..B]` — close issues referenced by closing
+/// keywords in commit messages (`closes #12`, `fixes #3, #4`) or an
+/// `Itr-Close: 12` trailer. The commit's short hash becomes the close reason.
+///
+/// Commits are processed oldest-first. Already-closed and missing issues are
+/// skipped with `REVIEW:` notes, so re-scanning the same range is a no-op.
+/// Every commit is linked to the issues it names, closed or not.
+pub fn scan(conn: &Connection, range: Option<&str>, fmt: Format) -> Result<(), ItrError> {
+    let commits = read_commits(range)?;
+    let (closed, notes) = close_from_commits(conn, &commits)?;
+    for note in &notes {
+        eprintln!("{}", note);
+    }
+    if closed.is_empty() {
+        error::print_empty(fmt.is_json(), "No commits closed any issues.");
+        return Ok(());
+    }
+    match fmt {
+        Format::Json => {
+            let arr: Vec<serde_json::Value> = closed
+                .iter()
+                .map(|c| {
+                    serde_json::json!({
+                        "id": c.id,
+                        "title": c.title,
+                        "commit": c.commit,
+                        "unblocked": c.unblocked.iter()
+                            .map(|(id, title)| serde_json::json!({"id": id, "title": title}))
+                            .collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            format::println_json(&serde_json::Value::Array(arr).to_string());
+        }
+        _ => {
+            let mut lines = Vec::new();
+            for c in &closed {
+                if format::quiet(fmt) {
+                    lines.push(c.id.to_string());
+                    continue;
+                }
+                lines.push(format!(
+                    "CLOSED:{} COMMIT:{} \"{}\"",
+                    c.id,
+                    c.commit,
+                    format::escape_quoted_value(&c.title)
+                ));
+                let unblocked = format::format_unblocked(&c.unblocked, fmt);
+                if !unblocked.is_empty() {
+                    lines.push(unblocked);
+                }
+            }
+            println!("{}", lines.join("\n"));
+        }
+    }
+    Ok(())
+}
+
+/// Close every issue referenced by `commits`, returning the closes plus
+/// `REVIEW:` notes for references that were skipped. Every existing issue a
+/// commit closes (already-closed ones too) or names in an `Itr-Issue:`
+/// trailer gets the commit linked, see `itr commits`.
+fn close_from_commits(
+    conn: &Connection,
+    commits: &[CommitInfo],
+) -> Result<(Vec<ScanClose>, Vec<String>), ItrError> {
+    let mut closed = Vec::new();
+    let mut notes = Vec::new();
+    for commit in commits {
+        for id in parse_issue_trailers(&commit.message) {
+            if db::issue_exists(conn, id)? {
+                db::link_commit(conn, id, &commit.hash, commit.subject(), "mention")?;
+            }
+        }
+        for id in parse_close_refs(&commit.message) {
+            let issue = match db::get_issue(conn, id) {
+                Ok(issue) => issue,
+                Err(ItrError::NotFound(_)) => {
+                    notes.push(format!(
+                        "REVIEW: commit {} references issue {}, which does not exist; skipped",
+                        commit.short_hash, id
+                    ));
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            db::link_commit(conn, id, &commit.hash, commit.subject(), "close")?;
+            if is_terminal(&issue.status) {
+                notes.push(format!(
+                    "REVIEW: issue {} (referenced by commit {}) is already {}; skipped",
+                    id, commit.short_hash, issue.status
+                ));
+                continue;
+            }
+            let reason = format!("Closed by commit {}", commit.short_hash);
+            let (detail, unblocked) = close_issue(conn, id, Some(reason), false, None)?;
+            closed.push(ScanClose {
+                id,
+                title: detail.issue.title,
+                commit: commit.short_hash.clone(),
+                unblocked,
+            });
+        }
+    }
+    Ok((closed, notes))
+}
+
+/// Issue IDs named by `Itr-Issue: #N` trailers (the prepare-commit-msg
+/// hook's), in order and without duplicates.
+fn parse_issue_trailers(message: &str) -> Vec<i64> {
+    let mut ids: Vec<i64> = Vec::new();
+    for line in message.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !key.trim().eq_ignore_ascii_case("itr-issue") {
+            continue;
+        }
+        for token in value.split(|c: char| c.is_whitespace() || c == ',') {
+            if let Ok(id) = token.trim_start_matches('#').parse::<i64>() {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+    ids
+}
+
+/// Extract issue IDs a commit message closes, in order of appearance and
+/// without duplicates. Recognizes a closing keyword followed by one or more
+/// `#N` references (`Fixes #3, #4 and #5`) and `Itr-Close: N[, N]` trailers,
+/// case-insensitively.
+fn parse_close_refs(message: &str) -> Vec<i64> {
+    let mut ids: Vec<i64> = Vec::new();
+    for line in message.lines() {
+        let lower = line.trim().to_ascii_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|w| !w.is_empty())
+            .collect();
+        // Which kind of reference list we are inside: `#N` after a keyword,
+        // or bare/hashed numbers after an `itr-close:` trailer key.
+        let mut bare_numbers_ok = false;
+        let mut in_refs = false;
+        for (i, word) in words.iter().enumerate() {
+            let stripped = word.trim_end_matches([':', '.', ';', ')']);
+            if i == 0 && stripped == "itr-close" && word.ends_with(':') {
+                in_refs = true;
+                bare_numbers_ok = true;
+                continue;
+            }
+            if CLOSE_KEYWORDS.contains(&stripped) {
+                in_refs = true;
+                bare_numbers_ok = false;
+                continue;
+            }
+            if !in_refs {
+                continue;
+            }
+            let number = match stripped.strip_prefix('#') {
+                Some(rest) => rest,
+                None if bare_numbers_ok => stripped,
+                None => {
+                    if stripped != "and" {
+                        in_refs = false;
+                    }
+                    continue;
+                }
+            };
+            match number.parse::<i64>() {
+                Ok(id) if id > 0 => {
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+                _ => in_refs = false,
+            }
+        }
+    }
+    ids
+}
+
+/// Read commits oldest-first: the given revision range, or the last
+/// [`DEFAULT_SCAN_DEPTH`] commits reachable from `HEAD`.
+fn read_commits(range: Option<&str>) -> Result<Vec<CommitInfo>, ItrError> {
+    let mut cmd = Command::new("git");
+    cmd.args(["log", "--reverse", "--format=%H%x1f%h%x1f%B%x1e"]);
+    match range {
+        Some(r) => {
+            cmd.arg(r);
+        }
+        None => {
+            cmd.arg(format!("-n{}", DEFAULT_SCAN_DEPTH));
+        }
+    }
+    let output = cmd.output()?;
+    if !output.status.success() {
+        return Err(ItrError::InvalidValue {
+            field: "range".to_string(),
+            value: range.unwrap_or("HEAD").to_string(),
+            valid: format!(
+                "a git revision range inside a git repository (git: {})",
+                String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+            ),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\x1f');
+            Some(CommitInfo {
+                hash: fields.next()?.to_string(),
+                short_hash: fields.next()?.to_string(),
+                message: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_hooks_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("itr-git-unit-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn write_hooks_installs_all_three_with_marker() {
+        let dir = temp_hooks_dir("fresh");
+        let (installed, skipped) = write_hooks(&dir, false).unwrap();
+        assert_eq!(installed.len(), 3);
+        assert!(skipped.is_empty());
+        for path in &installed {
+            let body = fs::read_to_string(path).unwrap();
+            assert!(body.starts_with("#!/bin/sh\n"));
+            assert!(body.contains(HOOK_MARKER));
+        }
+        // Reinstalling over our own hooks needs no --force.
+        let (installed, skipped) = write_hooks(&dir, false).unwrap();
+        assert_eq!((installed.len(), skipped.len()), (3, 0));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn write_hooks_keeps_foreign_hook_unless_forced() {
+        let dir = temp_hooks_dir("foreign");
+        fs::create_dir_all(&dir).unwrap();
+        let foreign = dir.join("post-commit");
+        fs::write(&foreign, "#!/bin/sh\necho mine\n").unwrap();
+
+        let (installed, skipped) = write_hooks(&dir, false).unwrap();
+        assert_eq!(installed.len(), 2);
+        assert_eq!(skipped, vec![foreign.clone()]);
+        assert_eq!(
+            fs::read_to_string(&foreign).unwrap(),
+            "#!/bin/sh\necho mine\n"
+        );
+
+        let (installed, skipped) = write_hooks(&dir, true).unwrap();
+        assert_eq!((installed.len(), skipped.len()), (3, 0));
+        assert!(fs::read_to_string(&foreign).unwrap().contains(HOOK_MARKER));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parse_close_refs_recognizes_keywords_lists_and_trailers() {
+        assert_eq!(
+            parse_close_refs("Fix login timeout\n\ncloses #12"),
+            vec![12]
+        );
+        assert_eq!(parse_close_refs("Fixes #3, #4 and #5."), vec![3, 4, 5]);
+        assert_eq!(parse_close_refs("RESOLVED: #7"), vec![7]);
+        assert_eq!(parse_close_refs("subject\n\nItr-Close: 8, 9"), vec![8, 9]);
+        assert_eq!(
+            parse_close_refs("closes #2\nfixes #2"),
+            vec![2],
+            "deduplicated"
+        );
+    }
+
+    #[test]
+    fn parse_close_refs_ignores_bare_mentions() {
+        assert!(parse_close_refs("Refs #12, see #13").is_empty());
+        assert!(parse_close_refs("fix the #12 regression").is_empty());
+        assert!(parse_close_refs("closes 12").is_empty(), "keywords need #N");
+        assert!(parse_close_refs("mentions itr-close: 4 mid-line").is_empty());
+    }
+
+    #[test]
+    fn close_from_commits_closes_skips_and_reports_unblocked() {
+        let conn = db::open_test_db();
+        let add = |title: &str| {
+            db::insert_issue(
+                &conn,
+                title,
+                "medium",
+                "task",
+                "",
+                &[],
+                &[],
+                &[],
+                "",
+                None,
+                "",
+            )
+            .unwrap()
+            .id
+        };
+        let blocker = add("blocker");
+        let waiting = add("waiting");
+        db::add_dependency(&conn, blocker, waiting).unwrap();
+        let commits = vec![
+            CommitInfo {
+                hash: "abc1234aaaa".to_string(),
+                short_hash: "abc1234".to_string(),
+                message: format!("fix it\n\ncloses #{blocker}, #999"),
+            },
+            CommitInfo {
+                hash: "def5678bbbb".to_string(),
+                short_hash: "def5678".to_string(),
+                message: format!("again\n\nItr-Close: {blocker}\nItr-Issue: #{waiting}"),
+            },
+        ];
+
+        let (closed, notes) = close_from_commits(&conn, &commits).unwrap();
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].id, blocker);
+        assert_eq!(closed[0].unblocked, vec![(waiting, "waiting".to_string())]);
+        let issue = db::get_issue(&conn, blocker).unwrap();
+        assert_eq!(issue.status, "done");
+        assert_eq!(issue.close_reason, "Closed by commit abc1234");
+        assert_eq!(
+            notes.len(),
+            2,
+            "missing issue and already-closed rescan: {notes:?}"
+        );
+        let linked = db::issue_commits(&conn, blocker).unwrap();
+        assert_eq!(linked.len(), 2);
+        assert!(linked.iter().all(|c| c.relation == "close"));
+        let mentioned = db::issue_commits(&conn, waiting).unwrap();
+        assert_eq!(mentioned.len(), 1);
+        assert_eq!(
+            (
+                mentioned[0].relation.as_str(),
+                mentioned[0].subject.as_str()
+            ),
+            ("mention", "again")
+        );
+    }
+
+    #[test]
+    fn parse_issue_trailers_reads_itr_issue_lines_only() {
+        assert_eq!(
+            parse_issue_trailers("subject\n\nItr-Issue: #4, 5\nitr-issue: 4"),
+            vec![4, 5]
+        );
+        assert!(parse_issue_trailers("see issue: 4\ncloses #4").is_empty());
+    }
+}
@@ -1,74 +1,58 @@
+use crate::commands::project;
 use crate::db;
 use crate::error::ItrError;
 use crate::format::{self, Format};
-use crate::models::{GraphEdge, GraphNode, GraphOutput, ListFilter};
-use crate::urgency::{self, UrgencyConfig};
+use crate::store;
 use rusqlite::Connection;
 
-pub fn run(conn: &Connection, all: bool, fmt: Format) -> Result<(), ItrError> {
-    let issues = if all {
-        db::all_issues(conn)?
-    } else {
-        db::list_issues(
-            conn,
-            &ListFilter {
-                include_blocked: true,
-                ..ListFilter::default()
-            },
-        )?
-    };
-
-    let config = UrgencyConfig::load(conn);
-    let deps = db::all_dependencies(conn)?;
-
-    let issue_ids: std::collections::HashSet<i64> = issues.iter().map(|i| i.id).collect();
-
-    let nodes: Vec<GraphNode> = issues
-        .iter()
-        .map(|i| {
-            let urg = urgency::compute_urgency(i, &config, conn);
-            let is_blocked = db::is_blocked(conn, i.id).unwrap_or(false);
-            GraphNode {
-                id: i.id,
-                title: i.title.clone(),
-                status: i.status.clone(),
-                urgency: urg,
-                is_blocked,
-            }
-        })
-        .collect();
-
-    let mut edges: Vec<GraphEdge> = deps
-        .iter()
-        .filter(|(blocker, blocked)| issue_ids.contains(blocker) && issue_ids.contains(blocked))
-        .map(|(blocker, blocked)| GraphEdge {
-            from: *blocker,
-            to: *blocked,
-            edge_type: "blocks".to_string(),
-        })
-        .collect();
+/// How `itr graph` narrows and draws the graph, applied in field order.
+#[derive(Debug)]
+pub struct View {
+    /// Keep only the issues within `depth` hops of this one.
+    pub around: Option<i64>,
+    pub depth: usize,
+    /// Drop `blocks` edges implied by longer chains.
+    pub reduce: bool,
+    /// `Some(true)` keeps only roots, `Some(false)` only leaves.
+    pub ends: Option<bool>,
+    /// Layered text instead of NODE/EDGE lines or DOT.
+    pub ascii: bool,
+}
 
-    // Add relation edges
-    let relations = db::all_relations(conn)?;
-    for rel in &relations {
-        if issue_ids.contains(&rel.source_id) && issue_ids.contains(&rel.target_id) {
-            edges.push(GraphEdge {
-                from: rel.source_id,
-                to: rel.target_id,
-                edge_type: rel.relation_type.clone(),
-            });
+pub fn run(conn: &Connection, all: bool, view: &View, fmt: Format) -> Result<(), ItrError> {
+    let mut graph = store::dependency_graph(conn, all, project::scope())?;
+
+    if let Some(center) = view.around {
+        if !graph.nodes.iter().any(|n| n.id == center) {
+            let issue = db::get_issue(conn, center)?;
+            eprintln!(
+                "REVIEW: #{} is {}; including resolved issues (as with --all) to graph around it",
+                center, issue.status
+            );
+            graph = store::dependency_graph(conn, true, project::scope())?;
         }
+        graph = store::graph_neighborhood(graph, center, view.depth);
+    }
+    if view.reduce {
+        graph = store::graph_transitive_reduction(graph);
+    }
+    if let Some(roots) = view.ends {
+        graph = store::graph_ends(graph, roots);
     }
 
-    let graph = GraphOutput { nodes, edges };
+    if view.ascii && fmt.is_json() {
+        eprintln!("REVIEW: --ascii applies to text formats; printing JSON");
+    }
 
     // Support DOT format via pretty
-    let output = if fmt == Format::Pretty {
+    let output = if view.ascii && !fmt.is_json() {
+        format::format_graph_ascii(&graph)
+    } else if fmt == Format::Pretty {
         format::format_graph(&graph, Format::Pretty) // outputs DOT
     } else {
         format::format_graph(&graph, fmt)
     };
 
-    println!("{}", output);
+    format::print_output(&output, fmt);
     Ok(())
 }
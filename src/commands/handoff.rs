@@ -0,0 +1,126 @@
+use super::assign::print_detail;
+use crate::db;
+use crate::error::ItrError;
+use crate::format::Format;
+use crate::models::is_terminal;
+use rusqlite::Connection;
+
+/// `itr handoff <ID> --to <AGENT> <SUMMARY>` — pass an issue to another
+/// agent in one transaction: assign it to them, claim it on their behalf
+/// (`open` becomes `in-progress`, so it leads their `ready --assigned-to`),
+/// and write the summary as a `handoff` note from the agent letting go.
+///
+/// A `done`/`wontfix` issue is handed off without being reopened, with a
+/// `REVIEW:` note, as `claim` does.
+pub fn run(
+    conn: &Connection,
+    id: i64,
+    to: &str,
+    summary: &str,
+    agent: &str,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let to = to.trim();
+    if to.is_empty() {
+        return Err(ItrError::InvalidValue {
+            field: "to".to_string(),
+            value: String::new(),
+            valid: "the name of the agent taking over".to_string(),
+        });
+    }
+    if summary.trim().is_empty() {
+        return Err(ItrError::InvalidValue {
+            field: "summary".to_string(),
+            value: summary.to_string(),
+            valid: "non-empty string".to_string(),
+        });
+    }
+    let from = super::note::resolve_agent(conn, agent);
+
+    let tx = db::begin_write(conn)?;
+    let issue = db::get_issue(&tx, id)?;
+    match issue.status.as_str() {
+        "open" => {
+            db::record_event(&tx, id, "status", &issue.status, "in-progress")?;
+            db::update_issue_field(&tx, id, "status", "in-progress")?;
+        }
+        status if is_terminal(status) => eprintln!(
+            "REVIEW: issue {} is '{}' and was not reopened; run `itr update {} --status open` first if '{}' should work on it",
+            id, issue.status, id, to
+        ),
+        _ => {}
+    }
+    if issue.assigned_to != to {
+        db::record_event(&tx, id, "assigned_to", &issue.assigned_to, to)?;
+        db::update_issue_field(&tx, id, "assigned_to", to)?;
+    }
+    db::add_note_of_kind(&tx, id, summary.trim(), &from, db::NOTE_KIND_HANDOFF)?;
+    tx.commit()?;
+
+    if !from.is_empty() && from == to {
+        eprintln!(
+            "REVIEW: issue {} was handed off to its own sender '{}'",
+            id, to
+        );
+    }
+    for note in super::agent::fit_notes(conn, to, &issue)? {
+        eprintln!("{}", note);
+    }
+    print_detail(conn, id, fmt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handoff_claims_for_the_target_and_writes_a_handoff_note() {
+        let conn = db::open_test_db();
+        let id = db::insert_issue(
+            &conn,
+            "half done",
+            "medium",
+            "task",
+            "",
+            &[],
+            &[],
+            &[],
+            "",
+            None,
+            "alice",
+        )
+        .unwrap()
+        .id;
+
+        run(
+            &conn,
+            id,
+            " bob ",
+            "tests pass; docs left",
+            "alice",
+            Format::Json,
+        )
+        .unwrap();
+
+        let issue = db::get_issue(&conn, id).unwrap();
+        assert_eq!(
+            (issue.status.as_str(), issue.assigned_to.as_str()),
+            ("in-progress", "bob")
+        );
+        let notes = db::get_notes(&conn, id).unwrap();
+        let note = notes.last().unwrap();
+        assert_eq!(
+            (
+                note.kind.as_str(),
+                note.agent.as_str(),
+                note.content.as_str()
+            ),
+            (db::NOTE_KIND_HANDOFF, "alice", "tests pass; docs left")
+        );
+
+        assert!(matches!(
+            run(&conn, id, "bob", "  ", "alice", Format::Json),
+            Err(ItrError::InvalidValue { .. })
+        ));
+    }
+}
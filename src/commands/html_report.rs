@@ -0,0 +1,380 @@
+//! `itr export --export-format html`: a static site for browsing the backlog
+//! without the CLI. `index.html` holds a filterable issue table and the
+//! dependency graph as inline SVG; `issues/<id>.html` has one issue with its
+//! notes. Every page carries its own CSS and script, so the directory can be
+//! zipped, attached, or served from anywhere.
+
+use crate::error::ItrError;
+use crate::models::ExportData;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Output directory when `--out` is not given.
+pub const DEFAULT_OUT: &str = "itr-report";
+
+const STYLE: &str = "body{font:14px/1.45 system-ui,sans-serif;margin:2em auto;max-width:1100px;padding:0 1em;color:#222}
+a{color:#2557a7;text-decoration:none}a:hover{text-decoration:underline}
+table{border-collapse:collapse;width:100%}th,td{text-align:left;padding:4px 8px;border-bottom:1px solid #e3e3e3;vertical-align:top}
+th{background:#f5f5f5}.status{border-radius:3px;padding:1px 6px;font-size:12px}
+.open{background:#e4efff}.in-progress{background:#fff2cc}.review{background:#f3e5ff}.done{background:#dff5e1}.wontfix{background:#eee}
+.filters{margin:1em 0;display:flex;gap:8px}.filters input{flex:1}
+pre{white-space:pre-wrap;background:#fafafa;padding:8px;border:1px solid #eee}
+.note{border-left:3px solid #ddd;padding-left:8px;margin:8px 0}.meta{color:#777;font-size:12px}
+svg text{font-size:12px}";
+
+const FILTER_JS: &str = "const q=document.getElementById('q'),s=document.getElementById('status'),k=document.getElementById('kind');
+function apply(){const t=q.value.toLowerCase();let n=0;
+for(const r of document.querySelectorAll('#issues tbody tr')){
+const ok=(!s.value||r.dataset.status===s.value)&&(!k.value||r.dataset.kind===k.value)&&(!t||r.textContent.toLowerCase().includes(t));
+r.hidden=!ok;if(ok)n++;}
+document.getElementById('shown').textContent=n;}
+[q,s,k].forEach(e=>e.addEventListener('input',apply));";
+
+/// Dependency graph node size and spacing, in pixels.
+const W: usize = 180;
+const H: usize = 28;
+const GAP_X: usize = 60;
+const GAP_Y: usize = 14;
+
+/// Write the site for `items` into `out`, creating it if needed. Pages for
+/// issues no longer exported are left alone. Returns the index path.
+pub fn write(items: &[ExportData], out: &Path) -> Result<String, ItrError> {
+    fs::create_dir_all(out.join("issues"))?;
+    let titles: HashMap<i64, (&str, &str)> = items
+        .iter()
+        .map(|item| {
+            (
+                item.issue.id,
+                (item.issue.title.as_str(), item.issue.status.as_str()),
+            )
+        })
+        .collect();
+    for item in items {
+        fs::write(
+            out.join("issues").join(format!("{}.html", item.issue.id)),
+            issue_page(item, &titles),
+        )?;
+    }
+    let index = out.join("index.html");
+    fs::write(&index, index_page(items))?;
+    Ok(index.display().to_string())
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        esc(title),
+        STYLE,
+        body
+    )
+}
+
+fn index_page(items: &[ExportData]) -> String {
+    let mut body = String::from("<h1>itr report</h1>\n");
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for status in ["open", "in-progress", "review", "done", "wontfix"] {
+        let n = items.iter().filter(|i| i.issue.status == status).count();
+        if n > 0 {
+            counts.push((status, n));
+        }
+    }
+    let summary: Vec<String> = counts.iter().map(|(s, n)| format!("{} {}", n, s)).collect();
+    let _ = writeln!(
+        body,
+        "<p>{} issues: {}</p>",
+        items.len(),
+        esc(&summary.join(", "))
+    );
+
+    let kinds: BTreeSet<&str> = items.iter().map(|i| i.issue.kind.as_str()).collect();
+    body.push_str("<div class=\"filters\"><input id=\"q\" placeholder=\"Filter by text\">\n<select id=\"status\"><option value=\"\">any status</option>");
+    for (status, _) in &counts {
+        let _ = write!(body, "<option>{}</option>", status);
+    }
+    body.push_str("</select>\n<select id=\"kind\"><option value=\"\">any kind</option>");
+    for kind in &kinds {
+        let _ = write!(body, "<option>{}</option>", esc(kind));
+    }
+    let _ = writeln!(
+        body,
+        "</select>\n<span><span id=\"shown\">{}</span> shown</span></div>",
+        items.len()
+    );
+
+    body.push_str("<table id=\"issues\">\n<thead><tr><th>ID</th><th>Title</th><th>Status</th><th>Priority</th><th>Kind</th><th>Tags</th><th>Assignee</th><th>Blocked by</th><th>Updated</th></tr></thead>\n<tbody>\n");
+    for item in items {
+        let issue = &item.issue;
+        let blockers: Vec<String> = item.blocked_by.iter().map(|b| issue_link(*b)).collect();
+        let _ = writeln!(
+            body,
+            "<tr data-status=\"{status}\" data-kind=\"{kind}\"><td>{id}</td><td><a href=\"issues/{id}.html\">{title}</a></td><td><span class=\"status {status}\">{status}</span></td><td>{priority}</td><td>{kind}</td><td>{tags}</td><td>{assignee}</td><td>{blockers}</td><td>{updated}</td></tr>",
+            id = issue.id,
+            title = esc(&issue.title),
+            status = esc(&issue.status),
+            priority = esc(&issue.priority),
+            kind = esc(&issue.kind),
+            tags = esc(&issue.tags.join(", ")),
+            assignee = esc(&issue.assigned_to),
+            blockers = blockers.join(" "),
+            updated = esc(&issue.updated_at),
+        );
+    }
+    body.push_str("</tbody>\n</table>\n<h2>Dependencies</h2>\n");
+    body.push_str(&graph_svg(items));
+    let _ = writeln!(body, "<script>{}</script>", FILTER_JS);
+    page("itr report", &body)
+}
+
+fn issue_page(item: &ExportData, titles: &HashMap<i64, (&str, &str)>) -> String {
+    let issue = &item.issue;
+    let mut body = String::from("<p><a href=\"../index.html\">&larr; All issues</a></p>\n");
+    let _ = writeln!(body, "<h1>#{} {}</h1>", issue.id, esc(&issue.title));
+    let related = |ids: &[i64]| -> String {
+        ids.iter()
+            .map(|id| match titles.get(id) {
+                Some((title, status)) => format!(
+                    "<a href=\"{}.html\">#{} {}</a> <span class=\"status {s}\">{s}</span>",
+                    id,
+                    id,
+                    esc(title),
+                    s = esc(status)
+                ),
+                None => format!("#{}", id),
+            })
+            .collect::<Vec<_>>()
+            .join("<br>")
+    };
+    let parent: Vec<i64> = issue.parent_id.into_iter().collect();
+    let rows = [
+        (
+            "Status",
+            format!(
+                "<span class=\"status {s}\">{s}</span>",
+                s = esc(&issue.status)
+            ),
+        ),
+        ("Priority", esc(&issue.priority)),
+        ("Kind", esc(&issue.kind)),
+        ("Tags", esc(&issue.tags.join(", "))),
+        ("Assignee", esc(&issue.assigned_to)),
+        ("Project", esc(&issue.project)),
+        ("Branch", esc(&issue.branch)),
+        ("Files", esc(&issue.files.join(", "))),
+        ("Skills", esc(&issue.skills.join(", "))),
+        ("Parent", related(&parent)),
+        ("Children", related(&item.children)),
+        ("Blocked by", related(&item.blocked_by)),
+        ("Blocks", related(&item.blocks)),
+        ("Close reason", esc(&issue.close_reason)),
+        ("Created", esc(&issue.created_at)),
+        ("Updated", esc(&issue.updated_at)),
+    ];
+    body.push_str("<table>\n");
+    for (label, value) in rows.iter().filter(|(_, v)| !v.is_empty()) {
+        let _ = writeln!(body, "<tr><th>{}</th><td>{}</td></tr>", label, value);
+    }
+    body.push_str("</table>\n");
+    if !issue.context.is_empty() {
+        let _ = writeln!(body, "<h2>Context</h2>\n<pre>{}</pre>", esc(&issue.context));
+    }
+    if !issue.acceptance.is_empty() {
+        let _ = writeln!(
+            body,
+            "<h2>Acceptance</h2>\n<pre>{}</pre>",
+            esc(&issue.acceptance)
+        );
+    }
+    let _ = writeln!(body, "<h2>Notes ({})</h2>", item.notes.len());
+    for note in &item.notes {
+        let _ = writeln!(
+            body,
+            "<div class=\"note\"><div class=\"meta\">{} {}</div><pre>{}</pre></div>",
+            esc(&note.created_at),
+            esc(&note.agent),
+            esc(&note.content)
+        );
+    }
+    page(&format!("#{} {}", issue.id, issue.title), &body)
+}
+
+/// The blocker -> blocked edges between exported issues as an SVG, issues
+/// in columns by how many blockers deep they sit. Issues without edges are
+/// left out so a large, mostly independent backlog stays readable.
+fn graph_svg(items: &[ExportData]) -> String {
+    let exported: HashMap<i64, &ExportData> = items.iter().map(|i| (i.issue.id, i)).collect();
+    let edges: Vec<(i64, i64)> = items
+        .iter()
+        .flat_map(|item| {
+            item.blocked_by
+                .iter()
+                .filter(|b| exported.contains_key(b))
+                .map(move |&b| (b, item.issue.id))
+        })
+        .collect();
+    if edges.is_empty() {
+        return "<p>No dependencies between these issues.</p>\n".to_string();
+    }
+    let nodes: BTreeSet<i64> = edges.iter().flat_map(|&(a, b)| [a, b]).collect();
+
+    // Longest path from a root; capped at one pass per node so a cycle
+    // can't loop forever.
+    let mut depth: HashMap<i64, usize> = nodes.iter().map(|&n| (n, 0)).collect();
+    for _ in 0..nodes.len() {
+        let mut changed = false;
+        for &(from, to) in &edges {
+            let next = depth[&from] + 1;
+            if next > depth[&to] && next < nodes.len() {
+                depth.insert(to, next);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut rows: HashMap<usize, usize> = HashMap::new();
+    let mut pos: HashMap<i64, (usize, usize)> = HashMap::new();
+    for &id in &nodes {
+        let col = depth[&id];
+        let row = rows.entry(col).or_insert(0);
+        pos.insert(id, (col * (W + GAP_X) + 10, *row * (H + GAP_Y) + 10));
+        *row += 1;
+    }
+    let width = pos.values().map(|p| p.0).max().unwrap_or(0) + W + 10;
+    let height = pos.values().map(|p| p.1).max().unwrap_or(0) + H + 10;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\"><path d=\"M0,0 L10,5 L0,10 z\" fill=\"#888\"/></marker></defs>\n",
+        w = width,
+        h = height
+    );
+    for &(from, to) in &edges {
+        let (fx, fy) = pos[&from];
+        let (tx, ty) = pos[&to];
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#888\" marker-end=\"url(#arrow)\"/>",
+            fx + W,
+            fy + H / 2,
+            tx,
+            ty + H / 2
+        );
+    }
+    for &id in &nodes {
+        let (x, y) = pos[&id];
+        let issue = &exported[&id].issue;
+        let fill = match issue.status.as_str() {
+            "in-progress" => "#fff2cc",
+            "review" => "#f3e5ff",
+            "done" => "#dff5e1",
+            "wontfix" => "#eee",
+            _ => "#e4efff",
+        };
+        let label: String = format!("#{} {}", id, issue.title)
+            .chars()
+            .take(26)
+            .collect();
+        let _ = writeln!(
+            svg,
+            "<a href=\"issues/{id}.html\"><rect x=\"{x}\" y=\"{y}\" width=\"{W}\" height=\"{H}\" rx=\"4\" fill=\"{fill}\" stroke=\"#999\"/><text x=\"{tx}\" y=\"{ty}\">{label}</text><title>{title}</title></a>",
+            tx = x + 6,
+            ty = y + 18,
+            label = esc(&label),
+            title = esc(&issue.title),
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn issue_link(id: i64) -> String {
+    format!("<a href=\"issues/{id}.html\">#{id}</a>")
+}
+
+fn esc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Issue;
+    use std::collections::BTreeMap;
+
+    fn item(id: i64, title: &str, blocked_by: Vec<i64>) -> ExportData {
+        ExportData {
+            issue: Issue {
+                id,
+                title: title.to_string(),
+                status: "open".to_string(),
+                priority: "medium".to_string(),
+                kind: "task".to_string(),
+                context: String::new(),
+                files: vec![],
+                tags: vec![],
+                skills: vec![],
+                acceptance: String::new(),
+                parent_id: None,
+                assigned_to: String::new(),
+                close_reason: String::new(),
+                branch: String::new(),
+                project: String::new(),
+                severity: String::new(),
+                component: String::new(),
+                affects_version: String::new(),
+                fixed_in_version: String::new(),
+                estimate: None,
+                created_at: "2026-01-01T00:00:00Z".to_string(),
+                updated_at: "2026-01-01T00:00:00Z".to_string(),
+            },
+            notes: vec![],
+            blocked_by,
+            events: vec![],
+            relations: vec![],
+            uuid: String::new(),
+            key: String::new(),
+            repro: None,
+            field_times: BTreeMap::new(),
+            blocks: vec![],
+            children: vec![],
+        }
+    }
+
+    #[test]
+    fn site_escapes_titles_and_links_issue_pages() {
+        let out = std::env::temp_dir().join(format!("itr-html-report-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&out);
+        let items = vec![
+            item(1, "Parse <script> tags", vec![]),
+            item(2, "Depends on 1", vec![1]),
+            item(3, "Blocked by a missing issue", vec![9]),
+        ];
+
+        write(&items, &out).unwrap();
+        let index = fs::read_to_string(out.join("index.html")).unwrap();
+        assert!(index.contains("Parse &lt;script&gt; tags"));
+        assert!(!index.contains("<script> tags"));
+        assert!(index.contains("<a href=\"issues/2.html\">Depends on 1</a>"));
+        // Only the 1 -> 2 edge is drawn; 9 is not exported.
+        assert_eq!(index.matches("<line ").count(), 1);
+        assert!(!index.contains("issues/3.html\"><rect"));
+        let page = fs::read_to_string(out.join("issues").join("3.html")).unwrap();
+        assert!(page.contains("<tr><th>Blocked by</th><td>#9</td></tr>"));
+
+        let _ = fs::remove_dir_all(&out);
+    }
+}
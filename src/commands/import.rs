@@ -1,8 +1,12 @@
+use super::{repro, sync, todotxt};
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
-use crate::models::ExportData;
+use crate::format::{self, Format};
+use crate::models::{ExportData, ExportHeader, Note};
+use crate::normalize::{validate_kind, validate_priority, validate_status};
+use crate::util;
 use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{self, BufRead};
 
@@ -13,51 +17,134 @@ struct ImportCounts {
     skipped: usize,
     /// Existing issues overwritten by ID collision in non-merge (replace) mode.
     replaced: usize,
+    /// Issues already here under the same UUID, merged field by field.
+    merged: usize,
+    /// `(source ID, ID here)` for merged-in issues whose ID was taken.
+    renumbered: Vec<(i64, i64)>,
+    /// `(blocker, blocked)` edges, in local IDs, that would close a cycle.
+    cycles: Vec<(i64, i64)>,
     dropped_events: usize,
     dropped_relations: usize,
+    /// Config keys set from a `--full` export's header.
+    config: usize,
+    /// `(parent, child)` links a `--full` export lists that did not land.
+    orphaned_children: Vec<(i64, i64)>,
+    /// `(ID here, key)` for issue keys another issue here already holds.
+    key_conflicts: Vec<(i64, String)>,
+}
+
+/// Where one imported item lands.
+enum Target {
+    Skip,
+    /// Insert (or, in replace mode, overwrite) the row under this ID.
+    Insert(i64),
+    /// Same UUID as this issue here: merge into it.
+    Merge(i64),
 }
 
 /// Core import logic, separated from I/O so it is unit-testable.
 ///
 /// Inserts each item's issue row (keeping its original ID for `blocked_by`
 /// fidelity), indexes it into FTS, and attaches its notes under fresh note
-/// IDs. In non-merge mode an ID collision replaces the existing issue; in
-/// merge mode it is skipped.
+/// IDs. In non-merge mode an ID collision replaces the existing issue. In
+/// merge mode an item whose UUID is already here is merged into that issue
+/// (see [`merge_item`]); an item that only collides by ID is added under the
+/// next free ID when it has a UUID, and skipped when it predates UUIDs.
+///
+/// With the `header` of a `--full` export, its config, tag metadata, and
+/// agent and component registries are applied (under `--merge`, entries
+/// already here win) and inserted issues get their audit events and
+/// relations back.
 fn import_items(
     conn: &Connection,
     items: &[ExportData],
     merge: bool,
+    header: Option<&ExportHeader>,
 ) -> Result<ImportCounts, ItrError> {
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
     let mut counts = ImportCounts::default();
 
+    if let Some(header) = header {
+        for (key, value) in &header.config {
+            if merge && db::config_get(&tx, key)?.is_some() {
+                continue;
+            }
+            db::config_set(&tx, key, value)?;
+            counts.config += 1;
+        }
+        restore_registries(&tx, header, merge)?;
+    }
+
+    // Decide every target first so parents and edges can be rewritten to
+    // IDs here no matter which order the items come in.
+    let mut next_id = items
+        .iter()
+        .map(|item| item.issue.id)
+        .chain(db::all_issues(&tx)?.iter().map(|i| i.id))
+        .max()
+        .unwrap_or(0);
+    let mut ids: HashMap<i64, i64> = HashMap::new();
+    let mut targets = Vec::with_capacity(items.len());
     for item in items {
+        let id = item.issue.id;
+        let known = if merge && !item.uuid.is_empty() {
+            db::issue_id_by_uuid(&tx, &item.uuid)?
+        } else {
+            None
+        };
+        let target = match known {
+            Some(local) => Target::Merge(local),
+            None if !db::issue_exists(&tx, id).unwrap_or(false) => Target::Insert(id),
+            None if !merge => {
+                counts.replaced += 1;
+                Target::Insert(id)
+            }
+            None if item.uuid.is_empty() => Target::Skip,
+            None => {
+                next_id += 1;
+                counts.renumbered.push((id, next_id));
+                Target::Insert(next_id)
+            }
+        };
+        if let Target::Insert(local) | Target::Merge(local) = target {
+            ids.insert(id, local);
+        }
+        targets.push(target);
+    }
+    let local_id = |id: i64| ids.get(&id).copied().unwrap_or(id);
+
+    for (item, target) in items.iter().zip(&targets) {
+        let local = match *target {
+            Target::Skip => {
+                counts.skipped += 1;
+                continue;
+            }
+            Target::Merge(local) => {
+                merge_item(&tx, item, local, &local_id, &mut counts)?;
+                counts.merged += 1;
+                continue;
+            }
+            Target::Insert(local) => local,
+        };
         let issue = &item.issue;
-        let exists = db::issue_exists(&tx, issue.id).unwrap_or(false);
 
-        if merge && exists {
-            counts.skipped += 1;
-            continue;
-        }
-        if exists {
-            counts.replaced += 1;
+        // Soft fallback: only `--full` exports restore audit events and
+        // relation rows. Count them so we can surface a single REVIEW:
+        // warning on stderr after the transaction commits.
+        if header.is_none() {
+            counts.dropped_events += item.events.len();
+            counts.dropped_relations += item.relations.len();
         }
 
-        // Soft fallback: import does not restore audit events or relation
-        // rows yet. Count them so we can surface a single REVIEW: warning
-        // on stderr after the transaction commits.
-        counts.dropped_events += item.events.len();
-        counts.dropped_relations += item.relations.len();
-
         let files_json = serde_json::to_string(&issue.files)?;
         let tags_json = serde_json::to_string(&issue.tags)?;
         let skills_json = serde_json::to_string(&issue.skills)?;
 
         tx.execute(
-            "INSERT OR REPLACE INTO issues (id, title, status, priority, kind, context, files, tags, skills, acceptance, parent_id, close_reason, created_at, updated_at, assigned_to)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            "INSERT OR REPLACE INTO issues (id, title, status, priority, kind, context, files, tags, skills, acceptance, parent_id, close_reason, created_at, updated_at, assigned_to, branch, project_id, severity, component_id, affects_version, fixed_in_version, estimate, repro)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
             params![
-                issue.id,
+                local,
                 issue.title,
                 issue.status,
                 issue.priority,
@@ -67,46 +154,420 @@ fn import_items(
                 tags_json,
                 skills_json,
                 issue.acceptance,
-                issue.parent_id,
+                issue.parent_id.map(local_id),
                 issue.close_reason,
                 issue.created_at,
                 issue.updated_at,
                 issue.assigned_to,
+                issue.branch,
+                db::ensure_project(&tx, &issue.project)?,
+                issue.severity,
+                db::ensure_component(&tx, &issue.component)?,
+                issue.affects_version,
+                issue.fixed_in_version,
+                issue.estimate,
+                repro::encode(&item.repro.clone().unwrap_or_default())?,
             ],
         )?;
+        if !item.uuid.is_empty() {
+            db::set_issue_uuid(&tx, local, &item.uuid)?;
+        }
 
         // Keep imported issues searchable: index into FTS the same way
         // db::insert_issue does, so search works without a manual reindex.
-        db::fts_index_issue(&tx, issue);
+        db::fts_index_issue(&tx, &db::get_issue(&tx, local)?);
 
         // Import notes under FRESH note IDs. Nothing in the export format
         // references note IDs, and reusing the source DB's rowids would
         // silently overwrite unrelated pre-existing notes on ID collision.
         for note in &item.notes {
-            tx.execute(
-                "INSERT INTO notes (issue_id, content, agent, created_at) VALUES (?1, ?2, ?3, ?4)",
-                params![issue.id, note.content, note.agent, note.created_at],
-            )?;
+            if !note.uuid.is_empty() && db::note_uuid_exists(&tx, &note.uuid)? {
+                continue;
+            }
+            insert_note(&tx, local, note)?;
         }
 
-        // Import dependencies
-        for blocker_id in &item.blocked_by {
+        if header.is_some() {
+            for event in &item.events {
+                tx.execute(
+                    "INSERT INTO events (issue_id, field, old_value, new_value, agent, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        local,
+                        event.field,
+                        event.old_value,
+                        event.new_value,
+                        event.agent,
+                        event.created_at
+                    ],
+                )?;
+            }
+        }
+
+        counts.imported += 1;
+    }
+
+    restore_keys(&tx, items, &targets, &mut counts)?;
+
+    // Edges and relations go in once every issue is, so one pointing at an
+    // issue later in the input is not lost to the foreign key. Both ends of
+    // an edge may list it; the second insert is ignored.
+    for (item, target) in items.iter().zip(&targets) {
+        let Target::Insert(local) = *target else {
+            continue;
+        };
+        let edges = item
+            .blocked_by
+            .iter()
+            .map(|&blocker| (local_id(blocker), local))
+            .chain(
+                item.blocks
+                    .iter()
+                    .map(|&blocked| (local, local_id(blocked))),
+            );
+        for (blocker, blocked) in edges {
             let _ = tx.execute(
                 "INSERT OR IGNORE INTO dependencies (blocker_id, blocked_id) VALUES (?1, ?2)",
-                params![blocker_id, issue.id],
+                params![blocker, blocked],
             );
         }
-
-        counts.imported += 1;
+        if header.is_none() {
+            continue;
+        }
+        for rel in &item.relations {
+            let _ = tx.execute(
+                "INSERT OR IGNORE INTO relations (source_id, target_id, relation_type, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    local_id(rel.source_id),
+                    local_id(rel.target_id),
+                    rel.relation_type,
+                    rel.created_at
+                ],
+            );
+        }
+        for &child in &item.children {
+            let child = local_id(child);
+            let parent: Option<Option<i64>> = tx
+                .query_row(
+                    "SELECT parent_id FROM issues WHERE id = ?1",
+                    params![child],
+                    |row| row.get(0),
+                )
+                .ok();
+            if parent != Some(Some(local)) {
+                counts.orphaned_children.push((local, child));
+            }
+        }
     }
 
+    db::refresh_all_references(&tx)?;
     tx.commit()?;
     Ok(counts)
 }
 
+/// Give imported issues back their keys once every issue is in. Keys the
+/// insert triggers handed out to those issues are dropped first, so they
+/// cannot shadow a key a later item carries; a merged issue that already has
+/// a key keeps it. A project with no key prefix here adopts the prefix of
+/// the keys filed under it, so new issues there continue the sequence.
+fn restore_keys(
+    conn: &Connection,
+    items: &[ExportData],
+    targets: &[Target],
+    counts: &mut ImportCounts,
+) -> Result<(), ItrError> {
+    let mut keyed: Vec<(&ExportData, i64)> = Vec::new();
+    for (item, target) in items.iter().zip(targets) {
+        let local = match *target {
+            _ if item.key.is_empty() => continue,
+            Target::Insert(local) => local,
+            Target::Merge(local) if db::issue_key(conn, local)?.is_empty() => local,
+            Target::Merge(_) | Target::Skip => continue,
+        };
+        keyed.push((item, local));
+    }
+    for &(_, local) in &keyed {
+        conn.execute("DELETE FROM issue_keys WHERE issue_id = ?1", params![local])?;
+    }
+    for (item, local) in keyed {
+        if !db::set_issue_key(conn, local, &item.key)? {
+            counts.key_conflicts.push((local, item.key.clone()));
+            continue;
+        }
+        if let Some((prefix, _)) = util::parse_issue_key(&item.key) {
+            conn.execute(
+                "UPDATE projects SET key_prefix = ?1
+                 WHERE key_prefix = '' AND id = (SELECT project_id FROM issues WHERE id = ?2)",
+                params![prefix, local],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// The input after [`validate_items`]: what is left to import, and why.
+struct Validation {
+    items: Vec<ExportData>,
+    /// One message per problem, in input order.
+    problems: Vec<String>,
+    /// Items dropped for an invalid status, priority, or kind.
+    rejected: usize,
+}
+
+/// Check the whole input before anything is written. Items with an invalid
+/// status, priority, or kind are dropped (the schema would reject them and
+/// fail the whole import); a `parent_id` that is the item itself or in
+/// neither the input nor here is cleared. IDs repeated in the input and
+/// dangling `blocked_by` entries, which the import already tolerates, are
+/// only reported.
+fn validate_items(conn: &Connection, items: Vec<ExportData>) -> Result<Validation, ItrError> {
+    let mut problems = Vec::new();
+    let mut seen: HashSet<i64> = HashSet::new();
+    let total = items.len();
+    let mut kept = Vec::with_capacity(total);
+    for item in items {
+        let issue = &item.issue;
+        if !seen.insert(issue.id) {
+            problems.push(format!(
+                "issue {} appears more than once in the input; the last copy wins",
+                issue.id
+            ));
+        }
+        let invalid: Vec<String> = [
+            validate_status(&issue.status),
+            validate_priority(&issue.priority),
+            validate_kind(&issue.kind),
+        ]
+        .into_iter()
+        .filter_map(|check| check.err().map(|e| e.to_string()))
+        .collect();
+        if invalid.is_empty() {
+            kept.push(item);
+        } else {
+            problems.extend(
+                invalid
+                    .into_iter()
+                    .map(|e| format!("issue {}: {}; the issue will be skipped", issue.id, e)),
+            );
+        }
+    }
+    let rejected = total - kept.len();
+
+    let input_ids: HashSet<i64> = kept.iter().map(|item| item.issue.id).collect();
+    let known = |id: i64| -> Result<bool, ItrError> {
+        Ok(input_ids.contains(&id) || db::issue_exists(conn, id)?)
+    };
+    for item in &mut kept {
+        let id = item.issue.id;
+        if let Some(parent) = item.issue.parent_id {
+            if parent == id {
+                problems.push(format!(
+                    "issue {} is its own parent; the parent link will be dropped",
+                    id
+                ));
+                item.issue.parent_id = None;
+            } else if !known(parent)? {
+                problems.push(format!(
+                    "issue {} has parent {}, which is neither in the input nor here; the parent link will be dropped",
+                    id, parent
+                ));
+                item.issue.parent_id = None;
+            }
+        }
+        for &blocker in &item.blocked_by {
+            if blocker == id {
+                problems.push(format!(
+                    "issue {} lists itself in blocked_by; the edge will be dropped",
+                    id
+                ));
+            } else if !known(blocker)? {
+                problems.push(format!(
+                    "issue {} is blocked by {}, which is neither in the input nor here; the edge will be dropped",
+                    id, blocker
+                ));
+            }
+        }
+    }
+    Ok(Validation {
+        items: kept,
+        problems,
+        rejected,
+    })
+}
+
+/// Write a `--full` header's tag metadata, agents, and components. Rows
+/// already here are overwritten, or kept under `merge`.
+fn restore_registries(
+    conn: &Connection,
+    header: &ExportHeader,
+    merge: bool,
+) -> Result<(), ItrError> {
+    let on_conflict = |update: &str| {
+        if merge {
+            "DO NOTHING".to_string()
+        } else {
+            format!("DO UPDATE SET {}", update)
+        }
+    };
+    for tag in &header.tags {
+        conn.execute(
+            &format!(
+                "INSERT INTO tags (name, description, color) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(name) {}",
+                on_conflict("description = excluded.description, color = excluded.color")
+            ),
+            params![tag.tag, tag.description, tag.color],
+        )?;
+    }
+    for agent in &header.agents {
+        conn.execute(
+            &format!(
+                "INSERT INTO agents (name, skills, description, registered_at, last_seen_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(name) {}",
+                on_conflict(
+                    "skills = excluded.skills, description = excluded.description, \
+                     registered_at = excluded.registered_at, last_seen_at = excluded.last_seen_at"
+                )
+            ),
+            params![
+                agent.name,
+                serde_json::to_string(&agent.skills)?,
+                agent.description,
+                agent.registered_at,
+                agent.last_seen_at
+            ],
+        )?;
+    }
+    for component in &header.components {
+        conn.execute(
+            &format!(
+                "INSERT INTO components (name, default_assignee, default_tags, created_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(name) {}",
+                on_conflict(
+                    "default_assignee = excluded.default_assignee, \
+                     default_tags = excluded.default_tags, created_at = excluded.created_at"
+                )
+            ),
+            params![
+                component.name,
+                component.default_assignee,
+                serde_json::to_string(&component.default_tags)?,
+                component.created_at
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_note(conn: &Connection, issue_id: i64, note: &Note) -> Result<(), ItrError> {
+    conn.execute(
+        "INSERT INTO notes (issue_id, content, agent, created_at, kind) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![issue_id, note.content, note.agent, note.created_at, note.kind],
+    )?;
+    if !note.uuid.is_empty() {
+        db::set_note_uuid(conn, conn.last_insert_rowid(), &note.uuid)?;
+    }
+    Ok(())
+}
+
+/// Merge an imported copy of an issue into the issue here with its UUID.
+///
+/// Each field keeps whichever side changed it last, going by the event log
+/// (exported as `field_times`; a field never changed dates from creation).
+/// Equal times fall back to comparing the values, so both copies settle on
+/// the same result whichever one imports the other. Notes and dependency
+/// edges are added, never removed.
+fn merge_item(
+    conn: &Connection,
+    item: &ExportData,
+    id: i64,
+    local_id: &dyn Fn(i64) -> i64,
+    counts: &mut ImportCounts,
+) -> Result<(), ItrError> {
+    let mut incoming = item.issue.clone();
+    incoming.parent_id = incoming.parent_id.map(local_id);
+    let local = db::get_issue(conn, id)?;
+    let local_times = db::field_times(conn, id)?;
+    // When theirs should replace ours, the time their copy changed it.
+    let theirs_wins = |field: &str, ours: &String, theirs: &String| {
+        let ours_at = local_times.get(field).unwrap_or(&local.created_at);
+        let theirs_at = item
+            .field_times
+            .get(field)
+            .unwrap_or(&item.issue.created_at);
+        ((theirs_at, theirs) >= (ours_at, ours)).then(|| theirs_at.clone())
+    };
+    for field in sync::FIELDS {
+        let (ours, theirs) = (
+            sync::field_value(&local, field),
+            sync::field_value(&incoming, field),
+        );
+        if ours == theirs {
+            continue;
+        }
+        let Some(theirs_at) = theirs_wins(field, &ours, &theirs) else {
+            continue;
+        };
+        if *field == "parent_id" {
+            match db::update_issue_parent(conn, id, incoming.parent_id) {
+                Ok(()) => {}
+                Err(ItrError::NotFound(_) | ItrError::CycleDetected(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        } else {
+            sync::write_field(conn, id, field, &theirs)?;
+        }
+        db::record_event_at(conn, id, field, &ours, &theirs, &theirs_at)?;
+    }
+    // The reproduction merges as one value.
+    let (ours, theirs) = (
+        repro::encode(&db::issue_repro(conn, id)?.unwrap_or_default())?,
+        repro::encode(&item.repro.clone().unwrap_or_default())?,
+    );
+    if ours != theirs {
+        if let Some(theirs_at) = theirs_wins("repro", &ours, &theirs) {
+            db::set_repro(conn, id, item.repro.as_ref())?;
+            db::record_event_at(conn, id, "repro", &ours, &theirs, &theirs_at)?;
+        }
+    }
+
+    let uuids: HashSet<String> = db::note_uuids(conn, id)?.into_values().collect();
+    let seen: HashSet<(String, String)> = db::get_notes(conn, id)?
+        .into_iter()
+        .map(|n| (n.content, n.created_at))
+        .collect();
+    for note in &item.notes {
+        let known = if note.uuid.is_empty() {
+            seen.contains(&(note.content.clone(), note.created_at.clone()))
+        } else {
+            uuids.contains(&note.uuid) || db::note_uuid_exists(conn, &note.uuid)?
+        };
+        if !known {
+            insert_note(conn, id, note)?;
+        }
+    }
+
+    for &blocker in &item.blocked_by {
+        let blocker = local_id(blocker);
+        if !db::issue_exists(conn, blocker)? {
+            continue;
+        }
+        match db::add_dependency(conn, blocker, id) {
+            Ok(_) => {}
+            Err(ItrError::CycleDetected(_)) => counts.cycles.push((blocker, id)),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
 pub fn run(
     conn: &Connection,
     file: Option<String>,
+    import_format: &str,
     merge: bool,
     fmt: Format,
 ) -> Result<(), ItrError> {
@@ -124,20 +585,48 @@ pub fn run(
         }
     };
 
-    let input = input.trim();
-
-    // Try JSON array first, then JSONL
-    let items: Vec<ExportData> = if input.starts_with('[') {
-        serde_json::from_str(input)?
-    } else {
-        input
-            .lines()
-            .filter(|l| !l.trim().is_empty())
-            .map(serde_json::from_str)
-            .collect::<Result<Vec<_>, _>>()?
+    let (header, items) = match import_format {
+        "jsonl" | "json" => itr_core::storage::parse_export(&input)?,
+        "todotxt" => {
+            let (items, notes) = todotxt::parse(conn, &input)?;
+            for note in &notes {
+                eprintln!("{}", note);
+            }
+            (None, items)
+        }
+        other => {
+            return Err(ItrError::InvalidValue {
+                field: "import-format".to_string(),
+                value: other.to_string(),
+                valid: "jsonl, json, todotxt".to_string(),
+            })
+        }
     };
 
-    let counts = import_items(conn, &items, merge)?;
+    let Validation {
+        items,
+        problems,
+        rejected,
+    } = validate_items(conn, items)?;
+    for problem in &problems {
+        eprintln!("REVIEW: import: {}", problem);
+    }
+
+    if !merge {
+        let collisions = items
+            .iter()
+            .filter(|item| db::issue_exists(conn, item.issue.id).unwrap_or(false))
+            .count();
+        if collisions > 0 {
+            util::confirm(&format!(
+                "Replace {} existing issue(s) with imported data?",
+                collisions
+            ))?;
+        }
+    }
+
+    let mut counts = import_items(conn, &items, merge, header.as_ref())?;
+    counts.skipped += rejected;
 
     if counts.dropped_events > 0 || counts.dropped_relations > 0 {
         let mut parts: Vec<String> = Vec::new();
@@ -156,6 +645,30 @@ pub fn run(
         );
     }
 
+    if !counts.orphaned_children.is_empty() {
+        let links: Vec<String> = counts
+            .orphaned_children
+            .iter()
+            .map(|(parent, child)| format!("{} -> {}", parent, child))
+            .collect();
+        eprintln!(
+            "REVIEW: import: the export lists children that are not under their parent here (parent -> child): {}",
+            links.join(", ")
+        );
+    }
+
+    if !counts.key_conflicts.is_empty() {
+        let keys: Vec<String> = counts
+            .key_conflicts
+            .iter()
+            .map(|(id, key)| format!("{} ({})", key, id))
+            .collect();
+        eprintln!(
+            "REVIEW: import: issue key(s) already held by another issue here were not restored (key (ID here)): {}",
+            keys.join(", ")
+        );
+    }
+
     if counts.replaced > 0 {
         eprintln!(
             "REVIEW: import replaced {} existing issue(s) whose IDs collided \
@@ -165,19 +678,48 @@ pub fn run(
         );
     }
 
+    if !counts.renumbered.is_empty() {
+        let moved: Vec<String> = counts
+            .renumbered
+            .iter()
+            .map(|(from, to)| format!("{} -> {}", from, to))
+            .collect();
+        eprintln!(
+            "REVIEW: import --merge added {} issue(s) under new IDs because their IDs belong to other issues here: {}",
+            moved.len(),
+            moved.join(", ")
+        );
+    }
+    if !counts.cycles.is_empty() {
+        let edges: Vec<String> = counts
+            .cycles
+            .iter()
+            .map(|(blocker, blocked)| format!("{} BLOCKED_BY:{}", blocked, blocker))
+            .collect();
+        eprintln!(
+            "REVIEW: import --merge skipped dependency edge(s) that would create a cycle here: {}",
+            edges.join(", ")
+        );
+    }
+
     match fmt {
         Format::Json => {
             let out = serde_json::json!({
                 "action": "import",
                 "imported": counts.imported,
+                "created": counts.imported - counts.replaced,
+                "replaced": counts.replaced,
                 "skipped": counts.skipped,
+                "merged": counts.merged,
+                "config": counts.config,
+                "problems": problems,
             });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             println!(
-                "IMPORT: {} imported, {} skipped",
-                counts.imported, counts.skipped
+                "IMPORT: {} imported, {} skipped, {} merged",
+                counts.imported, counts.skipped, counts.merged
             );
         }
     }
@@ -188,7 +730,8 @@ pub fn run(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{Issue, Note};
+    use crate::models::{Issue, Repro};
+    use std::collections::BTreeMap;
     use std::path::{Path, PathBuf};
 
     /// Open a fresh on-disk test DB (`init_db` runs the same schema,
@@ -244,6 +787,13 @@ mod tests {
                 parent_id: None,
                 assigned_to: String::new(),
                 close_reason: String::new(),
+                branch: String::new(),
+                project: String::new(),
+                severity: String::new(),
+                component: String::new(),
+                affects_version: String::new(),
+                fixed_in_version: String::new(),
+                estimate: None,
                 created_at: "2026-01-01T00:00:00Z".to_string(),
                 updated_at: "2026-01-01T00:00:00Z".to_string(),
             },
@@ -251,6 +801,12 @@ mod tests {
             blocked_by: vec![],
             events: vec![],
             relations: vec![],
+            uuid: String::new(),
+            key: String::new(),
+            repro: None,
+            field_times: BTreeMap::new(),
+            blocks: vec![],
+            children: vec![],
         }
     }
 
@@ -261,9 +817,48 @@ mod tests {
             content: content.to_string(),
             agent: "exporter".to_string(),
             created_at: "2026-01-02T00:00:00Z".to_string(),
+            uuid: String::new(),
+            kind: String::new(),
         }
     }
 
+    #[test]
+    fn validation_reports_bad_enums_repeats_and_dangling_references() {
+        let (conn, path) = test_db("validate");
+        seed_issue(&conn, "Existing issue");
+
+        let mut bad = export_item(10, "Bad enums", vec![]);
+        bad.issue.status = "bogus".to_string();
+        let mut orphan = export_item(11, "Orphan", vec![]);
+        orphan.issue.parent_id = Some(98);
+        orphan.blocked_by = vec![10, 1, 99, 11];
+        let repeat = export_item(11, "Repeat", vec![]);
+        let mut child = export_item(12, "Child", vec![]);
+        child.issue.parent_id = Some(1);
+
+        let checked = validate_items(&conn, vec![bad, orphan, repeat, child]).unwrap();
+        assert_eq!(
+            checked.problems,
+            [
+                "issue 10: Invalid value for status: 'bogus'. Valid: open, in-progress, review, done, wontfix; the issue will be skipped",
+                "issue 11 appears more than once in the input; the last copy wins",
+                "issue 11 has parent 98, which is neither in the input nor here; the parent link will be dropped",
+                "issue 11 is blocked by 10, which is neither in the input nor here; the edge will be dropped",
+                "issue 11 is blocked by 99, which is neither in the input nor here; the edge will be dropped",
+                "issue 11 lists itself in blocked_by; the edge will be dropped",
+            ]
+        );
+        assert_eq!(checked.rejected, 1);
+        let parents: Vec<Option<i64>> = checked
+            .items
+            .iter()
+            .map(|item| item.issue.parent_id)
+            .collect();
+        assert_eq!(parents, [None, None, Some(1)]);
+
+        cleanup(&path);
+    }
+
     /// #153: a note-ID collision under --merge must not modify or delete
     /// any pre-existing note row; imported notes get fresh IDs.
     #[test]
@@ -280,7 +875,7 @@ mod tests {
             "Imported issue",
             vec![export_note(1, 100, "imported note")],
         );
-        let counts = import_items(&conn, &[item], true).unwrap();
+        let counts = import_items(&conn, &[item], true, None).unwrap();
         assert_eq!(counts.imported, 1);
         assert_eq!(counts.skipped, 0);
 
@@ -313,7 +908,7 @@ mod tests {
             "Imported issue",
             vec![export_note(original.id, 100, "imported note")],
         );
-        import_items(&conn, &[item], false).unwrap();
+        import_items(&conn, &[item], false, None).unwrap();
 
         let kept = db::get_note(&conn, original.id).unwrap();
         assert_eq!(kept.issue_id, existing.id);
@@ -339,7 +934,7 @@ mod tests {
 
         let existing = seed_issue(&conn, "widget existing");
         let item = export_item(100, "widget imported", vec![]);
-        import_items(&conn, &[item], false).unwrap();
+        import_items(&conn, &[item], false, None).unwrap();
 
         let ids = db::fts_search(&conn, "widget").unwrap();
         assert!(
@@ -374,7 +969,7 @@ mod tests {
         let existing = seed_issue(&conn, "Old title");
         let item = export_item(existing.id, "New title", vec![]);
 
-        let counts = import_items(&conn, std::slice::from_ref(&item), false).unwrap();
+        let counts = import_items(&conn, std::slice::from_ref(&item), false, None).unwrap();
         assert_eq!(counts.imported, 1);
         assert_eq!(counts.skipped, 0);
         assert_eq!(counts.replaced, 1, "replace-on-collision must be counted");
@@ -384,11 +979,163 @@ mod tests {
         );
 
         // Merge mode on the same payload skips and replaces nothing.
-        let counts = import_items(&conn, &[item], true).unwrap();
+        let counts = import_items(&conn, &[item], true, None).unwrap();
         assert_eq!(counts.imported, 0);
         assert_eq!(counts.skipped, 1);
         assert_eq!(counts.replaced, 0);
 
         cleanup(&path);
     }
+
+    /// Two copies that diverged: each field keeps the later edit, whichever
+    /// copy imports the other, and issues both copies added as #2 both
+    /// survive.
+    #[test]
+    fn merge_import_is_last_write_wins_by_uuid() {
+        let (a, path_a) = test_db("lww-a");
+        let (b, path_b) = test_db("lww-b");
+        seed_issue(&a, "Shared");
+        import_items(
+            &b,
+            &super::super::export::items(&a, &super::super::export::ExportFilter::default(), false)
+                .unwrap(),
+            false,
+            None,
+        )
+        .unwrap();
+
+        let edit = |conn: &Connection, field: &str, value: &str, at: &str| {
+            db::update_issue_field(conn, 1, field, value).unwrap();
+            db::record_event_at(conn, 1, field, "", value, at).unwrap();
+        };
+        // Edit times come after the issues' real creation time.
+        edit(&a, "priority", "high", "2099-03-01T00:00:00Z");
+        edit(&b, "priority", "low", "2099-02-01T00:00:00Z");
+        edit(&b, "title", "Renamed", "2099-02-01T00:00:00Z");
+        seed_issue(&a, "Only in a");
+        seed_issue(&b, "Only in b");
+
+        let from_a =
+            super::super::export::items(&a, &super::super::export::ExportFilter::default(), false)
+                .unwrap();
+        let from_b =
+            super::super::export::items(&b, &super::super::export::ExportFilter::default(), false)
+                .unwrap();
+        let counts = import_items(&a, &from_b, true, None).unwrap();
+        assert_eq!((counts.merged, counts.imported), (1, 1));
+        assert_eq!(counts.renumbered, vec![(2, 3)]);
+        import_items(&b, &from_a, true, None).unwrap();
+
+        for conn in [&a, &b] {
+            let shared = db::get_issue(conn, 1).unwrap();
+            assert_eq!(
+                (shared.title.as_str(), shared.priority.as_str()),
+                ("Renamed", "high")
+            );
+            let mut titles: Vec<String> = db::all_issues(conn)
+                .unwrap()
+                .into_iter()
+                .map(|i| i.title)
+                .collect();
+            titles.sort();
+            assert_eq!(titles, ["Only in a", "Only in b", "Renamed"]);
+        }
+        assert_eq!(db::get_issue(&a, 3).unwrap().title, "Only in b");
+
+        // Merging the same export again changes nothing.
+        let again = import_items(&a, &from_b, true, None).unwrap();
+        assert_eq!((again.merged, again.imported), (2, 0));
+        assert_eq!(db::all_issues(&a).unwrap().len(), 3);
+
+        cleanup(&path_a);
+        cleanup(&path_b);
+    }
+
+    /// Issue keys follow the issue to its new ID, and the target project
+    /// picks up the prefix so the sequence continues there.
+    #[test]
+    fn merge_import_keeps_keys_across_renumbering() {
+        let (a, path_a) = test_db("keys-a");
+        let (b, path_b) = test_db("keys-b");
+        db::add_project(&a, "app", "").unwrap();
+        db::set_project_key_prefix(&a, "app", "APP").unwrap();
+        let first = seed_issue(&a, "First");
+        db::set_issue_project(&a, first.id, Some("app")).unwrap();
+        seed_issue(&b, "Local");
+
+        let items =
+            super::super::export::items(&a, &super::super::export::ExportFilter::default(), false)
+                .unwrap();
+        assert_eq!(items[0].key, "APP-1");
+        let counts = import_items(&b, &items, true, None).unwrap();
+        assert_eq!(counts.renumbered, vec![(1, 2)]);
+        assert_eq!(db::issue_key(&b, 2).unwrap(), "APP-1");
+        assert_eq!(db::issue_id_by_key(&b, "app-1").unwrap(), Some(2));
+        assert_eq!(db::project_key_prefix(&b, "app").unwrap(), "APP");
+
+        let next = seed_issue(&b, "Next");
+        db::set_issue_project(&b, next.id, Some("app")).unwrap();
+        assert_eq!(db::issue_key(&b, next.id).unwrap(), "APP-2");
+
+        cleanup(&path_a);
+        cleanup(&path_b);
+    }
+
+    /// A `--full` export restores config, events, relations, and an edge
+    /// whose blocker comes later in the input.
+    #[test]
+    fn full_export_round_trips_into_a_fresh_db() {
+        let (a, path_a) = test_db("full-a");
+        let (b, path_b) = test_db("full-b");
+        let epic = seed_issue(&a, "Epic");
+        let child = seed_issue(&a, "Child");
+        let blocker = seed_issue(&a, "Blocker");
+        db::update_issue_parent(&a, child.id, Some(epic.id)).unwrap();
+        db::add_dependency(&a, blocker.id, child.id).unwrap();
+        db::add_relation(&a, epic.id, blocker.id, "related").unwrap();
+        db::record_event(&a, epic.id, "priority", "medium", "high").unwrap();
+        db::set_estimate(&a, child.id, Some(5)).unwrap();
+        let repro = Repro {
+            steps: vec!["open the epic".to_string()],
+            expected: "it loads".to_string(),
+            ..Repro::default()
+        };
+        db::set_repro(&a, child.id, Some(&repro)).unwrap();
+        db::config_set(&a, "urgency.priority_high", "9").unwrap();
+        db::set_tag_meta(&a, "ui", Some("Front end"), Some("blue")).unwrap();
+        db::register_agent(&a, "ada", Some(&["rust".to_string()]), Some("reviewer")).unwrap();
+        db::upsert_component(&a, "api", Some("ada"), Some(&["backend".to_string()])).unwrap();
+
+        let items =
+            super::super::export::items(&a, &super::super::export::ExportFilter::default(), true)
+                .unwrap();
+        assert_eq!(items[0].children, [child.id]);
+        assert_eq!(items[2].blocks, [child.id]);
+        let header = super::super::export::header(&a).unwrap();
+        let counts = import_items(&b, &items, false, Some(&header)).unwrap();
+        assert_eq!((counts.imported, counts.dropped_events), (3, 0));
+        assert!(counts.orphaned_children.is_empty());
+
+        assert_eq!(db::config_list(&b).unwrap(), db::config_list(&a).unwrap());
+        let restored = super::super::export::header(&b).unwrap();
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap(),
+            serde_json::to_value(&header).unwrap()
+        );
+        assert_eq!(db::get_blockers(&b, child.id).unwrap(), [blocker.id]);
+        assert_eq!(db::issue_estimate(&b, child.id).unwrap(), Some(5));
+        assert_eq!(db::issue_repro(&b, child.id).unwrap(), Some(repro));
+        assert_eq!(db::all_relations(&b).unwrap().len(), 1);
+        let events = |conn: &Connection| -> Vec<(String, String, String)> {
+            db::get_events_for_issue(conn, epic.id)
+                .unwrap()
+                .into_iter()
+                .map(|e| (e.field, e.new_value, e.created_at))
+                .collect()
+        };
+        assert_eq!(events(&b), events(&a));
+
+        cleanup(&path_a);
+        cleanup(&path_b);
+    }
 }
@@ -1,12 +1,42 @@
+use super::config;
 use crate::agent_docs::AGENT_DOCS;
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
+use crate::store::{self, NewIssue};
+use rusqlite::Connection;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub fn run(agents_md: bool, fmt: Format, db_override: Option<&str>) -> Result<(), ItrError> {
+/// Settings file picked up from the new database's directory by `itr init`,
+/// so a repo can check in its tuned config (`itr config export > .itr.toml`).
+pub const SETTINGS_FILE: &str = ".itr.toml";
+
+/// Config key holding a custom agent-instructions block for `--agents-md`.
+pub const AGENTS_TEMPLATE_KEY: &str = "agents.template";
+/// Instructions file written when `--agents-md` names no `--target`.
+pub const DEFAULT_AGENTS_TARGET: &str = "AGENTS.md";
+const BLOCK_START: &str = "<!-- itr:agents:start -->";
+const BLOCK_END: &str = "<!-- itr:agents:end -->";
+
+/// Where `--agents-md` writes the instructions block and what it contains.
+#[derive(Debug, Default)]
+pub struct AgentsMd {
+    /// Files relative to the database's directory; empty means `AGENTS.md`.
+    pub targets: Vec<String>,
+    /// Template file from `--agents-template`, ahead of `agents.template`.
+    pub template: Option<String>,
+}
+
+pub fn run(
+    agents_md: Option<&AgentsMd>,
+    encrypt: bool,
+    settings: Option<&str>,
+    demo: bool,
+    fmt: Format,
+    db_override: Option<&str>,
+) -> Result<(), ItrError> {
     // Precedence matches every other command (docs/environment.md): an
     // explicit --db wins over an ambient ITR_DB_PATH, which wins over cwd. A
     // directory address resolves to <dir>/.itr.db so `itr init --db <root>`
@@ -22,21 +52,70 @@ pub fn run(agents_md: bool, fmt: Format, db_override: Option<&str>) -> Result<()
         },
     };
 
-    let created = if db_path.exists() {
+    let (conn, created) = if db_path.exists() {
         // Idempotent: already exists
-        let _conn = db::open_db(&db_path)?;
-        false
+        let conn = db::open_db(&db_path)?;
+        if encrypt && !db::is_encrypted(&db_path) {
+            eprintln!(
+                "REVIEW: {} already exists unencrypted; --encrypt only applies to new databases",
+                db_path.display()
+            );
+        }
+        (conn, false)
+    } else if encrypt {
+        (db::init_encrypted_db(&db_path)?, true)
     } else {
-        let _conn = db::init_db(&db_path)?;
-        true
+        (db::init_db(&db_path)?, true)
+    };
+    db::set_config_defaults(&conn, &crate::global_config::values())?;
+
+    // An explicit --config always applies; a checked-in .itr.toml only
+    // seeds a database created just now, never overwrites a tuned one.
+    let settings_path = match settings {
+        Some(p) => Some(PathBuf::from(p)),
+        None => db_path
+            .parent()
+            .map(|dir| dir.join(SETTINGS_FILE))
+            .filter(|p| created && p.is_file()),
+    };
+    let applied = match &settings_path {
+        Some(p) => {
+            let values = config::read_settings_file(p)?;
+            config::import_settings(&conn, &values)?.set.len()
+        }
+        None => 0,
     };
 
-    if agents_md {
+    // Demo data only goes into an empty database, never next to real work.
+    let demo_issues = if !demo {
+        0
+    } else if db::all_issues(&conn)?.is_empty() {
+        seed_demo(&conn)?
+    } else {
+        eprintln!(
+            "REVIEW: {} already has issues; --demo only seeds an empty database",
+            db_path.display()
+        );
+        0
+    };
+
+    let mut agents_files: Vec<(PathBuf, &str)> = Vec::new();
+    if let Some(agents) = agents_md {
         let agents_dir = db_path.parent().map_or_else(
             || env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
             std::path::Path::to_path_buf,
         );
-        append_agents_md(&agents_dir)?;
+        let block = agents_block(&conn, agents.template.as_deref(), &db_path)?;
+        let targets = if agents.targets.is_empty() {
+            vec![DEFAULT_AGENTS_TARGET.to_string()]
+        } else {
+            agents.targets.clone()
+        };
+        for target in targets {
+            let path = agents_dir.join(target);
+            let action = write_agents_block(&path, &block)?;
+            agents_files.push((path, action));
+        }
     }
 
     let path_str = db_path.to_string_lossy().to_string();
@@ -46,31 +125,418 @@ pub fn run(agents_md: bool, fmt: Format, db_override: Option<&str>) -> Result<()
                 "action": "init",
                 "path": path_str,
                 "created": created,
+                "encrypted": db::is_encrypted(&db_path),
+                "config_applied": applied,
+                "demo_issues": demo_issues,
+                "agents": agents_files
+                    .iter()
+                    .map(|(path, action)| serde_json::json!({
+                        "path": path.to_string_lossy(),
+                        "action": action,
+                    }))
+                    .collect::<Vec<_>>(),
             });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             println!("INIT: {}", path_str);
+            if let Some(p) = &settings_path {
+                println!(
+                    "CONFIG: applied {} setting(s) from {}",
+                    applied,
+                    p.display()
+                );
+            }
+            for (path, action) in &agents_files {
+                println!("AGENTS: {} {}", action, path.display());
+            }
+            if demo_issues > 0 {
+                println!(
+                    "DEMO: seeded {} issues; try 'itr summary', 'itr ready', or 'itr graph --ascii'",
+                    demo_issues
+                );
+            }
         }
     }
 
     Ok(())
 }
 
-fn append_agents_md(cwd: &std::path::Path) -> Result<(), ItrError> {
-    let agents_path = cwd.join("AGENTS.md");
+/// Seed a small web-shop backlog that exercises most commands: two epics
+/// with children, a three-issue blocking chain, a claimed bug, closed and
+/// wontfix issues, a relation, notes, and every kind and priority. Written in
+/// one transaction through the same paths `add`, `close`, and `note` use, so
+/// history and urgency look like real use. Returns the number of issues.
+fn seed_demo(conn: &Connection) -> Result<usize, ItrError> {
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| (*s).to_string()).collect()
+    }
+    fn add(conn: &Connection, req: NewIssue) -> Result<i64, ItrError> {
+        Ok(store::add_issue(conn, req)?.issue.id)
+    }
 
-    if agents_path.exists() {
-        let content = fs::read_to_string(&agents_path)?;
-        if content.contains("## Issue Tracking") {
-            return Ok(()); // already has it
-        }
-        let mut content = content;
-        content.push('\n');
-        content.push_str(AGENT_DOCS);
-        fs::write(&agents_path, content)?;
+    let tx = db::begin_write(conn)?;
+
+    let checkout = add(
+        &tx,
+        NewIssue {
+            priority: "high".to_string(),
+            kind: "epic".to_string(),
+            context: "Rebuild checkout so returning customers can pay in one step.".to_string(),
+            tags: strings(&["checkout"]),
+            ..NewIssue::new("Checkout v2")
+        },
+    )?;
+    let migration = add(
+        &tx,
+        NewIssue {
+            context: "New payment_methods table keyed by customer, with a token column only."
+                .to_string(),
+            files: strings(&["migrations/0042_payment_methods.sql"]),
+            tags: strings(&["checkout", "db"]),
+            skills: strings(&["sql"]),
+            acceptance: "Migration applies and rolls back cleanly on a copy of production."
+                .to_string(),
+            parent_id: Some(checkout),
+            ..NewIssue::new("Add payment_methods table migration")
+        },
+    )?;
+    let saved_cards = add(
+        &tx,
+        NewIssue {
+            priority: "high".to_string(),
+            kind: "feature".to_string(),
+            context: "Let signed-in customers save a card and pick it at checkout.".to_string(),
+            files: strings(&["src/checkout/payment.rs", "src/checkout/views.rs"]),
+            tags: strings(&["checkout", "payments"]),
+            skills: strings(&["rust"]),
+            acceptance:
+                "A saved card can be chosen, charged, and deleted; no card numbers are stored."
+                    .to_string(),
+            parent_id: Some(checkout),
+            blocked_by_ids: vec![migration],
+            ..NewIssue::new("Saved payment methods")
+        },
+    )?;
+    let discount = add(
+        &tx,
+        NewIssue {
+            priority: "critical".to_string(),
+            kind: "bug".to_string(),
+            context: "Repro: add two items, apply SAVE10. Expected: 10% off. Actual: full price."
+                .to_string(),
+            files: strings(&["src/cart/total.rs"]),
+            tags: strings(&["checkout", "regression"]),
+            skills: strings(&["rust"]),
+            acceptance: "Discount codes apply to the cart total; regression test added."
+                .to_string(),
+            parent_id: Some(checkout),
+            assigned_to: "demo-agent".to_string(),
+            ..NewIssue::new("Cart total ignores discount codes")
+        },
+    )?;
+    let review = add(
+        &tx,
+        NewIssue {
+            priority: "low".to_string(),
+            context: "Walk design through the new flow before build starts.".to_string(),
+            parent_id: Some(checkout),
+            ..NewIssue::new("Design review for the checkout flow")
+        },
+    )?;
+
+    let search = add(
+        &tx,
+        NewIssue {
+            kind: "epic".to_string(),
+            context: "Search is slow and misses obvious matches; fix both.".to_string(),
+            tags: strings(&["search"]),
+            ..NewIssue::new("Search overhaul")
+        },
+    )?;
+    let tracing = add(
+        &tx,
+        NewIssue {
+            context: "Add spans around query parsing, index lookup, and ranking.".to_string(),
+            files: strings(&["src/search/api.rs"]),
+            tags: strings(&["search", "observability"]),
+            skills: strings(&["rust"]),
+            parent_id: Some(search),
+            ..NewIssue::new("Add request tracing to the search API")
+        },
+    )?;
+    let benchmark = add(
+        &tx,
+        NewIssue {
+            priority: "high".to_string(),
+            context: "Record p50/p95 latency for the top 100 queries as the baseline.".to_string(),
+            files: strings(&["benches/search.rs"]),
+            tags: strings(&["search", "performance"]),
+            acceptance: "Baseline numbers are checked in under benches/.".to_string(),
+            parent_id: Some(search),
+            blocked_by_ids: vec![tracing],
+            ..NewIssue::new("Benchmark search latency")
+        },
+    )?;
+    let typo = add(
+        &tx,
+        NewIssue {
+            kind: "feature".to_string(),
+            context: "Match 'shoos' to 'shoes'; keep p95 under the benchmark baseline.".to_string(),
+            files: strings(&["src/search/query.rs"]),
+            tags: strings(&["search"]),
+            acceptance: "One-letter typos in product names still find the product.".to_string(),
+            parent_id: Some(search),
+            blocked_by_ids: vec![benchmark],
+            ..NewIssue::new("Typo-tolerant product search")
+        },
+    )?;
+    let archived = add(
+        &tx,
+        NewIssue {
+            priority: "high".to_string(),
+            kind: "bug".to_string(),
+            context: "Repro: archive a product, search its name. Expected: no result. Actual: it shows up."
+                .to_string(),
+            files: strings(&["src/search/index.rs"]),
+            tags: strings(&["search"]),
+            parent_id: Some(search),
+            ..NewIssue::new("Search returns archived products")
+        },
+    )?;
+
+    let reset = add(
+        &tx,
+        NewIssue {
+            priority: "critical".to_string(),
+            kind: "bug".to_string(),
+            context: "Several customers report no reset email; the mail log shows nothing sent."
+                .to_string(),
+            files: strings(&["src/auth/reset.rs", "src/mail/mod.rs"]),
+            tags: strings(&["auth", "email"]),
+            ..NewIssue::new("Password reset email never arrives")
+        },
+    )?;
+    add(
+        &tx,
+        NewIssue {
+            priority: "low".to_string(),
+            context: "The TLS crate is two minor versions behind; no known advisories.".to_string(),
+            files: strings(&["Cargo.toml"]),
+            tags: strings(&["security", "dependencies"]),
+            ..NewIssue::new("Upgrade the TLS library")
+        },
+    )?;
+    let dark_mode = add(
+        &tx,
+        NewIssue {
+            priority: "low".to_string(),
+            kind: "feature".to_string(),
+            context: "Requested by one admin user.".to_string(),
+            tags: strings(&["admin", "ui"]),
+            ..NewIssue::new("Dark mode for the admin dashboard")
+        },
+    )?;
+
+    db::update_issue_field(&tx, discount, "status", "in-progress")?;
+    db::record_event(&tx, discount, "status", "open", "in-progress")?;
+    db::add_note(
+        &tx,
+        discount,
+        "Traced it to total() running before apply_discounts(); fixing the order.",
+        "demo-agent",
+    )?;
+    db::add_note(
+        &tx,
+        saved_cards,
+        "Payment provider supports card tokens, so we never hold card numbers.",
+        "demo",
+    )?;
+    db::add_note(
+        &tx,
+        reset,
+        "Mail queue worker crashed on deploy and was never restarted; check alerting too.",
+        "demo",
+    )?;
+    db::add_relation(&tx, archived, typo, "related")?;
+    store::close_issue(
+        &tx,
+        review,
+        Some("Design signed off; notes in the checkout doc.".to_string()),
+        false,
+        None,
+    )?;
+    store::close_issue(
+        &tx,
+        dark_mode,
+        Some("Not worth it for one user; revisit if more ask.".to_string()),
+        true,
+        None,
+    )?;
+    let seeded = db::all_issues(&tx)?.len();
+    tx.commit()?;
+    Ok(seeded)
+}
+
+/// The instructions block: the `--agents-template` file, else the
+/// `agents.template` config value, else the built-in text. `{{command}}`
+/// becomes the name itr was run as and `{{db}}` the database file name, so
+/// rerunning `init --agents-md` after either changes refreshes the block.
+fn agents_block(
+    conn: &Connection,
+    template_file: Option<&str>,
+    db_path: &Path,
+) -> Result<String, ItrError> {
+    let template = match template_file {
+        Some(file) => fs::read_to_string(file)?,
+        None => db::config_get(conn, AGENTS_TEMPLATE_KEY)?
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| AGENT_DOCS.to_string()),
+    };
+    let command = env::args_os()
+        .next()
+        .and_then(|arg| {
+            Path::new(&arg)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "itr".to_string());
+    let db_name = db_path.file_name().map_or_else(
+        || ".itr.db".to_string(),
+        |n| n.to_string_lossy().to_string(),
+    );
+    Ok(template
+        .replace("{{command}}", &command)
+        .replace("{{db}}", &db_name))
+}
+
+/// Write `block` into `path` between the itr markers and say what happened:
+/// `created`, `appended`, `updated` (an earlier block replaced), or
+/// `unchanged`. A file holding an unmarked block from an older itr that no
+/// longer matches the built-in text is `skipped` with a `REVIEW:` line, since
+/// its end can't be found safely.
+fn write_agents_block(path: &Path, block: &str) -> Result<&'static str, ItrError> {
+    let marked = format!("{}\n{}\n{}", BLOCK_START, block.trim_end(), BLOCK_END);
+    if !path.exists() {
+        fs::write(path, format!("{}\n", marked))?;
+        return Ok("created");
+    }
+    let content = fs::read_to_string(path)?;
+    let existing = content.find(BLOCK_START).and_then(|start| {
+        content[start..]
+            .find(BLOCK_END)
+            .map(|end| start..start + end + BLOCK_END.len())
+    });
+    let updated = if let Some(range) = existing {
+        let mut updated = content.clone();
+        updated.replace_range(range, &marked);
+        updated
+    } else if content.contains(AGENT_DOCS) {
+        content.replacen(AGENT_DOCS, &format!("{}\n", marked), 1)
+    } else if content.contains("## Issue Tracking") {
+        eprintln!(
+            "REVIEW: {} has itr instructions without {} markers; left as is. Remove them and rerun to get an updatable block",
+            path.display(),
+            BLOCK_START
+        );
+        return Ok("skipped");
     } else {
-        fs::write(&agents_path, AGENT_DOCS)?;
+        let mut appended = content.clone();
+        if !appended.is_empty() && !appended.ends_with('\n') {
+            appended.push('\n');
+        }
+        appended.push('\n');
+        appended.push_str(&marked);
+        appended.push('\n');
+        fs::write(path, appended)?;
+        return Ok("appended");
+    };
+    if updated == content {
+        return Ok("unchanged");
+    }
+    fs::write(path, updated)?;
+    Ok("updated")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demo_seeds_a_connected_project() {
+        let conn = db::open_test_db();
+        let seeded = seed_demo(&conn).unwrap();
+        let issues = db::all_issues(&conn).unwrap();
+        assert_eq!(seeded, issues.len());
+
+        let count =
+            |f: &dyn Fn(&crate::models::Issue) -> bool| issues.iter().filter(|i| f(i)).count();
+        assert_eq!(count(&|i| i.kind == "epic"), 2);
+        assert!(count(&|i| i.parent_id.is_some()) >= 6);
+        for status in ["open", "in-progress", "done", "wontfix"] {
+            assert!(count(&|i| i.status == status) > 0, "no {status} issue");
+        }
+        for priority in ["critical", "high", "medium", "low"] {
+            assert!(
+                count(&|i| i.priority == priority) > 0,
+                "no {priority} issue"
+            );
+        }
+
+        // A three-issue blocking chain: tracing -> benchmark -> typo search.
+        let id = |title: &str| issues.iter().find(|i| i.title == title).unwrap().id;
+        let benchmark = id("Benchmark search latency");
+        assert_eq!(
+            db::get_blockers(&conn, id("Typo-tolerant product search")).unwrap(),
+            vec![benchmark]
+        );
+        assert_eq!(
+            db::get_blockers(&conn, benchmark).unwrap(),
+            vec![id("Add request tracing to the search API")]
+        );
+        assert!(
+            !db::get_notes(&conn, id("Password reset email never arrives"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn agents_block_is_appended_once_then_updated_in_place() {
+        let dir = env::temp_dir().join(format!("itr-init-agents-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("CLAUDE.md");
+        fs::write(&path, "# Notes\n").unwrap();
+
+        assert_eq!(write_agents_block(&path, "first").unwrap(), "appended");
+        assert_eq!(write_agents_block(&path, "first").unwrap(), "unchanged");
+        assert_eq!(write_agents_block(&path, "second").unwrap(), "updated");
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# Notes\n"));
+        assert!(content.contains("second") && !content.contains("first"));
+
+        // A block written before the markers existed is replaced, not doubled.
+        fs::write(&path, format!("# Notes\n\n{}", AGENT_DOCS)).unwrap();
+        assert_eq!(write_agents_block(&path, AGENT_DOCS).unwrap(), "updated");
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content.matches("## Issue Tracking").count(), 1);
+        assert!(content.contains(BLOCK_START));
+
+        fs::write(&path, "## Issue Tracking\nold text\n").unwrap();
+        assert_eq!(write_agents_block(&path, "new").unwrap(), "skipped");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn agents_template_fills_placeholders() {
+        let conn = db::open_test_db();
+        assert_eq!(
+            agents_block(&conn, None, Path::new("/p/.itr.db")).unwrap(),
+            AGENT_DOCS
+        );
+        db::config_set(&conn, AGENTS_TEMPLATE_KEY, "Use {{command}} on {{db}}").unwrap();
+        let block = agents_block(&conn, None, Path::new("/p/team.db")).unwrap();
+        assert!(block.ends_with(" on team.db"), "{block}");
+        assert!(!block.contains("{{command}}"));
     }
-    Ok(())
 }
@@ -0,0 +1,341 @@
+use crate::commands::project;
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::models::{Issue, ListFilter};
+use crate::normalize;
+use crate::util;
+use rusqlite::Connection;
+use std::collections::HashSet;
+
+/// Kinds that must have acceptance criteria.
+pub const ACCEPTANCE_KINDS_KEY: &str = "lint.acceptance_kinds";
+/// Kinds that must reference at least one file.
+pub const FILES_KINDS_KEY: &str = "lint.files_kinds";
+/// Kinds that must have context.
+pub const CONTEXT_KINDS_KEY: &str = "lint.context_kinds";
+/// Longest title allowed, in characters; 0 turns the rule off.
+pub const MAX_TITLE_KEY: &str = "lint.max_title_length";
+/// Whether an epic with no children is a finding.
+pub const CHILDLESS_EPICS_KEY: &str = "lint.childless_epics";
+
+/// Every `lint.*` key with the value used when it is unset.
+pub const SETTINGS: &[(&str, &str)] = &[
+    (ACCEPTANCE_KINDS_KEY, "feature"),
+    (FILES_KINDS_KEY, "bug"),
+    (CONTEXT_KINDS_KEY, "bug,feature,task,epic"),
+    (MAX_TITLE_KEY, "80"),
+    (CHILDLESS_EPICS_KEY, "true"),
+];
+
+/// stderr error code when findings remain, like doctor's.
+const FINDINGS_CODE: &str = "LINT_FINDINGS";
+
+/// One policy violation.
+#[derive(Debug, Clone, serde::Serialize)]
+struct Finding {
+    id: i64,
+    title: String,
+    rule: &'static str,
+    message: String,
+    /// The issue's first listed file, where `-f gha` annotates the finding.
+    #[serde(skip)]
+    file: Option<String>,
+}
+
+/// The configured rules, each already in effect-or-off form.
+#[derive(Debug)]
+struct Policy {
+    acceptance_kinds: Vec<String>,
+    files_kinds: Vec<String>,
+    context_kinds: Vec<String>,
+    max_title: usize,
+    childless_epics: bool,
+}
+
+/// Check a `lint.*` value for `config set`, returning it normalized: kind
+/// lists are normalized and de-duplicated (empty turns the rule off), the
+/// title limit is a count, and `childless_epics` a boolean.
+pub fn validate_setting(key: &str, value: &str) -> Result<String, String> {
+    let value = value.trim();
+    match key {
+        ACCEPTANCE_KINDS_KEY | FILES_KINDS_KEY | CONTEXT_KINDS_KEY => normalize_kind_list(value),
+        MAX_TITLE_KEY => value
+            .parse::<usize>()
+            .map(|n| n.to_string())
+            .map_err(|_| "expected a number of characters (0 disables)".to_string()),
+        CHILDLESS_EPICS_KEY => match value.to_ascii_lowercase().as_str() {
+            "true" | "on" | "yes" | "1" => Ok("true".to_string()),
+            "false" | "off" | "no" | "0" => Ok("false".to_string()),
+            _ => Err("expected true or false".to_string()),
+        },
+        _ => {
+            let keys: Vec<&str> = SETTINGS.iter().map(|(k, _)| *k).collect();
+            let valid = keys.join(", ");
+            Err(format!(
+                "unknown key{} (valid: {})",
+                util::did_you_mean(key, &valid),
+                valid
+            ))
+        }
+    }
+}
+
+/// Normalize a comma-separated kind list, dropping duplicates; an empty list
+/// is valid. Shared with the `policy.require.*` keys.
+pub(crate) fn normalize_kind_list(value: &str) -> Result<String, String> {
+    let mut kinds: Vec<String> = Vec::new();
+    for raw in value.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+        let kind = normalize::normalize_kind(raw);
+        if normalize::validate_kind(&kind).is_err() {
+            return Err(format!(
+                "'{}' is not a kind (valid: bug, feature, task, epic)",
+                raw
+            ));
+        }
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    Ok(kinds.join(","))
+}
+
+/// `itr lint [--all]` — check open and in-progress issues (every issue with
+/// `all`) against the `lint.*` policies and list each violation. Exits 1 with
+/// code `LINT_FINDINGS` when there are any, so CI can gate on it.
+pub fn run(conn: &Connection, all: bool, fmt: Format) -> Result<(), ItrError> {
+    let policy = load_policy(conn);
+    let issues = db::list_issues(
+        conn,
+        &ListFilter {
+            include_blocked: true,
+            all,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    let parents: HashSet<i64> = db::all_issues(conn)?
+        .iter()
+        .filter_map(|i| i.parent_id)
+        .collect();
+    let findings: Vec<Finding> = issues
+        .iter()
+        .flat_map(|issue| check(issue, &policy, &parents))
+        .collect();
+
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "findings": findings,
+                "clean": findings.is_empty(),
+            });
+            format::println_json(&out.to_string());
+        }
+        _ if format::gha(fmt) => {
+            for f in &findings {
+                println!(
+                    "{}",
+                    format::gha_command(
+                        "warning",
+                        f.file.as_deref(),
+                        &format!("itr lint ({})", f.rule),
+                        &format!("#{} {}: {}", f.id, f.message, f.title)
+                    )
+                );
+            }
+            if findings.is_empty() {
+                println!(
+                    "{}",
+                    format::gha_command("notice", None, "itr lint", "All clean")
+                );
+            }
+        }
+        _ => {
+            for f in &findings {
+                println!(
+                    "LINT:{} [{}] {} \"{}\"",
+                    f.id,
+                    f.rule,
+                    f.message,
+                    format::escape_quoted_value(&f.title)
+                );
+            }
+            if findings.is_empty() {
+                println!("LINT: All clean");
+            }
+        }
+    }
+
+    if !findings.is_empty() {
+        let flagged: HashSet<i64> = findings.iter().map(|f| f.id).collect();
+        let msg = format!(
+            "{} lint finding(s) in {} issue(s)",
+            findings.len(),
+            flagged.len()
+        );
+        if fmt.is_json() {
+            eprintln!(
+                "{}",
+                format::error_envelope(serde_json::json!({ "error": msg, "code": FINDINGS_CODE }))
+            );
+        } else {
+            eprintln!("ERROR: {}", msg);
+        }
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// The policy in effect: each key's stored value, or its default when unset
+/// or no longer valid.
+fn load_policy(conn: &Connection) -> Policy {
+    let value = |key: &str| {
+        let default = SETTINGS
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map_or("", |(_, d)| *d);
+        db::config_get(conn, key)
+            .ok()
+            .flatten()
+            .and_then(|raw| validate_setting(key, &raw).ok())
+            .unwrap_or_else(|| default.to_string())
+    };
+    let kinds = |key: &str| -> Vec<String> {
+        value(key)
+            .split(',')
+            .filter(|k| !k.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    Policy {
+        acceptance_kinds: kinds(ACCEPTANCE_KINDS_KEY),
+        files_kinds: kinds(FILES_KINDS_KEY),
+        context_kinds: kinds(CONTEXT_KINDS_KEY),
+        max_title: value(MAX_TITLE_KEY).parse().unwrap_or(0),
+        childless_epics: value(CHILDLESS_EPICS_KEY) == "true",
+    }
+}
+
+fn check(issue: &Issue, policy: &Policy, parents: &HashSet<i64>) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut flag = |rule: &'static str, message: String| {
+        findings.push(Finding {
+            id: issue.id,
+            title: issue.title.clone(),
+            rule,
+            message,
+            file: issue.files.first().cloned(),
+        });
+    };
+    let kind = issue.kind.as_str();
+    if policy.acceptance_kinds.iter().any(|k| k == kind) && issue.acceptance.trim().is_empty() {
+        flag(
+            "missing-acceptance",
+            format!("{} has no acceptance criteria", kind),
+        );
+    }
+    if policy.files_kinds.iter().any(|k| k == kind) && issue.files.is_empty() {
+        flag("missing-files", format!("{} references no files", kind));
+    }
+    if policy.context_kinds.iter().any(|k| k == kind) && issue.context.trim().is_empty() {
+        flag("empty-context", format!("{} has no context", kind));
+    }
+    let length = issue.title.chars().count();
+    if policy.max_title > 0 && length > policy.max_title {
+        flag(
+            "long-title",
+            format!("title is {} chars (max {})", length, policy.max_title),
+        );
+    }
+    if policy.childless_epics && kind == "epic" && !parents.contains(&issue.id) {
+        flag("childless-epic", "epic has no children".to_string());
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: i64, kind: &str, title: &str) -> Issue {
+        Issue {
+            id,
+            title: title.to_string(),
+            status: "open".to_string(),
+            priority: "medium".to_string(),
+            kind: kind.to_string(),
+            context: String::new(),
+            files: vec![],
+            tags: vec![],
+            skills: vec![],
+            acceptance: String::new(),
+            parent_id: None,
+            assigned_to: String::new(),
+            close_reason: String::new(),
+            branch: String::new(),
+            project: String::new(),
+            severity: String::new(),
+            component: String::new(),
+            affects_version: String::new(),
+            fixed_in_version: String::new(),
+            estimate: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn rules(findings: &[Finding]) -> Vec<&str> {
+        findings.iter().map(|f| f.rule).collect()
+    }
+
+    #[test]
+    fn default_policy_flags_each_rule_by_kind() {
+        let conn = db::open_test_db();
+        let policy = load_policy(&conn);
+        let parents: HashSet<i64> = [3].into_iter().collect();
+
+        let feature = issue(1, "feature", "Add login");
+        assert_eq!(
+            rules(&check(&feature, &policy, &parents)),
+            vec!["missing-acceptance", "empty-context"]
+        );
+        let mut bug = issue(2, "bug", &"x".repeat(81));
+        bug.context = "Steps".to_string();
+        assert_eq!(
+            rules(&check(&bug, &policy, &parents)),
+            vec!["missing-files", "long-title"]
+        );
+        let mut epic = issue(3, "epic", "Auth");
+        epic.context = "Why".to_string();
+        assert!(check(&epic, &policy, &parents).is_empty());
+        epic.id = 4;
+        assert_eq!(
+            rules(&check(&epic, &policy, &parents)),
+            vec!["childless-epic"]
+        );
+    }
+
+    #[test]
+    fn configured_policy_overrides_defaults() {
+        let conn = db::open_test_db();
+        db::config_set(&conn, ACCEPTANCE_KINDS_KEY, "bug").unwrap();
+        db::config_set(&conn, CONTEXT_KINDS_KEY, "").unwrap();
+        db::config_set(&conn, MAX_TITLE_KEY, "0").unwrap();
+        db::config_set(&conn, CHILDLESS_EPICS_KEY, "false").unwrap();
+        let policy = load_policy(&conn);
+        let parents = HashSet::new();
+
+        assert!(check(&issue(1, "feature", "Add login"), &policy, &parents).is_empty());
+        assert!(check(&issue(2, "epic", &"x".repeat(200)), &policy, &parents).is_empty());
+        assert_eq!(
+            rules(&check(&issue(3, "bug", "Crash"), &policy, &parents)),
+            vec!["missing-acceptance", "missing-files"]
+        );
+        assert_eq!(
+            validate_setting(FILES_KINDS_KEY, "Bug, story, bug").as_deref(),
+            Ok("bug,feature")
+        );
+        assert!(validate_setting(FILES_KINDS_KEY, "widget").is_err());
+        assert!(validate_setting("lint.nope", "1").is_err());
+    }
+}
@@ -1,10 +1,11 @@
-use super::{build_issue_summary_owned, sort_by_urgency_desc};
+use super::{build_issue_summaries, sort_by_urgency_desc};
 use crate::db;
 use crate::error::{self, ItrError};
 use crate::format::{self, Format};
 use crate::models::{IssueSummary, ListFilter};
 use crate::normalize;
 use crate::urgency::UrgencyConfig;
+use crate::util;
 use rusqlite::Connection;
 
 pub fn run(
@@ -12,32 +13,131 @@ pub fn run(
     filter: &ListFilter,
     sort: &str,
     limit: Option<usize>,
+    offset: Option<usize>,
     fmt: Format,
 ) -> Result<(), ItrError> {
-    let mut summaries = collect_summaries(conn, filter)?;
+    // Column sorts run in SQL together with limit/offset, so only the
+    // requested page is fetched and summarized. Urgency (and unrecognized
+    // keys, which fall back to it) needs every score before truncating.
+    let summaries = if db::SQL_SORTS.contains(&sort) {
+        let filter = ListFilter {
+            sort: Some(sort.to_string()),
+            limit,
+            offset,
+            ..filter.clone()
+        };
+        collect_summaries(conn, &filter)?
+    } else {
+        let mut summaries = collect_summaries(conn, filter)?;
+        sort_summaries(&mut summaries, sort);
+        summaries
+            .into_iter()
+            .skip(offset.unwrap_or(0))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect()
+    };
 
     if summaries.is_empty() {
         error::print_empty(fmt.is_json(), "No matching issues found.");
         return Ok(());
     }
 
-    sort_summaries(&mut summaries, sort);
+    format::print_output(&format::format_issue_list(&summaries, fmt), fmt);
+    Ok(())
+}
 
-    // Limit
-    if let Some(n) = limit {
-        summaries.truncate(n);
+/// Prefix of the config keys that fill in omitted `itr list` flags
+/// (`defaults.list.status=open`, `defaults.list.limit=20`, ...).
+pub const DEFAULTS_PREFIX: &str = "defaults.list.";
+/// Flags a `defaults.list.<flag>` key can stand in for. List-valued ones
+/// take a comma-separated value.
+pub const DEFAULT_FLAGS: &[&str] = &[
+    "status",
+    "priority",
+    "kind",
+    "severity",
+    "component",
+    "affects-version",
+    "fixed-in-version",
+    "tag",
+    "skill",
+    "assigned-to",
+    "sort",
+    "limit",
+];
+/// Accepted `--sort` keys.
+pub const SORTS: &[&str] = &["urgency", "priority", "created", "updated", "id"];
+
+/// Fill every filter the caller left unset from `defaults.list.*`, and
+/// resolve the sort key and limit the same way. A flag on the command line
+/// always wins; `--all` also overrides a status default.
+pub fn apply_defaults(
+    conn: &Connection,
+    filter: &mut ListFilter,
+    sort: Option<String>,
+    limit: Option<usize>,
+) -> Result<(String, Option<usize>), ItrError> {
+    let default = |flag: &str| db::config_get(conn, &format!("{}{}", DEFAULTS_PREFIX, flag));
+    let split = |value: String| -> Vec<String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+
+    for (flag, values) in [
+        ("status", &mut filter.statuses),
+        ("priority", &mut filter.priorities),
+        ("kind", &mut filter.kinds),
+        ("severity", &mut filter.severities),
+        ("component", &mut filter.components),
+        ("affects-version", &mut filter.affects_versions),
+        ("fixed-in-version", &mut filter.fixed_in_versions),
+        ("tag", &mut filter.tags),
+        ("skill", &mut filter.skills),
+    ] {
+        if values.is_empty() && !(flag == "status" && filter.all) {
+            if let Some(value) = default(flag)? {
+                *values = split(value);
+            }
+        }
+    }
+    if filter.assigned_to.is_none() {
+        filter.assigned_to = default("assigned-to")?.filter(|v| !v.trim().is_empty());
     }
 
-    println!("{}", format::format_issue_list(&summaries, fmt));
-    Ok(())
+    let sort = match sort {
+        Some(sort) => sort,
+        None => default("sort")?.unwrap_or_else(|| "urgency".to_string()),
+    };
+    let limit = match limit {
+        Some(limit) => Some(limit),
+        None => match default("limit")? {
+            Some(value) => match value.trim().parse::<usize>() {
+                Ok(n) => Some(n),
+                Err(_) => {
+                    eprintln!(
+                        "REVIEW: '{}' for '{}limit' is not a count; ignored",
+                        value, DEFAULTS_PREFIX
+                    );
+                    None
+                }
+            },
+            None => None,
+        },
+    };
+    Ok((sort, limit))
 }
 
 /// Fetch and summarize the issues matching `filter`.
 ///
-/// Status/priority/kind filter values are normalized with the same synonym
+/// Status/priority/kind/severity filter values are normalized with the same synonym
 /// tables as the write paths (`wip` → `in-progress`, `closed` → `done`, ...),
 /// and values still unrecognized after normalization emit a REVIEW note
-/// instead of silently matching nothing (#168).
+/// instead of silently matching nothing (#168); so do unregistered
+/// component names.
 fn collect_summaries(
     conn: &Connection,
     filter: &ListFilter,
@@ -45,31 +145,36 @@ fn collect_summaries(
     let (statuses, status_notes) = normalize::normalize_status_filters(&filter.statuses);
     let (priorities, priority_notes) = normalize::normalize_priority_filters(&filter.priorities);
     let (kinds, kind_notes) = normalize::normalize_kind_filters(&filter.kinds);
+    let (severities, severity_notes) = normalize::normalize_severity_filters(&filter.severities);
     for note in status_notes
         .iter()
         .chain(&priority_notes)
         .chain(&kind_notes)
+        .chain(&severity_notes)
     {
         eprintln!("{}", note);
     }
+    for name in &filter.components {
+        if db::get_component(conn, name)?.is_none() {
+            eprintln!(
+                "{}",
+                crate::store::unknown_component_note(conn, name, "so it matches nothing")?
+            );
+        }
+    }
 
     let filter = ListFilter {
         statuses,
         priorities,
         kinds,
+        severities,
         ..filter.clone()
     };
 
     let issues = db::list_issues(conn, &filter)?;
     let config = UrgencyConfig::load(conn);
 
-    // Consume issues by value so build_issue_summary_owned can move each
-    // Issue's string/vec fields directly into the resulting IssueSummary
-    // rather than cloning them.
-    Ok(issues
-        .into_iter()
-        .map(|i| build_issue_summary_owned(conn, i, &config))
-        .collect())
+    Ok(build_issue_summaries(conn, issues, &config))
 }
 
 /// Sort summaries in place by the requested key.
@@ -101,8 +206,10 @@ fn sort_summaries(summaries: &mut [IssueSummary], sort: &str) {
         "id" => summaries.sort_by_key(|s| s.id),
         other => {
             eprintln!(
-                "REVIEW: sort '{}' not recognized, defaulted to 'urgency'. Valid: urgency, priority, created, updated, id",
-                other
+                "REVIEW: sort '{}' not recognized{}, defaulted to 'urgency'. Valid: {}",
+                other,
+                util::did_you_mean(other, &SORTS.join(", ")),
+                SORTS.join(", ")
             );
             sort_by_urgency_desc(summaries);
         }
@@ -158,6 +265,12 @@ mod tests {
             context: String::new(),
             parent_id: None,
             close_reason: String::new(),
+            branch: String::new(),
+            project: String::new(),
+            severity: String::new(),
+            component: String::new(),
+            affects_version: String::new(),
+            fixed_in_version: String::new(),
             blocks: vec![],
             assigned_to: String::new(),
             created_at: created_at.to_string(),
@@ -169,6 +282,38 @@ mod tests {
         summaries.iter().map(|s| s.id).collect()
     }
 
+    #[test]
+    fn list_defaults_fill_only_omitted_flags() {
+        let conn = db::open_test_db();
+        db::config_set(&conn, "defaults.list.status", "open, in-progress").unwrap();
+        db::config_set(&conn, "defaults.list.sort", "id").unwrap();
+        db::config_set(&conn, "defaults.list.limit", "20").unwrap();
+
+        let mut filter = ListFilter::default();
+        let (sort, limit) = apply_defaults(&conn, &mut filter, None, None).unwrap();
+        assert_eq!(filter.statuses, vec!["open", "in-progress"]);
+        assert_eq!((sort.as_str(), limit), ("id", Some(20)));
+
+        let mut filter = ListFilter {
+            statuses: vec!["done".to_string()],
+            ..ListFilter::default()
+        };
+        let (sort, limit) =
+            apply_defaults(&conn, &mut filter, Some("created".to_string()), Some(5)).unwrap();
+        assert_eq!(filter.statuses, vec!["done"]);
+        assert_eq!((sort.as_str(), limit), ("created", Some(5)));
+
+        let mut filter = ListFilter {
+            all: true,
+            ..ListFilter::default()
+        };
+        apply_defaults(&conn, &mut filter, None, None).unwrap();
+        assert!(
+            filter.statuses.is_empty(),
+            "--all overrides a status default"
+        );
+    }
+
     // --- #168: list filters accept the same synonyms as write paths ---
 
     #[test]
@@ -263,6 +408,46 @@ mod tests {
         assert_eq!(ids(&summaries), vec![2, 3, 1]);
     }
 
+    #[test]
+    fn sql_column_sorts_page_like_the_in_memory_sort() {
+        let conn = db::open_test_db();
+        let stamps = [
+            "2026-01-02T00:00:00Z",
+            "2026-01-01T00:00:00Z",
+            "2026-01-02T00:00:00Z",
+            "2026-01-04T00:00:00Z",
+        ];
+        for (i, ts) in stamps.iter().enumerate() {
+            let id = insert_issue(&conn, &format!("issue {}", i));
+            conn.execute(
+                "UPDATE issues SET created_at = ?2 WHERE id = ?1",
+                rusqlite::params![id, ts],
+            )
+            .unwrap();
+        }
+        db::update_issue_field(&conn, 3, "priority", "critical").unwrap();
+
+        let base = ListFilter {
+            include_blocked: true,
+            ..ListFilter::default()
+        };
+        for sort in db::SQL_SORTS {
+            let mut expected = collect_summaries(&conn, &base).unwrap();
+            sort_summaries(&mut expected, sort);
+            let page = collect_summaries(
+                &conn,
+                &ListFilter {
+                    sort: Some((*sort).to_string()),
+                    limit: Some(2),
+                    offset: Some(1),
+                    ..base.clone()
+                },
+            )
+            .unwrap();
+            assert_eq!(ids(&page), ids(&expected[1..3]), "sort {}", sort);
+        }
+    }
+
     #[test]
     fn unknown_sort_falls_back_to_urgency() {
         let mut a = summary(1, "2026-01-01T00:00:00Z", "2026-01-01T00:00:00Z");
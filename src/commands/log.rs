@@ -19,7 +19,7 @@ pub fn run(
         return Ok(());
     }
 
-    println!("{}", format::format_events(&events, fmt));
+    format::print_output(&format::format_events(&events, fmt), fmt);
     Ok(())
 }
 
@@ -0,0 +1,355 @@
+use super::repro;
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::models::{Issue, Note, Repro};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// How one issue from the other database landed here.
+#[derive(Debug, Serialize, PartialEq)]
+struct Mapping {
+    from: i64,
+    to: i64,
+    /// `added` (copied under a new ID) or `duplicate` (same title and
+    /// `created_at` as an existing issue, which is kept as is).
+    action: &'static str,
+    title: String,
+}
+
+#[derive(Debug, Default)]
+struct MergeReport {
+    mappings: Vec<Mapping>,
+    notes: usize,
+    dependencies: usize,
+    /// `(blocker, blocked)` edges, in local IDs, that would close a cycle.
+    cycles: Vec<(i64, i64)>,
+    dropped_relations: usize,
+}
+
+/// `itr merge-db <other.db>` — copy issues, notes, and dependencies from
+/// another itr database into this one.
+pub fn run(conn: &Connection, db_path: &Path, other: &str, fmt: Format) -> Result<(), ItrError> {
+    let other_path = db::db_path_for(other);
+    if !other_path.is_file() {
+        return Err(ItrError::InvalidValue {
+            field: "database".to_string(),
+            value: other.to_string(),
+            valid: "an existing .itr.db file, or a directory containing one".to_string(),
+        });
+    }
+    if same_file(&other_path, db_path) {
+        return Err(ItrError::InvalidValue {
+            field: "database".to_string(),
+            value: other.to_string(),
+            valid: "a database other than the one being merged into".to_string(),
+        });
+    }
+    // open_db brings an older database up to the current schema first, the
+    // same as running any itr command against it.
+    let source = db::open_db(&other_path)?;
+    let report = merge(conn, &source)?;
+
+    if !report.cycles.is_empty() {
+        let edges: Vec<String> = report
+            .cycles
+            .iter()
+            .map(|(blocker, blocked)| format!("{} BLOCKED_BY:{}", blocked, blocker))
+            .collect();
+        eprintln!(
+            "REVIEW: merge-db skipped {} dependency edge(s) that would create a cycle here: {}",
+            edges.len(),
+            edges.join(", ")
+        );
+    }
+    if report.dropped_relations > 0 {
+        eprintln!(
+            "REVIEW: merge-db does not copy relations ({} row(s) skipped); re-add them with `itr relate` using the mapped IDs.",
+            report.dropped_relations
+        );
+    }
+
+    let added = report
+        .mappings
+        .iter()
+        .filter(|m| m.action == "added")
+        .count();
+    let duplicates = report.mappings.len() - added;
+    match fmt {
+        Format::Json => format::print_json(
+            &serde_json::json!({
+                "action": "merge-db",
+                "source": other_path.display().to_string(),
+                "added": added,
+                "duplicates": duplicates,
+                "notes": report.notes,
+                "dependencies": report.dependencies,
+                "mapping": report.mappings,
+            })
+            .to_string(),
+        ),
+        _ => {
+            for m in &report.mappings {
+                println!(
+                    "MAP:{} -> {} {} TITLE:{}",
+                    m.from,
+                    m.to,
+                    m.action.to_uppercase(),
+                    m.title
+                );
+            }
+            println!(
+                "MERGE: {} added, {} duplicate, {} note(s), {} dependency(ies)",
+                added, duplicates, report.notes, report.dependencies
+            );
+        }
+    }
+    Ok(())
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Core merge, separated from I/O so it is unit-testable.
+///
+/// Issues keep every field except their ID: each one gets the next free ID
+/// here, and `parent_id` and dependency edges are rewritten through the
+/// mapping. An issue whose title and `created_at` match an existing issue is
+/// the same issue seen from another checkout, so it maps onto that issue and
+/// only contributes notes the existing issue doesn't already have.
+fn merge(conn: &Connection, source: &Connection) -> Result<MergeReport, ItrError> {
+    let tx = db::begin_write(conn)?;
+    let mut report = MergeReport::default();
+
+    let existing: HashMap<(String, String), i64> = db::all_issues(&tx)?
+        .into_iter()
+        .map(|i| ((i.title, i.created_at), i.id))
+        .collect();
+
+    let incoming = db::all_issues(source)?;
+    let mut ids: BTreeMap<i64, i64> = BTreeMap::new();
+    for issue in &incoming {
+        let key = (issue.title.clone(), issue.created_at.clone());
+        let (to, action) = match existing.get(&key) {
+            Some(&id) => (id, "duplicate"),
+            None => {
+                let parent = issue.parent_id.and_then(|p| ids.get(&p).copied());
+                let repro = db::issue_repro(source, issue.id)?.unwrap_or_default();
+                (insert(&tx, issue, &repro, parent)?, "added")
+            }
+        };
+        ids.insert(issue.id, to);
+        report.mappings.push(Mapping {
+            from: issue.id,
+            to,
+            action,
+            title: issue.title.clone(),
+        });
+    }
+
+    // A child whose parent has a higher ID (re-parented later) is linked
+    // once every issue has its new ID.
+    for (m, issue) in report.mappings.iter().zip(&incoming) {
+        let Some(parent) = issue.parent_id else {
+            continue;
+        };
+        if m.action == "added" && parent > issue.id {
+            if let Some(&new_parent) = ids.get(&parent) {
+                db::update_issue_parent(&tx, m.to, Some(new_parent))?;
+            }
+        }
+    }
+
+    let mut notes_by_issue: HashMap<i64, Vec<Note>> = HashMap::new();
+    for note in db::all_notes(source)? {
+        notes_by_issue.entry(note.issue_id).or_default().push(note);
+    }
+    for (&from, &to) in &ids {
+        let Some(notes) = notes_by_issue.get(&from) else {
+            continue;
+        };
+        let have: HashSet<(String, String)> = db::get_notes(&tx, to)?
+            .into_iter()
+            .map(|n| (n.content, n.created_at))
+            .collect();
+        for note in notes {
+            if have.contains(&(note.content.clone(), note.created_at.clone())) {
+                continue;
+            }
+            tx.execute(
+                "INSERT INTO notes (issue_id, content, agent, created_at, kind) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![to, note.content, note.agent, note.created_at, note.kind],
+            )?;
+            report.notes += 1;
+        }
+    }
+
+    for (blocker, blocked) in db::all_dependencies(source)? {
+        let (Some(&blocker), Some(&blocked)) = (ids.get(&blocker), ids.get(&blocked)) else {
+            continue;
+        };
+        match db::add_dependency(&tx, blocker, blocked) {
+            Ok(true) => report.dependencies += 1,
+            Ok(false) => {}
+            Err(ItrError::CycleDetected(_)) => report.cycles.push((blocker, blocked)),
+            Err(e) => return Err(e),
+        }
+    }
+
+    report.dropped_relations = db::all_relations(source)?.len();
+    db::refresh_all_references(&tx)?;
+    tx.commit()?;
+    Ok(report)
+}
+
+/// Copy one issue, with its reproduction, under a fresh ID and index it.
+fn insert(
+    conn: &Connection,
+    issue: &Issue,
+    repro: &Repro,
+    parent_id: Option<i64>,
+) -> Result<i64, ItrError> {
+    conn.execute(
+        "INSERT INTO issues (title, status, priority, kind, context, files, tags, skills, acceptance, parent_id, close_reason, created_at, updated_at, assigned_to, branch, project_id, severity, affects_version, fixed_in_version, component_id, estimate, repro)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)",
+        params![
+            issue.title,
+            issue.status,
+            issue.priority,
+            issue.kind,
+            issue.context,
+            serde_json::to_string(&issue.files)?,
+            serde_json::to_string(&issue.tags)?,
+            serde_json::to_string(&issue.skills)?,
+            issue.acceptance,
+            parent_id,
+            issue.close_reason,
+            issue.created_at,
+            issue.updated_at,
+            issue.assigned_to,
+            issue.branch,
+            db::ensure_project(conn, &issue.project)?,
+            issue.severity,
+            issue.affects_version,
+            issue.fixed_in_version,
+            db::ensure_component(conn, &issue.component)?,
+            issue.estimate,
+            repro::encode(repro)?,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    db::fts_index_issue(conn, &db::get_issue(conn, id)?);
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_remaps_ids_and_detects_duplicates() {
+        let local = db::init_db(Path::new(":memory:")).unwrap();
+        let other = db::init_db(Path::new(":memory:")).unwrap();
+
+        let shared = db::insert_test_issue(&local, "shared");
+        db::insert_test_issue(&local, "local only");
+        let created_at = db::get_issue(&local, shared).unwrap().created_at;
+
+        let epic = db::insert_test_issue(&other, "epic");
+        let other_shared = db::insert_test_issue(&other, "shared");
+        other
+            .execute(
+                "UPDATE issues SET created_at = ?1 WHERE id = ?2",
+                params![created_at, other_shared],
+            )
+            .unwrap();
+        let child = db::insert_test_issue_with(&other, "child", "task", Some(epic));
+        db::add_dependency(&other, other_shared, child).unwrap();
+        db::add_note(&other, other_shared, "seen elsewhere", "").unwrap();
+
+        let report = merge(&local, &other).unwrap();
+        let mapped: Vec<(i64, i64, &str)> = report
+            .mappings
+            .iter()
+            .map(|m| (m.from, m.to, m.action))
+            .collect();
+        assert_eq!(
+            mapped,
+            vec![
+                (epic, 3, "added"),
+                (other_shared, shared, "duplicate"),
+                (child, 4, "added"),
+            ]
+        );
+        assert_eq!(db::get_issue(&local, 4).unwrap().parent_id, Some(3));
+        assert_eq!(db::get_blockers(&local, 4).unwrap(), vec![shared]);
+        assert_eq!(db::get_notes(&local, shared).unwrap().len(), 1);
+        assert_eq!((report.notes, report.dependencies), (1, 1));
+
+        // Merging again finds every issue and note already present.
+        let again = merge(&local, &other).unwrap();
+        assert!(again.mappings.iter().all(|m| m.action == "duplicate"));
+        assert_eq!((again.notes, again.dependencies), (0, 0));
+    }
+
+    #[test]
+    fn merge_copies_issue_fields() {
+        let local = db::init_db(Path::new(":memory:")).unwrap();
+        let other = db::init_db(Path::new(":memory:")).unwrap();
+        let id = db::insert_test_issue(&other, "full");
+        db::update_issue_field(&other, id, "severity", "major").unwrap();
+        db::update_issue_field(&other, id, "affects_version", "1.2").unwrap();
+        db::update_issue_field(&other, id, "fixed_in_version", "1.3").unwrap();
+        db::ensure_component(&other, "api").unwrap();
+        db::set_issue_component(&other, id, "api").unwrap();
+        db::set_estimate(&other, id, Some(3)).unwrap();
+        let repro = Repro {
+            steps: vec!["run it".to_string()],
+            ..Repro::default()
+        };
+        db::set_repro(&other, id, Some(&repro)).unwrap();
+
+        merge(&local, &other).unwrap();
+        assert_eq!(db::issue_repro(&local, 1).unwrap(), Some(repro));
+        let copy = db::get_issue(&local, 1).unwrap();
+        assert_eq!(copy.component, "api");
+        assert_eq!(copy.estimate, Some(3));
+        assert_eq!(copy.severity, "major");
+        assert_eq!(
+            (
+                copy.affects_version.as_str(),
+                copy.fixed_in_version.as_str()
+            ),
+            ("1.2", "1.3")
+        );
+    }
+
+    #[test]
+    fn merge_skips_edges_that_would_cycle() {
+        let local = db::init_db(Path::new(":memory:")).unwrap();
+        let other = db::init_db(Path::new(":memory:")).unwrap();
+        let a = db::insert_test_issue(&local, "a");
+        let b = db::insert_test_issue(&local, "b");
+        db::add_dependency(&local, a, b).unwrap();
+        for (title, id) in [("a", a), ("b", b)] {
+            let created_at = db::get_issue(&local, id).unwrap().created_at;
+            other
+                .execute(
+                    "INSERT INTO issues (title, created_at) VALUES (?1, ?2)",
+                    params![title, created_at],
+                )
+                .unwrap();
+        }
+        db::add_dependency(&other, 2, 1).unwrap();
+
+        let report = merge(&local, &other).unwrap();
+        assert_eq!(report.cycles, vec![(b, a)]);
+        assert_eq!(report.dependencies, 0);
+    }
+}
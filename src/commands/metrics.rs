@@ -0,0 +1,216 @@
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::models::is_terminal;
+use crate::urgency::{self, UrgencyConfig};
+use crate::util;
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// One metric family: every sample shares the name, help, and label key.
+struct Family {
+    name: &'static str,
+    help: &'static str,
+    label: Option<&'static str>,
+    samples: Vec<(String, f64)>,
+}
+
+/// One exported value, as printed by `itr metrics -f json`.
+#[derive(Debug, Serialize)]
+struct Sample<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    labels: serde_json::Map<String, serde_json::Value>,
+    value: f64,
+}
+
+pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
+    let families = collect(conn)?;
+    if fmt.is_json() {
+        let samples: Vec<Sample> = families
+            .iter()
+            .flat_map(|family| {
+                family.samples.iter().map(|(label, value)| Sample {
+                    name: family.name,
+                    labels: family
+                        .label
+                        .map(|key| (key.to_string(), serde_json::Value::from(label.as_str())))
+                        .into_iter()
+                        .collect(),
+                    value: *value,
+                })
+            })
+            .collect();
+        format::print_json(&serde_json::to_string(&samples)?);
+    } else {
+        print!("{}", exposition(&families));
+    }
+    Ok(())
+}
+
+/// The Prometheus text exposition of the current project, for `GET /metrics`.
+pub(crate) fn render(conn: &Connection) -> Result<String, ItrError> {
+    Ok(exposition(&collect(conn)?))
+}
+
+/// Gauges over the whole issue table. Only counts, ages, and scores are
+/// exported — never titles or other issue text.
+fn collect(conn: &Connection) -> Result<Vec<Family>, ItrError> {
+    let issues = db::all_issues(conn)?;
+    let config = UrgencyConfig::load(conn);
+    let relations = db::load_issue_relations(conn)?;
+
+    let statuses = ["open", "in-progress", "review", "done", "wontfix"];
+    let priorities = ["critical", "high", "medium", "low"];
+    let mut by_status = vec![0.0; statuses.len()];
+    let mut by_priority = vec![0.0; priorities.len()];
+    let (mut active, mut blocked, mut ready) = (0.0, 0.0, 0.0);
+    let mut urgency_sum = 0.0;
+    let mut oldest_days: f64 = 0.0;
+
+    for issue in &issues {
+        if let Some(i) = statuses.iter().position(|s| *s == issue.status) {
+            by_status[i] += 1.0;
+        }
+        if is_terminal(&issue.status) {
+            continue;
+        }
+        active += 1.0;
+        if let Some(i) = priorities.iter().position(|p| *p == issue.priority) {
+            by_priority[i] += 1.0;
+        }
+        if relations.is_blocked(issue.id) {
+            blocked += 1.0;
+        } else {
+            ready += 1.0;
+        }
+        let (score, _) = urgency::compute_urgency_from_inputs(
+            issue,
+            &config,
+            relations.urgency_inputs(issue.id),
+        );
+        urgency_sum += score;
+        oldest_days = oldest_days.max(util::days_since(&issue.created_at));
+    }
+
+    let labelled = |names: &[&str], values: Vec<f64>| -> Vec<(String, f64)> {
+        names.iter().map(ToString::to_string).zip(values).collect()
+    };
+    let single = |value: f64| vec![(String::new(), value)];
+    // Four decimals, like `stats`, so scrapes of an unchanged project match.
+    let avg_urgency = if active > 0.0 {
+        (urgency_sum / active * 10_000.0).round() / 10_000.0
+    } else {
+        0.0
+    };
+
+    Ok(vec![
+        Family {
+            name: "itr_issues",
+            help: "Issues by status.",
+            label: Some("status"),
+            samples: labelled(&statuses, by_status),
+        },
+        Family {
+            name: "itr_open_issues",
+            help: "Open, in-progress, and review issues.",
+            label: None,
+            samples: single(active),
+        },
+        Family {
+            name: "itr_open_issues_by_priority",
+            help: "Open, in-progress, and review issues by priority.",
+            label: Some("priority"),
+            samples: labelled(&priorities, by_priority),
+        },
+        Family {
+            name: "itr_blocked_issues",
+            help: "Open, in-progress, and review issues with an unfinished blocker.",
+            label: None,
+            samples: single(blocked),
+        },
+        Family {
+            name: "itr_ready_issues",
+            help: "Open, in-progress, and review issues with no unfinished blocker.",
+            label: None,
+            samples: single(ready),
+        },
+        Family {
+            name: "itr_oldest_open_issue_age_seconds",
+            help: "Age of the oldest open, in-progress, or review issue (0 when there are none).",
+            label: None,
+            samples: single((oldest_days * 86_400.0).round()),
+        },
+        Family {
+            name: "itr_urgency_average",
+            help: "Mean urgency score of open, in-progress, and review issues.",
+            label: None,
+            samples: single(avg_urgency),
+        },
+    ])
+}
+
+/// Prometheus text format 0.0.4: `# HELP`/`# TYPE` then one line per sample.
+fn exposition(families: &[Family]) -> String {
+    let mut out = String::new();
+    for family in families {
+        out.push_str(&format!("# HELP {} {}\n", family.name, family.help));
+        out.push_str(&format!("# TYPE {} gauge\n", family.name));
+        for (label, value) in &family.samples {
+            match family.label {
+                Some(key) => out.push_str(&format!(
+                    "{}{{{}=\"{}\"}} {}\n",
+                    family.name, key, label, value
+                )),
+                None => out.push_str(&format!("{} {}\n", family.name, value)),
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn insert(conn: &Connection, title: &str, priority: &str) -> i64 {
+        let id = db::insert_test_issue(conn, title);
+        db::update_issue_field(conn, id, "priority", priority).unwrap();
+        id
+    }
+
+    #[test]
+    fn exposition_reports_counts_without_issue_text() {
+        let conn = db::init_db(Path::new(":memory:")).unwrap();
+        let blocker = insert(&conn, "secret blocker", "high");
+        let blocked = insert(&conn, "secret blocked", "low");
+        let done = insert(&conn, "secret done", "low");
+        db::add_dependency(&conn, blocker, blocked).unwrap();
+        db::update_issue_field(&conn, done, "status", "done").unwrap();
+
+        let text = render(&conn).unwrap();
+        for line in [
+            "# TYPE itr_issues gauge",
+            "itr_issues{status=\"open\"} 2",
+            "itr_issues{status=\"done\"} 1",
+            "itr_open_issues 2",
+            "itr_open_issues_by_priority{priority=\"high\"} 1",
+            "itr_open_issues_by_priority{priority=\"critical\"} 0",
+            "itr_blocked_issues 1",
+            "itr_ready_issues 1",
+            "itr_oldest_open_issue_age_seconds 0",
+        ] {
+            assert!(
+                text.lines().any(|l| l == line),
+                "missing {line:?} in\n{text}"
+            );
+        }
+        assert!(!text.contains("secret"));
+        // Every sample line is `name[{labels}] value`.
+        for line in text.lines().filter(|l| !l.starts_with('#')) {
+            let (_, value) = line.rsplit_once(' ').unwrap();
+            value.parse::<f64>().unwrap();
+        }
+    }
+}
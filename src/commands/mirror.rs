@@ -0,0 +1,715 @@
+use super::{repro, sync};
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::models::{Issue, Repro};
+use rusqlite::Connection;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where `mirror write` and `mirror read` look by default, relative to the
+/// directory holding the database.
+pub const DEFAULT_PATH: &str = ".itr/issues.jsonl";
+
+/// Machine-readable code reported when `mirror merge` leaves conflicts. Like
+/// `DOCTOR_PROBLEMS_REMAIN`, a merge outcome rather than an `ItrError`.
+const CONFLICTS_CODE: &str = "MIRROR_CONFLICTS";
+
+/// One issue per line: the issue row without `updated_at` (it changes on
+/// every read and would conflict on every merge), then its UUID, key,
+/// reproduction, blockers, and notes.
+type Record = Map<String, Value>;
+
+/// Records by issue ID.
+type Records = BTreeMap<i64, Record>;
+
+fn resolve(db_path: &Path, path: Option<&str>) -> PathBuf {
+    match path {
+        Some(p) => PathBuf::from(p),
+        None => db_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(DEFAULT_PATH),
+    }
+}
+
+fn record(conn: &Connection, issue: &Issue) -> Result<Record, ItrError> {
+    let Value::Object(mut map) = serde_json::to_value(issue)? else {
+        unreachable!("an issue serializes to an object");
+    };
+    map.shift_remove("updated_at");
+    map.insert(
+        "uuid".to_string(),
+        Value::String(db::issue_uuid(conn, issue.id)?),
+    );
+    let key = db::issue_key(conn, issue.id)?;
+    if !key.is_empty() {
+        map.insert("key".to_string(), Value::String(key));
+    }
+    if let Some(repro) = db::issue_repro(conn, issue.id)? {
+        map.insert("repro".to_string(), serde_json::to_value(repro)?);
+    }
+    let mut blocked_by = db::get_blockers(conn, issue.id)?;
+    blocked_by.sort_unstable();
+    map.insert("blocked_by".to_string(), serde_json::to_value(blocked_by)?);
+    let notes: Vec<Value> = db::get_notes(conn, issue.id)?
+        .into_iter()
+        .map(|n| {
+            serde_json::json!({
+                "content": n.content,
+                "agent": n.agent,
+                "created_at": n.created_at,
+            })
+        })
+        .collect();
+    map.insert("notes".to_string(), Value::Array(sort_notes(notes)));
+    Ok(map)
+}
+
+/// Notes in a stable order that doesn't depend on note IDs.
+fn sort_notes(mut notes: Vec<Value>) -> Vec<Value> {
+    notes.sort_by(|a, b| {
+        (a["created_at"].as_str(), a["content"].as_str())
+            .cmp(&(b["created_at"].as_str(), b["content"].as_str()))
+    });
+    notes
+}
+
+fn render(records: &Records) -> String {
+    records
+        .values()
+        .map(|r| Value::Object(r.clone()).to_string() + "\n")
+        .collect()
+}
+
+/// Parse mirror text. A missing or empty file is an empty mirror; leftover
+/// conflict markers are an error naming the line.
+fn parse(text: &str, path: &Path) -> Result<Records, ItrError> {
+    let mut records = Records::new();
+    for (n, line) in text.lines().enumerate() {
+        let invalid = |valid: &str| ItrError::InvalidValue {
+            field: format!("{} line {}", path.display(), n + 1),
+            value: line.chars().take(60).collect(),
+            valid: valid.to_string(),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if ["<<<<<<<", "=======", ">>>>>>>"]
+            .iter()
+            .any(|m| line.starts_with(m))
+        {
+            return Err(invalid(
+                "one JSON object per issue; resolve the merge conflict by keeping one version of each issue",
+            ));
+        }
+        let Ok(Value::Object(map)) = serde_json::from_str::<Value>(line) else {
+            return Err(invalid("one JSON object per issue"));
+        };
+        let Some(id) = map.get("id").and_then(Value::as_i64) else {
+            return Err(invalid("an object with an integer \"id\""));
+        };
+        records.insert(id, map);
+    }
+    Ok(records)
+}
+
+fn load(path: &Path) -> Result<Records, ItrError> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse(&text, path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Records::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// `itr mirror write` — dump every issue to the mirror file, one sorted line
+/// per issue, so the file diffs and merges like source code.
+pub fn write(
+    conn: &Connection,
+    db_path: &Path,
+    path: Option<&str>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let path = resolve(db_path, path);
+    let mut records = Records::new();
+    for issue in db::all_issues(conn)? {
+        records.insert(issue.id, record(conn, &issue)?);
+    }
+    let text = render(&records);
+    let changed = fs::read_to_string(&path).map_or(true, |old| old != text);
+    if changed {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, &text)?;
+    }
+    match fmt {
+        Format::Json => format::print_json(
+            &serde_json::json!({
+                "action": "write",
+                "path": path.display().to_string(),
+                "issues": records.len(),
+                "changed": changed,
+            })
+            .to_string(),
+        ),
+        _ => println!(
+            "MIRROR: wrote {} issue(s) to {}{}",
+            records.len(),
+            path.display(),
+            if changed { "" } else { " (unchanged)" }
+        ),
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct ReadReport {
+    added: usize,
+    updated: usize,
+    unchanged: usize,
+    /// Same ID here, different `created_at` in the mirror.
+    mismatched: Vec<i64>,
+    /// Issues here that the mirror doesn't have.
+    local_only: Vec<i64>,
+    /// `(blocker, blocked)` edges that would close a cycle here.
+    cycles: Vec<(i64, i64)>,
+}
+
+/// `itr mirror read` — make the database match the mirror file: issues are
+/// added or updated, dependency edges match exactly, and missing notes are
+/// added. Issues only in the database are left alone. Overwriting changed
+/// issues asks first (or needs `--yes`).
+pub fn read(
+    conn: &Connection,
+    db_path: &Path,
+    path: Option<&str>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let path = resolve(db_path, path);
+    if !path.is_file() {
+        return Err(ItrError::InvalidValue {
+            field: "mirror".to_string(),
+            value: path.display().to_string(),
+            valid: format!(
+                "an existing file; run `itr mirror write` to create {}",
+                DEFAULT_PATH
+            ),
+        });
+    }
+    let records = load(&path)?;
+    let tx = db::begin_write(conn)?;
+    let report = apply(&tx, &records)?;
+    db::refresh_all_references(&tx)?;
+    if report.updated > 0 {
+        crate::util::confirm(&format!(
+            "Overwrite {} issue(s) with the mirror's version?",
+            report.updated
+        ))?;
+    }
+    tx.commit()?;
+
+    if !report.mismatched.is_empty() {
+        eprintln!(
+            "REVIEW: mirror skipped issue(s) {} whose created_at differs from the database's issue with the same ID; the IDs were reused for different issues (see `itr merge-db`)",
+            join_ids(&report.mismatched)
+        );
+    }
+    if !report.local_only.is_empty() {
+        eprintln!(
+            "REVIEW: {} issue(s) are in the database but not the mirror ({}); run `itr mirror write` to add them",
+            report.local_only.len(),
+            join_ids(&report.local_only)
+        );
+    }
+    if !report.cycles.is_empty() {
+        let edges: Vec<String> = report
+            .cycles
+            .iter()
+            .map(|(blocker, blocked)| format!("{} BLOCKED_BY:{}", blocked, blocker))
+            .collect();
+        eprintln!(
+            "REVIEW: mirror skipped dependency edge(s) that would create a cycle here: {}",
+            edges.join(", ")
+        );
+    }
+    match fmt {
+        Format::Json => format::print_json(
+            &serde_json::json!({
+                "action": "read",
+                "path": path.display().to_string(),
+                "added": report.added,
+                "updated": report.updated,
+                "unchanged": report.unchanged,
+                "skipped": report.mismatched,
+                "local_only": report.local_only,
+            })
+            .to_string(),
+        ),
+        _ => println!(
+            "MIRROR: read {} ADDED:{} UPDATED:{} UNCHANGED:{}",
+            path.display(),
+            report.added,
+            report.updated,
+            report.unchanged
+        ),
+    }
+    Ok(())
+}
+
+fn join_ids(ids: &[i64]) -> String {
+    ids.iter()
+        .map(|id| format!("#{}", id))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn to_issue(record: &Record) -> Result<Issue, ItrError> {
+    let mut map = record.clone();
+    let created_at = map.get("created_at").cloned().unwrap_or_default();
+    map.insert("updated_at".to_string(), created_at);
+    Ok(serde_json::from_value(Value::Object(map))?)
+}
+
+fn apply(conn: &Connection, records: &Records) -> Result<ReadReport, ItrError> {
+    let mut report = ReadReport::default();
+    let mut added: HashSet<i64> = HashSet::new();
+    let mut changed: BTreeSet<i64> = BTreeSet::new();
+    let mut issues = Vec::new();
+    for record in records.values() {
+        let issue = to_issue(record)?;
+        match db::get_issue(conn, issue.id) {
+            Ok(local) if local.created_at != issue.created_at => {
+                report.mismatched.push(issue.id);
+                continue;
+            }
+            Ok(local) => {
+                for field in sync::FIELDS.iter().filter(|f| **f != "parent_id") {
+                    let (ours, theirs) = (
+                        sync::field_value(&local, field),
+                        sync::field_value(&issue, field),
+                    );
+                    if ours != theirs {
+                        sync::set_field(conn, issue.id, field, &ours, &theirs)?;
+                        changed.insert(issue.id);
+                    }
+                }
+            }
+            Err(ItrError::NotFound(_)) => {
+                sync::insert(conn, &issue)?;
+                if let Some(uuid) = record.get("uuid").and_then(Value::as_str) {
+                    db::set_issue_uuid(conn, issue.id, uuid)?;
+                }
+                if let Some(key) = record.get("key").and_then(Value::as_str) {
+                    db::set_issue_key(conn, issue.id, key)?;
+                }
+                added.insert(issue.id);
+            }
+            Err(e) => return Err(e),
+        }
+        issues.push((issue, record));
+    }
+
+    // Parents, edges, and notes once every issue exists.
+    for (issue, record) in &issues {
+        let id = issue.id;
+        let local = db::get_issue(conn, id)?;
+        let (ours, theirs) = (
+            sync::field_value(&local, "parent_id"),
+            sync::field_value(issue, "parent_id"),
+        );
+        let mut touched = false;
+        if ours != theirs && sync::set_field(conn, id, "parent_id", &ours, &theirs)? {
+            touched = true;
+        }
+
+        let want: Repro = match record.get("repro") {
+            Some(value) => serde_json::from_value(value.clone())?,
+            None => Repro::default(),
+        };
+        let have = db::issue_repro(conn, id)?.unwrap_or_default();
+        if have != want {
+            db::record_event(
+                conn,
+                id,
+                "repro",
+                &repro::encode(&have)?,
+                &repro::encode(&want)?,
+            )?;
+            db::set_repro(conn, id, Some(&want))?;
+            touched = true;
+        }
+
+        let want: BTreeSet<i64> = serde_json::from_value(record["blocked_by"].clone())?;
+        let have: BTreeSet<i64> = db::get_blockers(conn, id)?.into_iter().collect();
+        for &blocker in have.difference(&want) {
+            touched |= db::remove_dependency(conn, blocker, id)?;
+        }
+        for &blocker in want.difference(&have) {
+            if !db::issue_exists(conn, blocker)? {
+                continue;
+            }
+            match db::add_dependency(conn, blocker, id) {
+                Ok(added) => touched |= added,
+                Err(ItrError::CycleDetected(_)) => report.cycles.push((blocker, id)),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let have: HashSet<(String, String)> = db::get_notes(conn, id)?
+            .into_iter()
+            .map(|n| (n.content, n.created_at))
+            .collect();
+        for note in record["notes"].as_array().into_iter().flatten() {
+            let (content, created_at) = (
+                note["content"].as_str().unwrap_or_default(),
+                note["created_at"].as_str().unwrap_or_default(),
+            );
+            if have.contains(&(content.to_string(), created_at.to_string())) {
+                continue;
+            }
+            conn.execute(
+                "INSERT INTO notes (issue_id, content, agent, created_at) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![
+                    id,
+                    content,
+                    note["agent"].as_str().unwrap_or_default(),
+                    created_at
+                ],
+            )?;
+            db::record_event(conn, id, "note_added", "", content)?;
+            touched = true;
+        }
+        if touched {
+            changed.insert(id);
+        }
+    }
+    report.added = added.len();
+    report.updated = changed.iter().filter(|id| !added.contains(id)).count();
+    report.unchanged = issues.len() - report.added - report.updated;
+    report.local_only = db::all_issues(conn)?
+        .into_iter()
+        .map(|i| i.id)
+        .filter(|id| !records.contains_key(id))
+        .collect();
+    Ok(report)
+}
+
+#[derive(Debug, Default)]
+struct MergeReport {
+    text: String,
+    issues: usize,
+    /// Issues added on both branches under the same ID: `(theirs, renumbered)`.
+    renumbered: Vec<(i64, i64)>,
+    /// Issues deleted on one branch and edited on the other; the edit wins.
+    kept: Vec<i64>,
+    /// Issues whose fields both branches changed differently.
+    conflicts: Vec<(i64, Vec<String>)>,
+}
+
+/// `itr mirror merge <base> <ours> <theirs>` — a git merge driver for the
+/// mirror file. Merges issue by issue and field by field, writes the result
+/// over `ours`, and exits 1 when conflicts are left for the user.
+pub fn merge(base: &str, ours: &str, theirs: &str, fmt: Format) -> Result<(), ItrError> {
+    let (base_path, ours_path, theirs_path) = (Path::new(base), Path::new(ours), Path::new(theirs));
+    let report = merge_records(&load(base_path)?, &load(ours_path)?, load(theirs_path)?);
+    fs::write(ours_path, &report.text)?;
+
+    for (from, to) in &report.renumbered {
+        eprintln!(
+            "REVIEW: both branches added issue #{}; theirs is now #{}",
+            from, to
+        );
+    }
+    if !report.kept.is_empty() {
+        eprintln!(
+            "REVIEW: kept issue(s) {} that one branch edited and the other removed",
+            join_ids(&report.kept)
+        );
+    }
+    match fmt {
+        Format::Json => {
+            let renumbered: Vec<Value> = report
+                .renumbered
+                .iter()
+                .map(|(from, to)| serde_json::json!({ "from": from, "to": to }))
+                .collect();
+            let conflicts: Vec<Value> = report
+                .conflicts
+                .iter()
+                .map(|(id, fields)| serde_json::json!({ "id": id, "fields": fields }))
+                .collect();
+            format::print_json(
+                &serde_json::json!({
+                    "action": "merge",
+                    "path": ours,
+                    "issues": report.issues,
+                    "renumbered": renumbered,
+                    "conflicts": conflicts,
+                })
+                .to_string(),
+            );
+        }
+        _ => {
+            for (id, fields) in &report.conflicts {
+                println!("CONFLICT:{} FIELDS:{}", id, fields.join(","));
+            }
+            println!(
+                "MIRROR: merged {} issue(s) into {}, {} conflict(s)",
+                report.issues,
+                ours,
+                report.conflicts.len()
+            );
+        }
+    }
+
+    if !report.conflicts.is_empty() {
+        let msg = format!(
+            "{} issue(s) conflict in {}; keep one version of each between the markers, then run `itr mirror read`",
+            report.conflicts.len(),
+            ours
+        );
+        if fmt.is_json() {
+            eprintln!(
+                "{}",
+                format::error_envelope(serde_json::json!({ "error": msg, "code": CONFLICTS_CODE }))
+            );
+        } else {
+            eprintln!("ERROR: {}", msg);
+        }
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn same_issue(a: &Record, b: &Record) -> bool {
+    match (a.get("uuid"), b.get("uuid")) {
+        (Some(x), Some(y)) => x == y,
+        _ => a.get("created_at") == b.get("created_at") && a.get("title") == b.get("title"),
+    }
+}
+
+/// Core three-way merge, separated from I/O so it is unit-testable.
+fn merge_records(base: &Records, ours: &Records, mut theirs: Records) -> MergeReport {
+    let mut report = MergeReport::default();
+
+    // An ID both branches used for different new issues: theirs moves to the
+    // next free ID, along with theirs' references to it.
+    let mut next = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .max()
+        .copied()
+        .unwrap_or(0);
+    let mut moved: BTreeMap<i64, i64> = BTreeMap::new();
+    for (id, record) in &theirs {
+        if !base.contains_key(id) && ours.get(id).is_some_and(|o| !same_issue(o, record)) {
+            next += 1;
+            moved.insert(*id, next);
+        }
+    }
+    if !moved.is_empty() {
+        let remap = |v: &Value| match v.as_i64().and_then(|id| moved.get(&id)) {
+            Some(&to) => Value::from(to),
+            None => v.clone(),
+        };
+        theirs = theirs
+            .into_iter()
+            .map(|(id, mut record)| {
+                let id = moved.get(&id).copied().unwrap_or(id);
+                record.insert("id".to_string(), Value::from(id));
+                if let Some(parent) = record.get("parent_id").map(remap) {
+                    record.insert("parent_id".to_string(), parent);
+                }
+                if let Some(Value::Array(blockers)) = record.get("blocked_by") {
+                    let blockers = blockers.iter().map(remap).collect();
+                    record.insert("blocked_by".to_string(), Value::Array(blockers));
+                }
+                (id, record)
+            })
+            .collect();
+        report.renumbered = moved.into_iter().collect();
+    }
+
+    let ids: BTreeSet<i64> = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .copied()
+        .collect();
+    for id in ids {
+        let b = base.get(&id);
+        let (ours_side, theirs_side) = match (ours.get(&id), theirs.get(&id)) {
+            (None, None) => continue,
+            (Some(o), None) | (None, Some(o)) if b.is_none() => (o.clone(), o.clone()),
+            (Some(o), None) | (None, Some(o)) => {
+                if Some(o) == b {
+                    continue;
+                }
+                report.kept.push(id);
+                (o.clone(), o.clone())
+            }
+            (Some(o), Some(t)) => {
+                let (o, t, fields) = merge_fields(b, o, t);
+                if !fields.is_empty() {
+                    report.conflicts.push((id, fields));
+                }
+                (o, t)
+            }
+        };
+        report.issues += 1;
+        if ours_side == theirs_side {
+            report.text += &(Value::Object(ours_side).to_string() + "\n");
+        } else {
+            report.text += &format!(
+                "<<<<<<< ours\n{}\n=======\n{}\n>>>>>>> theirs\n",
+                Value::Object(ours_side),
+                Value::Object(theirs_side)
+            );
+        }
+    }
+    report
+}
+
+/// Merge one issue's fields. Returns both sides with every non-conflicting
+/// field merged (identical unless something conflicts) and the conflicting
+/// field names.
+fn merge_fields(
+    base: Option<&Record>,
+    ours: &Record,
+    theirs: &Record,
+) -> (Record, Record, Vec<String>) {
+    let (mut o_out, mut t_out) = (Record::new(), Record::new());
+    let mut conflicts = Vec::new();
+    let keys = ours
+        .keys()
+        .chain(theirs.keys().filter(|k| !ours.contains_key(*k)));
+    for key in keys {
+        let (b, o, t) = (
+            base.and_then(|r| r.get(key)),
+            ours.get(key),
+            theirs.get(key),
+        );
+        let merged = if key == "blocked_by" || key == "notes" {
+            Some(merge_set(key, b, o, t))
+        } else if o == t || b == t {
+            o.cloned()
+        } else if b == o {
+            t.cloned()
+        } else {
+            conflicts.push(key.clone());
+            if let Some(v) = o {
+                o_out.insert(key.clone(), v.clone());
+            }
+            if let Some(v) = t {
+                t_out.insert(key.clone(), v.clone());
+            }
+            continue;
+        };
+        if let Some(v) = merged {
+            o_out.insert(key.clone(), v.clone());
+            t_out.insert(key.clone(), v);
+        }
+    }
+    (o_out, t_out, conflicts)
+}
+
+/// Three-way merge of a list field: keep what both sides have, plus what
+/// either side added since the base.
+fn merge_set(
+    key: &str,
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+) -> Value {
+    let items = |v: Option<&Value>| v.and_then(Value::as_array).cloned().unwrap_or_default();
+    let (b, o, t) = (items(base), items(ours), items(theirs));
+    let mut merged: Vec<Value> = Vec::new();
+    for item in o.iter().chain(&t) {
+        let kept = (o.contains(item) && t.contains(item)) || !b.contains(item);
+        if kept && !merged.contains(item) {
+            merged.push(item.clone());
+        }
+    }
+    if key == "notes" {
+        return Value::Array(sort_notes(merged));
+    }
+    merged.sort_by_key(Value::as_i64);
+    Value::Array(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(lines: &[&str]) -> Records {
+        parse(&(lines.join("\n") + "\n"), Path::new("issues.jsonl")).unwrap()
+    }
+
+    #[test]
+    fn read_copies_and_updates_the_reproduction() {
+        let (a, b) = (db::open_test_db(), db::open_test_db());
+        let id = db::insert_test_issue(&a, "crash");
+        let snapshot = |conn: &Connection| -> Records {
+            BTreeMap::from([(id, record(conn, &db::get_issue(conn, id).unwrap()).unwrap())])
+        };
+        let repro = Repro {
+            steps: vec!["run it".to_string()],
+            actual: "panic".to_string(),
+            ..Repro::default()
+        };
+        db::set_repro(&a, id, Some(&repro)).unwrap();
+        apply(&b, &snapshot(&a)).unwrap();
+        assert_eq!(db::issue_repro(&b, id).unwrap(), Some(repro));
+
+        db::set_repro(&a, id, None).unwrap();
+        let report = apply(&b, &snapshot(&a)).unwrap();
+        assert_eq!(report.updated, 1);
+        assert_eq!(db::issue_repro(&b, id).unwrap(), None);
+    }
+
+    #[test]
+    fn merge_combines_field_edits_and_renumbers_clashing_additions() {
+        let base = records(&[
+            r#"{"id":1,"title":"a","priority":"medium","status":"open","created_at":"t0","blocked_by":[],"notes":[]}"#,
+        ]);
+        let ours = records(&[
+            r#"{"id":1,"title":"a","priority":"high","status":"open","created_at":"t0","blocked_by":[2],"notes":[]}"#,
+            r#"{"id":2,"title":"ours","created_at":"t1","blocked_by":[],"notes":[]}"#,
+        ]);
+        let theirs = records(&[
+            r#"{"id":1,"title":"a","priority":"medium","status":"done","created_at":"t0","blocked_by":[2],"notes":[]}"#,
+            r#"{"id":2,"title":"theirs","created_at":"t2","blocked_by":[],"notes":[]}"#,
+        ]);
+
+        let report = merge_records(&base, &ours, theirs);
+        assert!(report.conflicts.is_empty());
+        assert_eq!(report.renumbered, vec![(2, 3)]);
+        let merged = parse(&report.text, Path::new("merged")).unwrap();
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[&1]["priority"], "high");
+        assert_eq!(merged[&1]["status"], "done");
+        // Each side's blocker #2 was a different issue.
+        assert_eq!(merged[&1]["blocked_by"], serde_json::json!([2, 3]));
+        assert_eq!(merged[&3]["title"], "theirs");
+    }
+
+    #[test]
+    fn merge_marks_conflicting_fields() {
+        let base = records(&[r#"{"id":1,"title":"a","created_at":"t0","notes":[]}"#]);
+        let ours = records(&[
+            r#"{"id":1,"title":"b","created_at":"t0","notes":[{"content":"x","agent":"","created_at":"t1"}]}"#,
+        ]);
+        let theirs = records(&[r#"{"id":1,"title":"c","created_at":"t0","notes":[]}"#]);
+
+        let report = merge_records(&base, &ours, theirs);
+        assert_eq!(report.conflicts, vec![(1, vec!["title".to_string()])]);
+        assert!(report.text.starts_with("<<<<<<< ours\n"));
+        // The note only ours added appears on both sides of the conflict.
+        assert_eq!(report.text.matches(r#""content":"x""#).count(), 2);
+        assert!(parse(&report.text, Path::new("merged")).is_err());
+    }
+}
@@ -1,146 +1,88 @@
+pub mod activity;
 pub mod add;
+pub mod agent;
 pub mod agent_info;
+pub mod apply;
 pub mod assign;
 pub mod batch;
+pub mod branch;
+pub mod brief;
 pub mod bulk;
 pub mod close;
+pub mod commits;
+pub mod completions;
+pub mod component;
 pub mod config;
 pub mod depend;
 pub mod doctor;
+pub mod escalate;
+pub mod events;
 pub mod export;
+pub mod files;
+pub mod gate;
 pub mod get;
+pub mod git;
 pub mod graph;
+pub mod handoff;
+pub mod html_report;
 pub mod import;
 pub mod init;
+pub mod lint;
 pub mod list;
 pub mod log;
+pub mod merge_db;
+pub mod metrics;
+pub mod mirror;
 pub mod next;
 pub mod note;
+pub mod plan_sprint;
+pub mod policy;
+pub mod project;
 pub mod ready;
+pub mod recompute;
 pub mod reindex;
 pub mod relate;
+pub mod repro;
+pub mod review;
+pub mod roadmap;
+pub mod scan;
 pub mod schema;
 pub mod search;
+pub mod session;
 pub mod skill;
+pub mod snapshot;
+pub mod stale;
 pub mod stats;
 pub mod summary;
+pub mod sync;
+pub mod tag;
+pub mod todotxt;
+pub mod tree;
 pub mod ui;
 pub mod update;
 pub mod upgrade;
+pub mod urgency;
+pub mod versions;
+pub mod watch;
 
-use crate::db;
-use crate::error::ItrError;
 use crate::format::{self, Format};
-use crate::models::{Issue, IssueDetail, IssueSummary};
-use crate::urgency::{self, UrgencyConfig};
-use rusqlite::Connection;
-use std::cmp::Ordering;
+use crate::models::IssueDetail;
 
-/// Build an `IssueSummary` for a single issue: compute urgency, resolve blockers.
-///
-/// Borrowing wrapper around [`build_issue_summary_owned`]. Prefer the owned
-/// variant when the caller owns the [`Issue`] (e.g. iterating `Vec<Issue>` with
-/// `into_iter()`) — it avoids cloning every string/vec field. This wrapper
-/// exists for callers that only have a borrow (e.g. `summary.rs` which iterates
-/// a slice and reuses each `Issue` afterwards).
-pub fn build_issue_summary(
-    conn: &Connection,
-    issue: &Issue,
-    config: &UrgencyConfig,
-) -> IssueSummary {
-    build_issue_summary_owned(conn, issue.clone(), config)
-}
+pub use itr_core::detail::{
+    build_issue_detail, build_issue_summaries, build_issue_summary, build_subtree_summaries,
+    sort_by_urgency_desc,
+};
 
-/// Owned-input variant of [`build_issue_summary`].
-///
-/// Moves the [`Issue`]'s string and vec fields directly into the resulting
-/// [`IssueSummary`], avoiding the per-field `.clone()` storm that the borrowing
-/// wrapper incurs. Use this from any caller that has ownership of the `Issue`.
-pub fn build_issue_summary_owned(
-    conn: &Connection,
-    issue: Issue,
-    config: &UrgencyConfig,
-) -> IssueSummary {
-    let urg = urgency::compute_urgency(&issue, config, conn);
-    let blocked_by = db::get_blockers(conn, issue.id).unwrap_or_default();
-    let blocks = db::get_blocking(conn, issue.id).unwrap_or_default();
-    let is_blocked = db::is_blocked(conn, issue.id).unwrap_or(false);
-    IssueSummary {
-        id: issue.id,
-        title: issue.title,
-        status: issue.status,
-        priority: issue.priority,
-        kind: issue.kind,
-        urgency: urg,
-        is_blocked,
-        blocked_by,
-        blocks,
-        tags: issue.tags,
-        files: issue.files,
-        skills: issue.skills,
-        acceptance: issue.acceptance,
-        context: issue.context,
-        parent_id: issue.parent_id,
-        close_reason: issue.close_reason,
-        assigned_to: issue.assigned_to,
-        created_at: issue.created_at,
-        updated_at: issue.updated_at,
+/// Format the issue a mutating command just touched: its detail block, or
+/// only its ID under `--quiet`.
+pub fn format_issue_result(detail: &IssueDetail, fmt: Format) -> String {
+    if format::quiet(fmt) {
+        detail.issue.id.to_string()
+    } else {
+        format::format_issue_detail(detail, fmt)
     }
 }
 
-/// Sort by urgency descending (highest first).
-pub fn sort_by_urgency_desc<T: HasUrgency>(items: &mut [T]) {
-    items.sort_by(|a, b| {
-        b.urgency_val()
-            .partial_cmp(&a.urgency_val())
-            .unwrap_or(Ordering::Equal)
-    });
-}
-
-/// Trait for types that have an urgency score.
-pub trait HasUrgency {
-    fn urgency_val(&self) -> f64;
-}
-
-impl HasUrgency for IssueSummary {
-    fn urgency_val(&self) -> f64 {
-        self.urgency
-    }
-}
-
-impl HasUrgency for crate::models::SearchResult {
-    fn urgency_val(&self) -> f64 {
-        self.urgency
-    }
-}
-
-/// Build an `IssueDetail` for a single issue using standard DB lookups.
-/// `children` and `relations` default to empty — callers that need them set
-/// the fields on the returned struct afterward, or use the `get` handler directly.
-pub fn build_issue_detail(
-    conn: &Connection,
-    issue: Issue,
-    config: &UrgencyConfig,
-) -> Result<IssueDetail, ItrError> {
-    let (urgency, urgency_breakdown) =
-        urgency::compute_urgency_with_breakdown(&issue, config, conn);
-    let blocked_by = db::get_blockers(conn, issue.id)?;
-    let blocks = db::get_blocking(conn, issue.id)?;
-    let is_blocked = db::is_blocked(conn, issue.id)?;
-    let notes = db::get_notes(conn, issue.id)?;
-    Ok(IssueDetail {
-        issue,
-        urgency,
-        blocked_by,
-        blocks,
-        is_blocked,
-        notes,
-        urgency_breakdown: Some(urgency_breakdown),
-        children: None,
-        relations: vec![],
-    })
-}
-
 /// Print an `IssueDetail` along with any newly-unblocked issues.
 /// Used by close.rs and update.rs after modifying an issue.
 pub fn print_detail_with_unblocked(detail: &IssueDetail, unblocked: &[(i64, String)], fmt: Format) {
@@ -157,7 +99,7 @@ pub fn print_detail_with_unblocked(detail: &IssueDetail, unblocked: &[(i64, Stri
             format::println_json(&value.to_string());
         }
         _ => {
-            println!("{}", format::format_issue_detail(detail, fmt));
+            format::print_output(&format_issue_result(detail, fmt), fmt);
             if !unblocked.is_empty() {
                 let unblocked_str = format::format_unblocked(unblocked, fmt);
                 if !unblocked_str.is_empty() {
@@ -1,24 +1,43 @@
 use crate::commands::build_issue_detail;
+use crate::commands::project;
 use crate::db::{self, ClaimOutcome};
 use crate::error::{self, ItrError};
 use crate::format::{self, Format};
 use crate::models::{Issue, ListFilter};
 use crate::urgency::{self, UrgencyConfig};
+use crate::util;
 use rusqlite::Connection;
-use std::env;
 
+/// `itr next [--claim]` / `itr claim [ID]`. `skills` filters candidates
+/// (AND); `prefer` (`--skills`, else the registered agent's skills) only
+/// reorders them: issues whose skills or tags match come first, then
+/// general issues that list no skills, then the rest. The tier is reported
+/// as `skill_match` in the urgency breakdown.
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     conn: &Connection,
     claim: bool,
     id: Option<i64>,
     skills: Vec<String>,
+    prefer: Option<String>,
     agent: Option<String>,
     assigned_to: Option<String>,
     fmt: Format,
 ) -> Result<(), ItrError> {
     let config = UrgencyConfig::load(conn);
-    // Resolve agent name: explicit flag > ITR_AGENT env var
-    let agent_name = agent.or_else(|| env::var("ITR_AGENT").ok().filter(|s| !s.is_empty()));
+    // Resolve agent name: explicit flag > ITR_AGENT > agent.name
+    let agent_name = agent
+        .filter(|a| !a.is_empty())
+        .or_else(|| Some(db::current_agent(conn)).filter(|a| !a.is_empty()));
+    let prefer = match prefer {
+        Some(list) => util::parse_comma_list_lower(&list),
+        None => match agent_name.as_deref() {
+            Some(name) => db::get_agent(conn, name)?
+                .map(|a| a.skills)
+                .unwrap_or_default(),
+            None => Vec::new(),
+        },
+    };
 
     // If a specific ID is provided, claim it directly (with guardrails)
     let issue = if let Some(target_id) = id {
@@ -36,25 +55,18 @@ pub fn run(
         }
         db::get_issue(conn, target_id)?
     } else {
-        // Get all open, unblocked issues
-        let issues = db::list_issues(
-            conn,
-            &ListFilter {
-                statuses: vec!["open".to_string()],
-                skills,
-                assigned_to,
-                ..ListFilter::default()
-            },
-        )?;
-
-        if issues.is_empty() {
-            error::print_empty(fmt.is_json(), "No eligible issues found.");
+        let mut candidates = candidates(conn, skills.clone(), assigned_to.clone(), &config)?;
+        // Stable: urgency order is kept within each tier.
+        candidates.sort_by_key(|issue| skill_tier(issue, &prefer).0);
+        if candidates.is_empty() {
+            error::print_empty_blocked(
+                fmt.is_json(),
+                "No eligible issues found.",
+                blocked_count(conn, skills, assigned_to)?,
+            );
             return Ok(());
         }
 
-        // Order candidates by urgency, highest first
-        let candidates = rank_by_urgency(conn, issues, &config);
-
         if claim {
             // Compare-and-swap claim: a race loser whose candidate was stolen
             // by a concurrent claimer moves on to the next one.
@@ -71,16 +83,142 @@ pub fn run(
         }
     };
 
-    let detail = build_issue_detail(conn, issue, &config)?;
-    println!("{}", format::format_issue_detail(&detail, fmt));
+    if claim {
+        if let Some(name) = agent_name.as_deref().filter(|n| issue.assigned_to == *n) {
+            for note in super::agent::fit_notes(conn, name, &issue)? {
+                eprintln!("{note}");
+            }
+        }
+    }
+
+    let mut skill_match = None;
+    if !prefer.is_empty() {
+        let (tier, matched) = skill_tier(&issue, &prefer);
+        skill_match = Some(match tier {
+            0 => format!("matches skill(s) {}", matched.join(", ")),
+            1 => format!(
+                "general issue: lists no skills and matches none of {}",
+                prefer.join(", ")
+            ),
+            _ => {
+                if id.is_none() {
+                    eprintln!(
+                        "REVIEW: no open issue matches skill(s) {} or is general; picked issue {}, which lists skill(s) {}",
+                        prefer.join(", "),
+                        issue.id,
+                        issue.skills.join(", ")
+                    );
+                }
+                format!(
+                    "lists skill(s) {}, none of {}",
+                    issue.skills.join(", "),
+                    prefer.join(", ")
+                )
+            }
+        });
+    }
+
+    let mut detail = build_issue_detail(conn, issue, &config)?;
+    if let Some(breakdown) = detail.urgency_breakdown.as_mut() {
+        breakdown.skill_match = skill_match;
+    }
+    format::print_output(&super::format_issue_result(&detail, fmt), fmt);
     Ok(())
 }
 
+/// Where `issue` ranks for an agent with skills `prefer`: 0 when its skills
+/// or tags include one of them (returned), 1 when it lists no skills, 2
+/// when it needs skills the agent didn't name. Everything is tier 0 when
+/// `prefer` is empty.
+fn skill_tier(issue: &Issue, prefer: &[String]) -> (u8, Vec<String>) {
+    if prefer.is_empty() {
+        return (0, Vec::new());
+    }
+    let matched: Vec<String> = prefer
+        .iter()
+        .filter(|p| {
+            issue
+                .skills
+                .iter()
+                .chain(&issue.tags)
+                .any(|s| s.eq_ignore_ascii_case(p))
+        })
+        .cloned()
+        .collect();
+    let tier = if !matched.is_empty() {
+        0
+    } else if issue.skills.is_empty() {
+        1
+    } else {
+        2
+    };
+    (tier, matched)
+}
+
+/// Open, unblocked issues matching the filters, highest urgency first — what
+/// `next` would pick from. Also used by `brief --next`.
+pub(crate) fn candidates(
+    conn: &Connection,
+    skills: Vec<String>,
+    assigned_to: Option<String>,
+    config: &UrgencyConfig,
+) -> Result<Vec<Issue>, ItrError> {
+    let issues = db::list_issues(
+        conn,
+        &ListFilter {
+            statuses: vec!["open".to_string()],
+            skills,
+            assigned_to,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    Ok(rank_by_urgency(conn, issues, config))
+}
+
+/// How many open issues matching the filters are blocked: when
+/// [`candidates`] is empty, these are why.
+pub(crate) fn blocked_count(
+    conn: &Connection,
+    skills: Vec<String>,
+    assigned_to: Option<String>,
+) -> Result<usize, ItrError> {
+    let issues = db::list_issues(
+        conn,
+        &ListFilter {
+            statuses: vec!["open".to_string()],
+            skills,
+            assigned_to,
+            blocked_only: true,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    Ok(issues.len())
+}
+
 /// Sort issues by computed urgency, highest first.
 fn rank_by_urgency(conn: &Connection, issues: Vec<Issue>, config: &UrgencyConfig) -> Vec<Issue> {
+    let relations = db::load_issue_relations(conn)
+        .map_err(|e| {
+            eprintln!(
+                "REVIEW: batch dependency load failed (scoring issues one by one): {}",
+                e
+            );
+        })
+        .ok();
     let mut scored: Vec<(f64, Issue)> = issues
         .into_iter()
-        .map(|issue| (urgency::compute_urgency(&issue, config, conn), issue))
+        .map(|issue| {
+            let score = match &relations {
+                Some(r) => {
+                    urgency::compute_urgency_from_inputs(&issue, config, r.urgency_inputs(issue.id))
+                        .0
+                }
+                None => urgency::compute_urgency(&issue, config, conn),
+            };
+            (score, issue)
+        })
         .collect();
     scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
     scored.into_iter().map(|(_, issue)| issue).collect()
@@ -115,6 +253,7 @@ fn try_claim_in_order(
 ///   someone else).
 /// - `done`/`wontfix` issues are NOT resurrected; a note explains how to
 ///   reopen deliberately.
+/// - `review` issues are left for `itr review approve|reject`; a note says so.
 /// - `in-progress` issues assigned to a different agent are NOT stolen; a
 ///   note names the current assignee.
 fn claim_by_id(
@@ -167,6 +306,11 @@ fn claim_by_id(
                     "REVIEW: issue {id} is '{status}' and was not reopened; run `itr update {id} --status open` first if you really want to claim it"
                 ));
             }
+            "review" => {
+                notes.push(format!(
+                    "REVIEW: issue {id} is awaiting review and was not claimed; approve or reject it with `itr review approve|reject {id}`"
+                ));
+            }
             _ => {
                 // in-progress (the only other non-open status)
                 let taken_by_other = !assigned_to.is_empty() && agent != Some(assigned_to.as_str());
@@ -176,8 +320,10 @@ fn claim_by_id(
                     ));
                 } else if assigned_to.is_empty() {
                     if let Some(name) = agent {
-                        db::record_event(conn, id, "assigned_to", &assigned_to, name)?;
-                        db::update_issue_field(conn, id, "assigned_to", name)?;
+                        let tx = db::begin_write(conn)?;
+                        db::record_event(&tx, id, "assigned_to", &assigned_to, name)?;
+                        db::update_issue_field(&tx, id, "assigned_to", name)?;
+                        tx.commit()?;
                         notes.push(format!(
                             "REVIEW: issue {id} was already in-progress; recorded assignment to '{name}'"
                         ));
@@ -235,6 +381,41 @@ mod tests {
         .id
     }
 
+    #[test]
+    fn skill_tier_prefers_matches_then_general_issues() {
+        let conn = test_conn();
+        let issue = |skills: &[&str], tags: &[&str]| {
+            let skills: Vec<String> = skills.iter().map(ToString::to_string).collect();
+            let tags: Vec<String> = tags.iter().map(ToString::to_string).collect();
+            db::insert_issue(
+                &conn,
+                "t",
+                "medium",
+                "task",
+                "",
+                &[],
+                &tags,
+                &skills,
+                "",
+                None,
+                "",
+            )
+            .unwrap()
+        };
+        let prefer = vec!["backend".to_string(), "db".to_string()];
+        assert_eq!(
+            skill_tier(&issue(&["db"], &[]), &prefer),
+            (0, vec!["db".to_string()])
+        );
+        assert_eq!(
+            skill_tier(&issue(&[], &["Backend"]), &prefer),
+            (0, vec!["backend".to_string()])
+        );
+        assert_eq!(skill_tier(&issue(&[], &["ui"]), &prefer).0, 1);
+        assert_eq!(skill_tier(&issue(&["css"], &[]), &prefer).0, 2);
+        assert_eq!(skill_tier(&issue(&["css"], &[]), &[]).0, 0);
+    }
+
     // --- #154: race loser retries the next candidate ---
 
     #[test]
@@ -413,4 +594,19 @@ mod tests {
         // The claim itself still goes through.
         assert_eq!(db::get_issue(&conn, id).unwrap().status, "in-progress");
     }
+
+    #[test]
+    fn blocked_count_explains_an_empty_pick() {
+        let conn = test_conn();
+        let blocker = add(&conn, "blocker");
+        let waiting = add(&conn, "waiting");
+        db::add_dependency(&conn, blocker, waiting).unwrap();
+        db::update_issue_field(&conn, blocker, "status", "in-progress").unwrap();
+
+        let config = UrgencyConfig::load(&conn);
+        assert!(candidates(&conn, vec![], None, &config).unwrap().is_empty());
+        assert_eq!(blocked_count(&conn, vec![], None).unwrap(), 1);
+        db::update_issue_field(&conn, waiting, "status", "done").unwrap();
+        assert_eq!(blocked_count(&conn, vec![], None).unwrap(), 0);
+    }
 }
@@ -1,14 +1,13 @@
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
 use crate::util;
 use rusqlite::Connection;
-use std::env;
 
-/// Resolve the acting agent name: explicit flag, else `ITR_AGENT`, else empty.
-pub(crate) fn resolve_agent(agent: &str) -> String {
+/// Resolve the acting agent name: explicit flag, else [`db::current_agent`].
+pub(crate) fn resolve_agent(conn: &Connection, agent: &str) -> String {
     if agent.is_empty() {
-        env::var("ITR_AGENT").unwrap_or_default()
+        db::current_agent(conn)
     } else {
         agent.to_string()
     }
@@ -24,7 +23,10 @@ pub(crate) fn format_note_line(note: &crate::models::Note) -> String {
     };
     format!(
         "NOTE:{} ISSUE:{}{} {}",
-        note.id, note.issue_id, agent_str, note.content
+        note.id,
+        note.issue_id,
+        agent_str,
+        format::note_text(note)
     )
 }
 
@@ -35,7 +37,7 @@ pub(crate) fn format_note_line(note: &crate::models::Note) -> String {
 ///   for a missing issue, `INVALID_VALUE` for missing text).
 /// - Multiple unique IDs: one note per issue in a single transaction with
 ///   per-ID soft fallback — a missing ID emits `REVIEW: id N not found;
-///   skipped`. Exit 0 if at least one note was added, exit 1 if none were.
+///   skipped`. Exit 0 if at least one note was added, exit 5 (`INVALID_VALUE`) if none were.
 pub fn run_multi(
     conn: &Connection,
     id_tokens: &[String],
@@ -43,7 +45,7 @@ pub fn run_multi(
     agent: &str,
     fmt: Format,
 ) -> Result<(), ItrError> {
-    let parsed = util::parse_id_tokens(id_tokens);
+    let parsed = util::parse_id_tokens(&db::resolve_id_tokens(conn, id_tokens)?);
     for note in &parsed.notes {
         eprintln!("{}", note);
     }
@@ -80,9 +82,9 @@ pub fn run_multi(
             valid: "non-empty string".to_string(),
         });
     };
-    let agent = resolve_agent(agent);
+    let agent = resolve_agent(conn, agent);
 
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
     let mut notes = Vec::new();
     for &id in &parsed.ids {
         match db::add_note(&tx, id, &content, &agent) {
@@ -104,11 +106,15 @@ pub fn run_multi(
 
     match fmt {
         Format::Json => {
-            println!("{}", serde_json::to_string(&notes)?);
+            format::print_json(&serde_json::to_string(&notes)?);
         }
         _ => {
             for note in &notes {
-                println!("{}", format_note_line(note));
+                if format::quiet(fmt) {
+                    println!("{}", note.id);
+                } else {
+                    println!("{}", format_note_line(note));
+                }
             }
         }
     }
@@ -122,8 +128,8 @@ pub fn run(
     agent: &str,
     fmt: Format,
 ) -> Result<(), ItrError> {
-    // Fall back to ITR_AGENT if agent is empty
-    let agent = resolve_agent(agent);
+    // Fall back to ITR_AGENT / agent.name if agent is empty
+    let agent = resolve_agent(conn, agent);
     let Some(content) = text else {
         return Err(ItrError::InvalidValue {
             field: "text".to_string(),
@@ -136,8 +142,9 @@ pub fn run(
 
     match fmt {
         Format::Json => {
-            println!("{}", serde_json::to_string(&note)?);
+            format::print_json(&serde_json::to_string(&note)?);
         }
+        _ if format::quiet(fmt) => println!("{}", note.id),
         _ => {
             println!("{}", format_note_line(&note));
         }
@@ -147,15 +154,19 @@ pub fn run(
 }
 
 pub fn run_delete(conn: &Connection, note_id: i64, fmt: Format) -> Result<(), ItrError> {
-    let note = db::delete_note(conn, note_id)?;
+    util::confirm(&format!("Delete note {}?", note_id))?;
+    let tx = db::begin_write(conn)?;
+    let note = db::delete_note(&tx, note_id)?;
 
     // Record event for audit trail
-    db::record_event(conn, note.issue_id, "note_deleted", &note.content, "")?;
+    db::record_event(&tx, note.issue_id, "note_deleted", &note.content, "")?;
+    tx.commit()?;
 
     match fmt {
         Format::Json => {
-            println!("{}", serde_json::to_string(&note)?);
+            format::print_json(&serde_json::to_string(&note)?);
         }
+        _ if format::quiet(fmt) => {}
         _ => {
             println!("DELETED NOTE:{} ISSUE:{}", note.id, note.issue_id);
         }
@@ -170,23 +181,26 @@ pub fn run_update(
     text: &str,
     fmt: Format,
 ) -> Result<(), ItrError> {
-    let old_note = db::get_note(conn, note_id)?;
+    let tx = db::begin_write(conn)?;
+    let old_note = db::get_note(&tx, note_id)?;
 
     // Record event for audit trail
     db::record_event(
-        conn,
+        &tx,
         old_note.issue_id,
         "note_updated",
         &old_note.content,
         text,
     )?;
 
-    let note = db::update_note(conn, note_id, text)?;
+    let note = db::update_note(&tx, note_id, text)?;
+    tx.commit()?;
 
     match fmt {
         Format::Json => {
-            println!("{}", serde_json::to_string(&note)?);
+            format::print_json(&serde_json::to_string(&note)?);
         }
+        _ if format::quiet(fmt) => println!("{}", note.id),
         _ => {
             let agent_str = if note.agent.is_empty() {
                 String::new()
@@ -195,7 +209,10 @@ pub fn run_update(
             };
             println!(
                 "NOTE:{} ISSUE:{}{} {}",
-                note.id, note.issue_id, agent_str, note.content
+                note.id,
+                note.issue_id,
+                agent_str,
+                format::note_text(&note)
             );
         }
     }
@@ -0,0 +1,191 @@
+use crate::commands::project;
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::{is_terminal, ListFilter, SprintItem, SprintPlan};
+use crate::urgency::{compute_urgency_from_inputs, UrgencyConfig};
+use crate::util;
+use rusqlite::Connection;
+use std::collections::HashSet;
+
+/// Points counted for an issue without an estimate.
+const UNESTIMATED_POINTS: i64 = 1;
+
+/// `itr plan-sprint --capacity 20pts [--agents N]` — propose a sprint from
+/// the open and in-progress queue, most urgent first. Each of the `agents`
+/// gets `capacity` points; an issue goes to the agent with the most room
+/// left, and only when every unfinished blocker is already in the plan.
+/// Read-only: the plan is printed for confirmation, nothing is assigned.
+pub fn run(conn: &Connection, capacity: &str, agents: usize, fmt: Format) -> Result<(), ItrError> {
+    let per_agent = util::parse_points(capacity).ok_or_else(|| ItrError::InvalidValue {
+        field: "capacity".to_string(),
+        value: capacity.to_string(),
+        valid: "a whole number of points per agent (e.g. 20 or 20pts)".to_string(),
+    })?;
+    if agents == 0 {
+        return Err(ItrError::InvalidValue {
+            field: "agents".to_string(),
+            value: "0".to_string(),
+            valid: "at least 1".to_string(),
+        });
+    }
+
+    let issues = db::list_issues(
+        conn,
+        &ListFilter {
+            statuses: vec!["open".to_string(), "in-progress".to_string()],
+            include_blocked: true,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    let config = UrgencyConfig::load(conn);
+    let relations = db::load_issue_relations(conn)?;
+    let estimates = db::issue_estimates(conn)?;
+    let finished: HashSet<i64> = db::all_issues(conn)?
+        .into_iter()
+        .filter(|i| is_terminal(&i.status))
+        .map(|i| i.id)
+        .collect();
+
+    let mut candidates: Vec<Candidate> = issues
+        .into_iter()
+        .map(|issue| Candidate {
+            urgency: compute_urgency_from_inputs(
+                &issue,
+                &config,
+                relations.urgency_inputs(issue.id),
+            )
+            .0,
+            estimate: estimates.get(&issue.id).copied(),
+            blockers: relations
+                .blockers(issue.id)
+                .into_iter()
+                .filter(|b| !finished.contains(b))
+                .collect(),
+            id: issue.id,
+            title: issue.title,
+            priority: issue.priority,
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        b.urgency
+            .partial_cmp(&a.urgency)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.id.cmp(&b.id))
+    });
+
+    let plan = fill(&candidates, per_agent, agents);
+    if plan.items.is_empty() {
+        error::print_empty(fmt.is_json(), "No ready issues fit the sprint capacity.");
+        return Ok(());
+    }
+    let unestimated = plan.items.iter().filter(|i| !i.estimated).count();
+    if unestimated > 0 {
+        eprintln!(
+            "REVIEW: {} planned issue(s) have no estimate and count as {} point each; set one with `itr update <ID> --estimate N`",
+            unestimated, UNESTIMATED_POINTS
+        );
+    }
+    format::print_output(&format::format_sprint_plan(&plan, fmt), fmt);
+    Ok(())
+}
+
+/// An open or in-progress issue competing for a sprint slot.
+struct Candidate {
+    id: i64,
+    title: String,
+    priority: String,
+    urgency: f64,
+    estimate: Option<i64>,
+    /// Unfinished blockers; each must be planned before this issue can be.
+    blockers: Vec<i64>,
+}
+
+/// Greedy fill over `candidates` (most urgent first): repeatedly take the
+/// first one whose blockers are all planned and that fits the agent with
+/// the most room. Rescanning from the top after every pick lets an urgent
+/// issue follow its blocker into the sprint ahead of less urgent work.
+fn fill(candidates: &[Candidate], per_agent: i64, agents: usize) -> SprintPlan {
+    let mut load = vec![0i64; agents];
+    let mut planned: HashSet<i64> = HashSet::new();
+    let mut items: Vec<SprintItem> = Vec::new();
+    loop {
+        let roomiest = (0..agents).min_by_key(|&a| (load[a], a)).unwrap_or(0);
+        let room = per_agent - load[roomiest];
+        let pick = candidates.iter().find(|c| {
+            !planned.contains(&c.id)
+                && c.blockers.iter().all(|b| planned.contains(b))
+                && c.estimate.unwrap_or(UNESTIMATED_POINTS) <= room
+        });
+        let Some(c) = pick else { break };
+        let points = c.estimate.unwrap_or(UNESTIMATED_POINTS);
+        load[roomiest] += points;
+        planned.insert(c.id);
+        items.push(SprintItem {
+            id: c.id,
+            title: c.title.clone(),
+            priority: c.priority.clone(),
+            urgency: c.urgency,
+            points,
+            estimated: c.estimate.is_some(),
+            agent: roomiest + 1,
+            after: c.blockers.clone(),
+        });
+    }
+    SprintPlan {
+        capacity: per_agent,
+        agents,
+        planned_points: load.iter().sum(),
+        left_out: candidates.len() - items.len(),
+        items,
+        agent_points: load,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: i64, urgency: f64, estimate: Option<i64>, blockers: &[i64]) -> Candidate {
+        Candidate {
+            id,
+            title: format!("#{}", id),
+            priority: "medium".to_string(),
+            urgency,
+            estimate,
+            blockers: blockers.to_vec(),
+        }
+    }
+
+    #[test]
+    fn fill_respects_capacity_and_dependencies() {
+        let candidates = vec![
+            candidate(1, 9.0, Some(5), &[3]),
+            candidate(2, 8.0, Some(8), &[]),
+            candidate(4, 7.0, None, &[]),
+            candidate(5, 6.0, Some(4), &[]),
+            candidate(3, 2.0, Some(3), &[]),
+        ];
+        let ids = |plan: &SprintPlan| plan.items.iter().map(|i| i.id).collect::<Vec<_>>();
+
+        // Each pick goes to the agent with the most room; 1 is never
+        // unblocked in time to fit.
+        let plan = fill(&candidates, 8, 2);
+        assert_eq!(ids(&plan), vec![2, 4, 5, 3]);
+        assert_eq!(
+            plan.items.iter().map(|i| i.agent).collect::<Vec<_>>(),
+            vec![1, 2, 2, 2]
+        );
+        assert_eq!(plan.agent_points, vec![8, 8]);
+        assert_eq!(plan.left_out, 1);
+
+        // With room for everything, 1 follows its blocker 3.
+        let plan = fill(&candidates, 21, 1);
+        assert_eq!(ids(&plan), vec![2, 4, 5, 3, 1]);
+        assert_eq!(plan.items[4].after, vec![3]);
+        assert!(!plan.items[1].estimated);
+        assert_eq!(plan.planned_points, 21);
+        assert_eq!(plan.left_out, 0);
+    }
+}
@@ -0,0 +1,127 @@
+use super::lint;
+use crate::db;
+use crate::error::ItrError;
+use crate::normalize;
+use crate::util;
+use rusqlite::Connection;
+
+/// Kinds that can't be created or left without acceptance criteria.
+pub const REQUIRE_ACCEPTANCE_KEY: &str = "policy.require.acceptance";
+/// Kinds that can't be created or left without context.
+pub const REQUIRE_CONTEXT_KEY: &str = "policy.require.context";
+/// Kinds that can't be created or left without reproduction steps.
+pub const REQUIRE_REPRO_KEY: &str = "policy.require.repro";
+
+/// Check a `policy.*` value for `config set`: every key takes a kind list,
+/// stored normalized (empty turns the requirement off).
+pub fn validate_setting(key: &str, value: &str) -> Result<String, String> {
+    match key {
+        REQUIRE_ACCEPTANCE_KEY | REQUIRE_CONTEXT_KEY | REQUIRE_REPRO_KEY => {
+            lint::normalize_kind_list(value.trim())
+        }
+        _ => {
+            let valid = format!(
+                "{}, {}, {}",
+                REQUIRE_ACCEPTANCE_KEY, REQUIRE_CONTEXT_KEY, REQUIRE_REPRO_KEY
+            );
+            Err(format!(
+                "unknown key{} (valid: {})",
+                util::did_you_mean(key, &valid),
+                valid
+            ))
+        }
+    }
+}
+
+/// Enforce the `policy.require.*` settings on an issue as `add` or `update`
+/// would leave it. `kind` may be raw input: it is normalized the same way,
+/// falling back to `task`. `has_repro` says whether the issue has a
+/// non-empty reproduction. Unset keys, and stored values that no longer
+/// parse, require nothing.
+pub(crate) fn check_required(
+    conn: &Connection,
+    kind: &str,
+    context: &str,
+    acceptance: &str,
+    has_repro: bool,
+) -> Result<(), ItrError> {
+    let kind = normalize::normalize_kind(kind);
+    let kind = if normalize::validate_kind(&kind).is_ok() {
+        kind
+    } else {
+        "task".to_string()
+    };
+    for (key, field, value) in [
+        (REQUIRE_ACCEPTANCE_KEY, "acceptance", acceptance),
+        (REQUIRE_CONTEXT_KEY, "context", context),
+    ] {
+        if !value.trim().is_empty() || !required_for(conn, key, &kind) {
+            continue;
+        }
+        return Err(ItrError::InvalidValue {
+            field: field.to_string(),
+            value: value.to_string(),
+            valid: format!("non-empty text; {} requires it for {} issues", key, kind),
+        });
+    }
+    if !has_repro && required_for(conn, REQUIRE_REPRO_KEY, &kind) {
+        return Err(ItrError::InvalidValue {
+            field: "repro".to_string(),
+            value: String::new(),
+            valid: format!(
+                "steps, expected, actual, or environment (`itr repro <ID> --stdin-json` or `--edit`, or a `repro` object in `add --stdin-json`); {} requires it for {} issues",
+                REQUIRE_REPRO_KEY, kind
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn required_for(conn: &Connection, key: &str, kind: &str) -> bool {
+    db::config_get(conn, key)
+        .ok()
+        .flatten()
+        .and_then(|raw| validate_setting(key, &raw).ok())
+        .is_some_and(|kinds| kinds.split(',').any(|k| k == kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_fields_follow_the_configured_kinds() {
+        let conn = db::open_test_db();
+        assert!(
+            check_required(&conn, "bug", "", "", false).is_ok(),
+            "off by default"
+        );
+
+        db::config_set(&conn, REQUIRE_ACCEPTANCE_KEY, "bug,feature").unwrap();
+        db::config_set(&conn, REQUIRE_CONTEXT_KEY, "feature").unwrap();
+        let err = check_required(&conn, "defect", "why", " ", false).unwrap_err();
+        assert!(
+            matches!(&err, ItrError::InvalidValue { field, .. } if field == "acceptance"),
+            "{err}"
+        );
+        let err = check_required(&conn, "story", "", "done when", false).unwrap_err();
+        assert!(matches!(&err, ItrError::InvalidValue { field, .. } if field == "context"));
+        assert!(check_required(&conn, "bug", "", "done when", false).is_ok());
+        assert!(
+            check_required(&conn, "nonsense", "", "", false).is_ok(),
+            "unknown kinds are tasks"
+        );
+
+        db::config_set(&conn, REQUIRE_REPRO_KEY, "bug").unwrap();
+        let err = check_required(&conn, "bug", "", "done when", false).unwrap_err();
+        assert!(matches!(&err, ItrError::InvalidValue { field, .. } if field == "repro"));
+        assert!(check_required(&conn, "bug", "", "done when", true).is_ok());
+
+        assert_eq!(
+            validate_setting(REQUIRE_ACCEPTANCE_KEY, " Bug, feat ").as_deref(),
+            Ok("bug,feature")
+        );
+        assert!(validate_setting(REQUIRE_CONTEXT_KEY, "widget").is_err());
+        assert!(validate_setting("policy.require.files", "bug").is_err());
+    }
+}
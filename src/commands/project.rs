@@ -0,0 +1,333 @@
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::util;
+use rusqlite::Connection;
+use std::sync::{Mutex, PoisonError};
+
+static SCOPE: Mutex<Option<String>> = Mutex::new(None);
+
+/// The project this process is scoped to by [`apply_scope`], if any.
+/// Commands pass it as `ListFilter::project` so listings (and `itr search`,
+/// `itr stats`) only see its issues, and as `NewIssue::project` so new
+/// issues are filed under it.
+pub fn scope() -> Option<String> {
+    SCOPE.lock().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+fn set_scope(project: String) {
+    *SCOPE.lock().unwrap_or_else(PoisonError::into_inner) = Some(project);
+}
+
+/// Resolve the project this process is scoped to for [`scope`]:
+/// `--project` wins, else `project.current`.
+/// An unknown `--project` is an error (a typo would otherwise show an empty
+/// backlog); a stale `project.current` is dropped with a `REVIEW:` note.
+pub fn apply_scope(conn: &Connection, flag: Option<&str>) -> Result<(), ItrError> {
+    if let Some(name) = flag.map(str::trim).filter(|n| !n.is_empty()) {
+        require(conn, name)?;
+        set_scope(name.to_string());
+        return Ok(());
+    }
+    let current = db::config_get(conn, db::CURRENT_PROJECT_KEY)?.filter(|n| !n.is_empty());
+    if let Some(name) = current {
+        if db::project_exists(conn, &name)? {
+            set_scope(name);
+        } else {
+            eprintln!(
+                "REVIEW: current project '{}' no longer exists; showing all projects",
+                name
+            );
+        }
+    }
+    Ok(())
+}
+
+/// `itr project add <name> [--description D] [--key PREFIX]` — create a
+/// project. Adding one that exists is a no-op reported with
+/// `"created": false`; a `--key` still applies to it.
+pub fn add(
+    conn: &Connection,
+    name: &str,
+    description: Option<&str>,
+    key: Option<&str>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let name = validate_name(name)?;
+    let prefix = key
+        .map(|k| validate_key_prefix(conn, &name, k))
+        .transpose()?;
+    let tx = db::begin_write(conn)?;
+    let created = db::add_project(&tx, &name, description.map_or("", str::trim))?;
+    if let Some(prefix) = &prefix {
+        db::set_project_key_prefix(&tx, &name, prefix)?;
+    }
+    tx.commit()?;
+    if !created {
+        let what = if prefix.is_some() {
+            "only its key prefix was set"
+        } else {
+            "nothing changed"
+        };
+        eprintln!("REVIEW: project '{}' already exists; {}", name, what);
+    }
+    match fmt {
+        Format::Json => {
+            let mut out = serde_json::json!({
+                "action": "project_add",
+                "project": name,
+                "created": created,
+            });
+            if let Some(prefix) = &prefix {
+                out["key_prefix"] = serde_json::json!(prefix);
+            }
+            format::println_json(&out.to_string());
+        }
+        _ if format::quiet(fmt) => println!("{}", name),
+        _ => match &prefix {
+            Some(prefix) => println!(
+                "PROJECT:{} KEY:{}",
+                format::escape_line_value(&name),
+                prefix
+            ),
+            None => println!("PROJECT:{}", format::escape_line_value(&name)),
+        },
+    }
+    Ok(())
+}
+
+/// `itr project key <name> <PREFIX>` — set the prefix of the project's issue
+/// keys (`APP` gives `APP-1`, `APP-2`, ...) and key every issue in it that
+/// has none yet, in ID order. Existing keys never change; an empty prefix
+/// stops handing out new ones.
+pub fn key(conn: &Connection, name: &str, prefix: &str, fmt: Format) -> Result<(), ItrError> {
+    let name = name.trim();
+    require(conn, name)?;
+    let prefix = if prefix.trim().is_empty() {
+        String::new()
+    } else {
+        validate_key_prefix(conn, name, prefix)?
+    };
+    let tx = db::begin_write(conn)?;
+    let keyed = db::set_project_key_prefix(&tx, name, &prefix)?;
+    tx.commit()?;
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "action": "project_key",
+                "project": name,
+                "key_prefix": prefix,
+                "keyed": keyed,
+            });
+            format::println_json(&out.to_string());
+        }
+        _ => println!(
+            "PROJECT:{} KEY:{} KEYED:{}",
+            format::escape_line_value(name),
+            prefix,
+            keyed
+        ),
+    }
+    Ok(())
+}
+
+/// `itr project switch [<name>]` — store `name` as `project.current`, which
+/// scopes every later command that has no `--project`. No name clears it.
+pub fn switch(conn: &Connection, name: Option<&str>, fmt: Format) -> Result<(), ItrError> {
+    let name = name.map(str::trim).filter(|n| !n.is_empty());
+    match name {
+        Some(n) => {
+            require(conn, n)?;
+            db::config_set(conn, db::CURRENT_PROJECT_KEY, n)?;
+        }
+        None => db::config_delete(conn, db::CURRENT_PROJECT_KEY)?,
+    }
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({ "action": "project_switch", "current": name });
+            format::println_json(&out.to_string());
+        }
+        _ => println!("CURRENT:{}", format::escape_line_value(name.unwrap_or(""))),
+    }
+    Ok(())
+}
+
+/// `itr project list` — every project with open/closed issue counts.
+pub fn list(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
+    let counts = db::project_counts(conn)?;
+    if counts.is_empty() {
+        error::print_empty(fmt.is_json(), "No projects.");
+        return Ok(());
+    }
+    format::print_output(&format::format_project_counts(&counts, fmt), fmt);
+    Ok(())
+}
+
+/// `itr project move <project> <id>...` — move issues into `project`,
+/// creating it if needed, in one transaction. An empty name detaches them.
+pub fn move_issues(
+    conn: &Connection,
+    project: &str,
+    ids: &[i64],
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let target = match project.trim() {
+        "" => None,
+        _ => Some(validate_name(project)?),
+    };
+    let tx = db::begin_write(conn)?;
+    let mut moved = Vec::with_capacity(ids.len());
+    for &id in ids {
+        if db::set_issue_project(&tx, id, target.as_deref())?.is_some() {
+            moved.push(id);
+        }
+    }
+    tx.commit()?;
+    if moved.len() < ids.len() {
+        eprintln!(
+            "REVIEW: {} issue(s) were already in the target project",
+            ids.len() - moved.len()
+        );
+    }
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "action": "project_move",
+                "project": target,
+                "moved": moved,
+            });
+            format::println_json(&out.to_string());
+        }
+        _ => println!(
+            "PROJECT:{} MOVED:{}",
+            format::escape_line_value(target.as_deref().unwrap_or("")),
+            moved
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+    }
+    Ok(())
+}
+
+fn require(conn: &Connection, name: &str) -> Result<(), ItrError> {
+    if db::project_exists(conn, name)? {
+        return Ok(());
+    }
+    let names: Vec<String> = db::project_counts(conn)?
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    Err(ItrError::InvalidValue {
+        field: "project".to_string(),
+        value: name.to_string(),
+        valid: if names.is_empty() {
+            "none yet; create one with `itr project add`".to_string()
+        } else {
+            names.join(", ")
+        },
+    })
+}
+
+/// Key prefixes are stored uppercased and belong to one project each, so a
+/// key always names the project it was handed out in.
+fn validate_key_prefix(conn: &Connection, name: &str, raw: &str) -> Result<String, ItrError> {
+    let invalid = |valid: String| ItrError::InvalidValue {
+        field: "key prefix".to_string(),
+        value: raw.to_string(),
+        valid,
+    };
+    let prefix = util::normalize_key_prefix(raw).ok_or_else(|| {
+        invalid(format!(
+            "a letter followed by letters or digits, at most {} characters (e.g. APP)",
+            util::MAX_KEY_PREFIX_LEN
+        ))
+    })?;
+    if let Some(other) = db::project_with_key_prefix(conn, &prefix, name)? {
+        return Err(invalid(format!(
+            "a prefix no other project uses ({} belongs to '{}')",
+            prefix, other
+        )));
+    }
+    Ok(prefix)
+}
+
+/// Project names are stored trimmed and, like tags, can't contain commas.
+fn validate_name(name: &str) -> Result<String, ItrError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed.contains(',') {
+        return Err(ItrError::InvalidValue {
+            field: "project".to_string(),
+            value: name.to_string(),
+            valid: "a non-empty name without commas".to_string(),
+        });
+    }
+    Ok(trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ListFilter;
+
+    #[test]
+    fn moved_issues_are_counted_and_filtered_by_project() {
+        let conn = db::open_test_db();
+        db::add_project(&conn, "api", "").unwrap();
+        let add = |title: &str| {
+            db::insert_issue(
+                &conn,
+                title,
+                "medium",
+                "task",
+                "",
+                &[],
+                &[],
+                &[],
+                "",
+                None,
+                "",
+            )
+            .unwrap()
+            .id
+        };
+        let (a, b, loose) = (add("a"), add("b"), add("loose"));
+        move_issues(&conn, "api", &[a], Format::Json).unwrap();
+        // Moving into a missing project creates it.
+        move_issues(&conn, "web", &[b], Format::Json).unwrap();
+
+        let filter = ListFilter {
+            project: Some("api".to_string()),
+            ..ListFilter::default()
+        };
+        let listed: Vec<i64> = db::list_issues(&conn, &filter)
+            .unwrap()
+            .iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(listed, [a]);
+        assert_eq!(db::get_issue(&conn, b).unwrap().project, "web");
+        assert_eq!(db::get_issue(&conn, loose).unwrap().project, "");
+        let counts = db::project_counts(&conn).unwrap();
+        assert_eq!(
+            counts
+                .iter()
+                .map(|p| (p.name.as_str(), p.open))
+                .collect::<Vec<_>>(),
+            [("api", 1), ("web", 1)]
+        );
+
+        // An empty name detaches, and the move is recorded as an event.
+        move_issues(&conn, "", &[a], Format::Json).unwrap();
+        assert_eq!(db::get_issue(&conn, a).unwrap().project, "");
+        let events = db::get_events_for_issue(&conn, a).unwrap();
+        assert!(events
+            .iter()
+            .any(|e| e.field == "project" && e.old_value == "api"));
+        assert!(matches!(
+            require(&conn, "nope"),
+            Err(ItrError::InvalidValue { .. })
+        ));
+    }
+}
@@ -1,4 +1,5 @@
-use super::{build_issue_summary_owned, sort_by_urgency_desc};
+use super::{build_issue_summaries, sort_by_urgency_desc};
+use crate::commands::project;
 use crate::db;
 use crate::error::{self, ItrError};
 use crate::format::{self, Format};
@@ -15,10 +16,26 @@ pub fn run(
     assigned_to: Option<String>,
     fmt: Format,
 ) -> Result<(), ItrError> {
-    let mut summaries = ready_summaries(conn, status, skills, assigned_to)?;
+    let statuses = ready_statuses(status);
+    let mut summaries =
+        ready_summaries(conn, statuses.clone(), skills.clone(), assigned_to.clone())?;
 
     if summaries.is_empty() {
-        error::print_empty(fmt.is_json(), "No ready issues found.");
+        let blocked = db::list_issues(
+            conn,
+            &ListFilter {
+                statuses,
+                skills,
+                assigned_to,
+                blocked_only: true,
+                project: project::scope(),
+                ..ListFilter::default()
+            },
+        )?
+        .into_iter()
+        .filter(|i| i.status == "open" || i.status == "in-progress")
+        .count();
+        error::print_empty_blocked(fmt.is_json(), "No ready issues found.", blocked);
         return Ok(());
     }
 
@@ -26,23 +43,18 @@ pub fn run(
         summaries.truncate(n);
     }
 
-    println!("{}", format::format_issue_list(&summaries, fmt));
+    format::print_output(&format::format_issue_list(&summaries, fmt), fmt);
     Ok(())
 }
 
-/// Collect ready (unblocked, non-terminal) issues sorted by urgency.
+/// The statuses `ready` draws from: open and in-progress by default.
 ///
 /// An explicit status filter is normalized with the same synonym tables as
 /// the write paths (`wip` → `in-progress`, ...); values still unrecognized
 /// after normalization emit a REVIEW note instead of silently matching
 /// nothing (#168).
-fn ready_summaries(
-    conn: &Connection,
-    status: Option<String>,
-    skills: Vec<String>,
-    assigned_to: Option<String>,
-) -> Result<Vec<IssueSummary>, ItrError> {
-    let statuses = match status {
+fn ready_statuses(status: Option<String>) -> Vec<String> {
+    match status {
         Some(s) => {
             let (normalized, notes) = normalize::normalize_status_filters(&[s]);
             for note in &notes {
@@ -51,8 +63,16 @@ fn ready_summaries(
             normalized
         }
         None => vec!["open".to_string(), "in-progress".to_string()],
-    };
+    }
+}
 
+/// Collect ready (unblocked, non-terminal) issues sorted by urgency.
+fn ready_summaries(
+    conn: &Connection,
+    statuses: Vec<String>,
+    skills: Vec<String>,
+    assigned_to: Option<String>,
+) -> Result<Vec<IssueSummary>, ItrError> {
     // Ready issues are always unblocked and non-terminal, even when an
     // explicit status filter asks for a terminal status.
     let issues: Vec<_> = db::list_issues(
@@ -61,6 +81,7 @@ fn ready_summaries(
             statuses,
             skills,
             assigned_to,
+            project: project::scope(),
             ..ListFilter::default()
         },
     )?
@@ -70,13 +91,7 @@ fn ready_summaries(
 
     let config = UrgencyConfig::load(conn);
 
-    // Consume issues by value so build_issue_summary_owned can move each
-    // Issue's string/vec fields directly into the resulting IssueSummary
-    // rather than cloning them.
-    let mut summaries: Vec<IssueSummary> = issues
-        .into_iter()
-        .map(|i| build_issue_summary_owned(conn, i, &config))
-        .collect();
+    let mut summaries = build_issue_summaries(conn, issues, &config);
 
     // Sort by urgency descending
     sort_by_urgency_desc(&mut summaries);
@@ -115,8 +130,9 @@ mod tests {
         let wip_id = insert_issue(&conn, "in flight");
         db::update_issue_field(&conn, wip_id, "status", "in-progress").expect("set status");
 
-        let summaries = ready_summaries(&conn, Some("wip".to_string()), vec![], None)
-            .expect("ready with wip filter");
+        let summaries =
+            ready_summaries(&conn, ready_statuses(Some("wip".to_string())), vec![], None)
+                .expect("ready with wip filter");
         let ids: Vec<i64> = summaries.iter().map(|s| s.id).collect();
         assert_eq!(ids, vec![wip_id], "-s wip must match in-progress issues");
     }
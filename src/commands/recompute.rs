@@ -0,0 +1,69 @@
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::urgency::{self, UrgencyConfig};
+use rusqlite::Connection;
+
+/// `itr recompute` — rebuild the urgency cache for every issue from scratch.
+/// Triggers keep the cache current on normal writes; this is the escape
+/// hatch for databases edited outside itr.
+pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
+    let tx = db::begin_write(conn)?;
+    db::clear_urgency_cache(&tx)?;
+    let config = UrgencyConfig::load(&tx);
+    let issues = db::all_issues(&tx)?;
+    for issue in &issues {
+        urgency::compute_urgency(issue, &config, &tx);
+    }
+    tx.commit()?;
+
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({ "action": "recompute", "count": issues.len() });
+            format::println_json(&out.to_string());
+        }
+        _ if format::quiet(fmt) => {}
+        _ => println!("RECOMPUTED: {} issues", issues.len()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(conn: &Connection, id: i64) -> Option<db::UrgencyInputs> {
+        db::cached_urgency_inputs(conn, id).unwrap()
+    }
+
+    #[test]
+    fn triggers_dirty_cache_on_notes_dependencies_and_status() {
+        let conn = db::open_test_db();
+        let config = UrgencyConfig::default();
+        let a = db::insert_test_issue(&conn, "a");
+        let b = db::insert_test_issue(&conn, "b");
+        run(&conn, Format::Compact).unwrap();
+        assert_eq!(cached(&conn, a).map(|i| i.note_count), Some(0));
+
+        db::add_note(&conn, a, "hello", "").unwrap();
+        assert!(cached(&conn, a).is_none(), "note insert dirties");
+        let issue = db::get_issue(&conn, a).unwrap();
+        urgency::compute_urgency(&issue, &config, &conn);
+        assert_eq!(cached(&conn, a).map(|i| i.note_count), Some(1));
+
+        db::add_dependency(&conn, a, b).unwrap();
+        assert!(cached(&conn, a).is_none() && cached(&conn, b).is_none());
+        run(&conn, Format::Compact).unwrap();
+        assert!(cached(&conn, b).unwrap().blocked);
+
+        // Closing the blocker changes b's blocked state through a's status.
+        db::update_issue_field(&conn, a, "status", "done").unwrap();
+        assert!(
+            cached(&conn, b).is_none(),
+            "neighbour status change dirties"
+        );
+        let issue = db::get_issue(&conn, b).unwrap();
+        urgency::compute_urgency(&issue, &config, &conn);
+        assert!(!cached(&conn, b).unwrap().blocked);
+    }
+}
@@ -1,9 +1,14 @@
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
 use rusqlite::Connection;
 
+/// `itr reindex` — rebuild the `#ID` cross-references and the full-text
+/// search index from the issues and notes.
 pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
+    let tx = db::begin_write(conn)?;
+    db::refresh_all_references(&tx)?;
+    tx.commit()?;
     db::fts_rebuild(conn)?;
 
     let count = db::all_issues(conn)?.len();
@@ -14,7 +19,7 @@ pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
                 "action": "reindex",
                 "indexed": count,
             });
-            println!("{}", json);
+            format::print_json(&json.to_string());
         }
         _ => {
             println!("REINDEX: Rebuilt FTS index for {} issues", count);
@@ -1,6 +1,6 @@
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
 use crate::util;
 use rusqlite::Connection;
 
@@ -24,7 +24,7 @@ pub(crate) fn validate_relation_type(relation_type: &str) -> Result<(), ItrError
 /// - Multiple unique IDs: all relations are created in one transaction with
 ///   per-ID soft fallback — a missing ID emits `REVIEW: id N not found;
 ///   skipped`, and an ID equal to `--to` skips the self-relation. Exit 0 if
-///   at least one relation was processed, exit 1 if none were.
+///   at least one relation was processed, exit 5 (`INVALID_VALUE`) if none were.
 pub fn run_relate_multi(
     conn: &Connection,
     id_tokens: &[String],
@@ -34,7 +34,7 @@ pub fn run_relate_multi(
 ) -> Result<(), ItrError> {
     validate_relation_type(relation_type)?;
 
-    let parsed = util::parse_id_tokens(id_tokens);
+    let parsed = util::parse_id_tokens(&db::resolve_id_tokens(conn, id_tokens)?);
     for note in &parsed.notes {
         eprintln!("{}", note);
     }
@@ -70,7 +70,7 @@ pub fn run_relate_multi(
         return Err(ItrError::NotFound(target_id));
     }
 
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
     let mut links: Vec<(i64, bool)> = Vec::new();
     for &id in &parsed.ids {
         if id == target_id {
@@ -110,8 +110,9 @@ pub fn run_relate_multi(
                     })
                 })
                 .collect();
-            println!("{}", serde_json::Value::Array(arr));
+            format::print_json(&serde_json::Value::Array(arr).to_string());
         }
+        _ if format::quiet(fmt) => {}
         _ => {
             for (id, created) in &links {
                 let verb = if *created { "created" } else { "exists" };
@@ -134,7 +135,9 @@ pub fn run_relate(
 ) -> Result<(), ItrError> {
     validate_relation_type(relation_type)?;
 
-    let created = db::add_relation(conn, source_id, target_id, relation_type)?;
+    let tx = db::begin_write(conn)?;
+    let created = db::add_relation(&tx, source_id, target_id, relation_type)?;
+    tx.commit()?;
 
     let msg = if created {
         format!(
@@ -156,8 +159,9 @@ pub fn run_relate(
                 "relation_type": relation_type,
                 "created": created,
             });
-            println!("{}", json);
+            format::print_json(&json.to_string());
         }
+        _ if format::quiet(fmt) => {}
         _ => {
             println!("{}", msg);
         }
@@ -182,7 +186,9 @@ pub fn run_unrelate(
 
     // Direction-aware: the pair is matched however it was stored, and every
     // removed link is reported with its type and stored direction (#186).
-    let removed = db::remove_relation(conn, source_id, target_id, relation_type)?;
+    let tx = db::begin_write(conn)?;
+    let removed = db::remove_relation(&tx, source_id, target_id, relation_type)?;
+    tx.commit()?;
 
     match fmt {
         Format::Json => {
@@ -202,8 +208,9 @@ pub fn run_unrelate(
                 "removed": !removed.is_empty(),
                 "removed_relations": removed_relations,
             });
-            println!("{}", json);
+            format::print_json(&json.to_string());
         }
+        _ if format::quiet(fmt) => {}
         _ => {
             if removed.is_empty() {
                 println!("RELATION:not_found {} -> {}", source_id, target_id);
@@ -0,0 +1,175 @@
+use super::policy;
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::Repro;
+use crate::urgency::UrgencyConfig;
+use rusqlite::Connection;
+use std::io::{self, Read};
+use std::process::Command;
+
+/// Where `itr repro` takes the new reproduction from.
+pub enum Source {
+    /// Print the current reproduction.
+    Show,
+    /// A JSON object on stdin.
+    StdinJson,
+    /// The current reproduction, as JSON, edited in `$VISUAL`/`$EDITOR`.
+    Edit,
+    /// Remove it.
+    Clear,
+}
+
+/// `itr repro <ID> [--stdin-json | --edit | --clear]` — show or replace an
+/// issue's structured reproduction (`steps`, `expected`, `actual`,
+/// `environment`). An empty object clears it, which `policy.require.repro`
+/// refuses for the kinds it lists. Changes are recorded as `repro` events.
+pub fn run(conn: &Connection, id: i64, source: Source, fmt: Format) -> Result<(), ItrError> {
+    let current = db::issue_repro(conn, id)?;
+    let new = match source {
+        Source::Show => {
+            match current {
+                Some(r) => format::print_output(&format::format_repro(id, &r, fmt), fmt),
+                None => {
+                    error::print_empty(
+                        fmt.is_json(),
+                        &format!("No reproduction for issue {}.", id),
+                    );
+                }
+            }
+            return Ok(());
+        }
+        Source::StdinJson => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            parse(&input)?
+        }
+        Source::Edit => parse(&edit(id, &current.clone().unwrap_or_default())?)?,
+        Source::Clear => Repro::default(),
+    };
+    set(conn, id, &new)?;
+    let detail =
+        super::build_issue_detail(conn, db::get_issue(conn, id)?, &UrgencyConfig::load(conn))?;
+    format::print_output(&super::format_issue_result(&detail, fmt), fmt);
+    Ok(())
+}
+
+/// Replace the reproduction in one transaction, checking policy when it
+/// would be left empty and recording a `repro` event when it changes.
+pub(crate) fn set(conn: &Connection, id: i64, repro: &Repro) -> Result<(), ItrError> {
+    let repro = repro.clone().normalized();
+    let tx = db::begin_write(conn)?;
+    let issue = db::get_issue(&tx, id)?;
+    if repro.is_empty() {
+        policy::check_required(&tx, &issue.kind, &issue.context, &issue.acceptance, false)?;
+    }
+    let old = db::issue_repro(&tx, id)?.unwrap_or_default();
+    if old != repro {
+        db::record_event(&tx, id, "repro", &encode(&old)?, &encode(&repro)?)?;
+        db::set_repro(&tx, id, Some(&repro))?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// A reproduction as its `repro` events record it: JSON, or empty for none.
+pub(crate) fn encode(repro: &Repro) -> Result<String, ItrError> {
+    Ok(if repro.is_empty() {
+        String::new()
+    } else {
+        serde_json::to_string(repro)?
+    })
+}
+
+/// Parse a reproduction object; unknown keys are rejected so a typo such as
+/// `expect` isn't silently dropped.
+fn parse(input: &str) -> Result<Repro, ItrError> {
+    if input.trim().is_empty() {
+        return Ok(Repro::default());
+    }
+    Ok(serde_json::from_str(input)?)
+}
+
+/// Open the reproduction as pretty JSON in `$VISUAL`, then `$EDITOR`, then
+/// `vi`, and return what was saved. The editor command runs through `sh`, so
+/// values such as `code --wait` work.
+fn edit(id: i64, repro: &Repro) -> Result<String, ItrError> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    let path = std::env::temp_dir().join(format!("itr-repro-{}-{}.json", id, std::process::id()));
+    std::fs::write(&path, serde_json::to_string_pretty(repro)? + "\n")?;
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(&path)
+        .status();
+    let edited = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+    let status = status?;
+    if !status.success() {
+        return Err(ItrError::Aborted(format!(
+            "editor `{}` exited with {}; reproduction unchanged",
+            editor, status
+        )));
+    }
+    Ok(edited?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{self, NewIssue};
+
+    #[test]
+    fn repro_is_set_cleared_and_required_by_policy() {
+        let conn = db::open_test_db();
+        let id = store::add_issue(
+            &conn,
+            NewIssue {
+                kind: "bug".to_string(),
+                repro: Some(Repro {
+                    steps: vec![" open file ".to_string(), "  ".to_string()],
+                    actual: "crash".to_string(),
+                    ..Repro::default()
+                }),
+                ..NewIssue::new("Crash on open")
+            },
+        )
+        .unwrap()
+        .issue
+        .id;
+        let stored = db::issue_repro(&conn, id).unwrap().unwrap();
+        assert_eq!(stored.steps, vec!["open file".to_string()]);
+
+        let edited =
+            parse(r#"{"steps":["open file"],"expected":"opens","actual":"crash"}"#).unwrap();
+        set(&conn, id, &edited).unwrap();
+        assert_eq!(
+            db::issue_repro(&conn, id).unwrap().unwrap().expected,
+            "opens"
+        );
+        assert!(
+            parse(r#"{"expect":"opens"}"#).is_err(),
+            "unknown keys are rejected"
+        );
+
+        db::config_set(&conn, policy::REQUIRE_REPRO_KEY, "bug").unwrap();
+        let err = set(&conn, id, &Repro::default()).unwrap_err();
+        assert!(matches!(&err, ItrError::InvalidValue { field, .. } if field == "repro"));
+        db::config_set(&conn, policy::REQUIRE_REPRO_KEY, "").unwrap();
+        set(&conn, id, &parse("").unwrap()).unwrap();
+        assert!(db::issue_repro(&conn, id).unwrap().is_none());
+
+        let events: Vec<_> = db::get_events_for_issue(&conn, id)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.field == "repro")
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].new_value, "");
+    }
+}
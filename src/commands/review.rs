@@ -0,0 +1,154 @@
+use super::assign::print_detail;
+use super::{build_issue_summaries, print_detail_with_unblocked, sort_by_urgency_desc};
+use crate::commands::project;
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::ListFilter;
+use crate::notify;
+use crate::urgency::UrgencyConfig;
+use rusqlite::Connection;
+
+/// Close reason recorded when `itr review approve` is given none.
+const DEFAULT_APPROVAL: &str = "Approved in review";
+
+/// `itr review list` — issues in `review` status, most urgent first.
+pub fn list(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
+    let issues = db::list_issues(
+        conn,
+        &ListFilter {
+            statuses: vec!["review".to_string()],
+            include_blocked: true,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    let mut summaries = build_issue_summaries(conn, issues, &UrgencyConfig::load(conn));
+    if summaries.is_empty() {
+        error::print_empty(fmt.is_json(), "No issues awaiting review.");
+        return Ok(());
+    }
+    sort_by_urgency_desc(&mut summaries);
+    format::print_output(&format::format_issue_list(&summaries, fmt), fmt);
+    Ok(())
+}
+
+/// `itr review approve <ID> [REASON]` — close an issue in review as `done`,
+/// with the reason (default "Approved in review") as its close reason.
+pub fn approve(
+    conn: &Connection,
+    id: i64,
+    reason: Option<String>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let tx = db::begin_write(conn)?;
+    require_review(&tx, id)?;
+    let reason = reason
+        .filter(|r| !r.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_APPROVAL.to_string());
+    let (detail, unblocked) = super::close::close_issue(&tx, id, Some(reason), false, None)?;
+    tx.commit()?;
+    print_detail_with_unblocked(&detail, &unblocked, fmt);
+    notify::unblocked(conn, &unblocked);
+    Ok(())
+}
+
+/// `itr review reject <ID> <NOTE>` — send an issue in review back to `open`
+/// and record why as a `review` note, in one transaction. The assignee is
+/// kept so the same agent picks the rework up.
+pub fn reject(
+    conn: &Connection,
+    id: i64,
+    note: &str,
+    agent: &str,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    if note.trim().is_empty() {
+        return Err(ItrError::InvalidValue {
+            field: "note".to_string(),
+            value: note.to_string(),
+            valid: "non-empty string saying what needs to change".to_string(),
+        });
+    }
+    let author = super::note::resolve_agent(conn, agent);
+
+    let tx = db::begin_write(conn)?;
+    require_review(&tx, id)?;
+    db::record_event(&tx, id, "status", "review", "open")?;
+    db::update_issue_field(&tx, id, "status", "open")?;
+    db::add_note_of_kind(&tx, id, note.trim(), &author, db::NOTE_KIND_REVIEW)?;
+    tx.commit()?;
+
+    print_detail(conn, id, fmt)
+}
+
+/// Fail with `INVALID_VALUE` unless the issue is in `review`.
+fn require_review(conn: &Connection, id: i64) -> Result<(), ItrError> {
+    let issue = db::get_issue(conn, id)?;
+    if issue.status == "review" {
+        Ok(())
+    } else {
+        Err(ItrError::InvalidValue {
+            field: "status".to_string(),
+            value: issue.status,
+            valid: format!(
+                "review (move issue {} there with `itr update {} --status review`)",
+                id, id
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn in_review(conn: &Connection) -> i64 {
+        let id = db::insert_test_issue_with(conn, "Parser fix", "bug", None);
+        db::update_issue_field(conn, id, "assigned_to", "ann").unwrap();
+        db::update_issue_field(conn, id, "status", "review").unwrap();
+        id
+    }
+
+    #[test]
+    fn reject_reopens_with_a_review_note() {
+        let conn = db::open_test_db();
+        let id = in_review(&conn);
+        reject(&conn, id, "  Missing a test  ", "bo", Format::Json).unwrap();
+
+        let issue = db::get_issue(&conn, id).unwrap();
+        assert_eq!(issue.status, "open");
+        assert_eq!(issue.assigned_to, "ann");
+        let notes = db::get_notes(&conn, id).unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].content, "Missing a test");
+        assert_eq!(notes[0].agent, "bo");
+        assert_eq!(notes[0].kind, db::NOTE_KIND_REVIEW);
+    }
+
+    #[test]
+    fn approve_and_reject_require_review_status() {
+        let conn = db::open_test_db();
+        let id = in_review(&conn);
+        assert!(matches!(
+            reject(&conn, id, " ", "", Format::Json),
+            Err(ItrError::InvalidValue { ref field, .. }) if field == "note"
+        ));
+
+        approve(&conn, id, None, Format::Json).unwrap();
+        let issue = db::get_issue(&conn, id).unwrap();
+        assert_eq!(issue.status, "done");
+        assert_eq!(issue.close_reason, DEFAULT_APPROVAL);
+
+        for result in [
+            approve(&conn, id, None, Format::Json),
+            reject(&conn, id, "again", "", Format::Json),
+        ] {
+            assert!(matches!(
+                result,
+                Err(ItrError::InvalidValue { ref field, ref value, .. })
+                    if field == "status" && value == "done"
+            ));
+        }
+    }
+}
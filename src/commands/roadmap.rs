@@ -0,0 +1,200 @@
+use crate::commands::project;
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::{is_terminal, Issue, ListFilter};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// One bar on the timeline. Dates are `YYYY-MM-DD` (UTC).
+#[derive(Debug, Serialize)]
+struct Bar {
+    id: i64,
+    title: String,
+    status: String,
+    start: String,
+    end: String,
+}
+
+/// An epic and its direct children, as printed by `itr roadmap -f json`.
+#[derive(Debug, Serialize)]
+struct Lane {
+    #[serde(flatten)]
+    epic: Bar,
+    done: usize,
+    total: usize,
+    items: Vec<Bar>,
+}
+
+/// `itr roadmap` — lay out epics and their children on a timeline. Issues
+/// have no due dates and point estimates are not durations, so each bar runs
+/// from the day the issue was created to the day it was closed, or to today
+/// while it is still open.
+///
+/// Closed epics are left out unless `all` is set; an epic's closed children
+/// are always shown, as finished bars.
+pub fn run(conn: &Connection, all: bool, fmt: Format) -> Result<(), ItrError> {
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let lanes = collect(conn, all, &today)?;
+    if lanes.is_empty() {
+        error::print_empty(fmt.is_json(), "No epics found.");
+    } else if fmt.is_json() {
+        format::print_json(&serde_json::to_string(&lanes)?);
+    } else {
+        print!("{}", gantt(&lanes));
+    }
+    Ok(())
+}
+
+fn collect(conn: &Connection, all: bool, today: &str) -> Result<Vec<Lane>, ItrError> {
+    let issues = db::list_issues(
+        conn,
+        &ListFilter {
+            all: true,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    let mut children: HashMap<i64, Vec<&Issue>> = HashMap::new();
+    for issue in &issues {
+        if let Some(parent) = issue.parent_id {
+            children.entry(parent).or_default().push(issue);
+        }
+    }
+
+    let mut lanes = Vec::new();
+    for epic in issues
+        .iter()
+        .filter(|i| i.kind == "epic" && (all || !is_terminal(&i.status)))
+    {
+        let kids = children.get(&epic.id).map_or(&[][..], Vec::as_slice);
+        let mut items = Vec::with_capacity(kids.len());
+        for kid in kids {
+            items.push(bar(conn, kid, today)?);
+        }
+        lanes.push(Lane {
+            epic: bar(conn, epic, today)?,
+            done: kids.iter().filter(|k| is_terminal(&k.status)).count(),
+            total: kids.len(),
+            items,
+        });
+    }
+    lanes.sort_by(|a, b| (&a.epic.start, a.epic.id).cmp(&(&b.epic.start, b.epic.id)));
+    Ok(lanes)
+}
+
+fn bar(conn: &Connection, issue: &Issue, today: &str) -> Result<Bar, ItrError> {
+    let start = date(&issue.created_at);
+    let end = if is_terminal(&issue.status) {
+        // The last move into a closed status; issues closed before events
+        // were recorded fall back to their last update.
+        let closed = db::get_events_for_issue(conn, issue.id)?
+            .into_iter()
+            .rev()
+            .find(|e| e.field == "status" && is_terminal(&e.new_value))
+            .map_or_else(|| issue.updated_at.clone(), |e| e.created_at);
+        date(&closed)
+    } else {
+        today.to_string()
+    };
+    Ok(Bar {
+        id: issue.id,
+        title: issue.title.clone(),
+        status: issue.status.clone(),
+        end: end.max(start.clone()),
+        start,
+    })
+}
+
+fn date(timestamp: &str) -> String {
+    timestamp.chars().take(10).collect()
+}
+
+/// Mermaid `gantt` source: one section per epic, the epic's own bar first.
+fn gantt(lanes: &[Lane]) -> String {
+    let mut out = String::from("gantt\n    title Roadmap\n    dateFormat YYYY-MM-DD\n");
+    for lane in lanes {
+        out.push_str(&format!("    section {}\n", label(&lane.epic.title)));
+        for bar in std::iter::once(&lane.epic).chain(&lane.items) {
+            let tag = match bar.status.as_str() {
+                "done" | "wontfix" => "done, ",
+                "in-progress" | "review" => "active, ",
+                _ => "",
+            };
+            // A bar needs a length: same-day issues get one day.
+            let end = if bar.end == bar.start { "1d" } else { &bar.end };
+            out.push_str(&format!(
+                "    {} :{}i{}, {}, {}\n",
+                label(&bar.title),
+                tag,
+                bar.id,
+                bar.start,
+                end
+            ));
+        }
+    }
+    out
+}
+
+/// A title Mermaid can parse: `:` ends a task name, `#` starts an entity, and
+/// `;` ends a statement.
+fn label(title: &str) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| if matches!(c, ':' | '#' | ';') { ' ' } else { c })
+        .collect();
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn epics_become_sections_with_history_spans() {
+        let conn = db::init_db(Path::new(":memory:")).unwrap();
+        let epic = db::insert_test_issue_with(&conn, "Auth: overhaul", "epic", None);
+        let login = db::insert_test_issue_with(&conn, "Login #1", "task", Some(epic));
+        let logout = db::insert_test_issue_with(&conn, "Logout", "task", Some(epic));
+        let closed = db::insert_test_issue_with(&conn, "Old epic", "epic", None);
+        conn.execute(
+            "UPDATE issues SET created_at = '2026-01-05T09:00:00Z' WHERE id IN (?1, ?2, ?3)",
+            [epic, login, logout],
+        )
+        .unwrap();
+        db::update_issue_field(&conn, login, "status", "done").unwrap();
+        db::record_event_at(
+            &conn,
+            login,
+            "status",
+            "open",
+            "done",
+            "2026-01-09T10:00:00Z",
+        )
+        .unwrap();
+        db::update_issue_field(&conn, logout, "status", "in-progress").unwrap();
+        db::update_issue_field(&conn, closed, "status", "done").unwrap();
+
+        let lanes = collect(&conn, false, "2026-02-01").unwrap();
+        assert_eq!(lanes.len(), 1, "closed epics are hidden without --all");
+        assert_eq!((lanes[0].done, lanes[0].total), (1, 2));
+        assert_eq!(lanes[0].items[0].end, "2026-01-09");
+        assert_eq!(lanes[0].items[1].end, "2026-02-01");
+        assert_eq!(collect(&conn, true, "2026-02-01").unwrap().len(), 2);
+
+        let text = gantt(&lanes);
+        assert!(text.starts_with("gantt\n"));
+        assert!(text.contains("    section Auth overhaul\n"));
+        assert!(text.contains(&format!(
+            "    Auth overhaul :i{epic}, 2026-01-05, 2026-02-01\n"
+        )));
+        assert!(text.contains(&format!(
+            "    Login 1 :done, i{login}, 2026-01-05, 2026-01-09\n"
+        )));
+        assert!(text.contains(&format!(
+            "    Logout :active, i{logout}, 2026-01-05, 2026-02-01\n"
+        )));
+    }
+}
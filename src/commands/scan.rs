@@ -0,0 +1,507 @@
+//! `itr scan [PATH]`: turn `TODO`, `FIXME`, and `HACK` comments in the
+//! source tree into issues tagged `code-todo`. Each comment is identified by
+//! a fingerprint of its file, marker, and text (not its line number), kept
+//! on the last line of the issue's context, so re-scanning after edits moves
+//! the issue along with the comment instead of filing a duplicate.
+
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::is_terminal;
+use itr_core::store::{self, NewIssue};
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Tag every scanned issue carries.
+pub const TAG: &str = "code-todo";
+
+/// Context line holding a scanned issue's fingerprint.
+const FINGERPRINT_PREFIX: &str = "itr-scan: ";
+
+const MARKERS: [&str; 3] = ["TODO", "FIXME", "HACK"];
+
+/// Where a line comment or block comment can start, across common languages.
+const COMMENT_STARTS: [&str; 7] = ["//", "/*", "<!--", "#", "--", ";", "%"];
+
+/// Files larger than this are assumed to be generated or data, and skipped.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// One marker comment found in a file.
+#[derive(Debug, PartialEq)]
+struct Found {
+    path: String,
+    line: usize,
+    marker: &'static str,
+    text: String,
+    source: String,
+    fingerprint: String,
+}
+
+impl Found {
+    fn title(&self) -> String {
+        if self.text.is_empty() {
+            format!("{} in {}", self.marker, self.path)
+        } else {
+            self.text.clone()
+        }
+    }
+
+    fn context(&self) -> String {
+        format!(
+            "`{}` comment at {}:{}:\n\n    {}\n\n{}{}",
+            self.marker, self.path, self.line, self.source, FINGERPRINT_PREFIX, self.fingerprint
+        )
+    }
+
+    fn location(&self) -> String {
+        format!("{}:{}", self.path, self.line)
+    }
+}
+
+/// The marker and its text when `line` holds a comment whose first word is
+/// `TODO`, `FIXME`, or `HACK` (uppercase, as a whole word). An `(author)`
+/// after the marker and `:`/`-` separators are dropped, as are the closing
+/// `*/` or `-->` of a one-line block comment.
+fn extract(line: &str) -> Option<(&'static str, String)> {
+    let trimmed = line.trim_start();
+    // A `*` line continues a block comment.
+    let mut bodies: Vec<&str> = trimmed.strip_prefix('*').into_iter().collect();
+    for start in COMMENT_STARTS {
+        if let Some(pos) = line.find(start) {
+            bodies.push(&line[pos + start.len()..]);
+        }
+    }
+    for body in bodies {
+        let body = body
+            .trim_start_matches(['/', '*', '!', '#', '-', ';', '%'])
+            .trim_start();
+        for marker in MARKERS {
+            let Some(rest) = body.strip_prefix(marker) else {
+                continue;
+            };
+            if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+                continue;
+            }
+            let rest = match rest.strip_prefix('(') {
+                Some(inner) => inner.split_once(')').map_or(inner, |(_, after)| after),
+                None => rest,
+            };
+            let text = rest
+                .trim_start_matches([':', '-', ' ', '\t'])
+                .trim_end()
+                .trim_end_matches("*/")
+                .trim_end_matches("-->")
+                .trim();
+            return Some((
+                marker,
+                text.split_whitespace().collect::<Vec<_>>().join(" "),
+            ));
+        }
+    }
+    None
+}
+
+/// 64-bit FNV-1a, written out so fingerprints stay the same across Rust
+/// versions (the standard hashers make no such promise).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Every marker comment in `text`. Identical comments in one file are told
+/// apart by their order, so each still gets its own issue.
+fn scan_text(path: &str, text: &str) -> Vec<Found> {
+    let mut seen: HashMap<(&'static str, String), usize> = HashMap::new();
+    let mut found = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let Some((marker, comment)) = extract(line) else {
+            continue;
+        };
+        let nth = seen.entry((marker, comment.clone())).or_default();
+        let key = format!("{}\0{}\0{}\0{}", path, marker, comment, nth);
+        *nth += 1;
+        found.push(Found {
+            path: path.to_string(),
+            line: i + 1,
+            marker,
+            text: comment,
+            source: line.trim().to_string(),
+            fingerprint: format!("{:016x}", fnv1a(key.as_bytes())),
+        });
+    }
+    found
+}
+
+/// The fingerprint on the last `itr-scan:` line of `context`, if any.
+fn fingerprint_of(context: &str) -> Option<&str> {
+    context
+        .lines()
+        .rev()
+        .find_map(|line| line.strip_prefix(FINGERPRINT_PREFIX))
+        .map(str::trim)
+}
+
+/// The files to scan under `path`, relative to the returned root. Inside a
+/// git work tree that is `git ls-files` (tracked plus untracked, minus
+/// anything ignored), named from the top of the tree so the paths do not
+/// depend on where `itr scan` runs. Elsewhere every file under `path` is
+/// walked, skipping hidden directories, with a `REVIEW:` note.
+fn list_files(path: &str) -> Result<(PathBuf, Vec<String>), ItrError> {
+    let start = Path::new(path);
+    if !start.exists() {
+        return Err(ItrError::InvalidValue {
+            field: "path".to_string(),
+            value: path.to_string(),
+            valid: "an existing file or directory".to_string(),
+        });
+    }
+    let toplevel = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success());
+    if let Some(top) = toplevel {
+        let output = Command::new("git")
+            .args([
+                "ls-files",
+                "-z",
+                "--full-name",
+                "--cached",
+                "--others",
+                "--exclude-standard",
+                "--",
+                path,
+            ])
+            .output()?;
+        if output.status.success() {
+            let root = PathBuf::from(String::from_utf8_lossy(&top.stdout).trim());
+            let mut files: Vec<String> = String::from_utf8_lossy(&output.stdout)
+                .split('\0')
+                .filter(|f| !f.is_empty())
+                .map(str::to_string)
+                .collect();
+            files.sort();
+            files.dedup();
+            return Ok((root, files));
+        }
+    }
+    eprintln!(
+        "REVIEW: scan: not inside a git work tree; scanning every file under {} except hidden directories",
+        path
+    );
+    let mut files = Vec::new();
+    let mut dirs = vec![start.to_path_buf()];
+    if start.is_file() {
+        files.push(path.to_string());
+        dirs.clear();
+    }
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                dirs.push(entry.path());
+            } else if file_type.is_file() {
+                let name = entry.path().to_string_lossy().into_owned();
+                files.push(name.strip_prefix("./").unwrap_or(&name).to_string());
+            }
+        }
+    }
+    files.sort();
+    Ok((PathBuf::from("."), files))
+}
+
+/// The text of a file worth scanning: small, valid UTF-8, and without NUL
+/// bytes. Anything else (binaries, images, a vanished file) is `None`.
+fn read_text(path: &Path) -> Option<String> {
+    if fs::metadata(path).ok()?.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// What one scan did.
+#[derive(Debug, Default)]
+struct ScanOutcome {
+    files: usize,
+    comments: usize,
+    created: Vec<(i64, String, String)>,
+    updated: Vec<(i64, String, String)>,
+    unchanged: usize,
+    /// Open scanned issues whose comment is gone from a scanned or deleted file.
+    gone: Vec<i64>,
+}
+
+/// File each comment in `found` as an issue, or bring the open issue with
+/// its fingerprint up to date with where the comment now sits. Closed issues
+/// are left alone even when their comment is still there. `scanned` and
+/// `exists` decide which unmatched issues count as gone.
+fn apply(
+    conn: &Connection,
+    found: Vec<Found>,
+    scanned: &HashSet<String>,
+    exists: impl Fn(&str) -> bool,
+) -> Result<ScanOutcome, ItrError> {
+    let tx = db::begin_write(conn)?;
+    let mut known: HashMap<String, crate::models::Issue> = HashMap::new();
+    for issue in db::all_issues(&tx)? {
+        if let Some(fingerprint) = fingerprint_of(&issue.context) {
+            known.entry(fingerprint.to_string()).or_insert(issue);
+        }
+    }
+
+    let mut outcome = ScanOutcome {
+        files: scanned.len(),
+        comments: found.len(),
+        ..ScanOutcome::default()
+    };
+    let mut seen: HashSet<String> = HashSet::new();
+    for comment in found {
+        seen.insert(comment.fingerprint.clone());
+        let context = comment.context();
+        match known.get(&comment.fingerprint) {
+            Some(issue) if is_terminal(&issue.status) => {
+                outcome.unchanged += 1;
+            }
+            Some(issue) => {
+                let files = vec![comment.path.clone()];
+                if issue.context == context && issue.files == files {
+                    outcome.unchanged += 1;
+                    continue;
+                }
+                if issue.context != context {
+                    db::record_event(&tx, issue.id, "context", &issue.context, &context)?;
+                    db::update_issue_field(&tx, issue.id, "context", &context)?;
+                }
+                super::update::persist_list_field(&tx, issue.id, "files", &issue.files, &files)?;
+                outcome
+                    .updated
+                    .push((issue.id, issue.title.clone(), comment.location()));
+            }
+            None => {
+                let mut request = NewIssue::new(comment.title());
+                request.kind = if comment.marker == "FIXME" {
+                    "bug"
+                } else {
+                    "task"
+                }
+                .to_string();
+                request.context = context;
+                request.files = vec![comment.path.clone()];
+                request.tags = vec![TAG.to_string()];
+                request.project = crate::commands::project::scope().unwrap_or_default();
+                let detail = store::add_issue(&tx, request)?;
+                outcome
+                    .created
+                    .push((detail.issue.id, detail.issue.title, comment.location()));
+            }
+        }
+    }
+
+    let mut gone: Vec<i64> = known
+        .into_iter()
+        .filter(|(fingerprint, issue)| {
+            !seen.contains(fingerprint)
+                && !is_terminal(&issue.status)
+                && issue
+                    .files
+                    .first()
+                    .is_some_and(|file| scanned.contains(file) || !exists(file))
+        })
+        .map(|(_, issue)| issue.id)
+        .collect();
+    gone.sort_unstable();
+    outcome.gone = gone;
+    tx.commit()?;
+    Ok(outcome)
+}
+
+/// `itr scan [PATH]`.
+pub fn run(conn: &Connection, path: Option<&str>, fmt: Format) -> Result<(), ItrError> {
+    let path = path.unwrap_or(".");
+    let (root, files) = list_files(path)?;
+    let mut found = Vec::new();
+    for file in &files {
+        if let Some(text) = read_text(&root.join(file)) {
+            found.extend(scan_text(file, &text));
+        }
+    }
+    let scanned: HashSet<String> = files.into_iter().collect();
+    let outcome = apply(conn, found, &scanned, |file| root.join(file).exists())?;
+
+    if !outcome.gone.is_empty() {
+        let ids: Vec<String> = outcome.gone.iter().map(ToString::to_string).collect();
+        eprintln!(
+            "REVIEW: scan: the comment behind open issue(s) {} is gone; close them with `itr close` if the work is done",
+            ids.join(", ")
+        );
+    }
+    if outcome.comments == 0 && outcome.gone.is_empty() {
+        error::print_empty(
+            fmt.is_json(),
+            &format!("No TODO/FIXME/HACK comments in {} file(s).", outcome.files),
+        );
+        return Ok(());
+    }
+
+    let entries = |list: &[(i64, String, String)]| -> Vec<serde_json::Value> {
+        list.iter()
+            .map(|(id, title, at)| serde_json::json!({"id": id, "title": title, "at": at}))
+            .collect()
+    };
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "action": "scan",
+                "files": outcome.files,
+                "comments": outcome.comments,
+                "created": entries(&outcome.created),
+                "updated": entries(&outcome.updated),
+                "unchanged": outcome.unchanged,
+                "gone": outcome.gone,
+            });
+            format::print_json(&out.to_string());
+        }
+        _ => {
+            let mut lines = Vec::new();
+            for (label, list) in [("CREATED", &outcome.created), ("UPDATED", &outcome.updated)] {
+                for (id, title, at) in list {
+                    if format::quiet(fmt) {
+                        lines.push(id.to_string());
+                    } else {
+                        lines.push(format!(
+                            "{}:{} AT:{} \"{}\"",
+                            label,
+                            id,
+                            at,
+                            format::escape_quoted_value(title)
+                        ));
+                    }
+                }
+            }
+            if !format::quiet(fmt) {
+                lines.push(format!(
+                    "SCAN: {} comment(s) in {} file(s): {} created, {} updated, {} unchanged",
+                    outcome.comments,
+                    outcome.files,
+                    outcome.created.len(),
+                    outcome.updated.len(),
+                    outcome.unchanged
+                ));
+            }
+            if !lines.is_empty() {
+                println!("{}", lines.join("\n"));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_needs_the_marker_to_open_a_comment() {
+        let cases = [
+            (
+                "    // TODO: handle overflow",
+                Some(("TODO", "handle overflow")),
+            ),
+            (
+                "x = 1  # FIXME(bob) - off by one",
+                Some(("FIXME", "off by one")),
+            ),
+            ("/* HACK: temporary */", Some(("HACK", "temporary"))),
+            (" * TODO follow up", Some(("TODO", "follow up"))),
+            ("<!-- TODO: docs -->", Some(("TODO", "docs"))),
+            ("/// TODO", Some(("TODO", ""))),
+            (
+                "-- TODO:   squash   spaces",
+                Some(("TODO", "squash spaces")),
+            ),
+            ("// not a TODO here", None),
+            ("// TODOS are words", None),
+            ("// todo: lowercase", None),
+            ("let todo = \"TODO\";", None),
+        ];
+        for (line, want) in cases {
+            let got = extract(line);
+            assert_eq!(
+                got.as_ref().map(|(m, t)| (*m, t.as_str())),
+                want,
+                "{:?}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn fingerprints_ignore_line_numbers_but_not_repeats() {
+        let before = scan_text("src/a.rs", "// TODO: x\nfn f() {}\n// TODO: x\n");
+        let after = scan_text("src/a.rs", "\n\n// TODO: x\n// TODO: x\n");
+        assert_eq!(before.len(), 2);
+        assert_ne!(before[0].fingerprint, before[1].fingerprint);
+        assert_eq!(
+            before.iter().map(|f| &f.fingerprint).collect::<Vec<_>>(),
+            after.iter().map(|f| &f.fingerprint).collect::<Vec<_>>()
+        );
+        assert_eq!(after[0].line, 3);
+        let elsewhere = scan_text("src/b.rs", "// TODO: x\n");
+        assert_ne!(elsewhere[0].fingerprint, before[0].fingerprint);
+        assert_eq!(
+            fingerprint_of(&before[0].context()),
+            Some(before[0].fingerprint.as_str())
+        );
+    }
+
+    #[test]
+    fn rescans_update_moved_comments_and_report_gone_ones() {
+        let conn = db::open_test_db();
+        let scanned = HashSet::from(["src/a.rs".to_string()]);
+        let first = apply(
+            &conn,
+            scan_text("src/a.rs", "// FIXME: one\n// TODO: two\n"),
+            &scanned,
+            |_| true,
+        )
+        .unwrap();
+        assert_eq!(first.created.len(), 2);
+        let one = db::get_issue(&conn, first.created[0].0).unwrap();
+        assert_eq!(one.kind, "bug");
+        assert_eq!(one.tags, [TAG]);
+        assert_eq!(one.files, ["src/a.rs"]);
+
+        let second = apply(
+            &conn,
+            scan_text("src/a.rs", "\n\n// TODO: two\n"),
+            &scanned,
+            |_| true,
+        )
+        .unwrap();
+        assert!(second.created.is_empty());
+        assert_eq!(second.updated.len(), 1);
+        assert_eq!(second.updated[0].2, "src/a.rs:3");
+        assert_eq!(second.gone, [one.id]);
+
+        let third = apply(
+            &conn,
+            scan_text("src/a.rs", "\n\n// TODO: two\n"),
+            &scanned,
+            |_| true,
+        )
+        .unwrap();
+        assert_eq!((third.updated.len(), third.unchanged), (0, 1));
+    }
+}
@@ -1,15 +1,21 @@
+use crate::cli::SchemaModel;
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
+use crate::models::{json_schema, GraphOutput, Issue, IssueDetail, Stats};
+use clap::ValueEnum;
+
+pub fn run(json: bool, model: Option<SchemaModel>, fmt: Format) -> Result<(), ItrError> {
+    if json {
+        return print_json_schema(model, fmt);
+    }
 
-#[allow(clippy::unnecessary_wraps)]
-pub fn run(fmt: Format) -> Result<(), ItrError> {
     let schema = db::get_schema_sql();
 
     match fmt {
         Format::Json => {
             let out = serde_json::json!({ "schema": schema });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             println!("{}", schema);
@@ -18,3 +24,61 @@ pub fn run(fmt: Format) -> Result<(), ItrError> {
 
     Ok(())
 }
+
+/// `itr schema --json [--type T]` — JSON Schema for what `-f json` puts under
+/// `data`: one model's schema, or every model's keyed by its `--type` name.
+/// Text formats pretty-print the document; `-f json` wraps it like any other
+/// response.
+fn print_json_schema(model: Option<SchemaModel>, fmt: Format) -> Result<(), ItrError> {
+    let doc = match model {
+        Some(model) => model_schema(model),
+        None => SchemaModel::value_variants()
+            .iter()
+            .map(|&model| (model_name(model), model_schema(model)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+    };
+    if fmt.is_json() {
+        format::print_json(&doc.to_string());
+    } else {
+        println!("{}", serde_json::to_string_pretty(&doc)?);
+    }
+    Ok(())
+}
+
+fn model_schema(model: SchemaModel) -> serde_json::Value {
+    match model {
+        SchemaModel::Issue => json_schema::<Issue>(),
+        SchemaModel::Detail => json_schema::<IssueDetail>(),
+        SchemaModel::Stats => json_schema::<Stats>(),
+        SchemaModel::Graph => json_schema::<GraphOutput>(),
+    }
+}
+
+fn model_name(model: SchemaModel) -> String {
+    model
+        .to_possible_value()
+        .map(|v| v.get_name().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schemas_describe_the_serialized_fields() {
+        let detail = model_schema(SchemaModel::Detail);
+        let props = detail["properties"].as_object().unwrap();
+        // Flattened issue fields sit beside the detail's own.
+        for key in ["id", "title", "urgency", "blocked_by", "notes"] {
+            assert!(props.contains_key(key), "missing {key}");
+        }
+        let graph = model_schema(SchemaModel::Graph);
+        assert!(graph["$defs"]["GraphEdge"]["properties"]
+            .as_object()
+            .unwrap()
+            .contains_key("type"));
+        assert_eq!(model_name(SchemaModel::Stats), "stats");
+    }
+}
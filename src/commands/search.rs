@@ -49,7 +49,7 @@ pub fn run(
         return Ok(());
     }
 
-    println!("{}", format::format_search_results(&results, fmt));
+    format::print_output(&format::format_search_results(&results, fmt), fmt);
     Ok(())
 }
 
@@ -139,10 +139,14 @@ fn run_core(
     }
 
     let config = UrgencyConfig::load(conn);
+    let project = crate::commands::project::scope();
 
     let mut results: Vec<SearchResult> = Vec::with_capacity(ids.len());
     for id in &ids {
         let issue = db::get_issue(conn, *id)?;
+        if project.as_ref().is_some_and(|p| *p != issue.project) {
+            continue;
+        }
         let notes = db::get_notes(conn, *id)?;
         let urg = urgency::compute_urgency(&issue, &config, conn);
         let blocked_by = db::get_blockers(conn, *id).unwrap_or_default();
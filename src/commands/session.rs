@@ -0,0 +1,151 @@
+use super::agent::resolve_name;
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::Session;
+use rusqlite::Connection;
+
+/// `itr session start [--agent X]` — open a work session for the agent.
+/// Starting while one is already open continues it with a `REVIEW:` note,
+/// so a restarted agent doesn't split its work across two sessions.
+pub fn start(conn: &Connection, agent: Option<&str>, fmt: Format) -> Result<(), ItrError> {
+    let agent = resolve_name(conn, agent)?;
+    let session = match db::open_session(conn, &agent)? {
+        Some(open) => {
+            eprintln!(
+                "REVIEW: session {} for '{}' has been open since {}; continuing it",
+                open.id, agent, open.started_at
+            );
+            open
+        }
+        None => db::start_session(conn, &agent)?,
+    };
+    print_session(&session, fmt);
+    Ok(())
+}
+
+/// `itr session end --summary S [--agent X]` — close the agent's open
+/// session in one transaction: collect the issues it touched since the start
+/// (audited events or notes under its name), store them with the summary,
+/// and add the summary to each as a `session` note.
+pub fn end(
+    conn: &Connection,
+    agent: Option<&str>,
+    summary: &str,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let agent = resolve_name(conn, agent)?;
+    let summary = summary.trim();
+    if summary.is_empty() {
+        return Err(ItrError::InvalidValue {
+            field: "summary".to_string(),
+            value: String::new(),
+            valid: "non-empty string".to_string(),
+        });
+    }
+
+    let tx = db::begin_write(conn)?;
+    let Some(open) = db::open_session(&tx, &agent)? else {
+        return Err(ItrError::InvalidValue {
+            field: "session".to_string(),
+            value: agent.clone(),
+            valid: format!(
+                "an open session; run `itr session start --agent {}` first",
+                agent
+            ),
+        });
+    };
+    let issues = db::issues_touched_by(&tx, &agent, &open.started_at)?;
+    for &id in &issues {
+        db::add_note_of_kind(&tx, id, summary, &agent, db::NOTE_KIND_SESSION)?;
+    }
+    let session = db::end_session(&tx, open.id, summary, &issues)?;
+    tx.commit()?;
+
+    if issues.is_empty() {
+        eprintln!(
+            "REVIEW: session {} touched no issues; the summary is kept only in `itr session list`",
+            session.id
+        );
+    }
+    print_session(&session, fmt);
+    Ok(())
+}
+
+/// A started or ended session, or only its ID under `--quiet`.
+fn print_session(session: &Session, fmt: Format) {
+    if format::quiet(fmt) {
+        println!("{}", session.id);
+    } else {
+        format::print_output(&format::format_session(session, fmt), fmt);
+    }
+}
+
+/// `itr session list [--agent X] [-n N]` — sessions newest first, with the
+/// issues each touched: the standup view.
+pub fn list(
+    conn: &Connection,
+    agent: Option<&str>,
+    limit: Option<usize>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let agent = agent.map(str::trim).filter(|a| !a.is_empty());
+    let sessions = db::sessions(conn, agent, limit)?;
+    if sessions.is_empty() {
+        error::print_empty(fmt.is_json(), "No sessions.");
+        return Ok(());
+    }
+    format::print_output(&format::format_sessions(&sessions, fmt), fmt);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn end_records_touched_issues_and_writes_session_notes() {
+        let conn = db::open_test_db();
+        let mut ids = Vec::new();
+        for title in ["mine", "theirs"] {
+            let issue = db::insert_issue(
+                &conn,
+                title,
+                "medium",
+                "task",
+                "",
+                &[],
+                &[],
+                &[],
+                "",
+                None,
+                "",
+            )
+            .unwrap();
+            ids.push(issue.id);
+        }
+
+        start(&conn, Some("bob"), Format::Json).unwrap();
+        // A second start continues the open session rather than nesting one.
+        start(&conn, Some("bob"), Format::Json).unwrap();
+        db::add_note(&conn, ids[0], "looked into it", "bob").unwrap();
+        db::add_note(&conn, ids[1], "not bob's", "carol").unwrap();
+        end(&conn, Some("bob"), "triaged the parser bug", Format::Json).unwrap();
+
+        let sessions = db::sessions(&conn, Some("bob"), None).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].issues, [ids[0]]);
+        assert!(sessions[0].ended_at.is_some());
+        let note = db::get_notes(&conn, ids[0]).unwrap().pop().unwrap();
+        assert_eq!(
+            (note.kind.as_str(), note.content.as_str()),
+            (db::NOTE_KIND_SESSION, "triaged the parser bug")
+        );
+        assert_eq!(db::get_notes(&conn, ids[1]).unwrap().len(), 1);
+
+        assert!(matches!(
+            end(&conn, Some("bob"), "again", Format::Json),
+            Err(ItrError::InvalidValue { .. })
+        ));
+    }
+}
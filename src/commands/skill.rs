@@ -1,6 +1,6 @@
 use crate::cli::{SkillAction, SkillScope};
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
 use std::env;
 use std::fs;
 use std::path::PathBuf;
@@ -21,7 +21,7 @@ fn emit(fmt: Format) -> Result<(), ItrError> {
     match fmt {
         Format::Json => {
             let out = serde_json::json!({ "skill": SKILL_MD });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             print!("{}", SKILL_MD);
@@ -48,7 +48,7 @@ fn install(scope: SkillScope, force: bool, fmt: Format) -> Result<(), ItrError>
     match fmt {
         Format::Json => {
             let out = serde_json::json!({ "installed": path.display().to_string() });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             println!("Installed itr skill → {}", path.display());
@@ -62,7 +62,7 @@ fn print_path(scope: SkillScope, fmt: Format) -> Result<(), ItrError> {
     match fmt {
         Format::Json => {
             let out = serde_json::json!({ "path": path.display().to_string() });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             println!("{}", path.display());
@@ -0,0 +1,312 @@
+use super::watch::{self, IssueState, WatchEvent};
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::Issue;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Name `diff` uses for the live database; it can't be saved as a snapshot.
+pub const CURRENT: &str = "current";
+
+/// What a snapshot stores: every issue and dependency edge.
+#[derive(Debug, Serialize, Deserialize)]
+struct Saved {
+    issues: Vec<Issue>,
+    /// `(blocker, blocked)` pairs.
+    dependencies: Vec<(i64, i64)>,
+}
+
+/// One side of a diff: the saved (or live) state and when it was taken.
+struct Side {
+    name: String,
+    at: String,
+    saved: Saved,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct Edge {
+    blocker: i64,
+    blocked: i64,
+}
+
+fn validate_name(name: &str) -> Result<&str, ItrError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty()
+        || trimmed.eq_ignore_ascii_case(CURRENT)
+        || trimmed.chars().any(char::is_whitespace)
+    {
+        return Err(ItrError::InvalidValue {
+            field: "snapshot name".to_string(),
+            value: name.to_string(),
+            valid: format!("a non-empty name without spaces, other than '{}'", CURRENT),
+        });
+    }
+    Ok(trimmed)
+}
+
+fn capture(conn: &Connection) -> Result<Saved, ItrError> {
+    let mut dependencies = db::all_dependencies(conn)?;
+    dependencies.sort_unstable();
+    Ok(Saved {
+        issues: db::all_issues(conn)?,
+        dependencies,
+    })
+}
+
+/// `itr snapshot save <name>` — store the current issues and dependencies.
+/// Saving over an existing name asks first (or needs `--yes`).
+pub fn save(conn: &Connection, name: &str, fmt: Format) -> Result<(), ItrError> {
+    let name = validate_name(name)?;
+    if db::get_snapshot(conn, name)?.is_some() {
+        crate::util::confirm(&format!("Replace snapshot '{}'?", name))?;
+    }
+    let saved = capture(conn)?;
+    db::put_snapshot(conn, name, &serde_json::to_string(&saved)?)?;
+    match fmt {
+        Format::Json => format::print_json(
+            &serde_json::json!({
+                "action": "saved",
+                "name": name,
+                "issues": saved.issues.len(),
+                "dependencies": saved.dependencies.len(),
+            })
+            .to_string(),
+        ),
+        _ if format::quiet(fmt) => println!("{}", name),
+        _ => println!(
+            "SNAPSHOT:saved {} ISSUES:{} DEPENDENCIES:{}",
+            name,
+            saved.issues.len(),
+            saved.dependencies.len()
+        ),
+    }
+    Ok(())
+}
+
+/// `itr snapshot list` — saved snapshots, oldest first.
+pub fn list(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
+    let snapshots = db::list_snapshots(conn)?;
+    if snapshots.is_empty() {
+        error::print_empty(fmt.is_json(), "No snapshots saved.");
+        return Ok(());
+    }
+    match fmt {
+        Format::Json => {
+            let items: Vec<serde_json::Value> = snapshots
+                .iter()
+                .map(|(name, at)| serde_json::json!({ "name": name, "created_at": at }))
+                .collect();
+            format::print_json(&serde_json::Value::Array(items).to_string());
+        }
+        _ => {
+            for (name, at) in &snapshots {
+                println!("SNAPSHOT:{} ({})", name, at);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `itr snapshot rm <name>`.
+pub fn rm(conn: &Connection, name: &str, fmt: Format) -> Result<(), ItrError> {
+    if db::get_snapshot(conn, name)?.is_none() {
+        return Err(unknown_snapshot(conn, name)?);
+    }
+    crate::util::confirm(&format!("Delete snapshot '{}'?", name))?;
+    db::delete_snapshot(conn, name)?;
+    match fmt {
+        Format::Json => format::print_json(
+            &serde_json::json!({ "action": "removed", "name": name }).to_string(),
+        ),
+        _ => println!("SNAPSHOT:removed {}", name),
+    }
+    Ok(())
+}
+
+fn unknown_snapshot(conn: &Connection, name: &str) -> Result<ItrError, ItrError> {
+    let mut names: Vec<String> = db::list_snapshots(conn)?
+        .into_iter()
+        .map(|(n, _)| n)
+        .collect();
+    names.push(CURRENT.to_string());
+    Ok(ItrError::InvalidValue {
+        field: "snapshot".to_string(),
+        value: name.to_string(),
+        valid: names.join(", "),
+    })
+}
+
+fn load(conn: &Connection, name: &str) -> Result<Side, ItrError> {
+    if name.eq_ignore_ascii_case(CURRENT) {
+        return Ok(Side {
+            name: CURRENT.to_string(),
+            at: watch::now(),
+            saved: capture(conn)?,
+        });
+    }
+    match db::get_snapshot(conn, name)? {
+        Some((data, at)) => Ok(Side {
+            name: name.to_string(),
+            at,
+            saved: serde_json::from_str(&data)?,
+        }),
+        None => Err(unknown_snapshot(conn, name)?),
+    }
+}
+
+/// The saved state in the shape `itr watch` diffs, with blocked computed
+/// from the saved edges.
+fn states(saved: &Saved) -> Result<watch::Snapshot, ItrError> {
+    let active: BTreeSet<i64> = saved
+        .issues
+        .iter()
+        .filter(|i| i.status != "done" && i.status != "wontfix")
+        .map(|i| i.id)
+        .collect();
+    let blocked: BTreeSet<i64> = saved
+        .dependencies
+        .iter()
+        .filter(|(blocker, _)| active.contains(blocker))
+        .map(|(_, blocked)| *blocked)
+        .collect();
+    saved
+        .issues
+        .iter()
+        .map(|issue| {
+            Ok((
+                issue.id,
+                IssueState {
+                    issue: serde_json::to_value(issue)?,
+                    blocked: blocked.contains(&issue.id),
+                },
+            ))
+        })
+        .collect::<Result<BTreeMap<_, _>, ItrError>>()
+}
+
+fn edge_changes(from: &Saved, to: &Saved) -> (Vec<Edge>, Vec<Edge>) {
+    let before: BTreeSet<(i64, i64)> = from.dependencies.iter().copied().collect();
+    let after: BTreeSet<(i64, i64)> = to.dependencies.iter().copied().collect();
+    let edge = |&(blocker, blocked): &(i64, i64)| Edge { blocker, blocked };
+    (
+        after.difference(&before).map(edge).collect(),
+        before.difference(&after).map(edge).collect(),
+    )
+}
+
+/// `itr diff <from> [to]` — what changed between two snapshots, or between a
+/// snapshot and the live database (`current`, the default `to`).
+pub fn diff(conn: &Connection, from: &str, to: &str, fmt: Format) -> Result<(), ItrError> {
+    let from = load(conn, from)?;
+    let to = load(conn, to)?;
+    let events = watch::diff(&states(&from.saved)?, &states(&to.saved)?, &to.at);
+    let (added, removed) = edge_changes(&from.saved, &to.saved);
+
+    if fmt.is_json() {
+        format::print_json(
+            &serde_json::json!({
+                "from": { "name": from.name, "at": from.at },
+                "to": { "name": to.name, "at": to.at },
+                "issues": events,
+                "dependencies": { "added": added, "removed": removed },
+            })
+            .to_string(),
+        );
+        return Ok(());
+    }
+    for event in &events {
+        println!("{}", format_event(event));
+    }
+    for (label, edges) in [("ADDED", &added), ("REMOVED", &removed)] {
+        for e in edges {
+            println!(
+                "DEPENDENCY:{} {} BLOCKED_BY:{}",
+                label, e.blocked, e.blocker
+            );
+        }
+    }
+    println!(
+        "DIFF: {} ({}) -> {} ({}) ISSUES:{} DEPENDENCIES:{}",
+        from.name,
+        from.at,
+        to.name,
+        to.at,
+        events.len(),
+        added.len() + removed.len()
+    );
+    Ok(())
+}
+
+/// `watch`'s compact line without the timestamp: every change in a diff
+/// shares the `to` side's time, printed once on the summary line.
+fn format_event(event: &WatchEvent) -> String {
+    let line = watch::format_event(event, Format::Compact);
+    match line.rfind(" (") {
+        Some(pos) => line[..pos].to_string(),
+        None => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn diff_between_snapshot_and_current() {
+        let conn = db::init_db(Path::new(":memory:")).unwrap();
+        let a = db::insert_test_issue(&conn, "a");
+        let b = db::insert_test_issue(&conn, "b");
+        db::add_dependency(&conn, a, b).unwrap();
+        save(&conn, "before", Format::Json).unwrap();
+
+        db::update_issue_field(&conn, a, "status", "done").unwrap();
+        db::remove_dependency(&conn, a, b).unwrap();
+        let c = db::insert_test_issue(&conn, "c");
+        db::add_dependency(&conn, b, c).unwrap();
+
+        let from = load(&conn, "before").unwrap();
+        let to = load(&conn, "current").unwrap();
+        let events: Vec<(&str, i64)> = watch::diff(
+            &states(&from.saved).unwrap(),
+            &states(&to.saved).unwrap(),
+            "t",
+        )
+        .iter()
+        .map(|e| (e.event, e.id))
+        .collect();
+        assert_eq!(events, vec![("closed", a), ("unblocked", b), ("added", c)]);
+
+        let (added, removed) = edge_changes(&from.saved, &to.saved);
+        assert_eq!(
+            added,
+            vec![Edge {
+                blocker: b,
+                blocked: c
+            }]
+        );
+        assert_eq!(
+            removed,
+            vec![Edge {
+                blocker: a,
+                blocked: b
+            }]
+        );
+    }
+
+    #[test]
+    fn names_are_validated_and_unknown_snapshots_list_choices() {
+        let conn = db::init_db(Path::new(":memory:")).unwrap();
+        assert!(validate_name("current").is_err());
+        assert!(validate_name("two words").is_err());
+        assert_eq!(validate_name(" sprint-1 ").unwrap(), "sprint-1");
+
+        save(&conn, "s1", Format::Json).unwrap();
+        let err = load(&conn, "s2").err().unwrap();
+        assert!(err.to_string().contains("s1, current"), "{}", err);
+        rm(&conn, "s1", Format::Json).unwrap();
+        assert!(rm(&conn, "s1", Format::Json).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+use crate::commands::project;
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::models::ListFilter;
+use crate::normalize;
+use crate::util;
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// Days without activity before an open issue counts as stale.
+pub const AFTER_DAYS_KEY: &str = "stale.after_days";
+/// Priority stale issues are lowered to; unset or empty leaves priorities.
+pub const DOWNGRADE_KEY: &str = "stale.downgrade_to";
+/// Tag added to every stale issue.
+pub const STALE_TAG: &str = "stale";
+
+/// One issue tagged (or planned, under `--dry-run`).
+#[derive(Debug, Clone, serde::Serialize)]
+struct StaleIssue {
+    id: i64,
+    title: String,
+    idle_days: f64,
+    /// Priority before and after the downgrade, when there was one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<String>,
+}
+
+/// `itr stale [--dry-run]` — apply the `stale.*` policy: an open or
+/// in-progress issue with no update and no new note for at least
+/// `stale.after_days` days gets the `stale` tag and, when
+/// `stale.downgrade_to` names a lower priority, that priority. Issues already
+/// tagged `stale` are skipped, so removing the tag opts an issue back in.
+///
+/// Each change records a `tags` or `priority` event. With no policy
+/// configured the command is a no-op with a `REVIEW:` note.
+pub fn run(conn: &Connection, dry_run: bool, fmt: Format) -> Result<(), ItrError> {
+    let policy = load_policy(conn);
+    if policy.is_none() {
+        eprintln!(
+            "REVIEW: no stale policy configured; set e.g. 'itr config set stale.after_days 60'"
+        );
+    }
+
+    let tx = db::begin_write(conn)?;
+    let stale = match &policy {
+        Some((days, downgrade)) => plan(&tx, *days, downgrade.as_deref())?,
+        None => Vec::new(),
+    };
+    if !dry_run {
+        for s in &stale {
+            let issue = db::get_issue(&tx, s.id)?;
+            let old_json = serde_json::to_string(&issue.tags)?;
+            let mut tags = issue.tags;
+            tags.push(STALE_TAG.to_string());
+            let new_json = serde_json::to_string(&tags)?;
+            db::record_event(&tx, s.id, "tags", &old_json, &new_json)?;
+            db::update_issue_field(&tx, s.id, "tags", &new_json)?;
+            if let (Some(from), Some(to)) = (&s.from, &s.to) {
+                db::record_event(&tx, s.id, "priority", from, to)?;
+                db::update_issue_field(&tx, s.id, "priority", to)?;
+            }
+        }
+    }
+    tx.commit()?;
+
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "action": "stale",
+                "count": stale.len(),
+                "results": stale,
+                "dry_run": dry_run,
+            });
+            format::println_json(&out.to_string());
+        }
+        _ if format::quiet(fmt) => {
+            for s in &stale {
+                println!("{}", s.id);
+            }
+        }
+        _ => {
+            for s in &stale {
+                let downgrade = match (&s.from, &s.to) {
+                    (Some(from), Some(to)) => format!(" PRIORITY:{} -> {}", from, to),
+                    _ => String::new(),
+                };
+                println!(
+                    "STALE:{} IDLE:{:.0}d{} \"{}\"{}",
+                    s.id,
+                    s.idle_days,
+                    downgrade,
+                    format::escape_quoted_value(&s.title),
+                    if dry_run { " (dry-run)" } else { "" }
+                );
+            }
+            if stale.is_empty() {
+                println!("STALE: 0 issues");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read `(after_days, downgrade_to)`. A missing or invalid `after_days` means
+/// no policy; an invalid `downgrade_to` only drops the downgrade. Both are
+/// reported with a `REVIEW:` note.
+fn load_policy(conn: &Connection) -> Option<(f64, Option<String>)> {
+    let raw = db::config_get(conn, AFTER_DAYS_KEY).ok().flatten()?;
+    let Some(days) = raw.trim().parse::<f64>().ok().filter(|d| *d >= 0.0) else {
+        eprintln!(
+            "REVIEW: '{}' for '{}' is not a non-negative number of days; policy skipped",
+            raw, AFTER_DAYS_KEY
+        );
+        return None;
+    };
+    let downgrade = match db::config_get(conn, DOWNGRADE_KEY).ok().flatten() {
+        Some(raw) if !raw.trim().is_empty() => {
+            let priority = normalize::normalize_priority(raw.trim());
+            if normalize::validate_priority(&priority).is_ok() {
+                Some(priority)
+            } else {
+                eprintln!(
+                    "REVIEW: '{}' for '{}' is not a priority; priorities left alone",
+                    raw, DOWNGRADE_KEY
+                );
+                None
+            }
+        }
+        _ => None,
+    };
+    Some((days, downgrade))
+}
+
+fn plan(
+    conn: &Connection,
+    after_days: f64,
+    downgrade: Option<&str>,
+) -> Result<Vec<StaleIssue>, ItrError> {
+    let last_note = last_note_at(conn)?;
+    let issues = db::list_issues(
+        conn,
+        &ListFilter {
+            include_blocked: true,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    let mut out = Vec::new();
+    for issue in issues {
+        if issue.tags.iter().any(|t| t == STALE_TAG) {
+            continue;
+        }
+        let since = last_note
+            .get(&issue.id)
+            .filter(|t| t.as_str() > issue.updated_at.as_str())
+            .unwrap_or(&issue.updated_at);
+        let idle_days = util::days_since(since);
+        if idle_days < after_days {
+            continue;
+        }
+        let to = downgrade.filter(|to| rank(to) > rank(&issue.priority));
+        out.push(StaleIssue {
+            id: issue.id,
+            title: issue.title,
+            idle_days,
+            from: to.map(|_| issue.priority),
+            to: to.map(str::to_string),
+        });
+    }
+    Ok(out)
+}
+
+/// Latest note timestamp per issue.
+fn last_note_at(conn: &Connection) -> Result<HashMap<i64, String>, ItrError> {
+    let mut stmt = conn.prepare("SELECT issue_id, MAX(created_at) FROM notes GROUP BY issue_id")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(rows)
+}
+
+/// Lower is more urgent; unknown priorities sort last.
+fn rank(priority: &str) -> u8 {
+    match priority {
+        "critical" => 0,
+        "high" => 1,
+        "medium" => 2,
+        "low" => 3,
+        _ => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(conn: &Connection, priority: &str, days_idle: i64) -> i64 {
+        let id = db::insert_test_issue(conn, "idle");
+        db::update_issue_field(conn, id, "priority", priority).unwrap();
+        // Without the updated_at trigger the backdated timestamp sticks.
+        conn.execute_batch("DROP TRIGGER IF EXISTS trg_issues_updated_at")
+            .unwrap();
+        conn.execute(
+            "UPDATE issues SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?2) WHERE id = ?1",
+            rusqlite::params![id, format!("-{} days", days_idle)],
+        )
+        .unwrap();
+        id
+    }
+
+    #[test]
+    fn tags_idle_issues_once_and_downgrades_higher_priorities() {
+        let conn = db::open_test_db();
+        db::config_set(&conn, AFTER_DAYS_KEY, "60").unwrap();
+        db::config_set(&conn, DOWNGRADE_KEY, "Low").unwrap();
+        let fresh = add(&conn, "high", 10);
+        let idle_high = add(&conn, "high", 90);
+        let idle_low = add(&conn, "low", 90);
+        let noted = add(&conn, "medium", 90);
+        db::add_note(&conn, noted, "still on it", "").unwrap();
+
+        let planned = plan(&conn, 60.0, Some("low")).unwrap();
+        let ids: Vec<i64> = planned.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![idle_high, idle_low]);
+        assert_eq!(planned[0].to.as_deref(), Some("low"));
+        assert!(planned[1].to.is_none(), "already low: no downgrade");
+        assert!(!ids.contains(&fresh));
+
+        run(&conn, false, Format::Compact).unwrap();
+        let issue = db::get_issue(&conn, idle_high).unwrap();
+        assert_eq!(issue.priority, "low");
+        assert!(issue.tags.contains(&STALE_TAG.to_string()));
+        let events = db::get_events_for_issue(&conn, idle_high).unwrap();
+        assert!(events.iter().any(|e| e.field == "tags"));
+        assert!(events
+            .iter()
+            .any(|e| e.field == "priority" && e.old_value == "high" && e.new_value == "low"));
+        assert!(
+            plan(&conn, 60.0, Some("low")).unwrap().is_empty(),
+            "already tagged issues are skipped"
+        );
+    }
+}
@@ -1,15 +1,22 @@
+use crate::cli::TrendPeriod;
 use crate::db;
 use crate::error::ItrError;
 use crate::format::{self, Format};
-use crate::models::{OldestOpen, Stats};
+use crate::models::{is_terminal, Event, OldestOpen, Stats};
 use crate::urgency::{self, UrgencyConfig};
 use crate::util;
+use chrono::{Datelike, Months, NaiveDate};
 use rusqlite::Connection;
+use serde::Serialize;
 use std::collections::HashMap;
 
 pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
-    let all_issues = db::all_issues(conn)?;
+    let mut all_issues = db::all_issues(conn)?;
+    if let Some(project) = crate::commands::project::scope() {
+        all_issues.retain(|i| i.project == project);
+    }
     let config = UrgencyConfig::load(conn);
+    let relations = db::load_issue_relations(conn)?;
 
     let total = all_issues.len() as i64;
 
@@ -18,7 +25,7 @@ pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
     let mut by_kind: HashMap<String, i64> = HashMap::new();
 
     // Initialize all known values to 0
-    for s in &["open", "in-progress", "done", "wontfix"] {
+    for s in &["open", "in-progress", "review", "done", "wontfix"] {
         by_status.insert(s.to_string(), 0);
     }
     for p in &["critical", "high", "medium", "low"] {
@@ -35,6 +42,7 @@ pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
     let mut oldest_open: Option<OldestOpen> = None;
     let mut by_skills: HashMap<String, i64> = HashMap::new();
     let mut by_assignee: HashMap<String, i64> = HashMap::new();
+    let mut by_component: HashMap<String, i64> = HashMap::new();
 
     for issue in &all_issues {
         *by_status.entry(issue.status.clone()).or_insert(0) += 1;
@@ -42,14 +50,17 @@ pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
         *by_kind.entry(issue.kind.clone()).or_insert(0) += 1;
 
         if issue.status != "done" && issue.status != "wontfix" {
-            let is_blocked = db::is_blocked(conn, issue.id).unwrap_or(false);
-            if is_blocked {
+            if relations.is_blocked(issue.id) {
                 blocked_count += 1;
             } else {
                 ready_count += 1;
             }
 
-            let urg = urgency::compute_urgency(issue, &config, conn);
+            let (urg, _) = urgency::compute_urgency_from_inputs(
+                issue,
+                &config,
+                relations.urgency_inputs(issue.id),
+            );
             urgency_sum += urg;
             active_count += 1;
 
@@ -59,6 +70,9 @@ pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
             if !issue.assigned_to.is_empty() {
                 *by_assignee.entry(issue.assigned_to.clone()).or_insert(0) += 1;
             }
+            if !issue.component.is_empty() {
+                *by_component.entry(issue.component.clone()).or_insert(0) += 1;
+            }
 
             // Track oldest open
             if issue.status == "open" {
@@ -85,6 +99,15 @@ pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
         }
     }
 
+    // Registered agents with nothing active still appear, at 0, so idle
+    // capacity shows up next to the busy assignees.
+    for agent in db::agents(conn)? {
+        by_assignee.entry(agent.name).or_insert(0);
+    }
+    for component in db::components(conn)? {
+        by_component.entry(component.name).or_insert(0);
+    }
+
     let avg_urgency = if active_count > 0 {
         urgency_sum / active_count as f64
     } else {
@@ -101,9 +124,230 @@ pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
         avg_urgency,
         by_skills,
         by_assignee,
+        by_component,
         oldest_open,
     };
 
-    println!("{}", format::format_stats(&stats, fmt));
+    format::print_output(&format::format_stats(&stats, fmt), fmt);
+    Ok(())
+}
+
+/// One period of `itr stats --trend`.
+#[derive(Debug, Serialize)]
+struct TrendRow {
+    period: String,
+    start: String,
+    created: i64,
+    closed: i64,
+    /// Open or in-progress at the end of the period.
+    open: i64,
+}
+
+/// `itr stats --trend` — how many issues were created and closed in each of
+/// the last `periods` periods, and how many were open at the end of each,
+/// rebuilt from the status events in the history table.
+pub fn trend(
+    conn: &Connection,
+    period: TrendPeriod,
+    periods: usize,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let periods = if periods == 0 {
+        eprintln!("REVIEW: --periods 0 shows nothing; showing 1");
+        1
+    } else {
+        periods
+    };
+    let rows = trend_rows(conn, period, periods, chrono::Utc::now().date_naive())?;
+    let output = match fmt {
+        Format::Json => serde_json::to_string(&rows)?,
+        Format::Compact | Format::Oneline => rows
+            .iter()
+            .map(|r| {
+                format!(
+                    "PERIOD:{} START:{} CREATED:{} CLOSED:{} OPEN:{}",
+                    r.period, r.start, r.created, r.closed, r.open
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Format::Pretty => {
+            let mut lines = vec![format!(
+                "{:<10}  {:<10}  {:>7}  {:>6}  {:>5}",
+                "Period", "Start", "Created", "Closed", "Open"
+            )];
+            lines.extend(rows.iter().map(|r| {
+                format!(
+                    "{:<10}  {:<10}  {:>7}  {:>6}  {:>5}",
+                    r.period, r.start, r.created, r.closed, r.open
+                )
+            }));
+            lines.join("\n")
+        }
+    };
+    format::print_output(&output, fmt);
     Ok(())
 }
+
+fn trend_rows(
+    conn: &Connection,
+    period: TrendPeriod,
+    periods: usize,
+    today: NaiveDate,
+) -> Result<Vec<TrendRow>, ItrError> {
+    let mut issues = db::all_issues(conn)?;
+    if let Some(project) = crate::commands::project::scope() {
+        issues.retain(|i| i.project == project);
+    }
+    let mut status_events: HashMap<i64, Vec<Event>> = HashMap::new();
+    for event in db::get_events_after(conn, 0, None)? {
+        if event.field == "status" {
+            status_events.entry(event.issue_id).or_default().push(event);
+        }
+    }
+
+    // Each issue's (time, closed) states, oldest first.
+    let timelines: Vec<Vec<(String, bool)>> = issues
+        .iter()
+        .map(|issue| {
+            let mut events = status_events.remove(&issue.id).unwrap_or_default();
+            events.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+            let initial = events.first().map_or("open", |e| e.old_value.as_str());
+            let mut states = vec![(issue.created_at.clone(), is_terminal(initial))];
+            states.extend(
+                events
+                    .iter()
+                    .map(|e| (e.created_at.clone(), is_terminal(&e.new_value))),
+            );
+            // Closed without a recorded event (e.g. imported): count it as
+            // closed at its last update.
+            if events.is_empty() && is_terminal(&issue.status) {
+                states.push((issue.updated_at.clone(), true));
+            }
+            states
+        })
+        .collect();
+
+    let mut start = period_start(today, period);
+    for _ in 1..periods {
+        start = step(start, period, false);
+    }
+    let mut rows = Vec::with_capacity(periods);
+    for _ in 0..periods {
+        let end = step(start, period, true);
+        let (from, to) = (midnight(start), midnight(end));
+        let within = |t: &String| *t >= from && *t < to;
+        let mut row = TrendRow {
+            period: period_label(start, period),
+            start: start.to_string(),
+            created: 0,
+            closed: 0,
+            open: 0,
+        };
+        for states in &timelines {
+            if within(&states[0].0) {
+                row.created += 1;
+            }
+            row.closed += states
+                .windows(2)
+                .filter(|w| !w[0].1 && w[1].1 && within(&w[1].0))
+                .count() as i64;
+            if states
+                .iter()
+                .rev()
+                .find(|(t, _)| *t < to)
+                .is_some_and(|(_, closed)| !closed)
+            {
+                row.open += 1;
+            }
+        }
+        rows.push(row);
+        start = end;
+    }
+    Ok(rows)
+}
+
+fn period_start(date: NaiveDate, period: TrendPeriod) -> NaiveDate {
+    match period {
+        TrendPeriod::Daily => date,
+        TrendPeriod::Weekly => {
+            date - chrono::Duration::days(i64::from(date.weekday().num_days_from_monday()))
+        }
+        TrendPeriod::Monthly => date.with_day(1).unwrap_or(date),
+    }
+}
+
+/// The start of the next period (`forward`) or the previous one.
+fn step(start: NaiveDate, period: TrendPeriod, forward: bool) -> NaiveDate {
+    let sign = if forward { 1 } else { -1 };
+    match period {
+        TrendPeriod::Daily => start.checked_add_signed(chrono::Duration::days(sign)),
+        TrendPeriod::Weekly => start.checked_add_signed(chrono::Duration::days(7 * sign)),
+        TrendPeriod::Monthly if forward => start.checked_add_months(Months::new(1)),
+        TrendPeriod::Monthly => start.checked_sub_months(Months::new(1)),
+    }
+    .unwrap_or(start)
+}
+
+fn midnight(date: NaiveDate) -> String {
+    format!("{}T00:00:00Z", date)
+}
+
+fn period_label(start: NaiveDate, period: TrendPeriod) -> String {
+    match period {
+        TrendPeriod::Daily => start.to_string(),
+        TrendPeriod::Weekly => {
+            let week = start.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        }
+        TrendPeriod::Monthly => start.format("%Y-%m").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn weekly_trend_replays_status_events() {
+        let conn = db::init_db(Path::new(":memory:")).unwrap();
+        for (title, created) in [
+            ("old", "2026-09-30T12:00:00Z"),
+            ("reopened", "2026-10-06T12:00:00Z"),
+            ("new", "2026-10-13T12:00:00Z"),
+        ] {
+            conn.execute(
+                "INSERT INTO issues (title, created_at, updated_at) VALUES (?1, ?2, ?2)",
+                [title, created],
+            )
+            .unwrap();
+        }
+        // #1 closes in week 41; #2 closes in week 41 and reopens in week 42.
+        conn.execute("UPDATE issues SET status = 'done' WHERE id = 1", [])
+            .unwrap();
+        for (id, old, new, at) in [
+            (1, "open", "done", "2026-10-07T09:00:00Z"),
+            (2, "open", "done", "2026-10-08T09:00:00Z"),
+            (2, "done", "open", "2026-10-14T09:00:00Z"),
+        ] {
+            db::record_event_at(&conn, id, "status", old, new, at).unwrap();
+        }
+
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let rows = trend_rows(&conn, TrendPeriod::Weekly, 3, today).unwrap();
+        let summary: Vec<(&str, i64, i64, i64)> = rows
+            .iter()
+            .map(|r| (r.period.as_str(), r.created, r.closed, r.open))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("2026-W40", 1, 0, 1),
+                ("2026-W41", 1, 2, 0),
+                ("2026-W42", 1, 0, 2),
+            ]
+        );
+        assert_eq!(rows[0].start, "2026-09-28");
+    }
+}
@@ -1,8 +1,7 @@
-use crate::commands::build_issue_summary;
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
-use crate::urgency::UrgencyConfig;
+use crate::format::{self, Format};
+use crate::urgency::{compute_urgency_from_inputs, UrgencyConfig};
 use crate::util;
 use rusqlite::Connection;
 use serde::Serialize;
@@ -13,6 +12,7 @@ struct Summary {
     done: usize,
     open: usize,
     in_progress: usize,
+    review: usize,
     blocked: usize,
     ready: usize,
     completion_pct: f64,
@@ -50,11 +50,15 @@ struct RecentEvent {
 pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
     let all_issues = db::all_issues(conn)?;
     let config = UrgencyConfig::load(conn);
+    let relations = db::load_issue_relations(conn)?;
+    let urgency_of =
+        |issue| compute_urgency_from_inputs(issue, &config, relations.urgency_inputs(issue.id)).0;
 
     let total = all_issues.len();
     let mut done = 0usize;
     let mut open = 0usize;
     let mut in_progress = 0usize;
+    let mut review = 0usize;
     let mut blocked = 0usize;
     let mut ready = 0usize;
     let mut oldest_open: Option<OldestEntry> = None;
@@ -64,32 +68,30 @@ pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
     for issue in &all_issues {
         match issue.status.as_str() {
             "done" | "wontfix" => done += 1,
+            "review" => review += 1,
             "in-progress" => {
                 in_progress += 1;
-                let s = build_issue_summary(conn, issue, &config);
                 wip_issues.push(SummaryIssue {
                     id: issue.id,
                     title: issue.title.clone(),
                     priority: issue.priority.clone(),
                     kind: issue.kind.clone(),
-                    urgency: s.urgency,
+                    urgency: urgency_of(issue),
                     assigned_to: issue.assigned_to.clone(),
                 });
             }
             _ => {
                 open += 1;
-                let is_blocked = db::is_blocked(conn, issue.id).unwrap_or(false);
-                if is_blocked {
+                if relations.is_blocked(issue.id) {
                     blocked += 1;
                 } else {
                     ready += 1;
-                    let s = build_issue_summary(conn, issue, &config);
                     ready_issues.push(SummaryIssue {
                         id: issue.id,
                         title: issue.title.clone(),
                         priority: issue.priority.clone(),
                         kind: issue.kind.clone(),
-                        urgency: s.urgency,
+                        urgency: urgency_of(issue),
                         assigned_to: issue.assigned_to.clone(),
                     });
                 }
@@ -147,6 +149,7 @@ pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
         done,
         open,
         in_progress,
+        review,
         blocked,
         ready,
         completion_pct,
@@ -157,7 +160,7 @@ pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
     };
 
     match fmt {
-        Format::Json => println!("{}", serde_json::to_string(&summary).unwrap_or_default()),
+        Format::Json => format::print_json(&serde_json::to_string(&summary).unwrap_or_default()),
         _ => print_compact(&summary),
     }
 
@@ -165,9 +168,14 @@ pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
 }
 
 fn print_compact(s: &Summary) {
+    let review = if s.review > 0 {
+        format!(", {} in review", s.review)
+    } else {
+        String::new()
+    };
     println!(
-        "PROJECT: {} issues, {} done ({:.0}%), {} in-progress, {} open ({} ready, {} blocked)",
-        s.total, s.done, s.completion_pct, s.in_progress, s.open, s.ready, s.blocked
+        "PROJECT: {} issues, {} done ({:.0}%), {} in-progress{}, {} open ({} ready, {} blocked)",
+        s.total, s.done, s.completion_pct, s.in_progress, review, s.open, s.ready, s.blocked
     );
 
     if !s.in_progress_issues.is_empty() {
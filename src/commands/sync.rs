@@ -0,0 +1,747 @@
+use crate::cli::SyncPrefer;
+use crate::db::{self, SyncCursors};
+use crate::error::ItrError;
+use crate::format::{self, Format};
+use crate::models::{Issue, Note};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::time::Duration;
+
+/// Environment variable read when `--token` is not given.
+pub const TOKEN_ENV: &str = "ITR_SYNC_TOKEN";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Issue columns `sync` carries, named as the events table names them.
+pub(crate) const FIELDS: &[&str] = &[
+    "title",
+    "status",
+    "priority",
+    "kind",
+    "context",
+    "files",
+    "tags",
+    "skills",
+    "acceptance",
+    "parent_id",
+    "assigned_to",
+    "close_reason",
+    "branch",
+    "severity",
+    "component",
+    "affects_version",
+    "fixed_in_version",
+    "estimate",
+];
+
+/// Where a change set starts: everything after this event and issue ID.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Cursor {
+    pub(crate) event: i64,
+    pub(crate) issue: i64,
+}
+
+/// One instance's changes since a cursor, and the cursor to resume from.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ChangeSet {
+    pub(crate) cursor: Cursor,
+    pub(crate) changes: Vec<Change>,
+}
+
+/// One issue's changes. `issue` is its current state on the sending side;
+/// `base` holds, for each field changed since the cursor, the value it had at
+/// the cursor, so the receiver can tell its own edits from stale values.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Change {
+    issue: Issue,
+    /// Created after the cursor: every field is sent, with no base.
+    #[serde(default)]
+    new: bool,
+    #[serde(default)]
+    base: BTreeMap<String, String>,
+    /// All notes when any were added since the cursor; receivers skip the
+    /// ones they already have (same content and `created_at`).
+    #[serde(default)]
+    notes: Vec<Note>,
+    #[serde(default)]
+    blockers_added: Vec<i64>,
+    #[serde(default)]
+    blockers_removed: Vec<i64>,
+    /// The issue's UUID, sent with new issues so both sides share it.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    uuid: String,
+}
+
+/// A field both sides changed to different values. `here` is the receiver's
+/// value, `incoming` the sender's; `kept` names the one now stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Conflict {
+    id: i64,
+    field: String,
+    here: String,
+    incoming: String,
+    kept: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ApplyReport {
+    applied: usize,
+    conflicts: Vec<Conflict>,
+}
+
+impl ApplyReport {
+    fn conflict(&mut self, id: i64, field: &str, here: String, incoming: String, take: bool) {
+        self.conflicts.push(Conflict {
+            id,
+            field: field.to_string(),
+            kept: if take { &incoming } else { &here }.clone(),
+            here,
+            incoming,
+        });
+    }
+}
+
+/// A [`Conflict`] as `itr sync` reports it, from the local side. `kept` is
+/// `local` or `remote` when `--prefer` settled it, else `each`.
+#[derive(Debug, Serialize)]
+struct SyncConflict<'a> {
+    id: i64,
+    field: &'a str,
+    local: &'a str,
+    remote: &'a str,
+    kept: &'static str,
+}
+
+/// What changed for one issue in a run of events.
+#[derive(Debug, Default)]
+struct Touched {
+    base: BTreeMap<String, String>,
+    notes: bool,
+    added: BTreeSet<i64>,
+    removed: BTreeSet<i64>,
+}
+
+/// Body of `POST /api/sync/push`.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct PushRequest {
+    changes: Vec<Change>,
+    /// Overwrite the receiver's value on conflict instead of keeping it.
+    #[serde(default)]
+    force: bool,
+}
+
+impl PushRequest {
+    pub(crate) fn apply(&self, conn: &Connection) -> Result<ApplyReport, ItrError> {
+        apply(conn, &self.changes, self.force)
+    }
+}
+
+/// `itr sync --remote URL` — push local changes to another itr instance's
+/// `itr ui` server, then pull its changes, resuming from the cursors saved
+/// for that remote.
+pub fn run(
+    conn: &Connection,
+    remote: &str,
+    token: Option<String>,
+    prefer: Option<SyncPrefer>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let remote = remote.trim().trim_end_matches('/');
+    if !(remote.starts_with("http://") || remote.starts_with("https://")) {
+        return Err(ItrError::InvalidValue {
+            field: "remote".to_string(),
+            value: remote.to_string(),
+            valid: "an http:// or https:// URL of a running `itr ui`".to_string(),
+        });
+    }
+    let token = token
+        .or_else(|| std::env::var(TOKEN_ENV).ok())
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| ItrError::InvalidValue {
+            field: "token".to_string(),
+            value: String::new(),
+            valid: format!(
+                "the remote `itr ui` session token, via --token or {}",
+                TOKEN_ENV
+            ),
+        })?;
+    let agent = ureq::AgentBuilder::new().timeout(TIMEOUT).build();
+    let mut cursors: SyncCursors = db::get_sync_cursors(conn, remote)?;
+
+    let outgoing = changes_since(
+        conn,
+        Cursor {
+            event: cursors.pushed_event,
+            issue: cursors.pushed_issue,
+        },
+    )?;
+    let pushed: ApplyReport = if outgoing.changes.is_empty() {
+        ApplyReport::default()
+    } else {
+        let body = PushRequest {
+            changes: outgoing.changes,
+            force: prefer == Some(SyncPrefer::Local),
+        };
+        let response = agent
+            .post(&format!("{}/api/sync/push", remote))
+            .set("X-Itr-Token", &token)
+            .set("Content-Type", "application/json")
+            .send_string(&serde_json::to_string(&body)?);
+        serde_json::from_str(&read(remote, response)?)?
+    };
+    cursors.pushed_event = outgoing.cursor.event;
+    cursors.pushed_issue = outgoing.cursor.issue;
+    db::set_sync_cursors(conn, remote, &cursors)?;
+
+    let response = agent
+        .get(&format!("{}/api/sync/changes", remote))
+        .query("after_event", &cursors.pulled_event.to_string())
+        .query("after_issue", &cursors.pulled_issue.to_string())
+        .set("X-Itr-Token", &token)
+        .call();
+    let incoming: ChangeSet = serde_json::from_str(&read(remote, response)?)?;
+    let pulled = apply(conn, &incoming.changes, prefer == Some(SyncPrefer::Remote))?;
+    cursors.pulled_event = incoming.cursor.event;
+    cursors.pulled_issue = incoming.cursor.issue;
+    db::set_sync_cursors(conn, remote, &cursors)?;
+
+    // Pushed conflicts were found on the remote, pulled ones here. The same
+    // field usually conflicts both ways; report it once.
+    let mut by_field: BTreeMap<(i64, &str), SyncConflict> = BTreeMap::new();
+    for (report, local_is_here) in [(&pushed, false), (&pulled, true)] {
+        for c in &report.conflicts {
+            let (local, remote) = if local_is_here {
+                (&c.here, &c.incoming)
+            } else {
+                (&c.incoming, &c.here)
+            };
+            let kept = match (c.kept == c.incoming, local_is_here) {
+                (false, _) => "each",
+                (true, false) => "local",
+                (true, true) => "remote",
+            };
+            let entry = by_field.entry((c.id, &c.field)).or_insert(SyncConflict {
+                id: c.id,
+                field: &c.field,
+                local,
+                remote,
+                kept,
+            });
+            if kept != "each" {
+                entry.kept = kept;
+            }
+        }
+    }
+    let conflicts: Vec<SyncConflict> = by_field.into_values().collect();
+
+    if fmt.is_json() {
+        format::print_json(
+            &serde_json::json!({
+                "action": "sync",
+                "remote": remote,
+                "pushed": pushed.applied,
+                "pulled": pulled.applied,
+                "conflicts": conflicts,
+            })
+            .to_string(),
+        );
+    } else {
+        for c in &conflicts {
+            println!(
+                "CONFLICT: #{} {} local={:?} remote={:?} KEPT:{}",
+                c.id, c.field, c.local, c.remote, c.kept
+            );
+        }
+        println!(
+            "SYNC: {} PUSHED:{} PULLED:{} CONFLICTS:{}",
+            remote,
+            pushed.applied,
+            pulled.applied,
+            conflicts.len()
+        );
+    }
+    let unresolved = conflicts.iter().filter(|c| c.kept == "each").count();
+    if unresolved > 0 {
+        eprintln!(
+            "REVIEW: {} conflict(s) left each side with its own value. Make the fields agree, or change one side again and re-run with --prefer local|remote. An `id` conflict means each side created a different issue under that ID; use `itr merge-db` for those.",
+            unresolved
+        );
+    }
+    Ok(())
+}
+
+fn read(remote: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String, ItrError> {
+    match response {
+        Ok(r) => Ok(r.into_string()?),
+        Err(ureq::Error::Status(code, r)) => {
+            let body = r.into_string().unwrap_or_default();
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|v| v["error"].as_str().map(str::to_string))
+                .unwrap_or(body);
+            Err(ItrError::SyncFailed(format!(
+                "{}: HTTP {}: {}",
+                remote, code, message
+            )))
+        }
+        Err(ureq::Error::Transport(t)) => Err(ItrError::SyncFailed(t.to_string())),
+    }
+}
+
+/// The value of one [`FIELDS`] column, encoded as the events table records it.
+pub(crate) fn field_value(issue: &Issue, field: &str) -> String {
+    let list = |v: &[String]| serde_json::to_string(v).unwrap_or_default();
+    match field {
+        "title" => issue.title.clone(),
+        "status" => issue.status.clone(),
+        "priority" => issue.priority.clone(),
+        "kind" => issue.kind.clone(),
+        "context" => issue.context.clone(),
+        "files" => list(&issue.files),
+        "tags" => list(&issue.tags),
+        "skills" => list(&issue.skills),
+        "acceptance" => issue.acceptance.clone(),
+        "parent_id" => issue.parent_id.map(|p| p.to_string()).unwrap_or_default(),
+        "assigned_to" => issue.assigned_to.clone(),
+        "close_reason" => issue.close_reason.clone(),
+        "branch" => issue.branch.clone(),
+        "severity" => issue.severity.clone(),
+        "component" => issue.component.clone(),
+        "affects_version" => issue.affects_version.clone(),
+        "fixed_in_version" => issue.fixed_in_version.clone(),
+        "estimate" => issue.estimate.map(|p| p.to_string()).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Everything recorded after `after`: issues created since (in full) and
+/// issues with events since (the fields, notes, and edges that changed).
+pub(crate) fn changes_since(conn: &Connection, after: Cursor) -> Result<ChangeSet, ItrError> {
+    let cursor = Cursor {
+        event: db::last_event_id(conn)?,
+        issue: db::last_issue_id(conn)?,
+    };
+    let mut touched: BTreeMap<i64, Touched> = BTreeMap::new();
+    for event in db::get_events_after(conn, after.event, None)? {
+        if event.id > cursor.event {
+            break;
+        }
+        let t = touched.entry(event.issue_id).or_default();
+        match event.field.as_str() {
+            "note_added" => t.notes = true,
+            "dependency_added" | "dependency_removed" => {
+                let adding = event.field == "dependency_added";
+                let blocker = if adding {
+                    &event.new_value
+                } else {
+                    &event.old_value
+                };
+                let Ok(blocker) = blocker.parse::<i64>() else {
+                    continue;
+                };
+                // Net effect: an edge added then removed since the cursor
+                // (or the reverse) cancels out.
+                let (to, from) = if adding {
+                    (&mut t.added, &mut t.removed)
+                } else {
+                    (&mut t.removed, &mut t.added)
+                };
+                if !from.remove(&blocker) {
+                    to.insert(blocker);
+                }
+            }
+            field if FIELDS.contains(&field) => {
+                t.base
+                    .entry(field.to_string())
+                    .or_insert(event.old_value.clone());
+            }
+            _ => {}
+        }
+    }
+
+    let mut changes = Vec::new();
+    let new_ids = (after.issue + 1)..=cursor.issue;
+    let ids: BTreeSet<i64> = touched.keys().copied().chain(new_ids).collect();
+    for id in ids {
+        let issue = match db::get_issue(conn, id) {
+            Ok(issue) => issue,
+            Err(ItrError::NotFound(_)) => continue,
+            Err(e) => return Err(e),
+        };
+        let t = touched.remove(&id).unwrap_or_default();
+        changes.push(if id > after.issue {
+            Change {
+                notes: db::get_notes(conn, id)?,
+                blockers_added: db::get_blockers(conn, id)?,
+                issue,
+                new: true,
+                base: BTreeMap::new(),
+                blockers_removed: Vec::new(),
+                uuid: db::issue_uuid(conn, id)?,
+            }
+        } else {
+            Change {
+                notes: if t.notes {
+                    db::get_notes(conn, id)?
+                } else {
+                    Vec::new()
+                },
+                issue,
+                new: false,
+                base: t.base,
+                blockers_added: t.added.into_iter().collect(),
+                blockers_removed: t.removed.into_iter().collect(),
+                uuid: String::new(),
+            }
+        });
+    }
+    Ok(ChangeSet { cursor, changes })
+}
+
+/// Apply another instance's changes in one transaction.
+///
+/// A field is updated when this side still has the sender's base value (it
+/// didn't change here). If it changed on both sides to different values,
+/// that's a conflict: this side's value stays unless `force`. Values that
+/// already match are skipped, so changes echoed back by the next sync are
+/// no-ops. Issues missing here are created under the sender's ID; an ID
+/// held by a different issue (another `created_at`) is a conflict.
+pub(crate) fn apply(
+    conn: &Connection,
+    changes: &[Change],
+    force: bool,
+) -> Result<ApplyReport, ItrError> {
+    let tx = db::begin_write(conn)?;
+    let mut report = ApplyReport::default();
+    let mut inserted: HashSet<i64> = HashSet::new();
+    let mut skipped: HashSet<i64> = HashSet::new();
+
+    for change in changes {
+        let incoming = &change.issue;
+        let local = match db::get_issue(&tx, incoming.id) {
+            Ok(issue) => issue,
+            Err(ItrError::NotFound(_)) => {
+                insert(&tx, incoming)?;
+                if !change.uuid.is_empty() {
+                    db::set_issue_uuid(&tx, incoming.id, &change.uuid)?;
+                }
+                inserted.insert(incoming.id);
+                report.applied += 1;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        // Created on both sides since the last sync under the same ID: only
+        // the same issue if the title matches too.
+        if local.created_at != incoming.created_at || (change.new && local.title != incoming.title)
+        {
+            report.conflict(
+                incoming.id,
+                "id",
+                format!("{} ({})", local.title, local.created_at),
+                format!("{} ({})", incoming.title, incoming.created_at),
+                false,
+            );
+            skipped.insert(incoming.id);
+            continue;
+        }
+        for field in FIELDS.iter().filter(|f| **f != "parent_id") {
+            merge_field(&tx, &mut report, change, &local, field, force)?;
+        }
+    }
+
+    // Parents, notes, and edges once every issue exists here.
+    for change in changes {
+        let id = change.issue.id;
+        if skipped.contains(&id) {
+            continue;
+        }
+        let local = db::get_issue(&tx, id)?;
+        if inserted.contains(&id) {
+            if let Some(parent) = change.issue.parent_id {
+                if set_field(&tx, id, "parent_id", "", &parent.to_string())? {
+                    report.applied += 1;
+                }
+            }
+        } else {
+            merge_field(&tx, &mut report, change, &local, "parent_id", force)?;
+        }
+
+        let have: HashSet<(String, String)> = db::get_notes(&tx, id)?
+            .into_iter()
+            .map(|n| (n.content, n.created_at))
+            .collect();
+        for note in &change.notes {
+            if have.contains(&(note.content.clone(), note.created_at.clone())) {
+                continue;
+            }
+            tx.execute(
+                "INSERT INTO notes (issue_id, content, agent, created_at, kind) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, note.content, note.agent, note.created_at, note.kind],
+            )?;
+            db::record_event(&tx, id, "note_added", "", &note.content)?;
+            report.applied += 1;
+        }
+
+        for &blocker in &change.blockers_added {
+            if skipped.contains(&blocker) || !db::issue_exists(&tx, blocker)? {
+                continue;
+            }
+            match db::add_dependency(&tx, blocker, id) {
+                Ok(true) => report.applied += 1,
+                Ok(false) => {}
+                Err(ItrError::CycleDetected(_)) => {
+                    report.conflict(id, "blocked_by", String::new(), blocker.to_string(), false);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        for &blocker in &change.blockers_removed {
+            if db::issue_exists(&tx, blocker)? && db::remove_dependency(&tx, blocker, id)? {
+                report.applied += 1;
+            }
+        }
+    }
+
+    db::refresh_all_references(&tx)?;
+    tx.commit()?;
+    Ok(report)
+}
+
+/// Three-way merge of one field of an issue both sides have.
+fn merge_field(
+    conn: &Connection,
+    report: &mut ApplyReport,
+    change: &Change,
+    local: &Issue,
+    field: &str,
+    force: bool,
+) -> Result<(), ItrError> {
+    let theirs = field_value(&change.issue, field);
+    let ours = field_value(local, field);
+    if theirs == ours {
+        return Ok(());
+    }
+    let take = match change.base.get(field) {
+        // Unchanged here since the sender's base.
+        Some(base) if *base == ours => true,
+        // Changed on both sides, or created on both with no common base.
+        Some(_) => {
+            report.conflict(local.id, field, ours.clone(), theirs.clone(), force);
+            force
+        }
+        None if change.new => {
+            report.conflict(local.id, field, ours.clone(), theirs.clone(), force);
+            force
+        }
+        // Changed here only.
+        None => false,
+    };
+    if take && set_field(conn, local.id, field, &ours, &theirs)? {
+        report.applied += 1;
+    }
+    Ok(())
+}
+
+/// Create an issue under the sender's ID, leaving the parent for later.
+pub(crate) fn insert(conn: &Connection, issue: &Issue) -> Result<(), ItrError> {
+    conn.execute(
+        "INSERT INTO issues (id, title, status, priority, kind, context, files, tags, skills, acceptance, close_reason, created_at, updated_at, assigned_to, branch, project_id, severity, affects_version, fixed_in_version, component_id, estimate)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
+        params![
+            issue.id,
+            issue.title,
+            issue.status,
+            issue.priority,
+            issue.kind,
+            issue.context,
+            serde_json::to_string(&issue.files)?,
+            serde_json::to_string(&issue.tags)?,
+            serde_json::to_string(&issue.skills)?,
+            issue.acceptance,
+            issue.close_reason,
+            issue.created_at,
+            issue.updated_at,
+            issue.assigned_to,
+            issue.branch,
+            db::ensure_project(conn, &issue.project)?,
+            issue.severity,
+            issue.affects_version,
+            issue.fixed_in_version,
+            db::ensure_component(conn, &issue.component)?,
+            issue.estimate,
+        ],
+    )?;
+    db::fts_index_issue(conn, &db::get_issue(conn, issue.id)?);
+    Ok(())
+}
+
+/// Store one synced field and record it in the event log. Returns false
+/// when a parent no longer exists here or would form a cycle.
+pub(crate) fn set_field(
+    conn: &Connection,
+    id: i64,
+    field: &str,
+    old: &str,
+    new: &str,
+) -> Result<bool, ItrError> {
+    if field == "parent_id" {
+        match db::update_issue_parent(conn, id, new.parse::<i64>().ok()) {
+            Ok(()) => {}
+            Err(ItrError::NotFound(_) | ItrError::CycleDetected(_)) => return Ok(false),
+            Err(e) => return Err(e),
+        }
+    } else {
+        write_field(conn, id, field, new)?;
+    }
+    db::record_event(conn, id, field, old, new)?;
+    Ok(true)
+}
+
+/// Store one [`FIELDS`] value other than `parent_id`, without an event. A
+/// component is registered here first if it isn't yet; an empty estimate
+/// clears it.
+pub(crate) fn write_field(
+    conn: &Connection,
+    id: i64,
+    field: &str,
+    value: &str,
+) -> Result<(), ItrError> {
+    match field {
+        "component" => {
+            db::ensure_component(conn, value)?;
+            db::set_component_id(conn, id, value)
+        }
+        "estimate" => db::set_estimate(conn, id, value.parse().ok()),
+        _ => db::update_issue_field(conn, id, field, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// Two databases that start as copies of each other.
+    fn pair() -> (Connection, Connection) {
+        let a = db::init_db(Path::new(":memory:")).unwrap();
+        let b = db::init_db(Path::new(":memory:")).unwrap();
+        let id = db::insert_test_issue(&a, "shared");
+        let issue = db::get_issue(&a, id).unwrap();
+        insert(&b, &issue).unwrap();
+        (a, b)
+    }
+
+    fn start(conn: &Connection) -> Cursor {
+        Cursor {
+            event: db::last_event_id(conn).unwrap(),
+            issue: db::last_issue_id(conn).unwrap(),
+        }
+    }
+
+    #[test]
+    fn changes_flow_one_way_and_echo_as_no_ops() {
+        let (a, b) = pair();
+        let (from_a, from_b) = (start(&a), start(&b));
+
+        db::update_issue_field(&a, 1, "status", "in-progress").unwrap();
+        db::record_event(&a, 1, "status", "open", "in-progress").unwrap();
+        let added = db::insert_test_issue(&a, "only in a");
+        db::add_dependency(&a, 1, added).unwrap();
+        db::add_note(&a, 1, "progress", "").unwrap();
+
+        let set = changes_since(&a, from_a).unwrap();
+        let report = apply(&b, &set.changes, false).unwrap();
+        assert!(report.conflicts.is_empty(), "{:?}", report.conflicts);
+        assert_eq!(db::get_issue(&b, 1).unwrap().status, "in-progress");
+        assert_eq!(db::get_issue(&b, added).unwrap().title, "only in a");
+        assert_eq!(db::get_blockers(&b, added).unwrap(), vec![1]);
+        assert_eq!(db::get_notes(&b, 1).unwrap().len(), 1);
+
+        // b's events from applying come back to a as changes a already has.
+        let echo = changes_since(&b, from_b).unwrap();
+        assert!(!echo.changes.is_empty());
+        assert_eq!(apply(&a, &echo.changes, false).unwrap().applied, 0);
+    }
+
+    #[test]
+    fn component_changes_sync_and_register_the_component() {
+        let (a, b) = pair();
+        let from_a = start(&a);
+        db::upsert_component(&a, "api", Some("bob"), None).unwrap();
+        db::set_issue_component(&a, 1, "api").unwrap();
+
+        let report = apply(&b, &changes_since(&a, from_a).unwrap().changes, false).unwrap();
+        assert!(report.conflicts.is_empty(), "{:?}", report.conflicts);
+        assert_eq!(db::get_issue(&b, 1).unwrap().component, "api");
+        assert!(db::get_component(&b, "api").unwrap().is_some());
+    }
+
+    #[test]
+    fn edits_on_both_sides_conflict_unless_forced() {
+        let (a, b) = pair();
+        let from_a = start(&a);
+        for (conn, value) in [(&a, "high"), (&b, "low")] {
+            db::update_issue_field(conn, 1, "priority", value).unwrap();
+            db::record_event(conn, 1, "priority", "medium", value).unwrap();
+        }
+        let set = changes_since(&a, from_a).unwrap();
+
+        let kept = apply(&b, &set.changes, false).unwrap();
+        assert_eq!(
+            kept.conflicts,
+            vec![Conflict {
+                id: 1,
+                field: "priority".to_string(),
+                here: "low".to_string(),
+                incoming: "high".to_string(),
+                kept: "low".to_string(),
+            }]
+        );
+        assert_eq!(db::get_issue(&b, 1).unwrap().priority, "low");
+
+        apply(&b, &set.changes, true).unwrap();
+        assert_eq!(db::get_issue(&b, 1).unwrap().priority, "high");
+    }
+
+    #[test]
+    fn insert_copies_every_synced_column() {
+        let a = db::open_test_db();
+        let b = db::open_test_db();
+        let id = db::insert_test_issue(&a, "full");
+        db::update_issue_field(&a, id, "severity", "major").unwrap();
+        db::update_issue_field(&a, id, "affects_version", "1.2").unwrap();
+        db::update_issue_field(&a, id, "fixed_in_version", "1.3").unwrap();
+        db::ensure_component(&a, "api").unwrap();
+        db::set_issue_component(&a, id, "api").unwrap();
+        db::set_estimate(&a, id, Some(3)).unwrap();
+
+        insert(&b, &db::get_issue(&a, id).unwrap()).unwrap();
+        let copy = db::get_issue(&b, id).unwrap();
+        assert_eq!(copy.component, "api");
+        assert_eq!(copy.estimate, Some(3));
+        assert_eq!(copy.severity, "major");
+        assert_eq!(
+            (
+                copy.affects_version.as_str(),
+                copy.fixed_in_version.as_str()
+            ),
+            ("1.2", "1.3")
+        );
+    }
+
+    #[test]
+    fn same_id_created_on_both_sides_is_an_id_conflict() {
+        let (a, b) = pair();
+        let from_a = start(&a);
+        let id = db::insert_test_issue(&a, "made in a");
+        assert_eq!(db::insert_test_issue(&b, "made in b"), id);
+        db::add_dependency(&a, 1, id).unwrap();
+
+        let report = apply(&b, &changes_since(&a, from_a).unwrap().changes, true).unwrap();
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].field, "id");
+        assert_eq!(db::get_issue(&b, id).unwrap().title, "made in b");
+        assert!(db::get_blockers(&b, id).unwrap().is_empty());
+    }
+}
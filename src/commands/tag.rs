@@ -0,0 +1,289 @@
+use super::update::persist_list_field;
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::util;
+use rusqlite::Connection;
+
+/// `itr tag list` — every tag in use with open/closed issue counts.
+pub fn list(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
+    let counts = db::tag_counts(conn)?;
+    if counts.is_empty() {
+        error::print_empty(fmt.is_json(), "No tags in use.");
+        return Ok(());
+    }
+    format::print_output(&format::format_tag_counts(&counts, fmt), fmt);
+    Ok(())
+}
+
+/// `itr tag rename <old> <new>` — replace `old` with `new` on every issue
+/// carrying it, in one transaction. An issue that already has `new` simply
+/// loses `old`, so renaming onto an existing tag merges the two.
+pub fn rename(conn: &Connection, old: &str, new: &str, fmt: Format) -> Result<(), ItrError> {
+    let new = validate_tag(new)?;
+    let tx = db::begin_write(conn)?;
+    let ids = rewrite(&tx, old, |tags| {
+        let mut out: Vec<String> = Vec::with_capacity(tags.len());
+        for t in tags {
+            let t = if t == old { new.clone() } else { t };
+            if !out.contains(&t) {
+                out.push(t);
+            }
+        }
+        out
+    })?;
+    db::rename_tag_meta(&tx, old, &new)?;
+    tx.commit()?;
+    print_result("rename", old, Some(&new), &ids, fmt);
+    Ok(())
+}
+
+/// `itr tag rm <tag>` — strip `tag` from every issue carrying it, along with
+/// its description and color.
+pub fn rm(conn: &Connection, tag: &str, fmt: Format) -> Result<(), ItrError> {
+    let carriers = db::issue_ids_with_tag(conn, tag)?.len();
+    if carriers > 0 {
+        util::confirm(&format!("Remove tag '{}' from {} issue(s)?", tag, carriers))?;
+    }
+    let tx = db::begin_write(conn)?;
+    let ids = rewrite(&tx, tag, |mut tags| {
+        tags.retain(|t| t != tag);
+        tags
+    })?;
+    db::delete_tag_meta(&tx, tag)?;
+    tx.commit()?;
+    print_result("rm", tag, None, &ids, fmt);
+    Ok(())
+}
+
+/// Apply `edit` to the tag list of every issue carrying `tag`, recording a
+/// `tags` event per changed issue. Runs inside the caller's transaction.
+/// Returns the touched IDs.
+fn rewrite(
+    tx: &Connection,
+    tag: &str,
+    edit: impl Fn(Vec<String>) -> Vec<String>,
+) -> Result<Vec<i64>, ItrError> {
+    let ids = db::issue_ids_with_tag(tx, tag)?;
+    for &id in &ids {
+        let current = db::get_issue(tx, id)?.tags;
+        let updated = edit(current.clone());
+        persist_list_field(tx, id, "tags", &current, &updated)?;
+    }
+    if ids.is_empty() {
+        eprintln!("REVIEW: no issues carry tag '{}'; nothing changed", tag);
+    }
+    Ok(ids)
+}
+
+/// `itr tag describe <tag> [--description D] [--color C]` — set a tag's
+/// metadata; an empty value clears it. With neither flag, prints the current
+/// metadata. Colors are validated up front so pretty output never meets an
+/// unknown one.
+pub fn describe(
+    conn: &Connection,
+    tag: &str,
+    description: Option<&str>,
+    color: Option<&str>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let tag = validate_tag(tag)?;
+    let color = color.map(str::trim);
+    if let Some(c) = color.filter(|c| !c.is_empty()) {
+        if util::ansi_color(c).is_none() {
+            return Err(ItrError::InvalidValue {
+                field: "color".to_string(),
+                value: c.to_string(),
+                valid: format!("{}, or #rgb / #rrggbb", util::COLOR_NAMES.join(", ")),
+            });
+        }
+    }
+    let setting = description.is_some() || color.is_some();
+    let (description, color) = if setting {
+        db::set_tag_meta(conn, &tag, description.map(str::trim), color)?
+    } else {
+        db::tag_counts(conn)?
+            .into_iter()
+            .find(|t| t.tag == tag)
+            .map(|t| (t.description, t.color))
+            .unwrap_or_default()
+    };
+
+    match fmt {
+        Format::Json => {
+            let out = serde_json::json!({
+                "tag": tag,
+                "description": description,
+                "color": color,
+            });
+            format::println_json(&out.to_string());
+        }
+        _ if setting && format::quiet(fmt) => println!("{}", tag),
+        _ => {
+            let mut line = format!("TAG:{}", format::escape_line_value(&tag));
+            if !color.is_empty() {
+                line.push_str(&format!(" COLOR:{}", color));
+            }
+            if !description.is_empty() {
+                line.push_str(&format!(
+                    " DESCRIPTION:\"{}\"",
+                    format::escape_quoted_value(&description)
+                ));
+            }
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+/// Tags are stored trimmed and can't contain commas (the `--tags` list
+/// separator), so a rename target must satisfy the same rules.
+fn validate_tag(tag: &str) -> Result<String, ItrError> {
+    let trimmed = tag.trim();
+    if trimmed.is_empty() || trimmed.contains(',') {
+        return Err(ItrError::InvalidValue {
+            field: "tag".to_string(),
+            value: tag.to_string(),
+            valid: "a non-empty tag without commas".to_string(),
+        });
+    }
+    Ok(trimmed.to_string())
+}
+
+fn print_result(action: &str, tag: &str, to: Option<&str>, ids: &[i64], fmt: Format) {
+    match fmt {
+        Format::Json => {
+            let mut out = serde_json::json!({
+                "action": action,
+                "tag": tag,
+                "count": ids.len(),
+                "ids": ids,
+            });
+            if let Some(to) = to {
+                out["to"] = serde_json::Value::String(to.to_string());
+            }
+            format::println_json(&out.to_string());
+        }
+        _ if format::quiet(fmt) => {
+            for id in ids {
+                println!("{}", id);
+            }
+        }
+        _ => {
+            let count = ids.len();
+            let ids = ids
+                .iter()
+                .map(std::string::ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            let target = match to {
+                Some(to) => format!(
+                    "\"{}\" -> \"{}\"",
+                    format::escape_quoted_value(tag),
+                    format::escape_quoted_value(to)
+                ),
+                None => format!("\"{}\"", format::escape_quoted_value(tag)),
+            };
+            println!(
+                "{}: {} issues [{}] {}",
+                action.to_uppercase(),
+                count,
+                ids,
+                target
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(conn: &Connection, title: &str, tags: &[&str]) -> i64 {
+        let tags: Vec<String> = tags.iter().map(|t| (*t).to_string()).collect();
+        db::insert_issue(
+            conn,
+            title,
+            "medium",
+            "task",
+            "",
+            &[],
+            &tags,
+            &[],
+            "",
+            None,
+            "",
+        )
+        .expect("insert issue")
+        .id
+    }
+
+    fn tags_of(conn: &Connection, id: i64) -> Vec<String> {
+        db::get_issue(conn, id).unwrap().tags
+    }
+
+    #[test]
+    fn rename_rewrites_every_issue_and_merges_duplicates() {
+        let conn = db::open_test_db();
+        let a = add(&conn, "a", &["auth", "api"]);
+        let b = add(&conn, "b", &["security", "auth"]);
+        let c = add(&conn, "c", &["ui"]);
+        db::update_issue_field(&conn, b, "status", "done").unwrap();
+
+        rename(&conn, "auth", "security", Format::Compact).unwrap();
+        assert_eq!(tags_of(&conn, a), vec!["security", "api"]);
+        assert_eq!(tags_of(&conn, b), vec!["security"]);
+        assert_eq!(tags_of(&conn, c), vec!["ui"]);
+        let counts = db::tag_counts(&conn).unwrap();
+        let security = counts.iter().find(|t| t.tag == "security").unwrap();
+        assert_eq!((security.open, security.closed), (1, 1));
+        assert!(counts.iter().all(|t| t.tag != "auth"));
+    }
+
+    #[test]
+    fn describe_sets_metadata_that_follows_rename_and_rm() {
+        let conn = db::open_test_db();
+        add(&conn, "a", &["auth"]);
+        describe(
+            &conn,
+            "auth",
+            Some("Login work"),
+            Some("red"),
+            Format::Compact,
+        )
+        .unwrap();
+        assert!(describe(&conn, "auth", None, Some("mauve"), Format::Compact).is_err());
+
+        rename(&conn, "auth", "security", Format::Compact).unwrap();
+        let counts = db::tag_counts(&conn).unwrap();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(
+            (counts[0].description.as_str(), counts[0].color.as_str()),
+            ("Login work", "red")
+        );
+
+        rm(&conn, "security", Format::Compact).unwrap();
+        assert!(db::tag_counts(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn described_tag_without_issues_is_listed_until_cleared() {
+        let conn = db::open_test_db();
+        describe(&conn, "later", Some("Parking lot"), None, Format::Compact).unwrap();
+        let counts = db::tag_counts(&conn).unwrap();
+        assert_eq!((counts[0].tag.as_str(), counts[0].total), ("later", 0));
+        describe(&conn, "later", Some(""), None, Format::Compact).unwrap();
+        assert!(db::tag_counts(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rm_strips_tag_and_records_events() {
+        let conn = db::open_test_db();
+        let a = add(&conn, "a", &["stale", "keep"]);
+        rm(&conn, "stale", Format::Compact).unwrap();
+        assert_eq!(tags_of(&conn, a), vec!["keep"]);
+        let events = db::get_events_for_issue(&conn, a).unwrap();
+        assert!(events.iter().any(|e| e.field == "tags"));
+        assert!(rename(&conn, "keep", " , ", Format::Compact).is_err());
+    }
+}
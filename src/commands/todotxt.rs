@@ -0,0 +1,561 @@
+//! The todo.txt format (<https://github.com/todotxt/todo.txt>) for
+//! `itr export --export-format todotxt` and `itr import --import-format
+//! todotxt`: one task per line, `x` and a completion date for closed ones,
+//! `(A)`-`(D)` for critical through low, `+project` for the parent, and
+//! `@context` for tags. What todo.txt has no syntax for rides along as
+//! `key:value` pairs (`itr:`, `kind:`, `status:`, `pri:`, `parent:`) that
+//! other todo.txt tools leave alone.
+
+use super::watch;
+use crate::db;
+use crate::error::ItrError;
+use crate::models::{is_terminal, ExportData, Issue};
+use crate::normalize;
+use crate::util;
+use rusqlite::Connection;
+use std::collections::{BTreeMap, HashMap};
+
+/// Longest `+project` slug written for a parent's title.
+const PROJECT_SLUG_LEN: usize = 40;
+
+fn letter(priority: &str) -> char {
+    match priority {
+        "critical" => 'A',
+        "high" => 'B',
+        "low" => 'D',
+        _ => 'C',
+    }
+}
+
+/// The `YYYY-MM-DD` part of an ISO 8601 timestamp, if it has one.
+fn date(iso: &str) -> Option<&str> {
+    let day = iso.get(..10)?;
+    chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d")
+        .ok()
+        .map(|_| day)
+}
+
+fn is_date(word: &str) -> bool {
+    word.len() == 10 && date(word).is_some()
+}
+
+/// Render `items` as todo.txt, in the order given. A parent in the export
+/// is named by the slug of its title when no other exported title shares
+/// it; otherwise, or when the parent is not exported, `parent:<id>` is
+/// written instead.
+pub(crate) fn render(items: &[ExportData]) -> String {
+    let mut slug_count: HashMap<String, usize> = HashMap::new();
+    for item in items {
+        *slug_count
+            .entry(util::slugify(&item.issue.title, PROJECT_SLUG_LEN))
+            .or_default() += 1;
+    }
+    let projects: HashMap<i64, String> = items
+        .iter()
+        .map(|item| {
+            (
+                item.issue.id,
+                util::slugify(&item.issue.title, PROJECT_SLUG_LEN),
+            )
+        })
+        .filter(|(_, slug)| !slug.is_empty() && slug_count[slug] == 1)
+        .collect();
+
+    let mut out = String::new();
+    for item in items {
+        let issue = &item.issue;
+        let mut words: Vec<String> = Vec::new();
+        let closed = is_terminal(&issue.status);
+        if closed {
+            words.push("x".to_string());
+            let completed = item.field_times.get("status").unwrap_or(&issue.updated_at);
+            // A creation date can only follow a completion date.
+            if let (Some(completed), Some(created)) = (date(completed), date(&issue.created_at)) {
+                words.push(completed.to_string());
+                words.push(created.to_string());
+            }
+        } else {
+            words.push(format!("({})", letter(&issue.priority)));
+            if let Some(created) = date(&issue.created_at) {
+                words.push(created.to_string());
+            }
+        }
+        words.push(issue.title.split_whitespace().collect::<Vec<_>>().join(" "));
+        if let Some(parent) = issue.parent_id {
+            match projects.get(&parent) {
+                Some(slug) => words.push(format!("+{}", slug)),
+                None => words.push(format!("parent:{}", parent)),
+            }
+        }
+        for tag in &issue.tags {
+            words.push(format!(
+                "@{}",
+                tag.split_whitespace().collect::<Vec<_>>().join("-")
+            ));
+        }
+        if issue.kind != "task" {
+            words.push(format!("kind:{}", issue.kind));
+        }
+        if matches!(issue.status.as_str(), "in-progress" | "review" | "wontfix") {
+            words.push(format!("status:{}", issue.status));
+        }
+        if closed {
+            words.push(format!("pri:{}", letter(&issue.priority)));
+        }
+        words.push(format!("itr:{}", issue.id));
+        out.push_str(&words.join(" "));
+        out.push('\n');
+    }
+    out
+}
+
+/// One parsed task line, before its `+project` is resolved.
+#[derive(Debug)]
+struct Task {
+    line: usize,
+    id: Option<i64>,
+    issue: Issue,
+    project: Option<String>,
+    completed: Option<String>,
+}
+
+/// `YYYY-MM-DD` as the midnight-UTC timestamp itr stores.
+fn timestamp(day: &str) -> String {
+    format!("{}T00:00:00Z", day)
+}
+
+fn priority_of(letter: &str, line: usize, notes: &mut Vec<String>) -> String {
+    match letter {
+        "A" => "critical",
+        "B" => "high",
+        "C" => "medium",
+        "D" => "low",
+        other => {
+            notes.push(format!(
+                "REVIEW: todo.txt line {}: priority ({}) has no itr equivalent; using low",
+                line, other
+            ));
+            "low"
+        }
+    }
+    .to_string()
+}
+
+/// `(A)`: a single uppercase letter in parentheses.
+fn priority_word(word: &str) -> Option<&str> {
+    let inner = word.strip_prefix('(')?.strip_suffix(')')?;
+    (inner.len() == 1 && inner.chars().all(|c| c.is_ascii_uppercase())).then_some(inner)
+}
+
+/// A fresh open, medium task with no fields set, stamped `now`.
+fn blank(now: &str) -> Issue {
+    Issue {
+        id: 0,
+        title: String::new(),
+        status: "open".to_string(),
+        priority: "medium".to_string(),
+        kind: "task".to_string(),
+        context: String::new(),
+        files: vec![],
+        tags: vec![],
+        skills: vec![],
+        acceptance: String::new(),
+        parent_id: None,
+        assigned_to: String::new(),
+        close_reason: String::new(),
+        branch: String::new(),
+        project: String::new(),
+        severity: String::new(),
+        component: String::new(),
+        affects_version: String::new(),
+        fixed_in_version: String::new(),
+        estimate: None,
+        created_at: now.to_string(),
+        updated_at: now.to_string(),
+    }
+}
+
+fn parse_line(text: &str, line: usize, now: &str, notes: &mut Vec<String>) -> Option<Task> {
+    let mut words = text.split_whitespace().peekable();
+    let done = words.next_if_eq(&"x").is_some();
+    let mut priority = "medium".to_string();
+    if let Some(p) = words.peek().and_then(|w| priority_word(w)) {
+        priority = priority_of(p, line, notes);
+        words.next();
+    }
+    // Dates are positional: a completed task's first date is when it was
+    // completed, and only then may a creation date follow.
+    let first = words.next_if(|w| is_date(w));
+    let (completed, created) = match first {
+        Some(day) if done => (Some(day.to_string()), words.next_if(|w| is_date(w))),
+        day => (None, day),
+    };
+
+    let mut title: Vec<&str> = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+    let mut project: Option<String> = None;
+    let mut parent: Option<i64> = None;
+    let mut id = None;
+    let mut kind = "task".to_string();
+    let mut status = None;
+    for word in words {
+        if let Some(name) = word.strip_prefix('+').filter(|n| !n.is_empty()) {
+            if project.is_none() {
+                project = Some(name.to_string());
+            } else {
+                notes.push(format!(
+                    "REVIEW: todo.txt line {}: only the first +project becomes the parent; '{}' kept as a tag",
+                    line, name
+                ));
+                tags.push(name.to_lowercase());
+            }
+            continue;
+        }
+        if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+            tags.push(context.to_lowercase());
+            continue;
+        }
+        match word.split_once(':') {
+            Some(("itr", n)) if n.parse::<i64>().is_ok() => id = n.parse().ok(),
+            Some(("parent", n)) if n.parse::<i64>().is_ok() => parent = n.parse().ok(),
+            Some(("kind", k)) if !k.is_empty() => kind = normalize::normalize_kind(k),
+            Some(("status", s)) if !s.is_empty() => status = Some(normalize::normalize_status(s)),
+            Some(("pri", p)) if priority_word(&format!("({})", p)).is_some() => {
+                priority = priority_of(p, line, notes);
+            }
+            _ => title.push(word),
+        }
+    }
+    if title.is_empty() {
+        notes.push(format!(
+            "REVIEW: todo.txt line {}: task has no title; skipped",
+            line
+        ));
+        return None;
+    }
+    // `x` decides open vs closed; `status:` only picks which kind.
+    let status = match (done, status.as_deref()) {
+        (true, Some("wontfix")) => "wontfix",
+        (true, _) => "done",
+        (false, Some("in-progress")) => "in-progress",
+        (false, Some("review")) => "review",
+        (false, _) => "open",
+    };
+    let created_at = created.map_or_else(|| now.to_string(), timestamp);
+    let updated_at = completed
+        .as_deref()
+        .map_or_else(|| created_at.clone(), timestamp);
+    tags.sort();
+    tags.dedup();
+    Some(Task {
+        line,
+        id,
+        issue: Issue {
+            title: title.join(" "),
+            status: status.to_string(),
+            priority,
+            kind,
+            tags,
+            parent_id: parent,
+            created_at,
+            updated_at,
+            ..blank(now)
+        },
+        project: project.filter(|_| parent.is_none()),
+        completed,
+    })
+}
+
+/// Parse todo.txt `input` into import items. Tasks keep the ID in their
+/// `itr:` pair; the rest get IDs past every ID here and in the file. A
+/// `+project` names the task in the file whose title slugs to the same
+/// thing, or else an epic here that does; when neither exists a new epic
+/// is added for it. Blank lines are skipped; lossy mappings come back as
+/// `REVIEW:` notes.
+pub(crate) fn parse(
+    conn: &Connection,
+    input: &str,
+) -> Result<(Vec<ExportData>, Vec<String>), ItrError> {
+    let now = watch::now();
+    let mut notes = Vec::new();
+    let mut tasks: Vec<Task> = input
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .filter_map(|(i, text)| parse_line(text, i + 1, &now, &mut notes))
+        .collect();
+
+    let here = db::all_issues(conn)?;
+    let mut next_id = tasks
+        .iter()
+        .filter_map(|t| t.id)
+        .chain(here.iter().map(|i| i.id))
+        .max()
+        .unwrap_or(0);
+    for task in &mut tasks {
+        task.issue.id = match task.id {
+            Some(id) => id,
+            None => {
+                next_id += 1;
+                next_id
+            }
+        };
+    }
+
+    let slug = |title: &str| util::slugify(title, PROJECT_SLUG_LEN);
+    let mut projects: HashMap<String, i64> = HashMap::new();
+    for task in &tasks {
+        projects
+            .entry(slug(&task.issue.title))
+            .or_insert(task.issue.id);
+    }
+    for epic in here.iter().filter(|i| i.kind == "epic") {
+        projects.entry(slug(&epic.title)).or_insert(epic.id);
+    }
+    let mut new_epics: Vec<Issue> = Vec::new();
+    for task in &mut tasks {
+        let Some(name) = task.project.take() else {
+            continue;
+        };
+        let key = slug(&name);
+        let parent = match projects.get(&key) {
+            Some(&id) => id,
+            None => {
+                next_id += 1;
+                notes.push(format!(
+                    "REVIEW: todo.txt line {}: no issue matches +{}; added epic {} for it",
+                    task.line, name, next_id
+                ));
+                new_epics.push(Issue {
+                    id: next_id,
+                    title: name.replace(['-', '_'], " "),
+                    kind: "epic".to_string(),
+                    ..blank(&now)
+                });
+                projects.insert(key, next_id);
+                next_id
+            }
+        };
+        if parent == task.issue.id {
+            notes.push(format!(
+                "REVIEW: todo.txt line {}: +{} names the task itself; no parent set",
+                task.line, name
+            ));
+        } else {
+            task.issue.parent_id = Some(parent);
+        }
+    }
+
+    let items = tasks
+        .into_iter()
+        .map(|task| {
+            let field_times = task
+                .completed
+                .map(|day| BTreeMap::from([("status".to_string(), timestamp(&day))]))
+                .unwrap_or_default();
+            item(task.issue, field_times)
+        })
+        .chain(
+            new_epics
+                .into_iter()
+                .map(|epic| item(epic, BTreeMap::new())),
+        )
+        .collect();
+    Ok((parents_first(items, &mut notes), notes))
+}
+
+/// Put each item after its parent, since import inserts them in order and
+/// the parent link is a foreign key. A parent loop is broken at the item
+/// that closes it.
+fn parents_first(mut items: Vec<ExportData>, notes: &mut Vec<String>) -> Vec<ExportData> {
+    let index: HashMap<i64, usize> = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (item.issue.id, i))
+        .collect();
+    let mut placed = vec![false; items.len()];
+    let mut order = Vec::with_capacity(items.len());
+    for start in 0..items.len() {
+        let mut path: Vec<usize> = Vec::new();
+        let mut next = Some(start);
+        while let Some(i) = next {
+            if placed[i] {
+                break;
+            }
+            if path.contains(&i) {
+                let last = path[path.len() - 1];
+                notes.push(format!(
+                    "REVIEW: todo.txt: +project links loop back to issue {}; issue {} gets no parent",
+                    items[i].issue.id, items[last].issue.id
+                ));
+                items[last].issue.parent_id = None;
+                break;
+            }
+            path.push(i);
+            next = items[i]
+                .issue
+                .parent_id
+                .and_then(|parent| index.get(&parent).copied());
+        }
+        for &i in path.iter().rev() {
+            placed[i] = true;
+            order.push(i);
+        }
+    }
+    let mut slots: Vec<Option<ExportData>> = items.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+fn item(issue: Issue, field_times: BTreeMap<String, String>) -> ExportData {
+    ExportData {
+        issue,
+        notes: vec![],
+        blocked_by: vec![],
+        events: vec![],
+        relations: vec![],
+        uuid: String::new(),
+        key: String::new(),
+        repro: None,
+        field_times,
+        blocks: vec![],
+        children: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: &str = "2026-02-01T12:00:00Z";
+
+    fn parsed(text: &str) -> (Option<Task>, Vec<String>) {
+        let mut notes = Vec::new();
+        let task = parse_line(text, 1, NOW, &mut notes);
+        (task, notes)
+    }
+
+    #[test]
+    fn lines_map_priorities_dates_projects_and_contexts() {
+        let (task, notes) = parsed("(B) 2026-01-02 Call plumber +House @phone @Errands itr:7");
+        let task = task.unwrap();
+        assert!(notes.is_empty());
+        assert_eq!(task.id, Some(7));
+        assert_eq!(task.project.as_deref(), Some("House"));
+        assert_eq!(task.issue.title, "Call plumber");
+        assert_eq!(task.issue.priority, "high");
+        assert_eq!(task.issue.status, "open");
+        assert_eq!(task.issue.tags, ["errands", "phone"]);
+        assert_eq!(task.issue.created_at, "2026-01-02T00:00:00Z");
+
+        let (task, _) = parsed("x 2026-01-05 2026-01-01 Buy paint kind:bug status:wontfix pri:A");
+        let task = task.unwrap();
+        assert_eq!(task.issue.status, "wontfix");
+        assert_eq!(task.issue.kind, "bug");
+        assert_eq!(task.issue.priority, "critical");
+        assert_eq!(task.completed.as_deref(), Some("2026-01-05"));
+        assert_eq!(task.issue.created_at, "2026-01-01T00:00:00Z");
+        assert_eq!(task.issue.updated_at, "2026-01-05T00:00:00Z");
+
+        // An open task has only a creation date; a second one is title text.
+        let (task, _) = parsed("2026-03-01 2026-03-02 email:me@example.com");
+        let task = task.unwrap();
+        assert_eq!(task.issue.title, "2026-03-02 email:me@example.com");
+        assert_eq!(task.issue.created_at, "2026-03-01T00:00:00Z");
+
+        let (task, notes) = parsed("(F) Read +a +b");
+        assert_eq!(task.unwrap().issue.priority, "low");
+        assert_eq!(notes.len(), 2, "{:?}", notes);
+
+        let (task, notes) = parsed("(C) @home +house");
+        assert!(task.is_none());
+        assert_eq!(
+            notes,
+            ["REVIEW: todo.txt line 1: task has no title; skipped"]
+        );
+    }
+
+    #[test]
+    fn render_writes_parents_as_projects_and_round_trips() {
+        let mut epic = blank("2026-01-01T09:00:00Z");
+        epic.id = 1;
+        epic.title = "Website Launch".to_string();
+        epic.kind = "epic".to_string();
+        epic.priority = "critical".to_string();
+        let mut child = blank("2026-01-02T09:00:00Z");
+        child.id = 2;
+        child.title = "Write copy".to_string();
+        child.status = "done".to_string();
+        child.parent_id = Some(1);
+        child.tags = vec!["docs".to_string()];
+        let mut stray = blank("2026-01-03T09:00:00Z");
+        stray.id = 3;
+        stray.title = "Orphan".to_string();
+        stray.status = "in-progress".to_string();
+        stray.parent_id = Some(99);
+        let mut done = item(child, BTreeMap::new());
+        done.field_times =
+            BTreeMap::from([("status".to_string(), "2026-01-04T10:00:00Z".to_string())]);
+
+        let text = render(&[
+            item(epic, BTreeMap::new()),
+            done,
+            item(stray, BTreeMap::new()),
+        ]);
+        assert_eq!(
+            text,
+            "(A) 2026-01-01 Website Launch kind:epic itr:1
+x 2026-01-04 2026-01-02 Write copy +website-launch @docs pri:C itr:2
+(C) 2026-01-03 Orphan parent:99 status:in-progress itr:3
+"
+        );
+
+        let mut notes = Vec::new();
+        let child = parse_line(text.lines().nth(1).unwrap(), 2, NOW, &mut notes).unwrap();
+        assert_eq!(child.id, Some(2));
+        assert_eq!(child.issue.status, "done");
+        assert_eq!(child.project.as_deref(), Some("website-launch"));
+        assert_eq!(
+            util::slugify("Website Launch", PROJECT_SLUG_LEN),
+            "website-launch"
+        );
+        let stray = parse_line(text.lines().nth(2).unwrap(), 3, NOW, &mut notes).unwrap();
+        assert_eq!(stray.issue.parent_id, Some(99));
+        assert_eq!(stray.issue.status, "in-progress");
+        assert!(notes.is_empty());
+    }
+
+    #[test]
+    fn parents_come_first_and_loops_are_broken() {
+        let issue = |id: i64, parent: Option<i64>| {
+            item(
+                Issue {
+                    id,
+                    parent_id: parent,
+                    ..blank(NOW)
+                },
+                BTreeMap::new(),
+            )
+        };
+        let mut notes = Vec::new();
+        let ordered = parents_first(
+            vec![
+                issue(1, Some(3)),
+                issue(2, None),
+                issue(3, Some(2)),
+                issue(4, Some(50)),
+            ],
+            &mut notes,
+        );
+        let ids: Vec<i64> = ordered.iter().map(|i| i.issue.id).collect();
+        assert_eq!(ids, [2, 3, 1, 4]);
+        assert!(notes.is_empty());
+
+        let ordered = parents_first(vec![issue(1, Some(2)), issue(2, Some(1))], &mut notes);
+        let parents: Vec<(i64, Option<i64>)> = ordered
+            .iter()
+            .map(|i| (i.issue.id, i.issue.parent_id))
+            .collect();
+        assert_eq!(parents, [(2, None), (1, Some(2))]);
+        assert_eq!(notes.len(), 1);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::{is_terminal, Issue, TreeNode};
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// `itr tree [ID]` — render the parent/child hierarchy, walking nested epics
+/// to any depth. With an ID, the tree is rooted at that issue; without one,
+/// every top-level issue that has children (plus childless top-level epics)
+/// is a root.
+///
+/// Closed issues are hidden unless `all` is set, except where a closed node
+/// still has open descendants — it is kept so the path to them stays intact.
+/// Progress rollups always count every descendant, closed ones included.
+pub fn run(conn: &Connection, id: Option<i64>, all: bool, fmt: Format) -> Result<(), ItrError> {
+    let issues: HashMap<i64, Issue> = db::all_issues(conn)?
+        .into_iter()
+        .map(|i| (i.id, i))
+        .collect();
+
+    let root_ids: Vec<i64> = match id {
+        Some(root) => {
+            if !issues.contains_key(&root) {
+                return Err(ItrError::NotFound(root));
+            }
+            vec![root]
+        }
+        None => {
+            let parents: std::collections::HashSet<i64> =
+                issues.values().filter_map(|i| i.parent_id).collect();
+            let mut roots: Vec<i64> = issues
+                .values()
+                .filter(|i| i.parent_id.is_none() && (i.kind == "epic" || parents.contains(&i.id)))
+                .map(|i| i.id)
+                .collect();
+            roots.sort_unstable();
+            roots
+        }
+    };
+
+    let mut roots = Vec::new();
+    for root_id in root_ids {
+        let mut node = build_tree(conn, &issues, root_id)?;
+        if all {
+            roots.push(node);
+        } else if id.is_some() {
+            // An explicitly requested root is shown even when closed.
+            node.children = std::mem::take(&mut node.children)
+                .into_iter()
+                .filter_map(prune_closed)
+                .collect();
+            roots.push(node);
+        } else if let Some(node) = prune_closed(node) {
+            roots.push(node);
+        }
+    }
+
+    if roots.is_empty() {
+        error::print_empty(fmt.is_json(), "No issues with children found.");
+        return Ok(());
+    }
+    format::print_output(&format::format_tree(&roots, fmt), fmt);
+    Ok(())
+}
+
+/// Build the nested node for `root_id` from the depth-first descendant list
+/// returned by [`db::get_descendant_ids`].
+fn build_tree(
+    conn: &Connection,
+    issues: &HashMap<i64, Issue>,
+    root_id: i64,
+) -> Result<TreeNode, ItrError> {
+    // Stack of open nodes; index 0 is the root at depth 0.
+    let mut stack = vec![leaf(conn, &issues[&root_id])?];
+    for (id, depth) in db::get_descendant_ids(conn, root_id)? {
+        let Some(issue) = issues.get(&id) else {
+            continue;
+        };
+        let depth = usize::try_from(depth).unwrap_or(usize::MAX);
+        while stack.len() > depth {
+            let done = stack.pop().expect("stack holds the root");
+            stack
+                .last_mut()
+                .expect("root is never popped here")
+                .children
+                .push(done);
+        }
+        stack.push(leaf(conn, issue)?);
+    }
+    while stack.len() > 1 {
+        let done = stack.pop().expect("stack holds the root");
+        stack
+            .last_mut()
+            .expect("root is never popped here")
+            .children
+            .push(done);
+    }
+    Ok(stack.pop().expect("stack holds the root"))
+}
+
+fn leaf(conn: &Connection, issue: &Issue) -> Result<TreeNode, ItrError> {
+    let progress = db::subtree_progress(conn, issue.id)?;
+    Ok(TreeNode {
+        id: issue.id,
+        title: issue.title.clone(),
+        status: issue.status.clone(),
+        kind: issue.kind.clone(),
+        priority: issue.priority.clone(),
+        progress: (progress.total > 0).then_some(progress),
+        children: Vec::new(),
+    })
+}
+
+/// Drop closed nodes that have no open descendants left after pruning.
+fn prune_closed(mut node: TreeNode) -> Option<TreeNode> {
+    node.children = std::mem::take(&mut node.children)
+        .into_iter()
+        .filter_map(prune_closed)
+        .collect();
+    let closed = is_terminal(&node.status);
+    if closed && node.children.is_empty() {
+        None
+    } else {
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue_map(conn: &Connection) -> HashMap<i64, Issue> {
+        db::all_issues(conn)
+            .unwrap()
+            .into_iter()
+            .map(|i| (i.id, i))
+            .collect()
+    }
+
+    #[test]
+    fn build_tree_nests_epics_and_rolls_up_progress() {
+        let conn = db::open_test_db();
+        let root = db::insert_test_issue_with(&conn, "root", "epic", None);
+        let sub = db::insert_test_issue_with(&conn, "sub", "epic", Some(root));
+        let a = db::insert_test_issue_with(&conn, "a", "task", Some(sub));
+        let b = db::insert_test_issue_with(&conn, "b", "task", Some(root));
+        db::update_issue_field(&conn, a, "status", "done").unwrap();
+
+        let tree = build_tree(&conn, &issue_map(&conn), root).unwrap();
+        assert_eq!(
+            tree.children.iter().map(|c| c.id).collect::<Vec<_>>(),
+            vec![sub, b]
+        );
+        assert_eq!(tree.children[0].children[0].id, a);
+        let p = tree.progress.expect("root has descendants");
+        assert_eq!((p.total, p.done, p.open), (3, 1, 2));
+        assert!(
+            tree.children[1].progress.is_none(),
+            "leaves carry no rollup"
+        );
+    }
+
+    #[test]
+    fn prune_closed_keeps_closed_parent_with_open_descendant() {
+        let conn = db::open_test_db();
+        let root = db::insert_test_issue_with(&conn, "root", "epic", None);
+        let sub = db::insert_test_issue_with(&conn, "sub", "epic", Some(root));
+        db::insert_test_issue_with(&conn, "open leaf", "task", Some(sub));
+        let closed_leaf = db::insert_test_issue_with(&conn, "closed leaf", "task", Some(root));
+        db::update_issue_field(&conn, sub, "status", "done").unwrap();
+        db::update_issue_field(&conn, closed_leaf, "status", "done").unwrap();
+
+        let tree = prune_closed(build_tree(&conn, &issue_map(&conn), root).unwrap()).unwrap();
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].id, sub);
+        assert_eq!(
+            tree.progress.unwrap().total,
+            3,
+            "rollup still counts closed"
+        );
+    }
+}
@@ -1,9 +1,13 @@
-use super::{build_issue_detail, build_issue_summary, sort_by_urgency_desc};
+use super::{
+    build_issue_detail, build_issue_summaries, build_issue_summary, build_subtree_summaries,
+    sort_by_urgency_desc,
+};
 use crate::db;
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
 use crate::models::{IssueDetail, IssueSummary};
 use crate::normalize::{self, validate_kind, validate_priority, validate_status};
+use crate::pool::ConnectionPool;
 use crate::urgency::UrgencyConfig;
 use rusqlite::types::ValueRef;
 use rusqlite::Connection;
@@ -29,9 +33,11 @@ const MAX_HEADER_LINE_BYTES: usize = 8_192;
 /// Cap on the number of header lines per request.
 const MAX_HEADER_COUNT: usize = 100;
 /// Socket read/write timeout per accepted connection. Generous so slow CI
-/// machines and human browsers never trip it, but bounded so one stalled
-/// connection cannot wedge the (serial) accept loop forever.
+/// machines and human browsers never trip it, but bounded so a stalled
+/// connection cannot hold its thread (or the writer) forever.
 const IO_TIMEOUT: Duration = Duration::from_secs(10);
+/// Read-only connections serving GET requests alongside the one writer.
+const UI_READERS: usize = 4;
 
 #[derive(Debug)]
 struct HttpRequest {
@@ -129,18 +135,20 @@ pub fn run(
     fmt: Format,
 ) -> Result<(), ItrError> {
     let token = session_token(conn)?;
+    let pool =
+        ConnectionPool::open_with_defaults(db_path, UI_READERS, &crate::global_config::values())?;
     let listener = TcpListener::bind(("127.0.0.1", port))?;
     let addr = listener.local_addr()?;
     let url = format!("http://{}:{}/?token={}", addr.ip(), addr.port(), token);
 
     if fmt.is_json() {
-        println!(
-            "{}",
-            json!({
+        format::print_json(
+            &json!({
                 "url": url,
                 "db_path": db_path.display().to_string(),
                 "port": addr.port(),
             })
+            .to_string(),
         );
     } else {
         println!("UI: {}", url);
@@ -163,7 +171,7 @@ pub fn run(
 
     serve(
         &listener,
-        conn,
+        &pool,
         db_path,
         &token,
         allow_dangerous,
@@ -174,10 +182,14 @@ pub fn run(
     Ok(())
 }
 
+/// Accept loop. Each connection is handled on its own thread, so a slow
+/// request never delays the next one; GETs read through the pool's readers
+/// while writes take turns on its writer. `once` handles a single request
+/// inline and returns.
 #[allow(clippy::too_many_arguments)]
 fn serve(
     listener: &TcpListener,
-    conn: &Connection,
+    pool: &ConnectionPool,
     db_path: &Path,
     token: &str,
     allow_dangerous: bool,
@@ -185,42 +197,58 @@ fn serve(
     io_timeout: Duration,
     port: u16,
 ) {
-    for stream in listener.incoming() {
-        match stream {
-            Ok(mut stream) => {
-                // Bound both directions so one stalled connection cannot
-                // wedge the serial accept loop indefinitely.
-                let _ = stream.set_read_timeout(Some(io_timeout));
-                let _ = stream.set_write_timeout(Some(io_timeout));
-                // Isolate per-connection handling: a panic while parsing or
-                // routing one request must not abort the accept loop.
-                let outcome = catch_unwind(AssertUnwindSafe(|| {
-                    handle_stream(&mut stream, conn, db_path, token, allow_dangerous, port)
-                }));
-                match outcome {
-                    Ok(Ok(())) => {}
-                    Ok(Err(err)) => {
-                        let response = error_response(500, &err.to_string(), "INTERNAL_ERROR");
-                        let _ = write_response(&mut stream, response);
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(mut stream) => {
+                    // Bound both directions so one stalled connection cannot
+                    // hold its thread indefinitely.
+                    let _ = stream.set_read_timeout(Some(io_timeout));
+                    let _ = stream.set_write_timeout(Some(io_timeout));
+                    let mut handle = move || {
+                        serve_connection(&mut stream, pool, db_path, token, allow_dangerous, port);
+                    };
+                    if once {
+                        handle();
+                        break;
                     }
-                    Err(_) => {
-                        eprintln!("REVIEW: UI request handler panicked; connection dropped");
-                        let response =
-                            error_response(500, "request handler panicked", "INTERNAL_ERROR");
-                        let _ = write_response(&mut stream, response);
-                    }
-                }
-                if once {
-                    break;
+                    scope.spawn(handle);
                 }
-            }
-            Err(err) => {
-                eprintln!("REVIEW: UI request failed: {}", err);
-                if once {
-                    break;
+                Err(err) => {
+                    eprintln!("REVIEW: UI request failed: {}", err);
+                    if once {
+                        break;
+                    }
                 }
             }
         }
+    });
+}
+
+fn serve_connection(
+    stream: &mut TcpStream,
+    pool: &ConnectionPool,
+    db_path: &Path,
+    token: &str,
+    allow_dangerous: bool,
+    port: u16,
+) {
+    // Isolate per-connection handling: a panic while parsing or routing one
+    // request must not take down the server.
+    let outcome = catch_unwind(AssertUnwindSafe(|| {
+        handle_stream(stream, pool, db_path, token, allow_dangerous, port)
+    }));
+    match outcome {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => {
+            let response = error_response(500, &err.to_string(), "INTERNAL_ERROR");
+            let _ = write_response(stream, response);
+        }
+        Err(_) => {
+            eprintln!("REVIEW: UI request handler panicked; connection dropped");
+            let response = error_response(500, "request handler panicked", "INTERNAL_ERROR");
+            let _ = write_response(stream, response);
+        }
     }
 }
 
@@ -247,7 +275,7 @@ impl From<std::io::Error> for RequestError {
 
 fn handle_stream(
     stream: &mut TcpStream,
-    conn: &Connection,
+    pool: &ConnectionPool,
     db_path: &Path,
     token: &str,
     allow_dangerous: bool,
@@ -256,10 +284,18 @@ fn handle_stream(
     let response = match read_request(stream) {
         Ok(request) => match host_rejection(&request, port) {
             Some(rejection) => rejection,
-            None => match route_request(&request, conn, db_path, token, allow_dangerous) {
-                Ok(response) => response,
-                Err(err) => error_response_for_itr(err),
-            },
+            None => {
+                // Only GET routes are read-only; everything else may write.
+                let conn = if request.method == "GET" {
+                    pool.read()
+                } else {
+                    pool.write()
+                };
+                match route_request(&request, &conn, db_path, token, allow_dangerous) {
+                    Ok(response) => response,
+                    Err(err) => error_response_for_itr(err),
+                }
+            }
         },
         Err(RequestError::Bad(message)) => error_response(400, &message, "BAD_REQUEST"),
         Err(RequestError::TooLarge(message)) => error_response(431, &message, "REQUEST_TOO_LARGE"),
@@ -479,6 +515,13 @@ fn route_request(
             "application/javascript; charset=utf-8",
             APP_JS,
         )),
+        // Aggregate counts only, so scrapers need no session token; the Host
+        // check above still keeps other sites from reading it.
+        ("GET", "/metrics") => Ok(response(
+            200,
+            "text/plain; version=0.0.4; charset=utf-8",
+            &super::metrics::render(conn)?,
+        )),
         ("GET", "/api/health") => {
             require_token(request, token)?;
             json_response(json!({
@@ -492,7 +535,7 @@ fn route_request(
             json_response(json!({
                 "db_path": db_path.display().to_string(),
                 "version": env!("ITR_VERSION"),
-                "statuses": ["open", "in-progress", "done", "wontfix"],
+                "statuses": ["open", "in-progress", "review", "done", "wontfix"],
                 "priorities": ["critical", "high", "medium", "low"],
                 "kinds": ["bug", "feature", "task", "epic"],
                 "dangerous_sql": allow_dangerous,
@@ -565,6 +608,21 @@ fn route_request(
                 "unblocked": unblocked,
             }))
         }
+        ("GET", "/api/sync/changes") => {
+            require_token(request, token)?;
+            let after = super::sync::Cursor {
+                event: query_i64(&request.query, "after_event")?,
+                issue: query_i64(&request.query, "after_issue")?,
+            };
+            json_response(serde_json::to_value(super::sync::changes_since(
+                conn, after,
+            )?)?)
+        }
+        ("POST", "/api/sync/push") => {
+            require_token(request, token)?;
+            let input: super::sync::PushRequest = parse_body(request)?;
+            json_response(serde_json::to_value(input.apply(conn)?)?)
+        }
         _ => route_dynamic(request, conn, token),
     }
 }
@@ -863,20 +921,11 @@ fn issue_detail(conn: &Connection, id: i64) -> Result<IssueDetail, ItrError> {
     let issue = db::get_issue(conn, id)?;
     let config = UrgencyConfig::load(conn);
     let mut detail = build_issue_detail(conn, issue, &config)?;
-    detail.children = Some(
-        db::list_issues(
-            conn,
-            &crate::models::ListFilter {
-                parent_id: Some(id),
-                all: true,
-                include_blocked: true,
-                ..crate::models::ListFilter::default()
-            },
-        )?
-        .iter()
-        .map(|issue| build_issue_summary(conn, issue, &config))
-        .collect(),
-    );
+    let children = build_subtree_summaries(conn, id, &config)?;
+    if children.is_some() {
+        detail.progress = Some(db::subtree_progress(conn, id)?);
+    }
+    detail.children = Some(children.unwrap_or_default());
     detail.relations = db::get_relations(conn, id)?;
     Ok(detail)
 }
@@ -900,20 +949,22 @@ fn create_issue(conn: &Connection, input: IssueCreateInput) -> Result<IssueDetai
 
     let priority = match validate_priority(&priority) {
         Ok(()) => priority,
-        Err(_) => {
+        Err(e) => {
             review_notes.push(format!(
-                "REVIEW: priority '{}' not recognized, defaulted to 'medium'. Valid: critical, high, medium, low",
-                priority
+                "REVIEW: priority '{}' not recognized{}, defaulted to 'medium'. Valid: critical, high, medium, low",
+                priority,
+                e.did_you_mean()
             ));
             "medium".to_string()
         }
     };
     let kind = match validate_kind(&kind) {
         Ok(()) => kind,
-        Err(_) => {
+        Err(e) => {
             review_notes.push(format!(
-                "REVIEW: kind '{}' not recognized, defaulted to 'task'. Valid: bug, feature, task, epic",
-                kind
+                "REVIEW: kind '{}' not recognized{}, defaulted to 'task'. Valid: bug, feature, task, epic",
+                kind,
+                e.did_you_mean()
             ));
             "task".to_string()
         }
@@ -936,6 +987,9 @@ fn create_issue(conn: &Connection, input: IssueCreateInput) -> Result<IssueDetai
         input.parent_id,
         &input.assigned_to,
     )?;
+    if let Some(project) = crate::commands::project::scope() {
+        db::file_new_issue(conn, issue.id, &project)?;
+    }
 
     for note in review_notes {
         db::add_note(conn, issue.id, &note, "itr")?;
@@ -949,7 +1003,7 @@ fn create_issue(conn: &Connection, input: IssueCreateInput) -> Result<IssueDetai
 
 fn patch_issue(conn: &Connection, id: i64, patch: &Value) -> Result<IssueDetail, ItrError> {
     // Single transaction: a failure on any field rolls back the whole patch.
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
     let old_issue = db::get_issue(&tx, id)?;
 
     patch_string_field(&tx, id, patch, "title", "title", &old_issue.title)?;
@@ -1102,7 +1156,7 @@ fn resolve_issue(
     // Single transaction (mirrors `itr close`): a mid-resolve failure leaves
     // the issue fully unchanged — no stray events, status flip, or lost edges.
     let status = if wontfix { "wontfix" } else { "done" };
-    let tx = conn.unchecked_transaction()?;
+    let tx = db::begin_write(conn)?;
     let old_issue = db::get_issue(&tx, id)?;
     db::record_event(&tx, id, "status", &old_issue.status, status)?;
     db::update_issue_field(&tx, id, "status", status)?;
@@ -1143,9 +1197,10 @@ fn list_issue_summaries(
     let skills = query_list(query, "skill");
     let assigned_to = query.get("assigned_to").filter(|s| !s.is_empty());
 
-    let mut summaries = Vec::new();
+    let relations = db::load_issue_relations(conn)?;
+    let mut matched = Vec::new();
     for issue in db::all_issues(conn)? {
-        if !all && statuses.is_empty() && issue.status != "open" && issue.status != "in-progress" {
+        if !all && statuses.is_empty() && !db::ACTIVE_STATUSES.contains(&issue.status.as_str()) {
             continue;
         }
         if !statuses.is_empty() && !statuses.contains(&issue.status) {
@@ -1170,7 +1225,7 @@ fn list_issue_summaries(
             continue;
         }
 
-        let is_blocked = db::is_blocked(conn, issue.id)?;
+        let is_blocked = relations.is_blocked(issue.id);
         if blocked_only && !is_blocked {
             continue;
         }
@@ -1181,8 +1236,9 @@ fn list_issue_summaries(
             continue;
         }
 
-        summaries.push(build_issue_summary(conn, &issue, &config));
+        matched.push(issue);
     }
+    let mut summaries = build_issue_summaries(conn, matched, &config);
 
     match query.get("sort").map_or("urgency", String::as_str) {
         "created" => summaries.sort_by(|a, b| b.created_at.cmp(&a.created_at)),
@@ -1263,6 +1319,17 @@ fn issue_matches_terms(
     }))
 }
 
+/// A sync cursor query parameter, 0 when absent.
+fn query_i64(query: &HashMap<String, String>, key: &str) -> Result<i64, ItrError> {
+    query.get(key).map_or(Ok(0), |value| {
+        value.parse::<i64>().map_err(|_| ItrError::InvalidValue {
+            field: key.to_string(),
+            value: value.clone(),
+            valid: "integer cursor".to_string(),
+        })
+    })
+}
+
 fn query_bool(query: &HashMap<String, String>, key: &str) -> bool {
     query
         .get(key)
@@ -1288,7 +1355,7 @@ fn stats_value(conn: &Connection) -> Result<Value, ItrError> {
     let total = issues.len();
     let open = issues
         .iter()
-        .filter(|issue| issue.status == "open" || issue.status == "in-progress")
+        .filter(|issue| db::ACTIVE_STATUSES.contains(&issue.status.as_str()))
         .count();
     let done = issues.iter().filter(|issue| issue.status == "done").count();
     let wontfix = issues
@@ -1347,10 +1414,13 @@ fn error_response_for_itr(err: ItrError) -> HttpResponse {
     let status = match err {
         ItrError::NotFound(_) => 404,
         ItrError::InvalidValue { .. } | ItrError::Parse(_) | ItrError::NoFilters => 400,
-        ItrError::CycleDetected(_) => 409,
-        ItrError::NoDatabase | ItrError::Db(_) | ItrError::Io(_) | ItrError::UpgradeFailed(_) => {
-            500
-        }
+        ItrError::CycleDetected(_) | ItrError::Aborted(_) | ItrError::Ambiguous { .. } => 409,
+        ItrError::NoDatabase
+        | ItrError::Db(_)
+        | ItrError::Io(_)
+        | ItrError::UpgradeFailed(_)
+        | ItrError::SyncFailed(_)
+        | ItrError::Encryption(_) => 500,
     };
     let code = err.error_code();
     error_response(status, &err.to_string(), code)
@@ -1424,10 +1494,12 @@ mod tests {
         let listener = TcpListener::bind("127.0.0.1:0").expect("bind test listener");
         let addr = listener.local_addr().expect("local addr");
         std::thread::spawn(move || {
-            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let pool = ConnectionPool::from_connection(
+                Connection::open_in_memory().expect("open in-memory db"),
+            );
             serve(
                 &listener,
-                &conn,
+                &pool,
                 Path::new(":memory:"),
                 TEST_TOKEN,
                 false,
@@ -1748,9 +1820,10 @@ mod tests {
         std::thread::spawn(move || {
             let conn = test_db();
             insert_test_issue(&conn, "seeded issue");
+            let pool = ConnectionPool::from_connection(conn);
             serve(
                 &listener,
-                &conn,
+                &pool,
                 Path::new(":memory:"),
                 TEST_TOKEN,
                 false,
@@ -1947,6 +2020,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn metrics_route_needs_no_token_but_checks_host() {
+        let addr = spawn_seeded_test_server();
+        let scraped = send_raw(
+            addr,
+            b"GET /metrics HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n",
+        );
+        assert!(scraped.starts_with("HTTP/1.1 200"), "{:?}", scraped);
+        assert!(scraped.contains("text/plain; version=0.0.4"));
+        assert!(scraped.contains("\nitr_open_issues "));
+
+        let foreign = send_raw(
+            addr,
+            b"GET /metrics HTTP/1.1\r\nHost: evil.example\r\nConnection: close\r\n\r\n",
+        );
+        assert!(!foreign.starts_with("HTTP/1.1 200"), "{:?}", foreign);
+    }
+
     #[test]
     fn issues_route_with_ids_serves_batched_details_over_http() {
         // The batched form must not disturb the existing GET /api/issues
@@ -1,10 +1,12 @@
+use crate::commands::policy;
 use crate::commands::{build_issue_detail, print_detail_with_unblocked};
 use crate::db;
 use crate::error::ItrError;
 use crate::format::Format;
 use crate::models::IssueDetail;
 use crate::normalize;
-use crate::normalize::{validate_kind, validate_priority, validate_status};
+use crate::normalize::{validate_kind, validate_priority, validate_severity, validate_status};
+use crate::store;
 use crate::urgency::UrgencyConfig;
 use crate::util;
 use rusqlite::Connection;
@@ -35,6 +37,11 @@ pub(crate) struct UpdateRequest {
     pub remove_files: Vec<String>,
     pub add_skills: Vec<String>,
     pub remove_skills: Vec<String>,
+    pub estimate: Option<String>,
+    pub severity: Option<String>,
+    pub component: Option<String>,
+    pub affects_version: Option<String>,
+    pub fixed_in_version: Option<String>,
 }
 
 /// Persist a new value for a JSON-array list column (`files`/`tags`/`skills`)
@@ -82,6 +89,11 @@ pub fn run(
     remove_files: Vec<String>,
     add_skills: Vec<String>,
     remove_skills: Vec<String>,
+    estimate: Option<String>,
+    severity: Option<String>,
+    component: Option<String>,
+    affects_version: Option<String>,
+    fixed_in_version: Option<String>,
     fmt: Format,
 ) -> Result<(), ItrError> {
     let (detail, unblocked) = run_core(
@@ -109,6 +121,11 @@ pub fn run(
             remove_files,
             add_skills,
             remove_skills,
+            estimate,
+            severity,
+            component,
+            affects_version,
+            fixed_in_version,
         },
     )?;
     print_detail_with_unblocked(&detail, &unblocked, fmt);
@@ -142,6 +159,11 @@ pub(crate) fn run_core(
         remove_files,
         add_skills,
         remove_skills,
+        estimate,
+        severity,
+        component,
+        affects_version,
+        fixed_in_version,
     } = req;
 
     // Capture old values for event recording
@@ -151,7 +173,21 @@ pub(crate) fn run_core(
     let priority = priority.map(|p| normalize::normalize_priority(&p));
     let kind = kind.map(|k| normalize::normalize_kind(&k));
 
-    let tx = conn.unchecked_transaction()?;
+    // Required fields are checked only when this update could break them, so
+    // issues that predate a policy can still be closed or retagged.
+    if kind.is_some() || context.is_some() || acceptance.is_some() {
+        policy::check_required(
+            conn,
+            kind.as_deref().unwrap_or(&old_issue.kind),
+            context.as_deref().unwrap_or(&old_issue.context),
+            acceptance.as_deref().unwrap_or(&old_issue.acceptance),
+            db::issue_repro(conn, id)?.is_some(),
+        )?;
+    }
+
+    let estimate = estimate.as_deref().map(parse_estimate).transpose()?;
+
+    let tx = db::begin_write(conn)?;
     let mut review_notes: Vec<String> = Vec::new();
     let mut terminal_status_applied = false;
 
@@ -162,13 +198,15 @@ pub(crate) fn run_core(
                 db::update_issue_field(&tx, id, "status", s)?;
                 terminal_status_applied = s == "done" || s == "wontfix";
             }
-            Err(_) => {
+            Err(e) => {
                 // Soft fallback (#163): keep the current status instead of
                 // force-reopening — a typo must not mutate workflow state the
                 // caller never asked to change. Matches `batch update`.
                 review_notes.push(format!(
-                    "REVIEW: status '{}' not recognized, kept '{}'. Valid: open, in-progress, done, wontfix",
-                    s, old_issue.status
+                    "REVIEW: status '{}' not recognized{}, kept '{}'. Valid: open, in-progress, review, done, wontfix",
+                    s,
+                    e.did_you_mean(),
+                    old_issue.status
                 ));
             }
         }
@@ -179,10 +217,11 @@ pub(crate) fn run_core(
                 db::record_event(&tx, id, "priority", &old_issue.priority, p)?;
                 db::update_issue_field(&tx, id, "priority", p)?;
             }
-            Err(_) => {
+            Err(e) => {
                 review_notes.push(format!(
-                    "REVIEW: priority '{}' not recognized, defaulted to 'medium'. Valid: critical, high, medium, low",
-                    p
+                    "REVIEW: priority '{}' not recognized{}, defaulted to 'medium'. Valid: critical, high, medium, low",
+                    p,
+                    e.did_you_mean()
                 ));
                 db::record_event(&tx, id, "priority", &old_issue.priority, "medium")?;
                 db::update_issue_field(&tx, id, "priority", "medium")?;
@@ -195,10 +234,11 @@ pub(crate) fn run_core(
                 db::record_event(&tx, id, "kind", &old_issue.kind, k)?;
                 db::update_issue_field(&tx, id, "kind", k)?;
             }
-            Err(_) => {
+            Err(e) => {
                 review_notes.push(format!(
-                    "REVIEW: kind '{}' not recognized, defaulted to 'task'. Valid: bug, feature, task, epic",
-                    k
+                    "REVIEW: kind '{}' not recognized{}, defaulted to 'task'. Valid: bug, feature, task, epic",
+                    k,
+                    e.did_you_mean()
                 ));
                 db::record_event(&tx, id, "kind", &old_issue.kind, "task")?;
                 db::update_issue_field(&tx, id, "kind", "task")?;
@@ -221,6 +261,90 @@ pub(crate) fn run_core(
         db::record_event(&tx, id, "assigned_to", &old_issue.assigned_to, a)?;
         db::update_issue_field(&tx, id, "assigned_to", a)?;
     }
+    if let Some(points) = estimate {
+        let old = db::issue_estimate(&tx, id)?;
+        if old != points {
+            let show = |p: Option<i64>| p.map(|p| p.to_string()).unwrap_or_default();
+            db::record_event(&tx, id, "estimate", &show(old), &show(points))?;
+            db::set_estimate(&tx, id, points)?;
+        }
+    }
+    // `none` clears the severity; an unknown value keeps the current one.
+    if let Some(raw) = severity {
+        let s = match raw.trim() {
+            "" | "none" => String::new(),
+            raw => normalize::normalize_severity(raw),
+        };
+        match if s.is_empty() {
+            Ok(())
+        } else {
+            validate_severity(&s)
+        } {
+            Ok(()) if s != old_issue.severity => {
+                db::record_event(&tx, id, "severity", &old_issue.severity, &s)?;
+                db::update_issue_field(&tx, id, "severity", &s)?;
+            }
+            Ok(()) => {}
+            Err(e) => {
+                review_notes.push(format!(
+                    "REVIEW: severity '{}' not recognized{}, kept '{}'. Valid: {}, none",
+                    s,
+                    e.did_you_mean(),
+                    old_issue.severity,
+                    normalize::SEVERITIES
+                ));
+            }
+        }
+    }
+    // `none` takes the issue out of its component. Moving it into one routes
+    // it like a new issue: the default assignee if it has none, plus the
+    // default tags.
+    if let Some(raw) = component {
+        let name = raw.trim();
+        if name.is_empty() || name == "none" {
+            db::set_issue_component(&tx, id, "")?;
+        } else if let Some(c) = db::get_component(&tx, name)? {
+            if db::set_issue_component(&tx, id, &c.name)?.is_some() {
+                let current = db::get_issue(&tx, id)?;
+                if current.assigned_to.is_empty() && !c.default_assignee.is_empty() {
+                    db::record_event(&tx, id, "assigned_to", "", &c.default_assignee)?;
+                    db::update_issue_field(&tx, id, "assigned_to", &c.default_assignee)?;
+                }
+                let tags = util::apply_tags(current.tags.clone(), &c.default_tags, &[]);
+                persist_list_field(&tx, id, "tags", &current.tags, &tags)?;
+            }
+        } else {
+            review_notes.push(store::unknown_component_note(
+                &tx,
+                name,
+                &format!("kept '{}'", old_issue.component),
+            )?);
+        }
+    }
+    // Versions are free text; `none` or blank clears one.
+    for (field, raw, old) in [
+        (
+            "affects_version",
+            affects_version,
+            &old_issue.affects_version,
+        ),
+        (
+            "fixed_in_version",
+            fixed_in_version,
+            &old_issue.fixed_in_version,
+        ),
+    ] {
+        if let Some(raw) = raw {
+            let version = match raw.trim() {
+                "none" => "",
+                v => v,
+            };
+            if version != old {
+                db::record_event(&tx, id, field, old, version)?;
+                db::update_issue_field(&tx, id, field, version)?;
+            }
+        }
+    }
 
     // List fields (files/tags/skills). The replace form is applied first;
     // add/remove edits then apply on top of the replacement instead of being
@@ -394,6 +518,21 @@ pub(crate) fn run_core(
     Ok((detail, unblocked))
 }
 
+/// Parse an `--estimate` value: `none` (or blank) means no estimate,
+/// anything else must be a point count.
+pub(crate) fn parse_estimate(raw: &str) -> Result<Option<i64>, ItrError> {
+    match raw.trim() {
+        "" | "none" => Ok(None),
+        points => util::parse_points(points)
+            .map(Some)
+            .ok_or_else(|| ItrError::InvalidValue {
+                field: "estimate".to_string(),
+                value: points.to_string(),
+                valid: "a whole number of points (e.g. 3 or 3pts), or none to clear".to_string(),
+            }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -438,6 +577,52 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn required_field_policy_applies_only_to_updates_that_touch_it() {
+        let conn = open_test_db();
+        let id = seed(&conn, "legacy");
+        db::config_set(&conn, policy::REQUIRE_ACCEPTANCE_KEY, "bug").unwrap();
+
+        // Unrelated edits to an issue that predates the policy still work.
+        update(
+            &conn,
+            id,
+            UpdateRequest {
+                status: Some("in-progress".to_string()),
+                ..Default::default()
+            },
+        );
+        let err = run_core(
+            &conn,
+            id,
+            UpdateRequest {
+                kind: Some("bug".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ItrError::InvalidValue { .. }));
+        assert_eq!(db::get_issue(&conn, id).unwrap().kind, "task", "no write");
+        update(
+            &conn,
+            id,
+            UpdateRequest {
+                kind: Some("bug".to_string()),
+                acceptance: Some("no crash".to_string()),
+                ..Default::default()
+            },
+        );
+        assert!(run_core(
+            &conn,
+            id,
+            UpdateRequest {
+                acceptance: Some(String::new()),
+                ..Default::default()
+            },
+        )
+        .is_err());
+    }
+
     // --- #163: unrecognized --status keeps the current status ---
 
     #[test]
@@ -480,6 +665,41 @@ mod tests {
         assert_eq!(status_events[0].new_value, "done");
     }
 
+    #[test]
+    fn severity_is_set_kept_on_typo_and_cleared() {
+        let conn = open_test_db();
+        let id = seed(&conn, "crash on save");
+        let set = |severity: &str| {
+            update(
+                &conn,
+                id,
+                UpdateRequest {
+                    severity: Some(severity.to_string()),
+                    ..Default::default()
+                },
+            );
+        };
+
+        set("S2");
+        assert_eq!(db::get_issue(&conn, id).unwrap().severity, "major");
+        set("catastrophic");
+        let issue = db::get_issue(&conn, id).unwrap();
+        assert_eq!(
+            issue.severity, "major",
+            "unrecognized severity keeps the old one"
+        );
+        assert_eq!(issue.priority, "medium", "severity never touches priority");
+        set("none");
+        assert_eq!(db::get_issue(&conn, id).unwrap().severity, "");
+
+        let events = events_for(&conn, id, "severity");
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            (events[1].old_value.as_str(), events[1].new_value.as_str()),
+            ("major", "")
+        );
+    }
+
     // --- #187: list-field changes record audit events ---
 
     #[test]
@@ -1,5 +1,5 @@
 use crate::error::ItrError;
-use crate::format::Format;
+use crate::format::{self, Format};
 use std::env;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
@@ -91,7 +91,7 @@ pub fn run(no_pull: bool, source_dir: Option<String>, fmt: Format) -> Result<(),
                 "pulled": !no_pull,
                 "new_changes": pulled_changes,
             });
-            println!("{}", out);
+            format::print_json(&out.to_string());
         }
         _ => {
             println!("UPGRADE: {} -> {}", old_version, new_version);
@@ -0,0 +1,91 @@
+use crate::commands::project;
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::{ListFilter, UrgencyReport};
+use crate::urgency::{compute_urgency_with_breakdown, UrgencyConfig};
+use rusqlite::Connection;
+use std::cmp::Ordering;
+
+/// `itr urgency [ID | --all]` — show how scores are assembled. With an ID,
+/// the breakdown for that issue (any status); otherwise every open and
+/// in-progress issue, blocked ones included, highest score first — the
+/// same ranking `next` draws from.
+pub fn run(
+    conn: &Connection,
+    id: Option<i64>,
+    limit: Option<usize>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let issues = match id {
+        Some(id) => vec![db::get_issue(conn, id)?],
+        None => db::list_issues(
+            conn,
+            &ListFilter {
+                include_blocked: true,
+                project: project::scope(),
+                ..ListFilter::default()
+            },
+        )?,
+    };
+
+    let config = UrgencyConfig::load(conn);
+    let mut reports: Vec<UrgencyReport> = issues
+        .into_iter()
+        .map(|issue| {
+            let (urgency, urgency_breakdown) =
+                compute_urgency_with_breakdown(&issue, &config, conn);
+            UrgencyReport {
+                id: issue.id,
+                title: issue.title,
+                status: issue.status,
+                priority: issue.priority,
+                kind: issue.kind,
+                urgency,
+                urgency_breakdown,
+            }
+        })
+        .collect();
+    // Stable: equal scores keep ascending-ID order from list_issues.
+    reports.sort_by(|a, b| b.urgency.partial_cmp(&a.urgency).unwrap_or(Ordering::Equal));
+    if let Some(n) = limit {
+        reports.truncate(n);
+    }
+
+    if reports.is_empty() {
+        error::print_empty(fmt.is_json(), "No open issues to score.");
+        return Ok(());
+    }
+    format::print_output(&format::format_urgency_reports(&reports, fmt), fmt);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_accepts_closed_issue_by_id_and_rejects_missing() {
+        let conn = db::open_test_db();
+        let issue = db::insert_issue(
+            &conn,
+            "done",
+            "high",
+            "bug",
+            "",
+            &[],
+            &[],
+            &[],
+            "",
+            None,
+            "",
+        )
+        .unwrap();
+        db::update_issue_field(&conn, issue.id, "status", "done").unwrap();
+        assert!(run(&conn, Some(issue.id), None, Format::Compact).is_ok());
+        assert!(matches!(
+            run(&conn, Some(999), None, Format::Compact),
+            Err(ItrError::NotFound(999))
+        ));
+    }
+}
@@ -0,0 +1,146 @@
+use crate::commands::project;
+use crate::db;
+use crate::error::{self, ItrError};
+use crate::format::{self, Format};
+use crate::models::{Issue, ListFilter, VersionReport};
+use rusqlite::Connection;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// `itr versions` — the active issues (open, in-progress, review) grouped by
+/// `affects_version`, oldest version first, with how many already name a
+/// `fixed_in_version`. Issues without an affected version are left out.
+pub fn run(conn: &Connection, fmt: Format) -> Result<(), ItrError> {
+    let issues = db::list_issues(
+        conn,
+        &ListFilter {
+            include_blocked: true,
+            project: project::scope(),
+            ..ListFilter::default()
+        },
+    )?;
+    let report = aggregate(&issues);
+    if report.is_empty() {
+        error::print_empty(fmt.is_json(), "No open issues with an affected version.");
+        return Ok(());
+    }
+    format::print_output(&format::format_versions(&report, fmt), fmt);
+    Ok(())
+}
+
+/// Group issues by affected version, in [`compare_versions`] order, with
+/// issue IDs ascending inside each version.
+fn aggregate(issues: &[Issue]) -> Vec<VersionReport> {
+    let mut by_version: BTreeMap<&str, Vec<&Issue>> = BTreeMap::new();
+    for issue in issues.iter().filter(|i| !i.affects_version.is_empty()) {
+        by_version
+            .entry(issue.affects_version.as_str())
+            .or_default()
+            .push(issue);
+    }
+    let mut report: Vec<VersionReport> = by_version
+        .into_iter()
+        .map(|(version, issues)| {
+            let mut issue_ids: Vec<i64> = issues.iter().map(|i| i.id).collect();
+            issue_ids.sort_unstable();
+            VersionReport {
+                version: version.to_string(),
+                open: issues.len() as i64,
+                fix_scheduled: issues
+                    .iter()
+                    .filter(|i| !i.fixed_in_version.is_empty())
+                    .count() as i64,
+                issue_ids,
+            }
+        })
+        .collect();
+    report.sort_by(|a, b| compare_versions(&a.version, &b.version));
+    report
+}
+
+/// Order version strings so that `1.9` comes before `1.10`: split on `.`,
+/// `-`, and `+`, compare numeric parts as numbers (ahead of text parts) and
+/// the rest as text. A leading `v` is ignored.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<String> {
+        v.trim_start_matches(['v', 'V'])
+            .split(['.', '-', '+'])
+            .map(str::to_string)
+            .collect()
+    };
+    let (pa, pb) = (parts(a), parts(b));
+    for (x, y) in pa.iter().zip(&pb) {
+        let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => x.cmp(y),
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    pa.len().cmp(&pb.len()).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{self, NewIssue};
+
+    #[test]
+    fn versions_group_open_issues_in_version_order() {
+        let conn = db::open_test_db();
+        let add = |title: &str, affects: &str, fixed: &str| {
+            store::add_issue(
+                &conn,
+                NewIssue {
+                    affects_version: affects.to_string(),
+                    fixed_in_version: fixed.to_string(),
+                    ..NewIssue::new(title)
+                },
+            )
+            .unwrap()
+            .issue
+            .id
+        };
+        let a = add("crash", "1.10.0", "");
+        let b = add("leak", "1.9.2", "1.10.0");
+        let c = add("typo", "1.9.2", "");
+        add("unversioned", "", "");
+        let closed = add("old", "1.2", "");
+        db::update_issue_field(&conn, closed, "status", "done").unwrap();
+
+        let issues = db::list_issues(
+            &conn,
+            &ListFilter {
+                include_blocked: true,
+                ..ListFilter::default()
+            },
+        )
+        .unwrap();
+        let report = aggregate(&issues);
+        let rows: Vec<(&str, i64, i64, Vec<i64>)> = report
+            .iter()
+            .map(|r| {
+                (
+                    r.version.as_str(),
+                    r.open,
+                    r.fix_scheduled,
+                    r.issue_ids.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![("1.9.2", 2, 1, vec![b, c]), ("1.10.0", 1, 0, vec![a])]
+        );
+    }
+
+    #[test]
+    fn compare_versions_is_numeric_per_part() {
+        let mut versions = vec!["v2.0", "1.10", "nightly", "1.9", "1.9.1"];
+        versions.sort_by(|a, b| compare_versions(a, b));
+        assert_eq!(versions, vec!["1.9", "1.9.1", "1.10", "v2.0", "nightly"]);
+    }
+}
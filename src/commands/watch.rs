@@ -0,0 +1,277 @@
+use crate::db;
+use crate::error::ItrError;
+use crate::format::{self, escape_quoted_value, Format};
+use crate::models::{is_terminal, Issue};
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{ErrorKind, Write};
+use std::time::Duration;
+
+/// One change seen by `itr watch`, printed as a JSON line in `-f json`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WatchEvent {
+    /// `added`, `updated`, `closed`, `reopened`, `deleted`, `blocked`, or
+    /// `unblocked`.
+    pub event: &'static str,
+    pub id: i64,
+    pub title: String,
+    pub status: String,
+    /// Issue fields that changed (`updated`, `closed`, `reopened` only).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+    pub at: String,
+}
+
+/// What `watch` remembers about an issue between polls.
+#[derive(Debug, Clone)]
+pub(crate) struct IssueState {
+    pub(crate) issue: Value,
+    pub(crate) blocked: bool,
+}
+
+pub(crate) type Snapshot = BTreeMap<i64, IssueState>;
+
+pub fn run(
+    conn: &Connection,
+    interval_ms: u64,
+    count: Option<usize>,
+    fmt: Format,
+) -> Result<(), ItrError> {
+    let mut stdout = std::io::stdout();
+    let result = watch(conn, Duration::from_millis(interval_ms), count, |event| {
+        let line = format_event(event, fmt);
+        writeln!(stdout, "{}", format::stream_line(line, fmt))?;
+        stdout.flush()
+    });
+    match result {
+        // The reader went away (`itr watch | head -1`): stop quietly.
+        Err(ItrError::Io(e)) if e.kind() == ErrorKind::BrokenPipe => Ok(()),
+        other => other,
+    }
+}
+
+/// Poll `PRAGMA data_version` every `interval`; whenever another connection
+/// has committed, diff the issue table against the previous snapshot and
+/// hand each change to `emit`. Returns after `count` events, or never.
+pub(crate) fn watch(
+    conn: &Connection,
+    interval: Duration,
+    count: Option<usize>,
+    mut emit: impl FnMut(&WatchEvent) -> std::io::Result<()>,
+) -> Result<(), ItrError> {
+    let mut version = data_version(conn)?;
+    let mut previous = snapshot(conn)?;
+    let mut emitted = 0;
+    loop {
+        if count.is_some_and(|n| emitted >= n) {
+            return Ok(());
+        }
+        std::thread::sleep(interval);
+        let current_version = data_version(conn)?;
+        if current_version == version {
+            continue;
+        }
+        version = current_version;
+        let current = snapshot(conn)?;
+        for event in diff(&previous, &current, &now()) {
+            if count.is_some_and(|n| emitted >= n) {
+                break;
+            }
+            emit(&event)?;
+            emitted += 1;
+        }
+        previous = current;
+    }
+}
+
+/// Bumped whenever another connection commits to the database.
+pub(crate) fn data_version(conn: &Connection) -> Result<i64, ItrError> {
+    Ok(conn.query_row("PRAGMA data_version", [], |row| row.get(0))?)
+}
+
+pub(crate) fn snapshot(conn: &Connection) -> Result<Snapshot, ItrError> {
+    let relations = db::load_issue_relations(conn)?;
+    db::all_issues(conn)?
+        .into_iter()
+        .map(|issue: Issue| {
+            let blocked = relations.is_blocked(issue.id);
+            Ok((
+                issue.id,
+                IssueState {
+                    issue: serde_json::to_value(issue)?,
+                    blocked,
+                },
+            ))
+        })
+        .collect()
+}
+
+pub(crate) fn now() -> String {
+    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+/// Changes between two snapshots, in issue ID order. Each issue yields at
+/// most one lifecycle event (added/updated/closed/reopened/deleted), then a
+/// blocked/unblocked event when its active blockers appeared or cleared.
+pub(crate) fn diff(previous: &Snapshot, current: &Snapshot, at: &str) -> Vec<WatchEvent> {
+    let text =
+        |state: &IssueState, key: &str| state.issue[key].as_str().unwrap_or_default().to_string();
+    let event = |name: &'static str, id: i64, state: &IssueState, fields: Vec<String>| WatchEvent {
+        event: name,
+        id,
+        title: text(state, "title"),
+        status: text(state, "status"),
+        fields,
+        at: at.to_string(),
+    };
+
+    let mut ids: Vec<i64> = previous.keys().chain(current.keys()).copied().collect();
+    ids.sort_unstable();
+    ids.dedup();
+
+    let mut events = Vec::new();
+    for id in ids {
+        match (previous.get(&id), current.get(&id)) {
+            (None, Some(now)) => events.push(event("added", id, now, vec![])),
+            (Some(before), None) => events.push(event("deleted", id, before, vec![])),
+            (Some(before), Some(now)) => {
+                let fields: Vec<String> = now
+                    .issue
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter(|(key, value)| {
+                        key.as_str() != "updated_at"
+                            && before.issue.get(key.as_str()) != Some(value)
+                    })
+                    .map(|(key, _)| key.clone())
+                    .collect();
+                let was_closed = is_terminal(&text(before, "status"));
+                let is_closed = is_terminal(&text(now, "status"));
+                let lifecycle = match (was_closed, is_closed) {
+                    (false, true) => Some("closed"),
+                    (true, false) => Some("reopened"),
+                    _ if !fields.is_empty() => Some("updated"),
+                    _ => None,
+                };
+                if let Some(name) = lifecycle {
+                    events.push(event(name, id, now, fields));
+                }
+                if !is_closed && before.blocked != now.blocked {
+                    let name = if now.blocked { "blocked" } else { "unblocked" };
+                    events.push(event(name, id, now, vec![]));
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    events
+}
+
+pub(crate) fn format_event(event: &WatchEvent, fmt: Format) -> String {
+    if fmt.is_json() {
+        return serde_json::to_string(event).unwrap_or_default();
+    }
+    let fields = if event.fields.is_empty() {
+        String::new()
+    } else {
+        format!(" FIELDS:{}", event.fields.join(","))
+    };
+    format!(
+        "{}:{} \"{}\" STATUS:{}{} ({})",
+        event.event.to_ascii_uppercase(),
+        event.id,
+        escape_quoted_value(&event.title),
+        event.status,
+        fields,
+        event.at
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn events_of(before: &Snapshot, after: &Snapshot) -> Vec<(&'static str, i64)> {
+        diff(before, after, "t")
+            .iter()
+            .map(|e| (e.event, e.id))
+            .collect()
+    }
+
+    #[test]
+    fn diff_reports_lifecycle_and_blocking_changes() {
+        let conn = db::init_db(Path::new(":memory:")).unwrap();
+        let blocker = db::insert_test_issue(&conn, "blocker");
+        let blocked = db::insert_test_issue(&conn, "blocked");
+        let s0 = snapshot(&conn).unwrap();
+
+        db::add_dependency(&conn, blocker, blocked).unwrap();
+        let added = db::insert_test_issue(&conn, "new");
+        let s1 = snapshot(&conn).unwrap();
+        assert_eq!(
+            events_of(&s0, &s1),
+            vec![("blocked", blocked), ("added", added)]
+        );
+
+        db::update_issue_field(&conn, blocker, "priority", "high").unwrap();
+        let s2 = snapshot(&conn).unwrap();
+        let changes = diff(&s1, &s2, "t");
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            (changes[0].event, changes[0].fields.clone()),
+            ("updated", vec!["priority".to_string()])
+        );
+
+        db::update_issue_field(&conn, blocker, "status", "done").unwrap();
+        let s3 = snapshot(&conn).unwrap();
+        assert_eq!(
+            events_of(&s2, &s3),
+            vec![("closed", blocker), ("unblocked", blocked)]
+        );
+
+        db::update_issue_field(&conn, blocker, "status", "open").unwrap();
+        conn.execute("DELETE FROM issues WHERE id = ?1", [added])
+            .unwrap();
+        let s4 = snapshot(&conn).unwrap();
+        assert_eq!(
+            events_of(&s3, &s4),
+            vec![
+                ("reopened", blocker),
+                ("blocked", blocked),
+                ("deleted", added)
+            ]
+        );
+        assert!(diff(&s4, &s4, "t").is_empty());
+    }
+
+    #[test]
+    fn watch_sees_commits_from_another_connection() {
+        let path = db::temp_test_db("watch-commits");
+        let watcher = db::open_db(&path).unwrap();
+        let writer_path = path.clone();
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            let conn = db::open_db(&writer_path).unwrap();
+            let id = db::insert_test_issue(&conn, "from elsewhere");
+            db::update_issue_field(&conn, id, "status", "done").unwrap();
+        });
+
+        let mut seen = Vec::new();
+        watch(&watcher, Duration::from_millis(20), Some(1), |e| {
+            seen.push(e.clone());
+            Ok(())
+        })
+        .unwrap();
+        writer.join().unwrap();
+
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].title, "from elsewhere");
+        assert!(matches!(seen[0].event, "added" | "closed"));
+        assert!(format_event(&seen[0], Format::Compact).contains("\"from elsewhere\""));
+        db::remove_test_db(&path);
+    }
+}
@@ -0,0 +1,85 @@
+//! Value candidates for the dynamic shell completions behind
+//! `itr completions`. They run inside the `COMPLETE=<shell> itr` callback,
+//! so each opens the database the way a normal command would (`ITR_DB_PATH`,
+//! walk-up, `db.fallback`) and quietly offers nothing when that fails: a
+//! broken <TAB> must never print errors into the user's prompt.
+
+use crate::models::is_terminal;
+use crate::{db, global_config, notify, webhook};
+use clap_complete::CompletionCandidate;
+use itr_core::urgency::{UrgencyConfig, FORMULA_KEY};
+use rusqlite::Connection;
+use std::collections::BTreeSet;
+
+/// Issue IDs with their titles as help: open and in-progress first, then
+/// closed ones, each ascending.
+pub fn issue_ids() -> Vec<CompletionCandidate> {
+    with_db(|conn| {
+        let mut issues = db::all_issues(conn).ok()?;
+        issues.sort_by_key(|i| (is_terminal(&i.status), i.id));
+        Some(
+            issues
+                .into_iter()
+                .map(|i| CompletionCandidate::new(i.id.to_string()).help(Some(i.title.into())))
+                .collect(),
+        )
+    })
+}
+
+/// Tags in use or described, with their open-issue counts as help.
+pub fn tags() -> Vec<CompletionCandidate> {
+    with_db(|conn| {
+        Some(
+            db::tag_counts(conn)
+                .ok()?
+                .into_iter()
+                .map(|t| {
+                    CompletionCandidate::new(t.tag).help(Some(format!("{} open", t.open).into()))
+                })
+                .collect(),
+        )
+    })
+}
+
+/// Every key `itr config list` would show, plus the documented non-urgency
+/// keys that have no default row.
+pub fn config_keys() -> Vec<CompletionCandidate> {
+    let mut keys: BTreeSet<String> = UrgencyConfig::defaults_map()
+        .into_iter()
+        .map(|(k, _)| k.to_string())
+        .collect();
+    keys.extend(
+        [
+            FORMULA_KEY,
+            global_config::FORMAT_KEY,
+            global_config::COLOR_KEY,
+            db::AGENT_KEY,
+            webhook::URL_KEY,
+            notify::DESKTOP_KEY,
+        ]
+        .map(String::from),
+    );
+    // `with_db` installs the global config, so it runs first.
+    keys.extend(with_db(|conn| {
+        Some(
+            db::config_list(conn)
+                .ok()?
+                .into_iter()
+                .map(|(k, _)| k)
+                .collect(),
+        )
+    }));
+    keys.extend(global_config::values().into_keys());
+    keys.into_iter().map(CompletionCandidate::new).collect()
+}
+
+fn with_db<T>(f: impl FnOnce(&Connection) -> Option<Vec<T>>) -> Vec<T> {
+    global_config::install();
+    let defaults = global_config::values();
+    db::find_db(None, &defaults)
+        .and_then(|path| db::open_db(&path))
+        .and_then(|conn| db::set_config_defaults(&conn, &defaults).map(|()| conn))
+        .ok()
+        .and_then(|conn| f(&conn))
+        .unwrap_or_default()
+}
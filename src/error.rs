@@ -1,87 +1,142 @@
+use crate::cli::FailOn;
+use rusqlite::Connection;
+use std::cell::{Cell, RefCell};
 use std::process;
 
-#[derive(Debug, thiserror::Error)]
-pub enum ItrError {
-    #[error("Issue {0} not found")]
-    NotFound(i64),
+pub use itr_core::error::ItrError;
 
-    #[error("Cycle detected: {0}")]
-    CycleDetected(String),
+/// Exit status for an empty result under `--fail-on empty`.
+pub const EMPTY_EXIT: i32 = 9;
+/// Exit status under `--fail-on` when only blocked issues remain.
+pub const BLOCKED_EXIT: i32 = 10;
 
-    #[error("Invalid value for {field}: '{value}'. Valid: {valid}")]
-    InvalidValue {
-        field: String,
-        value: String,
-        valid: String,
-    },
-
-    #[error("No .itr.db found. Run 'itr init' to create one.")]
-    NoDatabase,
-
-    #[error("Database error: {0}")]
-    Db(#[from] rusqlite::Error),
-
-    #[error("JSON parse error: {0}")]
-    Parse(#[from] serde_json::Error),
-
-    #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
-
-    #[error("Upgrade failed: {0}")]
-    UpgradeFailed(String),
-
-    #[error("At least one filter is required for bulk operations")]
-    NoFilters,
+thread_local! {
+    static FAIL_ON: Cell<FailOn> = const { Cell::new(FailOn::None) };
+    /// Set by an empty result: how many matching issues were left out because
+    /// they are blocked (0 when emptiness has nothing to do with blockers).
+    static EMPTY: Cell<Option<usize>> = const { Cell::new(None) };
+    /// Issues a `NOT_FOUND` ID may have meant; see [`suggest_issues`].
+    static CANDIDATES: RefCell<Vec<(i64, String)>> = const { RefCell::new(Vec::new()) };
 }
 
-impl ItrError {
-    pub fn exit_code(&self) -> i32 {
-        match self {
-            ItrError::NotFound(_) => 1,
-            ItrError::CycleDetected(_) => 1,
-            ItrError::InvalidValue { .. } => 1,
-            ItrError::NoDatabase => 1,
-            ItrError::Db(_) => 1,
-            ItrError::Parse(_) => 1,
-            ItrError::Io(_) => 1,
-            ItrError::UpgradeFailed(_) => 1,
-            ItrError::NoFilters => 1,
-        }
-    }
+/// How many issues a `NOT_FOUND` error suggests.
+const MAX_CANDIDATES: usize = 3;
 
-    pub fn error_code(&self) -> &'static str {
-        match self {
-            ItrError::NotFound(_) => "NOT_FOUND",
-            ItrError::CycleDetected(_) => "CYCLE_DETECTED",
-            ItrError::InvalidValue { .. } => "INVALID_VALUE",
-            ItrError::NoDatabase => "NO_DATABASE",
-            ItrError::Db(_) => "DB_ERROR",
-            ItrError::Parse(_) => "PARSE_ERROR",
-            ItrError::Io(_) => "IO_ERROR",
-            ItrError::UpgradeFailed(_) => "UPGRADE_FAILED",
-            ItrError::NoFilters => "NO_FILTERS",
+/// Before reporting `err`, look up the issues a missing ID may have meant:
+/// existing IDs one keystroke away (`12` for `21` or `112`), then the most
+/// recently updated open issues. [`handle_error`] lists them.
+pub fn suggest_issues(conn: &Connection, err: &ItrError) {
+    let ItrError::NotFound(missing) = err else {
+        return;
+    };
+    let Ok(mut issues) = crate::db::all_issues(conn) else {
+        return;
+    };
+    issues.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then(b.id.cmp(&a.id)));
+    let missing = missing.to_string();
+    let near = issues
+        .iter()
+        .filter(|i| crate::util::levenshtein(&i.id.to_string(), &missing) == 1);
+    let recent = issues
+        .iter()
+        .filter(|i| crate::db::ACTIVE_STATUSES.contains(&i.status.as_str()));
+    let mut candidates: Vec<(i64, String)> = Vec::new();
+    for issue in near.chain(recent) {
+        if candidates.len() == MAX_CANDIDATES {
+            break;
+        }
+        if !candidates.iter().any(|(id, _)| *id == issue.id) {
+            candidates.push((issue.id, issue.title.clone()));
         }
     }
+    CANDIDATES.with(|c| *c.borrow_mut() = candidates);
 }
 
 pub fn handle_error(err: ItrError, json_mode: bool) -> ! {
+    let suggested = CANDIDATES.with(RefCell::take);
+    let mut message = err.to_string();
+    if !suggested.is_empty() {
+        let list: Vec<String> = suggested
+            .iter()
+            .map(|(id, title)| format!("#{} {}", id, title))
+            .collect();
+        message.push_str(&format!(" (did you mean {}?)", list.join(", ")));
+    }
     if json_mode {
-        let err_json = serde_json::json!({
-            "error": err.to_string(),
+        let mut err_json = serde_json::json!({
+            "error": message,
             "code": err.error_code(),
         });
-        eprintln!("{}", err_json);
+        // AMBIGUOUS carries the matches, and NOT_FOUND the likely meant
+        // issues, so an agent can retry with an ID.
+        let candidates = match &err {
+            ItrError::Ambiguous { candidates, .. } => candidates.as_slice(),
+            _ => suggested.as_slice(),
+        };
+        if !candidates.is_empty() {
+            err_json["candidates"] = candidates
+                .iter()
+                .map(|(id, title)| serde_json::json!({"id": id, "title": title}))
+                .collect();
+        }
+        if let Some(value) = err.suggestion() {
+            err_json["suggestion"] = value.into();
+        }
+        eprintln!("{}", crate::format::error_envelope(err_json));
     } else {
-        eprintln!("ERROR: {}", err);
+        eprintln!("ERROR: {}", message);
     }
     process::exit(err.exit_code());
 }
 
-/// Print empty result output. NOT an error — returns normally with exit 0.
+/// Print empty result output. NOT an error — returns normally with exit 0,
+/// unless `--fail-on empty` asks for a non-zero exit once the command ends.
 pub fn print_empty(json_mode: bool, msg: &str) {
+    print_empty_blocked(json_mode, msg, 0);
+}
+
+/// [`print_empty`] for a pick from unblocked issues (`next`, `ready`) that
+/// came back empty while `blocked` matching issues are waiting on others.
+pub fn print_empty_blocked(json_mode: bool, msg: &str, blocked: usize) {
     if json_mode {
-        println!("[]");
+        crate::format::print_json("[]");
     } else {
         println!("{}", msg);
     }
+    EMPTY.with(|e| e.set(Some(blocked)));
+}
+
+pub fn set_fail_on(fail_on: FailOn) {
+    FAIL_ON.with(|f| f.set(fail_on));
+}
+
+/// Apply `--fail-on` after a command succeeded: an empty result exits
+/// [`EMPTY_EXIT`] under `empty`, and one caused by blockers exits
+/// [`BLOCKED_EXIT`] under either `empty` or `blocked`. Reported on stderr
+/// like any error, with code `EMPTY_RESULT` or `ALL_BLOCKED`.
+pub fn apply_fail_on(json_mode: bool) {
+    let Some(blocked) = EMPTY.with(Cell::get) else {
+        return;
+    };
+    let (exit, code, msg) = match FAIL_ON.with(Cell::get) {
+        FailOn::Empty | FailOn::Blocked if blocked > 0 => (
+            BLOCKED_EXIT,
+            "ALL_BLOCKED",
+            format!(
+                "No unblocked issues: {} matching issue(s) are blocked",
+                blocked
+            ),
+        ),
+        FailOn::Empty => (EMPTY_EXIT, "EMPTY_RESULT", "Empty result".to_string()),
+        FailOn::Blocked | FailOn::None => return,
+    };
+    if json_mode {
+        eprintln!(
+            "{}",
+            crate::format::error_envelope(serde_json::json!({ "error": msg, "code": code }))
+        );
+    } else {
+        eprintln!("ERROR: {}", msg);
+    }
+    process::exit(exit);
 }
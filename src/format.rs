@@ -1,11 +1,82 @@
 use crate::models::{
-    BatchResult, Event, GraphOutput, IssueDetail, IssueSummary, Relation, SearchResult, Stats,
-    UnblockedIssue,
+    Agent, AutoAssignPlan, BatchResult, Brief, BriefIssue, Component, Event, ExternalBlocker,
+    FileCommit, FileHotspot, GraphNode, GraphOutput, IssueCommit, IssueDetail, IssueSummary, Note,
+    ProjectCount, Relation, Repro, SearchResult, Session, SprintPlan, Stats, SubtreeProgress,
+    TagCount, TreeNode, UnblockedIssue, UrgencyReport, VersionReport,
 };
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 
 thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+    static RAW: Cell<bool> = const { Cell::new(false) };
+    static GHA: Cell<bool> = const { Cell::new(false) };
     static FIELDS_FILTER: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+    static TAG_COLORS: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Install the tag → ANSI escape map used to color tags in pretty output.
+///
+/// Set once per invocation, and only when [`crate::util::color_enabled`]
+/// holds, so piped or non-pretty output never carries escape codes. Colors
+/// that fail [`crate::util::ansi_color`] are dropped.
+pub fn set_tag_colors(colors: Vec<(String, String)>) {
+    let map = colors
+        .into_iter()
+        .filter_map(|(tag, color)| crate::util::ansi_color(&color).map(|esc| (tag, esc)))
+        .collect();
+    TAG_COLORS.with(|c| *c.borrow_mut() = map);
+}
+
+/// Turn on `--quiet` for this thread: mutating commands print only the
+/// identifier they touched in text formats, and `UNBLOCKED:` lines are
+/// dropped. JSON output is unaffected.
+pub fn set_quiet(on: bool) {
+    QUIET.with(|q| q.set(on));
+}
+
+/// Whether `--quiet` trims text output for `fmt` (never for JSON).
+pub fn quiet(fmt: Format) -> bool {
+    !fmt.is_json() && QUIET.with(Cell::get)
+}
+
+/// Turn on `-f gha` for this thread: `lint`, `doctor`, and `gate` print
+/// GitHub Actions workflow commands instead of their compact lines. Every
+/// other command prints compact output.
+pub fn set_gha(on: bool) {
+    GHA.with(|g| g.set(on));
+}
+
+/// Whether `-f gha` asked for workflow commands; `fmt` is compact then.
+pub fn gha(fmt: Format) -> bool {
+    fmt == Format::Compact && GHA.with(Cell::get)
+}
+
+/// One GitHub Actions workflow command (`level` is `error`, `warning`, or
+/// `notice`), e.g. `::error file=src/a.rs,title=itr gate::Critical issue #3`.
+/// Values are escaped the way the Actions runner unescapes them, so a
+/// newline or `::` in a title cannot end the command early.
+pub fn gha_command(level: &str, file: Option<&str>, title: &str, message: &str) -> String {
+    let data = |s: &str| {
+        s.replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A")
+    };
+    let property = |s: &str| data(s).replace(':', "%3A").replace(',', "%2C");
+    let mut props = Vec::new();
+    if let Some(file) = file {
+        props.push(format!("file={}", property(file)));
+    }
+    props.push(format!("title={}", property(title)));
+    format!("::{} {}::{}", level, props.join(","), data(message))
+}
+
+/// Wrap `tag` in its configured color, or return it unchanged.
+fn paint_tag(tag: &str) -> String {
+    TAG_COLORS.with(|c| match c.borrow().get(tag) {
+        Some(esc) => format!("{}{}\x1b[0m", esc, tag),
+        None => tag.to_string(),
+    })
 }
 
 /// Install a thread-local allowlist of output field names.
@@ -58,6 +129,8 @@ const LIST_SUMMARY_FIELDS: &[&str] = &[
     "status",
     "priority",
     "kind",
+    "severity",
+    "component",
     "urgency",
     "is_blocked",
     "blocked_by",
@@ -70,6 +143,10 @@ const LIST_SUMMARY_FIELDS: &[&str] = &[
     "parent_id",
     "close_reason",
     "assigned_to",
+    "branch",
+    "project",
+    "affects_version",
+    "fixed_in_version",
     "created_at",
     "updated_at",
 ];
@@ -136,14 +213,76 @@ pub fn println_json(json_str: &str) {
         if let Some(ref fields) = *filter {
             if let Ok(value) = serde_json::from_str::<serde_json::Value>(json_str) {
                 let filtered = filter_json_fields(value, fields);
-                println!("{}", filtered);
+                print_json(&filtered.to_string());
                 return;
             }
         }
-        println!("{}", json_str);
+        print_json(json_str);
     });
 }
 
+/// Version of the JSON envelope's `data` contracts. Bumped when a command's
+/// JSON shape changes incompatibly; adding fields does not bump it.
+pub const API_VERSION: u32 = 1;
+
+/// Turn off the JSON envelope for this thread (`--raw`): JSON responses are
+/// printed bare, as before the envelope existed.
+pub fn set_raw(on: bool) {
+    RAW.with(|r| r.set(on));
+}
+
+/// Wrap a JSON response as `{"api_version":1,"ok":true,"data":<json>}`, or
+/// return it unchanged under `--raw`. `json` must already be valid JSON.
+pub fn envelope(json: &str) -> String {
+    if RAW.with(Cell::get) {
+        return json.to_string();
+    }
+    format!(
+        "{{\"api_version\":{},\"ok\":true,\"data\":{}}}",
+        API_VERSION,
+        json.trim_end()
+    )
+}
+
+/// The stderr form of a JSON error object: `api_version` and `"ok": false`
+/// ahead of its own keys, or the object unchanged under `--raw`.
+pub fn error_envelope(error: serde_json::Value) -> serde_json::Value {
+    if RAW.with(Cell::get) {
+        return error;
+    }
+    let mut out = serde_json::json!({ "api_version": API_VERSION, "ok": false });
+    if let (Some(out), serde_json::Value::Object(fields)) = (out.as_object_mut(), error) {
+        out.extend(fields);
+    }
+    out
+}
+
+/// Print one JSON response to stdout in its envelope. Unlike
+/// [`println_json`], no `--fields` filter is applied.
+pub fn print_json(json: &str) {
+    println!("{}", envelope(json));
+}
+
+/// One line of a stream (`events`, `watch`): each JSON line is its own
+/// response, so it gets its own envelope.
+pub fn stream_line(line: String, fmt: Format) -> String {
+    if fmt.is_json() {
+        envelope(&line)
+    } else {
+        line
+    }
+}
+
+/// Print the output of a `format_*` function: enveloped when `fmt` is JSON,
+/// verbatim otherwise.
+pub fn print_output(output: &str, fmt: Format) {
+    if fmt.is_json() {
+        print_json(output);
+    } else {
+        println!("{}", output);
+    }
+}
+
 /// Output mode selected by `--format` on every CLI subcommand.
 ///
 /// - `Compact` (default) — token-efficient key/value lines for agent
@@ -305,6 +444,20 @@ pub fn format_issue_detail(detail: &IssueDetail, fmt: Format) -> String {
     }
 }
 
+/// Join dependency IDs for display, using the `project/ID` form for any
+/// that live in another project than the issue itself.
+fn detail_ids(d: &IssueDetail, ids: &[i64], sep: &str) -> String {
+    ids.iter()
+        .map(|id| {
+            d.qualified_ids
+                .get(id)
+                .cloned()
+                .unwrap_or_else(|| id.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
 /// Render a batch of issue details (`itr get 1,2,3`, #136) in the requested
 /// output mode.
 ///
@@ -356,32 +509,32 @@ fn format_issue_detail_compact(d: &IssueDetail) -> String {
     if on("kind") {
         first_parts.push(format!("KIND:{}", d.issue.kind));
     }
+    if on("severity") && !d.issue.severity.is_empty() {
+        first_parts.push(format!("SEVERITY:{}", d.issue.severity));
+    }
+    if on("component") && !d.issue.component.is_empty() {
+        first_parts.push(format!(
+            "COMPONENT:{}",
+            escape_line_value(&d.issue.component)
+        ));
+    }
     if on("urgency") {
         first_parts.push(format!("URGENCY:{:.1}", d.urgency));
     }
     if on("blocked_by") && !d.blocked_by.is_empty() {
-        first_parts.push(format!(
-            "BLOCKED_BY:{}",
-            d.blocked_by
-                .iter()
-                .map(std::string::ToString::to_string)
-                .collect::<Vec<_>>()
-                .join(",")
-        ));
+        first_parts.push(format!("BLOCKED_BY:{}", detail_ids(d, &d.blocked_by, ",")));
     }
     if on("blocks") && !d.blocks.is_empty() {
-        first_parts.push(format!(
-            "BLOCKS:{}",
-            d.blocks
-                .iter()
-                .map(std::string::ToString::to_string)
-                .collect::<Vec<_>>()
-                .join(",")
-        ));
+        first_parts.push(format!("BLOCKS:{}", detail_ids(d, &d.blocks, ",")));
     }
     if !first_parts.is_empty() {
         lines.push(first_parts.join(" "));
     }
+    if on("external_blockers") {
+        for b in &d.external_blockers {
+            lines.push(external_blocker_line(b));
+        }
+    }
 
     if on("tags") && !d.issue.tags.is_empty() {
         lines.push(format!(
@@ -424,12 +577,52 @@ fn format_issue_detail_compact(d: &IssueDetail) -> String {
             lines.push(format!("PARENT: {}", pid));
         }
     }
+    if on("progress") {
+        if let Some(ref p) = d.progress {
+            lines.push(format!("PROGRESS: {}", progress_compact(p)));
+        }
+    }
     if on("close_reason") && !d.issue.close_reason.is_empty() {
         lines.push(format!(
             "CLOSE_REASON: {}",
             escape_line_value(&d.issue.close_reason)
         ));
     }
+    if on("branch") && !d.issue.branch.is_empty() {
+        lines.push(format!("BRANCH: {}", escape_line_value(&d.issue.branch)));
+    }
+    if on("project") && !d.issue.project.is_empty() {
+        lines.push(format!("PROJECT: {}", escape_line_value(&d.issue.project)));
+    }
+    if on("affects_version") && !d.issue.affects_version.is_empty() {
+        lines.push(format!(
+            "AFFECTS: {}",
+            escape_line_value(&d.issue.affects_version)
+        ));
+    }
+    if on("fixed_in_version") && !d.issue.fixed_in_version.is_empty() {
+        lines.push(format!(
+            "FIXED_IN: {}",
+            escape_line_value(&d.issue.fixed_in_version)
+        ));
+    }
+    if on("key") && !d.key.is_empty() {
+        lines.push(format!("KEY: {}", d.key));
+    }
+    if on("commits") && d.commits > 0 {
+        lines.push(format!("COMMITS: {}", d.commits));
+    }
+    if on("estimate") {
+        if let Some(points) = d.issue.estimate {
+            lines.push(format!("ESTIMATE: {}", points));
+        }
+    }
+    if on("referenced_by") && !d.referenced_by.is_empty() {
+        lines.push(format!(
+            "REFERENCED_BY:{}",
+            detail_ids(d, &d.referenced_by, ",")
+        ));
+    }
     if on("created_at") {
         lines.push(format!("CREATED: {}", d.issue.created_at));
     }
@@ -437,6 +630,13 @@ fn format_issue_detail_compact(d: &IssueDetail) -> String {
         lines.push(format!("UPDATED: {}", d.issue.updated_at));
     }
 
+    if on("repro") {
+        if let Some(ref repro) = d.repro {
+            lines.push("--- REPRO ---".to_string());
+            lines.extend(repro_lines_compact(repro));
+        }
+    }
+
     if on("urgency_breakdown") {
         if let Some(ref breakdown) = d.urgency_breakdown {
             lines.push("--- URGENCY BREAKDOWN ---".to_string());
@@ -447,6 +647,9 @@ fn format_issue_detail_compact(d: &IssueDetail) -> String {
                 .map(|(k, v)| format!("{}={:.1}", k, v))
                 .collect();
             lines.push(parts.join(" "));
+            if let Some(ref why) = breakdown.skill_match {
+                lines.push(format!("SKILL_MATCH: {}", escape_line_value(why)));
+            }
         }
     }
 
@@ -457,26 +660,145 @@ fn format_issue_detail_compact(d: &IssueDetail) -> String {
         }
     }
 
-    if on("notes") && !d.notes.is_empty() {
-        lines.push("--- NOTES ---".to_string());
-        for note in &d.notes {
-            let agent_str = if note.agent.is_empty() {
-                String::new()
-            } else {
-                format!(" ({})", escape_line_value(&note.agent))
-            };
+    if on("blocked_by_issues") && !d.blocked_by_issues.is_empty() {
+        lines.push("--- BLOCKERS ---".to_string());
+        for b in &d.blocked_by_issues {
             lines.push(format!(
-                "[{}]{} {}",
-                note.created_at,
-                agent_str,
-                escape_line_value(&note.content)
+                "#{} STATUS:{} TITLE: {}",
+                b.id,
+                b.status,
+                escape_line_value(&b.title)
             ));
         }
     }
 
+    if on("notes") && !d.notes.is_empty() {
+        match d.notes_omitted {
+            Some(omitted) if omitted > 0 => lines.push(format!(
+                "--- NOTES (last {} of {}) ---",
+                d.notes.len(),
+                d.notes.len() + omitted
+            )),
+            _ => lines.push("--- NOTES ---".to_string()),
+        }
+        lines.extend(d.notes.iter().map(note_line_compact));
+    }
+
+    lines.join("\n")
+}
+
+/// Render `itr brief`.
+///
+/// - `Json` — the [`Brief`] object: `IssueDetail` fields plus `notes_total`,
+///   `parent`, `blockers`, and `related_files` (respects `--fields`).
+/// - `Compact`/`Oneline`/`Pretty` — the compact issue detail, then
+///   `--- PARENT ---`, `--- BLOCKERS ---`, `--- FILES ---`, and
+///   `--- NOTES (last k of n) ---` sections, each only when non-empty. A
+///   brief is meant for a prompt, so every text mode shares the one shape.
+pub fn format_brief(brief: &Brief, fmt: Format) -> String {
+    if fmt == Format::Json {
+        return apply_fields_filter(&serde_json::to_string(brief).unwrap_or_default());
+    }
+    let mut head = brief.detail.clone();
+    let notes = std::mem::take(&mut head.notes);
+    let mut lines = vec![format_issue_detail_compact(&head)];
+
+    if let Some(parent) = &brief.parent {
+        lines.push("--- PARENT ---".to_string());
+        lines.extend(brief_issue_lines(parent));
+    }
+    if !brief.blockers.is_empty() {
+        lines.push("--- BLOCKERS ---".to_string());
+        for blocker in &brief.blockers {
+            lines.extend(brief_issue_lines(blocker));
+        }
+    }
+    if !brief.related_files.is_empty() {
+        lines.push("--- FILES ---".to_string());
+        for file in &brief.related_files {
+            let mut line = format!("FILE: {}", escape_line_value(&file.path));
+            if !file.issue_ids.is_empty() {
+                line.push_str(&format!(" ALSO:{}", join_ids(&file.issue_ids)));
+            }
+            lines.push(line);
+        }
+    }
+    if !notes.is_empty() {
+        lines.push(format!(
+            "--- NOTES (last {} of {}) ---",
+            notes.len(),
+            brief.notes_total
+        ));
+        lines.extend(notes.iter().map(note_line_compact));
+    }
     lines.join("\n")
 }
 
+fn brief_issue_lines(issue: &BriefIssue) -> Vec<String> {
+    let mut head = format!(
+        "#{} STATUS:{} PRIORITY:{} KIND:{}",
+        issue.id, issue.status, issue.priority, issue.kind
+    );
+    if !issue.assigned_to.is_empty() {
+        head.push_str(&format!(
+            " ASSIGNED:{}",
+            escape_line_value(&issue.assigned_to)
+        ));
+    }
+    if let Some(p) = &issue.progress {
+        head.push_str(&format!(" PROGRESS:{}", progress_compact(p)));
+    }
+    head.push_str(&format!(" \"{}\"", escape_quoted_value(&issue.title)));
+    let mut lines = vec![head];
+    if !issue.context.is_empty() {
+        lines.push(format!("  CONTEXT: {}", escape_line_value(&issue.context)));
+    }
+    if !issue.acceptance.is_empty() {
+        lines.push(format!(
+            "  ACCEPTANCE: {}",
+            escape_line_value(&issue.acceptance)
+        ));
+    }
+    if let Some(note) = &issue.last_note {
+        lines.push(format!("  LAST_NOTE: {}", note_line_compact(note)));
+    }
+    lines
+}
+
+/// `[created_at] (agent) kind: content` — one note in compact detail output.
+fn note_line_compact(note: &Note) -> String {
+    let agent_str = if note.agent.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", escape_line_value(&note.agent))
+    };
+    format!(
+        "[{}]{} {}",
+        note.created_at,
+        agent_str,
+        escape_line_value(&note_text(note))
+    )
+}
+
+/// `closed/total (pct%)` — the compact rendering of a subtree rollup, shared
+/// by `get` detail and `itr tree`.
+/// `EXTERNAL:<id> "<description>"`, plus ` URL:<url>` when one was given.
+pub(crate) fn external_blocker_line(b: &ExternalBlocker) -> String {
+    let mut line = format!(
+        "EXTERNAL:{} \"{}\"",
+        b.id,
+        escape_quoted_value(&b.description)
+    );
+    if !b.url.is_empty() {
+        line.push_str(&format!(" URL:{}", escape_line_value(&b.url)));
+    }
+    line
+}
+
+fn progress_compact(p: &SubtreeProgress) -> String {
+    format!("{}/{} ({:.1}%)", p.closed(), p.total, p.percent_complete)
+}
+
 fn format_relation_compact(rel: &Relation, current_id: i64) -> String {
     if rel.source_id == current_id {
         format!(
@@ -498,8 +820,15 @@ fn format_issue_detail_pretty(d: &IssueDetail) -> String {
         "  Status: {}  Priority: {}  Kind: {}  Urgency: {:.1}",
         d.issue.status, d.issue.priority, d.issue.kind, d.urgency
     ));
+    if !d.issue.severity.is_empty() {
+        lines.push(format!("  Severity: {}", d.issue.severity));
+    }
+    if !d.issue.component.is_empty() {
+        lines.push(format!("  Component: {}", d.issue.component));
+    }
     if !d.issue.tags.is_empty() {
-        lines.push(format!("  Tags: {}", d.issue.tags.join(", ")));
+        let tags: Vec<String> = d.issue.tags.iter().map(|t| paint_tag(t)).collect();
+        lines.push(format!("  Tags: {}", tags.join(", ")));
     }
     if !d.issue.files.is_empty() {
         lines.push(format!("  Files: {}", d.issue.files.join(", ")));
@@ -510,30 +839,83 @@ fn format_issue_detail_pretty(d: &IssueDetail) -> String {
     if !d.issue.assigned_to.is_empty() {
         lines.push(format!("  Assigned to: {}", d.issue.assigned_to));
     }
+    if !d.issue.branch.is_empty() {
+        lines.push(format!("  Branch: {}", d.issue.branch));
+    }
+    if !d.issue.project.is_empty() {
+        lines.push(format!("  Project: {}", d.issue.project));
+    }
+    if !d.issue.affects_version.is_empty() {
+        lines.push(format!("  Affects version: {}", d.issue.affects_version));
+    }
+    if !d.issue.fixed_in_version.is_empty() {
+        lines.push(format!("  Fixed in: {}", d.issue.fixed_in_version));
+    }
+    if !d.key.is_empty() {
+        lines.push(format!("  Key: {}", d.key));
+    }
+    if d.commits > 0 {
+        lines.push(format!("  Commits: {}", d.commits));
+    }
+    if let Some(points) = d.issue.estimate {
+        lines.push(format!("  Estimate: {} pts", points));
+    }
     if !d.issue.context.is_empty() {
         lines.push(format!("  Context: {}", d.issue.context));
     }
     if !d.issue.acceptance.is_empty() {
         lines.push(format!("  Acceptance: {}", d.issue.acceptance));
     }
-    if !d.blocked_by.is_empty() {
+    if let Some(ref repro) = d.repro {
+        lines.push("  Reproduction:".to_string());
+        lines.extend(repro_lines_pretty(repro, "    "));
+    }
+    if let Some(ref p) = d.progress {
+        lines.push(format!(
+            "  Progress: {}/{} closed ({:.1}%), {} open, {} in progress{}",
+            p.closed(),
+            p.total,
+            p.percent_complete,
+            p.open,
+            p.in_progress,
+            if p.review > 0 {
+                format!(", {} in review", p.review)
+            } else {
+                String::new()
+            }
+        ));
+    }
+    if !d.blocked_by_issues.is_empty() {
+        let blockers: Vec<String> = d
+            .blocked_by_issues
+            .iter()
+            .map(|b| format!("#{} {} ({})", b.id, b.title, b.status))
+            .collect();
+        lines.push(format!("  Blocked by: {}", blockers.join(", ")));
+    } else if !d.blocked_by.is_empty() {
         lines.push(format!(
             "  Blocked by: {}",
-            d.blocked_by
-                .iter()
-                .map(std::string::ToString::to_string)
-                .collect::<Vec<_>>()
-                .join(", ")
+            detail_ids(d, &d.blocked_by, ", ")
+        ));
+    }
+    for b in &d.external_blockers {
+        let url = if b.url.is_empty() {
+            String::new()
+        } else {
+            format!(" <{}>", b.url)
+        };
+        lines.push(format!(
+            "  Blocked by external [{}]: {}{}",
+            b.id, b.description, url
         ));
     }
     if !d.blocks.is_empty() {
+        lines.push(format!("  Blocks: {}", detail_ids(d, &d.blocks, ", ")));
+    }
+    if !d.referenced_by.is_empty() {
         lines.push(format!(
-            "  Blocks: {}",
-            d.blocks
-                .iter()
-                .map(std::string::ToString::to_string)
-                .collect::<Vec<_>>()
-                .join(", ")
+            "  Referenced by: {}",
+            detail_ids(d, &d.referenced_by, ", ")
         ));
     }
     if !d.relations.is_empty() {
@@ -547,14 +929,31 @@ fn format_issue_detail_pretty(d: &IssueDetail) -> String {
         }
     }
     if !d.notes.is_empty() {
-        lines.push("  Notes:".to_string());
+        match d.notes_omitted {
+            Some(omitted) if omitted > 0 => lines.push(format!(
+                "  Notes (last {} of {}):",
+                d.notes.len(),
+                d.notes.len() + omitted
+            )),
+            _ => lines.push("  Notes:".to_string()),
+        }
         for note in &d.notes {
-            lines.push(format!("    [{}] {}", note.created_at, note.content));
+            lines.push(format!("    [{}] {}", note.created_at, note_text(note)));
         }
     }
     lines.join("\n")
 }
 
+/// A note's text as displayed: typed notes lead with their kind, as in
+/// `handoff: tests pass, docs left`.
+pub fn note_text(note: &Note) -> String {
+    if note.kind.is_empty() {
+        note.content.clone()
+    } else {
+        format!("{}: {}", note.kind, note.content)
+    }
+}
+
 // --- Issue Summary List ---
 
 /// Render a list of issue summaries in the requested output mode.
@@ -615,6 +1014,10 @@ fn oneline_field_value(i: &IssueSummary, field: &str) -> String {
         "parent_id" => i.parent_id.map(|p| p.to_string()).unwrap_or_default(),
         "close_reason" => escape_line_value(&i.close_reason),
         "assigned_to" => escape_line_value(&i.assigned_to),
+        "branch" => escape_line_value(&i.branch),
+        "project" => escape_line_value(&i.project),
+        "affects_version" => escape_line_value(&i.affects_version),
+        "fixed_in_version" => escape_line_value(&i.fixed_in_version),
         "created_at" => i.created_at.clone(),
         "updated_at" => i.updated_at.clone(),
         _ => String::new(),
@@ -666,8 +1069,16 @@ fn format_issue_list_oneline(issues: &[IssueSummary]) -> String {
 /// own line. These are the *token-efficient default* — the full renderable set
 /// is larger (see `COMPACT_FIRST_LINE_CAPABLE`/`COMPACT_LINE_CAPABLE`), so
 /// `--fields` can surface flat fields that the default omits for brevity.
-const COMPACT_FIRST_LINE_DEFAULT: &[&str] =
-    &["id", "status", "priority", "kind", "urgency", "blocked_by"];
+const COMPACT_FIRST_LINE_DEFAULT: &[&str] = &[
+    "id",
+    "status",
+    "priority",
+    "kind",
+    "severity",
+    "component",
+    "urgency",
+    "blocked_by",
+];
 const COMPACT_LINE_DEFAULT: &[&str] = &[
     "tags",
     "files",
@@ -685,6 +1096,8 @@ const COMPACT_FIRST_LINE_CAPABLE: &[&str] = &[
     "status",
     "priority",
     "kind",
+    "severity",
+    "component",
     "urgency",
     "blocked_by",
     "blocks",
@@ -699,6 +1112,10 @@ const COMPACT_LINE_CAPABLE: &[&str] = &[
     "acceptance",
     "parent_id",
     "close_reason",
+    "branch",
+    "project",
+    "affects_version",
+    "fixed_in_version",
     "created_at",
     "updated_at",
 ];
@@ -734,6 +1151,12 @@ fn format_issue_list_compact(issues: &[IssueSummary]) -> String {
                     "status" => first_parts.push(format!("STATUS:{}", i.status)),
                     "priority" => first_parts.push(format!("PRIORITY:{}", i.priority)),
                     "kind" => first_parts.push(format!("KIND:{}", i.kind)),
+                    "severity" if !i.severity.is_empty() => {
+                        first_parts.push(format!("SEVERITY:{}", i.severity));
+                    }
+                    "component" if !i.component.is_empty() => {
+                        first_parts.push(format!("COMPONENT:{}", escape_line_value(&i.component)));
+                    }
                     "urgency" => first_parts.push(format!("URGENCY:{:.1}", i.urgency)),
                     "blocked_by" if !i.blocked_by.is_empty() => first_parts.push(format!(
                         "BLOCKED_BY:{}",
@@ -791,6 +1214,24 @@ fn format_issue_list_compact(issues: &[IssueSummary]) -> String {
                             escape_line_value(&i.close_reason)
                         ));
                     }
+                    "branch" if !i.branch.is_empty() => {
+                        lines.push(format!("BRANCH: {}", escape_line_value(&i.branch)));
+                    }
+                    "project" if !i.project.is_empty() => {
+                        lines.push(format!("PROJECT: {}", escape_line_value(&i.project)));
+                    }
+                    "affects_version" if !i.affects_version.is_empty() => {
+                        lines.push(format!(
+                            "AFFECTS: {}",
+                            escape_line_value(&i.affects_version)
+                        ));
+                    }
+                    "fixed_in_version" if !i.fixed_in_version.is_empty() => {
+                        lines.push(format!(
+                            "FIXED_IN: {}",
+                            escape_line_value(&i.fixed_in_version)
+                        ));
+                    }
                     "created_at" => lines.push(format!("CREATED: {}", i.created_at)),
                     "updated_at" => lines.push(format!("UPDATED: {}", i.updated_at)),
                     _ => {}
@@ -824,6 +1265,10 @@ const PRETTY_LIST_COLS: &[(&str, &str, usize, bool)] = &[
     ("parent_id", "Parent", 6, true),
     ("blocks", "Blocks", 8, false),
     ("close_reason", "Close Reason", 20, false),
+    ("branch", "Branch", 24, false),
+    ("project", "Project", 16, false),
+    ("affects_version", "Affects", 10, false),
+    ("fixed_in_version", "Fixed In", 10, false),
     ("created_at", "Created", 20, false),
     ("updated_at", "Updated", 20, false),
 ];
@@ -917,6 +1362,10 @@ fn format_issue_list_pretty(issues: &[IssueSummary]) -> String {
                         .collect::<Vec<_>>()
                         .join(", "),
                     "close_reason" => truncate_with_ellipsis(&i.close_reason, 20),
+                    "branch" => truncate_with_ellipsis(&i.branch, 24),
+                    "project" => truncate_with_ellipsis(&i.project, 16),
+                    "affects_version" => truncate_with_ellipsis(&i.affects_version, 10),
+                    "fixed_in_version" => truncate_with_ellipsis(&i.fixed_in_version, 10),
                     "created_at" => i.created_at.clone(),
                     "updated_at" => i.updated_at.clone(),
                     _ => String::new(),
@@ -979,6 +1428,7 @@ fn stats_to_deterministic_json(stats: &Stats) -> String {
         avg_urgency,
         by_skills,
         by_assignee,
+        by_component,
         oldest_open,
     } = stats;
 
@@ -1012,6 +1462,7 @@ fn stats_to_deterministic_json(stats: &Stats) -> String {
     obj.insert("avg_urgency".to_string(), round_urgency_value(*avg_urgency));
     obj.insert("blocked".to_string(), Value::from(*blocked));
     obj.insert("by_assignee".to_string(), ordered_map(by_assignee));
+    obj.insert("by_component".to_string(), ordered_map(by_component));
     obj.insert("by_kind".to_string(), ordered_map(by_kind));
     obj.insert("by_priority".to_string(), ordered_map(by_priority));
     obj.insert("by_skills".to_string(), ordered_map(by_skills));
@@ -1027,9 +1478,10 @@ fn format_stats_compact(stats: &Stats) -> String {
     let mut lines = Vec::new();
     lines.push(format!("TOTAL:{}", stats.total));
     lines.push(format!(
-        "BY_STATUS: open={} in-progress={} done={} wontfix={}",
+        "BY_STATUS: open={} in-progress={} review={} done={} wontfix={}",
         stats.by_status.get("open").unwrap_or(&0),
         stats.by_status.get("in-progress").unwrap_or(&0),
+        stats.by_status.get("review").unwrap_or(&0),
         stats.by_status.get("done").unwrap_or(&0),
         stats.by_status.get("wontfix").unwrap_or(&0),
     ));
@@ -1067,6 +1519,15 @@ fn format_stats_compact(stats: &Stats) -> String {
             .collect();
         lines.push(format!("BY_ASSIGNEE: {}", parts.join(" ")));
     }
+    if !stats.by_component.is_empty() {
+        let mut pairs: Vec<(&String, &i64)> = stats.by_component.iter().collect();
+        pairs.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let parts: Vec<String> = pairs
+            .iter()
+            .map(|(k, v)| format!("{}={}", escape_line_value(k), v))
+            .collect();
+        lines.push(format!("BY_COMPONENT: {}", parts.join(" ")));
+    }
     if let Some(ref oldest) = stats.oldest_open {
         lines.push(format!(
             "OLDEST_OPEN: ID:{} DAYS:{} \"{}\"",
@@ -1075,133 +1536,1295 @@ fn format_stats_compact(stats: &Stats) -> String {
             escape_quoted_value(&oldest.title)
         ));
     }
-    lines.join("\n")
+    lines.join("\n")
+}
+
+// --- Graph ---
+
+/// Render a dependency / blocker graph.
+///
+/// `Pretty` and `Oneline` both emit Graphviz DOT (`digraph itr { ... }`);
+/// `Compact` emits one `NODE:…` / `EDGE:…` line per element; `Json` serializes
+/// the raw [`GraphOutput`] struct.
+///
+/// # Examples
+///
+/// ```text
+/// use itr::format::{format_graph, Format};
+/// use itr::models::GraphOutput;
+/// let empty = GraphOutput { nodes: vec![], edges: vec![] };
+/// assert!(format_graph(&empty, Format::Pretty).contains("digraph itr"));
+/// ```
+pub fn format_graph(graph: &GraphOutput, fmt: Format) -> String {
+    match fmt {
+        Format::Json => apply_fields_filter(&graph_to_deterministic_json(graph)),
+        Format::Compact => {
+            warn_fields_unsupported("graph compact output");
+            format_graph_compact(graph)
+        }
+        Format::Pretty | Format::Oneline => {
+            warn_fields_unsupported("graph DOT output");
+            format_graph_dot(graph)
+        }
+    }
+}
+
+/// Serialize [`GraphOutput`] to JSON with a deterministic urgency-precision
+/// contract.
+///
+/// Node urgency is an `f64` computed fresh from current state, so values like
+/// `9.00019212962963` leak into JSON and make byte-level snapshots flap on
+/// runs that differ only in float noise (issue #139). This rounds each node's
+/// urgency to [`URGENCY_JSON_DECIMALS`] decimal places at the serialization
+/// boundary — the ranking math is untouched, only the rendered precision is
+/// pinned. See `docs/command-contracts.md` for the documented contract.
+///
+/// The rounding happens on a clone of the struct so the serde-derived
+/// `Serialize` impl emits fields in declaration order (`nodes` before
+/// `edges`; node keys `id`, `title`, `status`, `urgency`, `is_blocked`).
+/// Round-tripping through `serde_json::Value` instead would re-sort every
+/// object key alphabetically (the default `Map` is a `BTreeMap`) — an
+/// undocumented whole-document reorder (issue #179).
+fn graph_to_deterministic_json(graph: &GraphOutput) -> String {
+    let mut rounded = graph.clone();
+    for node in &mut rounded.nodes {
+        node.urgency = round_urgency(node.urgency);
+    }
+    serde_json::to_string(&rounded).unwrap_or_default()
+}
+
+/// Number of decimal places urgency is rounded to in JSON output. Keeps
+/// parseable formats byte-stable without affecting urgency ranking.
+const URGENCY_JSON_DECIMALS: i32 = 4;
+
+/// Round an urgency `f64` to the JSON precision contract. NaN/Inf (which
+/// `serde_json` cannot represent) fall back to `0.0`.
+fn round_urgency(urgency: f64) -> f64 {
+    let factor = 10f64.powi(URGENCY_JSON_DECIMALS);
+    let rounded = (urgency * factor).round() / factor;
+    if rounded.is_finite() {
+        rounded
+    } else {
+        0.0
+    }
+}
+
+/// [`round_urgency`] wrapped as a JSON number `Value`. Integral results (e.g.
+/// `9.0`) serialize as `9.0` because the source value is always a float;
+/// callers that need it embedded in an object should insert the returned
+/// `Value` directly.
+fn round_urgency_value(urgency: f64) -> serde_json::Value {
+    serde_json::Number::from_f64(round_urgency(urgency))
+        .map_or_else(|| serde_json::Value::from(0.0), serde_json::Value::Number)
+}
+
+/// Node IDs keyed to their display form: `project/ID` for issues in a
+/// project, so cross-project edges read unambiguously.
+fn graph_labels(graph: &GraphOutput) -> HashMap<i64, String> {
+    graph
+        .nodes
+        .iter()
+        .map(|n| (n.id, crate::util::qualified_id(&n.project, n.id)))
+        .collect()
+}
+
+fn format_graph_compact(graph: &GraphOutput) -> String {
+    let labels = graph_labels(graph);
+    let label = |id: i64| labels.get(&id).cloned().unwrap_or_else(|| id.to_string());
+    let mut lines = Vec::new();
+    for node in &graph.nodes {
+        let blocked = if node.is_blocked { " [BLOCKED]" } else { "" };
+        lines.push(format!(
+            "NODE:{} STATUS:{} URGENCY:{:.1}{} \"{}\"",
+            label(node.id),
+            node.status,
+            node.urgency,
+            blocked,
+            escape_quoted_value(&node.title)
+        ));
+    }
+    for edge in &graph.edges {
+        lines.push(format!(
+            "EDGE: {} -> {} ({})",
+            label(edge.from),
+            label(edge.to),
+            edge.edge_type
+        ));
+    }
+    lines.join("\n")
+}
+
+fn format_graph_dot(graph: &GraphOutput) -> String {
+    let mut lines = Vec::new();
+    lines.push("digraph itr {".to_string());
+    lines.push("  rankdir=LR;".to_string());
+    for node in &graph.nodes {
+        // Truncate first, then escape, so escape sequences are never cut in
+        // half by the truncation (issue #176).
+        let title_short = escape_dot_label(&truncate_with_ellipsis(&node.title, 30));
+        let style = if node.is_blocked {
+            " style=filled fillcolor=gray"
+        } else {
+            ""
+        };
+        lines.push(format!(
+            "  {} [label=\"{}: {}\" shape=box{}]",
+            node.id,
+            escape_dot_label(&crate::util::qualified_id(&node.project, node.id)),
+            title_short,
+            style
+        ));
+    }
+    for edge in &graph.edges {
+        lines.push(format!("  {} -> {}", edge.from, edge.to));
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+/// Render the graph as layered text for a terminal (`itr graph --ascii`).
+///
+/// Issues are grouped into layers by the longest chain of `blocks` edges
+/// leading to them, so layer 0 holds the issues nothing in view blocks. Each
+/// issue lists its outgoing edges with box-drawing connectors: `─▶` for
+/// `blocks`, `─<type>─▶` for relations. Issues left over by a cycle (which
+/// `depend` prevents) go in one last layer.
+pub fn format_graph_ascii(graph: &GraphOutput) -> String {
+    warn_fields_unsupported("graph ascii output");
+    let labels = graph_labels(graph);
+    let label = |id: i64| {
+        format!(
+            "#{}",
+            labels.get(&id).cloned().unwrap_or_else(|| id.to_string())
+        )
+    };
+    let titles: HashMap<i64, &str> = graph
+        .nodes
+        .iter()
+        .map(|n| (n.id, n.title.as_str()))
+        .collect();
+
+    // Kahn's algorithm over `blocks` edges, tracking each node's longest path.
+    let mut indegree: HashMap<i64, usize> = graph.nodes.iter().map(|n| (n.id, 0)).collect();
+    let mut blocks: HashMap<i64, Vec<i64>> = HashMap::new();
+    for edge in graph.edges.iter().filter(|e| e.edge_type == "blocks") {
+        *indegree.entry(edge.to).or_default() += 1;
+        blocks.entry(edge.from).or_default().push(edge.to);
+    }
+    let mut layer: HashMap<i64, usize> = HashMap::new();
+    let mut ready: Vec<i64> = graph
+        .nodes
+        .iter()
+        .filter(|n| indegree[&n.id] == 0)
+        .map(|n| n.id)
+        .collect();
+    for id in &ready {
+        layer.insert(*id, 0);
+    }
+    while let Some(id) = ready.pop() {
+        let depth = layer[&id];
+        for &next in blocks.get(&id).into_iter().flatten() {
+            let entry = layer.entry(next).or_default();
+            *entry = (*entry).max(depth + 1);
+            let remaining = indegree.entry(next).or_default();
+            *remaining -= 1;
+            if *remaining == 0 {
+                ready.push(next);
+            }
+        }
+    }
+    let last = layer.values().max().map_or(0, |m| m + 1);
+    let mut layers: Vec<Vec<&GraphNode>> = Vec::new();
+    for node in &graph.nodes {
+        let depth = if indegree[&node.id] == 0 {
+            layer[&node.id]
+        } else {
+            last
+        };
+        if layers.len() <= depth {
+            layers.resize_with(depth + 1, Vec::new);
+        }
+        layers[depth].push(node);
+    }
+
+    let mut lines = Vec::new();
+    for (depth, nodes) in layers.iter().enumerate().filter(|(_, n)| !n.is_empty()) {
+        lines.push(format!("\u{2500}\u{2500} layer {} \u{2500}\u{2500}", depth));
+        for node in nodes {
+            let blocked = if node.is_blocked { ", blocked" } else { "" };
+            lines.push(format!(
+                "{} {} ({}{})",
+                label(node.id),
+                one_line(&node.title),
+                node.status,
+                blocked
+            ));
+            let outgoing: Vec<_> = graph.edges.iter().filter(|e| e.from == node.id).collect();
+            for (i, edge) in outgoing.iter().enumerate() {
+                let branch = if i + 1 == outgoing.len() {
+                    "\u{2514}"
+                } else {
+                    "\u{251c}"
+                };
+                let arrow = if edge.edge_type == "blocks" {
+                    "\u{2500}\u{25b6}".to_string()
+                } else {
+                    format!("\u{2500}{}\u{2500}\u{25b6}", edge.edge_type)
+                };
+                lines.push(format!(
+                    "{}{} {} {}",
+                    branch,
+                    arrow,
+                    label(edge.to),
+                    titles.get(&edge.to).map_or(String::new(), |t| one_line(t))
+                ));
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// `s` with every run of whitespace, newlines included, collapsed to one space.
+fn one_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// --- Urgency reports ---
+
+/// Render `itr urgency`.
+///
+/// - `Json` — the [`UrgencyReport`] array (respects `--fields`).
+/// - `Compact`/`Oneline` — `ID:<n> URGENCY:<score> <component>=<value>...
+///   "<title>"` per issue; zero-valued components are omitted as in `get`.
+/// - `Pretty` — a header line per issue with its components aligned below.
+pub fn format_urgency_reports(reports: &[UrgencyReport], fmt: Format) -> String {
+    let nonzero = |r: &UrgencyReport| -> Vec<(String, f64)> {
+        r.urgency_breakdown
+            .components
+            .iter()
+            .filter(|(_, v)| *v != 0.0)
+            .cloned()
+            .collect()
+    };
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(reports).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("urgency compact output");
+            reports
+                .iter()
+                .map(|r| {
+                    let mut line = format!("ID:{} URGENCY:{:.1}", r.id, r.urgency);
+                    for (k, v) in nonzero(r) {
+                        line.push_str(&format!(" {}={:.1}", k, v));
+                    }
+                    line.push_str(&format!(" \"{}\"", escape_quoted_value(&r.title)));
+                    line
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("urgency pretty output");
+            let mut lines = Vec::new();
+            for r in reports {
+                lines.push(format!(
+                    "#{} {:.1}  {}  [{} {} {}]",
+                    r.id, r.urgency, r.title, r.status, r.priority, r.kind
+                ));
+                let components = nonzero(r);
+                let width = components
+                    .iter()
+                    .map(|(k, _)| display_width(k))
+                    .max()
+                    .unwrap_or(0);
+                for (k, v) in components {
+                    lines.push(format!("    {}  {:+6.1}", pad_display(&k, width, false), v));
+                }
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+// --- Tags ---
+
+/// Render `itr tag list`.
+///
+/// - `Json` — the [`TagCount`] array (respects `--fields`).
+/// - `Compact`/`Oneline` — `OPEN:<n> CLOSED:<n> "<tag>"` per tag, with
+///   `COLOR:` / `DESCRIPTION:"..."` appended when set.
+/// - `Pretty` — an aligned `Tag  Open  Closed` table, tags in their colors,
+///   plus a `Description` column when any tag has one.
+pub fn format_tag_counts(counts: &[TagCount], fmt: Format) -> String {
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(counts).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("tag list compact output");
+            counts
+                .iter()
+                .map(|t| {
+                    let mut line = format!(
+                        "OPEN:{} CLOSED:{} \"{}\"",
+                        t.open,
+                        t.closed,
+                        escape_quoted_value(&t.tag)
+                    );
+                    if !t.color.is_empty() {
+                        line.push_str(&format!(" COLOR:{}", t.color));
+                    }
+                    if !t.description.is_empty() {
+                        line.push_str(&format!(
+                            " DESCRIPTION:\"{}\"",
+                            escape_quoted_value(&t.description)
+                        ));
+                    }
+                    line
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("tag list pretty output");
+            let tag_width = counts
+                .iter()
+                .map(|t| display_width(&t.tag))
+                .max()
+                .unwrap_or(0)
+                .max(3);
+            let described = counts.iter().any(|t| !t.description.is_empty());
+            let mut header = format!(
+                "{}  {}  {}",
+                pad_display("Tag", tag_width, false),
+                pad_display("Open", 4, true),
+                pad_display("Closed", 6, true)
+            );
+            if described {
+                header.push_str("  Description");
+            }
+            let mut lines = vec![header];
+            for t in counts {
+                // Pad on the raw tag so escape codes don't skew alignment.
+                let pad = " ".repeat(tag_width.saturating_sub(display_width(&t.tag)));
+                let mut line = format!(
+                    "{}{}  {}  {}",
+                    paint_tag(&t.tag),
+                    pad,
+                    pad_display(&t.open.to_string(), 4, true),
+                    pad_display(&t.closed.to_string(), 6, true)
+                );
+                if described {
+                    line.push_str(&format!("  {}", t.description));
+                }
+                lines.push(line.trim_end().to_string());
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+// --- Projects ---
+
+/// Render `itr project list`.
+///
+/// - `Json` — the [`ProjectCount`] array (respects `--fields`).
+/// - `Compact`/`Oneline` — `OPEN:<n> CLOSED:<n> "<name>"` per project, with
+///   `CURRENT` and `DESCRIPTION:"..."` appended when they apply.
+/// - `Pretty` — an aligned `Project  Open  Closed` table with the current
+///   project starred.
+pub fn format_project_counts(counts: &[ProjectCount], fmt: Format) -> String {
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(counts).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("project list compact output");
+            counts
+                .iter()
+                .map(|p| {
+                    let mut line = format!(
+                        "OPEN:{} CLOSED:{} \"{}\"",
+                        p.open,
+                        p.closed,
+                        escape_quoted_value(&p.name)
+                    );
+                    if p.current {
+                        line.push_str(" CURRENT");
+                    }
+                    if !p.key_prefix.is_empty() {
+                        line.push_str(&format!(" KEY:{}", p.key_prefix));
+                    }
+                    if !p.description.is_empty() {
+                        line.push_str(&format!(
+                            " DESCRIPTION:\"{}\"",
+                            escape_quoted_value(&p.description)
+                        ));
+                    }
+                    line
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("project list pretty output");
+            let label = |p: &ProjectCount| {
+                if p.key_prefix.is_empty() {
+                    p.name.clone()
+                } else {
+                    format!("{} [{}]", p.name, p.key_prefix)
+                }
+            };
+            let name_width = counts
+                .iter()
+                .map(|p| display_width(&label(p)) + 2)
+                .max()
+                .unwrap_or(0)
+                .max(7);
+            let mut lines = vec![format!(
+                "{}  {}  {}  Description",
+                pad_display("Project", name_width, false),
+                pad_display("Open", 4, true),
+                pad_display("Closed", 6, true)
+            )
+            .trim_end()
+            .to_string()];
+            for p in counts {
+                let marker = if p.current { "* " } else { "  " };
+                let line = format!(
+                    "{}  {}  {}  {}",
+                    pad_display(&format!("{marker}{}", label(p)), name_width, false),
+                    pad_display(&p.open.to_string(), 4, true),
+                    pad_display(&p.closed.to_string(), 6, true),
+                    p.description
+                );
+                lines.push(line.trim_end().to_string());
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+// --- Components ---
+
+/// ` ASSIGNEE:ann TAGS:a,b` for a component's non-empty defaults.
+fn component_defaults_compact(c: &Component) -> String {
+    let mut out = String::new();
+    if !c.default_assignee.is_empty() {
+        out.push_str(&format!(
+            " ASSIGNEE:{}",
+            escape_line_value(&c.default_assignee)
+        ));
+    }
+    if !c.default_tags.is_empty() {
+        out.push_str(&format!(" TAGS:{}", c.default_tags.join(",")));
+    }
+    out
+}
+
+/// `COMPONENT:<name> [ASSIGNEE:<a>] [TAGS:<t,...>]`, printed by
+/// `itr component add`.
+pub(crate) fn component_line(c: &Component) -> String {
+    format!(
+        "COMPONENT:{}{}",
+        escape_line_value(&c.name),
+        component_defaults_compact(c)
+    )
+}
+
+/// Render `itr component list`.
+pub fn format_components(components: &[Component], fmt: Format) -> String {
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(components).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("component list compact output");
+            components
+                .iter()
+                .map(|c| {
+                    format!(
+                        "OPEN:{} CLOSED:{} \"{}\"{}",
+                        c.open,
+                        c.closed,
+                        escape_quoted_value(&c.name),
+                        component_defaults_compact(c)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("component list pretty output");
+            let name_width = components
+                .iter()
+                .map(|c| display_width(&c.name))
+                .max()
+                .unwrap_or(0)
+                .max(9);
+            let assignee_width = components
+                .iter()
+                .map(|c| display_width(&c.default_assignee))
+                .max()
+                .unwrap_or(0)
+                .max(8);
+            let mut lines = vec![format!(
+                "{}  {}  {}  {}  Tags",
+                pad_display("Component", name_width, false),
+                pad_display("Open", 4, true),
+                pad_display("Closed", 6, true),
+                pad_display("Assignee", assignee_width, false)
+            )];
+            for c in components {
+                let line = format!(
+                    "{}  {}  {}  {}  {}",
+                    pad_display(&c.name, name_width, false),
+                    pad_display(&c.open.to_string(), 4, true),
+                    pad_display(&c.closed.to_string(), 6, true),
+                    pad_display(&c.default_assignee, assignee_width, false),
+                    c.default_tags.join(", ")
+                );
+                lines.push(line.trim_end().to_string());
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+// --- Agents ---
+
+fn join_ids(ids: &[i64]) -> String {
+    ids.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Render `itr agent list`.
+///
+/// - `Json` — the [`Agent`] array (respects `--fields`).
+/// - `Compact`/`Oneline` — `ACTIVE:<n> CLOSED:<n> SEEN:<time> "<name>"` per
+///   agent, with `SKILLS:a,b` appended when it has any.
+/// - `Pretty` — an aligned `Agent  Active  Closed  Last seen  Skills` table.
+pub fn format_agents(agents: &[Agent], fmt: Format) -> String {
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(agents).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("agent list compact output");
+            agents
+                .iter()
+                .map(|a| {
+                    let mut line = format!(
+                        "ACTIVE:{} CLOSED:{} SEEN:{} \"{}\"",
+                        a.assigned.len(),
+                        a.closed,
+                        a.last_seen_at,
+                        escape_quoted_value(&a.name)
+                    );
+                    if !a.skills.is_empty() {
+                        line.push_str(&format!(" SKILLS:{}", a.skills.join(",")));
+                    }
+                    line
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("agent list pretty output");
+            let name_width = agents
+                .iter()
+                .map(|a| display_width(&a.name))
+                .max()
+                .unwrap_or(0)
+                .max(5);
+            let mut lines = vec![format!(
+                "{}  {}  {}  {}  Skills",
+                pad_display("Agent", name_width, false),
+                pad_display("Active", 6, true),
+                pad_display("Closed", 6, true),
+                pad_display("Last seen", 20, false)
+            )];
+            for a in agents {
+                let line = format!(
+                    "{}  {}  {}  {}  {}",
+                    pad_display(&a.name, name_width, false),
+                    pad_display(&a.assigned.len().to_string(), 6, true),
+                    pad_display(&a.closed.to_string(), 6, true),
+                    pad_display(&a.last_seen_at, 20, false),
+                    a.skills.join(", ")
+                );
+                lines.push(line.trim_end().to_string());
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+/// Render `itr agent show`.
+///
+/// - `Json` — the [`Agent`] object (respects `--fields`).
+/// - `Compact` — one `KEY: value` line per field, `ASSIGNED:` listing the
+///   active issue IDs.
+/// - `Oneline` — the `agent list` line for this agent.
+/// - `Pretty` — an indented block with the same fields.
+pub fn format_agent(agent: &Agent, fmt: Format) -> String {
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(agent).unwrap_or_default()),
+        Format::Oneline => format_agents(std::slice::from_ref(agent), fmt),
+        Format::Compact => {
+            warn_fields_unsupported("agent show compact output");
+            let mut lines = vec![format!("AGENT: {}", escape_line_value(&agent.name))];
+            if !agent.description.is_empty() {
+                lines.push(format!(
+                    "DESCRIPTION: {}",
+                    escape_line_value(&agent.description)
+                ));
+            }
+            lines.push(format!("SKILLS: {}", agent.skills.join(",")));
+            lines.push(format!("ASSIGNED: {}", join_ids(&agent.assigned)));
+            lines.push(format!("CLOSED: {}", agent.closed));
+            lines.push(format!("REGISTERED: {}", agent.registered_at));
+            lines.push(format!("LAST_SEEN: {}", agent.last_seen_at));
+            lines.join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("agent show pretty output");
+            let mut lines = vec![agent.name.clone()];
+            if !agent.description.is_empty() {
+                lines.push(format!("  {}", agent.description));
+            }
+            lines.push(format!("  Skills: {}", agent.skills.join(", ")));
+            let assigned: Vec<String> = agent.assigned.iter().map(|id| format!("#{id}")).collect();
+            lines.push(format!("  Assigned: {}", assigned.join(", ")));
+            lines.push(format!("  Closed: {}", agent.closed));
+            lines.push(format!("  Registered: {}", agent.registered_at));
+            lines.push(format!("  Last seen: {}", agent.last_seen_at));
+            lines.join("\n")
+        }
+    }
+}
+
+// --- Sessions ---
+
+/// Render `itr session list`, newest first — the standup view.
+///
+/// - `Json` — the [`Session`] array (respects `--fields`).
+/// - `Compact`/`Oneline` — `SESSION:<id> AGENT:<name> STARTED:<time>
+///   ENDED:<time|open> ISSUES:<id,...> "<summary>"` per session.
+/// - `Pretty` — a block per session: heading, touched issues, summary.
+pub fn format_sessions(sessions: &[Session], fmt: Format) -> String {
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(sessions).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("session compact output");
+            sessions
+                .iter()
+                .map(|s| {
+                    format!(
+                        "SESSION:{} AGENT:{} STARTED:{} ENDED:{} ISSUES:{} \"{}\"",
+                        s.id,
+                        escape_line_value(&s.agent),
+                        s.started_at,
+                        s.ended_at.as_deref().unwrap_or("open"),
+                        join_ids(&s.issues),
+                        escape_quoted_value(&s.summary)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("session pretty output");
+            sessions
+                .iter()
+                .map(|s| {
+                    let mut lines = vec![format!(
+                        "Session {} \u{2014} {} ({} \u{2192} {})",
+                        s.id,
+                        s.agent,
+                        s.started_at,
+                        s.ended_at.as_deref().unwrap_or("open")
+                    )];
+                    if !s.issues.is_empty() {
+                        let ids: Vec<String> = s.issues.iter().map(|id| format!("#{id}")).collect();
+                        lines.push(format!("  Issues: {}", ids.join(", ")));
+                    }
+                    if !s.summary.is_empty() {
+                        lines.push(format!("  {}", s.summary));
+                    }
+                    lines.join("\n")
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        }
+    }
+}
+
+/// Render one session for `itr session start`/`end`: the [`Session`] object
+/// in JSON, otherwise its [`format_sessions`] entry.
+pub fn format_session(session: &Session, fmt: Format) -> String {
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(session).unwrap_or_default()),
+        _ => format_sessions(std::slice::from_ref(session), fmt),
+    }
+}
+
+// --- Commits ---
+
+/// Render the commits linked to one issue for `itr commits`.
+///
+/// - `Json` — the [`IssueCommit`] array (respects `--fields`).
+/// - `Compact`/`Oneline` — `COMMIT:<short> RELATION:<mention|close>
+///   LINKED:<time> "<subject>"` per commit.
+/// - `Pretty` — `<short>  <subject> (<relation>, <time>)` per commit.
+pub fn format_commits(commits: &[IssueCommit], fmt: Format) -> String {
+    let short = |c: &IssueCommit| c.hash.chars().take(7).collect::<String>();
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(commits).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("commits compact output");
+            commits
+                .iter()
+                .map(|c| {
+                    format!(
+                        "COMMIT:{} RELATION:{} LINKED:{} \"{}\"",
+                        short(c),
+                        c.relation,
+                        c.linked_at,
+                        escape_quoted_value(&c.subject)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("commits pretty output");
+            commits
+                .iter()
+                .map(|c| {
+                    format!(
+                        "{}  {} ({}, {})",
+                        short(c),
+                        c.subject,
+                        c.relation,
+                        c.linked_at
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+// --- Activity ---
+
+/// Render `itr activity`: commits touching an issue's `total_files` paths
+/// since `since`, newest first.
+///
+/// - `Json` — the [`FileCommit`] array (respects `--fields`).
+/// - `Compact`/`Oneline` — `COMMIT:<short> DATE:<date> AUTHOR:<name>
+///   FILES:<path,...> "<subject>"` per commit, then `ACTIVITY:<id>
+///   SINCE:<since> COMMITS:<n> FILES:<touched>/<total>`.
+/// - `Pretty` — a heading, then date, short hash, author, and subject per
+///   commit with its paths indented below.
+pub fn format_activity(
+    id: i64,
+    since: &str,
+    commits: &[FileCommit],
+    total_files: usize,
+    fmt: Format,
+) -> String {
+    let short = |c: &FileCommit| c.hash.chars().take(7).collect::<String>();
+    let day = |c: &FileCommit| c.date.chars().take(10).collect::<String>();
+    let mut touched: Vec<&str> = commits
+        .iter()
+        .flat_map(|c| c.files.iter().map(String::as_str))
+        .collect();
+    touched.sort_unstable();
+    touched.dedup();
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(commits).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("activity compact output");
+            let mut lines: Vec<String> = commits
+                .iter()
+                .map(|c| {
+                    format!(
+                        "COMMIT:{} DATE:{} AUTHOR:{} FILES:{} \"{}\"",
+                        short(c),
+                        c.date,
+                        escape_line_value(&c.author),
+                        escape_line_value(&c.files.join(",")),
+                        escape_quoted_value(&c.subject)
+                    )
+                })
+                .collect();
+            lines.push(format!(
+                "ACTIVITY:{} SINCE:{} COMMITS:{} FILES:{}/{}",
+                id,
+                escape_line_value(since),
+                commits.len(),
+                touched.len(),
+                total_files
+            ));
+            lines.join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("activity pretty output");
+            let mut lines = vec![format!(
+                "{} commit(s) touched {} of {} file(s) of #{} since {}",
+                commits.len(),
+                touched.len(),
+                total_files,
+                id,
+                since
+            )];
+            for c in commits {
+                lines.push(format!(
+                    "  {}  {}  {}  {}",
+                    day(c),
+                    short(c),
+                    c.author,
+                    c.subject
+                ));
+                for f in &c.files {
+                    lines.push(format!("      {}", f));
+                }
+            }
+            lines.join("\n")
+        }
+    }
 }
 
-// --- Graph ---
+// --- Auto-assignment ---
 
-/// Render a dependency / blocker graph.
-///
-/// `Pretty` and `Oneline` both emit Graphviz DOT (`digraph itr { ... }`);
-/// `Compact` emits one `NODE:…` / `EDGE:…` line per element; `Json` serializes
-/// the raw [`GraphOutput`] struct.
+/// Render an `itr assign --auto` result.
 ///
-/// # Examples
+/// - `Json` — the [`AutoAssignPlan`] object (respects `--fields`).
+/// - `Compact`/`Oneline` — `ASSIGN:<id> AGENT:<name> WHY:"<reason>"
+///   "<title>"` and `SKIP:<id> WHY:"<reason>" "<title>"` lines, then an
+///   `AUTO_ASSIGN:` summary with each agent's load.
+/// - `Pretty` — the assignments grouped by agent, then the skipped issues.
+pub fn format_auto_assign(plan: &AutoAssignPlan, fmt: Format) -> String {
+    let loads = plan
+        .load
+        .iter()
+        .map(|(name, n)| format!("{}={}", escape_line_value(name), n))
+        .collect::<Vec<_>>()
+        .join(",");
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(plan).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("assign --auto compact output");
+            let mut lines: Vec<String> = plan
+                .assigned
+                .iter()
+                .map(|a| {
+                    format!(
+                        "ASSIGN:{} AGENT:{} WHY:\"{}\" \"{}\"",
+                        a.id,
+                        escape_line_value(&a.agent),
+                        escape_quoted_value(&a.reason),
+                        escape_quoted_value(&a.title)
+                    )
+                })
+                .collect();
+            lines.extend(plan.skipped.iter().map(|s| {
+                format!(
+                    "SKIP:{} WHY:\"{}\" \"{}\"",
+                    s.id,
+                    escape_quoted_value(&s.reason),
+                    escape_quoted_value(&s.title)
+                )
+            }));
+            lines.push(format!(
+                "AUTO_ASSIGN: {} assigned, {} skipped LOAD:{}",
+                plan.assigned.len(),
+                plan.skipped.len(),
+                loads
+            ));
+            lines.join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("assign --auto pretty output");
+            let mut lines = vec![format!(
+                "Assigned {} issue(s), skipped {}",
+                plan.assigned.len(),
+                plan.skipped.len()
+            )];
+            for (name, load) in &plan.load {
+                let mine: Vec<_> = plan.assigned.iter().filter(|a| &a.agent == name).collect();
+                if mine.is_empty() {
+                    continue;
+                }
+                lines.push(format!("  {} ({} active)", name, load));
+                for a in mine {
+                    lines.push(format!("    #{:<5} {}  ({})", a.id, a.title, a.reason));
+                }
+            }
+            if !plan.skipped.is_empty() {
+                lines.push("  Skipped".to_string());
+                for s in &plan.skipped {
+                    lines.push(format!("    #{:<5} {}  ({})", s.id, s.title, s.reason));
+                }
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+// --- Sprint plans ---
+
+/// Render an `itr plan-sprint` proposal.
 ///
-/// ```text
-/// use itr::format::{format_graph, Format};
-/// use itr::models::GraphOutput;
-/// let empty = GraphOutput { nodes: vec![], edges: vec![] };
-/// assert!(format_graph(&empty, Format::Pretty).contains("digraph itr"));
-/// ```
-pub fn format_graph(graph: &GraphOutput, fmt: Format) -> String {
+/// - `Json` — the [`SprintPlan`] object (respects `--fields`).
+/// - `Compact`/`Oneline` — `PLAN:<id> AGENT:<n> PTS:<p> [AFTER:<ids>]
+///   "<title>"` per issue, then a `SPRINT:` summary line.
+/// - `Pretty` — one section per agent with its points and issues.
+pub fn format_sprint_plan(plan: &SprintPlan, fmt: Format) -> String {
+    let points = |p: i64, estimated: bool| {
+        if estimated {
+            p.to_string()
+        } else {
+            format!("{}?", p)
+        }
+    };
+    let ids = |ids: &[i64]| {
+        ids.iter()
+            .map(std::string::ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let total = plan.capacity * i64::try_from(plan.agents).unwrap_or(i64::MAX);
     match fmt {
-        Format::Json => apply_fields_filter(&graph_to_deterministic_json(graph)),
-        Format::Compact => {
-            warn_fields_unsupported("graph compact output");
-            format_graph_compact(graph)
+        Format::Json => apply_fields_filter(&serde_json::to_string(plan).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("plan-sprint compact output");
+            let mut lines: Vec<String> = plan
+                .items
+                .iter()
+                .map(|i| {
+                    let after = if i.after.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" AFTER:{}", ids(&i.after))
+                    };
+                    format!(
+                        "PLAN:{} AGENT:{} PTS:{}{} \"{}\"",
+                        i.id,
+                        i.agent,
+                        points(i.points, i.estimated),
+                        after,
+                        escape_quoted_value(&i.title)
+                    )
+                })
+                .collect();
+            lines.push(format!(
+                "SPRINT: {} issue(s) {}/{} pts across {} agent(s), {} left out",
+                plan.items.len(),
+                plan.planned_points,
+                total,
+                plan.agents,
+                plan.left_out
+            ));
+            lines.join("\n")
         }
-        Format::Pretty | Format::Oneline => {
-            warn_fields_unsupported("graph DOT output");
-            format_graph_dot(graph)
+        Format::Pretty => {
+            warn_fields_unsupported("plan-sprint pretty output");
+            let mut lines = vec![format!(
+                "Sprint: {} issue(s), {}/{} pts across {} agent(s) ({} left out)",
+                plan.items.len(),
+                plan.planned_points,
+                total,
+                plan.agents,
+                plan.left_out
+            )];
+            for (slot, used) in plan.agent_points.iter().enumerate() {
+                lines.push(format!(
+                    "  Agent {}: {}/{} pts",
+                    slot + 1,
+                    used,
+                    plan.capacity
+                ));
+                for i in plan.items.iter().filter(|i| i.agent == slot + 1) {
+                    let after = if i.after.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            " (after #{})",
+                            i.after
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join(", #")
+                        )
+                    };
+                    lines.push(format!(
+                        "    #{:<5} {:>3} pts  {:<8} {}{}",
+                        i.id,
+                        points(i.points, i.estimated),
+                        i.priority,
+                        i.title,
+                        after
+                    ));
+                }
+            }
+            lines.join("\n")
         }
     }
 }
 
-/// Serialize [`GraphOutput`] to JSON with a deterministic urgency-precision
-/// contract.
+// --- File hotspots ---
+
+/// Render the `itr files` report.
 ///
-/// Node urgency is an `f64` computed fresh from current state, so values like
-/// `9.00019212962963` leak into JSON and make byte-level snapshots flap on
-/// runs that differ only in float noise (issue #139). This rounds each node's
-/// urgency to [`URGENCY_JSON_DECIMALS`] decimal places at the serialization
-/// boundary — the ranking math is untouched, only the rendered precision is
-/// pinned. See `docs/command-contracts.md` for the documented contract.
+/// - `Json` — the [`FileHotspot`] array (respects `--fields`).
+/// - `Compact`/`Oneline` — `COUNT:<n> IDS:<id,...> "<path>"` per path.
+/// - `Pretty` — an aligned `Issues  Path  IDs` table.
+pub fn format_file_hotspots(hotspots: &[FileHotspot], fmt: Format) -> String {
+    let ids = |h: &FileHotspot| {
+        h.issue_ids
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(hotspots).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("files compact output");
+            hotspots
+                .iter()
+                .map(|h| {
+                    format!(
+                        "COUNT:{} IDS:{} \"{}\"",
+                        h.count,
+                        ids(h),
+                        escape_quoted_value(&h.path)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("files pretty output");
+            let path_width = hotspots
+                .iter()
+                .map(|h| display_width(&h.path))
+                .max()
+                .unwrap_or(0)
+                .max(4);
+            let mut lines = vec![format!(
+                "{}  {}  IDs",
+                pad_display("Issues", 6, true),
+                pad_display("Path", path_width, false)
+            )];
+            for h in hotspots {
+                lines.push(format!(
+                    "{}  {}  {}",
+                    pad_display(&h.count.to_string(), 6, true),
+                    pad_display(&h.path, path_width, false),
+                    ids(h)
+                ));
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+/// Render `itr versions`: one row per affected version.
 ///
-/// The rounding happens on a clone of the struct so the serde-derived
-/// `Serialize` impl emits fields in declaration order (`nodes` before
-/// `edges`; node keys `id`, `title`, `status`, `urgency`, `is_blocked`).
-/// Round-tripping through `serde_json::Value` instead would re-sort every
-/// object key alphabetically (the default `Map` is a `BTreeMap`) — an
-/// undocumented whole-document reorder (issue #179).
-fn graph_to_deterministic_json(graph: &GraphOutput) -> String {
-    let mut rounded = graph.clone();
-    for node in &mut rounded.nodes {
-        node.urgency = round_urgency(node.urgency);
+/// - `Json` — the [`VersionReport`] array.
+/// - `Compact`/`Oneline` — `OPEN:n FIX_SCHEDULED:n IDS:a,b "version"` lines.
+/// - `Pretty` — an aligned table.
+pub fn format_versions(report: &[VersionReport], fmt: Format) -> String {
+    let ids = |r: &VersionReport| {
+        r.issue_ids
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(report).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("versions compact output");
+            report
+                .iter()
+                .map(|r| {
+                    format!(
+                        "OPEN:{} FIX_SCHEDULED:{} IDS:{} \"{}\"",
+                        r.open,
+                        r.fix_scheduled,
+                        ids(r),
+                        escape_quoted_value(&r.version)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("versions pretty output");
+            let version_width = report
+                .iter()
+                .map(|r| display_width(&r.version))
+                .max()
+                .unwrap_or(0)
+                .max(7);
+            let mut lines = vec![format!(
+                "{}  {}  {}  IDs",
+                pad_display("Version", version_width, false),
+                pad_display("Open", 4, true),
+                pad_display("Fix set", 7, true)
+            )];
+            for r in report {
+                lines.push(format!(
+                    "{}  {}  {}  {}",
+                    pad_display(&r.version, version_width, false),
+                    pad_display(&r.open.to_string(), 4, true),
+                    pad_display(&r.fix_scheduled.to_string(), 7, true),
+                    ids(r)
+                ));
+            }
+            lines.join("\n")
+        }
     }
-    serde_json::to_string(&rounded).unwrap_or_default()
 }
 
-/// Number of decimal places urgency is rounded to in JSON output. Keeps
-/// parseable formats byte-stable without affecting urgency ranking.
-const URGENCY_JSON_DECIMALS: i32 = 4;
+// --- Reproduction ---
 
-/// Round an urgency `f64` to the JSON precision contract. NaN/Inf (which
-/// `serde_json` cannot represent) fall back to `0.0`.
-fn round_urgency(urgency: f64) -> f64 {
-    let factor = 10f64.powi(URGENCY_JSON_DECIMALS);
-    let rounded = (urgency * factor).round() / factor;
-    if rounded.is_finite() {
-        rounded
-    } else {
-        0.0
+/// Render `itr repro <ID>`.
+///
+/// - `Json` — `{"id": …, "repro": {steps, expected, actual, environment}}`.
+/// - `Compact`/`Oneline` — `STEP n: …`, `EXPECTED: …`, `ACTUAL: …`, and
+///   `ENVIRONMENT: …` lines, each only when non-empty (the same lines as the
+///   `--- REPRO ---` section of the issue detail).
+/// - `Pretty` — a numbered step list followed by the labelled fields.
+pub fn format_repro(id: i64, repro: &Repro, fmt: Format) -> String {
+    match fmt {
+        Format::Json => {
+            apply_fields_filter(&serde_json::json!({ "id": id, "repro": repro }).to_string())
+        }
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("repro compact output");
+            repro_lines_compact(repro).join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("repro pretty output");
+            let mut lines = vec![format!("#{} reproduction:", id)];
+            lines.extend(repro_lines_pretty(repro, "  "));
+            lines.join("\n")
+        }
     }
 }
 
-/// [`round_urgency`] wrapped as a JSON number `Value`. Integral results (e.g.
-/// `9.0`) serialize as `9.0` because the source value is always a float;
-/// callers that need it embedded in an object should insert the returned
-/// `Value` directly.
-fn round_urgency_value(urgency: f64) -> serde_json::Value {
-    serde_json::Number::from_f64(round_urgency(urgency))
-        .map_or_else(|| serde_json::Value::from(0.0), serde_json::Value::Number)
+fn repro_lines_compact(repro: &Repro) -> Vec<String> {
+    let mut lines: Vec<String> = repro
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| format!("STEP {}: {}", i + 1, escape_line_value(step)))
+        .collect();
+    for (label, value) in [
+        ("EXPECTED", &repro.expected),
+        ("ACTUAL", &repro.actual),
+        ("ENVIRONMENT", &repro.environment),
+    ] {
+        if !value.is_empty() {
+            lines.push(format!("{}: {}", label, escape_line_value(value)));
+        }
+    }
+    lines
 }
 
-fn format_graph_compact(graph: &GraphOutput) -> String {
-    let mut lines = Vec::new();
-    for node in &graph.nodes {
-        let blocked = if node.is_blocked { " [BLOCKED]" } else { "" };
-        lines.push(format!(
-            "NODE:{} STATUS:{} URGENCY:{:.1}{} \"{}\"",
-            node.id,
-            node.status,
-            node.urgency,
-            blocked,
-            escape_quoted_value(&node.title)
-        ));
+fn repro_lines_pretty(repro: &Repro, indent: &str) -> Vec<String> {
+    let mut lines: Vec<String> = repro
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| format!("{}{}. {}", indent, i + 1, step))
+        .collect();
+    for (label, value) in [
+        ("Expected", &repro.expected),
+        ("Actual", &repro.actual),
+        ("Environment", &repro.environment),
+    ] {
+        if !value.is_empty() {
+            lines.push(format!("{}{}: {}", indent, label, value));
+        }
     }
-    for edge in &graph.edges {
-        lines.push(format!(
-            "EDGE: {} -> {} ({})",
-            edge.from, edge.to, edge.edge_type
-        ));
+    lines
+}
+
+// --- Tree ---
+
+/// Render the parent/child hierarchy (`itr tree`).
+///
+/// - `Json` — the nested [`TreeNode`] array as-is.
+/// - `Compact`/`Oneline` — one `ID:… KIND:… STATUS:…` line per node, indented
+///   two spaces per level; nodes with children carry `PROGRESS:closed/total`.
+/// - `Pretty` — box-drawing connectors (`├──`, `└──`) per level.
+pub fn format_tree(roots: &[TreeNode], fmt: Format) -> String {
+    match fmt {
+        Format::Json => apply_fields_filter(&serde_json::to_string(roots).unwrap_or_default()),
+        Format::Compact | Format::Oneline => {
+            warn_fields_unsupported("tree compact output");
+            let mut lines = Vec::new();
+            for root in roots {
+                format_tree_compact(root, 0, &mut lines);
+            }
+            lines.join("\n")
+        }
+        Format::Pretty => {
+            warn_fields_unsupported("tree pretty output");
+            let mut lines = Vec::new();
+            for root in roots {
+                lines.push(tree_label_pretty(root));
+                format_tree_pretty_children(&root.children, "", &mut lines);
+            }
+            lines.join("\n")
+        }
     }
-    lines.join("\n")
 }
 
-fn format_graph_dot(graph: &GraphOutput) -> String {
-    let mut lines = Vec::new();
-    lines.push("digraph itr {".to_string());
-    lines.push("  rankdir=LR;".to_string());
-    for node in &graph.nodes {
-        // Truncate first, then escape, so escape sequences are never cut in
-        // half by the truncation (issue #176).
-        let title_short = escape_dot_label(&truncate_with_ellipsis(&node.title, 30));
-        let style = if node.is_blocked {
-            " style=filled fillcolor=gray"
+fn format_tree_compact(node: &TreeNode, depth: usize, lines: &mut Vec<String>) {
+    let progress = node
+        .progress
+        .as_ref()
+        .map(|p| format!(" PROGRESS:{}/{}", p.closed(), p.total))
+        .unwrap_or_default();
+    lines.push(format!(
+        "{}ID:{} KIND:{} STATUS:{} PRIORITY:{}{} \"{}\"",
+        "  ".repeat(depth),
+        node.id,
+        node.kind,
+        node.status,
+        node.priority,
+        progress,
+        escape_quoted_value(&node.title)
+    ));
+    for child in &node.children {
+        format_tree_compact(child, depth + 1, lines);
+    }
+}
+
+fn tree_label_pretty(node: &TreeNode) -> String {
+    let progress = node
+        .progress
+        .as_ref()
+        .map(|p| format!(" [{}/{} {:.0}%]", p.closed(), p.total, p.percent_complete))
+        .unwrap_or_default();
+    format!(
+        "#{} {} ({}, {}){}",
+        node.id, node.title, node.kind, node.status, progress
+    )
+}
+
+fn format_tree_pretty_children(children: &[TreeNode], prefix: &str, lines: &mut Vec<String>) {
+    for (i, child) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        let (branch, indent) = if last {
+            ("\u{2514}\u{2500}\u{2500} ", "    ")
         } else {
-            ""
+            ("\u{251c}\u{2500}\u{2500} ", "\u{2502}   ")
         };
-        lines.push(format!(
-            "  {} [label=\"{}: {}\" shape=box{}]",
-            node.id, node.id, title_short, style
-        ));
-    }
-    for edge in &graph.edges {
-        lines.push(format!("  {} -> {}", edge.from, edge.to));
+        lines.push(format!("{}{}{}", prefix, branch, tree_label_pretty(child)));
+        format_tree_pretty_children(&child.children, &format!("{}{}", prefix, indent), lines);
     }
-    lines.push("}".to_string());
-    lines.join("\n")
 }
 
 // --- Display width, padding, and truncation helpers ---
@@ -1438,33 +3061,45 @@ pub fn format_events(events: &[Event], fmt: Format) -> String {
     }
 }
 
+/// One event as a single line for streaming output (`itr events`): a JSON
+/// object in JSON mode (honoring `--fields`), the `log` compact line otherwise.
+pub fn format_event_line(event: &Event, fmt: Format) -> String {
+    if fmt.is_json() {
+        apply_fields_filter(&serde_json::to_string(event).unwrap_or_default())
+    } else {
+        format_event_compact(event)
+    }
+}
+
 fn format_events_compact(events: &[Event]) -> String {
     events
         .iter()
-        .map(|e| {
-            let agent_str = if e.agent.is_empty() {
-                String::new()
-            } else {
-                format!(" AGENT:{}", escape_line_value(&e.agent))
-            };
-            // OLD/NEW are free text (often multi-word, possibly containing
-            // literal ` NEW:` or quotes): double-quote them with internal
-            // escaping so a parser can recover the exact values (issue #177).
-            format!(
-                "EVENT:{} ISSUE:{} FIELD:{} OLD:\"{}\" NEW:\"{}\"{} ({})",
-                e.id,
-                e.issue_id,
-                e.field,
-                escape_quoted_value(&e.old_value),
-                escape_quoted_value(&e.new_value),
-                agent_str,
-                e.created_at
-            )
-        })
+        .map(format_event_compact)
         .collect::<Vec<_>>()
         .join("\n")
 }
 
+fn format_event_compact(e: &Event) -> String {
+    let agent_str = if e.agent.is_empty() {
+        String::new()
+    } else {
+        format!(" AGENT:{}", escape_line_value(&e.agent))
+    };
+    // OLD/NEW are free text (often multi-word, possibly containing
+    // literal ` NEW:` or quotes): double-quote them with internal
+    // escaping so a parser can recover the exact values (issue #177).
+    format!(
+        "EVENT:{} ISSUE:{} FIELD:{} OLD:\"{}\" NEW:\"{}\"{} ({})",
+        e.id,
+        e.issue_id,
+        e.field,
+        escape_quoted_value(&e.old_value),
+        escape_quoted_value(&e.new_value),
+        agent_str,
+        e.created_at
+    )
+}
+
 fn format_events_pretty(events: &[Event]) -> String {
     if events.is_empty() {
         return String::new();
@@ -1520,6 +3155,8 @@ const VALID_FIELDS: &[&str] = &[
     "parent_id",
     "assigned_to",
     "close_reason",
+    "branch",
+    "project",
     "created_at",
     "updated_at",
     "urgency",
@@ -1529,10 +3166,23 @@ const VALID_FIELDS: &[&str] = &[
     "notes",
     "urgency_breakdown",
     "children",
+    "progress",
     "matched_fields",
     "unblocked",
     "context_snippets",
     "relations",
+    "notes_omitted",
+    "blocked_by_issues",
+    "key",
+    "commits",
+    "estimate",
+    "external_blockers",
+    "severity",
+    "component",
+    "affects_version",
+    "fixed_in_version",
+    "repro",
+    "referenced_by",
     // Batch result fields
     "action",
     "results",
@@ -1552,7 +3202,37 @@ const VALID_FIELDS: &[&str] = &[
     "avg_urgency",
     "by_skills",
     "by_assignee",
+    "by_component",
     "oldest_open",
+    // Tag list fields (tag list -f json)
+    "tag",
+    "open",
+    "closed",
+    "description",
+    "color",
+    // Project list fields (project list -f json)
+    "name",
+    "current",
+    // Component list fields (component list -f json)
+    "default_assignee",
+    "default_tags",
+    // Agent fields (agent list/show -f json)
+    "assigned",
+    "registered_at",
+    "last_seen_at",
+    // Brief fields (brief -f json)
+    "notes_total",
+    "parent",
+    "blockers",
+    "related_files",
+    // Session fields (session start/end/list -f json)
+    "started_at",
+    "ended_at",
+    "issues",
+    // File hotspot fields (files -f json)
+    "path",
+    "count",
+    "issue_ids",
     // Graph fields (graph -f json top-level filtering, issue #197)
     "nodes",
     "edges",
@@ -1562,6 +3242,14 @@ const VALID_FIELDS: &[&str] = &[
     "old_value",
     "new_value",
     "agent",
+    // Commit fields (commits -f json)
+    "hash",
+    "subject",
+    "relation",
+    "linked_at",
+    // Activity fields (activity -f json)
+    "author",
+    "date",
 ];
 
 /// Parse a `--fields` argument like `id,title,urgency` into a normalized
@@ -1647,7 +3335,7 @@ fn filter_json_object(value: serde_json::Value, fields: &[String]) -> serde_json
 // --- Unblocked notifications ---
 
 pub fn format_unblocked(issues: &[(i64, String)], fmt: Format) -> String {
-    if issues.is_empty() {
+    if issues.is_empty() || quiet(fmt) {
         return String::new();
     }
     match fmt {
@@ -1690,7 +3378,9 @@ fn format_batch_result_compact(result: &BatchResult) -> String {
         result.summary.ok,
         result.summary.error,
         result.summary.review,
-        if result.dry_run {
+        if result.rolled_back {
+            " (rolled back — nothing written)"
+        } else if result.dry_run {
             " (dry-run — nothing written)"
         } else {
             ""
@@ -1856,7 +3546,7 @@ mod tests {
     #[test]
     fn format_from_str_unknown_stays_none() {
         // Truly unknown values keep the existing hard-error path in main.rs
-        // (the integration suite pins `-f bogus` → exit 1 with the
+        // (the integration suite pins `-f bogus` → exit 5 with the
         // enumerated valid-formats message).
         assert_eq!(Format::from_str("bogus"), None);
         assert_eq!(Format::from_str(""), None);
@@ -1930,6 +3620,12 @@ mod tests {
             context: String::new(),
             parent_id: None,
             close_reason: String::new(),
+            branch: String::new(),
+            project: String::new(),
+            severity: String::new(),
+            component: String::new(),
+            affects_version: String::new(),
+            fixed_in_version: String::new(),
             blocks: vec![],
             assigned_to: String::new(),
             created_at: "2026-01-01T00:00:00Z".to_string(),
@@ -2177,6 +3873,7 @@ mod tests {
                 status: "open".to_string(),
                 urgency: 5.0,
                 is_blocked: false,
+                project: String::new(),
             }],
             edges: vec![],
         };
@@ -2208,6 +3905,18 @@ mod tests {
         assert_eq!(escape_line_value("plain title"), "plain title");
     }
 
+    #[test]
+    fn gha_command_escapes_data_and_properties() {
+        assert_eq!(
+            gha_command("error", Some("src/a,b.rs"), "itr gate", "100% broken\nnow"),
+            "::error file=src/a%2Cb.rs,title=itr gate::100%25 broken%0Anow"
+        );
+        assert_eq!(
+            gha_command("warning", None, "itr lint: x", "a::b"),
+            "::warning title=itr lint%3A x::a::b"
+        );
+    }
+
     fn make_detail(title: &str, context: &str) -> IssueDetail {
         IssueDetail {
             issue: Issue {
@@ -2224,6 +3933,13 @@ mod tests {
                 parent_id: None,
                 assigned_to: String::new(),
                 close_reason: String::new(),
+                branch: String::new(),
+                project: String::new(),
+                severity: String::new(),
+                component: String::new(),
+                affects_version: String::new(),
+                fixed_in_version: String::new(),
+                estimate: None,
                 created_at: "2026-01-01T00:00:00Z".to_string(),
                 updated_at: "2026-01-01T00:00:00Z".to_string(),
             },
@@ -2234,7 +3950,16 @@ mod tests {
             notes: vec![],
             urgency_breakdown: None,
             children: None,
+            progress: None,
             relations: vec![],
+            qualified_ids: std::collections::BTreeMap::new(),
+            notes_omitted: None,
+            blocked_by_issues: vec![],
+            key: String::new(),
+            commits: 0,
+            external_blockers: vec![],
+            repro: None,
+            referenced_by: vec![],
         }
     }
 
@@ -2342,6 +4067,7 @@ mod tests {
                 status: "open".to_string(),
                 urgency: 5.0,
                 is_blocked: false,
+                project: String::new(),
             }],
             edges: vec![],
         };
@@ -2351,6 +4077,49 @@ mod tests {
         assert!(out.contains("\\n"));
     }
 
+    #[test]
+    fn ascii_graph_layers_by_longest_blocking_chain() {
+        let node = |id: i64, title: &str| GraphNode {
+            id,
+            title: title.to_string(),
+            status: "open".to_string(),
+            urgency: 0.0,
+            is_blocked: id != 1,
+            project: String::new(),
+        };
+        let edge = |from: i64, to: i64, edge_type: &str| GraphEdge {
+            from,
+            to,
+            edge_type: edge_type.to_string(),
+        };
+        // 1 blocks 2 and 3, 2 blocks 3: 3 sits two layers down, not one.
+        let graph = GraphOutput {
+            nodes: vec![node(1, "root"), node(2, "mid"), node(3, "multi\nline")],
+            edges: vec![
+                edge(1, 2, "blocks"),
+                edge(1, 3, "blocks"),
+                edge(2, 3, "blocks"),
+                edge(3, 1, "related"),
+            ],
+        };
+        let out = format_graph_ascii(&graph);
+        assert_eq!(
+            out.lines().collect::<Vec<_>>(),
+            vec![
+                "\u{2500}\u{2500} layer 0 \u{2500}\u{2500}",
+                "#1 root (open)",
+                "\u{251c}\u{2500}\u{25b6} #2 mid",
+                "\u{2514}\u{2500}\u{25b6} #3 multi line",
+                "\u{2500}\u{2500} layer 1 \u{2500}\u{2500}",
+                "#2 mid (open, blocked)",
+                "\u{2514}\u{2500}\u{25b6} #3 multi line",
+                "\u{2500}\u{2500} layer 2 \u{2500}\u{2500}",
+                "#3 multi line (open, blocked)",
+                "\u{2514}\u{2500}related\u{2500}\u{25b6} #1 root",
+            ]
+        );
+    }
+
     #[test]
     fn compact_stats_oldest_open_newline_title_stays_single_line() {
         // Issue #156: stats OLDEST_OPEN quoted title must stay on one line
@@ -2365,6 +4134,7 @@ mod tests {
             avg_urgency: 5.0,
             by_skills: HashMap::default(),
             by_assignee: HashMap::default(),
+            by_component: HashMap::default(),
             oldest_open: Some(crate::models::OldestOpen {
                 id: 1,
                 title: "old\ntitle \"q\"".to_string(),
@@ -2393,6 +4163,37 @@ mod tests {
         assert_eq!(out, "UNBLOCKED:5 \"a\\nb \\\"q\\\"\"");
     }
 
+    #[test]
+    fn envelope_wraps_responses_and_errors_unless_raw() {
+        assert_eq!(envelope("[]\n"), r#"{"api_version":1,"ok":true,"data":[]}"#);
+        let err = error_envelope(serde_json::json!({ "error": "gone", "code": "NOT_FOUND" }));
+        assert_eq!(
+            err.to_string(),
+            r#"{"api_version":1,"ok":false,"error":"gone","code":"NOT_FOUND"}"#
+        );
+        assert_eq!(stream_line("{}".to_string(), Format::Compact), "{}");
+
+        set_raw(true);
+        assert_eq!(envelope("[]"), "[]");
+        assert_eq!(
+            error_envelope(serde_json::json!({ "code": "X" })).to_string(),
+            r#"{"code":"X"}"#
+        );
+        set_raw(false);
+    }
+
+    #[test]
+    fn quiet_drops_unblocked_lines_but_not_json() {
+        let unblocked = [(5, "next".to_string())];
+        set_quiet(true);
+        assert!(quiet(Format::Compact));
+        assert!(!quiet(Format::Json));
+        assert_eq!(format_unblocked(&unblocked, Format::Pretty), "");
+        assert!(format_unblocked(&unblocked, Format::Json).contains("\"id\":5"));
+        set_quiet(false);
+        assert!(!format_unblocked(&unblocked, Format::Compact).is_empty());
+    }
+
     #[test]
     fn oneline_escapes_tab_newline_and_quote_in_titles() {
         // Issue #175: oneline must emit exactly one physical line per issue
@@ -2445,6 +4246,7 @@ mod tests {
                 status: "open".to_string(),
                 urgency: 5.0,
                 is_blocked: false,
+                project: String::new(),
             }],
             edges: vec![],
         };
@@ -2526,6 +4328,7 @@ mod tests {
             avg_urgency: 5.0,
             by_skills: count_map("rust"),
             by_assignee: count_map("agent-x"),
+            by_component: count_map("parser"),
             oldest_open: Some(OldestOpen {
                 id: 1,
                 title: "Old".to_string(),
@@ -2548,6 +4351,7 @@ mod tests {
                 status: "open".to_string(),
                 urgency: 9.000_192_129_629_63,
                 is_blocked: false,
+                project: String::new(),
             }],
             edges: vec![GraphEdge {
                 from: 1,
@@ -2572,6 +4376,7 @@ mod tests {
         let out = format_stats(&make_stats_full(), Format::Json);
         let expected = concat!(
             "{\"avg_urgency\":5.0,\"blocked\":0,\"by_assignee\":{\"agent-x\":1},",
+            "\"by_component\":{\"parser\":1},",
             "\"by_kind\":{\"bug\":1},\"by_priority\":{\"high\":1},",
             "\"by_skills\":{\"rust\":1},\"by_status\":{\"open\":1},",
             "\"oldest_open\":{\"days_old\":3,\"id\":1,\"title\":\"Old\"},",
@@ -2622,6 +4427,7 @@ mod tests {
                 status: "open".to_string(),
                 urgency: 5.0,
                 is_blocked: false,
+                project: String::new(),
             }],
             edges: vec![GraphEdge {
                 from: 1,
@@ -0,0 +1,278 @@
+//! The user-level config file, `$XDG_CONFIG_HOME/itr/config.toml` (else
+//! `~/.config/itr/config.toml`): defaults for every database on the machine.
+//!
+//! It holds the same dotted keys as `itr config`, written as TOML tables
+//! (`urgency.blocking` is `blocking` under `[urgency]`). Once loaded by
+//! [`install`], each opened connection gets it through
+//! `db::set_config_defaults`, under the database's own `config` table:
+//! `db::config_get` answers from the database first. A few keys are read
+//! before any database is open and only make sense here: [`COLOR_KEY`],
+//! `db.search`, and `db.fallback`.
+
+use crate::error::ItrError;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+
+static LOADED: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Output format used when `-f` and [`FORMAT_ENV`] are both unset. Read
+/// from here before the database opens, then from the database's own
+/// config once it does.
+pub const FORMAT_KEY: &str = "format.default";
+/// Environment override for [`FORMAT_KEY`]; `-f` still wins.
+pub const FORMAT_ENV: &str = "ITR_FORMAT";
+/// `auto` (default), `always`, or `never` — ANSI color in pretty output.
+pub const COLOR_KEY: &str = "color.mode";
+/// Keys that are read before a database is open, so a per-project value
+/// would never be seen.
+pub const GLOBAL_ONLY_KEYS: &[&str] = &[COLOR_KEY, "db.search", "db.fallback"];
+
+/// Where the file lives; `None` when no home directory can be found.
+pub fn path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| {
+            env::var_os("HOME")
+                .or_else(|| env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".config"))
+        })?;
+    Some(base.join("itr").join("config.toml"))
+}
+
+/// Load the file for [`values`] and [`value`]. A missing file is an empty
+/// layer; one that doesn't parse is ignored with a `REVIEW:` note so a typo
+/// in it can't stop every `itr` command.
+pub fn install() {
+    match read() {
+        Ok(values) => *LOADED.lock().unwrap_or_else(PoisonError::into_inner) = values,
+        Err(e) => eprintln!("REVIEW: global config ignored: {}", e),
+    }
+}
+
+/// Every key loaded by [`install`], sorted.
+pub fn values() -> BTreeMap<String, String> {
+    LOADED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// One key loaded by [`install`].
+pub fn value(key: &str) -> Option<String> {
+    LOADED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(key)
+        .cloned()
+}
+
+/// The file's keys, flattened to `itr config` form. Missing file: empty.
+pub fn read() -> Result<BTreeMap<String, String>, ItrError> {
+    let Some(path) = path() else {
+        return Ok(BTreeMap::new());
+    };
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(e.into()),
+    };
+    parse(&text).map_err(|e| ItrError::InvalidValue {
+        field: "global config".to_string(),
+        value: path.display().to_string(),
+        valid: e,
+    })
+}
+
+/// Replace the file with `values`, creating its directory if needed.
+pub fn write(values: &BTreeMap<String, String>) -> Result<PathBuf, ItrError> {
+    let path = path().ok_or_else(|| {
+        ItrError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Could not resolve the config directory (XDG_CONFIG_HOME/HOME unset)",
+        ))
+    })?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(
+        &path,
+        render(
+            values,
+            "itr user-level defaults; see `itr config --global list`.",
+        ),
+    )?;
+    Ok(path)
+}
+
+/// Remove the file; a missing one is fine.
+pub fn remove() -> Result<(), ItrError> {
+    match path().map(fs::remove_file) {
+        Some(Err(e)) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Whether `key` can be stored next to `values`: TOML can't hold both
+/// `color` and `color.mode`, since one makes `color` a string and the
+/// other a table.
+pub fn conflicting_key<'a>(values: &'a BTreeMap<String, String>, key: &str) -> Option<&'a str> {
+    values
+        .keys()
+        .find(|k| {
+            k.strip_prefix(key)
+                .is_some_and(|rest| rest.starts_with('.'))
+                || key
+                    .strip_prefix(k.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+        .map(String::as_str)
+}
+
+/// Parse TOML into flat `itr config` keys: tables and dotted keys join with
+/// `.`; strings, numbers, and booleans become their text. Also used for
+/// `itr config import`.
+pub fn parse(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let doc: toml_edit::DocumentMut = text
+        .parse()
+        .map_err(|e: toml_edit::TomlError| e.message().to_string())?;
+    let mut out = BTreeMap::new();
+    flatten("", &doc.as_table().iter().collect::<Vec<_>>(), &mut out)?;
+    Ok(out)
+}
+
+fn flatten(
+    prefix: &str,
+    entries: &[(&str, &toml_edit::Item)],
+    out: &mut BTreeMap<String, String>,
+) -> Result<(), String> {
+    for &(key, item) in entries {
+        let key = if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match item {
+            toml_edit::Item::Table(table) => {
+                flatten(&key, &table.iter().collect::<Vec<_>>(), out)?;
+            }
+            toml_edit::Item::Value(toml_edit::Value::InlineTable(table)) => {
+                let owned: Vec<(&str, toml_edit::Item)> = table
+                    .iter()
+                    .map(|(k, v)| (k, toml_edit::Item::Value(v.clone())))
+                    .collect();
+                let entries: Vec<(&str, &toml_edit::Item)> =
+                    owned.iter().map(|(k, v)| (*k, v)).collect();
+                flatten(&key, &entries, out)?;
+            }
+            toml_edit::Item::Value(value) => {
+                let text = match value {
+                    toml_edit::Value::String(s) => s.value().clone(),
+                    toml_edit::Value::Integer(n) => n.value().to_string(),
+                    toml_edit::Value::Float(f) => f.value().to_string(),
+                    toml_edit::Value::Boolean(b) => b.value().to_string(),
+                    _ => return Err(format!("'{}' must be a string, number, or boolean", key)),
+                };
+                out.insert(key, text);
+            }
+            _ => return Err(format!("'{}' must be a string, number, or boolean", key)),
+        }
+    }
+    Ok(())
+}
+
+/// Top-level keys first, then one `[table]` per shared prefix, all sorted,
+/// under a `# header` comment. Values are always written as strings, as
+/// `itr config set` stores them. Also used for `itr config export`.
+pub fn render(values: &BTreeMap<String, String>, header: &str) -> String {
+    let mut tables: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+    for (key, value) in values {
+        let (table, leaf) = key.rsplit_once('.').unwrap_or(("", key));
+        tables.entry(table).or_default().push((leaf, value));
+    }
+    let mut out = format!("# {}\n", header);
+    for (table, entries) in tables {
+        if !table.is_empty() {
+            let header: Vec<String> = table.split('.').map(toml_key).collect();
+            out.push_str(&format!("\n[{}]\n", header.join(".")));
+        }
+        for (leaf, value) in entries {
+            out.push_str(&format!("{} = {}\n", toml_key(leaf), toml_string(value)));
+        }
+    }
+    out
+}
+
+fn toml_key(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        key.to_string()
+    } else {
+        toml_string(key)
+    }
+}
+
+fn toml_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_round_trips_through_parse() {
+        let values: BTreeMap<String, String> = [
+            ("format.default", "json"),
+            ("urgency.priority.high", "6"),
+            ("urgency.blocking", "8"),
+            ("webhook.url", "https://example.com/\"hook\"\n"),
+            ("odd key.x", "1"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        assert_eq!(parse(&render(&values, "test")).unwrap(), values);
+    }
+
+    #[test]
+    fn hand_written_files_accept_dotted_keys_and_plain_types() {
+        let values =
+            parse("urgency.blocking = 8\n[color]\nmode = \"never\"\n[notify]\ndesktop = true\n")
+                .unwrap();
+        assert_eq!(values["urgency.blocking"], "8");
+        assert_eq!(values["color.mode"], "never");
+        assert_eq!(values["notify.desktop"], "true");
+        assert!(parse("tags = [1, 2]").is_err());
+        assert!(parse("not toml").is_err());
+    }
+
+    #[test]
+    fn a_key_cannot_be_both_a_value_and_a_table() {
+        let values: BTreeMap<String, String> =
+            [("color.mode".to_string(), "never".to_string())].into();
+        assert_eq!(conflicting_key(&values, "color"), Some("color.mode"));
+        assert_eq!(conflicting_key(&values, "color.mode.x"), Some("color.mode"));
+        assert_eq!(conflicting_key(&values, "colors"), None);
+    }
+}
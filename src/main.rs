@@ -1,16 +1,21 @@
 mod agent_docs;
 mod cli;
 mod commands;
-mod db;
+mod completion;
 mod error;
 mod format;
-mod models;
-mod normalize;
-mod urgency;
+mod global_config;
+mod notify;
 mod util;
+mod webhook;
 
-use clap::Parser;
-use cli::{BatchAction, BulkAction, Cli, Commands, ConfigAction};
+use itr_core::{db, formula, models, normalize, pool, store, urgency};
+
+use clap::{CommandFactory, Parser};
+use cli::{
+    AgentAction, BatchAction, BulkAction, Cli, Commands, ComponentAction, ConfigAction, GitAction,
+    MirrorAction, ProjectAction, ReviewAction, SessionAction, SnapshotAction, TagAction,
+};
 use error::handle_error;
 use format::Format;
 use models::ListFilter;
@@ -37,14 +42,22 @@ fn preprocess_args() -> Vec<std::ffi::OsString> {
 }
 
 fn main() {
+    // `COMPLETE=<shell> itr -- <words>` is the shell calling back at <TAB>
+    // time (see `itr completions`); answer and exit before normal parsing.
+    clap_complete::CompleteEnv::with_factory(Cli::command).complete();
     let cli = Cli::parse_from(preprocess_args());
+    // The library stays silent unless given a sink; the CLI's is stderr.
+    util::set_diagnostic_sink(Some(|note| eprintln!("{}", note)));
+    global_config::install();
 
-    let fmt = Format::from_str(&cli.format).unwrap_or_else(|| {
-        eprintln!(
-            "ERROR: Invalid format '{}'. Valid: compact, json, pretty, oneline",
-            cli.format
-        );
-        std::process::exit(1);
+    // `-f`, then ITR_FORMAT; failing both, `format.default` decides — from
+    // the global config until the database is open, then from its config.
+    let explicit_fmt = explicit_format(cli.format.as_deref());
+    let mut fmt = explicit_fmt.unwrap_or_else(|| {
+        configured_format(
+            global_config::value(global_config::FORMAT_KEY),
+            "the global config",
+        )
     });
 
     // Parse and validate --fields (unknown fields are warned but kept)
@@ -59,18 +72,63 @@ fn main() {
         format::set_fields_filter(f);
     }
 
+    db::set_verbose(cli.verbose);
+    util::set_assume_yes(cli.yes);
+    format::set_quiet(cli.quiet);
+    format::set_raw(cli.raw);
+    error::set_fail_on(cli.fail_on);
+    if format::gha(fmt)
+        && !matches!(
+            cli.command,
+            Commands::Lint { .. } | Commands::Doctor { .. } | Commands::Gate { .. }
+        )
+    {
+        eprintln!("REVIEW: -f gha only changes lint, doctor, and gate output; using compact");
+    }
+
     let result = match cli.command {
-        Commands::Init { agents_md } => commands::init::run(agents_md, fmt, cli.db.as_deref()),
+        Commands::Init {
+            agents_md,
+            target,
+            agents_template,
+            encrypt,
+            config,
+            demo,
+        } => commands::init::run(
+            agents_md
+                .then_some(commands::init::AgentsMd {
+                    targets: target,
+                    template: agents_template,
+                })
+                .as_ref(),
+            encrypt,
+            config.as_deref(),
+            demo,
+            fmt,
+            cli.db.as_deref(),
+        ),
         Commands::AgentInfo => commands::agent_info::run(fmt),
-        Commands::Schema => commands::schema::run(fmt),
+        Commands::Schema { json, model } => commands::schema::run(json, model, fmt),
+        Commands::Completions { shell } => commands::completions::run(shell, fmt),
         Commands::Skill { action } => commands::skill::run(action, fmt),
+        Commands::Git {
+            action: GitAction::InstallHooks { force },
+        } => commands::git::install_hooks(force, fmt),
         Commands::Upgrade {
             no_pull,
             source_dir,
         } => commands::upgrade::run(no_pull, source_dir, fmt),
+        Commands::Mirror {
+            action: MirrorAction::Merge { base, ours, theirs },
+        } => commands::mirror::merge(&base, &ours, &theirs, fmt),
+        Commands::Config {
+            global: true,
+            action,
+        } => commands::config::run_global(action, fmt),
         _ => {
             // All other commands need the database
-            let db_path = match db::find_db(cli.db.as_deref()) {
+            let defaults = global_config::values();
+            let db_path = match db::find_db(cli.db.as_deref(), &defaults) {
                 Ok(p) => p,
                 Err(e) => handle_error(e, fmt.is_json()),
             };
@@ -78,13 +136,111 @@ fn main() {
                 Ok(c) => c,
                 Err(e) => handle_error(e, fmt.is_json()),
             };
+            if let Err(e) = db::set_config_defaults(&conn, &defaults) {
+                handle_error(e, fmt.is_json());
+            }
+            if explicit_fmt.is_none() {
+                let own = db::config_list(&conn)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .find(|(k, _)| k == global_config::FORMAT_KEY);
+                if let Some((_, value)) = own {
+                    fmt = configured_format(Some(value), "this database");
+                }
+            }
 
-            run_command(cli.command, &conn, &db_path, fmt)
+            if matches!(fmt, Format::Pretty) && util::color_enabled() {
+                format::set_tag_colors(db::tag_colors(&conn).unwrap_or_default());
+            }
+
+            if let Err(e) = commands::project::apply_scope(&conn, cli.project.as_deref()) {
+                handle_error(e, fmt.is_json());
+            }
+
+            // Long-running commands would only deliver on exit; skip them.
+            let hook = if matches!(
+                cli.command,
+                Commands::Watch { .. } | Commands::Events { .. } | Commands::Ui { .. }
+            ) {
+                None
+            } else {
+                webhook::Webhook::prepare(&conn)
+            };
+            let result = run_command(cli.command, &conn, &db_path, fmt);
+            if let Err(e) = &result {
+                error::suggest_issues(&conn, e);
+            }
+            // Deliver even on error: a multi-ID command may have committed
+            // some changes before failing.
+            if let Some(hook) = hook {
+                hook.deliver(&conn);
+            }
+            result
         }
     };
 
-    if let Err(e) = result {
-        handle_error(e, fmt.is_json());
+    match result {
+        Ok(()) => error::apply_fail_on(fmt.is_json()),
+        Err(e) => handle_error(e, fmt.is_json()),
+    }
+}
+
+/// The format asked for on this invocation: `-f` (an invalid value is a hard
+/// error), else `ITR_FORMAT` (an invalid value is ignored with a REVIEW).
+fn explicit_format(flag: Option<&str>) -> Option<Format> {
+    if let Some(f) = flag {
+        if is_gha(f) {
+            format::set_gha(true);
+            return Some(Format::Compact);
+        }
+        return Some(Format::from_str(f).unwrap_or_else(|| {
+            eprintln!(
+                "ERROR: Invalid format '{}'. Valid: compact, json, pretty, oneline, gha",
+                f
+            );
+            // A validation error, so INVALID_VALUE's status.
+            std::process::exit(5);
+        }));
+    }
+    let env = std::env::var(global_config::FORMAT_ENV)
+        .ok()
+        .filter(|v| !v.trim().is_empty())?;
+    if is_gha(&env) {
+        format::set_gha(true);
+        return Some(Format::Compact);
+    }
+    let fmt = Format::from_str(&env);
+    if fmt.is_none() {
+        eprintln!(
+            "REVIEW: {} '{}' is not a format; ignored. Valid: compact, json, pretty, oneline, gha",
+            global_config::FORMAT_ENV,
+            env
+        );
+    }
+    fmt
+}
+
+/// `gha` is not a [`Format`]: it is compact output with `lint`, `doctor`, and
+/// `gate` findings as GitHub Actions workflow commands (see
+/// [`format::set_gha`]).
+fn is_gha(value: &str) -> bool {
+    value.trim().eq_ignore_ascii_case("gha")
+}
+
+/// A stored `format.default`, falling back to compact with a REVIEW note
+/// naming where the bad value came from.
+fn configured_format(value: Option<String>, source: &str) -> Format {
+    match value {
+        Some(f) => Format::from_str(&f).unwrap_or_else(|| {
+            eprintln!(
+                "REVIEW: {} '{}' in {} is not a format; using compact",
+                global_config::FORMAT_KEY,
+                f,
+                source
+            );
+            Format::Compact
+        }),
+        None => Format::Compact,
     }
 }
 
@@ -112,15 +268,71 @@ fn build_list_filter(
         statuses,
         priorities,
         kinds,
+        severities: Vec::new(),
+        components: Vec::new(),
+        affects_versions: Vec::new(),
+        fixed_in_versions: Vec::new(),
         tags,
         tag_any,
+        files: Vec::new(),
         skills,
         blocked_only: blocked,
         include_blocked: true,
         parent_id,
+        recursive: false,
         assigned_to,
+        project: commands::project::scope(),
         all,
+        sort: None,
+        limit: None,
+        offset: None,
+    }
+}
+
+/// Run a mutating command, rolling all of its writes back under `--dry-run`.
+/// Output is exactly what a real run would print; a `DRY-RUN:` line on stderr
+/// marks that nothing was written.
+fn with_dry_run(
+    conn: &rusqlite::Connection,
+    dry_run: bool,
+    f: impl FnOnce() -> Result<(), error::ItrError>,
+) -> Result<(), error::ItrError> {
+    if !dry_run {
+        return f();
+    }
+    // Nothing is written, so there is nothing to confirm or announce.
+    util::set_assume_yes(true);
+    notify::suppress();
+    db::dry_run(conn, f)?;
+    eprintln!("DRY-RUN: rolled back; no changes were written");
+    Ok(())
+}
+
+/// Resolve an optional single-issue argument — an ID, `project/ID`, issue key,
+/// or name — with [`db::resolve_issue_ref`].
+fn issue_arg(
+    conn: &rusqlite::Connection,
+    token: Option<String>,
+) -> Result<Option<i64>, error::ItrError> {
+    token.map(|t| db::resolve_issue_ref(conn, &t)).transpose()
+}
+
+/// Rewrite the leading issue keys (`APP-42`) and `project/ID`s of `close` /
+/// `note` arguments to bare IDs, so [`util::split_ids_and_text`] counts them
+/// as IDs. Everything from the first non-ID argument on is left alone.
+fn resolve_leading_ids(
+    conn: &rusqlite::Connection,
+    args: &[String],
+) -> Result<Vec<String>, error::ItrError> {
+    let mut out = args.to_vec();
+    for arg in &mut out {
+        let resolved = db::resolve_id_tokens(conn, std::slice::from_ref(arg))?.remove(0);
+        if !util::is_id_token(&resolved) {
+            break;
+        }
+        *arg = resolved;
     }
+    Ok(out)
 }
 
 /// Resolve the `(reason, wontfix)` pair passed to `close::run`.
@@ -151,9 +363,16 @@ fn run_command(
     match command {
         Commands::Init { .. }
         | Commands::AgentInfo
-        | Commands::Schema
+        | Commands::Schema { .. }
+        | Commands::Completions { .. }
         | Commands::Skill { .. }
-        | Commands::Upgrade { .. } => {
+        | Commands::Git {
+            action: GitAction::InstallHooks { .. },
+        }
+        | Commands::Upgrade { .. }
+        | Commands::Mirror {
+            action: MirrorAction::Merge { .. },
+        } => {
             unreachable!()
         }
 
@@ -173,9 +392,17 @@ fn run_command(
             blocked_by,
             parent,
             assigned_to,
+            severity,
+            component,
+            affects_version,
+            fixed_in_version,
+            estimate,
             stdin_json,
+            from_markdown,
+            dry_run,
         } => {
             // Merge: --title flag takes precedence over positional
+            let parent = issue_arg(conn, parent)?;
             let effective_title = match (title, title_flag) {
                 (Some(pos), Some(flag)) => {
                     eprintln!(
@@ -188,25 +415,33 @@ fn run_command(
                 (None, Some(flag)) => Some(flag),
                 (pos, None) => pos,
             };
-            commands::add::run(
-                conn,
-                effective_title,
-                &priority,
-                &kind,
-                context,
-                files,
-                file,
-                tags,
-                tag,
-                skills,
-                skill,
-                acceptance,
-                blocked_by,
-                parent,
-                assigned_to,
-                stdin_json,
-                fmt,
-            )
+            with_dry_run(conn, dry_run, || {
+                commands::add::run(
+                    conn,
+                    effective_title,
+                    priority,
+                    &kind,
+                    context,
+                    files,
+                    file,
+                    tags,
+                    tag,
+                    skills,
+                    skill,
+                    acceptance,
+                    blocked_by,
+                    parent,
+                    assigned_to,
+                    severity,
+                    component,
+                    affects_version,
+                    fixed_in_version,
+                    estimate,
+                    stdin_json,
+                    from_markdown,
+                    fmt,
+                )
+            })
         }
 
         Commands::List {
@@ -214,17 +449,25 @@ fn run_command(
             status,
             priority,
             kind,
+            severity,
+            component,
+            affects_version,
+            fixed_in_version,
             tag,
             tag_any,
+            file,
             skill,
             blocked,
             include_blocked,
             parent,
+            recursive,
             assigned_to,
             sort,
             limit,
+            offset,
         } => {
-            let filter = build_list_filter(
+            let parent = issue_arg(conn, parent)?;
+            let mut filter = build_list_filter(
                 all,
                 status,
                 priority,
@@ -237,10 +480,31 @@ fn run_command(
                 parent,
                 assigned_to,
             );
-            commands::list::run(conn, &filter, &sort, limit, fmt)
+            filter.recursive = recursive;
+            filter.severities = severity;
+            filter.components = component;
+            filter.affects_versions = affects_version;
+            filter.fixed_in_versions = fixed_in_version;
+            filter.files = file;
+            let (sort, limit) = commands::list::apply_defaults(conn, &mut filter, sort, limit)?;
+            commands::list::run(conn, &filter, &sort, limit, offset, fmt)
         }
 
-        Commands::Get { ids } => commands::get::run(conn, &ids, fmt),
+        Commands::Get {
+            mut ids,
+            id_list,
+            no_notes,
+            notes_last,
+            with_blockers,
+        } => {
+            ids.extend(id_list);
+            let opts = commands::get::GetOptions {
+                no_notes,
+                notes_last,
+                with_blockers,
+            };
+            commands::get::run(conn, &ids, &opts, fmt)
+        }
 
         Commands::Update {
             id,
@@ -265,42 +529,93 @@ fn run_command(
             remove_file,
             add_skill,
             remove_skill,
-        } => commands::update::run(
-            conn,
+            estimate,
+            severity,
+            component,
+            affects_version,
+            fixed_in_version,
+            dry_run,
+        } => {
+            let id = db::resolve_issue_ref(conn, &id)?;
+            let parent = issue_arg(conn, parent)?;
+            with_dry_run(conn, dry_run, || {
+                commands::update::run(
+                    conn,
+                    id,
+                    status,
+                    priority,
+                    kind,
+                    title,
+                    context,
+                    files,
+                    file,
+                    tags,
+                    tag,
+                    skills,
+                    skill,
+                    acceptance,
+                    parent,
+                    no_parent,
+                    assigned_to,
+                    add_tag,
+                    remove_tag,
+                    add_file,
+                    remove_file,
+                    add_skill,
+                    remove_skill,
+                    estimate,
+                    severity,
+                    component,
+                    affects_version,
+                    fixed_in_version,
+                    fmt,
+                )
+            })
+        }
+
+        Commands::Repro {
             id,
-            status,
-            priority,
-            kind,
-            title,
-            context,
-            files,
-            file,
-            tags,
-            tag,
-            skills,
-            skill,
-            acceptance,
-            parent,
-            no_parent,
-            assigned_to,
-            add_tag,
-            remove_tag,
-            add_file,
-            remove_file,
-            add_skill,
-            remove_skill,
-            fmt,
-        ),
+            stdin_json,
+            edit,
+            clear,
+        } => {
+            let id = db::resolve_issue_ref(conn, &id)?;
+            let source = if stdin_json {
+                commands::repro::Source::StdinJson
+            } else if edit {
+                commands::repro::Source::Edit
+            } else if clear {
+                commands::repro::Source::Clear
+            } else {
+                commands::repro::Source::Show
+            };
+            commands::repro::run(conn, id, source, fmt)
+        }
 
         Commands::Close {
             args,
             reason_flag,
             wontfix,
             duplicate_of,
+            dry_run,
         } => {
             // The leading run of ID-shaped tokens is the ID list; the first
-            // non-ID token starts the positional reason.
-            let (id_tokens, positional_reason) = util::split_ids_and_text(&args);
+            // non-ID token starts the positional reason. With no ID at all,
+            // the first argument may name the issue by slug or title prefix.
+            let duplicate_of = issue_arg(conn, duplicate_of)?;
+            let args = resolve_leading_ids(conn, &args)?;
+            let (mut id_tokens, mut positional_reason) = util::split_ids_and_text(&args);
+            if id_tokens.is_empty() && !args.is_empty() {
+                let Some(id) = db::find_issue_by_name(conn, &args[0])? else {
+                    return Err(error::ItrError::InvalidValue {
+                        field: "id".to_string(),
+                        value: args[0].clone(),
+                        valid: "integer issue IDs (e.g. `itr close 12,14,17`), or an issue key, slug, or title prefix".to_string(),
+                    });
+                };
+                id_tokens = vec![id.to_string()];
+                positional_reason = (args.len() > 1).then(|| args[1..].join(" "));
+            }
             // Merge: --reason flag takes precedence over positional
             let effective_reason = match (positional_reason, reason_flag) {
                 (Some(pos), Some(flag)) => {
@@ -315,10 +630,13 @@ fn run_command(
                 (pos, None) => pos,
             };
             let (reason, wontfix) = close_args(effective_reason, wontfix, duplicate_of);
-            commands::close::run_multi(conn, &id_tokens, reason, wontfix, duplicate_of, fmt)
+            with_dry_run(conn, dry_run, || {
+                commands::close::run_multi(conn, &id_tokens, reason, wontfix, duplicate_of, fmt)
+            })
         }
 
         Commands::Note { args, agent } => {
+            let args = resolve_leading_ids(conn, &args)?;
             let (id_tokens, text) = util::split_ids_and_text(&args);
             commands::note::run_multi(conn, &id_tokens, text, &agent, fmt)
         }
@@ -327,16 +645,50 @@ fn run_command(
 
         Commands::NoteUpdate { id, text } => commands::note::run_update(conn, id, &text, fmt),
 
-        Commands::Depend { ids, on } => commands::depend::run_multi(conn, &ids, on, fmt),
+        Commands::Depend {
+            ids,
+            on,
+            on_external,
+            url,
+            dry_run,
+        } => {
+            if let Some(description) = on_external {
+                let url = url.unwrap_or_default();
+                return with_dry_run(conn, dry_run, || {
+                    commands::depend::run_external(conn, &ids, &description, &url, fmt)
+                });
+            }
+            let on = db::resolve_issue_ref(conn, on.as_deref().unwrap_or_default())?;
+            with_dry_run(conn, dry_run, || {
+                commands::depend::run_multi(conn, &ids, on, fmt)
+            })
+        }
+
+        Commands::ResolveExternal { id, blocker } => {
+            let id = db::resolve_issue_ref(conn, &id)?;
+            commands::depend::run_resolve_external(conn, id, blocker, fmt)
+        }
 
-        Commands::Undepend { id, on } => commands::depend::run_undepend(conn, id, on, fmt),
+        Commands::Undepend { id, on } => {
+            let id = db::resolve_issue_ref(conn, &id)?;
+            let on = db::resolve_issue_ref(conn, &on)?;
+            commands::depend::run_undepend(conn, id, on, fmt)
+        }
 
         Commands::Next {
             claim,
             skill,
+            skills,
             agent,
             assigned_to,
-        } => commands::next::run(conn, claim, None, skill, agent, assigned_to, fmt),
+        } => commands::next::run(conn, claim, None, skill, skills, agent, assigned_to, fmt),
+
+        Commands::Brief {
+            id,
+            next: _,
+            skill,
+            notes,
+        } => commands::brief::run(conn, issue_arg(conn, id)?, skill, notes, fmt),
 
         Commands::Ready {
             limit,
@@ -344,6 +696,9 @@ fn run_command(
             skill,
             assigned_to,
         } => commands::ready::run(conn, limit, status, skill, assigned_to, fmt),
+        Commands::PlanSprint { capacity, agents } => {
+            commands::plan_sprint::run(conn, &capacity, agents, fmt)
+        }
 
         Commands::Batch { action } => match action {
             BatchAction::Add { dry_run } => commands::batch::run_add(conn, dry_run, fmt),
@@ -352,6 +707,8 @@ fn run_command(
             BatchAction::Note { dry_run } => commands::batch::run_note(conn, dry_run, fmt),
         },
 
+        Commands::Apply { dry_run } => commands::apply::run(conn, dry_run, fmt),
+
         Commands::Bulk { action } => match action {
             BulkAction::Close {
                 reason,
@@ -413,7 +770,7 @@ fn run_command(
                 dry_run,
             } => commands::bulk::run_relate(
                 conn,
-                to,
+                db::resolve_issue_ref(conn, &to)?,
                 &relation_type,
                 status,
                 priority,
@@ -435,7 +792,7 @@ fn run_command(
                 dry_run,
             } => commands::bulk::run_depend(
                 conn,
-                on,
+                db::resolve_issue_ref(conn, &on)?,
                 status,
                 priority,
                 kind,
@@ -470,16 +827,210 @@ fn run_command(
             ),
         },
 
-        Commands::Graph { all } => commands::graph::run(conn, all, fmt),
+        Commands::Graph {
+            all,
+            around,
+            depth,
+            ascii,
+            reduce,
+            roots_only,
+            leaves_only,
+        } => {
+            let view = commands::graph::View {
+                around: issue_arg(conn, around)?,
+                depth,
+                reduce,
+                ends: match (roots_only, leaves_only) {
+                    (true, _) => Some(true),
+                    (_, true) => Some(false),
+                    _ => None,
+                },
+                ascii,
+            };
+            commands::graph::run(conn, all, &view, fmt)
+        }
+        Commands::Escalate { dry_run } => commands::escalate::run(conn, dry_run, fmt),
+        Commands::Stale { dry_run } => commands::stale::run(conn, dry_run, fmt),
+        Commands::Lint { all } => commands::lint::run(conn, all, fmt),
+        Commands::Gate {
+            max_critical,
+            touching_staged,
+        } => commands::gate::run(conn, max_critical, touching_staged, fmt),
+        Commands::Urgency { id, all: _, limit } => {
+            commands::urgency::run(conn, issue_arg(conn, id)?, limit, fmt)
+        }
+        Commands::Recompute => commands::recompute::run(conn, fmt),
+        Commands::Files {
+            status,
+            kind,
+            limit,
+        } => commands::files::run(conn, &status, &kind, limit, fmt),
+        Commands::Versions => commands::versions::run(conn, fmt),
+        Commands::Tree { id, all } => commands::tree::run(conn, issue_arg(conn, id)?, all, fmt),
+
+        Commands::Roadmap { all } => commands::roadmap::run(conn, all, fmt),
+
+        Commands::Branch { id, checkout } => {
+            commands::branch::run(conn, db::resolve_issue_ref(conn, &id)?, checkout, fmt)
+        }
+
+        Commands::Git {
+            action: GitAction::Scan { range },
+        } => commands::git::scan(conn, range.as_deref(), fmt),
+
+        Commands::Git {
+            action: GitAction::Link { id, rev },
+        } => commands::git::link(conn, db::resolve_issue_ref(conn, &id)?, rev.as_deref(), fmt),
 
-        Commands::Stats => commands::stats::run(conn, fmt),
+        Commands::Commits { id } => {
+            commands::commits::run(conn, db::resolve_issue_ref(conn, &id)?, fmt)
+        }
+
+        Commands::Activity { id, since, limit } => commands::activity::run(
+            conn,
+            db::resolve_issue_ref(conn, &id)?,
+            since.as_deref(),
+            limit,
+            fmt,
+        ),
+
+        Commands::Scan { path, dry_run } => with_dry_run(conn, dry_run, || {
+            commands::scan::run(conn, path.as_deref(), fmt)
+        }),
+
+        Commands::Tag { action } => match action {
+            TagAction::List => commands::tag::list(conn, fmt),
+            TagAction::Rename { old, new } => commands::tag::rename(conn, &old, &new, fmt),
+            TagAction::Rm { tag } => commands::tag::rm(conn, &tag, fmt),
+            TagAction::Describe {
+                tag,
+                description,
+                color,
+            } => commands::tag::describe(conn, &tag, description.as_deref(), color.as_deref(), fmt),
+        },
+
+        Commands::Project { action } => match action {
+            ProjectAction::Add {
+                name,
+                description,
+                key,
+            } => commands::project::add(conn, &name, description.as_deref(), key.as_deref(), fmt),
+            ProjectAction::Key { name, prefix } => {
+                commands::project::key(conn, &name, &prefix, fmt)
+            }
+            ProjectAction::Switch { name } => commands::project::switch(conn, name.as_deref(), fmt),
+            ProjectAction::List => commands::project::list(conn, fmt),
+            ProjectAction::Move { target, ids } => {
+                let ids = ids
+                    .iter()
+                    .map(|id| db::resolve_issue_ref(conn, id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                commands::project::move_issues(conn, &target, &ids, fmt)
+            }
+        },
+
+        Commands::Component { action } => match action {
+            ComponentAction::Add {
+                name,
+                assignee,
+                tags,
+            } => commands::component::add(conn, &name, assignee.as_deref(), tags.as_deref(), fmt),
+            ComponentAction::List => commands::component::list(conn, fmt),
+            ComponentAction::Remove { name } => commands::component::remove(conn, &name, fmt),
+        },
+
+        Commands::Agent { action } => match action {
+            AgentAction::Register {
+                name,
+                skill,
+                description,
+            } => commands::agent::register(
+                conn,
+                name.as_deref(),
+                &skill,
+                description.as_deref(),
+                fmt,
+            ),
+            AgentAction::List => commands::agent::list(conn, fmt),
+            AgentAction::Show { name } => commands::agent::show(conn, name.as_deref(), fmt),
+        },
+
+        Commands::Session { action } => match action {
+            SessionAction::Start { agent } => commands::session::start(conn, agent.as_deref(), fmt),
+            SessionAction::End { summary, agent } => {
+                commands::session::end(conn, agent.as_deref(), &summary, fmt)
+            }
+            SessionAction::List { agent, limit } => {
+                commands::session::list(conn, agent.as_deref(), limit, fmt)
+            }
+        },
+
+        Commands::Snapshot { action } => match action {
+            SnapshotAction::Save { name } => commands::snapshot::save(conn, &name, fmt),
+            SnapshotAction::List => commands::snapshot::list(conn, fmt),
+            SnapshotAction::Rm { name } => commands::snapshot::rm(conn, &name, fmt),
+        },
+        Commands::Diff { from, to } => commands::snapshot::diff(conn, &from, &to, fmt),
+
+        Commands::Stats { trend, periods } => match trend {
+            Some(period) => commands::stats::trend(conn, period, periods, fmt),
+            None => commands::stats::run(conn, fmt),
+        },
+        Commands::Metrics => commands::metrics::run(conn, fmt),
         Commands::Summary => commands::summary::run(conn, fmt),
 
-        Commands::Export { export_format } => commands::export::run(conn, &export_format),
+        Commands::Export {
+            export_format,
+            status,
+            kind,
+            tag,
+            ids,
+            subtree,
+            since,
+            full,
+            out,
+        } => {
+            let filter = commands::export::ExportFilter {
+                statuses: status,
+                kinds: kind,
+                tags: tag,
+                ids: db::resolve_id_tokens(conn, &ids)?,
+                subtree: issue_arg(conn, subtree)?,
+                since,
+            };
+            commands::export::run(conn, &export_format, &filter, full, out.as_deref())
+        }
+
+        Commands::Import {
+            file,
+            import_format,
+            merge,
+            dry_run,
+        } => with_dry_run(conn, dry_run, || {
+            commands::import::run(conn, file, &import_format, merge, fmt)
+        }),
+
+        Commands::MergeDb { other, dry_run } => with_dry_run(conn, dry_run, || {
+            commands::merge_db::run(conn, db_path, &other, fmt)
+        }),
 
-        Commands::Import { file, merge } => commands::import::run(conn, file, merge, fmt),
+        Commands::Mirror { action } => match action {
+            MirrorAction::Write { path } => {
+                commands::mirror::write(conn, db_path, path.as_deref(), fmt)
+            }
+            MirrorAction::Read { path, dry_run } => with_dry_run(conn, dry_run, || {
+                commands::mirror::read(conn, db_path, path.as_deref(), fmt)
+            }),
+            MirrorAction::Merge { .. } => unreachable!(),
+        },
 
-        Commands::Doctor { fix } => commands::doctor::run(conn, fix, fmt),
+        Commands::Sync {
+            remote,
+            token,
+            prefer,
+        } => commands::sync::run(conn, &remote, token, prefer, fmt),
+
+        Commands::Doctor { fix, fix_cycles } => commands::doctor::run(conn, fix, fix_cycles, fmt),
 
         Commands::Ui {
             port,
@@ -488,11 +1039,24 @@ fn run_command(
             allow_dangerous,
         } => commands::ui::run(conn, db_path, port, no_open, once, allow_dangerous, fmt),
 
-        Commands::Config { action } => match action {
+        Commands::Watch { interval, count } => commands::watch::run(conn, interval, count, fmt),
+
+        Commands::Events {
+            since,
+            follow,
+            interval,
+            count,
+        } => commands::events::run(conn, since, follow, interval, count, fmt),
+
+        Commands::Config { action, .. } => match action {
             ConfigAction::List => commands::config::run_list(conn, fmt),
             ConfigAction::Get { key } => commands::config::run_get(conn, &key, fmt),
             ConfigAction::Set { key, value } => commands::config::run_set(conn, &key, &value, fmt),
             ConfigAction::Reset => commands::config::run_reset(conn, fmt),
+            ConfigAction::Export { export_format } => {
+                commands::config::run_export(conn, &export_format)
+            }
+            ConfigAction::Import { file } => commands::config::run_import(conn, &file, fmt),
         },
 
         Commands::Log {
@@ -500,7 +1064,7 @@ fn run_command(
             limit,
             since,
             agent,
-        } => commands::log::run(conn, id, limit, since, agent, fmt),
+        } => commands::log::run(conn, issue_arg(conn, id)?, limit, since, agent, fmt),
 
         Commands::Reindex => commands::reindex::run(conn, fmt),
 
@@ -508,13 +1072,25 @@ fn run_command(
             ids,
             to,
             relation_type,
-        } => commands::relate::run_relate_multi(conn, &ids, to, &relation_type, fmt),
+        } => commands::relate::run_relate_multi(
+            conn,
+            &ids,
+            db::resolve_issue_ref(conn, &to)?,
+            &relation_type,
+            fmt,
+        ),
 
         Commands::Unrelate {
             id,
             from,
             relation_type,
-        } => commands::relate::run_unrelate(conn, id, from, relation_type.as_deref(), fmt),
+        } => commands::relate::run_unrelate(
+            conn,
+            db::resolve_issue_ref(conn, &id)?,
+            db::resolve_issue_ref(conn, &from)?,
+            relation_type.as_deref(),
+            fmt,
+        ),
 
         Commands::Search {
             query,
@@ -541,23 +1117,76 @@ fn run_command(
         Commands::Claim {
             id,
             skill,
+            skills,
             agent,
             assigned_to,
-        } => commands::next::run(conn, true, id, skill, agent, assigned_to, fmt),
+        } => commands::next::run(
+            conn,
+            true,
+            issue_arg(conn, id)?,
+            skill,
+            skills,
+            agent,
+            assigned_to,
+            fmt,
+        ),
 
-        Commands::Assign { id, agent } => commands::assign::run_assign(conn, id, &agent, fmt),
+        Commands::Assign {
+            id,
+            agent,
+            auto,
+            max_load,
+            dry_run,
+        } => match (id, agent) {
+            (Some(id), Some(agent)) if !auto => {
+                commands::assign::run_assign(conn, db::resolve_issue_ref(conn, &id)?, &agent, fmt)
+            }
+            _ => with_dry_run(conn, dry_run, || {
+                commands::assign::run_auto(conn, max_load, fmt)
+            }),
+        },
+        Commands::Handoff {
+            id,
+            to,
+            summary,
+            agent,
+        } => commands::handoff::run(
+            conn,
+            db::resolve_issue_ref(conn, &id)?,
+            &to,
+            &summary,
+            &agent,
+            fmt,
+        ),
+        Commands::Review { action } => match action {
+            ReviewAction::List => commands::review::list(conn, fmt),
+            ReviewAction::Approve { id, reason } => {
+                commands::review::approve(conn, db::resolve_issue_ref(conn, &id)?, reason, fmt)
+            }
+            ReviewAction::Reject { id, note, agent } => commands::review::reject(
+                conn,
+                db::resolve_issue_ref(conn, &id)?,
+                &note,
+                &agent,
+                fmt,
+            ),
+        },
 
-        Commands::Unassign { id } => commands::assign::run_unassign(conn, id, fmt),
+        Commands::Unassign { id } => {
+            commands::assign::run_unassign(conn, db::resolve_issue_ref(conn, &id)?, fmt)
+        }
 
         Commands::Wip => commands::list::run(
             conn,
             &ListFilter {
                 statuses: vec!["in-progress".to_string()],
                 include_blocked: true,
+                project: commands::project::scope(),
                 ..ListFilter::default()
             },
             "urgency",
             None,
+            None,
             fmt,
         ),
 
@@ -571,14 +1200,16 @@ fn run_command(
                     &ListFilter {
                         include_blocked: true,
                         all,
+                        project: commands::project::scope(),
                         ..ListFilter::default()
                     },
                     "urgency",
                     None,
+                    None,
                     fmt,
                 )
             } else {
-                commands::get::run(conn, &ids, fmt)
+                commands::get::run(conn, &ids, &commands::get::GetOptions::default(), fmt)
             }
         }
     }
@@ -711,7 +1342,8 @@ mod tests {
                 args: vec![dup.to_string()],
                 reason_flag: None,
                 wontfix: true,
-                duplicate_of: Some(original),
+                duplicate_of: Some(original.to_string()),
+                dry_run: false,
             },
             &conn,
             std::path::Path::new("unused"),
@@ -731,4 +1363,52 @@ mod tests {
             "duplicate relation must still be recorded"
         );
     }
+
+    #[test]
+    fn dry_run_close_rolls_back_everything() {
+        let conn = db::open_test_db();
+        let insert = |title: &str| {
+            db::insert_issue(
+                &conn,
+                title,
+                "medium",
+                "task",
+                "",
+                &[],
+                &[],
+                &[],
+                "",
+                None,
+                "",
+            )
+            .expect("insert")
+            .id
+        };
+        let original = insert("original");
+        let blocker = insert("blocker");
+        let blocked = insert("blocked");
+        db::add_dependency(&conn, blocker, blocked).expect("depend");
+
+        run_command(
+            Commands::Close {
+                args: vec![blocker.to_string()],
+                reason_flag: Some("done".to_string()),
+                wontfix: false,
+                duplicate_of: Some(original.to_string()),
+                dry_run: true,
+            },
+            &conn,
+            std::path::Path::new("unused"),
+            Format::Compact,
+        )
+        .expect("dry-run close");
+
+        assert_eq!(db::get_issue(&conn, blocker).expect("get").status, "open");
+        assert!(db::is_blocked(&conn, blocked).expect("blocked"));
+        assert!(db::get_relations(&conn, blocker).expect("rels").is_empty());
+        assert!(
+            conn.is_autocommit(),
+            "dry run must not leave a transaction open"
+        );
+    }
 }
@@ -0,0 +1,125 @@
+//! Desktop notifications for humans supervising agent runs: when `close` or
+//! `undepend` unblocks issues and `notify.desktop` is `true`, raise one
+//! notification listing them.
+//!
+//! The notifier needs the `notify` cargo feature (it pulls in a D-Bus client
+//! on Linux). Without it, an enabled `notify.desktop` is a `REVIEW:` note and
+//! nothing else; notifications never fail a command.
+
+use crate::db;
+use crate::util;
+use rusqlite::Connection;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const DESKTOP_KEY: &str = "notify.desktop";
+
+/// Shown in the notification body before "and N more".
+const MAX_LISTED: usize = 5;
+
+static SUPPRESSED: AtomicBool = AtomicBool::new(false);
+
+/// Drop notifications for the rest of the process (`--dry-run`: the close
+/// that would unblock is rolled back).
+pub fn suppress() {
+    SUPPRESSED.store(true, Ordering::Relaxed);
+}
+
+/// Check a `notify.*` value for `config set`, returning it normalized.
+pub fn validate_setting(key: &str, value: &str) -> Result<String, String> {
+    match key {
+        DESKTOP_KEY => match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "on" | "yes" | "1" => Ok("true".to_string()),
+            "false" | "off" | "no" | "0" | "" => Ok("false".to_string()),
+            _ => Err("expected true or false".to_string()),
+        },
+        _ => Err(format!(
+            "unknown key{} (valid: {})",
+            util::did_you_mean(key, DESKTOP_KEY),
+            DESKTOP_KEY
+        )),
+    }
+}
+
+/// Notify about issues a committed command just unblocked, if enabled.
+pub fn unblocked(conn: &Connection, issues: &[(i64, String)]) {
+    if issues.is_empty() || SUPPRESSED.load(Ordering::Relaxed) {
+        return;
+    }
+    let enabled = db::config_get(conn, DESKTOP_KEY)
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true");
+    if !enabled {
+        return;
+    }
+    let (summary, body) = message(issues);
+    if let Err(e) = show(&summary, &body) {
+        eprintln!("REVIEW: desktop notification not shown: {}", e);
+    }
+}
+
+fn message(issues: &[(i64, String)]) -> (String, String) {
+    let summary = match issues {
+        [_] => "itr: 1 issue unblocked".to_string(),
+        _ => format!("itr: {} issues unblocked", issues.len()),
+    };
+    let mut lines: Vec<String> = issues
+        .iter()
+        .take(MAX_LISTED)
+        .map(|(id, title)| format!("#{} {}", id, title))
+        .collect();
+    if issues.len() > MAX_LISTED {
+        lines.push(format!("and {} more", issues.len() - MAX_LISTED));
+    }
+    (summary, lines.join("\n"))
+}
+
+#[cfg(feature = "notify")]
+fn show(summary: &str, body: &str) -> Result<(), String> {
+    notify_rust::Notification::new()
+        .appname("itr")
+        .summary(summary)
+        .body(body)
+        .show()
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "notify"))]
+fn show(_summary: &str, _body: &str) -> Result<(), String> {
+    Err(format!(
+        "this itr was built without the `notify` feature; rebuild with `--features notify` or `itr config set {} false`",
+        DESKTOP_KEY
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_lists_issues_and_truncates() {
+        let one = message(&[(3, "Wire API".to_string())]);
+        assert_eq!(
+            one,
+            (
+                "itr: 1 issue unblocked".to_string(),
+                "#3 Wire API".to_string()
+            )
+        );
+
+        let many: Vec<(i64, String)> = (1..=7).map(|i| (i, format!("t{}", i))).collect();
+        let (summary, body) = message(&many);
+        assert_eq!(summary, "itr: 7 issues unblocked");
+        assert_eq!(body.lines().count(), MAX_LISTED + 1);
+        assert!(body.ends_with("and 2 more"));
+    }
+
+    #[test]
+    fn desktop_setting_is_a_boolean() {
+        assert_eq!(validate_setting(DESKTOP_KEY, "On").unwrap(), "true");
+        assert_eq!(validate_setting(DESKTOP_KEY, "0").unwrap(), "false");
+        assert!(validate_setting(DESKTOP_KEY, "maybe").is_err());
+        assert!(validate_setting("notify.sound", "true").is_err());
+    }
+}
@@ -0,0 +1,244 @@
+//! Webhook delivery: when `webhook.url` is set, every issue change a command
+//! commits is sent there as one JSON `POST`.
+//!
+//! Changes are found the same way `itr watch` finds them: snapshot the issue
+//! table before the command, diff it afterwards. Every mutating command is
+//! covered without touching it, and a `--dry-run` (rolled back) sends nothing.
+//! Delivery never fails the command; problems are `REVIEW:` lines on stderr.
+
+use crate::commands::watch::{self, Snapshot, WatchEvent};
+use crate::db;
+use crate::util;
+use rusqlite::Connection;
+use serde_json::Value;
+use std::time::Duration;
+
+pub const URL_KEY: &str = "webhook.url";
+pub const TIMEOUT_KEY: &str = "webhook.timeout_ms";
+/// Per-request timeout (connect plus response) when `webhook.timeout_ms` is unset.
+pub const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+/// Check a `webhook.*` value for `config set`, returning it normalized. An
+/// empty URL is allowed and turns delivery off.
+pub fn validate_setting(key: &str, value: &str) -> Result<String, String> {
+    let value = value.trim();
+    match key {
+        URL_KEY => {
+            if value.is_empty() || value.starts_with("http://") || value.starts_with("https://") {
+                Ok(value.to_string())
+            } else {
+                Err("expected an http:// or https:// URL (empty disables webhooks)".to_string())
+            }
+        }
+        TIMEOUT_KEY => value
+            .parse::<u64>()
+            .ok()
+            .filter(|ms| *ms > 0)
+            .map(|ms| ms.to_string())
+            .ok_or_else(|| "expected a positive number of milliseconds".to_string()),
+        _ => {
+            let valid = format!("{}, {}", URL_KEY, TIMEOUT_KEY);
+            Err(format!(
+                "unknown key{} (valid: {})",
+                util::did_you_mean(key, &valid),
+                valid
+            ))
+        }
+    }
+}
+
+/// The issue table as it was before a command ran, plus where to send what
+/// the command changed.
+pub struct Webhook {
+    url: String,
+    timeout: Duration,
+    before: Snapshot,
+}
+
+impl Webhook {
+    /// Capture the current state when a webhook is configured; `None` when
+    /// it isn't (or the state can't be read, which the command will report).
+    pub fn prepare(conn: &Connection) -> Option<Webhook> {
+        let url = db::config_get(conn, URL_KEY).ok().flatten()?;
+        if url.trim().is_empty() {
+            return None;
+        }
+        let timeout_ms = match db::config_get(conn, TIMEOUT_KEY).ok().flatten() {
+            Some(v) => v.parse::<u64>().unwrap_or_else(|_| {
+                eprintln!(
+                    "REVIEW: '{}' for '{}' is not a number of milliseconds; using {}",
+                    v, TIMEOUT_KEY, DEFAULT_TIMEOUT_MS
+                );
+                DEFAULT_TIMEOUT_MS
+            }),
+            None => DEFAULT_TIMEOUT_MS,
+        };
+        let before = watch::snapshot(conn).ok()?;
+        Some(Webhook {
+            url: url.trim().to_string(),
+            timeout: Duration::from_millis(timeout_ms),
+            before,
+        })
+    }
+
+    /// POST one event per change since [`Webhook::prepare`]. Stops at the
+    /// first failed delivery so an unreachable endpoint costs one timeout,
+    /// not one per change.
+    pub fn deliver(self, conn: &Connection) {
+        let after = match watch::snapshot(conn) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("REVIEW: webhook skipped; could not read issues: {}", e);
+                return;
+            }
+        };
+        let events = watch::diff(&self.before, &after, &watch::now());
+        if events.is_empty() {
+            return;
+        }
+        let agent = ureq::AgentBuilder::new().timeout(self.timeout).build();
+        for (sent, event) in events.iter().enumerate() {
+            let issue = after
+                .get(&event.id)
+                .or_else(|| self.before.get(&event.id))
+                .map_or(Value::Null, |state| state.issue.clone());
+            if let Err(e) = post(&agent, &self.url, &payload(event, issue)) {
+                let skipped = events.len() - sent - 1;
+                let rest = if skipped > 0 {
+                    format!("; {} more event(s) not sent", skipped)
+                } else {
+                    String::new()
+                };
+                eprintln!(
+                    "REVIEW: webhook delivery failed for {} #{}: {}{}",
+                    event.event, event.id, e, rest
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// Event body: the `itr watch` event name as `action`, the issue as it is
+/// now (as it was, for `deleted`), and the changed fields.
+fn payload(event: &WatchEvent, issue: Value) -> Value {
+    serde_json::json!({
+        "action": event.event,
+        "id": event.id,
+        "issue": issue,
+        "fields": event.fields,
+        "at": event.at,
+    })
+}
+
+fn post(agent: &ureq::Agent, url: &str, body: &Value) -> Result<(), String> {
+    agent
+        .post(url)
+        .set("User-Agent", concat!("itr/", env!("CARGO_PKG_VERSION")))
+        .set("Content-Type", "application/json")
+        .send_string(&body.to_string())
+        .map(drop)
+        .map_err(|e| match e {
+            ureq::Error::Status(code, _) => format!("{}: HTTP {}", url, code),
+            ureq::Error::Transport(t) => t.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::path::Path;
+
+    /// Accept `n` requests, answer each with `status`, and return their bodies.
+    fn serve(n: usize, status: &'static str) -> (String, std::thread::JoinHandle<Vec<Value>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut bodies = Vec::new();
+            for _ in 0..n {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = v.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                bodies.push(serde_json::from_slice(&body).unwrap());
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                )
+                .unwrap();
+            }
+            bodies
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn posts_one_event_per_change() {
+        let conn = db::init_db(Path::new(":memory:")).unwrap();
+        assert!(Webhook::prepare(&conn).is_none());
+
+        let blocker = db::insert_test_issue(&conn, "blocker");
+        let blocked = db::insert_test_issue(&conn, "blocked");
+        db::add_dependency(&conn, blocker, blocked).unwrap();
+        let (url, server) = serve(2, "200 OK");
+        db::config_set(&conn, URL_KEY, &url).unwrap();
+
+        let hook = Webhook::prepare(&conn).unwrap();
+        db::update_issue_field(&conn, blocker, "status", "done").unwrap();
+        hook.deliver(&conn);
+
+        let bodies = server.join().unwrap();
+        assert_eq!(bodies[0]["action"], "closed");
+        assert_eq!(bodies[0]["issue"]["title"], "blocker");
+        assert_eq!(bodies[0]["issue"]["status"], "done");
+        assert!(bodies[0]["fields"]
+            .as_array()
+            .unwrap()
+            .contains(&Value::from("status")));
+        assert_eq!(bodies[1]["action"], "unblocked");
+        assert_eq!(bodies[1]["id"], blocked);
+    }
+
+    #[test]
+    fn failed_delivery_stops_and_keeps_the_change() {
+        let conn = db::init_db(Path::new(":memory:")).unwrap();
+        let (url, server) = serve(1, "500 Internal Server Error");
+        db::config_set(&conn, URL_KEY, &url).unwrap();
+        let hook = Webhook::prepare(&conn).unwrap();
+        db::insert_test_issue(&conn, "one");
+        db::insert_test_issue(&conn, "two");
+        hook.deliver(&conn);
+        // Only the first event reached the endpoint.
+        assert_eq!(server.join().unwrap().len(), 1);
+        assert_eq!(db::all_issues(&conn).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn settings_are_validated() {
+        assert_eq!(
+            validate_setting(URL_KEY, " https://hooks.example.com/x ").unwrap(),
+            "https://hooks.example.com/x"
+        );
+        assert_eq!(validate_setting(URL_KEY, "").unwrap(), "");
+        assert!(validate_setting(URL_KEY, "hooks.example.com").is_err());
+        assert_eq!(validate_setting(TIMEOUT_KEY, "2500").unwrap(), "2500");
+        assert!(validate_setting(TIMEOUT_KEY, "0").is_err());
+        assert!(validate_setting("webhook.secret", "x")
+            .unwrap_err()
+            .contains(URL_KEY));
+    }
+}
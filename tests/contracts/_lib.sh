@@ -45,6 +45,8 @@
 #       e.g. /tmp/tmp.XXXX, /var/folders/.../T/tmp.XXXX, and the per-case DB dir
 #   - localhost ports          -> 127.0.0.1:<PORT>  /  localhost:<PORT>
 #   - UI session tokens        -> token=<TOKEN>  /  X-ITR-Token: <TOKEN>
+#   - random v4 UUIDs          -> <UUID>
+#       issue/note `uuid` fields in export output are generated per database
 #   - version describe suffix  -> itr X.Y.Z
 #       strips the optional leading `v`, the `-<n>-g<hash>` git-describe suffix,
 #       a `+<hash>` build-metadata suffix, and a trailing `-dirty`.
@@ -78,6 +80,12 @@ if [ -z "${ITR:-}" ]; then
     esac
 fi
 
+# A developer's ~/.config/itr/config.toml (format.default, urgency overrides)
+# or ITR_FORMAT would change every snapshot; point the global config at an
+# empty dir.
+export XDG_CONFIG_HOME="$(mktemp -d)"
+unset ITR_FORMAT
+
 # ──────────────────────────────────────────────────────────────────────────
 # Fallback pass/fail/counters when run standalone. integration.sh defines the
 # real ones and they take precedence because it sources this file AFTER
@@ -123,6 +131,7 @@ contract_normalize() {
         -e 's#(localhost):[0-9]+#\1:<PORT>#g' \
         -e 's#(token=)[A-Za-z0-9._-]+#\1<TOKEN>#g' \
         -e 's#(X-ITR-Token: )[A-Za-z0-9._-]+#\1<TOKEN>#g' \
+        -e 's/[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}/<UUID>/g' \
         -e 's#(itr )v?[0-9]+\.[0-9]+\.[0-9]+(-[0-9]+-g[0-9a-f]+)?(\+[0-9a-f]+)?(-dirty)?#\1X.Y.Z#g'
 }
 
@@ -143,7 +152,7 @@ contract_normalize() {
 # Fix: pin created_at to an ANCIENT date (default 2000-01-01T00:00:00Z). Then
 # `days_since/10` clamps to 1.0, so `age` saturates to the CONSTANT
 # `urgency.age` coefficient (default 2.0) on every run, forever -- fully
-# deterministic, zero maintenance. The scoring math in src/urgency.rs is
+# deterministic, zero maintenance. The scoring math in crates/itr-core/src/urgency.rs is
 # unchanged; only the fixture's stored timestamp is pinned.
 #
 # updated_at is pinned to the same instant for parity. The urgency scorer reads
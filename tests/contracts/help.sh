@@ -46,11 +46,20 @@ snapshot help cmd_note_delete_help -- note-delete --help
 snapshot help cmd_note_update_help -- note-update --help
 snapshot help cmd_depend_help      -- depend --help
 snapshot help cmd_undepend_help    -- undepend --help
+snapshot help cmd_resolve_external_help -- resolve-external --help
+snapshot help cmd_component_help -- component --help
+snapshot help cmd_versions_help -- versions --help
+snapshot help cmd_repro_help -- repro --help
 snapshot help cmd_next_help        -- next --help
+snapshot help cmd_urgency_help     -- urgency --help
+snapshot help cmd_recompute_help   -- recompute --help
+snapshot help cmd_escalate_help    -- escalate --help
 snapshot help cmd_ready_help       -- ready --help
 snapshot help cmd_batch_help       -- batch --help
 snapshot help cmd_bulk_help        -- bulk --help
 snapshot help cmd_graph_help       -- graph --help
+snapshot help cmd_tree_help        -- tree --help
+snapshot help cmd_files_help       -- files --help
 snapshot help cmd_stats_help       -- stats --help
 snapshot help cmd_summary_help     -- summary --help
 snapshot help cmd_export_help      -- export --help
@@ -60,6 +69,9 @@ snapshot help cmd_ui_help          -- ui --help
 snapshot help cmd_config_help      -- config --help
 snapshot help cmd_agent_info_help  -- agent-info --help
 snapshot help cmd_skill_help       -- skill --help
+snapshot help cmd_git_help         -- git --help
+snapshot help cmd_tag_help         -- tag --help
+snapshot help cmd_branch_help      -- branch --help
 snapshot help cmd_schema_help      -- schema --help
 snapshot help cmd_upgrade_help     -- upgrade --help
 snapshot help cmd_claim_help       -- claim --help
@@ -107,6 +119,14 @@ snapshot help config_reset_help -- config reset --help
 snapshot help skill_install_help -- skill install --help
 snapshot help skill_path_help    -- skill path --help
 
+snapshot help git_install_hooks_help -- git install-hooks --help
+snapshot help git_scan_help          -- git scan --help
+
+snapshot help tag_list_help   -- tag list --help
+snapshot help tag_rename_help -- tag rename --help
+snapshot help tag_rm_help     -- tag rm --help
+snapshot help tag_describe_help -- tag describe --help
+
 # ──────────────────────────────────────────────────────────────────────────
 # 4) The no-database INFORMATIONAL commands themselves (not just their --help).
 #    These run without an existing DB and form the agent onboarding contract:
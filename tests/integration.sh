@@ -97,13 +97,19 @@ assert_exit() {
 }
 
 jq_val() {
-    echo "$1" | python3 -c "import sys,json; d=json.load(sys.stdin); print($2)"
+    # Successful responses arrive in the {"api_version","ok","data"} envelope;
+    # expressions see the data. Error objects and --raw output pass through.
+    echo "$1" | python3 -c "import sys,json; d=json.load(sys.stdin); d=d['data'] if isinstance(d, dict) and d.get('ok') is True else d; print($2)"
 }
 
 # ─────────────────────────────────────────────
 # Setup
 # ─────────────────────────────────────────────
 WORKDIR=$(mktemp -d)
+# Keep the developer's own ~/.config/itr/config.toml (format.default,
+# db.fallback, urgency overrides) and ITR_FORMAT out of every assertion below.
+export XDG_CONFIG_HOME="$WORKDIR/.config"
+unset ITR_FORMAT
 
 # Background UI server pids (set by the `--- ui ---` section, cleared again
 # after its own kill/wait). Tracked globally so the single EXIT trap below can
@@ -246,8 +252,8 @@ assert_eq "add third issue id" "3" "$(jq_val "$OUT" "d['id']")"
 
 ADD_BLOCK_DIR=$(mktemp -d)
 $ITR init --db "$ADD_BLOCK_DIR/.itr.db" >/dev/null
-ADD_BLOCKER_A=$($ITR --db "$ADD_BLOCK_DIR/.itr.db" add "Add blocker A" -f json | python3 -c "import sys,json; print(json.load(sys.stdin)['id'])")
-ADD_BLOCKER_B=$($ITR --db "$ADD_BLOCK_DIR/.itr.db" add "Add blocker B" -f json | python3 -c "import sys,json; print(json.load(sys.stdin)['id'])")
+ADD_BLOCKER_A=$($ITR --db "$ADD_BLOCK_DIR/.itr.db" add "Add blocker A" -f json | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['id'])")
+ADD_BLOCKER_B=$($ITR --db "$ADD_BLOCK_DIR/.itr.db" add "Add blocker B" -f json | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['id'])")
 
 OUT=$($ITR --db "$ADD_BLOCK_DIR/.itr.db" add "Blocked by two" --blocked-by "$ADD_BLOCKER_A,$ADD_BLOCKER_B" -f json)
 assert_eq "add --blocked-by creates multi dependencies" "[$ADD_BLOCKER_A, $ADD_BLOCKER_B]" "$(jq_val "$OUT" "sorted(d['blocked_by'])")"
@@ -261,7 +267,7 @@ assert_eq "add --blocked-by invalid token keeps valid dependencies" "[$ADD_BLOCK
 ADD_COUNT_BEFORE=$(python3 -c "import sqlite3,sys; print(sqlite3.connect(sys.argv[1]).execute('SELECT COUNT(*) FROM issues').fetchone()[0])" "$ADD_BLOCK_DIR/.itr.db")
 ADD_MISSING_EXIT=0
 ADD_MISSING_OUT=$($ITR --db "$ADD_BLOCK_DIR/.itr.db" add "Missing blocker should rollback" --blocked-by 999 -f json 2>&1) || ADD_MISSING_EXIT=$?
-assert_eq "add --blocked-by missing id exits 1" "1" "$ADD_MISSING_EXIT"
+assert_eq "add --blocked-by missing id exits 3" "3" "$ADD_MISSING_EXIT"
 assert_contains "add --blocked-by missing id reports not found" "Issue 999 not found" "$ADD_MISSING_OUT"
 ADD_COUNT_AFTER=$(python3 -c "import sqlite3,sys; print(sqlite3.connect(sys.argv[1]).execute('SELECT COUNT(*) FROM issues').fetchone()[0])" "$ADD_BLOCK_DIR/.itr.db")
 assert_eq "add --blocked-by missing id rolls back issue" "$ADD_COUNT_BEFORE" "$ADD_COUNT_AFTER"
@@ -302,7 +308,21 @@ assert_contains "get compact has ID" "ID:1" "$COMPACT"
 assert_contains "get compact has TITLE" "TITLE: Fix login bug" "$COMPACT"
 assert_contains "get compact has URGENCY BREAKDOWN" "URGENCY BREAKDOWN" "$COMPACT"
 
-assert_exit "get nonexistent exits 1" "1" $ITR get 999
+assert_exit "get nonexistent exits 3" "3" $ITR get 999
+
+OUT=$($ITR get 1 --no-notes -f json)
+assert_eq "get --no-notes drops notes" "[]" "$(jq_val "$OUT" "d['notes']")"
+assert_eq "get --no-notes reports notes_omitted" "True" "$(jq_val "$OUT" "'notes_omitted' in d")"
+assert_exit "get --no-notes conflicts with --notes-last" "2" $ITR get 1 --no-notes --notes-last 1
+
+OUT=$($ITR get fix-login-bug -f json)
+assert_eq "get resolves a title slug" "1" "$(jq_val "$OUT" "d['id']")"
+OUT=$($ITR get "add logout" -f json)
+assert_eq "get resolves a title prefix" "Add logout endpoint" "$(jq_val "$OUT" "d['title']")"
+assert_exit "get ambiguous name exits 6" "6" $ITR get soft
+OUT=$($ITR get soft -f json 2>&1 || true)
+assert_eq "get ambiguous name reports AMBIGUOUS" "AMBIGUOUS" "$(jq_val "$OUT" "d['code']")"
+assert_eq "get ambiguous name lists candidates" "['Soft priority', 'Soft kind']" "$(jq_val "$OUT" "[c['title'] for c in d['candidates']]")"
 
 # ─────────────────────────────────────────────
 echo "--- get (multi-ID batch, #136) ---"
@@ -313,6 +333,8 @@ OUT=$($ITR get 1,2 -f json)
 assert_eq "multi-get returns 2 details" "2" "$(jq_val "$OUT" "len(d)")"
 assert_eq "multi-get preserves request order" "[1, 2]" "$(jq_val "$OUT" "[i['id'] for i in d]")"
 assert_eq "multi-get elements are full details" "True" "$(jq_val "$OUT" "all('urgency_breakdown' in i and 'notes' in i for i in d)")"
+OUT=$($ITR get 2 --ids 1 -f json)
+assert_eq "multi-get --ids adds to positional IDs" "[2, 1]" "$(jq_val "$OUT" "[i['id'] for i in d]")"
 
 # Repeated-argument form is equivalent to the comma form
 OUT=$($ITR get 2 1 -f json)
@@ -335,7 +357,7 @@ assert_exit "multi-get partial missing exits 0" "0" $ITR get 1,999 -f json
 
 # All-missing: empty result ([] in JSON), one REVIEW note per ID, exit 0
 OUT=$($ITR get 998,999 -f json 2>"$MG_ERR")
-assert_eq "multi-get all missing returns empty array" "[]" "$OUT"
+assert_eq "multi-get all missing returns empty array" '{"api_version":1,"ok":true,"data":[]}' "$OUT"
 MG_REVIEWS=$(grep -c "REVIEW" "$MG_ERR" || true)
 assert_eq "multi-get all missing emits one REVIEW per id" "2" "$MG_REVIEWS"
 assert_exit "multi-get all missing exits 0" "0" $ITR get 998,999
@@ -386,6 +408,13 @@ assert_eq "list filter by tag" "1" "$COUNT"
 PRETTY=$($ITR list -f pretty)
 assert_contains "list pretty has header" "Status" "$PRETTY"
 
+# defaults.list.* fill in omitted flags; explicit flags win
+$ITR config set defaults.list.kind bug >/dev/null
+$ITR config set defaults.list.limit 1 >/dev/null
+assert_eq "list applies defaults.list.kind and limit" "1" "$(jq_val "$($ITR list -f json)" "len(d)")"
+assert_eq "list flags override defaults.list.*" "2" "$(jq_val "$($ITR list -n 5 -f json)" "len(d)")"
+$ITR config reset >/dev/null
+
 # Sort by urgency — first issue should be highest urgency
 FIRST_ID=$(jq_val "$($ITR list --sort urgency -f json)" "d[0]['id']")
 assert_eq "list sorted by urgency, critical first" "4" "$FIRST_ID"
@@ -430,7 +459,7 @@ OUT=$($ITR depend 3 --on 1)
 pass "depend idempotent re-add succeeds"
 
 # Cycle detection
-assert_exit "depend cycle detection" "1" $ITR depend 1 --on 3
+assert_exit "depend cycle detection" "4" $ITR depend 1 --on 3
 
 # Undepend
 $ITR undepend 3 --on 1 >/dev/null
@@ -463,7 +492,7 @@ NOTES_COUNT=$(jq_val "$OUT" "len(d['notes'])")
 EXPECTED=$((BEFORE_COUNT + 1))
 assert_eq "note appended via arg" "$EXPECTED" "$NOTES_COUNT"
 
-assert_exit "note on nonexistent issue" "1" $ITR note 999 "nope"
+assert_exit "note on nonexistent issue" "3" $ITR note 999 "nope"
 
 # ─────────────────────────────────────────────
 echo "--- next ---"
@@ -639,6 +668,33 @@ DOT=$($ITR graph -f pretty)
 assert_contains "graph DOT output" "digraph itr" "$DOT"
 assert_contains "graph DOT has edges" "->" "$DOT"
 
+GRAPH_DIR=$(mktemp -d)
+GRAPH_DB="$GRAPH_DIR/.itr.db"
+ITR_DB_PATH="$GRAPH_DB" $ITR init >/dev/null
+for t in a b c d e; do ITR_DB_PATH="$GRAPH_DB" $ITR add "$t" >/dev/null; done
+ITR_DB_PATH="$GRAPH_DB" $ITR depend 2 --on 1 >/dev/null
+ITR_DB_PATH="$GRAPH_DB" $ITR depend 3 --on 2 >/dev/null
+ITR_DB_PATH="$GRAPH_DB" $ITR depend 4 --on 3 >/dev/null
+OUT=$(ITR_DB_PATH="$GRAPH_DB" $ITR graph --around 2 -f json)
+assert_eq "graph --around keeps one hop both ways" "[1, 2, 3]" "$(jq_val "$OUT" "[n['id'] for n in d['nodes']]")"
+OUT=$(ITR_DB_PATH="$GRAPH_DB" $ITR graph --around 2 --depth 2 -f json)
+assert_eq "graph --depth widens the neighborhood" "4" "$(jq_val "$OUT" "len(d['nodes'])")"
+assert_exit "graph --around missing issue" "3" env ITR_DB_PATH="$GRAPH_DB" $ITR graph --around 99
+assert_exit "graph --depth requires --around" "2" env ITR_DB_PATH="$GRAPH_DB" $ITR graph --depth 2
+OUT=$(ITR_DB_PATH="$GRAPH_DB" $ITR graph --ascii)
+assert_contains "graph --ascii layers blockers first" "── layer 0 ──" "$OUT"
+assert_contains "graph --ascii draws edges" "└─▶ #4 d" "$OUT"
+assert_eq "graph --ascii puts the chain end in layer 3" "#4 d (open, blocked)" "$(echo "$OUT" | sed -n '/layer 3/{n;p}')"
+ITR_DB_PATH="$GRAPH_DB" $ITR depend 3 --on 1 >/dev/null
+OUT=$(ITR_DB_PATH="$GRAPH_DB" $ITR graph --reduce -f json)
+assert_eq "graph --reduce drops the implied edge" "[[1, 2], [2, 3], [3, 4]]" "$(jq_val "$OUT" "sorted([e['from'], e['to']] for e in d['edges'])")"
+OUT=$(ITR_DB_PATH="$GRAPH_DB" $ITR graph --roots-only -f json)
+assert_eq "graph --roots-only" "[1, 5]" "$(jq_val "$OUT" "[n['id'] for n in d['nodes']]")"
+OUT=$(ITR_DB_PATH="$GRAPH_DB" $ITR graph --leaves-only -f json)
+assert_eq "graph --leaves-only" "[4, 5]" "$(jq_val "$OUT" "[n['id'] for n in d['nodes']]")"
+assert_exit "graph --roots-only conflicts with --leaves-only" "2" env ITR_DB_PATH="$GRAPH_DB" $ITR graph --roots-only --leaves-only
+rm -rf "$GRAPH_DIR"
+
 # ─────────────────────────────────────────────
 echo "--- export/import ---"
 # ─────────────────────────────────────────────
@@ -652,6 +708,19 @@ EXPORT_LINES=$(wc -l < "$EXPORT_FILE" | tr -d ' ')
 $ITR export --export-format json > "$WORKDIR/export.json"
 python3 -c "import json; json.load(open('$WORKDIR/export.json'))" && pass "export json is valid JSON" || fail "export json is valid JSON" "parse error"
 
+# Org export: one heading per issue
+$ITR export --export-format org > "$WORKDIR/export.org"
+assert_contains "export org declares TODO keywords" "#+TODO: TODO IN-PROGRESS REVIEW | DONE WONTFIX" "$(cat "$WORKDIR/export.org")"
+ORG_HEADINGS=$(grep -c '^\*\+ ' "$WORKDIR/export.org" || true)
+assert_eq "export org has one heading per issue" "$EXPORT_LINES" "$ORG_HEADINGS"
+
+# HTML export: a static site with one page per issue
+OUT=$($ITR export --export-format html --out "$WORKDIR/site")
+assert_contains "export html reports the index" "EXPORTED: $EXPORT_LINES issues" "$OUT"
+HTML_PAGES=$(ls "$WORKDIR/site/issues" | wc -l | tr -d ' ')
+assert_eq "export html writes one page per issue" "$EXPORT_LINES" "$HTML_PAGES"
+assert_contains "export html index has the issue table" '<table id="issues">' "$(cat "$WORKDIR/site/index.html")"
+
 # Import into fresh db
 IMPORT_DIR=$(mktemp -d)
 cd "$IMPORT_DIR"
@@ -664,10 +733,34 @@ assert_eq "import count matches export" "$EXPORT_LINES" "$IMPORTED"
 IMPORT_TOTAL=$(jq_val "$($ITR stats -f json)" "d['total']")
 assert_eq "import total matches" "$EXPORT_LINES" "$IMPORT_TOTAL"
 
-# Merge mode — re-import should skip all
+# Merge mode — re-import finds every issue by UUID and adds nothing
 OUT=$($ITR import --file "$EXPORT_FILE" --merge -f json)
-SKIPPED=$(jq_val "$OUT" "d['skipped']")
-assert_eq "import --merge skips existing" "$EXPORT_LINES" "$SKIPPED"
+MERGED=$(jq_val "$OUT" "d['merged']")
+assert_eq "import --merge matches existing by UUID" "$EXPORT_LINES" "$MERGED"
+assert_eq "import --merge adds no duplicates" "$EXPORT_LINES" "$(jq_val "$($ITR stats -f json)" "d['total']")"
+
+# Filtered export
+OUT=$($ITR export --ids 1 --status open --status in-progress --status done --status wontfix | wc -l | tr -d ' ')
+assert_eq "export --ids narrows to one issue" "1" "$OUT"
+OUT=$($ITR export --since 2999-01-01 | wc -l | tr -d ' ')
+assert_eq "export --since in the future is empty" "0" "$OUT"
+assert_exit "export --subtree missing root fails" "3" $ITR export --subtree 99999
+
+# Dry run validates the whole input and writes nothing
+python3 -c "
+import json, sys
+for id, status, blockers in [(900, 'bogus', []), (901, 'open', [999])]:
+    item = json.loads(open(sys.argv[1]).readline())
+    item['issue'].update(id=id, status=status, parent_id=None)
+    item.pop('uuid', None)
+    item['blocked_by'] = blockers
+    print(json.dumps(item))
+" "$EXPORT_FILE" > "$IMPORT_DIR/invalid.jsonl"
+OUT=$($ITR import --file "$IMPORT_DIR/invalid.jsonl" --dry-run -f json 2>"$IMPORT_DIR/err")
+assert_eq "import --dry-run reports validation problems" "2" "$(jq_val "$OUT" "len(d['problems'])")"
+assert_eq "import --dry-run counts created and skipped" "1 1" "$(jq_val "$OUT" "f\"{d['created']} {d['skipped']}\"")"
+assert_contains "import --dry-run names the bad status" "REVIEW: import: issue 900: Invalid value for status: 'bogus'" "$(cat "$IMPORT_DIR/err")"
+assert_eq "import --dry-run writes nothing" "$EXPORT_LINES" "$(jq_val "$($ITR stats -f json)" "d['total']")"
 
 cd "$WORKDIR"
 rm -rf "$IMPORT_DIR"
@@ -690,6 +783,41 @@ $ITR config reset >/dev/null
 OUT=$($ITR config get urgency.priority.critical -f json)
 assert_eq "config reset restores default" "10" "$(jq_val "$OUT" "d['value']")"
 
+# The global file sits under the database's own settings.
+$ITR config --global set urgency.priority.critical 12 >/dev/null
+OUT=$($ITR config get urgency.priority.critical -f json)
+assert_eq "global config fills unset keys" "12" "$(jq_val "$OUT" "d['value']")"
+assert_contains "config list marks global values" "urgency.priority.critical=12 (global)" "$($ITR config list)"
+$ITR config set urgency.priority.critical 14 >/dev/null
+OUT=$($ITR config get urgency.priority.critical -f json)
+assert_eq "database config wins over global" "14" "$(jq_val "$OUT" "d['value']")"
+$ITR config reset >/dev/null
+$ITR config --global set format.default json >/dev/null
+assert_contains "global format.default applies" '"key":"urgency.priority.critical"' "$($ITR config get urgency.priority.critical)"
+assert_contains "-f still wins over format.default" "urgency.priority.critical=12" "$($ITR config get urgency.priority.critical -f compact)"
+assert_contains "ITR_FORMAT wins over format.default" "urgency.priority.critical=12" "$(ITR_FORMAT=compact $ITR config get urgency.priority.critical)"
+$ITR config set format.default compact >/dev/null
+assert_contains "database format.default wins over global" "urgency.priority.critical=12" "$($ITR config get urgency.priority.critical)"
+assert_contains "-f wins over ITR_FORMAT" "urgency.priority.critical=12" "$(ITR_FORMAT=json $ITR config get urgency.priority.critical -f compact)"
+$ITR config reset >/dev/null
+$ITR --yes config --global reset >/dev/null
+
+# export/import: a tuned setting travels to a fresh database via .itr.toml.
+$ITR config set urgency.blocking 9 >/dev/null
+CFG_DIR=$(mktemp -d)
+$ITR config export > "$CFG_DIR/.itr.toml"
+assert_contains "config export writes TOML tables" "[urgency]" "$(cat "$CFG_DIR/.itr.toml")"
+OUT=$(cd "$CFG_DIR" && $ITR init -f json)
+assert_eq "init applies a checked-in .itr.toml" "1" "$(jq_val "$OUT" "d['config_applied']")"
+OUT=$(ITR_DB_PATH="$CFG_DIR/.itr.db" $ITR config get urgency.blocking -f json)
+assert_eq "imported setting persists" "9" "$(jq_val "$OUT" "d['value']")"
+echo '{"urgency.blocking": "3"}' > "$CFG_DIR/tuned.json"
+OUT=$(ITR_DB_PATH="$CFG_DIR/.itr.db" $ITR config import "$CFG_DIR/tuned.json" -f json)
+assert_eq "config import reads JSON" "3" "$(jq_val "$OUT" "d['set']['urgency.blocking']")"
+rm -rf "$CFG_DIR"
+$ITR config reset >/dev/null
+[ ! -e "$XDG_CONFIG_HOME/itr/config.toml" ] && pass "config --global reset removes the file" || fail "config --global reset" "file still present"
+
 # ─────────────────────────────────────────────
 echo "--- doctor ---"
 # ─────────────────────────────────────────────
@@ -702,6 +830,70 @@ set -e
 # Doctor may exit 1 if problems found (done blockers from earlier tests)
 [ "$DOC_EXIT" -eq 0 ] || [ "$DOC_EXIT" -eq 1 ] && pass "doctor runs successfully" || fail "doctor runs" "exit $DOC_EXIT"
 
+# Open issues whose titles differ only in case/punctuation are flagged
+DOC_DUP_DIR=$(mktemp -d)
+ITR_DB_PATH="$DOC_DUP_DIR/.itr.db" $ITR init >/dev/null
+ITR_DB_PATH="$DOC_DUP_DIR/.itr.db" $ITR add "Fix login bug" >/dev/null
+ITR_DB_PATH="$DOC_DUP_DIR/.itr.db" $ITR add "fix login-bug." >/dev/null
+OUT=$(ITR_DB_PATH="$DOC_DUP_DIR/.itr.db" $ITR doctor -f json 2>/dev/null || true)
+assert_eq "doctor flags near-duplicate open titles" "['duplicate_title']" "$(jq_val "$OUT" "[p['kind'] for p in d['problems']]")"
+rm -rf "$DOC_DUP_DIR"
+
+# Cycles (only reachable by writing around `depend`) list their full path,
+# and --fix-cycles drops the suggested edge
+if command -v sqlite3 >/dev/null 2>&1; then
+  DOC_CYC_DIR=$(mktemp -d)
+  ITR_DB_PATH="$DOC_CYC_DIR/.itr.db" $ITR init >/dev/null
+  for t in A B C; do ITR_DB_PATH="$DOC_CYC_DIR/.itr.db" $ITR add "Cycle $t" >/dev/null; done
+  ITR_DB_PATH="$DOC_CYC_DIR/.itr.db" $ITR depend 2 --on 1 >/dev/null
+  ITR_DB_PATH="$DOC_CYC_DIR/.itr.db" $ITR depend 3 --on 2 >/dev/null
+  if sqlite3 "$DOC_CYC_DIR/.itr.db" "INSERT INTO dependencies (blocker_id, blocked_id) VALUES (3, 1)" 2>/dev/null; then
+    OUT=$(ITR_DB_PATH="$DOC_CYC_DIR/.itr.db" $ITR doctor 2>/dev/null || true)
+    assert_contains "doctor prints the full cycle path" "Cycle: 1 -> 2 -> 3 -> 1; suggested fix: itr undepend 2 --on 1" "$OUT"
+    OUT=$(ITR_DB_PATH="$DOC_CYC_DIR/.itr.db" $ITR doctor --fix-cycles interactive -f json 2>/dev/null </dev/null || true)
+    assert_eq "doctor --fix-cycles without a tty drops the suggested edge" "True" "$(jq_val "$OUT" "d['clean']")"
+    OUT=$(ITR_DB_PATH="$DOC_CYC_DIR/.itr.db" $ITR get 2 -f json 2>/dev/null)
+    assert_eq "doctor --fix-cycles keeps the other edges" "[] [3]" "$(jq_val "$OUT" "f\"{d['blocked_by']} {d['blocks']}\"")"
+  fi
+  rm -rf "$DOC_CYC_DIR"
+fi
+
+# Claims held by an agent unseen for a day are released by --fix
+if command -v sqlite3 >/dev/null 2>&1; then
+  DOC_CLAIM_DIR=$(mktemp -d)
+  ITR_DB_PATH="$DOC_CLAIM_DIR/.itr.db" $ITR init >/dev/null
+  ITR_DB_PATH="$DOC_CLAIM_DIR/.itr.db" $ITR add "Held work" >/dev/null
+  ITR_DB_PATH="$DOC_CLAIM_DIR/.itr.db" $ITR agent register ghost >/dev/null
+  ITR_DB_PATH="$DOC_CLAIM_DIR/.itr.db" ITR_AGENT=ghost $ITR claim 1 >/dev/null 2>&1
+  if sqlite3 "$DOC_CLAIM_DIR/.itr.db" "UPDATE agents SET last_seen_at = '2020-01-01T00:00:00Z'" 2>/dev/null; then
+    OUT=$(ITR_DB_PATH="$DOC_CLAIM_DIR/.itr.db" $ITR doctor 2>/dev/null || true)
+    assert_contains "doctor flags abandoned claims" "[abandoned_claim] [fixable] Issue 1" "$OUT"
+    ITR_DB_PATH="$DOC_CLAIM_DIR/.itr.db" $ITR doctor --fix >/dev/null 2>&1 || true
+    OUT=$(ITR_DB_PATH="$DOC_CLAIM_DIR/.itr.db" $ITR get 1 -f json 2>/dev/null)
+    assert_eq "doctor --fix releases abandoned claims" "open " "$(jq_val "$OUT" "d['status'] + ' ' + d['assigned_to']")"
+  fi
+  rm -rf "$DOC_CLAIM_DIR"
+fi
+
+# Corrupt list JSON is surfaced with its raw value, then reset by --fix
+# (needs a sqlite3 CLI new enough to load the FTS5 triggers)
+if command -v sqlite3 >/dev/null 2>&1; then
+  DOC_JSON_DIR=$(mktemp -d)
+  ITR_DB_PATH="$DOC_JSON_DIR/.itr.db" $ITR init >/dev/null
+  ITR_DB_PATH="$DOC_JSON_DIR/.itr.db" $ITR add "Corrupt files" --files src/a.rs >/dev/null
+  if sqlite3 "$DOC_JSON_DIR/.itr.db" "UPDATE issues SET files='[src/a.rs' WHERE id=1" 2>/dev/null; then
+    set +e
+    OUT=$(ITR_DB_PATH="$DOC_JSON_DIR/.itr.db" $ITR doctor 2>/dev/null)
+    set -e
+    assert_contains "doctor flags malformed files JSON" "[malformed_json] [fixable] Issue 1 files" "$OUT"
+    assert_contains "doctor shows the raw value" '"[src/a.rs"' "$OUT"
+    ITR_DB_PATH="$DOC_JSON_DIR/.itr.db" $ITR doctor --fix >/dev/null 2>&1 || true
+    OUT=$(ITR_DB_PATH="$DOC_JSON_DIR/.itr.db" $ITR get 1 -f json 2>/dev/null)
+    assert_eq "doctor --fix resets malformed files to []" "[]" "$(jq_val "$OUT" "d['files']")"
+  fi
+  rm -rf "$DOC_JSON_DIR"
+fi
+
 # ─────────────────────────────────────────────
 echo "--- schema ---"
 # ─────────────────────────────────────────────
@@ -804,7 +996,7 @@ echo "--- upgrade ---"
 
 # Basic smoke test with --no-pull and explicit source dir
 OUT=$($ITR upgrade --no-pull --source-dir "$SCRIPT_DIR" -f json 2>&1) || true
-if echo "$OUT" | python3 -c "import sys,json; d=json.load(sys.stdin); print(d.get('action',''))" 2>/dev/null | grep -q "upgrade"; then
+if echo "$OUT" | python3 -c "import sys,json; d=json.load(sys.stdin)['data']; print(d.get('action',''))" 2>/dev/null | grep -q "upgrade"; then
     pass "upgrade --no-pull succeeds"
 else
     # May fail in test env due to permissions, that's OK
@@ -843,7 +1035,7 @@ ALL_COUNT=$(jq_val "$OUT" "len(d)")
 
 # Empty result
 OUT=$($ITR search "zzz_nonexistent_term_zzz" -f json)
-assert_eq "search empty result returns []" "[]" "$OUT"
+assert_eq "search empty result returns []" '{"api_version":1,"ok":true,"data":[]}' "$OUT"
 assert_exit "search empty result exits 0" "0" $ITR search "zzz_nonexistent_term_zzz"
 
 # Compact format has MATCHED field
@@ -859,7 +1051,7 @@ assert_eq "search --limit 2" "2" "$COUNT"
 echo "--- exit codes ---"
 # ─────────────────────────────────────────────
 
-assert_exit "exit 1 on not found" "1" $ITR get 999
+assert_exit "exit 3 on not found" "3" $ITR get 999
 
 # Empty result set should exit 0 (not an error)
 EMPTY_DIR=$(mktemp -d)
@@ -870,12 +1062,12 @@ assert_exit "exit 0 on empty ready" "0" $ITR ready
 assert_exit "exit 0 on empty next" "0" $ITR next
 # Verify empty JSON output
 OUT=$($ITR list -f json)
-assert_eq "empty list json returns []" "[]" "$OUT"
+assert_eq "empty list json returns []" '{"api_version":1,"ok":true,"data":[]}' "$OUT"
 cd "$WORKDIR"
 rm -rf "$EMPTY_DIR"
 
 # No database should exit 1
-assert_exit "exit 1 on no database" "1" env -u ITR_DB_PATH $ITR list --db /nonexistent/path/.itr.db
+assert_exit "exit 7 on no database" "7" env -u ITR_DB_PATH $ITR list --db /nonexistent/path/.itr.db
 
 # ─────────────────────────────────────────────
 echo "--- ITR_DB_PATH env var ---"
@@ -1165,6 +1357,56 @@ OUT=$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR log 1 -f json)
 AGENT=$(jq_val "$OUT" "[e for e in d if e['field']=='status'][0]['agent']")
 assert_eq "log records ITR_AGENT" "test-logger" "$AGENT"
 
+# agent.name attributes writes when ITR_AGENT is unset; ITR_AGENT still wins
+ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR config set agent.name cfg-agent >/dev/null
+env -u ITR_AGENT ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR update 1 --status open -f json >/dev/null
+OUT=$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR log 1 -f json)
+assert_eq "log records config agent.name" "cfg-agent" "$(jq_val "$OUT" "[e for e in d if e['field']=='status'][-1]['agent']")"
+OUT=$(env -u ITR_AGENT ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR note 1 "from config" -f json)
+assert_eq "note falls back to agent.name" "cfg-agent" "$(jq_val "$OUT" "d['agent']")"
+OUT=$(ITR_AGENT=env-agent ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR note 1 "from env" -f json)
+assert_eq "ITR_AGENT wins over agent.name" "env-agent" "$(jq_val "$OUT" "d['agent']")"
+ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR config reset >/dev/null
+
+# Agent registry
+OUT=$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR agent register reg-bot --skill Rust,sql -f json)
+assert_eq "agent register lowercases skills" "rust,sql" "$(jq_val "$OUT" "','.join(d['agent']['skills'])")"
+ASSIGN_ERR=$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR assign 1 someone-else 2>&1 >/dev/null)
+assert_contains "assign flags an unregistered agent" "not registered" "$ASSIGN_ERR"
+ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR assign 1 reg-bot >/dev/null 2>&1
+OUT=$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR agent show reg-bot -f json)
+assert_eq "agent show lists assigned issues" "[1]" "$(jq_val "$OUT" "d['assigned']")"
+assert_contains "agent list shows the agent" '"reg-bot" SKILLS:rust,sql' "$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR agent list)"
+
+# Handoff
+OUT=$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR handoff 1 --to next-bot "parser done; tests left" --agent reg-bot -f json 2>/dev/null)
+assert_eq "handoff reassigns" "next-bot" "$(jq_val "$OUT" "d['assigned_to']")"
+assert_eq "handoff writes a handoff note" "handoff" "$(jq_val "$OUT" "d['notes'][-1]['kind']")"
+OUT=$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR ready --assigned-to next-bot -f json)
+assert_contains "handoff shows in the target's ready" '"id":1,' "$OUT"
+
+# Shell completions
+assert_contains "completions bash registers itr" "complete -o nospace" "$($ITR completions bash)"
+OUT=$(cd "$LOG_DIR" && _CLAP_IFS=$'\n' _CLAP_COMPLETE_INDEX=2 COMPLETE=bash $ITR -- itr get "")
+assert_eq "completion offers issue IDs from the database" "1" "${OUT%%$'\n'*}"
+OUT=$(cd "$LOG_DIR" && _CLAP_IFS=$'\n' _CLAP_COMPLETE_INDEX=3 COMPLETE=bash $ITR -- itr config get "agent")
+assert_eq "completion offers config keys" "agent.name" "$OUT"
+
+# Brief
+OUT=$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR brief 1 --notes 1 -f json)
+assert_eq "brief keeps the last N notes" "1" "$(jq_val "$OUT" "len(d['notes'])")"
+[ "$(jq_val "$OUT" "d['notes_total']")" -gt 1 ] && pass "brief counts all notes" || fail "brief counts all notes" "$OUT"
+assert_contains "brief compact has a notes section" "--- NOTES (last 1 of" "$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR brief 1 --notes 1)"
+
+# Sessions
+ITR_AGENT=sess-bot ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR session start >/dev/null
+ITR_AGENT=sess-bot ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR note 1 "session work" >/dev/null
+OUT=$(ITR_AGENT=sess-bot ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR session end --summary "wrapped up" -f json)
+assert_eq "session end records touched issues" "[1]" "$(jq_val "$OUT" "d['issues']")"
+OUT=$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR show 1 -f json)
+assert_eq "session end writes a session note" "session" "$(jq_val "$OUT" "d['notes'][-1]['kind']")"
+assert_contains "session list is the standup" 'AGENT:sess-bot' "$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR session list)"
+
 # Global log
 OUT=$(ITR_DB_PATH="$LOG_DIR/.itr.db" $ITR log -f json)
 [ "$(jq_val "$OUT" "len(d)")" -ge 1 ] && pass "global log has events" || fail "global log has events" "empty"
@@ -1218,7 +1460,7 @@ assert_eq "typed unrelate removes" "True" "$(jq_val "$OUT" "d['removed']")"
 assert_eq "typed unrelate removes only the requested type" "['related']" "$(jq_val "$OUT" "[r['relation_type'] for r in d['removed_relations']]")"
 OUT=$(ITR_DB_PATH="$REL_DIR/.itr.db" $ITR get 5 -f json)
 assert_eq "typed unrelate leaves other types intact" "['duplicate']" "$(jq_val "$OUT" "[r['relation_type'] for r in d.get('relations', [])]")"
-assert_exit "typed unrelate rejects unknown type" "1" env ITR_DB_PATH="$REL_DIR/.itr.db" $ITR unrelate 4 --from 5 --type bogus
+assert_exit "typed unrelate rejects unknown type" "5" env ITR_DB_PATH="$REL_DIR/.itr.db" $ITR unrelate 4 --from 5 --type bogus
 
 # Graph includes relation edges
 ITR_DB_PATH="$REL_DIR/.itr.db" $ITR relate 1 --to 2 --relation-type supersedes >/dev/null
@@ -1494,17 +1736,17 @@ OUT=$(echo '[{"id":3,"assigned_to":"agent-y"}]' | ITR_DB_PATH="$BU_DIR/.itr.db"
 assert_contains "batch update --fields has results" '"results"' "$OUT"
 assert_contains "batch update --fields has summary" '"summary"' "$OUT"
 # Verify 'action' key is filtered out
-HAS_ACTION=$(echo "$OUT" | python3 -c "import sys,json; d=json.load(sys.stdin); print('action' in d)")
+HAS_ACTION=$(echo "$OUT" | python3 -c "import sys,json; d=json.load(sys.stdin)['data']; print('action' in d)")
 assert_eq "batch update --fields filters action" "False" "$HAS_ACTION"
 
 # --fields on batch add (envelope format)
 BF_OUT=$(echo '[{"title":"Fields test"}]' | ITR_DB_PATH="$BU_DIR/.itr.db" $ITR batch add -f json --fields action,results)
-KEYS=$(echo "$BF_OUT" | python3 -c "import sys,json; d=json.load(sys.stdin); print(sorted(d.keys()))")
+KEYS=$(echo "$BF_OUT" | python3 -c "import sys,json; d=json.load(sys.stdin)['data']; print(sorted(d.keys()))")
 assert_eq "batch add --fields filters keys" "['action', 'results']" "$KEYS"
 
 # --fields on batch close
 BC_OUT=$(echo '[{"id":3}]' | ITR_DB_PATH="$BU_DIR/.itr.db" $ITR batch close -f json --fields results)
-HAS_SUMMARY=$(echo "$BC_OUT" | python3 -c "import sys,json; d=json.load(sys.stdin); print('summary' in d)")
+HAS_SUMMARY=$(echo "$BC_OUT" | python3 -c "import sys,json; d=json.load(sys.stdin)['data']; print('summary' in d)")
 assert_eq "batch close --fields filters summary" "False" "$HAS_SUMMARY"
 
 # Batch update parent changes (#211): set, clear via null, soft fallbacks
@@ -1605,7 +1847,7 @@ assert_eq "batch update dry-run priority unchanged" "medium" "$(jq_val "$DR_STAT
 
 # Verify dry_run not in normal (non-dry-run) output
 DR_NORMAL=$(echo '[{"id":1}]' | ITR_DB_PATH="$DR_DIR/.itr.db" $ITR batch close -f json)
-assert_eq "batch close normal no dry_run key" "False" "$(echo "$DR_NORMAL" | python3 -c "import sys,json; print('dry_run' in json.load(sys.stdin))")"
+assert_eq "batch close normal no dry_run key" "False" "$(echo "$DR_NORMAL" | python3 -c "import sys,json; print('dry_run' in json.load(sys.stdin)['data'])")"
 
 rm -rf "$DR_DIR"
 
@@ -1656,8 +1898,8 @@ echo "--- Known Bug Tests (documenting expected behavior) ---"
 # Currently exits 2 with clap error "unrecognized subcommand 'deps'"
 BUG_DIR=$(mktemp -d)
 $ITR init --db "$BUG_DIR/.itr.db" > /dev/null
-ID1=$($ITR add "dep test 1" --db "$BUG_DIR/.itr.db" -f json | python3 -c "import sys,json; print(json.load(sys.stdin)['id'])")
-ID2=$($ITR add "dep test 2" --db "$BUG_DIR/.itr.db" -f json | python3 -c "import sys,json; print(json.load(sys.stdin)['id'])")
+ID1=$($ITR add "dep test 1" --db "$BUG_DIR/.itr.db" -f json | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['id'])")
+ID2=$($ITR add "dep test 2" --db "$BUG_DIR/.itr.db" -f json | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['id'])")
 OUT=$($ITR --db "$BUG_DIR/.itr.db" depend "$ID1" --on "$ID2" 2>&1) || true
 assert_contains "bug42: 'depend' command works" "blocked by" "$OUT"
 # The actual bug: 'deps' should also work as an alias
@@ -1706,7 +1948,7 @@ $ITR init --db "$ALIAS_DIR/.itr.db" > /dev/null
 
 # --title flag creates issue
 TITLE_FLAG_OUT=$($ITR --db "$ALIAS_DIR/.itr.db" add --title "Flag title" -f json 2>/dev/null)
-TITLE_FLAG_VAL=$(echo "$TITLE_FLAG_OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['title'])")
+TITLE_FLAG_VAL=$(echo "$TITLE_FLAG_OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['title'])")
 if [ "$TITLE_FLAG_VAL" = "Flag title" ]; then
     pass "alias: --title flag creates issue"
 else
@@ -1716,7 +1958,7 @@ fi
 # --title flag takes precedence over positional, stderr warns
 BOTH_STDERR=$($ITR --db "$ALIAS_DIR/.itr.db" add "Positional" --title "Flag" -f json 2>&1 1>/dev/null)
 BOTH_OUT=$($ITR --db "$ALIAS_DIR/.itr.db" add "Positional2" --title "Flag2" -f json 2>/dev/null)
-BOTH_TITLE=$(echo "$BOTH_OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['title'])")
+BOTH_TITLE=$(echo "$BOTH_OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['title'])")
 if [ "$BOTH_TITLE" = "Flag2" ] && echo "$BOTH_STDERR" | grep -q "REVIEW:"; then
     pass "alias: --title flag overrides positional with REVIEW warning"
 else
@@ -1725,7 +1967,7 @@ fi
 
 # --body maps to context
 BODY_OUT=$($ITR --db "$ALIAS_DIR/.itr.db" add --title "Body test" --body "body content" -f json 2>/dev/null)
-BODY_CTX=$(echo "$BODY_OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['context'])")
+BODY_CTX=$(echo "$BODY_OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['context'])")
 if [ "$BODY_CTX" = "body content" ]; then
     pass "alias: --body maps to context"
 else
@@ -1734,7 +1976,7 @@ fi
 
 # batch create alias works
 BATCH_OUT=$(echo '[{"title":"batch created"}]' | $ITR --db "$ALIAS_DIR/.itr.db" batch create -f json 2>/dev/null)
-BATCH_TITLE=$(echo "$BATCH_OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['results'][0]['issue']['title'])")
+BATCH_TITLE=$(echo "$BATCH_OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['results'][0]['issue']['title'])")
 if [ "$BATCH_TITLE" = "batch created" ]; then
     pass "alias: batch create works"
 else
@@ -1743,9 +1985,9 @@ fi
 
 # --reason flag on close works
 REASON_ADD=$($ITR --db "$ALIAS_DIR/.itr.db" add --title "Reason flag test" -f json 2>/dev/null)
-REASON_ID=$(echo "$REASON_ADD" | python3 -c "import sys,json; print(json.load(sys.stdin)['id'])")
+REASON_ID=$(echo "$REASON_ADD" | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['id'])")
 REASON_OUT=$($ITR --db "$ALIAS_DIR/.itr.db" close "$REASON_ID" --reason "closed via flag" -f json 2>/dev/null)
-REASON_VAL=$(echo "$REASON_OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['close_reason'])")
+REASON_VAL=$(echo "$REASON_OUT" | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['close_reason'])")
 if [ "$REASON_VAL" = "closed via flag" ]; then
     pass "alias: --reason flag on close works"
 else
@@ -1754,10 +1996,10 @@ fi
 
 # --reason flag overrides positional with REVIEW warning
 REASON_ADD2=$($ITR --db "$ALIAS_DIR/.itr.db" add --title "Reason both test" -f json 2>/dev/null)
-REASON_ID2=$(echo "$REASON_ADD2" | python3 -c "import sys,json; print(json.load(sys.stdin)['id'])")
+REASON_ID2=$(echo "$REASON_ADD2" | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['id'])")
 REASON_STDERR=$($ITR --db "$ALIAS_DIR/.itr.db" close "$REASON_ID2" "positional reason" --reason "flag reason" -f json 2>&1 1>/dev/null)
 REASON_OUT2=$($ITR --db "$ALIAS_DIR/.itr.db" close "$REASON_ID2" -f json 2>/dev/null)
-REASON_VAL2=$(echo "$REASON_OUT2" | python3 -c "import sys,json; print(json.load(sys.stdin)['close_reason'])")
+REASON_VAL2=$(echo "$REASON_OUT2" | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['close_reason'])")
 if [ "$REASON_VAL2" = "flag reason" ] && echo "$REASON_STDERR" | grep -q "REVIEW:"; then
     pass "alias: --reason flag overrides positional with REVIEW warning"
 else
@@ -1776,7 +2018,7 @@ $ITR init --db "$UNK_DIR/.itr.db" > /dev/null
 
 UNK_EXIT=0
 UNK_STDERR=$($ITR --db "$UNK_DIR/.itr.db" add "Unknown flag test" --urgency high -f json 2>&1 1>/dev/null) || UNK_EXIT=$?
-UNK_COUNT=$($ITR --db "$UNK_DIR/.itr.db" list -f json | python3 -c "import sys,json; print(len(json.load(sys.stdin)))")
+UNK_COUNT=$($ITR --db "$UNK_DIR/.itr.db" list -f json | python3 -c "import sys,json; print(len(json.load(sys.stdin)['data']))")
 if [ "$UNK_EXIT" -ne 0 ] && echo "$UNK_STDERR" | grep -q -- "--urgency" && [ "$UNK_COUNT" = "0" ]; then
     pass "unknown-flag: add rejects unknown flag without mutation"
 else
@@ -1784,10 +2026,10 @@ else
 fi
 
 BULK_TYPO_ADD=$($ITR --db "$UNK_DIR/.itr.db" add "Bulk typo guard" --tags stale -f json)
-BULK_TYPO_ID=$(echo "$BULK_TYPO_ADD" | python3 -c "import sys,json; print(json.load(sys.stdin)['id'])")
+BULK_TYPO_ID=$(echo "$BULK_TYPO_ADD" | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['id'])")
 BULK_TYPO_EXIT=0
 BULK_TYPO_STDERR=$($ITR --db "$UNK_DIR/.itr.db" bulk close --tag stale --dryrun -f json 2>&1 1>/dev/null) || BULK_TYPO_EXIT=$?
-BULK_TYPO_STATUS=$($ITR --db "$UNK_DIR/.itr.db" get "$BULK_TYPO_ID" -f json | python3 -c "import sys,json; print(json.load(sys.stdin)['status'])")
+BULK_TYPO_STATUS=$($ITR --db "$UNK_DIR/.itr.db" get "$BULK_TYPO_ID" -f json | python3 -c "import sys,json; print(json.load(sys.stdin)['data']['status'])")
 if [ "$BULK_TYPO_EXIT" -ne 0 ] && echo "$BULK_TYPO_STDERR" | grep -q -- "--dryrun" && [ "$BULK_TYPO_STATUS" = "open" ]; then
     pass "unknown-flag: bulk dry-run typo fails before mutation"
 else
@@ -1833,7 +2075,7 @@ else
     TOKEN=$(python3 - "$UI_OUT" <<'PY'
 import json, sys, urllib.parse
 with open(sys.argv[1], encoding="utf-8") as f:
-    data = json.load(f)
+    data = json.load(f)["data"]
 print(urllib.parse.parse_qs(urllib.parse.urlparse(data["url"]).query)["token"][0])
 PY
 )
@@ -1953,7 +2195,7 @@ else
     SQL_TOKEN=$(python3 - "$UI_SQL_OUT" <<'PY'
 import json, sys, urllib.parse
 with open(sys.argv[1], encoding="utf-8") as f:
-    data = json.load(f)
+    data = json.load(f)["data"]
 print(urllib.parse.parse_qs(urllib.parse.urlparse(data["url"]).query)["token"][0])
 PY
 )
@@ -2043,7 +2285,7 @@ set +e
 MISSING_OUT=$(ITR_DB_PATH="$PARENT_DIR/.itr.db" $ITR update 3 --parent 999 2>&1)
 MISSING_RC=$?
 set -e
-assert_eq "update --parent missing exits 1" "1" "$MISSING_RC"
+assert_eq "update --parent missing exits 3" "3" "$MISSING_RC"
 assert_contains "update --parent missing message mentions 999" "999" "$MISSING_OUT"
 
 # No partial write: parent_id should still be null after rejection
@@ -2056,7 +2298,7 @@ set +e
 SELF_OUT=$(ITR_DB_PATH="$PARENT_DIR/.itr.db" $ITR update 1 --parent 1 2>&1)
 SELF_RC=$?
 set -e
-assert_eq "update --parent self exits 1" "1" "$SELF_RC"
+assert_eq "update --parent self exits 4" "4" "$SELF_RC"
 assert_contains "update --parent self mentions cycle" "ycle" "$SELF_OUT"
 
 # Descendant-cycle rejection: parent grandchild (4) under child (3) under epic A (1),
@@ -2067,7 +2309,7 @@ set +e
 DESC_OUT=$(ITR_DB_PATH="$PARENT_DIR/.itr.db" $ITR update 1 --parent 4 2>&1)
 DESC_RC=$?
 set -e
-assert_eq "update --parent descendant exits 1" "1" "$DESC_RC"
+assert_eq "update --parent descendant exits 4" "4" "$DESC_RC"
 assert_contains "update --parent descendant mentions cycle" "ycle" "$DESC_OUT"
 
 # Verify no partial write after descendant-cycle rejection
@@ -2293,7 +2535,34 @@ case "$CLEAN_STDERR" in
     *) pass "import without events/relations stays quiet" ;;
 esac
 
-rm -rf "$IMPORT_WARN_SRC" "$IMPORT_WARN_DST" "$CLEAN_SRC" "$CLEAN_DST"
+# A --full export restores config, events, and relations: exporting the
+# copy again matches the source apart from event/note/relation row IDs.
+ITR_DB_PATH="$IMPORT_WARN_SRC/.itr.db" $ITR config set urgency.priority.high 9 >/dev/null
+ITR_DB_PATH="$IMPORT_WARN_SRC/.itr.db" $ITR export --full > "$IMPORT_WARN_SRC/full.jsonl"
+assert_contains "export --full starts with a format header" '"format":"itr-export"' "$(head -1 "$IMPORT_WARN_SRC/full.jsonl")"
+FULL_DST=$(mktemp -d)
+ITR_DB_PATH="$FULL_DST/.itr.db" $ITR init >/dev/null
+FULL_STDERR=$(ITR_DB_PATH="$FULL_DST/.itr.db" $ITR import --file "$IMPORT_WARN_SRC/full.jsonl" 2>&1 >/dev/null)
+case "$FULL_STDERR" in
+    *dropped*) fail "import of --full export drops nothing" "$FULL_STDERR" ;;
+    *) pass "import of --full export drops nothing" ;;
+esac
+ITR_DB_PATH="$FULL_DST/.itr.db" $ITR export --full > "$FULL_DST/full.jsonl"
+FULL_SAME=$(python3 -c "import json,sys
+def load(path):
+    out=[]
+    for line in open(path):
+        d=json.loads(line)
+        for key in ('events','notes','relations'):
+            for row in d.get(key,[]): row.pop('id')
+        out.append(d)
+    return out
+print(load(sys.argv[1])==load(sys.argv[2]))" "$IMPORT_WARN_SRC/full.jsonl" "$FULL_DST/full.jsonl")
+assert_eq "export --full round-trips into a fresh database" "True" "$FULL_SAME"
+echo '{"format":"itr-export","version":99,"config":{}}' > "$FULL_DST/future.jsonl"
+assert_exit "import rejects a newer export version" "5" env ITR_DB_PATH="$FULL_DST/.itr.db" $ITR import --file "$FULL_DST/future.jsonl"
+
+rm -rf "$IMPORT_WARN_SRC" "$IMPORT_WARN_DST" "$CLEAN_SRC" "$CLEAN_DST" "$FULL_DST"
 
 # ─────────────────────────────────────────────
 echo "--- invalid format error message lists oneline ---"
@@ -2329,11 +2598,12 @@ ITR_DB_PATH="$FMT_SRC/.itr.db" $ITR list -f bogus >/dev/null 2>"$BAD_STDERR_FILE
 BAD_RC=$?
 set -e
 BAD_STDERR=$(cat "$BAD_STDERR_FILE")
-assert_eq "invalid format exits 1" "1" "$BAD_RC"
+assert_eq "invalid format exits 5" "5" "$BAD_RC"
 assert_contains "invalid-format error message lists compact" "compact" "$BAD_STDERR"
 assert_contains "invalid-format error message lists json" "json" "$BAD_STDERR"
 assert_contains "invalid-format error message lists pretty" "pretty" "$BAD_STDERR"
 assert_contains "invalid-format error message lists oneline" "oneline" "$BAD_STDERR"
+assert_contains "invalid-format error message lists gha" "gha" "$BAD_STDERR"
 
 rm -rf "$FMT_SRC"
 
@@ -2401,15 +2671,15 @@ def hook(pairs):
     return dict(pairs)
 with open(sys.argv[1], encoding="utf-8") as f:
     json.loads(f.read(), object_pairs_hook=hook)
-# object_pairs_hook fires bottom-up (nested objects first), so the top-level
-# Stats object is the LAST one decoded.
-print(','.join(order[-1]))
+# object_pairs_hook fires bottom-up (nested objects first), so the envelope is
+# the LAST object decoded and the Stats object in its `data` the one before.
+print(','.join(order[-2]))
 PY
 )
 # serde_json's Map (default build) sorts object keys alphabetically, which is a
 # stable, deterministic order. Assert that exact order.
 assert_eq "stats -f json top-level key order is deterministic" \
-    "avg_urgency,blocked,by_assignee,by_kind,by_priority,by_skills,by_status,oldest_open,ready,total" \
+    "avg_urgency,blocked,by_assignee,by_component,by_kind,by_priority,by_skills,by_status,oldest_open,ready,total" \
     "$DET_STATS_TOPKEYS"
 
 # (a.3) Nested count-map keys appear in a fixed (sorted) order — the part that
@@ -2438,7 +2708,7 @@ print('|'.join([
 PY
 )
 assert_eq "stats -f json nested count-map keys are sorted/deterministic" \
-    "done,in-progress,open,wontfix|critical,high,low,medium|bug,epic,feature,task" \
+    "done,in-progress,open,review,wontfix|critical,high,low,medium|bug,epic,feature,task" \
     "$DET_BY_STATUS_KEYS"
 
 # (b) graph -f json urgency precision contract: every node urgency must have at
@@ -2451,7 +2721,7 @@ ITR_DB_PATH="$DET_DIR_B/.itr.db" $ITR graph --all -f json > "$DET_GRAPH_B_FILE"
 DET_URG_OK=$(python3 - "$DET_GRAPH_A_FILE" "$DET_URG_DECIMALS" <<'PY'
 import sys, json
 with open(sys.argv[1], encoding="utf-8") as f:
-    data = json.load(f)
+    data = json.load(f)["data"]
 max_dec = int(sys.argv[2])
 bad = []
 for node in data["nodes"]:
@@ -2478,7 +2748,7 @@ assert_eq "graph -f json urgency honors fixed precision contract (<= $DET_URG_DE
 DET_URG_OK_B=$(python3 - "$DET_GRAPH_B_FILE" "$DET_URG_DECIMALS" <<'PY'
 import sys, json
 with open(sys.argv[1], encoding="utf-8") as f:
-    data = json.load(f)
+    data = json.load(f)["data"]
 max_dec = int(sys.argv[2])
 bad = []
 for node in data["nodes"]:
@@ -2584,18 +2854,18 @@ SPEC_PAYLOAD='[{"title":"Pan gesture","priority":"urgent","file":"src/chart.rs"}
 DRY_OUT=$(echo "$SPEC_PAYLOAD" | ITR_DB_PATH="$SPEC_DB" $ITR batch add --dry-run -f json)
 assert_eq "batch add dry-run flags dry_run" "True" "$(jq_val "$DRY_OUT" "d['dry_run']")"
 DRY_VERDICTS=$(jq_val "$DRY_OUT" "','.join(r['outcome'] for r in d['results'])")
-COUNT_BEFORE=$(ITR_DB_PATH="$SPEC_DB" $ITR list --all -f json | python3 -c "import sys,json;print(len(json.load(sys.stdin)))")
+COUNT_BEFORE=$(ITR_DB_PATH="$SPEC_DB" $ITR list --all -f json | python3 -c "import sys,json;print(len(json.load(sys.stdin)['data']))")
 REAL_OUT=$(echo "$SPEC_PAYLOAD" | ITR_DB_PATH="$SPEC_DB" $ITR batch add -f json)
 REAL_VERDICTS=$(jq_val "$REAL_OUT" "','.join(r['outcome'] for r in d['results'])")
 assert_eq "batch add dry-run verdicts match real run" "$REAL_VERDICTS" "$DRY_VERDICTS"
-COUNT_AFTER=$(ITR_DB_PATH="$SPEC_DB" $ITR list --all -f json | python3 -c "import sys,json;print(len(json.load(sys.stdin)))")
+COUNT_AFTER=$(ITR_DB_PATH="$SPEC_DB" $ITR list --all -f json | python3 -c "import sys,json;print(len(json.load(sys.stdin)['data']))")
 assert_eq "batch add real run created exactly the valid items" "$((COUNT_BEFORE + 2))" "$COUNT_AFTER"
 assert_contains "batch add dry-run shows resolved priority default" "critical" "$DRY_OUT"
 
 # (3) batch note --dry-run writes nothing
-NOTE_COUNT_BEFORE=$(ITR_DB_PATH="$SPEC_DB" $ITR get 8 -f json | python3 -c "import sys,json;print(len(json.load(sys.stdin)['notes']))")
+NOTE_COUNT_BEFORE=$(ITR_DB_PATH="$SPEC_DB" $ITR get 8 -f json | python3 -c "import sys,json;print(len(json.load(sys.stdin)['data']['notes']))")
 echo '[{"id":8,"text":"planned note"}]' | ITR_DB_PATH="$SPEC_DB" $ITR batch note --dry-run >/dev/null
-NOTE_COUNT_AFTER=$(ITR_DB_PATH="$SPEC_DB" $ITR get 8 -f json | python3 -c "import sys,json;print(len(json.load(sys.stdin)['notes']))")
+NOTE_COUNT_AFTER=$(ITR_DB_PATH="$SPEC_DB" $ITR get 8 -f json | python3 -c "import sys,json;print(len(json.load(sys.stdin)['data']['notes']))")
 assert_eq "batch note dry-run writes no notes" "$NOTE_COUNT_BEFORE" "$NOTE_COUNT_AFTER"
 
 # (4) --fields on all four formats honors order + unknown-name soft-fallback
@@ -2609,7 +2879,7 @@ case "$OUT" in
     *"Status"*"Blocked"*"Title"*) pass "pretty fields header honors requested order" ;;
     *) fail "pretty fields header honors requested order" "got: $OUT" ;;
 esac
-OUT=$(ITR_DB_PATH="$SPEC_DB" $ITR list -f json --fields title,id | python3 -c "import sys,json; print(','.join(list(json.load(sys.stdin)[0].keys())))")
+OUT=$(ITR_DB_PATH="$SPEC_DB" $ITR list -f json --fields title,id | python3 -c "import sys,json; print(','.join(list(json.load(sys.stdin)['data'][0].keys())))")
 assert_eq "json fields honor requested order" "title,id" "$OUT"
 OUT=$(ITR_DB_PATH="$SPEC_DB" $ITR list --fields status,id | head -1)
 case "$OUT" in
@@ -2632,6 +2902,675 @@ assert_eq "claim rejects multi-ID syntax" "2" "$SPEC_EXIT"
 
 rm -rf "$SPEC_DIR"
 
+echo "--- branch: generated branch names recorded on the issue ---"
+BRANCH_DIR=$(mktemp -d)
+BRANCH_DB="$BRANCH_DIR/.itr.db"
+ITR_DB_PATH="$BRANCH_DB" $ITR init >/dev/null
+ITR_DB_PATH="$BRANCH_DB" $ITR add "Fix login timeout!" -k bug >/dev/null
+OUT=$(ITR_DB_PATH="$BRANCH_DB" $ITR branch 1)
+assert_eq "branch name is kind/id-slug" "bug/1-fix-login-timeout" "$OUT"
+assert_eq "branch recorded on issue" "bug/1-fix-login-timeout" "$(ITR_DB_PATH="$BRANCH_DB" $ITR get 1 -f json | python3 -c "import sys,json;print(json.load(sys.stdin)['data']['branch'])")"
+ITR_DB_PATH="$BRANCH_DB" $ITR update 1 --title "Renamed" >/dev/null
+assert_eq "recorded branch survives retitle" "bug/1-fix-login-timeout" "$(ITR_DB_PATH="$BRANCH_DB" $ITR branch 1)"
+assert_exit "branch on missing issue is NOT_FOUND" "3" env ITR_DB_PATH="$BRANCH_DB" $ITR branch 99
+rm -rf "$BRANCH_DIR"
+
+echo "--- issue keys: project prefix + sequence, accepted wherever an ID is ---"
+KEY_DIR=$(mktemp -d)
+KEY_DB="$KEY_DIR/.itr.db"
+ITR_DB_PATH="$KEY_DB" $ITR init >/dev/null
+ITR_DB_PATH="$KEY_DB" $ITR add "Unfiled" >/dev/null
+ITR_DB_PATH="$KEY_DB" $ITR project add app --key app >/dev/null
+ITR_DB_PATH="$KEY_DB" $ITR add "Keyed later" >/dev/null
+ITR_DB_PATH="$KEY_DB" $ITR project move app 2 >/dev/null
+assert_eq "moved issue gets the next key" "APP-1" "$(jq_val "$(ITR_DB_PATH="$KEY_DB" $ITR get 2 -f json)" "d['key']")"
+assert_eq "unkeyed issue has no key" "False" "$(jq_val "$(ITR_DB_PATH="$KEY_DB" $ITR get 1 -f json)" "'key' in d")"
+ITR_DB_PATH="$KEY_DB" $ITR update app-1 --priority high >/dev/null
+assert_eq "update accepts a key" "high" "$(jq_val "$(ITR_DB_PATH="$KEY_DB" $ITR get APP-1 -f json)" "d['priority']")"
+ITR_DB_PATH="$KEY_DB" $ITR close APP-1 "shipped" >/dev/null
+assert_eq "close accepts a key" "done" "$(jq_val "$(ITR_DB_PATH="$KEY_DB" $ITR get 2 -f json)" "d['status']")"
+assert_exit "a prefix another project uses is rejected" "5" env ITR_DB_PATH="$KEY_DB" $ITR project add web --key APP
+ITR_DB_PATH="$KEY_DB" $ITR export > "$KEY_DIR/keys.jsonl"
+KEY_DB2="$KEY_DIR/copy.db"
+ITR_DB_PATH="$KEY_DB2" $ITR init >/dev/null
+ITR_DB_PATH="$KEY_DB2" $ITR add "Already here" >/dev/null
+ITR_DB_PATH="$KEY_DB2" $ITR add "Also here" >/dev/null
+ITR_DB_PATH="$KEY_DB2" $ITR import --merge --file "$KEY_DIR/keys.jsonl" >/dev/null 2>&1
+assert_eq "key survives import renumbering" "Keyed later" "$(jq_val "$(ITR_DB_PATH="$KEY_DB2" $ITR get APP-1 -f json)" "d['title']")"
+rm -rf "$KEY_DIR"
+
+echo "--- stats --trend: created/closed/open per period ---"
+TREND_DIR=$(mktemp -d)
+TREND_DB="$TREND_DIR/.itr.db"
+ITR_DB_PATH="$TREND_DB" $ITR init >/dev/null
+ITR_DB_PATH="$TREND_DB" $ITR add "first" >/dev/null
+ITR_DB_PATH="$TREND_DB" $ITR add "second" >/dev/null
+ITR_DB_PATH="$TREND_DB" $ITR close 1 "done" >/dev/null
+OUT=$(ITR_DB_PATH="$TREND_DB" $ITR stats --trend weekly --periods 3 -f json)
+assert_eq "stats --trend row count" "3" "$(jq_val "$OUT" "len(d)")"
+assert_eq "stats --trend current period counts" "2 1 1" "$(jq_val "$OUT" "f\"{d[-1]['created']} {d[-1]['closed']} {d[-1]['open']}\"")"
+OUT=$(ITR_DB_PATH="$TREND_DB" $ITR stats --trend monthly --periods 1)
+assert_contains "stats --trend compact line" "CREATED:2 CLOSED:1 OPEN:1" "$OUT"
+assert_exit "stats --periods requires --trend" "2" env ITR_DB_PATH="$TREND_DB" $ITR stats --periods 2
+rm -rf "$TREND_DIR"
+
+echo "--- stale: config-driven tagging of idle issues ---"
+STALE_DIR=$(mktemp -d)
+STALE_DB="$STALE_DIR/.itr.db"
+ITR_DB_PATH="$STALE_DB" $ITR init >/dev/null
+ITR_DB_PATH="$STALE_DB" $ITR add "idle" -p high >/dev/null
+assert_eq "stale without policy is a no-op" "STALE: 0 issues" "$(ITR_DB_PATH="$STALE_DB" $ITR stale 2>/dev/null)"
+ITR_DB_PATH="$STALE_DB" $ITR config set stale.after_days 0 >/dev/null
+ITR_DB_PATH="$STALE_DB" $ITR config set stale.downgrade_to low >/dev/null
+OUT=$(ITR_DB_PATH="$STALE_DB" $ITR stale)
+assert_contains "stale tags and downgrades" "STALE:1 IDLE:0d PRIORITY:high -> low" "$OUT"
+OUT=$(ITR_DB_PATH="$STALE_DB" $ITR get 1 -f json)
+assert_eq "stale issue carries the tag and new priority" "low True" "$(jq_val "$OUT" "f\"{d['priority']} {'stale' in d['tags']}\"")"
+assert_eq "stale skips tagged issues" "STALE: 0 issues" "$(ITR_DB_PATH="$STALE_DB" $ITR stale)"
+rm -rf "$STALE_DIR"
+
+echo "--- lint: issue quality policies ---"
+LINT_DIR=$(mktemp -d)
+LINT_DB="$LINT_DIR/.itr.db"
+ITR_DB_PATH="$LINT_DB" $ITR init >/dev/null
+ITR_DB_PATH="$LINT_DB" $ITR add "Login page" -k feature >/dev/null
+ITR_DB_PATH="$LINT_DB" $ITR add "Crash on save" -k bug --context "Steps" --file src/save.rs >/dev/null
+OUT=$(ITR_DB_PATH="$LINT_DB" $ITR lint 2>/dev/null) || true
+assert_contains "lint flags a feature without acceptance" 'LINT:1 [missing-acceptance] feature has no acceptance criteria "Login page"' "$OUT"
+assert_exit "lint exits 1 on findings" 1 env ITR_DB_PATH="$LINT_DB" $ITR lint
+OUT=$(ITR_DB_PATH="$LINT_DB" $ITR lint -f json 2>&1 >/dev/null) || true
+assert_eq "lint JSON error code" "LINT_FINDINGS" "$(jq_val "$OUT" "d['code']")"
+ITR_DB_PATH="$LINT_DB" $ITR update 1 --acceptance "Logs in" --context "Needed" >/dev/null
+assert_eq "lint clean after fixes" "LINT: All clean" "$(ITR_DB_PATH="$LINT_DB" $ITR lint)"
+assert_exit "lint exits 0 when clean" 0 env ITR_DB_PATH="$LINT_DB" $ITR lint
+rm -rf "$LINT_DIR"
+
+echo "--- policy.require: required fields on add/update ---"
+POL_DIR=$(mktemp -d)
+POL_DB="$POL_DIR/.itr.db"
+ITR_DB_PATH="$POL_DB" $ITR init >/dev/null
+ITR_DB_PATH="$POL_DB" $ITR add "Before policy" -k bug >/dev/null
+ITR_DB_PATH="$POL_DB" $ITR config set policy.require.acceptance bug,feature >/dev/null
+assert_exit "add without required acceptance fails" 5 env ITR_DB_PATH="$POL_DB" $ITR add "Crash" -k bug
+OUT=$(ITR_DB_PATH="$POL_DB" $ITR add "Crash" -k bug -f json 2>&1 >/dev/null) || true
+assert_eq "required field error code" "INVALID_VALUE" "$(jq_val "$OUT" "d['code']")"
+assert_exit "add with acceptance succeeds" 0 env ITR_DB_PATH="$POL_DB" $ITR add "Crash" -k bug -a "No crash on save"
+assert_exit "unrelated update of an older issue succeeds" 0 env ITR_DB_PATH="$POL_DB" $ITR update 1 -p high
+assert_exit "update clearing required acceptance fails" 5 env ITR_DB_PATH="$POL_DB" $ITR update 2 -a ""
+rm -rf "$POL_DIR"
+
+echo "--- defaults.add: per-kind add templates ---"
+TPL_DIR=$(mktemp -d)
+TPL_DB="$TPL_DIR/.itr.db"
+ITR_DB_PATH="$TPL_DB" $ITR init >/dev/null
+ITR_DB_PATH="$TPL_DB" $ITR config set defaults.add.bug.context "Repro: Expected: Actual:" >/dev/null
+ITR_DB_PATH="$TPL_DB" $ITR config set defaults.add.bug.priority high >/dev/null
+OUT=$(ITR_DB_PATH="$TPL_DB" $ITR add "Crash" -k bug -f json)
+assert_eq "bug template fills priority and context" "high|Repro: Expected: Actual:" "$(jq_val "$OUT" "d['priority'] + '|' + d['context']")"
+OUT=$(ITR_DB_PATH="$TPL_DB" $ITR add "Crash" -k bug -p low -c "Seen on save" -f json)
+assert_eq "explicit fields beat the template" "low|Seen on save" "$(jq_val "$OUT" "d['priority'] + '|' + d['context']")"
+OUT=$(ITR_DB_PATH="$TPL_DB" $ITR add "Chore" -f json)
+assert_eq "other kinds keep medium" "medium" "$(jq_val "$OUT" "d['priority']")"
+rm -rf "$TPL_DIR"
+
+echo "--- init --demo: example project ---"
+DEMO_DIR=$(mktemp -d)
+DEMO_DB="$DEMO_DIR/.itr.db"
+OUT=$(ITR_DB_PATH="$DEMO_DB" $ITR init --demo -f json)
+assert_eq "init --demo reports seeded issues" "13" "$(jq_val "$OUT" "d['demo_issues']")"
+OUT=$(ITR_DB_PATH="$DEMO_DB" $ITR list --blocked -f json)
+assert_eq "demo has blocked issues" "True" "$(jq_val "$OUT" "len(d) >= 3")"
+OUT=$(ITR_DB_PATH="$DEMO_DB" $ITR init --demo -f json 2>/dev/null)
+assert_eq "init --demo leaves a non-empty database alone" "0" "$(jq_val "$OUT" "d['demo_issues']")"
+assert_eq "no duplicate demo issues" "13" "$(ITR_DB_PATH="$DEMO_DB" $ITR list --all -f json | python3 -c 'import json,sys; print(len(json.load(sys.stdin)["data"]))')"
+rm -rf "$DEMO_DIR"
+
+echo "--- init --agents-md: targets and templates ---"
+AGT_DIR=$(mktemp -d)
+printf 'Track work with {{command}} in {{db}}.\n' > "$AGT_DIR/tpl.md"
+OUT=$($ITR --db "$AGT_DIR" init --agents-md --target CLAUDE.md --agents-template "$AGT_DIR/tpl.md" -f json)
+assert_eq "agents target created" "created" "$(jq_val "$OUT" "d['agents'][0]['action']")"
+assert_contains "template placeholders filled" "Track work with itr in .itr.db." "$(cat "$AGT_DIR/CLAUDE.md")"
+$ITR --db "$AGT_DIR" config set agents.template "Use {{command}} for issues." >/dev/null
+OUT=$($ITR --db "$AGT_DIR" init --agents-md --target CLAUDE.md)
+assert_contains "rerun updates the block" "AGENTS: updated" "$OUT"
+assert_eq "one block after update" "1" "$(grep -c 'itr:agents:start' "$AGT_DIR/CLAUDE.md")"
+assert_contains "config template replaces old text" "Use itr for issues." "$(cat "$AGT_DIR/CLAUDE.md")"
+rm -rf "$AGT_DIR"
+
+echo "--- quiet: IDs only for scripts ---"
+Q_DIR=$(mktemp -d)
+Q_DB="$Q_DIR/.itr.db"
+ITR_DB_PATH="$Q_DB" $ITR init >/dev/null
+Q1=$(ITR_DB_PATH="$Q_DB" $ITR add "Quiet blocker" -q)
+Q2=$(ITR_DB_PATH="$Q_DB" $ITR add "Quiet blocked" -q)
+assert_eq "add -q prints the new ID" "2" "$Q2"
+assert_eq "depend -q prints nothing" "" "$(ITR_DB_PATH="$Q_DB" $ITR depend "$Q2" --on "$Q1" -q)"
+assert_eq "close -q prints only the ID, no UNBLOCKED" "$Q1" "$(ITR_DB_PATH="$Q_DB" $ITR close "$Q1" "done" -q)"
+assert_eq "update -q prints the ID" "$Q2" "$(ITR_DB_PATH="$Q_DB" $ITR update "$Q2" --priority high -q)"
+OUT=$(ITR_DB_PATH="$Q_DB" $ITR update "$Q2" --priority low -q -f json)
+assert_eq "quiet leaves JSON alone" "low" "$(jq_val "$OUT" "d['priority']")"
+ITR_DB_PATH="$Q_DB" $ITR update "$Q2" --tags old -q >/dev/null
+assert_eq "tag rename -q prints the issue IDs" "$Q2" "$(ITR_DB_PATH="$Q_DB" $ITR tag rename old new -q)"
+assert_eq "tag describe -q prints the tag" "new" "$(ITR_DB_PATH="$Q_DB" $ITR tag describe new --description "Fresh" -q)"
+assert_eq "tag rm -q prints the issue IDs" "$Q2" "$(ITR_DB_PATH="$Q_DB" $ITR tag rm new --yes -q)"
+assert_eq "config set -q prints nothing" "" "$(ITR_DB_PATH="$Q_DB" $ITR config set stale.after_days 0 -q)"
+assert_eq "stale -q prints the issue IDs" "$Q2" "$(ITR_DB_PATH="$Q_DB" $ITR stale -q)"
+ITR_DB_PATH="$Q_DB" $ITR config set escalate.high_after_days 0 -q
+assert_eq "escalate -q prints the issue IDs" "$Q2" "$(ITR_DB_PATH="$Q_DB" $ITR escalate -q)"
+assert_eq "recompute -q prints nothing" "" "$(ITR_DB_PATH="$Q_DB" $ITR recompute -q)"
+assert_eq "project add -q prints the name" "web" "$(ITR_DB_PATH="$Q_DB" $ITR project add web -q)"
+assert_eq "component add -q prints the name" "parser" "$(ITR_DB_PATH="$Q_DB" $ITR component add parser --assignee bot -q)"
+assert_eq "agent register -q prints the name" "bot" "$(ITR_DB_PATH="$Q_DB" $ITR agent register bot -q)"
+assert_eq "snapshot save -q prints the name" "base" "$(ITR_DB_PATH="$Q_DB" $ITR snapshot save base -q)"
+assert_eq "session start -q prints the session ID" "1" "$(ITR_DB_PATH="$Q_DB" $ITR session start --agent bot -q 2>/dev/null)"
+assert_eq "session end -q prints the session ID" "1" "$(ITR_DB_PATH="$Q_DB" $ITR session end --agent bot --summary "Done" -q 2>/dev/null)"
+rm -rf "$Q_DIR"
+
+echo "--- json envelope: api_version, ok, data; --raw ---"
+ENV_DIR=$(mktemp -d)
+ENV_DB="$ENV_DIR/.itr.db"
+ITR_DB_PATH="$ENV_DB" $ITR init >/dev/null
+OUT=$(ITR_DB_PATH="$ENV_DB" $ITR add "Enveloped" -f json)
+assert_eq "response carries api_version and ok" "1 True" "$(echo "$OUT" | python3 -c 'import json,sys; e=json.load(sys.stdin); print(e["api_version"], e["ok"])')"
+assert_eq "response is under data" "Enveloped" "$(jq_val "$OUT" "d['title']")"
+OUT=$(ITR_DB_PATH="$ENV_DB" $ITR get 1 -f json --raw)
+assert_eq "--raw prints the bare object" "Enveloped" "$(echo "$OUT" | python3 -c 'import json,sys; print(json.load(sys.stdin)["title"])')"
+ERR=$(ITR_DB_PATH="$ENV_DB" $ITR get 99 -f json 2>&1 >/dev/null || true)
+assert_eq "errors are ok:false envelopes" "False NOT_FOUND" "$(echo "$ERR" | python3 -c 'import json,sys; e=json.load(sys.stdin); print(e["ok"], e["code"])')"
+OUT=$(ITR_DB_PATH="$ENV_DB" $ITR export --export-format json)
+assert_eq "export stays a bare document" "True" "$(echo "$OUT" | python3 -c 'import json,sys; print(isinstance(json.load(sys.stdin), list))')"
+rm -rf "$ENV_DIR"
+
+echo "--- schema --json: JSON Schema for output models ---"
+OUT=$($ITR schema --json -f json)
+assert_eq "schema --json covers each model" "['issue', 'detail', 'stats', 'graph']" "$(jq_val "$OUT" "list(d)")"
+OUT=$($ITR schema --json --type detail)
+assert_eq "schema --type detail is one document" "IssueDetail" "$(echo "$OUT" | python3 -c 'import json,sys; print(json.load(sys.stdin)["title"])')"
+assert_exit "--type requires --json" "2" $ITR schema --type graph
+
+echo "--- --fail-on: exit codes for empty and blocked results ---"
+FAIL_DB="$(mktemp -d)/.itr.db"
+env ITR_DB_PATH="$FAIL_DB" $ITR init >/dev/null 2>&1
+assert_exit "empty results exit 0 by default" "0" env ITR_DB_PATH="$FAIL_DB" $ITR list
+assert_exit "--fail-on empty fails an empty list" "9" env ITR_DB_PATH="$FAIL_DB" $ITR list --fail-on empty
+assert_exit "--fail-on blocked ignores a finished backlog" "0" env ITR_DB_PATH="$FAIL_DB" $ITR next --fail-on blocked
+env ITR_DB_PATH="$FAIL_DB" $ITR add "Blocker" >/dev/null
+env ITR_DB_PATH="$FAIL_DB" $ITR add "Waiting" --blocked-by 1 >/dev/null
+env ITR_DB_PATH="$FAIL_DB" $ITR claim 1 >/dev/null 2>&1
+assert_exit "--fail-on blocked fails next when all is blocked" "10" env ITR_DB_PATH="$FAIL_DB" $ITR next --fail-on blocked
+set +e
+OUT=$(env ITR_DB_PATH="$FAIL_DB" $ITR brief --next -f json --fail-on empty 2>&1 >/dev/null)
+set -e
+assert_eq "blocked failure names ALL_BLOCKED" "ALL_BLOCKED" "$(echo "$OUT" | python3 -c 'import json,sys; print(json.load(sys.stdin)["code"])')"
+assert_exit "not found exits 3" "3" env ITR_DB_PATH="$FAIL_DB" $ITR get 99
+assert_exit "a cycle exits 4" "4" env ITR_DB_PATH="$FAIL_DB" $ITR depend 1 --on 2
+assert_exit "a bad value exits 5" "5" env ITR_DB_PATH="$FAIL_DB" $ITR project add web --key 1
+rm -rf "$(dirname "$FAIL_DB")"
+
+echo "--- did-you-mean suggestions in errors ---"
+HINT_DB="$(mktemp -d)/.itr.db"
+env ITR_DB_PATH="$HINT_DB" $ITR init >/dev/null 2>&1
+env ITR_DB_PATH="$HINT_DB" $ITR add "Fix login" >/dev/null
+env ITR_DB_PATH="$HINT_DB" $ITR add "Add tests" >/dev/null
+set +e
+OUT=$(env ITR_DB_PATH="$HINT_DB" $ITR get 12 -f json 2>&1 >/dev/null)
+set -e
+assert_eq "not found lists nearby IDs" "[1, 2]" "$(echo "$OUT" | python3 -c 'import json,sys; print(sorted(c["id"] for c in json.load(sys.stdin)["candidates"]))')"
+set +e
+OUT=$(env ITR_DB_PATH="$HINT_DB" $ITR relate 1 --to 2 --type relatd -f json 2>&1 >/dev/null)
+set -e
+assert_eq "invalid value names the closest option" "related" "$(echo "$OUT" | python3 -c 'import json,sys; print(json.load(sys.stdin)["suggestion"])')"
+OUT=$(env ITR_DB_PATH="$HINT_DB" $ITR list -s dne 2>&1 >/dev/null)
+assert_contains "filter REVIEW suggests a status" "did you mean 'done'?" "$OUT"
+rm -rf "$(dirname "$HINT_DB")"
+
+echo "--- apply: transactional multi-operation sessions ---"
+APPLY_DB="$(mktemp -d)/.itr.db"
+env ITR_DB_PATH="$APPLY_DB" $ITR init >/dev/null 2>&1
+OUT=$(echo '[{"op":"add","title":"Epic","kind":"epic"},{"op":"add","title":"Child","parent":"@0"},{"op":"depend","id":"@1","on":"@0"},{"op":"note","id":"@1","text":"started"},{"op":"close","id":"@0"}]' | env ITR_DB_PATH="$APPLY_DB" $ITR apply -f json)
+assert_eq "apply runs every operation" "5" "$(jq_val "$OUT" "d['summary']['ok']")"
+assert_eq "apply close unblocks the @N child" "2" "$(jq_val "$OUT" "d['results'][4]['unblocked'][0]['id']")"
+assert_exit "apply rolls back on a failed operation" 8 sh -c "echo '[{\"op\":\"add\",\"title\":\"Lost\"},{\"op\":\"close\",\"id\":99}]' | env ITR_DB_PATH=\"$APPLY_DB\" $ITR apply"
+OUT=$(env ITR_DB_PATH="$APPLY_DB" $ITR list --all -f json)
+assert_eq "rolled-back apply wrote nothing" "2" "$(jq_val "$OUT" "len(d)")"
+rm -rf "$(dirname "$APPLY_DB")"
+
+echo "--- batch add: JSONL, status, inline notes ---"
+JSONL_DB="$(mktemp -d)/.itr.db"
+env ITR_DB_PATH="$JSONL_DB" $ITR init >/dev/null 2>&1
+OUT=$(printf '%s\n' '{"title":"Imported","status":"in-progress","notes":["picked up from the old tracker"]}' '{"title":"Queued"}' | env ITR_DB_PATH="$JSONL_DB" $ITR batch add -f json)
+assert_eq "batch add reads one item per JSONL line" "2" "$(jq_val "$OUT" "d['summary']['ok']")"
+assert_eq "batch add status lands in progress" "in-progress" "$(jq_val "$OUT" "d['results'][0]['issue']['status']")"
+assert_eq "batch add inline note is stored" "picked up from the old tracker" "$(jq_val "$OUT" "d['results'][0]['issue']['notes'][0]['content']")"
+OUT=$(echo '[{"title":"Tests","blocked_by":["ref:schema"]},{"title":"Schema","ref":"schema"}]' | env ITR_DB_PATH="$JSONL_DB" $ITR batch add -f json)
+assert_eq "batch add ref: resolves a later item" "$(jq_val "$OUT" "d['results'][1]['id']")" "$(jq_val "$OUT" "d['results'][0]['issue']['blocked_by'][0]")"
+rm -rf "$(dirname "$JSONL_DB")"
+
+echo "--- add --from-markdown: checklist to issues ---"
+MD_DIR="$(mktemp -d)"
+env ITR_DB_PATH="$MD_DIR/.itr.db" $ITR init >/dev/null 2>&1
+printf '%s\n' '# Plan' '- [ ] Auth #auth !high' '  - [x] Mockup' '- [ ] Docs' > "$MD_DIR/plan.md"
+OUT=$(env ITR_DB_PATH="$MD_DIR/.itr.db" $ITR add --from-markdown "$MD_DIR/plan.md" -f json)
+assert_eq "from-markdown creates one issue per task" "3" "$(jq_val "$OUT" "d['summary']['total']")"
+assert_eq "from-markdown nests by indentation" "$(jq_val "$OUT" "d['results'][0]['id']")" "$(jq_val "$OUT" "d['results'][1]['issue']['parent_id']")"
+assert_eq "from-markdown reads annotations" "high auth" "$(jq_val "$OUT" "d['results'][0]['issue']['priority'] + ' ' + d['results'][0]['issue']['tags'][0]")"
+assert_eq "from-markdown closes checked tasks" "done" "$(jq_val "$OUT" "d['results'][1]['issue']['status']")"
+printf 'no tasks here\n' > "$MD_DIR/empty.md"
+assert_exit "from-markdown without tasks is invalid" 5 env ITR_DB_PATH="$MD_DIR/.itr.db" $ITR add --from-markdown "$MD_DIR/empty.md"
+rm -rf "$MD_DIR"
+
+echo "--- todo.txt export and import ---"
+TT_DIR="$(mktemp -d)"
+env ITR_DB_PATH="$TT_DIR/.itr.db" $ITR init >/dev/null 2>&1
+printf '%s\n' '(A) 2026-01-02 Call plumber +house @phone' 'x 2026-01-05 2026-01-01 Buy paint +house' > "$TT_DIR/todo.txt"
+OUT=$(env ITR_DB_PATH="$TT_DIR/.itr.db" $ITR import --import-format todotxt --file "$TT_DIR/todo.txt" -f json 2>/dev/null)
+assert_eq "todotxt import adds tasks and a +project epic" "3" "$(jq_val "$OUT" "d['imported']")"
+OUT=$(env ITR_DB_PATH="$TT_DIR/.itr.db" $ITR get 1 -f json)
+assert_eq "todotxt import maps priority, context, and project" "critical phone 3" "$(jq_val "$OUT" "d['priority'] + ' ' + d['tags'][0] + ' ' + str(d['parent_id'])")"
+OUT=$(env ITR_DB_PATH="$TT_DIR/.itr.db" $ITR export --export-format todotxt)
+assert_contains "todotxt export keeps the completion date" "$OUT" "x 2026-01-05 2026-01-01 Buy paint +house pri:C itr:2"
+assert_exit "unknown import format is invalid" 5 env ITR_DB_PATH="$TT_DIR/.itr.db" $ITR import --import-format csv --file "$TT_DIR/todo.txt"
+rm -rf "$TT_DIR"
+
+echo "--- scan: TODO comments to issues ---"
+SC_DIR="$(mktemp -d)"
+cd "$SC_DIR"
+git init -q .
+env ITR_DB_PATH="$SC_DIR/.itr.db" $ITR init >/dev/null 2>&1
+printf '%s\n' 'fn main() {' '    // TODO: handle overflow' '    // FIXME(ann): off by one' '}' > main.rs
+printf '%s\n' '# TODO: never filed' > skipped.py
+printf 'skipped.py\n' > .gitignore
+OUT=$(env ITR_DB_PATH="$SC_DIR/.itr.db" $ITR scan -f json)
+assert_eq "scan files each comment, skipping ignored files" "2" "$(jq_val "$OUT" "len(d['created'])")"
+assert_eq "scan records where the comment is" "main.rs:3" "$(jq_val "$OUT" "d['created'][1]['at']")"
+OUT=$(env ITR_DB_PATH="$SC_DIR/.itr.db" $ITR list --tag code-todo -k bug -f json)
+assert_eq "scan files FIXME as a bug tagged code-todo" "off by one" "$(jq_val "$OUT" "d[0]['title']")"
+printf '%s\n' '// moved' 'fn main() {' '    // TODO: handle overflow' '    // FIXME(ann): off by one' '}' > main.rs
+OUT=$(env ITR_DB_PATH="$SC_DIR/.itr.db" $ITR scan -f json)
+assert_eq "rescan updates moved comments instead of duplicating" "0 2" "$(jq_val "$OUT" "str(len(d['created'])) + ' ' + str(len(d['updated']))")"
+printf '%s\n' '// moved' 'fn main() {' '    // FIXME(ann): off by one' '}' > main.rs
+OUT=$(env ITR_DB_PATH="$SC_DIR/.itr.db" $ITR scan -f json 2>/dev/null)
+assert_eq "rescan reports issues whose comment is gone" "[1]" "$(jq_val "$OUT" "d['gone']")"
+assert_exit "scan of a missing path is invalid" 5 env ITR_DB_PATH="$SC_DIR/.itr.db" $ITR scan nowhere
+cd "$WORKDIR"
+rm -rf "$SC_DIR"
+
+echo "--- commits: linking commits to issues ---"
+CM_DIR="$(mktemp -d)"
+cd "$CM_DIR"
+git init -q .
+CM_ITR="env ITR_DB_PATH=$CM_DIR/.itr.db $ITR"
+$CM_ITR init >/dev/null 2>&1
+CM_A=$($CM_ITR add "Parser work" -q)
+CM_B=$($CM_ITR add "Lexer work" -q)
+git -c user.name=t -c user.email=t@example.com commit -q --allow-empty -m "Start parser" -m "Itr-Issue: #$CM_A"
+OUT=$($CM_ITR git link "$CM_A" -f json)
+assert_eq "git link records HEAD on the issue" "Start parser" "$(jq_val "$OUT" "d['subject']")"
+OUT=$($CM_ITR git link "$CM_A" -f json)
+assert_eq "git link of an already linked commit is a no-op" "False" "$(jq_val "$OUT" "d['linked']")"
+git -c user.name=t -c user.email=t@example.com commit -q --allow-empty -m "Finish parser" -m "Itr-Issue: #$CM_B" -m "closes #$CM_A"
+$CM_ITR git scan >/dev/null 2>&1
+OUT=$($CM_ITR commits "$CM_A" -f json)
+assert_eq "git scan links closing commits" "mention close" "$(jq_val "$OUT" "' '.join(c['relation'] for c in d)")"
+OUT=$($CM_ITR commits "$CM_B" -f json)
+assert_eq "git scan links Itr-Issue trailers as mentions" "Finish parser" "$(jq_val "$OUT" "d[0]['subject']")"
+OUT=$($CM_ITR get "$CM_A")
+assert_contains "get shows the commit count" "COMMITS: 2" "$OUT"
+OUT=$($CM_ITR add "Untouched" -q)
+OUT=$($CM_ITR commits "$OUT")
+assert_contains "commits without links prints the empty result" "No commits linked" "$OUT"
+assert_exit "git link of an unknown revision is invalid" 5 $CM_ITR git link "$CM_A" no-such-rev
+assert_exit "commits of a missing issue is not found" 3 $CM_ITR commits 999
+cd "$WORKDIR"
+rm -rf "$CM_DIR"
+
+echo "--- activity: recent commits on an issue's files ---"
+AC_DIR="$(mktemp -d)"
+cd "$AC_DIR"
+git init -q .
+AC_ITR="env ITR_DB_PATH=$AC_DIR/.itr.db $ITR"
+$AC_ITR init >/dev/null 2>&1
+mkdir src
+printf 'a\n' > src/a.rs
+printf 'b\n' > src/b.rs
+git add src
+git -c user.name=t -c user.email=t@example.com commit -q -m "Add sources"
+printf 'a2\n' > src/a.rs
+git -c user.name=t -c user.email=t@example.com commit -q -am "Touch a"
+AC_ID=$($AC_ITR add "Fix a" --files src/a.rs,src/gone.rs -q)
+OUT=$($AC_ITR activity "$AC_ID" --since "1 year ago" -f json)
+assert_eq "activity lists commits touching the issue's files" "Touch a,Add sources" "$(jq_val "$OUT" "','.join(c['subject'] for c in d)")"
+assert_eq "activity keeps only the issue's paths" "['src/a.rs']" "$(jq_val "$OUT" "d[1]['files']")"
+cd src
+OUT=$($AC_ITR activity "$AC_ID" --since "1 year ago" -n 1)
+assert_contains "activity reads paths from the work tree top" "COMMITS:1 FILES:1/2" "$OUT"
+cd "$AC_DIR"
+OUT=$($AC_ITR add "No files" -q)
+OUT=$($AC_ITR activity "$OUT")
+assert_contains "activity without files prints the empty result" "lists no files" "$OUT"
+assert_exit "activity of a missing issue is not found" 3 $AC_ITR activity 999
+cd "$WORKDIR"
+rm -rf "$AC_DIR"
+
+echo "--- gate: critical issues block commits ---"
+GT_DIR="$(mktemp -d)"
+cd "$GT_DIR"
+git init -q .
+GT_ITR="env ITR_DB_PATH=$GT_DIR/.itr.db $ITR"
+$GT_ITR init >/dev/null 2>&1
+assert_exit "gate passes without critical issues" 0 $GT_ITR gate
+GT_ID=$($GT_ITR add "Data loss" -p critical --files src/store.rs -q)
+assert_exit "gate fails on an open critical issue" 1 $GT_ITR gate
+assert_exit "gate allows up to --max-critical" 0 $GT_ITR gate --max-critical 1
+assert_exit "gate --touching-staged ignores unstaged files" 0 $GT_ITR gate --touching-staged
+mkdir src
+printf 'x\n' > src/store.rs
+git add src
+OUT=$($GT_ITR gate --touching-staged -f json 2>/dev/null) || true
+assert_eq "gate --touching-staged reports the staged match" "False ['src/store.rs']" "$(jq_val "$OUT" "str(d['passed']) + ' ' + str(d['issues'][0]['files'])")"
+$GT_ITR close "$GT_ID" >/dev/null 2>&1
+assert_exit "gate passes once the critical issue is closed" 0 $GT_ITR gate --touching-staged
+OUT=$($GT_ITR add "Crash" -p critical --files "src/a:b.rs" -q)
+OUT=$($GT_ITR gate -f gha 2>/dev/null) || true
+assert_eq "gate -f gha emits an error annotation" "::error file=src/a%3Ab.rs,title=itr gate::Critical issue #$(($GT_ID + 1)) is open: Crash" "$OUT"
+OUT=$($GT_ITR doctor -f gha)
+assert_eq "doctor -f gha emits a notice when clean" "::notice title=itr doctor::All clean" "$OUT"
+OUT=$($GT_ITR list -f gha 2>&1 >/dev/null)
+assert_contains "-f gha elsewhere falls back to compact" "only changes lint, doctor, and gate" "$OUT"
+cd "$WORKDIR"
+rm -rf "$GT_DIR"
+
+echo "--- review: finished work waits for approve or reject ---"
+RV_DIR="$(mktemp -d)"
+cd "$RV_DIR"
+RV_ITR="env ITR_DB_PATH=$RV_DIR/.itr.db $ITR"
+$RV_ITR init >/dev/null 2>&1
+OUT=$($RV_ITR review list)
+assert_eq "review list is empty at first" "No issues awaiting review." "$OUT"
+RV_ID=$($RV_ITR add "Parser fix" -q)
+$RV_ITR update "$RV_ID" --status in-review >/dev/null 2>&1
+OUT=$($RV_ITR review list -f json)
+assert_eq "in-review normalizes to review" "review" "$(jq_val "$OUT" "d[0]['status']")"
+OUT=$($RV_ITR ready -f json)
+assert_eq "ready skips issues in review" "0" "$(jq_val "$OUT" "len(d)")"
+assert_exit "reject needs a note" 5 $RV_ITR review reject "$RV_ID" ""
+OUT=$($RV_ITR review reject "$RV_ID" "Add a regression test" --agent rev -f json)
+assert_eq "reject reopens with a review note" "open review rev" "$(jq_val "$OUT" "d['status'] + ' ' + d['notes'][-1]['kind'] + ' ' + d['notes'][-1]['agent']")"
+assert_exit "approve needs an issue in review" 5 $RV_ITR review approve "$RV_ID"
+$RV_ITR update "$RV_ID" --status review >/dev/null 2>&1
+OUT=$($RV_ITR review approve "$RV_ID" -f json)
+assert_eq "approve closes as done" "done Approved in review" "$(jq_val "$OUT" "d['status'] + ' ' + d['close_reason']")"
+cd "$WORKDIR"
+rm -rf "$RV_DIR"
+
+echo "--- plan-sprint: fill a sprint within estimate capacity ---"
+PS_DIR="$(mktemp -d)"
+cd "$PS_DIR"
+PS_ITR="env ITR_DB_PATH=$PS_DIR/.itr.db $ITR"
+$PS_ITR init >/dev/null 2>&1
+PS_A=$($PS_ITR add "Schema change" -p high -q)
+PS_B=$($PS_ITR add "API on new schema" -p critical --blocked-by "$PS_A" -q)
+PS_C=$($PS_ITR add "Big refactor" -p low -q)
+$PS_ITR update "$PS_A" --estimate 3pts >/dev/null
+$PS_ITR update "$PS_B" --estimate 5 >/dev/null
+$PS_ITR update "$PS_C" --estimate 13 >/dev/null
+assert_exit "update rejects a non-numeric estimate" 5 $PS_ITR update "$PS_A" --estimate lots
+OUT=$($PS_ITR get "$PS_A" -f json)
+assert_eq "get shows the estimate" "3" "$(jq_val "$OUT" "d['estimate']")"
+OUT=$($PS_ITR plan-sprint --capacity 10pts -f json)
+assert_eq "plan-sprint plans the blocker before its dependent" "[$PS_A, $PS_B] [$PS_A] 8 1" "$(jq_val "$OUT" "str([i['id'] for i in d['items']]) + ' ' + str(d['items'][1]['after']) + ' ' + str(d['planned_points']) + ' ' + str(d['left_out'])")"
+OUT=$($PS_ITR plan-sprint --capacity 13 --agents 2)
+assert_contains "plan-sprint spreads work across agents" "PLAN:$PS_C AGENT:2 PTS:13" "$OUT"
+OUT=$($PS_ITR plan-sprint --capacity 2)
+assert_eq "plan-sprint reports when nothing fits" "No ready issues fit the sprint capacity." "$OUT"
+assert_exit "plan-sprint rejects a bad capacity" 5 $PS_ITR plan-sprint --capacity lots
+cd "$WORKDIR"
+rm -rf "$PS_DIR"
+
+echo "--- assign --auto: distribute the ready queue ---"
+AA_DIR="$(mktemp -d)"
+cd "$AA_DIR"
+AA_ITR="env ITR_DB_PATH=$AA_DIR/.itr.db $ITR"
+$AA_ITR init >/dev/null 2>&1
+assert_exit "assign --auto needs registered agents" 5 $AA_ITR assign --auto
+$AA_ITR agent register ann --skill sql >/dev/null
+$AA_ITR agent register bo >/dev/null
+AA_SQL=$($AA_ITR add "Schema" --skills sql -q)
+AA_P1=$($AA_ITR add "Parser" --files src/parse.rs -q)
+AA_P2=$($AA_ITR add "Parser follow-up" --files src/parse.rs -q)
+AA_GPU=$($AA_ITR add "Kernel" --skills cuda -q)
+OUT=$($AA_ITR assign --auto --dry-run 2>/dev/null)
+assert_contains "assign --auto --dry-run prints the plan" "ASSIGN:$AA_SQL AGENT:ann" "$OUT"
+OUT=$($AA_ITR get "$AA_SQL" -f json)
+assert_eq "assign --auto --dry-run writes nothing" "" "$(jq_val "$OUT" "d['assigned_to']")"
+OUT=$($AA_ITR assign --auto -f json)
+assert_eq "assign --auto matches skills and keeps shared files together" "ann bo bo" "$(jq_val "$OUT" "' '.join(a['agent'] for a in d['assigned'])")"
+assert_eq "assign --auto skips issues nobody can take" "$AA_GPU" "$(jq_val "$OUT" "d['skipped'][0]['id']")"
+OUT=$($AA_ITR get "$AA_P2" -f json)
+assert_eq "assign --auto writes the assignment" "bo" "$(jq_val "$OUT" "d['assigned_to']")"
+assert_exit "assign without --auto still needs an agent" 2 $AA_ITR assign "$AA_P1"
+cd "$WORKDIR"
+rm -rf "$AA_DIR"
+
+echo "--- next --skills: prefer matching issues ---"
+NS_DIR="$(mktemp -d)"
+cd "$NS_DIR"
+NS_ITR="env ITR_DB_PATH=$NS_DIR/.itr.db $ITR"
+$NS_ITR init >/dev/null 2>&1
+NS_UI=$($NS_ITR add "Button" --priority critical --skills css -q)
+NS_DB=$($NS_ITR add "Index" --priority low --tags db -q)
+NS_ANY=$($NS_ITR add "Docs" --priority medium -q)
+OUT=$($NS_ITR next -f json)
+assert_eq "next without skills keeps urgency order" "$NS_UI" "$(jq_val "$OUT" "d['id']")"
+OUT=$($NS_ITR next --skills backend,DB -f compact)
+assert_contains "next --skills prefers a matching tag" "ID:$NS_DB" "$OUT"
+assert_contains "next --skills says why" "SKILL_MATCH: matches skill(s) db" "$OUT"
+OUT=$($NS_ITR next --skills go -f json)
+assert_eq "next --skills falls back to general issues" "$NS_ANY" "$(jq_val "$OUT" "d['id']")"
+$NS_ITR agent register ann --skill db >/dev/null
+OUT=$($NS_ITR claim --agent ann -f json)
+assert_eq "claim defaults to the registered agent's skills" "$NS_DB" "$(jq_val "$OUT" "d['id']")"
+cd "$WORKDIR"
+rm -rf "$NS_DIR"
+
+echo "--- depend --on-external: blockers outside the repo ---"
+EX_DIR="$(mktemp -d)"
+cd "$EX_DIR"
+EX_ITR="env ITR_DB_PATH=$EX_DIR/.itr.db $ITR"
+$EX_ITR init >/dev/null 2>&1
+EX_ID=$($EX_ITR add "Bump parser" -q)
+OUT=$($EX_ITR depend "$EX_ID" --on-external "waiting for upstream release 1.4" --url https://example.com/1.4 -f json)
+EX_BLOCKER=$(jq_val "$OUT" "d['external']['id']")
+assert_eq "depend --on-external records the description" "waiting for upstream release 1.4" "$(jq_val "$OUT" "d['external']['description']")"
+OUT=$($EX_ITR get "$EX_ID" -f json)
+assert_eq "external blocker marks the issue blocked" "True" "$(jq_val "$OUT" "d['is_blocked']")"
+OUT=$($EX_ITR get "$EX_ID")
+assert_contains "get shows the external blocker" "EXTERNAL:$EX_BLOCKER \"waiting for upstream release 1.4\" URL:https://example.com/1.4" "$OUT"
+OUT=$($EX_ITR ready -f json)
+assert_eq "externally blocked issue is not ready" "0" "$(jq_val "$OUT" "len(d)")"
+assert_exit "resolve-external rejects a foreign blocker ID" 5 $EX_ITR resolve-external "$EX_ID" --blocker 999
+assert_exit "--url needs --on-external" 2 $EX_ITR depend "$EX_ID" --url https://example.com
+OUT=$($EX_ITR resolve-external "$EX_ID")
+assert_contains "resolve-external reports the unblocked issue" "UNBLOCKED:$EX_ID" "$OUT"
+OUT=$($EX_ITR ready -f json)
+assert_eq "resolved issue is ready again" "$EX_ID" "$(jq_val "$OUT" "d[0]['id']")"
+cd "$WORKDIR"
+rm -rf "$EX_DIR"
+
+echo "--- severity: impact separate from priority ---"
+SV_DIR="$(mktemp -d)"
+cd "$SV_DIR"
+SV_ITR="env ITR_DB_PATH=$SV_DIR/.itr.db $ITR"
+$SV_ITR init >/dev/null 2>&1
+SV_TYPO=$($SV_ITR add "Typo in footer" -k bug -p low --severity cosmetic -q)
+SV_CRASH=$($SV_ITR add "Crash on save" -k bug -p low --severity showstopper -q)
+OUT=$($SV_ITR get "$SV_CRASH" -f json)
+assert_eq "add --severity normalizes synonyms" "blocker" "$(jq_val "$OUT" "d['severity']")"
+assert_eq "severity leaves priority alone" "low" "$(jq_val "$OUT" "d['priority']")"
+assert_contains "compact get shows severity" "SEVERITY:blocker" "$($SV_ITR get "$SV_CRASH")"
+OUT=$($SV_ITR list -f json)
+assert_eq "blocker severity outranks trivial at equal priority" "$SV_CRASH" "$(jq_val "$OUT" "d[0]['id']")"
+OUT=$($SV_ITR list --severity s4 -f json)
+assert_eq "list --severity filters by normalized severity" "$SV_TYPO" "$(jq_val "$OUT" "','.join(str(i['id']) for i in d)")"
+$SV_ITR update "$SV_CRASH" --severity catastrophic >/dev/null 2>&1
+assert_eq "unrecognized severity keeps the old value" "blocker" "$(jq_val "$($SV_ITR get "$SV_CRASH" -f json)" "d['severity']")"
+$SV_ITR update "$SV_CRASH" --severity none >/dev/null
+assert_eq "update --severity none clears it" "" "$(jq_val "$($SV_ITR get "$SV_CRASH" -f json)" "d['severity']")"
+cd "$WORKDIR"
+rm -rf "$SV_DIR"
+
+echo "--- component: routing by default assignee and tags ---"
+CP_DIR="$(mktemp -d)"
+cd "$CP_DIR"
+CP_ITR="env ITR_DB_PATH=$CP_DIR/.itr.db $ITR"
+$CP_ITR init >/dev/null 2>&1
+$CP_ITR component add parser --assignee ann --tags lang >/dev/null
+$CP_ITR component add ui >/dev/null
+CP_BUG=$($CP_ITR add "Bad escape" --component parser -q)
+OUT=$($CP_ITR get "$CP_BUG" -f json)
+assert_eq "add --component sets the component" "parser" "$(jq_val "$OUT" "d['component']")"
+assert_eq "component default assignee fills an unassigned issue" "ann" "$(jq_val "$OUT" "d['assigned_to']")"
+assert_eq "component default tags are added" "lang" "$(jq_val "$OUT" "','.join(d['tags'])")"
+CP_UI=$($CP_ITR add "Button misaligned" --component ui -q)
+$CP_ITR update "$CP_UI" --component parser >/dev/null
+assert_eq "update --component applies the new defaults" "ann" "$(jq_val "$($CP_ITR get "$CP_UI" -f json)" "d['assigned_to']")"
+$CP_ITR add "Unrouted" >/dev/null
+OUT=$($CP_ITR list --component parser -f json)
+assert_eq "list --component filters" "$CP_BUG,$CP_UI" "$(jq_val "$OUT" "','.join(str(i['id']) for i in sorted(d, key=lambda i: i['id']))")"
+OUT=$($CP_ITR add "Typo" --component parsr)
+assert_contains "unregistered component is a REVIEW note" "component 'parsr' is not registered" "$OUT"
+assert_contains "stats counts by component" "BY_COMPONENT:" "$($CP_ITR stats)"
+assert_exit "component remove of an unknown name is INVALID_VALUE" 5 $CP_ITR component remove nope
+$CP_ITR component remove parser >/dev/null
+assert_eq "removing a component detaches its issues" "" "$(jq_val "$($CP_ITR get "$CP_BUG" -f json)" "d['component']")"
+cd "$WORKDIR"
+rm -rf "$CP_DIR"
+
+echo "--- versions: affected and fixed-in releases ---"
+VR_DIR="$(mktemp -d)"
+cd "$VR_DIR"
+VR_ITR="env ITR_DB_PATH=$VR_DIR/.itr.db $ITR"
+$VR_ITR init >/dev/null 2>&1
+VR_NEW=$($VR_ITR add "Crash on start" -k bug --affects-version 1.10.0 -q)
+VR_OLD=$($VR_ITR add "Leak" -k bug --affects-version 1.9.2 --fixed-in-version 1.10.0 -q)
+$VR_ITR add "Unversioned" >/dev/null
+OUT=$($VR_ITR get "$VR_OLD" -f json)
+assert_eq "add --affects-version is stored" "1.9.2" "$(jq_val "$OUT" "d['affects_version']")"
+assert_eq "add --fixed-in-version is stored" "1.10.0" "$(jq_val "$OUT" "d['fixed_in_version']")"
+assert_contains "compact get shows AFFECTS" "AFFECTS: 1.9.2" "$($VR_ITR get "$VR_OLD")"
+OUT=$($VR_ITR list --affects-version 1.10.0 -f json)
+assert_eq "list --affects-version filters" "$VR_NEW" "$(jq_val "$OUT" "','.join(str(i['id']) for i in d)")"
+OUT=$($VR_ITR versions -f json)
+assert_eq "versions orders releases numerically" "1.9.2,1.10.0" "$(jq_val "$OUT" "','.join(r['version'] for r in d)")"
+assert_eq "versions counts scheduled fixes" "1" "$(jq_val "$OUT" "d[0]['fix_scheduled']")"
+$VR_ITR close "$VR_OLD" >/dev/null
+OUT=$($VR_ITR versions -f json)
+assert_eq "closed issues drop out of versions" "1.10.0" "$(jq_val "$OUT" "','.join(r['version'] for r in d)")"
+$VR_ITR update "$VR_NEW" --affects-version none >/dev/null
+assert_contains "versions with nothing open is empty" "No open issues" "$($VR_ITR versions 2>&1)"
+cd "$WORKDIR"
+rm -rf "$VR_DIR"
+
+echo "--- repro: structured reproduction steps ---"
+RP_DIR="$(mktemp -d)"
+cd "$RP_DIR"
+RP_ITR="env ITR_DB_PATH=$RP_DIR/.itr.db $ITR"
+$RP_ITR init >/dev/null 2>&1
+RP_ID=$(echo '{"title":"Crash on save","kind":"bug","repro":{"steps":["open a file"," ","press save"],"actual":"crash"}}' | $RP_ITR add --stdin-json -q)
+OUT=$($RP_ITR get "$RP_ID" -f json)
+assert_eq "add --stdin-json stores repro steps, dropping blanks" "open a file|press save" "$(jq_val "$OUT" "'|'.join(d['repro']['steps'])")"
+assert_contains "compact get shows the REPRO section" "STEP 2: press save" "$($RP_ITR get "$RP_ID")"
+echo '{"steps":["open a file"],"expected":"saved","actual":"crash","environment":"linux"}' | $RP_ITR repro "$RP_ID" --stdin-json >/dev/null
+assert_eq "repro --stdin-json replaces it" "saved" "$(jq_val "$($RP_ITR repro "$RP_ID" -f json)" "d['repro']['expected']")"
+assert_exit "repro rejects unknown keys" 5 $RP_ITR repro "$RP_ID" --stdin-json <<< '{"expect":"saved"}'
+printf '{"steps":["reopen"],"actual":"hang"}\n' > "$RP_DIR/edited.json"
+EDITOR="cp $RP_DIR/edited.json" $RP_ITR repro "$RP_ID" --edit >/dev/null
+assert_eq "repro --edit saves the editor's result" "ACTUAL: hang" "$($RP_ITR repro "$RP_ID" | tail -1)"
+$RP_ITR config set policy.require.repro bug >/dev/null
+assert_exit "policy.require.repro refuses a bug without one" 5 $RP_ITR add "No steps" -k bug
+assert_exit "policy.require.repro refuses repro --clear" 5 $RP_ITR repro "$RP_ID" --clear
+$RP_ITR config set policy.require.repro "" >/dev/null
+$RP_ITR repro "$RP_ID" --clear >/dev/null
+assert_contains "repro --clear removes it" "No reproduction" "$($RP_ITR repro "$RP_ID" 2>&1)"
+assert_eq "repro changes are audited" "3" "$(jq_val "$($RP_ITR log "$RP_ID" -f json)" "len([e for e in d if e['field'] == 'repro'])")"
+cd "$WORKDIR"
+rm -rf "$RP_DIR"
+
+echo "--- references: #ID backlinks from context and notes ---"
+XR_DIR="$(mktemp -d)"
+cd "$XR_DIR"
+XR_ITR="env ITR_DB_PATH=$XR_DIR/.itr.db $ITR"
+$XR_ITR init >/dev/null 2>&1
+XR_A=$($XR_ITR add "Login fails" -q)
+XR_B=$($XR_ITR add "Token refresh" -c "Root cause of #$XR_A, maybe #$XR_A's sibling" -q)
+XR_C=$($XR_ITR add "Cleanup" -q)
+$XR_ITR note "$XR_C" "after #$XR_A lands; see also #$XR_C" >/dev/null
+OUT=$($XR_ITR get "$XR_A" -f json)
+assert_eq "context and note mentions list the referencing issues" "$XR_B,$XR_C" "$(jq_val "$OUT" "','.join(str(i) for i in d['referenced_by'])")"
+assert_contains "compact get shows REFERENCED_BY" "REFERENCED_BY:$XR_B,$XR_C" "$($XR_ITR get "$XR_A")"
+assert_eq "self-mentions are ignored" "[]" "$(jq_val "$($XR_ITR get "$XR_C" -f json)" "d.get('referenced_by', [])")"
+$XR_ITR update "$XR_B" -c "unrelated now" >/dev/null
+assert_eq "editing the context drops its references" "$XR_C" "$(jq_val "$($XR_ITR get "$XR_A" -f json)" "','.join(str(i) for i in d['referenced_by'])")"
+XR_D=$($XR_ITR add "Docs" -c "follow up in #$((XR_C + 2))" -q)
+XR_E=$($XR_ITR add "Later" -q)
+assert_contains "a mention of a future ID counts once it exists" "REFERENCED_BY:$XR_D" "$($XR_ITR get "$XR_E")"
+$XR_ITR reindex >/dev/null
+assert_contains "reindex keeps references" "REFERENCED_BY:$XR_C" "$($XR_ITR get "$XR_A")"
+cd "$WORKDIR"
+rm -rf "$XR_DIR"
+
+echo "--- dry-run: add, update, depend print and roll back ---"
+DRY_DIR=$(mktemp -d)
+DRY_DB="$DRY_DIR/.itr.db"
+ITR_DB_PATH="$DRY_DB" $ITR init >/dev/null
+DRY_A=$(ITR_DB_PATH="$DRY_DB" $ITR add "Dry blocked" -q)
+DRY_B=$(ITR_DB_PATH="$DRY_DB" $ITR add "Dry blocker" -q)
+# Every field but the urgency, which ages with the clock.
+DRY_FIELDS="json.dumps({k: v for k, v in d.items() if not k.startswith('urgency')}, sort_keys=True)"
+DRY_BEFORE_A=$(jq_val "$(ITR_DB_PATH="$DRY_DB" $ITR get "$DRY_A" -f json)" "$DRY_FIELDS")
+DRY_BEFORE_B=$(jq_val "$(ITR_DB_PATH="$DRY_DB" $ITR get "$DRY_B" -f json)" "$DRY_FIELDS")
+
+OUT=$(ITR_DB_PATH="$DRY_DB" $ITR add "Dry new" --priority high --dry-run -f json 2>"$DRY_DIR/err")
+assert_eq "add --dry-run prints the would-be issue" "3 Dry new high" "$(jq_val "$OUT" "f\"{d['id']} {d['title']} {d['priority']}\"")"
+assert_contains "add --dry-run says it rolled back" "DRY-RUN: rolled back" "$(cat "$DRY_DIR/err")"
+assert_eq "add --dry-run writes no row" "2" "$(jq_val "$(ITR_DB_PATH="$DRY_DB" $ITR stats -f json)" "d['total']")"
+assert_exit "add --dry-run leaves the ID unused" 3 env ITR_DB_PATH="$DRY_DB" $ITR get 3
+
+OUT=$(ITR_DB_PATH="$DRY_DB" $ITR update "$DRY_A" --title "Renamed" --priority critical --dry-run -f json 2>/dev/null)
+assert_eq "update --dry-run prints the would-be issue" "Renamed critical" "$(jq_val "$OUT" "f\"{d['title']} {d['priority']}\"")"
+assert_eq "update --dry-run leaves the issue unchanged" "$DRY_BEFORE_A" "$(jq_val "$(ITR_DB_PATH="$DRY_DB" $ITR get "$DRY_A" -f json)" "$DRY_FIELDS")"
+assert_eq "update --dry-run records no event" "0" "$(jq_val "$(ITR_DB_PATH="$DRY_DB" $ITR log "$DRY_A" -f json)" "len(d)")"
+
+OUT=$(ITR_DB_PATH="$DRY_DB" $ITR depend "$DRY_A" --on "$DRY_B" --dry-run 2>/dev/null)
+assert_eq "depend --dry-run prints the would-be edge" "DEPEND: $DRY_A blocked by $DRY_B" "$OUT"
+assert_eq "depend --dry-run leaves the blocked issue unchanged" "$DRY_BEFORE_A" "$(jq_val "$(ITR_DB_PATH="$DRY_DB" $ITR get "$DRY_A" -f json)" "$DRY_FIELDS")"
+assert_eq "depend --dry-run leaves the blocker unchanged" "$DRY_BEFORE_B" "$(jq_val "$(ITR_DB_PATH="$DRY_DB" $ITR get "$DRY_B" -f json)" "$DRY_FIELDS")"
+assert_eq "depend --dry-run writes no edge" "0" "$(jq_val "$(ITR_DB_PATH="$DRY_DB" $ITR graph -f json)" "len(d['edges'])")"
+assert_eq "issue count unchanged after every dry run" "2" "$(jq_val "$(ITR_DB_PATH="$DRY_DB" $ITR stats -f json)" "d['total']")"
+rm -rf "$DRY_DIR"
+
+echo "--- roadmap: epics as Mermaid gantt sections ---"
+ROAD_DIR=$(mktemp -d)
+ROAD_DB="$ROAD_DIR/.itr.db"
+ITR_DB_PATH="$ROAD_DB" $ITR init >/dev/null
+OUT=$(ITR_DB_PATH="$ROAD_DB" $ITR roadmap)
+assert_eq "roadmap without epics" "No epics found." "$OUT"
+ITR_DB_PATH="$ROAD_DB" $ITR add "Auth: overhaul" -k epic >/dev/null
+ITR_DB_PATH="$ROAD_DB" $ITR add "Login form" --parent 1 >/dev/null
+ITR_DB_PATH="$ROAD_DB" $ITR add "Logout" --parent 1 >/dev/null
+ITR_DB_PATH="$ROAD_DB" $ITR close 2 "shipped" >/dev/null
+OUT=$(ITR_DB_PATH="$ROAD_DB" $ITR roadmap)
+assert_eq "roadmap starts with gantt header" "gantt" "$(echo "$OUT" | head -1)"
+assert_contains "roadmap section per epic" "section Auth overhaul" "$OUT"
+assert_contains "roadmap closed child is done" "Login form :done, i2," "$OUT"
+OUT=$(ITR_DB_PATH="$ROAD_DB" $ITR roadmap -f json)
+assert_eq "roadmap json progress" "1/2" "$(jq_val "$OUT" "f\"{d[0]['done']}/{d[0]['total']}\"")"
+ITR_DB_PATH="$ROAD_DB" $ITR close 1 "done" >/dev/null
+assert_eq "roadmap hides closed epics" "[]" "$(ITR_DB_PATH="$ROAD_DB" $ITR roadmap -f json --raw)"
+assert_eq "roadmap --all keeps closed epics" "1" "$(jq_val "$(ITR_DB_PATH="$ROAD_DB" $ITR roadmap --all -f json)" "len(d)")"
+rm -rf "$ROAD_DIR"
+
 # ─────────────────────────────────────────────
 # Auto-discovered normalized snapshot contracts (issue #140)
 # ─────────────────────────────────────────────